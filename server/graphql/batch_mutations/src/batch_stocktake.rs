@@ -122,7 +122,7 @@ impl BatchInput {
 }
 
 impl BatchResponse {
-    fn from_domain(
+    pub(crate) fn from_domain(
         ServiceResult {
             insert_stocktake,
             insert_line,
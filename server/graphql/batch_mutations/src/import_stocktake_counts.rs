@@ -0,0 +1,431 @@
+use async_graphql::*;
+use chrono::NaiveDate;
+use graphql_core::{
+    simple_generic_errors::{CannotEditStocktake, StocktakeIsLocked},
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use graphql_types::types::StocktakeLineNode;
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    stocktake::{
+        ImportStocktakeCounts, ImportStocktakeCountsError as ServiceError,
+        ImportStocktakeCountsResult, ImportedCountConflict, ImportedCountError, ImportedCountRow,
+        ImportedCountStatus,
+    },
+};
+
+use crate::batch_stocktake::BatchResponse;
+
+#[derive(InputObject)]
+pub struct ImportStocktakeCountsInput {
+    pub stocktake_id: String,
+    /// Text content of the scanner or spreadsheet export, either with a header row (barcode or
+    /// item code, batch, expiry and quantity columns) or in the scanner order: barcode, batch,
+    /// expiry and quantity
+    pub content: String,
+    /// Only report how the rows would be applied
+    pub dry_run: bool,
+}
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ImportedStocktakeCountStatus {
+    Matched,
+    NewLine,
+    /// Row doesn't identify a single stocktake line, e.g. the batch is missing
+    MultipleLinesMatch,
+    /// Line was already counted with a different number of packs, it's not overwritten
+    AlreadyCounted,
+    ItemNotFound,
+    InvalidQuantity,
+    InvalidExpiryDate,
+}
+
+pub struct ImportedStocktakeCountRowNode {
+    pub row: ImportedCountRow,
+}
+
+#[Object]
+impl ImportedStocktakeCountRowNode {
+    pub async fn row_number(&self) -> u32 {
+        self.row.row_number
+    }
+
+    pub async fn barcode(&self) -> &Option<String> {
+        &self.row.barcode
+    }
+
+    pub async fn item_code(&self) -> &Option<String> {
+        &self.row.item_code
+    }
+
+    pub async fn item_id(&self) -> &Option<String> {
+        &self.row.item_id
+    }
+
+    pub async fn batch(&self) -> &Option<String> {
+        &self.row.batch
+    }
+
+    pub async fn expiry_date(&self) -> &Option<NaiveDate> {
+        &self.row.expiry_date
+    }
+
+    pub async fn number_of_packs(&self) -> f64 {
+        self.row.number_of_packs
+    }
+
+    pub async fn status(&self) -> ImportedStocktakeCountStatus {
+        match &self.row.status {
+            ImportedCountStatus::Matched { .. } => ImportedStocktakeCountStatus::Matched,
+            ImportedCountStatus::NewLine { .. } => ImportedStocktakeCountStatus::NewLine,
+            ImportedCountStatus::Conflict(ImportedCountConflict::MultipleLinesMatch) => {
+                ImportedStocktakeCountStatus::MultipleLinesMatch
+            }
+            ImportedCountStatus::Conflict(ImportedCountConflict::AlreadyCounted { .. }) => {
+                ImportedStocktakeCountStatus::AlreadyCounted
+            }
+            ImportedCountStatus::Error(ImportedCountError::ItemNotFound) => {
+                ImportedStocktakeCountStatus::ItemNotFound
+            }
+            ImportedCountStatus::Error(ImportedCountError::InvalidQuantity) => {
+                ImportedStocktakeCountStatus::InvalidQuantity
+            }
+            ImportedCountStatus::Error(ImportedCountError::InvalidExpiryDate) => {
+                ImportedStocktakeCountStatus::InvalidExpiryDate
+            }
+        }
+    }
+
+    /// Matched or new stocktake line
+    pub async fn stocktake_line_id(&self) -> Option<&str> {
+        match &self.row.status {
+            ImportedCountStatus::Matched { stocktake_line_id }
+            | ImportedCountStatus::NewLine { stocktake_line_id } => Some(stocktake_line_id),
+            _ => None,
+        }
+    }
+
+    /// Existing count of the line when the status is ALREADY_COUNTED
+    pub async fn counted_number_of_packs(&self) -> Option<f64> {
+        match &self.row.status {
+            ImportedCountStatus::Conflict(ImportedCountConflict::AlreadyCounted {
+                counted_number_of_packs,
+            }) => Some(*counted_number_of_packs),
+            _ => None,
+        }
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct ImportStocktakeCountsNode {
+    pub rows: Vec<ImportedStocktakeCountRowNode>,
+    /// Result of applying the matched and new rows, null for a dry run
+    pub batch_stocktake: Option<BatchResponse>,
+    /// Lines of a blind stocktake the counts of the user were recorded on
+    pub blind_count_lines: Vec<StocktakeLineNode>,
+}
+
+#[derive(Interface)]
+#[graphql(field(name = "description", type = "String"))]
+pub enum ImportStocktakeCountsErrorInterface {
+    CannotEditStocktake(CannotEditStocktake),
+    StocktakeIsLocked(StocktakeIsLocked),
+}
+
+#[derive(SimpleObject)]
+pub struct ImportStocktakeCountsError {
+    pub error: ImportStocktakeCountsErrorInterface,
+}
+
+#[derive(Union)]
+pub enum ImportStocktakeCountsResponse {
+    Error(ImportStocktakeCountsError),
+    Response(ImportStocktakeCountsNode),
+}
+
+pub fn import_stocktake_counts(
+    ctx: &Context<'_>,
+    store_id: &str,
+    input: ImportStocktakeCountsInput,
+) -> Result<ImportStocktakeCountsResponse> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateStocktake,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+
+    map_response(
+        service_provider
+            .stocktake_service
+            .import_stocktake_counts(&service_context, input.to_domain()),
+    )
+}
+
+impl ImportStocktakeCountsInput {
+    pub fn to_domain(self) -> ImportStocktakeCounts {
+        let ImportStocktakeCountsInput {
+            stocktake_id,
+            content,
+            dry_run,
+        } = self;
+
+        ImportStocktakeCounts {
+            stocktake_id,
+            content,
+            dry_run,
+        }
+    }
+}
+
+fn map_response(
+    from: Result<ImportStocktakeCountsResult, ServiceError>,
+) -> Result<ImportStocktakeCountsResponse> {
+    let result = match from {
+        Ok(ImportStocktakeCountsResult {
+            rows,
+            batch_result,
+            blind_count_lines,
+        }) => ImportStocktakeCountsResponse::Response(ImportStocktakeCountsNode {
+            rows: rows
+                .into_iter()
+                .map(|row| ImportedStocktakeCountRowNode { row })
+                .collect(),
+            batch_stocktake: batch_result.map(BatchResponse::from_domain).transpose()?,
+            blind_count_lines: blind_count_lines
+                .into_iter()
+                .map(StocktakeLineNode::from_domain)
+                .collect(),
+        }),
+        Err(error) => ImportStocktakeCountsResponse::Error(ImportStocktakeCountsError {
+            error: map_error(error)?,
+        }),
+    };
+
+    Ok(result)
+}
+
+fn map_error(error: ServiceError) -> Result<ImportStocktakeCountsErrorInterface> {
+    use StandardGraphqlError::*;
+    let formatted_error = format!("{:#?}", error);
+
+    let graphql_error = match error {
+        // Structured Errors
+        ServiceError::CannotEditFinalised => {
+            return Ok(ImportStocktakeCountsErrorInterface::CannotEditStocktake(
+                CannotEditStocktake {},
+            ))
+        }
+        ServiceError::StocktakeIsLocked => {
+            return Ok(ImportStocktakeCountsErrorInterface::StocktakeIsLocked(
+                StocktakeIsLocked {},
+            ))
+        }
+        // Standard Graphql Errors
+        ServiceError::InvalidStore => BadUserInput(formatted_error),
+        ServiceError::StocktakeDoesNotExist => BadUserInput(formatted_error),
+        ServiceError::InvalidFile(_) => BadUserInput(formatted_error),
+        ServiceError::DatabaseError(_) => InternalError(formatted_error),
+        ServiceError::InternalError(_) => InternalError(formatted_error),
+    };
+
+    Err(graphql_error.extend())
+}
+
+#[cfg(test)]
+mod test {
+    use async_graphql::EmptyMutation;
+    use graphql_core::{
+        assert_graphql_query, assert_standard_graphql_error, test_helpers::setup_graphl_test,
+    };
+    use repository::{mock::MockDataInserts, StorageConnectionManager};
+    use serde_json::json;
+    use service::{
+        service_provider::{ServiceContext, ServiceProvider},
+        stocktake::{
+            ImportStocktakeCounts, ImportStocktakeCountsError, ImportStocktakeCountsResult,
+            ImportedCountConflict, ImportedCountRow, ImportedCountStatus, StocktakeServiceTrait,
+        },
+    };
+
+    use crate::BatchMutations;
+
+    type Method = dyn Fn(ImportStocktakeCounts) -> Result<ImportStocktakeCountsResult, ImportStocktakeCountsError>
+        + Sync
+        + Send;
+
+    pub struct TestService(pub Box<Method>);
+
+    impl StocktakeServiceTrait for TestService {
+        fn import_stocktake_counts(
+            &self,
+            _: &ServiceContext,
+            input: ImportStocktakeCounts,
+        ) -> Result<ImportStocktakeCountsResult, ImportStocktakeCountsError> {
+            self.0(input)
+        }
+    }
+
+    fn service_provider(
+        test_service: TestService,
+        connection_manager: &StorageConnectionManager,
+    ) -> ServiceProvider {
+        let mut service_provider = ServiceProvider::new(connection_manager.clone(), "app_data");
+        service_provider.stocktake_service = Box::new(test_service);
+        service_provider
+    }
+
+    #[actix_rt::test]
+    async fn test_graphql_import_stocktake_counts() {
+        let (_, _, connection_manager, settings) = setup_graphl_test(
+            EmptyMutation,
+            BatchMutations,
+            "test_graphql_import_stocktake_counts",
+            MockDataInserts::all(),
+        )
+        .await;
+
+        let mutation = r#"
+        mutation ($input: ImportStocktakeCountsInput!, $storeId: String!) {
+            importStocktakeCounts(storeId: $storeId, input: $input) {
+              ... on ImportStocktakeCountsError {
+                error {
+                  __typename
+                }
+              }
+              ... on ImportStocktakeCountsNode {
+                rows {
+                  rowNumber
+                  status
+                  stocktakeLineId
+                  countedNumberOfPacks
+                }
+                batchStocktake {
+                  __typename
+                }
+              }
+            }
+          }
+        "#;
+        let variables = json!({
+          "input": {
+            "stocktakeId": "stocktake",
+            "content": "barcode,batch,expiry,quantity\n9300001,A1,,2",
+            "dryRun": true
+          },
+          "storeId": "store_a"
+        });
+
+        // CannotEditStocktake
+        let test_service = TestService(Box::new(|_| {
+            Err(ImportStocktakeCountsError::CannotEditFinalised)
+        }));
+        let expected = json!({
+            "importStocktakeCounts": {
+              "error": {
+                "__typename": "CannotEditStocktake"
+              }
+            }
+          }
+        );
+        assert_graphql_query!(
+            &settings,
+            mutation,
+            &Some(variables.clone()),
+            &expected,
+            Some(service_provider(test_service, &connection_manager))
+        );
+
+        // InvalidFile
+        let test_service = TestService(Box::new(|_| {
+            Err(ImportStocktakeCountsError::InvalidFile(
+                "File doesn't have any rows".to_string(),
+            ))
+        }));
+        let expected_message = "Bad user input";
+        assert_standard_graphql_error!(
+            &settings,
+            &mutation,
+            &Some(variables.clone()),
+            &expected_message,
+            None,
+            Some(service_provider(test_service, &connection_manager))
+        );
+
+        // Dry run
+        let test_service = TestService(Box::new(|input| {
+            assert_eq!(
+                input,
+                ImportStocktakeCounts {
+                    stocktake_id: "stocktake".to_string(),
+                    content: "barcode,batch,expiry,quantity\n9300001,A1,,2".to_string(),
+                    dry_run: true,
+                }
+            );
+            Ok(ImportStocktakeCountsResult {
+                rows: vec![
+                    ImportedCountRow {
+                        row_number: 2,
+                        barcode: Some("9300001".to_string()),
+                        item_code: None,
+                        item_id: Some("item_a".to_string()),
+                        batch: Some("A1".to_string()),
+                        expiry_date: None,
+                        number_of_packs: 2.0,
+                        status: ImportedCountStatus::Matched {
+                            stocktake_line_id: "line_a1".to_string(),
+                        },
+                    },
+                    ImportedCountRow {
+                        row_number: 3,
+                        barcode: Some("9300001".to_string()),
+                        item_code: None,
+                        item_id: Some("item_a".to_string()),
+                        batch: Some("A2".to_string()),
+                        expiry_date: None,
+                        number_of_packs: 4.0,
+                        status: ImportedCountStatus::Conflict(
+                            ImportedCountConflict::AlreadyCounted {
+                                counted_number_of_packs: 3.0,
+                            },
+                        ),
+                    },
+                ],
+                batch_result: None,
+                blind_count_lines: Vec::new(),
+            })
+        }));
+        let expected = json!({
+            "importStocktakeCounts": {
+              "rows": [
+                {
+                  "rowNumber": 2,
+                  "status": "MATCHED",
+                  "stocktakeLineId": "line_a1",
+                  "countedNumberOfPacks": null
+                },
+                {
+                  "rowNumber": 3,
+                  "status": "ALREADY_COUNTED",
+                  "stocktakeLineId": null,
+                  "countedNumberOfPacks": 3.0
+                }
+              ],
+              "batchStocktake": null
+            }
+          }
+        );
+        assert_graphql_query!(
+            &settings,
+            mutation,
+            &Some(variables),
+            &expected,
+            Some(service_provider(test_service, &connection_manager))
+        );
+    }
+}
@@ -2,6 +2,7 @@ mod batch_inbound_shipment;
 mod batch_outbound_shipment;
 mod batch_request_requisition;
 mod batch_stocktake;
+mod import_stocktake_counts;
 use async_graphql::*;
 
 #[derive(Default, Clone)]
@@ -44,6 +45,17 @@ impl BatchMutations {
     ) -> Result<batch_stocktake::BatchResponse> {
         batch_stocktake::batch(ctx, &store_id, input)
     }
+
+    /// Matches the counts of a scanner or spreadsheet export to the stocktake lines, unless it's a
+    /// dry run these are applied as a batchStocktake
+    async fn import_stocktake_counts(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: import_stocktake_counts::ImportStocktakeCountsInput,
+    ) -> Result<import_stocktake_counts::ImportStocktakeCountsResponse> {
+        import_stocktake_counts::import_stocktake_counts(ctx, &store_id, input)
+    }
 }
 
 pub trait VecOrNone<T> {
@@ -0,0 +1,376 @@
+mod mutations;
+use self::mutations::*;
+
+use async_graphql::*;
+use graphql_core::{
+    pagination::PaginationInput,
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use graphql_types::types::*;
+use repository::{
+    EqualFilter, PaginationOption, SensorFilter, TemperatureBreachFilter, TemperatureLogFilter,
+};
+use service::auth::{Resource, ResourceAccessRequest};
+
+#[derive(Default, Clone)]
+pub struct ColdChainQueries;
+
+#[Object]
+impl ColdChainQueries {
+    /// Temperature sensors of the store
+    pub async fn sensors(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        #[graphql(desc = "Pagination option (first and offset)")] page: Option<PaginationInput>,
+        #[graphql(desc = "Filter option")] filter: Option<SensorFilterInput>,
+        #[graphql(desc = "Sort options (only first sort input is evaluated for this endpoint)")]
+        sort: Option<Vec<SensorSortInput>>,
+    ) -> Result<SensorsResponse> {
+        let user = validate_auth(
+            ctx,
+            &ResourceAccessRequest {
+                resource: Resource::QueryLocation,
+                store_id: Some(store_id.clone()),
+            },
+        )?;
+
+        let service_provider = ctx.service_provider();
+        let service_context = service_provider.context(store_id.clone(), user.user_id)?;
+
+        // always filter by store_id
+        let filter = filter
+            .map(SensorFilter::from)
+            .unwrap_or(SensorFilter::new())
+            .store_id(EqualFilter::equal_to(&store_id));
+
+        let sensors = service_provider
+            .cold_chain_service
+            .get_sensors(
+                &service_context,
+                page.map(PaginationOption::from),
+                Some(filter),
+                sort.and_then(|mut sort_list| sort_list.pop())
+                    .map(|sort| sort.to_domain()),
+            )
+            .map_err(StandardGraphqlError::from_list_error)?;
+
+        Ok(SensorsResponse::Response(SensorConnector::from_domain(
+            sensors,
+        )))
+    }
+
+    /// Temperature ranges that define a breach
+    pub async fn temperature_breach_configs(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+    ) -> Result<Vec<TemperatureBreachConfigNode>> {
+        let user = validate_auth(
+            ctx,
+            &ResourceAccessRequest {
+                resource: Resource::QueryLocation,
+                store_id: Some(store_id.clone()),
+            },
+        )?;
+
+        let service_provider = ctx.service_provider();
+        let service_context = service_provider.context(store_id, user.user_id)?;
+
+        let configs = service_provider
+            .cold_chain_service
+            .get_temperature_breach_configs(&service_context)
+            .map_err(|error| {
+                StandardGraphqlError::InternalError(format!("{:#?}", error)).extend()
+            })?;
+
+        Ok(configs
+            .into_iter()
+            .map(TemperatureBreachConfigNode::from_domain)
+            .collect())
+    }
+
+    pub async fn temperature_logs(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        #[graphql(desc = "Pagination option (first and offset)")] page: Option<PaginationInput>,
+        #[graphql(desc = "Filter option")] filter: Option<TemperatureLogFilterInput>,
+        #[graphql(desc = "Sort options (only first sort input is evaluated for this endpoint)")]
+        sort: Option<Vec<TemperatureLogSortInput>>,
+    ) -> Result<TemperatureLogsResponse> {
+        let user = validate_auth(
+            ctx,
+            &ResourceAccessRequest {
+                resource: Resource::QueryLocation,
+                store_id: Some(store_id.clone()),
+            },
+        )?;
+
+        let service_provider = ctx.service_provider();
+        let service_context = service_provider.context(store_id.clone(), user.user_id)?;
+
+        // always filter by store_id
+        let filter = filter
+            .map(TemperatureLogFilter::from)
+            .unwrap_or(TemperatureLogFilter::new())
+            .store_id(EqualFilter::equal_to(&store_id));
+
+        let temperature_logs = service_provider
+            .cold_chain_service
+            .get_temperature_logs(
+                &service_context,
+                page.map(PaginationOption::from),
+                Some(filter),
+                sort.and_then(|mut sort_list| sort_list.pop())
+                    .map(|sort| sort.to_domain()),
+            )
+            .map_err(StandardGraphqlError::from_list_error)?;
+
+        Ok(TemperatureLogsResponse::Response(
+            TemperatureLogConnector::from_domain(temperature_logs),
+        ))
+    }
+
+    /// Recorded temperature breaches, filter by `acknowledged: false` for breaches with stock
+    /// still to be reviewed
+    pub async fn temperature_breaches(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        #[graphql(desc = "Pagination option (first and offset)")] page: Option<PaginationInput>,
+        #[graphql(desc = "Filter option")] filter: Option<TemperatureBreachFilterInput>,
+        #[graphql(desc = "Sort options (only first sort input is evaluated for this endpoint)")]
+        sort: Option<Vec<TemperatureBreachSortInput>>,
+    ) -> Result<TemperatureBreachesResponse> {
+        let user = validate_auth(
+            ctx,
+            &ResourceAccessRequest {
+                resource: Resource::QueryLocation,
+                store_id: Some(store_id.clone()),
+            },
+        )?;
+
+        let service_provider = ctx.service_provider();
+        let service_context = service_provider.context(store_id.clone(), user.user_id)?;
+
+        // always filter by store_id
+        let filter = filter
+            .map(TemperatureBreachFilter::from)
+            .unwrap_or(TemperatureBreachFilter::new())
+            .store_id(EqualFilter::equal_to(&store_id));
+
+        let temperature_breaches = service_provider
+            .cold_chain_service
+            .get_temperature_breaches(
+                &service_context,
+                page.map(PaginationOption::from),
+                Some(filter),
+                sort.and_then(|mut sort_list| sort_list.pop())
+                    .map(|sort| sort.to_domain()),
+            )
+            .map_err(StandardGraphqlError::from_list_error)?;
+
+        Ok(TemperatureBreachesResponse::Response(
+            TemperatureBreachConnector::from_domain(temperature_breaches),
+        ))
+    }
+}
+
+#[derive(Default, Clone)]
+pub struct ColdChainMutations;
+
+#[Object]
+impl ColdChainMutations {
+    async fn insert_sensor(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: InsertSensorInput,
+    ) -> Result<SensorResponse> {
+        insert_sensor(ctx, &store_id, input)
+    }
+
+    async fn update_sensor(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: UpdateSensorInput,
+    ) -> Result<SensorResponse> {
+        update_sensor(ctx, &store_id, input)
+    }
+
+    async fn upsert_temperature_breach_config(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: UpsertTemperatureBreachConfigInput,
+    ) -> Result<UpsertTemperatureBreachConfigResponse> {
+        upsert_temperature_breach_config(ctx, &store_id, input)
+    }
+
+    /// Records sensor readings, breaches of the configured temperature ranges are recorded
+    /// with the stock that was in the location at the time
+    async fn insert_temperature_logs(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: InsertTemperatureLogsInput,
+    ) -> Result<InsertTemperatureLogsResponse> {
+        insert_temperature_logs(ctx, &store_id, input)
+    }
+
+    /// Same as insertTemperatureLogs with readings from a logger export file
+    async fn import_temperature_logs(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: ImportTemperatureLogsInput,
+    ) -> Result<InsertTemperatureLogsResponse> {
+        import_temperature_logs(ctx, &store_id, input)
+    }
+
+    async fn acknowledge_temperature_breach(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: AcknowledgeTemperatureBreachInput,
+    ) -> Result<AcknowledgeTemperatureBreachResponse> {
+        acknowledge_temperature_breach(ctx, &store_id, input)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use async_graphql::EmptyMutation;
+    use chrono::NaiveDate;
+    use graphql_core::{assert_graphql_query, test_helpers::setup_graphl_test};
+    use repository::{
+        mock::MockDataInserts, PaginationOption, StorageConnectionManager, TemperatureBreach,
+        TemperatureBreachFilter, TemperatureBreachRow, TemperatureBreachSort,
+        TemperatureBreachType,
+    };
+    use serde_json::json;
+    use service::{
+        cold_chain::ColdChainServiceTrait,
+        service_provider::{ServiceContext, ServiceProvider},
+        ListError, ListResult,
+    };
+
+    use crate::ColdChainQueries;
+
+    type GetTemperatureBreaches = dyn Fn(
+            Option<PaginationOption>,
+            Option<TemperatureBreachFilter>,
+            Option<TemperatureBreachSort>,
+        ) -> Result<ListResult<TemperatureBreach>, ListError>
+        + Sync
+        + Send;
+
+    pub struct TestService(pub Box<GetTemperatureBreaches>);
+
+    impl ColdChainServiceTrait for TestService {
+        fn get_temperature_breaches(
+            &self,
+            _: &ServiceContext,
+            pagination: Option<PaginationOption>,
+            filter: Option<TemperatureBreachFilter>,
+            sort: Option<TemperatureBreachSort>,
+        ) -> Result<ListResult<TemperatureBreach>, ListError> {
+            (self.0)(pagination, filter, sort)
+        }
+    }
+
+    pub fn service_provider(
+        cold_chain_service: TestService,
+        connection_manager: &StorageConnectionManager,
+    ) -> ServiceProvider {
+        let mut service_provider = ServiceProvider::new(connection_manager.clone(), "app_data");
+        service_provider.cold_chain_service = Box::new(cold_chain_service);
+        service_provider
+    }
+
+    #[actix_rt::test]
+    async fn test_graphql_temperature_breaches() {
+        let (_, _, connection_manager, settings) = setup_graphl_test(
+            ColdChainQueries,
+            EmptyMutation,
+            "test_graphql_temperature_breaches",
+            MockDataInserts::all(),
+        )
+        .await;
+
+        let query = r#"
+        query($filter: TemperatureBreachFilterInput) {
+            temperatureBreaches(storeId: \"store_a\", filter: $filter) {
+              ... on TemperatureBreachConnector {
+                nodes {
+                  id
+                  type
+                  startDatetime
+                  endDatetime
+                  extremeTemperature
+                  acknowledged
+                }
+                totalCount
+              }
+            }
+        }
+        "#;
+
+        let variables = json!({
+          "filter": {
+            "acknowledged": false,
+            "type": { "equalTo": "HOT" },
+          }
+        });
+
+        let test_service = TestService(Box::new(|_, filter, _| {
+            assert_eq!(
+                filter,
+                Some(
+                    TemperatureBreachFilter::new()
+                        .acknowledged(false)
+                        .r#type(TemperatureBreachType::Hot.equal_to())
+                        .store_id(repository::EqualFilter::equal_to("store_a"))
+                )
+            );
+            Ok(ListResult {
+                rows: vec![TemperatureBreach {
+                    temperature_breach_row: TemperatureBreachRow {
+                        id: "breach".to_string(),
+                        r#type: TemperatureBreachType::Hot,
+                        start_datetime: NaiveDate::from_ymd(2023, 1, 1).and_hms(10, 0, 0),
+                        end_datetime: None,
+                        extreme_temperature: 12.5,
+                        ..Default::default()
+                    },
+                }],
+                count: 1,
+            })
+        }));
+
+        let expected = json!({
+            "temperatureBreaches": {
+                "nodes": [{
+                    "id": "breach",
+                    "type": "HOT",
+                    "startDatetime": "2023-01-01T10:00:00+00:00",
+                    "endDatetime": null,
+                    "extremeTemperature": 12.5,
+                    "acknowledged": false,
+                }],
+                "totalCount": 1
+            }
+        });
+
+        assert_graphql_query!(
+            &settings,
+            query,
+            &Some(variables),
+            &expected,
+            Some(service_provider(test_service, &connection_manager))
+        );
+    }
+}
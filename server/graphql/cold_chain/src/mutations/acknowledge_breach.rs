@@ -0,0 +1,73 @@
+use async_graphql::*;
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use graphql_types::types::TemperatureBreachNode;
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    cold_chain::breach::{
+        AcknowledgeTemperatureBreach, AcknowledgeTemperatureBreachError as ServiceError,
+    },
+};
+
+#[derive(InputObject)]
+pub struct AcknowledgeTemperatureBreachInput {
+    pub id: String,
+    /// Outcome of reviewing the affected stock
+    pub comment: Option<String>,
+}
+
+#[derive(Union)]
+pub enum AcknowledgeTemperatureBreachResponse {
+    Response(TemperatureBreachNode),
+}
+
+pub fn acknowledge_temperature_breach(
+    ctx: &Context<'_>,
+    store_id: &str,
+    input: AcknowledgeTemperatureBreachInput,
+) -> Result<AcknowledgeTemperatureBreachResponse> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateLocation,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+
+    match service_provider
+        .cold_chain_service
+        .acknowledge_temperature_breach(
+            &service_context,
+            AcknowledgeTemperatureBreach {
+                id: input.id,
+                comment: input.comment,
+            },
+        ) {
+        Ok(breach) => Ok(AcknowledgeTemperatureBreachResponse::Response(
+            TemperatureBreachNode::from_domain(breach),
+        )),
+        Err(error) => Err(map_error(error)),
+    }
+}
+
+fn map_error(error: ServiceError) -> Error {
+    use StandardGraphqlError::*;
+    let formatted_error = format!("{:#?}", error);
+
+    let graphql_error = match error {
+        ServiceError::TemperatureBreachDoesNotExist
+        | ServiceError::TemperatureBreachDoesNotBelongToCurrentStore => {
+            BadUserInput(formatted_error)
+        }
+        ServiceError::UpdatedRecordNotFound | ServiceError::DatabaseError(_) => {
+            InternalError(formatted_error)
+        }
+    };
+
+    graphql_error.extend()
+}
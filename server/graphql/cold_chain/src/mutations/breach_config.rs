@@ -0,0 +1,96 @@
+use async_graphql::*;
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use graphql_types::types::TemperatureBreachConfigNode;
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    cold_chain::breach_config::{
+        UpsertTemperatureBreachConfig, UpsertTemperatureBreachConfigError as ServiceError,
+    },
+};
+
+#[derive(InputObject)]
+pub struct UpsertTemperatureBreachConfigInput {
+    pub id: String,
+    /// Applies to all locations of the store when not set
+    pub location_id: Option<String>,
+    pub description: String,
+    pub minimum_temperature: f64,
+    pub maximum_temperature: f64,
+    /// How long the temperature has to stay out of range to be recorded as a breach
+    pub duration_seconds: i32,
+    pub is_active: Option<bool>,
+}
+
+#[derive(Union)]
+pub enum UpsertTemperatureBreachConfigResponse {
+    Response(TemperatureBreachConfigNode),
+}
+
+pub fn upsert_temperature_breach_config(
+    ctx: &Context<'_>,
+    store_id: &str,
+    input: UpsertTemperatureBreachConfigInput,
+) -> Result<UpsertTemperatureBreachConfigResponse> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateLocation,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+
+    match service_provider
+        .cold_chain_service
+        .upsert_temperature_breach_config(&service_context, input.to_domain())
+    {
+        Ok(config) => Ok(UpsertTemperatureBreachConfigResponse::Response(
+            TemperatureBreachConfigNode::from_domain(config),
+        )),
+        Err(error) => Err(map_error(error)),
+    }
+}
+
+impl UpsertTemperatureBreachConfigInput {
+    pub fn to_domain(self) -> UpsertTemperatureBreachConfig {
+        let UpsertTemperatureBreachConfigInput {
+            id,
+            location_id,
+            description,
+            minimum_temperature,
+            maximum_temperature,
+            duration_seconds,
+            is_active,
+        } = self;
+
+        UpsertTemperatureBreachConfig {
+            id,
+            location_id,
+            description,
+            minimum_temperature,
+            maximum_temperature,
+            duration_seconds,
+            is_active: is_active.unwrap_or(true),
+        }
+    }
+}
+
+fn map_error(error: ServiceError) -> Error {
+    use StandardGraphqlError::*;
+    let formatted_error = format!("{:#?}", error);
+
+    let graphql_error = match error {
+        ServiceError::TemperatureBreachConfigDoesNotBelongToCurrentStore
+        | ServiceError::LocationDoesNotExist
+        | ServiceError::InvalidTemperatureRange
+        | ServiceError::InvalidDuration => BadUserInput(formatted_error),
+        ServiceError::DatabaseError(_) => InternalError(formatted_error),
+    };
+
+    graphql_error.extend()
+}
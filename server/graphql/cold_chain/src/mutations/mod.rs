@@ -0,0 +1,9 @@
+mod acknowledge_breach;
+mod breach_config;
+mod sensor;
+mod temperature_log;
+
+pub use acknowledge_breach::*;
+pub use breach_config::*;
+pub use sensor::*;
+pub use temperature_log::*;
@@ -0,0 +1,155 @@
+use async_graphql::*;
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use graphql_types::types::SensorNode;
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    cold_chain::sensor::{InsertSensor, InsertSensorError, UpdateSensor, UpdateSensorError},
+};
+
+#[derive(InputObject)]
+pub struct InsertSensorInput {
+    pub id: String,
+    pub name: String,
+    /// Serial number of the logger
+    pub serial: String,
+    pub location_id: Option<String>,
+}
+
+#[derive(InputObject)]
+pub struct UpdateSensorInput {
+    pub id: String,
+    pub name: Option<String>,
+    /// Set to null when the sensor is no longer in a location
+    pub location_id: MaybeUndefined<String>,
+    pub is_active: Option<bool>,
+}
+
+#[derive(Union)]
+pub enum SensorResponse {
+    Response(SensorNode),
+}
+
+pub fn insert_sensor(
+    ctx: &Context<'_>,
+    store_id: &str,
+    input: InsertSensorInput,
+) -> Result<SensorResponse> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateLocation,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+
+    match service_provider
+        .cold_chain_service
+        .insert_sensor(&service_context, input.to_domain())
+    {
+        Ok(sensor) => Ok(SensorResponse::Response(SensorNode::from_domain(sensor))),
+        Err(error) => Err(map_insert_error(error)),
+    }
+}
+
+pub fn update_sensor(
+    ctx: &Context<'_>,
+    store_id: &str,
+    input: UpdateSensorInput,
+) -> Result<SensorResponse> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateLocation,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+
+    match service_provider
+        .cold_chain_service
+        .update_sensor(&service_context, input.to_domain())
+    {
+        Ok(sensor) => Ok(SensorResponse::Response(SensorNode::from_domain(sensor))),
+        Err(error) => Err(map_update_error(error)),
+    }
+}
+
+impl InsertSensorInput {
+    pub fn to_domain(self) -> InsertSensor {
+        let InsertSensorInput {
+            id,
+            name,
+            serial,
+            location_id,
+        } = self;
+
+        InsertSensor {
+            id,
+            name,
+            serial,
+            location_id,
+        }
+    }
+}
+
+impl UpdateSensorInput {
+    pub fn to_domain(self) -> UpdateSensor {
+        let UpdateSensorInput {
+            id,
+            name,
+            location_id,
+            is_active,
+        } = self;
+
+        UpdateSensor {
+            id,
+            name,
+            location_id: match location_id {
+                MaybeUndefined::Undefined => None,
+                MaybeUndefined::Null => Some(None),
+                MaybeUndefined::Value(location_id) => Some(Some(location_id)),
+            },
+            is_active,
+        }
+    }
+}
+
+fn map_insert_error(error: InsertSensorError) -> Error {
+    use StandardGraphqlError::*;
+    let formatted_error = format!("{:#?}", error);
+
+    let graphql_error = match error {
+        InsertSensorError::SensorAlreadyExists
+        | InsertSensorError::SensorWithSerialAlreadyExists
+        | InsertSensorError::LocationDoesNotExist => BadUserInput(formatted_error),
+        InsertSensorError::CreatedRecordNotFound | InsertSensorError::DatabaseError(_) => {
+            InternalError(formatted_error)
+        }
+    };
+
+    graphql_error.extend()
+}
+
+fn map_update_error(error: UpdateSensorError) -> Error {
+    use StandardGraphqlError::*;
+    let formatted_error = format!("{:#?}", error);
+
+    let graphql_error = match error {
+        UpdateSensorError::SensorDoesNotExist
+        | UpdateSensorError::SensorDoesNotBelongToCurrentStore
+        | UpdateSensorError::LocationDoesNotExist => BadUserInput(formatted_error),
+        UpdateSensorError::UpdatedRecordNotFound | UpdateSensorError::DatabaseError(_) => {
+            InternalError(formatted_error)
+        }
+    };
+
+    graphql_error.extend()
+}
@@ -0,0 +1,174 @@
+use async_graphql::*;
+use chrono::{DateTime, Utc};
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use graphql_types::types::TemperatureBreachNode;
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    cold_chain::{
+        import::{ImportTemperatureLogs, TemperatureLogFileFormat},
+        temperature_log::{
+            InsertTemperatureLogs, InsertTemperatureLogsError as ServiceError,
+            InsertTemperatureLogsResult, TemperatureLogInput,
+        },
+    },
+};
+
+#[derive(InputObject)]
+pub struct TemperatureReadingInput {
+    pub datetime: DateTime<Utc>,
+    pub temperature: f64,
+}
+
+#[derive(InputObject)]
+pub struct InsertTemperatureLogsInput {
+    pub sensor_id: String,
+    pub logs: Vec<TemperatureReadingInput>,
+}
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq)]
+pub enum TemperatureLogFileFormatInput {
+    /// Delimited export with a date (or date and time) column and a temperature column
+    Csv,
+    /// Fridge-tag style report with daily minimum and maximum temperatures
+    FridgeTag,
+}
+
+#[derive(InputObject)]
+pub struct ImportTemperatureLogsInput {
+    pub sensor_id: String,
+    pub format: TemperatureLogFileFormatInput,
+    /// Text content of the logger export
+    pub content: String,
+}
+
+pub struct InsertTemperatureLogsNode {
+    pub result: InsertTemperatureLogsResult,
+}
+
+#[Object]
+impl InsertTemperatureLogsNode {
+    /// Logs already recorded for the sensor at the same time are skipped
+    pub async fn number_of_logs_inserted(&self) -> u32 {
+        self.result.number_of_logs_inserted
+    }
+
+    /// Breaches recorded or extended by the new logs, stock in these locations should be
+    /// reviewed
+    pub async fn temperature_breaches(&self) -> Vec<TemperatureBreachNode> {
+        self.result
+            .temperature_breaches
+            .iter()
+            .cloned()
+            .map(TemperatureBreachNode::from_domain)
+            .collect()
+    }
+}
+
+#[derive(Union)]
+pub enum InsertTemperatureLogsResponse {
+    Response(InsertTemperatureLogsNode),
+}
+
+pub fn insert_temperature_logs(
+    ctx: &Context<'_>,
+    store_id: &str,
+    input: InsertTemperatureLogsInput,
+) -> Result<InsertTemperatureLogsResponse> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateLocation,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+
+    map_response(
+        service_provider
+            .cold_chain_service
+            .insert_temperature_logs(&service_context, input.to_domain()),
+    )
+}
+
+pub fn import_temperature_logs(
+    ctx: &Context<'_>,
+    store_id: &str,
+    input: ImportTemperatureLogsInput,
+) -> Result<InsertTemperatureLogsResponse> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateLocation,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+
+    map_response(
+        service_provider
+            .cold_chain_service
+            .import_temperature_logs(&service_context, input.to_domain()),
+    )
+}
+
+impl InsertTemperatureLogsInput {
+    pub fn to_domain(self) -> InsertTemperatureLogs {
+        InsertTemperatureLogs {
+            sensor_id: self.sensor_id,
+            logs: self
+                .logs
+                .into_iter()
+                .map(|log| TemperatureLogInput {
+                    datetime: log.datetime.naive_utc(),
+                    temperature: log.temperature,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl ImportTemperatureLogsInput {
+    pub fn to_domain(self) -> ImportTemperatureLogs {
+        ImportTemperatureLogs {
+            sensor_id: self.sensor_id,
+            format: match self.format {
+                TemperatureLogFileFormatInput::Csv => TemperatureLogFileFormat::Csv,
+                TemperatureLogFileFormatInput::FridgeTag => TemperatureLogFileFormat::FridgeTag,
+            },
+            content: self.content,
+        }
+    }
+}
+
+fn map_response(
+    from: Result<InsertTemperatureLogsResult, ServiceError>,
+) -> Result<InsertTemperatureLogsResponse> {
+    use StandardGraphqlError::*;
+
+    let error = match from {
+        Ok(result) => {
+            return Ok(InsertTemperatureLogsResponse::Response(
+                InsertTemperatureLogsNode { result },
+            ))
+        }
+        Err(error) => error,
+    };
+    let formatted_error = format!("{:#?}", error);
+
+    let graphql_error = match error {
+        ServiceError::SensorDoesNotExist
+        | ServiceError::SensorDoesNotBelongToCurrentStore
+        | ServiceError::NoTemperatureLogs => BadUserInput(formatted_error),
+        ServiceError::InvalidFile(reason) => BadUserInput(reason),
+        ServiceError::DatabaseError(_) => InternalError(formatted_error),
+    };
+
+    Err(graphql_error.extend())
+}
@@ -5,3 +5,9 @@ pub struct TaxInput {
     /// Set or unset the tax value (in percentage)
     pub percentage: Option<f64>,
 }
+
+#[derive(InputObject)]
+pub struct NullableStringUpdate {
+    /// Set or unset the value
+    pub value: Option<String>,
+}
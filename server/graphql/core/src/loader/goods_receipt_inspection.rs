@@ -0,0 +1,34 @@
+use repository::{
+    GoodsReceiptInspectionRow, GoodsReceiptInspectionRowRepository, RepositoryError,
+    StorageConnectionManager,
+};
+
+use async_graphql::dataloader::*;
+use async_graphql::*;
+use std::collections::HashMap;
+
+pub struct GoodsReceiptInspectionByInvoiceIdLoader {
+    pub connection_manager: StorageConnectionManager,
+}
+
+#[async_trait::async_trait]
+impl Loader<String> for GoodsReceiptInspectionByInvoiceIdLoader {
+    type Value = Vec<GoodsReceiptInspectionRow>;
+    type Error = RepositoryError;
+
+    async fn load(
+        &self,
+        invoice_ids: &[String],
+    ) -> Result<HashMap<String, Self::Value>, Self::Error> {
+        let connection = self.connection_manager.connection()?;
+        let repo = GoodsReceiptInspectionRowRepository::new(&connection);
+
+        let mut map: HashMap<String, Vec<GoodsReceiptInspectionRow>> = HashMap::new();
+        for inspection in repo.find_many_by_invoice_ids(invoice_ids)? {
+            map.entry(inspection.invoice_id.clone())
+                .or_default()
+                .push(inspection);
+        }
+        Ok(map)
+    }
+}
@@ -0,0 +1,30 @@
+use repository::EqualFilter;
+use repository::{Hold, HoldFilter, HoldRepository, RepositoryError, StorageConnectionManager};
+
+use async_graphql::dataloader::*;
+use async_graphql::*;
+use std::collections::HashMap;
+
+/// Current hold of a stock line, location or invoice, by the id of the held record
+pub struct HoldByRecordIdLoader {
+    pub connection_manager: StorageConnectionManager,
+}
+
+#[async_trait::async_trait]
+impl Loader<String> for HoldByRecordIdLoader {
+    type Value = Hold;
+    type Error = RepositoryError;
+
+    async fn load(&self, ids: &[String]) -> Result<HashMap<String, Self::Value>, Self::Error> {
+        let connection = self.connection_manager.connection()?;
+        let repo = HoldRepository::new(&connection);
+
+        let result = repo
+            .query_by_filter(HoldFilter::new().record_id(EqualFilter::equal_any(ids.to_owned())))?;
+
+        Ok(result
+            .into_iter()
+            .map(|hold| (hold.hold_row.record_id.clone(), hold))
+            .collect())
+    }
+}
@@ -119,6 +119,20 @@ pub async fn get_loaders(
         async_std::task::spawn,
     );
 
+    let hold_by_record_id_loader = DataLoader::new(
+        HoldByRecordIdLoader {
+            connection_manager: connection_manager.clone(),
+        },
+        async_std::task::spawn,
+    );
+
+    let goods_receipt_inspection_by_invoice_id_loader = DataLoader::new(
+        GoodsReceiptInspectionByInvoiceIdLoader {
+            connection_manager: connection_manager.clone(),
+        },
+        async_std::task::spawn,
+    );
+
     let master_list_line_by_master_list_id = DataLoader::new(
         MasterListLineByMasterListId {
             connection_manager: connection_manager.clone(),
@@ -133,6 +147,20 @@ pub async fn get_loaders(
         async_std::task::spawn,
     );
 
+    let stocktake_by_id_loader = DataLoader::new(
+        StocktakeByIdLoader {
+            connection_manager: connection_manager.clone(),
+        },
+        async_std::task::spawn,
+    );
+
+    let stocktake_line_count_loader = DataLoader::new(
+        StocktakeLineCountByStocktakeLineIdLoader {
+            connection_manager: connection_manager.clone(),
+        },
+        async_std::task::spawn,
+    );
+
     let requisitions_by_id_loader = DataLoader::new(
         RequisitionsByIdLoader {
             service_provider: service_provider.clone(),
@@ -215,11 +243,15 @@ pub async fn get_loaders(
     loaders.insert(requisition_line_by_linked_requisition_line_id_loader);
     loaders.insert(item_stats_for_item_loader);
     loaders.insert(stocktake_line_loader);
+    loaders.insert(stocktake_by_id_loader);
+    loaders.insert(stocktake_line_count_loader);
     loaders.insert(requisition_line_supply_status_loader);
     loaders.insert(requisition_lines_remaining_to_supply_loader);
     loaders.insert(name_row_loader);
     loaders.insert(inventory_adjustment_reason_loader);
     loaders.insert(stock_on_hand);
+    loaders.insert(hold_by_record_id_loader);
+    loaders.insert(goods_receipt_inspection_by_invoice_id_loader);
 
     loaders
 }
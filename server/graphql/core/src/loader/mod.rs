@@ -1,3 +1,5 @@
+mod goods_receipt_inspection;
+mod hold;
 mod inventory_adjustment_reason;
 mod invoice;
 mod invoice_line;
@@ -13,12 +15,15 @@ mod requisition;
 mod requisition_line;
 mod requisition_supply_status;
 mod stock_line;
+mod stocktake;
 mod stocktake_lines;
 mod store;
 mod user;
 
 use std::{collections::HashSet, hash::Hasher};
 
+pub use goods_receipt_inspection::GoodsReceiptInspectionByInvoiceIdLoader;
+pub use hold::HoldByRecordIdLoader;
 pub use inventory_adjustment_reason::*;
 pub use invoice::*;
 pub use invoice_line::*;
@@ -34,6 +39,7 @@ pub use requisition::*;
 pub use requisition_line::*;
 pub use requisition_supply_status::*;
 pub use stock_line::*;
+pub use stocktake::*;
 pub use stocktake_lines::*;
 pub use store::*;
 pub use user::*;
@@ -2,8 +2,8 @@ use async_graphql::dataloader::*;
 use async_graphql::*;
 use repository::EqualFilter;
 use repository::{
-    RepositoryError, StocktakeLine, StocktakeLineFilter, StocktakeLineRepository,
-    StorageConnectionManager,
+    RepositoryError, StocktakeLine, StocktakeLineCountRow, StocktakeLineCountRowRepository,
+    StocktakeLineFilter, StocktakeLineRepository, StorageConnectionManager,
 };
 use std::collections::HashMap;
 
@@ -38,3 +38,29 @@ impl Loader<String> for StocktakeLineByStocktakeIdLoader {
         Ok(map)
     }
 }
+
+pub struct StocktakeLineCountByStocktakeLineIdLoader {
+    pub connection_manager: StorageConnectionManager,
+}
+
+#[async_trait::async_trait]
+impl Loader<String> for StocktakeLineCountByStocktakeLineIdLoader {
+    type Value = Vec<StocktakeLineCountRow>;
+    type Error = RepositoryError;
+
+    async fn load(
+        &self,
+        stocktake_line_ids: &[String],
+    ) -> Result<HashMap<String, Self::Value>, Self::Error> {
+        let connection = self.connection_manager.connection()?;
+        let repo = StocktakeLineCountRowRepository::new(&connection);
+
+        let mut map: HashMap<String, Vec<StocktakeLineCountRow>> = HashMap::new();
+        for count in repo.find_many_by_stocktake_line_ids(stocktake_line_ids)? {
+            map.entry(count.stocktake_line_id.clone())
+                .or_default()
+                .push(count);
+        }
+        Ok(map)
+    }
+}
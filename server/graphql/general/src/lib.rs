@@ -17,6 +17,7 @@ use mutations::{
     },
     initialise_site::{initialise_site, InitialiseSiteResponse},
     manual_sync::manual_sync,
+    store_preference::{update_store_preferences, UpdateStorePreferencesInput},
     sync_settings::{update_sync_settings, UpdateSyncSettingsResponse},
 };
 use queries::{
@@ -290,6 +291,16 @@ impl GeneralMutations {
     ) -> Result<mutations::barcode::InsertResponse> {
         insert_barcode(ctx, &store_id, input)
     }
+
+    /// Updates the local store settings, i.e. settings that are not synced with the central server
+    pub async fn update_store_preferences(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: UpdateStorePreferencesInput,
+    ) -> Result<StorePreferenceNode> {
+        update_store_preferences(ctx, &store_id, input)
+    }
 }
 
 /// Auth is not checked during initialisation stage
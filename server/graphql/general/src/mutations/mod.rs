@@ -3,4 +3,5 @@ pub mod common;
 pub mod display_settings;
 pub mod initialise_site;
 pub mod manual_sync;
+pub mod store_preference;
 pub mod sync_settings;
@@ -0,0 +1,72 @@
+use async_graphql::*;
+
+use graphql_core::{
+    generic_inputs::NullableStringUpdate,
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use graphql_types::types::StorePreferenceNode;
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    store_preference::{
+        update_store_preferences as update, UpdateStorePreferences,
+        UpdateStorePreferencesError as ServiceError,
+    },
+    NullableUpdate,
+};
+
+/// Settings that are not provided are kept
+#[derive(InputObject)]
+pub struct UpdateStorePreferencesInput {
+    /// IANA timezone name, e.g. "Pacific/Auckland"
+    pub timezone: Option<NullableStringUpdate>,
+    /// ISO 4217 currency code, e.g. "NZD"
+    pub currency_code: Option<NullableStringUpdate>,
+    /// Hold received stock until it passes QA inspection
+    pub requires_goods_receipt_inspection: Option<bool>,
+}
+
+impl UpdateStorePreferencesInput {
+    pub fn to_domain(self) -> UpdateStorePreferences {
+        UpdateStorePreferences {
+            timezone: self.timezone.map(|timezone| NullableUpdate {
+                value: timezone.value,
+            }),
+            currency_code: self.currency_code.map(|currency_code| NullableUpdate {
+                value: currency_code.value,
+            }),
+            requires_goods_receipt_inspection: self.requires_goods_receipt_inspection,
+        }
+    }
+}
+
+pub fn update_store_preferences(
+    ctx: &Context<'_>,
+    store_id: &str,
+    input: UpdateStorePreferencesInput,
+) -> Result<StorePreferenceNode> {
+    validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateStorePreferences,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let connection = ctx.get_connection_manager().connection()?;
+    match update(&connection, store_id, input.to_domain()) {
+        Ok(result) => Ok(StorePreferenceNode::from_domain(result)),
+        Err(error) => {
+            use StandardGraphqlError::*;
+            let formatted_error = format!("{:#?}", error);
+
+            let graphql_error = match error {
+                ServiceError::InvalidTimezone => BadUserInput(formatted_error),
+                ServiceError::InvalidCurrencyCode => BadUserInput(formatted_error),
+                ServiceError::DatabaseError(_) => InternalError(formatted_error),
+            };
+
+            Err(graphql_error.extend())
+        }
+    }
+}
@@ -5,6 +5,7 @@ use mutations::AddToShipmentFromMasterListInput;
 
 mod invoice_queries;
 use self::invoice_queries::*;
+pub use self::invoice_queries::InvoiceFilterInput;
 
 pub mod mutations;
 use self::mutations::{inbound_shipment, outbound_shipment};
@@ -117,6 +118,16 @@ impl InvoiceMutations {
         inbound_shipment::delete(ctx, &store_id, input)
     }
 
+    /// Record the QA inspection result of received inbound shipment lines
+    async fn inspect_inbound_shipment(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: inbound_shipment::InspectInput,
+    ) -> Result<inbound_shipment::InspectResponse> {
+        inbound_shipment::inspect(ctx, &store_id, input)
+    }
+
     /// Add invoice lines from master item master list
     async fn add_to_outbound_shipment_from_master_list(
         &self,
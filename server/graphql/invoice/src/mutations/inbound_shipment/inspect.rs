@@ -0,0 +1,375 @@
+use async_graphql::*;
+
+use graphql_core::simple_generic_errors::{CannotEditInvoice, RecordNotFound};
+use graphql_core::standard_graphql_error::{validate_auth, StandardGraphqlError};
+use graphql_core::ContextExt;
+use graphql_types::types::InvoiceNode;
+use repository::Invoice;
+use service::auth::{Resource, ResourceAccessRequest};
+use service::invoice::inbound_shipment::{
+    InspectInboundShipment as ServiceInput, InspectInboundShipmentError as ServiceError,
+    InspectInboundShipmentLine,
+};
+
+#[derive(InputObject)]
+#[graphql(name = "InspectInboundShipmentLineInput")]
+pub struct InspectLineInput {
+    pub invoice_line_id: String,
+    pub passed: bool,
+    pub note: Option<String>,
+}
+
+#[derive(InputObject)]
+#[graphql(name = "InspectInboundShipmentInput")]
+pub struct InspectInput {
+    pub id: String,
+    pub lines: Vec<InspectLineInput>,
+    /// Quarantine location stock of failed lines is moved to
+    pub quarantine_location_id: Option<String>,
+}
+
+#[derive(SimpleObject)]
+#[graphql(name = "InspectInboundShipmentError")]
+pub struct InspectError {
+    pub error: InspectErrorInterface,
+}
+
+#[derive(Union)]
+#[graphql(name = "InspectInboundShipmentResponse")]
+pub enum InspectResponse {
+    Error(InspectError),
+    Response(InvoiceNode),
+}
+
+pub fn inspect(ctx: &Context<'_>, store_id: &str, input: InspectInput) -> Result<InspectResponse> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateInboundShipment,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+
+    map_response(
+        service_provider
+            .invoice_service
+            .inspect_inbound_shipment(&service_context, input.to_domain()),
+    )
+}
+
+#[derive(Interface)]
+#[graphql(name = "InspectInboundShipmentErrorInterface")]
+#[graphql(field(name = "description", type = "&str"))]
+pub enum InspectErrorInterface {
+    RecordNotFound(RecordNotFound),
+    CannotEditInvoice(CannotEditInvoice),
+}
+
+impl InspectInput {
+    pub fn to_domain(self) -> ServiceInput {
+        let InspectInput {
+            id,
+            lines,
+            quarantine_location_id,
+        } = self;
+
+        ServiceInput {
+            invoice_id: id,
+            lines: lines
+                .into_iter()
+                .map(
+                    |InspectLineInput {
+                         invoice_line_id,
+                         passed,
+                         note,
+                     }| InspectInboundShipmentLine {
+                        invoice_line_id,
+                        passed,
+                        note,
+                    },
+                )
+                .collect(),
+            quarantine_location_id,
+        }
+    }
+}
+
+pub fn map_response(from: Result<Invoice, ServiceError>) -> Result<InspectResponse> {
+    let result = match from {
+        Ok(invoice) => InspectResponse::Response(InvoiceNode::from_domain(invoice)),
+        Err(error) => InspectResponse::Error(InspectError {
+            error: map_error(error)?,
+        }),
+    };
+
+    Ok(result)
+}
+
+fn map_error(error: ServiceError) -> Result<InspectErrorInterface> {
+    use StandardGraphqlError::*;
+    let formatted_error = format!("{:#?}", error);
+
+    let graphql_error = match error {
+        // Structured Errors
+        ServiceError::InvoiceDoesNotExist => {
+            return Ok(InspectErrorInterface::RecordNotFound(RecordNotFound))
+        }
+        ServiceError::CannotEditFinalised => {
+            return Ok(InspectErrorInterface::CannotEditInvoice(CannotEditInvoice))
+        }
+        // Standard Graphql Errors
+        ServiceError::NotThisStoreInvoice => BadUserInput(formatted_error),
+        ServiceError::NotAnInboundShipment => BadUserInput(formatted_error),
+        ServiceError::NoLinesProvided => BadUserInput(formatted_error),
+        ServiceError::LocationDoesNotExist => BadUserInput(formatted_error),
+        ServiceError::LocationIsNotQuarantine => BadUserInput(formatted_error),
+        ServiceError::LineDoesNotExist(_) => BadUserInput(formatted_error),
+        ServiceError::NotThisInvoiceLine(_) => BadUserInput(formatted_error),
+        ServiceError::LineIsNotPendingInspection(_) => BadUserInput(formatted_error),
+        ServiceError::DatabaseError(_) => InternalError(formatted_error),
+        ServiceError::UpdatedInvoiceDoesNotExist => InternalError(formatted_error),
+    };
+
+    Err(graphql_error.extend())
+}
+
+#[cfg(test)]
+mod test {
+    use async_graphql::EmptyMutation;
+    use graphql_core::{
+        assert_graphql_query, assert_standard_graphql_error, test_helpers::setup_graphl_test,
+    };
+    use repository::{
+        mock::{mock_inbound_shipment_c, mock_name_store_a, mock_store_a, MockDataInserts},
+        Invoice, RepositoryError, StorageConnectionManager,
+    };
+    use serde_json::json;
+    use service::{
+        invoice::{
+            inbound_shipment::{
+                InspectInboundShipment as ServiceInput,
+                InspectInboundShipmentError as ServiceError, InspectInboundShipmentLine,
+            },
+            InvoiceServiceTrait,
+        },
+        service_provider::{ServiceContext, ServiceProvider},
+    };
+
+    use crate::InvoiceMutations;
+
+    type InspectMethod = dyn Fn(ServiceInput) -> Result<Invoice, ServiceError> + Sync + Send;
+
+    pub struct TestService(pub Box<InspectMethod>);
+
+    impl InvoiceServiceTrait for TestService {
+        fn inspect_inbound_shipment(
+            &self,
+            _: &ServiceContext,
+            input: ServiceInput,
+        ) -> Result<Invoice, ServiceError> {
+            self.0(input)
+        }
+    }
+
+    fn service_provider(
+        test_service: TestService,
+        connection_manager: &StorageConnectionManager,
+    ) -> ServiceProvider {
+        let mut service_provider = ServiceProvider::new(connection_manager.clone(), "app_data");
+        service_provider.invoice_service = Box::new(test_service);
+        service_provider
+    }
+
+    fn empty_variables() -> serde_json::Value {
+        json!({
+          "input": {
+            "id": "n/a",
+            "lines": [{ "invoiceLineId": "n/a", "passed": true }]
+          }
+        })
+    }
+
+    #[actix_rt::test]
+    async fn test_graphql_inspect_inbound_shipment_errors() {
+        let (_, _, connection_manager, settings) = setup_graphl_test(
+            EmptyMutation,
+            InvoiceMutations,
+            "test_graphql_inspect_inbound_shipment_errors",
+            MockDataInserts::all(),
+        )
+        .await;
+
+        let mutation = r#"
+        mutation ($input: InspectInboundShipmentInput!) {
+            inspectInboundShipment(input: $input, storeId: \"store_a\") {
+                ... on InspectInboundShipmentError {
+                    error {
+                        __typename
+                    }
+                }
+            }
+        }
+        "#;
+
+        //InvoiceDoesNotExist
+        let test_service = TestService(Box::new(|_| Err(ServiceError::InvoiceDoesNotExist)));
+
+        let expected = json!({
+            "inspectInboundShipment": {
+              "error": {
+                "__typename": "RecordNotFound"
+              }
+            }
+          }
+        );
+
+        assert_graphql_query!(
+            &settings,
+            mutation,
+            &Some(empty_variables()),
+            &expected,
+            Some(service_provider(test_service, &connection_manager))
+        );
+
+        //CannotEditFinalised
+        let test_service = TestService(Box::new(|_| Err(ServiceError::CannotEditFinalised)));
+
+        let expected = json!({
+            "inspectInboundShipment" : {
+                "error": {
+                    "__typename": "CannotEditInvoice"
+                }
+            }
+        });
+
+        assert_graphql_query!(
+            &settings,
+            mutation,
+            &Some(empty_variables()),
+            &expected,
+            Some(service_provider(test_service, &connection_manager))
+        );
+
+        //LineIsNotPendingInspection
+        let test_service = TestService(Box::new(|_| {
+            Err(ServiceError::LineIsNotPendingInspection("n/a".to_string()))
+        }));
+        let expected_message = "Bad user input";
+        assert_standard_graphql_error!(
+            &settings,
+            &mutation,
+            &Some(empty_variables()),
+            &expected_message,
+            None,
+            Some(service_provider(test_service, &connection_manager))
+        );
+
+        //LocationIsNotQuarantine
+        let test_service = TestService(Box::new(|_| Err(ServiceError::LocationIsNotQuarantine)));
+        let expected_message = "Bad user input";
+        assert_standard_graphql_error!(
+            &settings,
+            &mutation,
+            &Some(empty_variables()),
+            &expected_message,
+            None,
+            Some(service_provider(test_service, &connection_manager))
+        );
+
+        //DatabaseError
+        let test_service = TestService(Box::new(|_| {
+            Err(ServiceError::DatabaseError(
+                RepositoryError::UniqueViolation("row already exists".to_string()),
+            ))
+        }));
+        let expected_message = "Internal error";
+        assert_standard_graphql_error!(
+            &settings,
+            &mutation,
+            &Some(empty_variables()),
+            &expected_message,
+            None,
+            Some(service_provider(test_service, &connection_manager))
+        );
+    }
+
+    #[actix_rt::test]
+    async fn test_graphql_inspect_inbound_shipment_success() {
+        let (_, _, connection_manager, settings) = setup_graphl_test(
+            EmptyMutation,
+            InvoiceMutations,
+            "test_graphql_inspect_inbound_shipment_success",
+            MockDataInserts::all(),
+        )
+        .await;
+
+        let mutation = r#"
+        mutation ($storeId: String, $input: InspectInboundShipmentInput!) {
+            inspectInboundShipment(storeId: $storeId, input: $input) {
+                ... on InvoiceNode {
+                    id
+                    goodsReceiptInspectionStatus
+                }
+            }
+          }
+        "#;
+
+        let test_service = TestService(Box::new(|input| {
+            assert_eq!(
+                input,
+                ServiceInput {
+                    invoice_id: "id input".to_string(),
+                    lines: vec![
+                        InspectInboundShipmentLine {
+                            invoice_line_id: "line a".to_string(),
+                            passed: true,
+                            note: None,
+                        },
+                        InspectInboundShipmentLine {
+                            invoice_line_id: "line b".to_string(),
+                            passed: false,
+                            note: Some("broken seal".to_string()),
+                        }
+                    ],
+                    quarantine_location_id: Some("location input".to_string()),
+                }
+            );
+            Ok(Invoice {
+                invoice_row: mock_inbound_shipment_c(),
+                name_row: mock_name_store_a(),
+                store_row: mock_store_a(),
+            })
+        }));
+
+        let variables = json!({
+          "input": {
+            "id": "id input",
+            "lines": [
+                { "invoiceLineId": "line a", "passed": true },
+                { "invoiceLineId": "line b", "passed": false, "note": "broken seal" }
+            ],
+            "quarantineLocationId": "location input"
+          },
+          "storeId": "store_a"
+        });
+
+        let expected = json!({
+            "inspectInboundShipment": {
+                "id": mock_inbound_shipment_c().id,
+                "goodsReceiptInspectionStatus": null
+            }
+          }
+        );
+
+        assert_graphql_query!(
+            &settings,
+            mutation,
+            &Some(variables),
+            &expected,
+            Some(service_provider(test_service, &connection_manager))
+        );
+    }
+}
@@ -7,5 +7,8 @@ pub use self::insert::*;
 pub mod update;
 pub use self::update::*;
 
+pub mod inspect;
+pub use self::inspect::*;
+
 pub mod add_from_master_list;
 pub use add_from_master_list::*;
@@ -78,6 +78,16 @@ pub enum UpdateErrorInterface {
     CannotEditInvoice(CannotEditInvoice),
     CannotReverseInvoiceStatus(CannotReverseInvoiceStatus),
     CannotChangeStatusOfInvoiceOnHold(CannotChangeStatusOfInvoiceOnHold),
+    GoodsReceiptInspectionPending(GoodsReceiptInspectionPending),
+}
+
+pub struct GoodsReceiptInspectionPending;
+
+#[Object]
+impl GoodsReceiptInspectionPending {
+    pub async fn description(&self) -> &'static str {
+        "Received stock is pending QA inspection, shipment cannot be verified."
+    }
 }
 
 impl UpdateInput {
@@ -144,6 +154,11 @@ fn map_error(error: ServiceError) -> Result<UpdateErrorInterface> {
                 CannotChangeStatusOfInvoiceOnHold,
             ))
         }
+        ServiceError::GoodsReceiptInspectionPending => {
+            return Ok(UpdateErrorInterface::GoodsReceiptInspectionPending(
+                GoodsReceiptInspectionPending,
+            ))
+        }
         ServiceError::OtherPartyNotASupplier => {
             return Ok(UpdateErrorInterface::OtherPartyNotASupplier(
                 OtherPartyNotASupplier,
@@ -339,6 +354,27 @@ mod test {
             Some(service_provider(test_service, &connection_manager))
         );
 
+        //GoodsReceiptInspectionPending
+        let test_service = TestService(Box::new(|_| {
+            Err(ServiceError::GoodsReceiptInspectionPending)
+        }));
+
+        let expected = json!({
+            "updateInboundShipment" : {
+                "error": {
+                    "__typename": "GoodsReceiptInspectionPending"
+                }
+            }
+        });
+
+        assert_graphql_query!(
+            &settings,
+            mutation,
+            &Some(empty_variables()),
+            &expected,
+            Some(service_provider(test_service, &connection_manager))
+        );
+
         //OtherPartyNotASupplier
         let test_service = TestService(Box::new(|_| Err(ServiceError::OtherPartyNotASupplier)));
 
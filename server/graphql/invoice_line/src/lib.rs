@@ -94,8 +94,11 @@ impl InvoiceLineMutations {
         ctx: &Context<'_>,
         store_id: String,
         line_id: String,
+        location_types: Option<
+            outbound_shipment_line::unallocated_line::AllocationLocationTypesInput,
+        >,
     ) -> Result<outbound_shipment_line::unallocated_line::AllocateResponse> {
-        outbound_shipment_line::unallocated_line::allocate(ctx, &store_id, line_id)
+        outbound_shipment_line::unallocated_line::allocate(ctx, &store_id, line_id, location_types)
     }
 
     // Inbound
@@ -3,15 +3,25 @@ use graphql_core::{
     simple_generic_errors::RecordNotFound, standard_graphql_error::validate_auth,
     standard_graphql_error::StandardGraphqlError, ContextExt,
 };
-use graphql_types::types::{DeleteResponse, InvoiceLineConnector, StockLineConnector};
+use graphql_types::types::{
+    DeleteResponse, InvoiceLineConnector, LocationNodeType, StockLineConnector,
+};
 use service::{
     auth::{Resource, ResourceAccessRequest},
     invoice_line::outbound_shipment_unallocated_line::{
         AllocateLineResult as ServiceResult,
-        AllocateOutboundShipmentUnallocatedLineError as ServiceError,
+        AllocateOutboundShipmentUnallocatedLineError as ServiceError, AllocationLocationTypes,
     },
 };
 
+#[derive(InputObject)]
+pub struct AllocationLocationTypesInput {
+    /// Stock in these location types is allocated first
+    pub prefer: Option<Vec<LocationNodeType>>,
+    /// Stock in these location types is not allocated
+    pub exclude: Option<Vec<LocationNodeType>>,
+}
+
 #[derive(Interface)]
 #[graphql(name = "AllocateOutboundShipmentUnallocatedLineErrorInterface")]
 #[graphql(field(name = "description", type = "String"))]
@@ -42,7 +52,12 @@ pub struct ResponseNode {
     issued_expiring_soon_stock_lines: StockLineConnector,
 }
 
-pub fn allocate(ctx: &Context<'_>, store_id: &str, line_id: String) -> Result<AllocateResponse> {
+pub fn allocate(
+    ctx: &Context<'_>,
+    store_id: &str,
+    line_id: String,
+    location_types: Option<AllocationLocationTypesInput>,
+) -> Result<AllocateResponse> {
     let user = validate_auth(
         ctx,
         &ResourceAccessRequest {
@@ -57,7 +72,13 @@ pub fn allocate(ctx: &Context<'_>, store_id: &str, line_id: String) -> Result<Al
     map_response(
         service_provider
             .invoice_line_service
-            .allocate_outbound_shipment_unallocated_line(&service_context, line_id),
+            .allocate_outbound_shipment_unallocated_line(
+                &service_context,
+                line_id,
+                location_types
+                    .map(AllocationLocationTypesInput::to_domain)
+                    .unwrap_or_default(),
+            ),
     )
 }
 
@@ -72,6 +93,23 @@ pub fn map_response(from: Result<ServiceResult, ServiceError>) -> Result<Allocat
     Ok(result)
 }
 
+impl AllocationLocationTypesInput {
+    pub fn to_domain(self) -> AllocationLocationTypes {
+        let to_domain = |location_types: Option<Vec<LocationNodeType>>| {
+            location_types
+                .unwrap_or_default()
+                .into_iter()
+                .map(LocationNodeType::to_domain)
+                .collect()
+        };
+
+        AllocationLocationTypes {
+            prefer: to_domain(self.prefer),
+            exclude: to_domain(self.exclude),
+        }
+    }
+}
+
 fn map_error(error: ServiceError) -> Result<AllocateErrorInterface> {
     use StandardGraphqlError::*;
     let formatted_error = format!("{:#?}", error);
@@ -132,6 +170,7 @@ mod graphql {
             outbound_shipment_unallocated_line::{
                 AllocateLineResult as ServiceResult,
                 AllocateOutboundShipmentUnallocatedLineError as ServiceError,
+                AllocationLocationTypes,
             },
             InvoiceLineServiceTrait,
         },
@@ -150,6 +189,7 @@ mod graphql {
             &self,
             _: &ServiceContext,
             input: String,
+            _: AllocationLocationTypes,
         ) -> Result<ServiceResult, ServiceError> {
             self.0(input)
         }
@@ -10,6 +10,7 @@ use async_graphql::{EmptyMutation, EmptySubscription, Schema};
 use async_graphql::{MergedObject, Response};
 use async_graphql_actix_web::{GraphQLRequest, GraphQLResponse};
 use graphql_batch_mutations::BatchMutations;
+use graphql_cold_chain::{ColdChainMutations, ColdChainQueries};
 use graphql_core::loader::LoaderRegistry;
 use graphql_core::{auth_data_from_request, BoxedSelfRequest, RequestUserData, SelfRequest};
 use graphql_general::{
@@ -49,6 +50,7 @@ pub struct Queries(
     pub RequisitionQueries,
     pub ReportQueries,
     pub StockLineQueries,
+    pub ColdChainQueries,
 );
 
 impl Queries {
@@ -61,6 +63,7 @@ impl Queries {
             RequisitionQueries,
             ReportQueries,
             StockLineQueries,
+            ColdChainQueries,
         )
     }
 }
@@ -77,6 +80,7 @@ pub struct Mutations(
     pub RequisitionLineMutations,
     pub StockLineMutations,
     pub GeneralMutations,
+    pub ColdChainMutations,
 );
 
 impl Mutations {
@@ -92,6 +96,7 @@ impl Mutations {
             RequisitionLineMutations,
             StockLineMutations,
             GeneralMutations,
+            ColdChainMutations,
         )
     }
 }
@@ -8,7 +8,7 @@ use graphql_core::{
     ContextExt,
 };
 use graphql_types::types::*;
-use repository::{EqualFilter, LocationFilter, PaginationOption};
+use repository::{EqualFilter, LocationFilter, LocationMovementFilter, PaginationOption};
 use service::auth::{Resource, ResourceAccessRequest};
 
 #[derive(Default, Clone)]
@@ -59,6 +59,49 @@ impl LocationQueries {
             locations,
         )))
     }
+
+    /// Query the history of stock lines moving in and out of locations
+    pub async fn location_movements(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        #[graphql(desc = "Pagination option (first and offset)")] page: Option<PaginationInput>,
+        #[graphql(desc = "Filter option")] filter: Option<LocationMovementFilterInput>,
+        #[graphql(desc = "Sort options (only first sort input is evaluated for this endpoint)")]
+        sort: Option<Vec<LocationMovementSortInput>>,
+    ) -> Result<LocationMovementsResponse> {
+        let user = validate_auth(
+            ctx,
+            &ResourceAccessRequest {
+                resource: Resource::QueryLocation,
+                store_id: Some(store_id.clone()),
+            },
+        )?;
+
+        let service_provider = ctx.service_provider();
+        let service_context = service_provider.context(store_id.clone(), user.user_id)?;
+
+        // always filter by store_id
+        let filter = filter
+            .map(LocationMovementFilter::from)
+            .unwrap_or(LocationMovementFilter::new())
+            .store_id(EqualFilter::equal_to(&store_id));
+
+        let location_movements = service_provider
+            .location_service
+            .get_location_movements(
+                &service_context,
+                page.map(PaginationOption::from),
+                Some(filter),
+                sort.and_then(|mut sort_list| sort_list.pop())
+                    .map(|sort| sort.to_domain()),
+            )
+            .map_err(StandardGraphqlError::from_list_error)?;
+
+        Ok(LocationMovementsResponse::Response(
+            LocationMovementConnector::from_domain(location_movements),
+        ))
+    }
 }
 
 #[derive(Default, Clone)]
@@ -102,7 +145,7 @@ mod test {
     use repository::mock::mock_locations;
     use repository::{
         mock::MockDataInserts, Location, LocationFilter, LocationRow, LocationSort,
-        LocationSortField, StorageConnectionManager,
+        LocationSortField, LocationType, StorageConnectionManager,
     };
     use repository::{EqualFilter, PaginationOption, Sort};
     use serde_json::json;
@@ -189,6 +232,10 @@ mod test {
                         code: "test_code".to_owned(),
                         on_hold: true,
                         store_id: "store_a".to_owned(),
+                        parent_id: None,
+                        location_type: LocationType::Ambient,
+                        volume_capacity: None,
+                        pack_capacity: None,
                     },
                 }],
                 count: 1,
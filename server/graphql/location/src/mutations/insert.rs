@@ -6,7 +6,7 @@ use graphql_core::{
     standard_graphql_error::{validate_auth, StandardGraphqlError},
     ContextExt,
 };
-use graphql_types::types::LocationNode;
+use graphql_types::types::{LocationNode, LocationNodeType};
 use service::{
     auth::{Resource, ResourceAccessRequest},
     location::insert::{InsertLocation, InsertLocationError as ServiceError},
@@ -47,6 +47,10 @@ pub struct InsertLocationInput {
     pub code: String,
     pub name: Option<String>,
     pub on_hold: Option<bool>,
+    pub parent_id: Option<String>,
+    pub location_type: Option<LocationNodeType>,
+    pub volume_capacity: Option<f64>,
+    pub pack_capacity: Option<f64>,
 }
 
 impl From<InsertLocationInput> for InsertLocation {
@@ -56,6 +60,10 @@ impl From<InsertLocationInput> for InsertLocation {
             code,
             name,
             on_hold,
+            parent_id,
+            location_type,
+            volume_capacity,
+            pack_capacity,
         }: InsertLocationInput,
     ) -> Self {
         InsertLocation {
@@ -63,6 +71,10 @@ impl From<InsertLocationInput> for InsertLocation {
             code,
             name,
             on_hold,
+            parent_id,
+            location_type: location_type.map(LocationNodeType::to_domain),
+            volume_capacity,
+            pack_capacity,
         }
     }
 }
@@ -94,6 +106,8 @@ fn map_error(error: ServiceError) -> Result<InsertLocationErrorInterface> {
         // Standard Graphql Errors
         ServiceError::LocationAlreadyExists => BadUserInput(formatted_error),
         ServiceError::LocationWithCodeAlreadyExists => BadUserInput(formatted_error),
+        ServiceError::ParentLocationDoesNotExist => BadUserInput(formatted_error),
+        ServiceError::InvalidCapacity => BadUserInput(formatted_error),
         ServiceError::CreatedRecordNotFound => InternalError(formatted_error),
         ServiceError::DatabaseError(_) => InternalError(formatted_error),
     };
@@ -108,7 +122,9 @@ mod test {
     use graphql_core::{
         assert_graphql_query, assert_standard_graphql_error, test_helpers::setup_graphl_test,
     };
-    use repository::{mock::MockDataInserts, Location, LocationRow, StorageConnectionManager};
+    use repository::{
+        mock::MockDataInserts, Location, LocationRow, LocationType, StorageConnectionManager,
+    };
     use serde_json::json;
 
     use service::{
@@ -289,6 +305,10 @@ mod test {
                     code: "code".to_owned(),
                     on_hold: true,
                     store_id: "store_a".to_owned(),
+                    parent_id: None,
+                    location_type: LocationType::Ambient,
+                    volume_capacity: None,
+                    pack_capacity: None,
                 },
             })
         }));
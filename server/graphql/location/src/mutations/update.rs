@@ -8,7 +8,7 @@ use graphql_core::{
     standard_graphql_error::{validate_auth, StandardGraphqlError},
     ContextExt,
 };
-use graphql_types::types::LocationNode;
+use graphql_types::types::{LocationNode, LocationNodeType};
 use service::{
     auth::{Resource, ResourceAccessRequest},
     location::update::{UpdateLocation, UpdateLocationError as ServiceError},
@@ -49,6 +49,13 @@ pub struct UpdateLocationInput {
     pub code: Option<String>,
     pub name: Option<String>,
     pub on_hold: Option<bool>,
+    /// Set to null to move the location to the top level
+    pub parent_id: MaybeUndefined<String>,
+    pub location_type: Option<LocationNodeType>,
+    /// Set to null to clear the capacity
+    pub volume_capacity: MaybeUndefined<f64>,
+    /// Set to null to clear the capacity
+    pub pack_capacity: MaybeUndefined<f64>,
 }
 
 impl From<UpdateLocationInput> for UpdateLocation {
@@ -58,6 +65,10 @@ impl From<UpdateLocationInput> for UpdateLocation {
             code,
             name,
             on_hold,
+            parent_id,
+            location_type,
+            volume_capacity,
+            pack_capacity,
         }: UpdateLocationInput,
     ) -> Self {
         UpdateLocation {
@@ -65,6 +76,22 @@ impl From<UpdateLocationInput> for UpdateLocation {
             code,
             name,
             on_hold,
+            parent_id: match parent_id {
+                MaybeUndefined::Undefined => None,
+                MaybeUndefined::Null => Some(None),
+                MaybeUndefined::Value(parent_id) => Some(Some(parent_id)),
+            },
+            location_type: location_type.map(LocationNodeType::to_domain),
+            volume_capacity: match volume_capacity {
+                MaybeUndefined::Undefined => None,
+                MaybeUndefined::Null => Some(None),
+                MaybeUndefined::Value(volume_capacity) => Some(Some(volume_capacity)),
+            },
+            pack_capacity: match pack_capacity {
+                MaybeUndefined::Undefined => None,
+                MaybeUndefined::Null => Some(None),
+                MaybeUndefined::Value(pack_capacity) => Some(Some(pack_capacity)),
+            },
         }
     }
 }
@@ -99,6 +126,8 @@ fn map_error(error: ServiceError) -> Result<UpdateLocationErrorInterface> {
         ServiceError::LocationDoesNotExist => BadUserInput(formatted_error),
         ServiceError::CodeAlreadyExists => BadUserInput(formatted_error),
         ServiceError::LocationDoesNotBelongToCurrentStore => BadUserInput(formatted_error),
+        ServiceError::InvalidParentLocation => BadUserInput(formatted_error),
+        ServiceError::InvalidCapacity => BadUserInput(formatted_error),
         ServiceError::UpdatedRecordNotFound => InternalError(formatted_error),
         ServiceError::DatabaseError(_) => InternalError(formatted_error),
     };
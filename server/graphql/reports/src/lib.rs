@@ -1,6 +1,9 @@
 use async_graphql::*;
 use graphql_core::pagination::PaginationInput;
-use printing::{print_report, print_report_definition, PrintReportResponse};
+use printing::{
+    print_report, print_report_batch, print_report_definition, PrintReportBatchFilterInput,
+    PrintReportBatchResponse, PrintReportResponse,
+};
 use reports::{reports, ReportFilterInput, ReportSortInput, ReportsResponse};
 
 mod printing;
@@ -15,6 +18,19 @@ pub enum PrintFormat {
     Html,
 }
 
+impl PrintFormat {
+    fn to_domain(
+        format: Option<PrintFormat>,
+    ) -> Option<service::report::report_service::PrintFormat> {
+        match format {
+            Some(PrintFormat::Html) => Some(service::report::report_service::PrintFormat::Html),
+            Some(PrintFormat::Pdf) | None => {
+                Some(service::report::report_service::PrintFormat::Pdf)
+            }
+        }
+    }
+}
+
 #[Object]
 impl ReportQueries {
     /// Queries a list of available reports
@@ -43,16 +59,49 @@ impl ReportQueries {
         #[graphql(
             desc = "The data id that should be used for the report, e.g. the invoice id when printing an invoice"
         )]
-        data_id: String,
+        data_id: Option<String>,
+        #[graphql(
+            desc = "Optional report arguments, e.g. a date range, which are passed to the report query as variables"
+        )]
+        arguments: Option<serde_json::Value>,
         format: Option<PrintFormat>,
     ) -> Result<PrintReportResponse> {
-        let report_format = match format {
-            Some(PrintFormat::Html) => Some(service::report::report_service::PrintFormat::Html),
-            Some(PrintFormat::Pdf) | None => {
-                Some(service::report::report_service::PrintFormat::Pdf)
-            }
-        };
-        print_report(ctx, store_id, report_id, data_id, report_format).await
+        let report_format = PrintFormat::to_domain(format);
+        print_report(ctx, store_id, report_id, data_id, arguments, report_format).await
+    }
+
+    /// Prints multiple records, e.g. all outbound shipments picked today, into a single file.
+    ///
+    /// Each record starts on a new page. Records that fail to print are returned as errors and
+    /// don't fail the whole batch.
+    /// The printed report can be retrieved from the `/files` endpoint using the returned file id.
+    pub async fn print_report_batch(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        #[graphql(desc = "The id of the report to be printed")] report_id: String,
+        #[graphql(desc = "The data ids of the records to be printed")] data_ids: Option<
+            Vec<String>,
+        >,
+        #[graphql(desc = "Selects the records to be printed, in addition to the dataIds")]
+        filter: Option<PrintReportBatchFilterInput>,
+        #[graphql(
+            desc = "Optional report arguments which are passed to the report query of each record"
+        )]
+        arguments: Option<serde_json::Value>,
+        format: Option<PrintFormat>,
+    ) -> Result<PrintReportBatchResponse> {
+        let report_format = PrintFormat::to_domain(format);
+        print_report_batch(
+            ctx,
+            store_id,
+            report_id,
+            data_ids,
+            filter,
+            arguments,
+            report_format,
+        )
+        .await
     }
 
     pub async fn print_report_definition(
@@ -60,9 +109,13 @@ impl ReportQueries {
         ctx: &Context<'_>,
         store_id: String,
         #[graphql(desc = "Name of the report")] name: Option<String>,
-        #[graphql(desc = "The report definition to be printed")] report: serde_json::Value,
-        data_id: String,
+        #[graphql(
+            desc = "The report definition to be printed, definitions with a SQL query require server admin permission"
+        )]
+        report: serde_json::Value,
+        data_id: Option<String>,
+        arguments: Option<serde_json::Value>,
     ) -> Result<PrintReportResponse> {
-        print_report_definition(ctx, store_id, name, report, data_id).await
+        print_report_definition(ctx, store_id, name, report, data_id, arguments).await
     }
 }
@@ -1,9 +1,16 @@
+use std::collections::{HashMap, HashSet};
+
 use async_graphql::*;
 use graphql_core::standard_graphql_error::{validate_auth, StandardGraphqlError};
 use graphql_core::{ContextExt, RequestUserData};
+use graphql_invoice::InvoiceFilterInput;
+use graphql_requisition::RequisitionFilterInput;
+use graphql_stocktake::StocktakeFilterInput;
 use service::auth::{Resource, ResourceAccessRequest};
 use service::report::definition::{GraphQlQuery, ReportDefinition};
-use service::report::report_service::{PrintFormat, ReportError};
+use service::report::report_service::{
+    BatchReportData, PrintFormat, ReportBatchFilter, ReportError, ReportQuery, MAX_BATCH_SIZE,
+};
 
 pub struct FailedToFetchReportData {
     errors: serde_json::Value,
@@ -54,7 +61,8 @@ pub async fn print_report(
     ctx: &Context<'_>,
     store_id: String,
     report_id: String,
-    data_id: String,
+    data_id: Option<String>,
+    arguments: Option<serde_json::Value>,
     format: Option<PrintFormat>,
 ) -> Result<PrintReportResponse> {
     let user = validate_auth(
@@ -66,7 +74,7 @@ pub async fn print_report(
     )?;
 
     let service_provider = ctx.service_provider();
-    let service_context = service_provider.context(store_id.clone(), user.user_id)?;
+    let service_context = service_provider.context(store_id.clone(), user.user_id.clone())?;
     let service = &service_provider.report_service;
 
     // get the required report
@@ -79,9 +87,10 @@ pub async fn print_report(
         }
     };
     let query = resolved_report.query.clone();
+    let variables = query.query_variables(&store_id, data_id.as_deref(), arguments.as_ref());
 
     // fetch data required for the report
-    let result = fetch_data(ctx, query, &store_id, &data_id)
+    let result = fetch_report_data(ctx, &store_id, &user.user_id, &query, variables.clone())
         .await
         .map_err(|err| StandardGraphqlError::InternalError(format!("{:#?}", err)))?;
     let report_data = match result {
@@ -100,6 +109,7 @@ pub async fn print_report(
         &ctx.get_settings().server.base_dir,
         &resolved_report,
         report_data,
+        Some(variables),
         format,
     ) {
         Ok(file_id) => file_id,
@@ -118,7 +128,8 @@ pub async fn print_report_definition(
     store_id: String,
     name: Option<String>,
     report: serde_json::Value,
-    data_id: String,
+    data_id: Option<String>,
+    arguments: Option<serde_json::Value>,
 ) -> Result<PrintReportResponse> {
     let user = validate_auth(
         ctx,
@@ -129,7 +140,7 @@ pub async fn print_report_definition(
     )?;
 
     let service_provider = ctx.service_provider();
-    let service_context = service_provider.context(store_id.clone(), user.user_id)?;
+    let service_context = service_provider.context(store_id.clone(), user.user_id.clone())?;
     let service = &service_provider.report_service;
 
     // get the required report
@@ -147,10 +158,21 @@ pub async fn print_report_definition(
             }))
         }
     };
+    // ad-hoc SQL queries can read any whitelisted table, only allow them for server admins
+    if let ReportQuery::SqlQuery(_) = &resolved_report.query {
+        validate_auth(
+            ctx,
+            &ResourceAccessRequest {
+                resource: Resource::ServerAdmin,
+                store_id: None,
+            },
+        )?;
+    }
     let query = resolved_report.query.clone();
+    let variables = query.query_variables(&store_id, data_id.as_deref(), arguments.as_ref());
 
     // fetch data required for the report
-    let result = fetch_data(ctx, query, &store_id, &data_id)
+    let result = fetch_report_data(ctx, &store_id, &user.user_id, &query, variables.clone())
         .await
         .map_err(|err| StandardGraphqlError::InternalError(format!("{:#?}", err)))?;
     let report_data = match result {
@@ -169,6 +191,7 @@ pub async fn print_report_definition(
         &ctx.get_settings().server.base_dir,
         &resolved_report,
         report_data,
+        Some(variables),
         None,
     ) {
         Ok(file_id) => file_id,
@@ -182,20 +205,252 @@ pub async fn print_report_definition(
     Ok(PrintReportResponse::Response(PrintReportNode { file_id }))
 }
 
+/// Selects the records of a batch print, exactly one of the filters must be set
+#[derive(InputObject)]
+pub struct PrintReportBatchFilterInput {
+    /// Used for inbound and outbound shipment reports
+    pub invoice: Option<InvoiceFilterInput>,
+    pub requisition: Option<RequisitionFilterInput>,
+    pub stocktake: Option<StocktakeFilterInput>,
+}
+
+impl PrintReportBatchFilterInput {
+    fn to_domain(self) -> Option<ReportBatchFilter> {
+        match (self.invoice, self.requisition, self.stocktake) {
+            (Some(invoice), None, None) => Some(ReportBatchFilter::Invoice(invoice.to_domain())),
+            (None, Some(requisition), None) => {
+                Some(ReportBatchFilter::Requisition(requisition.to_domain()))
+            }
+            (None, None, Some(stocktake)) => Some(ReportBatchFilter::Stocktake(stocktake.into())),
+            _ => None,
+        }
+    }
+}
+
+#[derive(PartialEq, Debug)]
+pub struct PrintReportBatchRecordError {
+    data_id: String,
+    description: String,
+    errors: Option<serde_json::Value>,
+}
+
+#[Object]
+impl PrintReportBatchRecordError {
+    /// The data id of the record that failed to print
+    pub async fn data_id(&self) -> &str {
+        &self.data_id
+    }
+
+    pub async fn description(&self) -> &str {
+        &self.description
+    }
+
+    /// Query errors if the data of the record couldn't be fetched
+    pub async fn errors(&self) -> &Option<serde_json::Value> {
+        &self.errors
+    }
+}
+
+#[derive(PartialEq, Debug)]
+pub struct PrintReportBatchNode {
+    file_id: Option<String>,
+    printed_count: u32,
+    errors: Vec<PrintReportBatchRecordError>,
+}
+
+#[Object]
+impl PrintReportBatchNode {
+    /// Return the file id of the printed report, null if no record could be printed.
+    /// The file can be fetched using the /files?id={id} endpoint
+    pub async fn file_id(&self) -> &Option<String> {
+        &self.file_id
+    }
+
+    /// Number of records contained in the printed report
+    pub async fn printed_count(&self) -> u32 {
+        self.printed_count
+    }
+
+    /// Records that failed to print and are not contained in the printed report
+    pub async fn errors(&self) -> &Vec<PrintReportBatchRecordError> {
+        &self.errors
+    }
+}
+
+#[derive(Union)]
+pub enum PrintReportBatchResponse {
+    Error(PrintReportError),
+    Response(PrintReportBatchNode),
+}
+
+pub async fn print_report_batch(
+    ctx: &Context<'_>,
+    store_id: String,
+    report_id: String,
+    data_ids: Option<Vec<String>>,
+    filter: Option<PrintReportBatchFilterInput>,
+    arguments: Option<serde_json::Value>,
+    format: Option<PrintFormat>,
+) -> Result<PrintReportBatchResponse> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::Report,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.clone(), user.user_id.clone())?;
+    let service = &service_provider.report_service;
+
+    // get the required report
+    let resolved_report = match service.resolve_report(&service_context, &report_id) {
+        Ok(resolved_report) => resolved_report,
+        Err(err) => {
+            return Ok(PrintReportBatchResponse::Error(PrintReportError {
+                error: map_error(err)?,
+            }))
+        }
+    };
+
+    // collect the data ids of all records to be printed
+    if data_ids.is_none() && filter.is_none() {
+        return Err(StandardGraphqlError::BadUserInput(
+            "Either dataIds or filter must be set".to_string(),
+        )
+        .extend());
+    }
+    let mut ids = data_ids.unwrap_or_default();
+    if let Some(filter) = filter {
+        let filter = filter.to_domain().ok_or_else(|| {
+            StandardGraphqlError::BadUserInput(
+                "Exactly one of the invoice, requisition or stocktake filters must be set"
+                    .to_string(),
+            )
+            .extend()
+        })?;
+        let filtered_ids = service
+            .batch_data_ids(&service_context, filter)
+            .map_err(|err| StandardGraphqlError::from_repository_error(err).extend())?;
+        ids.extend(filtered_ids);
+    }
+    let mut seen = HashSet::new();
+    ids.retain(|id| seen.insert(id.clone()));
+    if ids.len() > MAX_BATCH_SIZE {
+        return Err(StandardGraphqlError::BadUserInput(format!(
+            "Too many records ({}), at most {} records can be printed in a batch",
+            ids.len(),
+            MAX_BATCH_SIZE
+        ))
+        .extend());
+    }
+
+    // fetch data for each record, records that fail are reported but don't fail the batch
+    let query = resolved_report.query.clone();
+    let mut records = Vec::new();
+    let mut errors = Vec::new();
+    for data_id in &ids {
+        let variables = query.query_variables(&store_id, Some(data_id), arguments.as_ref());
+        match fetch_report_data(ctx, &store_id, &user.user_id, &query, variables.clone()).await {
+            Ok(FetchResult::Data(report_data)) => records.push(BatchReportData {
+                data_id: data_id.clone(),
+                report_data,
+                arguments: Some(variables),
+            }),
+            Ok(FetchResult::Error(fetch_errors)) => errors.push(PrintReportBatchRecordError {
+                data_id: data_id.clone(),
+                description: "Failed to query data required for the report".to_string(),
+                errors: Some(fetch_errors),
+            }),
+            Err(err) => errors.push(PrintReportBatchRecordError {
+                data_id: data_id.clone(),
+                description: format!("{:#?}", err),
+                errors: None,
+            }),
+        }
+    }
+    let fetched_count = records.len();
+
+    // print all records into a single file
+    let result = match service.print_html_report_batch(
+        &ctx.get_settings().server.base_dir,
+        &resolved_report,
+        records,
+        format,
+    ) {
+        Ok(result) => result,
+        Err(err) => {
+            return Ok(PrintReportBatchResponse::Error(PrintReportError {
+                error: map_error(err)?,
+            }))
+        }
+    };
+    let printed_count = fetched_count - result.errors.len();
+    errors.extend(
+        result
+            .errors
+            .into_iter()
+            .map(|record| PrintReportBatchRecordError {
+                data_id: record.data_id,
+                description: match record.error {
+                    ReportError::DocGenerationError(message) => message,
+                    error => format!("{:#?}", error),
+                },
+                errors: None,
+            }),
+    );
+    // report errors in the order of the requested records
+    let order: HashMap<&String, usize> = ids.iter().enumerate().map(|(i, id)| (id, i)).collect();
+    errors.sort_by_key(|error| order.get(&error.data_id).cloned().unwrap_or_default());
+
+    Ok(PrintReportBatchResponse::Response(PrintReportBatchNode {
+        file_id: result.file_id,
+        printed_count: printed_count as u32,
+        errors,
+    }))
+}
+
 enum FetchResult {
     Data(serde_json::Value),
     Error(serde_json::Value),
 }
 
+async fn fetch_report_data(
+    ctx: &Context<'_>,
+    store_id: &str,
+    user_id: &str,
+    query: &ReportQuery,
+    variables: serde_json::Value,
+) -> anyhow::Result<FetchResult> {
+    let query = match query {
+        ReportQuery::GraphQlQuery(query) => return fetch_data(ctx, query.clone(), variables).await,
+        ReportQuery::SqlQuery(query) => query,
+    };
+    let service_provider = ctx.service_provider();
+    // the query is bound to the store of the context
+    let service_context = service_provider.context(store_id.to_string(), user_id.to_string())?;
+    match service_provider
+        .report_service
+        .query_sql_report_data(&service_context, query, &variables)
+    {
+        Ok(data) => Ok(FetchResult::Data(data)),
+        // report errors in the same format as GraphQL errors
+        Err(ReportError::QueryError(message)) => Ok(FetchResult::Error(
+            serde_json::json!([{ "message": message }]),
+        )),
+        Err(err) => Err(anyhow::Error::msg(format!("{:#?}", err))),
+    }
+}
+
 async fn fetch_data(
     ctx: &Context<'_>,
     query: GraphQlQuery,
-    store_id: &str,
-    data_id: &str,
+    variables: serde_json::Value,
 ) -> anyhow::Result<FetchResult> {
     let user_data = ctx.data_unchecked::<RequestUserData>().clone();
     let self_requester = ctx.self_request().unwrap();
-    let variables = serde_json::from_value(query.query_variables(store_id, data_id))?;
+    let variables = serde_json::from_value(variables)?;
     let request = Request::new(query.query).variables(variables);
     let response = self_requester.call(request, user_data).await;
     if !response.errors.is_empty() {
@@ -8,6 +8,7 @@ use program_settings::{get_program_requisition_settings, ProgramRequisitionSetti
 
 use self::mutations::{request_requisition, response_requisition};
 use self::requisition_queries::*;
+pub use self::requisition_queries::RequisitionFilterInput;
 #[derive(Default, Clone)]
 pub struct RequisitionQueries;
 
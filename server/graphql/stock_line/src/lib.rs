@@ -1,5 +1,6 @@
 pub mod mutations;
 use async_graphql::*;
+use chrono::{DateTime, NaiveDate, Utc};
 use graphql_core::{
     generic_filters::{DateFilterInput, EqualFilterStringInput, SimpleStringFilterInput},
     pagination::PaginationInput,
@@ -8,9 +9,14 @@ use graphql_core::{
 };
 use graphql_types::types::*;
 use repository::{
-    DateFilter, EqualFilter, PaginationOption, StockLineFilter, StockLineSort, StockLineSortField,
+    DateFilter, EqualFilter, ItemLedgerFilter, PaginationOption, StockLineFilter, StockLineSort,
+    StockLineSortField, StockMovementFilter,
+};
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    historical_stock::{HistoricalStockGroupBy, HistoricalStockInput, StockValuationMethod},
+    traceability::trace::{BatchTraceError, BatchTraceStart},
 };
-use service::auth::{Resource, ResourceAccessRequest};
 
 #[derive(Default, Clone)]
 pub struct StockLineQueries;
@@ -45,6 +51,8 @@ pub struct StockLineFilterInput {
     pub location_id: Option<EqualFilterStringInput>,
     pub store_id: Option<EqualFilterStringInput>,
     pub has_packs_in_store: Option<bool>,
+    pub is_on_hold: Option<bool>,
+    pub hold_reason_id: Option<EqualFilterStringInput>,
 }
 
 impl From<StockLineFilterInput> for StockLineFilter {
@@ -58,6 +66,8 @@ impl From<StockLineFilterInput> for StockLineFilter {
             location_id: f.location_id.map(EqualFilter::from),
             store_id: None,
             has_packs_in_store: f.has_packs_in_store,
+            is_on_hold: f.is_on_hold,
+            hold_reason_id: f.hold_reason_id.map(EqualFilter::from),
         }
     }
 }
@@ -83,6 +93,32 @@ impl StockLineSortInput {
     }
 }
 
+#[derive(InputObject)]
+pub struct BatchTraceInput {
+    /// Traces the stock line, takes precedence over `itemId` and `batch`
+    pub stock_line_id: Option<String>,
+    /// Traces all stock lines of the item and `batch` in the store
+    pub item_id: Option<String>,
+    pub batch: Option<String>,
+}
+
+impl BatchTraceInput {
+    pub fn to_domain(self) -> Option<BatchTraceStart> {
+        match self {
+            BatchTraceInput {
+                stock_line_id: Some(stock_line_id),
+                ..
+            } => Some(BatchTraceStart::StockLine(stock_line_id)),
+            BatchTraceInput {
+                item_id: Some(item_id),
+                batch: Some(batch),
+                ..
+            } => Some(BatchTraceStart::Batch { item_id, batch }),
+            _ => None,
+        }
+    }
+}
+
 #[Object]
 impl StockLineQueries {
     /// Query for "stock_line" entries
@@ -129,6 +165,226 @@ impl StockLineQueries {
             StockLineConnector::from_domain(stock_lines),
         ))
     }
+
+    /// Query the stock movements of a store, ordered by datetime
+    pub async fn stock_movements(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        #[graphql(desc = "Pagination option (first and offset)")] page: Option<PaginationInput>,
+        #[graphql(desc = "Filter option")] filter: Option<StockMovementFilterInput>,
+    ) -> Result<StockMovementsResponse> {
+        let user = validate_auth(
+            ctx,
+            &ResourceAccessRequest {
+                resource: Resource::QueryStockLine,
+                store_id: Some(store_id.clone()),
+            },
+        )?;
+
+        let service_provider = ctx.service_provider();
+        let service_context = service_provider.context(store_id.clone(), user.user_id)?;
+
+        let stock_movements = service_provider
+            .stock_line_service
+            .get_stock_movements(
+                &service_context,
+                &store_id,
+                page.map(PaginationOption::from),
+                filter.map(StockMovementFilter::from),
+            )
+            .map_err(StandardGraphqlError::from_list_error)?;
+
+        Ok(StockMovementsResponse::Response(
+            StockMovementConnector::from_domain(stock_movements),
+        ))
+    }
+
+    /// Stock card of an item or stock line: every stock movement with its source document and
+    /// the running balance, ordered by datetime
+    pub async fn item_ledger(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        #[graphql(desc = "Pagination option (first and offset)")] page: Option<PaginationInput>,
+        #[graphql(desc = "Filter option")] filter: Option<ItemLedgerFilterInput>,
+    ) -> Result<ItemLedgerResponse> {
+        let user = validate_auth(
+            ctx,
+            &ResourceAccessRequest {
+                resource: Resource::QueryStockLine,
+                store_id: Some(store_id.clone()),
+            },
+        )?;
+
+        let service_provider = ctx.service_provider();
+        let service_context = service_provider.context(store_id, user.user_id)?;
+
+        let ledger = service_provider
+            .item_ledger_service
+            .get_item_ledger(
+                &service_context,
+                page.map(PaginationOption::from),
+                filter.map(ItemLedgerFilter::from),
+            )
+            .map_err(StandardGraphqlError::from_list_error)?;
+
+        Ok(ItemLedgerResponse::Response(
+            ItemLedgerConnector::from_domain(ledger),
+        ))
+    }
+
+    /// Stock on hand at a past datetime, reconstructed from stock movements (including stocktake
+    /// adjustments) and valued using the cost price of the receipts
+    pub async fn historical_stock(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        datetime: DateTime<Utc>,
+        #[graphql(desc = "Defaults to batch")] group_by: Option<HistoricalStockGroupByInput>,
+        #[graphql(desc = "Defaults to weighted average")] valuation_method: Option<
+            StockValuationMethodInput,
+        >,
+        #[graphql(desc = "Filter option")] filter: Option<HistoricalStockFilterInput>,
+    ) -> Result<HistoricalStockResponse> {
+        let user = validate_auth(
+            ctx,
+            &ResourceAccessRequest {
+                resource: Resource::QueryStockLine,
+                store_id: Some(store_id.clone()),
+            },
+        )?;
+
+        let service_provider = ctx.service_provider();
+        let service_context = service_provider.context(store_id, user.user_id)?;
+
+        let stock = service_provider
+            .historical_stock_service
+            .get_historical_stock(
+                &service_context,
+                HistoricalStockInput {
+                    datetime: datetime.naive_utc(),
+                    group_by: group_by
+                        .map(HistoricalStockGroupByInput::to_domain)
+                        .unwrap_or(HistoricalStockGroupBy::Batch),
+                    valuation_method: valuation_method
+                        .map(StockValuationMethodInput::to_domain)
+                        .unwrap_or(StockValuationMethod::WeightedAverage),
+                    item_id: filter.and_then(|f| f.item_id).map(EqualFilter::from),
+                },
+            )
+            .map_err(StandardGraphqlError::from_repository_error)?;
+
+        Ok(HistoricalStockResponse::Response(
+            HistoricalStockConnector::from_domain(stock),
+        ))
+    }
+
+    /// Distribution tree of a batch for recalls: the stock lines it was received from, and the
+    /// shipments to customers and other stores with the downstream stock lines still on hand
+    pub async fn batch_trace(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: BatchTraceInput,
+    ) -> Result<BatchTraceResponse> {
+        let user = validate_auth(
+            ctx,
+            &ResourceAccessRequest {
+                resource: Resource::QueryStockLine,
+                store_id: Some(store_id.clone()),
+            },
+        )?;
+
+        let service_provider = ctx.service_provider();
+        let service_context = service_provider.context(store_id, user.user_id)?;
+
+        let start = input.to_domain().ok_or(
+            StandardGraphqlError::BadUserInput(
+                "Either stockLineId or itemId and batch are required".to_string(),
+            )
+            .extend(),
+        )?;
+
+        let trace = service_provider
+            .traceability_service
+            .get_batch_trace(&service_context, start)
+            .map_err(|error| {
+                let formatted_error = format!("{:#?}", error);
+                match error {
+                    BatchTraceError::StockLineDoesNotExist
+                    | BatchTraceError::StockLineDoesNotBelongToStore => {
+                        StandardGraphqlError::BadUserInput(formatted_error)
+                    }
+                    BatchTraceError::DatabaseError(_) => {
+                        StandardGraphqlError::InternalError(formatted_error)
+                    }
+                }
+                .extend()
+            })?;
+
+        Ok(BatchTraceResponse::Response(BatchTraceNode::from_domain(
+            trace,
+        )))
+    }
+
+    /// Stock lines with packs in store expiring on or before a date, ordered by expiry date
+    pub async fn expiring_stock(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        #[graphql(desc = "Defaults to today")] expiry_date: Option<NaiveDate>,
+    ) -> Result<StockLinesResponse> {
+        let user = validate_auth(
+            ctx,
+            &ResourceAccessRequest {
+                resource: Resource::QueryStockLine,
+                store_id: Some(store_id.clone()),
+            },
+        )?;
+
+        let service_provider = ctx.service_provider();
+        let service_context = service_provider.context(store_id, user.user_id)?;
+
+        let stock_lines = service_provider
+            .expired_stock_service
+            .get_expiring_stock(
+                &service_context,
+                expiry_date.unwrap_or_else(|| Utc::now().naive_utc().date()),
+            )
+            .map_err(StandardGraphqlError::from_repository_error)?;
+
+        Ok(StockLinesResponse::Response(StockLineConnector::from_vec(
+            stock_lines,
+        )))
+    }
+
+    /// Reasons stock lines, locations and invoices can be put on hold for
+    pub async fn hold_reasons(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        #[graphql(desc = "Only return reasons that can be used for new holds, defaults to true")]
+        active_only: Option<bool>,
+    ) -> Result<HoldReasonConnector> {
+        let user = validate_auth(
+            ctx,
+            &ResourceAccessRequest {
+                resource: Resource::QueryStockLine,
+                store_id: Some(store_id.clone()),
+            },
+        )?;
+
+        let service_provider = ctx.service_provider();
+        let service_context = service_provider.context(store_id, user.user_id)?;
+
+        let hold_reasons = service_provider
+            .hold_service
+            .get_hold_reasons(&service_context, active_only.unwrap_or(true))
+            .map_err(StandardGraphqlError::from_repository_error)?;
+
+        Ok(HoldReasonConnector::from_vec(hold_reasons))
+    }
 }
 
 #[derive(Default, Clone)]
@@ -144,4 +400,101 @@ impl StockLineMutations {
     ) -> Result<mutations::UpdateResponse> {
         mutations::update(ctx, &store_id, input)
     }
+
+    async fn split_stock_line(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: mutations::SplitInput,
+    ) -> Result<mutations::SplitResponse> {
+        mutations::split(ctx, &store_id, input)
+    }
+
+    async fn merge_stock_lines(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: mutations::MergeInput,
+    ) -> Result<mutations::MergeResponse> {
+        mutations::merge(ctx, &store_id, input)
+    }
+
+    /// Converts packs of a stock line into a new or existing stock line of the same batch with a
+    /// different pack size
+    async fn repack_stock_line(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: mutations::RepackInput,
+    ) -> Result<mutations::RepackResponse> {
+        mutations::repack(ctx, &store_id, input)
+    }
+
+    async fn insert_inventory_adjustment(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: mutations::InsertInventoryAdjustmentInput,
+    ) -> Result<mutations::InsertInventoryAdjustmentResponse> {
+        mutations::insert_inventory_adjustment(ctx, &store_id, input)
+    }
+
+    /// Puts all remaining stock of a recalled batch in the store on hold
+    async fn recall_batch(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: mutations::RecallBatchInput,
+    ) -> Result<mutations::RecallBatchResponse> {
+        mutations::recall_batch(ctx, &store_id, input)
+    }
+
+    /// Puts a stock line, location or invoice on hold with a reason, or changes the reason of an
+    /// existing hold
+    async fn place_hold(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: mutations::PlaceHoldInput,
+    ) -> Result<mutations::PlaceHoldResponse> {
+        mutations::place_hold(ctx, &store_id, input)
+    }
+
+    async fn release_hold(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: mutations::ReleaseHoldInput,
+    ) -> Result<mutations::ReleaseHoldResponse> {
+        mutations::release_hold(ctx, &store_id, input)
+    }
+
+    async fn upsert_hold_reason(
+        &self,
+        ctx: &Context<'_>,
+        input: mutations::UpsertHoldReasonInput,
+    ) -> Result<mutations::UpsertHoldReasonResponse> {
+        mutations::upsert_hold_reason(ctx, input)
+    }
+
+    /// Moves expired stock lines to a quarantine location and/or puts them on hold
+    async fn quarantine_expired_stock(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: mutations::QuarantineExpiredStockInput,
+    ) -> Result<mutations::QuarantineExpiredStockResponse> {
+        mutations::quarantine_expired_stock(ctx, &store_id, input)
+    }
+
+    /// Writes off all packs of expired stock lines in a single inventory reduction, which the
+    /// destruction certificate report is printed from
+    async fn write_off_expired_stock(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: mutations::WriteOffExpiredStockInput,
+    ) -> Result<mutations::WriteOffExpiredStockResponse> {
+        mutations::write_off_expired_stock(ctx, &store_id, input)
+    }
 }
@@ -0,0 +1,298 @@
+use async_graphql::*;
+use graphql_core::{
+    simple_generic_errors::RecordNotFound,
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use graphql_types::{
+    generic_errors::{
+        AdjustmentReasonNotValid, AdjustmentReasonRequired, StockLineReducedBelowZero,
+    },
+    types::InvoiceNode,
+};
+use repository::Invoice;
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    inventory_adjustment::insert::{
+        AdjustmentType, InsertInventoryAdjustment as ServiceInput,
+        InsertInventoryAdjustmentError as ServiceError,
+    },
+};
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq, Debug)]
+pub enum AdjustmentTypeInput {
+    Addition,
+    Reduction,
+}
+
+#[derive(InputObject)]
+#[graphql(name = "InsertInventoryAdjustmentInput")]
+pub struct InsertInventoryAdjustmentInput {
+    pub stock_line_id: String,
+    pub adjustment_type: AdjustmentTypeInput,
+    /// Number of packs added to or removed from the stock line
+    pub adjustment: f64,
+    /// Required when active reasons exist for the adjustment direction
+    pub inventory_adjustment_reason_id: Option<String>,
+    pub comment: Option<String>,
+}
+
+#[derive(Interface)]
+#[graphql(name = "InsertInventoryAdjustmentErrorInterface")]
+#[graphql(field(name = "description", type = "String"))]
+pub enum InsertInventoryAdjustmentErrorInterface {
+    RecordNotFound(RecordNotFound),
+    StockLineReducedBelowZero(StockLineReducedBelowZero),
+    AdjustmentReasonRequired(AdjustmentReasonRequired),
+    AdjustmentReasonNotValid(AdjustmentReasonNotValid),
+}
+
+#[derive(SimpleObject)]
+#[graphql(name = "InsertInventoryAdjustmentError")]
+pub struct InsertInventoryAdjustmentError {
+    pub error: InsertInventoryAdjustmentErrorInterface,
+}
+
+#[derive(Union)]
+#[graphql(name = "InsertInventoryAdjustmentResponse")]
+pub enum InsertInventoryAdjustmentResponse {
+    Error(InsertInventoryAdjustmentError),
+    Response(InvoiceNode),
+}
+
+pub fn insert_inventory_adjustment(
+    ctx: &Context<'_>,
+    store_id: &str,
+    input: InsertInventoryAdjustmentInput,
+) -> Result<InsertInventoryAdjustmentResponse> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateStockLine,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+
+    map_response(
+        service_provider
+            .inventory_adjustment_service
+            .insert_inventory_adjustment(&service_context, input.to_domain()),
+    )
+}
+
+pub fn map_response(
+    from: Result<Invoice, ServiceError>,
+) -> Result<InsertInventoryAdjustmentResponse> {
+    let result = match from {
+        Ok(invoice) => {
+            InsertInventoryAdjustmentResponse::Response(InvoiceNode::from_domain(invoice))
+        }
+        Err(error) => InsertInventoryAdjustmentResponse::Error(InsertInventoryAdjustmentError {
+            error: map_error(error)?,
+        }),
+    };
+
+    Ok(result)
+}
+
+impl InsertInventoryAdjustmentInput {
+    pub fn to_domain(self) -> ServiceInput {
+        let InsertInventoryAdjustmentInput {
+            stock_line_id,
+            adjustment_type,
+            adjustment,
+            inventory_adjustment_reason_id,
+            comment,
+        } = self;
+
+        ServiceInput {
+            stock_line_id,
+            adjustment_type: match adjustment_type {
+                AdjustmentTypeInput::Addition => AdjustmentType::Addition,
+                AdjustmentTypeInput::Reduction => AdjustmentType::Reduction,
+            },
+            adjustment,
+            inventory_adjustment_reason_id,
+            comment,
+        }
+    }
+}
+
+fn map_error(error: ServiceError) -> Result<InsertInventoryAdjustmentErrorInterface> {
+    use InsertInventoryAdjustmentErrorInterface as OutError;
+    use StandardGraphqlError::*;
+    let formatted_error = format!("{:#?}", error);
+
+    let graphql_error = match error {
+        // Structured Errors
+        ServiceError::StockLineDoesNotExist => {
+            return Ok(OutError::RecordNotFound(RecordNotFound {}))
+        }
+        ServiceError::StockLineReducedBelowZero(stock_line) => {
+            return Ok(OutError::StockLineReducedBelowZero(
+                StockLineReducedBelowZero::from_domain(*stock_line),
+            ))
+        }
+        ServiceError::AdjustmentReasonNotProvided => {
+            return Ok(OutError::AdjustmentReasonRequired(AdjustmentReasonRequired))
+        }
+        ServiceError::AdjustmentReasonNotValid => {
+            return Ok(OutError::AdjustmentReasonNotValid(AdjustmentReasonNotValid))
+        }
+        // Standard Graphql Errors
+        ServiceError::StockLineDoesNotBelongToCurrentStore => BadUserInput(formatted_error),
+        ServiceError::InvalidAdjustment => BadUserInput(formatted_error),
+        ServiceError::NewlyCreatedInvoiceDoesNotExist => InternalError(formatted_error),
+        ServiceError::InternalError(_) => InternalError(formatted_error),
+        ServiceError::DatabaseError(_) => InternalError(formatted_error),
+    };
+
+    Err(graphql_error.extend())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::StockLineMutations;
+    use async_graphql::EmptyMutation;
+    use graphql_core::{
+        assert_graphql_query, assert_standard_graphql_error, test_helpers::setup_graphl_test,
+    };
+    use repository::{
+        mock::{mock_inbound_shipment_a, mock_stock_line_a, MockDataInserts},
+        Invoice, StorageConnectionManager,
+    };
+    use serde_json::json;
+
+    use service::{
+        inventory_adjustment::{
+            insert::{
+                AdjustmentType, InsertInventoryAdjustment as ServiceInput,
+                InsertInventoryAdjustmentError as ServiceError,
+            },
+            InventoryAdjustmentServiceTrait,
+        },
+        service_provider::{ServiceContext, ServiceProvider},
+    };
+
+    type InsertMethod = dyn Fn(ServiceInput) -> Result<Invoice, ServiceError> + Sync + Send;
+
+    pub struct TestService(pub Box<InsertMethod>);
+
+    impl InventoryAdjustmentServiceTrait for TestService {
+        fn insert_inventory_adjustment(
+            &self,
+            _: &ServiceContext,
+            input: ServiceInput,
+        ) -> Result<Invoice, ServiceError> {
+            self.0(input)
+        }
+    }
+
+    fn service_provider(
+        test_service: TestService,
+        connection_manager: &StorageConnectionManager,
+    ) -> ServiceProvider {
+        let mut service_provider = ServiceProvider::new(connection_manager.clone(), "app_data");
+        service_provider.inventory_adjustment_service = Box::new(test_service);
+        service_provider
+    }
+
+    #[actix_rt::test]
+    async fn test_graphql_insert_inventory_adjustment() {
+        let (_, _, connection_manager, settings) = setup_graphl_test(
+            EmptyMutation,
+            StockLineMutations,
+            "test_graphql_insert_inventory_adjustment",
+            MockDataInserts::all(),
+        )
+        .await;
+
+        let mutation = r#"
+        mutation ($input: InsertInventoryAdjustmentInput!, $storeId: String) {
+            insertInventoryAdjustment(storeId: $storeId, input: $input) {
+              ... on InsertInventoryAdjustmentError {
+                error {
+                  __typename
+                }
+              }
+              ... on InvoiceNode {
+                id
+              }
+            }
+          }
+        "#;
+        let variables = json!({
+          "input": {
+            "stockLineId": mock_stock_line_a().id,
+            "adjustmentType": "REDUCTION",
+            "adjustment": 2.0,
+            "inventoryAdjustmentReasonId": "reason"
+          },
+          "storeId": "store_a"
+        });
+
+        // AdjustmentReasonNotValid
+        let test_service = TestService(Box::new(|_| Err(ServiceError::AdjustmentReasonNotValid)));
+        let expected = json!({
+            "insertInventoryAdjustment": {
+              "error": {
+                "__typename": "AdjustmentReasonNotValid"
+              }
+            }
+          }
+        );
+        assert_graphql_query!(
+            &settings,
+            mutation,
+            &Some(variables.clone()),
+            &expected,
+            Some(service_provider(test_service, &connection_manager))
+        );
+
+        // InvalidAdjustment
+        let test_service = TestService(Box::new(|_| Err(ServiceError::InvalidAdjustment)));
+        let expected_message = "Bad user input";
+        assert_standard_graphql_error!(
+            &settings,
+            &mutation,
+            &Some(variables.clone()),
+            &expected_message,
+            None,
+            Some(service_provider(test_service, &connection_manager))
+        );
+
+        // Success
+        let test_service = TestService(Box::new(|input| {
+            assert_eq!(
+                input,
+                ServiceInput {
+                    stock_line_id: mock_stock_line_a().id,
+                    adjustment_type: AdjustmentType::Reduction,
+                    adjustment: 2.0,
+                    inventory_adjustment_reason_id: Some("reason".to_string()),
+                    comment: None,
+                }
+            );
+            Ok(Invoice {
+                invoice_row: mock_inbound_shipment_a(),
+                ..Default::default()
+            })
+        }));
+        let expected = json!({
+            "insertInventoryAdjustment": {
+                "id": mock_inbound_shipment_a().id,
+            }
+          }
+        );
+        assert_graphql_query!(
+            &settings,
+            mutation,
+            &Some(variables),
+            &expected,
+            Some(service_provider(test_service, &connection_manager))
+        );
+    }
+}
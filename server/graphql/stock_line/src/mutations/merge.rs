@@ -0,0 +1,123 @@
+use async_graphql::*;
+use graphql_core::{
+    simple_generic_errors::RecordNotFound,
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use graphql_types::types::StockLineNode;
+use repository::StockLine;
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    stock_line::{MergeStockLines as ServiceInput, MergeStockLinesError as ServiceError},
+};
+
+use super::StockLineIsAllocated;
+
+#[derive(InputObject)]
+#[graphql(name = "MergeStockLinesInput")]
+pub struct MergeInput {
+    /// Stock line receiving the packs, it keeps its location, prices and hold status
+    pub id: String,
+    /// Stock lines merged into the stock line `id`
+    pub stock_line_ids: Vec<String>,
+}
+
+pub struct StockLinesDoNotMatch;
+#[Object]
+impl StockLinesDoNotMatch {
+    pub async fn description(&self) -> &'static str {
+        "Stock lines must have the same item, batch, expiry date and pack size"
+    }
+}
+
+#[derive(Interface)]
+#[graphql(name = "MergeStockLinesErrorInterface")]
+#[graphql(field(name = "description", type = "String"))]
+pub enum MergeErrorInterface {
+    RecordNotFound(RecordNotFound),
+    StockLineIsAllocated(StockLineIsAllocated),
+    StockLinesDoNotMatch(StockLinesDoNotMatch),
+}
+
+#[derive(SimpleObject)]
+#[graphql(name = "MergeStockLinesError")]
+pub struct MergeError {
+    pub error: MergeErrorInterface,
+}
+
+#[derive(Union)]
+#[graphql(name = "MergeStockLinesResponse")]
+pub enum MergeResponse {
+    Error(MergeError),
+    Response(StockLineNode),
+}
+
+pub fn merge(ctx: &Context<'_>, store_id: &str, input: MergeInput) -> Result<MergeResponse> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateStockLine,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+
+    map_response(
+        service_provider
+            .stock_line_service
+            .merge_stock_lines(&service_context, input.to_domain()),
+    )
+}
+
+pub fn map_response(from: Result<StockLine, ServiceError>) -> Result<MergeResponse> {
+    let result = match from {
+        Ok(stock_line) => MergeResponse::Response(StockLineNode::from_domain(stock_line)),
+        Err(error) => MergeResponse::Error(MergeError {
+            error: map_error(error)?,
+        }),
+    };
+
+    Ok(result)
+}
+
+impl MergeInput {
+    pub fn to_domain(self) -> ServiceInput {
+        let MergeInput { id, stock_line_ids } = self;
+
+        ServiceInput { id, stock_line_ids }
+    }
+}
+
+fn map_error(error: ServiceError) -> Result<MergeErrorInterface> {
+    use StandardGraphqlError::*;
+    let formatted_error = format!("{:#?}", error);
+
+    let graphql_error = match error {
+        // Structured Errors
+        ServiceError::StockDoesNotExist => {
+            return Ok(MergeErrorInterface::RecordNotFound(RecordNotFound {}))
+        }
+        ServiceError::StockLineIsAllocated => {
+            return Ok(MergeErrorInterface::StockLineIsAllocated(
+                StockLineIsAllocated {},
+            ))
+        }
+        ServiceError::StockLinesDoNotMatch => {
+            return Ok(MergeErrorInterface::StockLinesDoNotMatch(
+                StockLinesDoNotMatch {},
+            ))
+        }
+        // Standard Graphql Errors
+        ServiceError::StockDoesNotBelongToStore => BadUserInput(formatted_error),
+        ServiceError::InvalidStockLineIds => BadUserInput(formatted_error),
+        ServiceError::CannotReleaseHold => BadUserInput(formatted_error),
+        ServiceError::StockLineIsPendingInspection => BadUserInput(formatted_error),
+        ServiceError::MergedStockLineNotFound => InternalError(formatted_error),
+        ServiceError::InternalError(_) => InternalError(formatted_error),
+        ServiceError::DatabaseError(_) => InternalError(formatted_error),
+    };
+
+    Err(graphql_error.extend())
+}
@@ -1,2 +1,22 @@
+pub mod inventory_adjustment;
+pub use inventory_adjustment::*;
+pub mod merge;
+pub use merge::*;
+pub mod place_hold;
+pub use place_hold::*;
+pub mod quarantine_expired_stock;
+pub use quarantine_expired_stock::*;
+pub mod recall_batch;
+pub use recall_batch::*;
+pub mod release_hold;
+pub use release_hold::*;
+pub mod repack;
+pub use repack::*;
+pub mod split;
+pub use split::*;
 pub mod update;
 pub use update::*;
+pub mod upsert_hold_reason;
+pub use upsert_hold_reason::*;
+pub mod write_off_expired_stock;
+pub use write_off_expired_stock::*;
@@ -0,0 +1,239 @@
+use async_graphql::*;
+use chrono::NaiveDate;
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use graphql_types::types::{HoldNode, HoldRecordTypeNode};
+use repository::Hold;
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    hold::place::{PlaceHold as ServiceInput, PlaceHoldError as ServiceError},
+};
+
+#[derive(InputObject)]
+#[graphql(name = "PlaceHoldInput")]
+pub struct PlaceHoldInput {
+    pub record_type: HoldRecordTypeNode,
+    pub record_id: String,
+    pub hold_reason_id: String,
+    /// Date the hold is expected to be released
+    pub release_date: Option<NaiveDate>,
+}
+
+#[derive(Union)]
+#[graphql(name = "PlaceHoldResponse")]
+pub enum PlaceHoldResponse {
+    Response(HoldNode),
+}
+
+pub fn place_hold(
+    ctx: &Context<'_>,
+    store_id: &str,
+    input: PlaceHoldInput,
+) -> Result<PlaceHoldResponse> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: hold_resource(input.record_type),
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+
+    map_response(
+        service_provider
+            .hold_service
+            .place_hold(&service_context, input.to_domain()),
+    )
+}
+
+/// Holds are placed with the permission to edit the held record
+pub fn hold_resource(record_type: HoldRecordTypeNode) -> Resource {
+    match record_type {
+        HoldRecordTypeNode::StockLine => Resource::MutateStockLine,
+        HoldRecordTypeNode::Location => Resource::MutateLocation,
+        HoldRecordTypeNode::Invoice => Resource::MutateInvoiceHold,
+    }
+}
+
+pub fn map_response(from: Result<Hold, ServiceError>) -> Result<PlaceHoldResponse> {
+    match from {
+        Ok(hold) => Ok(PlaceHoldResponse::Response(HoldNode::from_domain(hold))),
+        Err(error) => Err(map_error(error)),
+    }
+}
+
+impl PlaceHoldInput {
+    pub fn to_domain(self) -> ServiceInput {
+        let PlaceHoldInput {
+            record_type,
+            record_id,
+            hold_reason_id,
+            release_date,
+        } = self;
+
+        ServiceInput {
+            record_type: record_type.to_domain(),
+            record_id,
+            hold_reason_id,
+            release_date,
+        }
+    }
+}
+
+fn map_error(error: ServiceError) -> async_graphql::Error {
+    use StandardGraphqlError::*;
+    let formatted_error = format!("{:#?}", error);
+
+    let graphql_error = match error {
+        ServiceError::RecordDoesNotExist
+        | ServiceError::RecordDoesNotBelongToStore
+        | ServiceError::HoldReasonDoesNotExist
+        | ServiceError::HoldReasonIsNotActive
+        | ServiceError::ReleaseDateInThePast => BadUserInput(formatted_error),
+        ServiceError::CreatedHoldDoesNotExist | ServiceError::DatabaseError(_) => {
+            InternalError(formatted_error)
+        }
+    };
+
+    graphql_error.extend()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::StockLineMutations;
+    use async_graphql::EmptyMutation;
+    use graphql_core::{
+        assert_graphql_query, assert_standard_graphql_error, test_helpers::setup_graphl_test,
+    };
+    use repository::{
+        mock::{mock_stock_line_a, MockDataInserts},
+        Hold, HoldReasonRow, HoldRecordType, HoldRow, StorageConnectionManager,
+        HOLD_REASON_DAMAGED_ID,
+    };
+    use serde_json::json;
+
+    use service::{
+        hold::{
+            place::{PlaceHold as ServiceInput, PlaceHoldError as ServiceError},
+            HoldServiceTrait,
+        },
+        service_provider::{ServiceContext, ServiceProvider},
+    };
+
+    type PlaceHoldMethod = dyn Fn(ServiceInput) -> Result<Hold, ServiceError> + Sync + Send;
+
+    pub struct TestService(pub Box<PlaceHoldMethod>);
+
+    impl HoldServiceTrait for TestService {
+        fn place_hold(
+            &self,
+            _: &ServiceContext,
+            input: ServiceInput,
+        ) -> Result<Hold, ServiceError> {
+            self.0(input)
+        }
+    }
+
+    fn service_provider(
+        test_service: TestService,
+        connection_manager: &StorageConnectionManager,
+    ) -> ServiceProvider {
+        let mut service_provider = ServiceProvider::new(connection_manager.clone(), "app_data");
+        service_provider.hold_service = Box::new(test_service);
+        service_provider
+    }
+
+    #[actix_rt::test]
+    async fn test_graphql_place_hold() {
+        let (_, _, connection_manager, settings) = setup_graphl_test(
+            EmptyMutation,
+            StockLineMutations,
+            "test_graphql_place_hold",
+            MockDataInserts::all(),
+        )
+        .await;
+
+        let mutation = r#"
+        mutation ($input: PlaceHoldInput!, $storeId: String) {
+            placeHold(storeId: $storeId, input: $input) {
+              ... on HoldNode {
+                recordType
+                recordId
+                releaseDate
+                reason {
+                  reason
+                }
+              }
+            }
+          }
+        "#;
+        let variables = json!({
+          "input": {
+            "recordType": "STOCK_LINE",
+            "recordId": mock_stock_line_a().id,
+            "holdReasonId": HOLD_REASON_DAMAGED_ID,
+            "releaseDate": "2030-01-01"
+          },
+          "storeId": "store_a"
+        });
+
+        // HoldReasonIsNotActive
+        let test_service = TestService(Box::new(|_| Err(ServiceError::HoldReasonIsNotActive)));
+        let expected_message = "Bad user input";
+        assert_standard_graphql_error!(
+            &settings,
+            &mutation,
+            &Some(variables.clone()),
+            &expected_message,
+            None,
+            Some(service_provider(test_service, &connection_manager))
+        );
+
+        // Success
+        let test_service = TestService(Box::new(|input| {
+            assert_eq!(
+                input,
+                ServiceInput {
+                    record_type: HoldRecordType::StockLine,
+                    record_id: mock_stock_line_a().id,
+                    hold_reason_id: HOLD_REASON_DAMAGED_ID.to_string(),
+                    release_date: Some(chrono::NaiveDate::from_ymd(2030, 1, 1)),
+                }
+            );
+            Ok(Hold {
+                hold_row: HoldRow {
+                    record_type: HoldRecordType::StockLine,
+                    record_id: input.record_id,
+                    hold_reason_id: input.hold_reason_id,
+                    release_date: input.release_date,
+                    ..Default::default()
+                },
+                hold_reason_row: HoldReasonRow {
+                    id: HOLD_REASON_DAMAGED_ID.to_string(),
+                    reason: "Damaged".to_string(),
+                    is_active: true,
+                },
+            })
+        }));
+        let expected = json!({
+            "placeHold": {
+                "recordType": "STOCK_LINE",
+                "recordId": mock_stock_line_a().id,
+                "releaseDate": "2030-01-01",
+                "reason": { "reason": "Damaged" }
+            }
+          }
+        );
+        assert_graphql_query!(
+            &settings,
+            mutation,
+            &Some(variables),
+            &expected,
+            Some(service_provider(test_service, &connection_manager))
+        );
+    }
+}
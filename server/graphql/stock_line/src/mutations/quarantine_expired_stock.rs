@@ -0,0 +1,278 @@
+use async_graphql::*;
+use graphql_core::{
+    simple_generic_errors::RecordNotFound,
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use graphql_types::types::StockLineConnector;
+use repository::StockLine;
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    expired_stock::quarantine::{
+        QuarantineExpiredStock as ServiceInput, QuarantineExpiredStockError as ServiceError,
+    },
+};
+
+#[derive(InputObject)]
+#[graphql(name = "QuarantineExpiredStockInput")]
+pub struct QuarantineExpiredStockInput {
+    pub stock_line_ids: Vec<String>,
+    /// Quarantine location the stock lines are moved to
+    pub location_id: Option<String>,
+    /// Puts the stock lines on hold, at least one of `locationId` or `onHold` must be set
+    pub on_hold: Option<bool>,
+}
+
+pub struct LocationIsNotQuarantine;
+#[Object]
+impl LocationIsNotQuarantine {
+    pub async fn description(&self) -> &'static str {
+        "Location must be a quarantine location"
+    }
+}
+
+#[derive(Interface)]
+#[graphql(name = "QuarantineExpiredStockErrorInterface")]
+#[graphql(field(name = "description", type = "String"))]
+pub enum QuarantineExpiredStockErrorInterface {
+    RecordNotFound(RecordNotFound),
+    LocationIsNotQuarantine(LocationIsNotQuarantine),
+}
+
+#[derive(SimpleObject)]
+#[graphql(name = "QuarantineExpiredStockError")]
+pub struct QuarantineExpiredStockError {
+    pub error: QuarantineExpiredStockErrorInterface,
+}
+
+#[derive(Union)]
+#[graphql(name = "QuarantineExpiredStockResponse")]
+pub enum QuarantineExpiredStockResponse {
+    Error(QuarantineExpiredStockError),
+    Response(StockLineConnector),
+}
+
+pub fn quarantine_expired_stock(
+    ctx: &Context<'_>,
+    store_id: &str,
+    input: QuarantineExpiredStockInput,
+) -> Result<QuarantineExpiredStockResponse> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateStockLine,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+
+    map_response(
+        service_provider
+            .expired_stock_service
+            .quarantine_expired_stock(&service_context, input.to_domain()),
+    )
+}
+
+pub fn map_response(
+    from: Result<Vec<StockLine>, ServiceError>,
+) -> Result<QuarantineExpiredStockResponse> {
+    let result = match from {
+        Ok(stock_lines) => {
+            QuarantineExpiredStockResponse::Response(StockLineConnector::from_vec(stock_lines))
+        }
+        Err(error) => QuarantineExpiredStockResponse::Error(QuarantineExpiredStockError {
+            error: map_error(error)?,
+        }),
+    };
+
+    Ok(result)
+}
+
+impl QuarantineExpiredStockInput {
+    pub fn to_domain(self) -> ServiceInput {
+        let QuarantineExpiredStockInput {
+            stock_line_ids,
+            location_id,
+            on_hold,
+        } = self;
+
+        ServiceInput {
+            stock_line_ids,
+            location_id,
+            on_hold: on_hold.unwrap_or(false),
+        }
+    }
+}
+
+fn map_error(error: ServiceError) -> Result<QuarantineExpiredStockErrorInterface> {
+    use QuarantineExpiredStockErrorInterface as OutError;
+    use StandardGraphqlError::*;
+    let formatted_error = format!("{:#?}", error);
+
+    let graphql_error = match error {
+        // Structured Errors
+        ServiceError::StockDoesNotExist => return Ok(OutError::RecordNotFound(RecordNotFound {})),
+        ServiceError::LocationIsNotQuarantine => {
+            return Ok(OutError::LocationIsNotQuarantine(LocationIsNotQuarantine))
+        }
+        // Standard Graphql Errors
+        ServiceError::NoStockLines => BadUserInput(formatted_error),
+        ServiceError::NothingToUpdate => BadUserInput(formatted_error),
+        ServiceError::StockDoesNotBelongToStore => BadUserInput(formatted_error),
+        ServiceError::LocationDoesNotExist => BadUserInput(formatted_error),
+        ServiceError::StockLineIsPendingInspection => BadUserInput(formatted_error),
+        ServiceError::UpdatedStockNotFound => InternalError(formatted_error),
+        ServiceError::StockMovementNotFound => InternalError(formatted_error),
+        ServiceError::DatabaseError(_) => InternalError(formatted_error),
+    };
+
+    Err(graphql_error.extend())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::StockLineMutations;
+    use async_graphql::EmptyMutation;
+    use graphql_core::{
+        assert_graphql_query, assert_standard_graphql_error, test_helpers::setup_graphl_test,
+    };
+    use repository::{
+        mock::{mock_item_a, mock_stock_line_a, MockDataInserts},
+        StockLine, StorageConnectionManager,
+    };
+    use serde_json::json;
+
+    use service::{
+        expired_stock::{
+            quarantine::{
+                QuarantineExpiredStock as ServiceInput, QuarantineExpiredStockError as ServiceError,
+            },
+            ExpiredStockServiceTrait,
+        },
+        service_provider::{ServiceContext, ServiceProvider},
+    };
+
+    type QuarantineMethod =
+        dyn Fn(ServiceInput) -> Result<Vec<StockLine>, ServiceError> + Sync + Send;
+
+    pub struct TestService(pub Box<QuarantineMethod>);
+
+    impl ExpiredStockServiceTrait for TestService {
+        fn quarantine_expired_stock(
+            &self,
+            _: &ServiceContext,
+            input: ServiceInput,
+        ) -> Result<Vec<StockLine>, ServiceError> {
+            self.0(input)
+        }
+    }
+
+    fn service_provider(
+        test_service: TestService,
+        connection_manager: &StorageConnectionManager,
+    ) -> ServiceProvider {
+        let mut service_provider = ServiceProvider::new(connection_manager.clone(), "app_data");
+        service_provider.expired_stock_service = Box::new(test_service);
+        service_provider
+    }
+
+    #[actix_rt::test]
+    async fn test_graphql_quarantine_expired_stock() {
+        let (_, _, connection_manager, settings) = setup_graphl_test(
+            EmptyMutation,
+            StockLineMutations,
+            "test_graphql_quarantine_expired_stock",
+            MockDataInserts::all(),
+        )
+        .await;
+
+        let mutation = r#"
+        mutation ($input: QuarantineExpiredStockInput!, $storeId: String) {
+            quarantineExpiredStock(storeId: $storeId, input: $input) {
+              ... on QuarantineExpiredStockError {
+                error {
+                  __typename
+                }
+              }
+              ... on StockLineConnector {
+                totalCount
+                nodes {
+                  id
+                }
+              }
+            }
+          }
+        "#;
+        let variables = json!({
+          "input": {
+            "stockLineIds": [mock_stock_line_a().id],
+            "locationId": "quarantine_location"
+          },
+          "storeId": "store_a"
+        });
+
+        // LocationIsNotQuarantine
+        let test_service = TestService(Box::new(|_| Err(ServiceError::LocationIsNotQuarantine)));
+        let expected = json!({
+            "quarantineExpiredStock": {
+              "error": {
+                "__typename": "LocationIsNotQuarantine"
+              }
+            }
+          }
+        );
+        assert_graphql_query!(
+            &settings,
+            mutation,
+            &Some(variables.clone()),
+            &expected,
+            Some(service_provider(test_service, &connection_manager))
+        );
+
+        // NothingToUpdate
+        let test_service = TestService(Box::new(|_| Err(ServiceError::NothingToUpdate)));
+        let expected_message = "Bad user input";
+        assert_standard_graphql_error!(
+            &settings,
+            &mutation,
+            &Some(variables.clone()),
+            &expected_message,
+            None,
+            Some(service_provider(test_service, &connection_manager))
+        );
+
+        // Success
+        let test_service = TestService(Box::new(|input| {
+            assert_eq!(
+                input,
+                ServiceInput {
+                    stock_line_ids: vec![mock_stock_line_a().id],
+                    location_id: Some("quarantine_location".to_string()),
+                    on_hold: false,
+                }
+            );
+            Ok(vec![StockLine {
+                stock_line_row: mock_stock_line_a(),
+                item_row: mock_item_a(),
+                location_row: None,
+                name_row: None,
+            }])
+        }));
+        let expected = json!({
+            "quarantineExpiredStock": {
+                "totalCount": 1,
+                "nodes": [{ "id": mock_stock_line_a().id }]
+            }
+          }
+        );
+        assert_graphql_query!(
+            &settings,
+            mutation,
+            &Some(variables),
+            &expected,
+            Some(service_provider(test_service, &connection_manager))
+        );
+    }
+}
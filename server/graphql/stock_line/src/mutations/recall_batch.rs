@@ -0,0 +1,207 @@
+use async_graphql::*;
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use graphql_types::types::StockLineConnector;
+use repository::StockLine;
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    traceability::recall::{RecallBatch as ServiceInput, RecallBatchError as ServiceError},
+};
+
+#[derive(InputObject)]
+#[graphql(name = "RecallBatchInput")]
+pub struct RecallBatchInput {
+    pub item_id: String,
+    pub batch: String,
+    /// Logged on each stock line put on hold
+    pub reason: String,
+}
+
+#[derive(Union)]
+#[graphql(name = "RecallBatchResponse")]
+pub enum RecallBatchResponse {
+    Response(StockLineConnector),
+}
+
+pub fn recall_batch(
+    ctx: &Context<'_>,
+    store_id: &str,
+    input: RecallBatchInput,
+) -> Result<RecallBatchResponse> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateStockLine,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+
+    map_response(
+        service_provider
+            .traceability_service
+            .recall_batch(&service_context, input.to_domain()),
+    )
+}
+
+pub fn map_response(from: Result<Vec<StockLine>, ServiceError>) -> Result<RecallBatchResponse> {
+    match from {
+        Ok(stock_lines) => Ok(RecallBatchResponse::Response(StockLineConnector::from_vec(
+            stock_lines,
+        ))),
+        Err(error) => Err(map_error(error)),
+    }
+}
+
+impl RecallBatchInput {
+    pub fn to_domain(self) -> ServiceInput {
+        let RecallBatchInput {
+            item_id,
+            batch,
+            reason,
+        } = self;
+
+        ServiceInput {
+            item_id,
+            batch,
+            reason,
+        }
+    }
+}
+
+fn map_error(error: ServiceError) -> async_graphql::Error {
+    use StandardGraphqlError::*;
+    let formatted_error = format!("{:#?}", error);
+
+    let graphql_error = match error {
+        ServiceError::ReasonNotProvided => BadUserInput(formatted_error),
+        ServiceError::DatabaseError(_) => InternalError(formatted_error),
+    };
+
+    graphql_error.extend()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::StockLineMutations;
+    use async_graphql::EmptyMutation;
+    use graphql_core::{
+        assert_graphql_query, assert_standard_graphql_error, test_helpers::setup_graphl_test,
+    };
+    use repository::{
+        mock::{mock_item_a, mock_stock_line_a, MockDataInserts},
+        StockLine, StorageConnectionManager,
+    };
+    use serde_json::json;
+
+    use service::{
+        service_provider::{ServiceContext, ServiceProvider},
+        traceability::{
+            recall::{RecallBatch as ServiceInput, RecallBatchError as ServiceError},
+            TraceabilityServiceTrait,
+        },
+    };
+
+    type RecallMethod = dyn Fn(ServiceInput) -> Result<Vec<StockLine>, ServiceError> + Sync + Send;
+
+    pub struct TestService(pub Box<RecallMethod>);
+
+    impl TraceabilityServiceTrait for TestService {
+        fn recall_batch(
+            &self,
+            _: &ServiceContext,
+            input: ServiceInput,
+        ) -> Result<Vec<StockLine>, ServiceError> {
+            self.0(input)
+        }
+    }
+
+    fn service_provider(
+        test_service: TestService,
+        connection_manager: &StorageConnectionManager,
+    ) -> ServiceProvider {
+        let mut service_provider = ServiceProvider::new(connection_manager.clone(), "app_data");
+        service_provider.traceability_service = Box::new(test_service);
+        service_provider
+    }
+
+    #[actix_rt::test]
+    async fn test_graphql_recall_batch() {
+        let (_, _, connection_manager, settings) = setup_graphl_test(
+            EmptyMutation,
+            StockLineMutations,
+            "test_graphql_recall_batch",
+            MockDataInserts::all(),
+        )
+        .await;
+
+        let mutation = r#"
+        mutation ($input: RecallBatchInput!, $storeId: String) {
+            recallBatch(storeId: $storeId, input: $input) {
+              ... on StockLineConnector {
+                totalCount
+                nodes {
+                  id
+                }
+              }
+            }
+          }
+        "#;
+        let variables = json!({
+          "input": {
+            "itemId": mock_item_a().id,
+            "batch": "B1",
+            "reason": "Contaminated"
+          },
+          "storeId": "store_a"
+        });
+
+        // ReasonNotProvided
+        let test_service = TestService(Box::new(|_| Err(ServiceError::ReasonNotProvided)));
+        let expected_message = "Bad user input";
+        assert_standard_graphql_error!(
+            &settings,
+            &mutation,
+            &Some(variables.clone()),
+            &expected_message,
+            None,
+            Some(service_provider(test_service, &connection_manager))
+        );
+
+        // Success
+        let test_service = TestService(Box::new(|input| {
+            assert_eq!(
+                input,
+                ServiceInput {
+                    item_id: mock_item_a().id,
+                    batch: "B1".to_string(),
+                    reason: "Contaminated".to_string(),
+                }
+            );
+            Ok(vec![StockLine {
+                stock_line_row: mock_stock_line_a(),
+                item_row: mock_item_a(),
+                location_row: None,
+                name_row: None,
+            }])
+        }));
+        let expected = json!({
+            "recallBatch": {
+                "totalCount": 1,
+                "nodes": [{ "id": mock_stock_line_a().id }]
+            }
+          }
+        );
+        assert_graphql_query!(
+            &settings,
+            mutation,
+            &Some(variables),
+            &expected,
+            Some(service_provider(test_service, &connection_manager))
+        );
+    }
+}
@@ -0,0 +1,205 @@
+use async_graphql::*;
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use graphql_types::types::{DeleteResponse, HoldRecordTypeNode};
+use service::{
+    auth::ResourceAccessRequest,
+    hold::release::{ReleaseHold as ServiceInput, ReleaseHoldError as ServiceError},
+};
+
+use super::hold_resource;
+
+#[derive(InputObject)]
+#[graphql(name = "ReleaseHoldInput")]
+pub struct ReleaseHoldInput {
+    pub record_type: HoldRecordTypeNode,
+    pub record_id: String,
+}
+
+#[derive(Union)]
+#[graphql(name = "ReleaseHoldResponse")]
+pub enum ReleaseHoldResponse {
+    /// Id of the record taken off hold
+    Response(DeleteResponse),
+}
+
+pub fn release_hold(
+    ctx: &Context<'_>,
+    store_id: &str,
+    input: ReleaseHoldInput,
+) -> Result<ReleaseHoldResponse> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: hold_resource(input.record_type),
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+
+    let record_id = input.record_id.clone();
+    match service_provider
+        .hold_service
+        .release_hold(&service_context, input.to_domain())
+    {
+        Ok(()) => Ok(ReleaseHoldResponse::Response(DeleteResponse(record_id))),
+        Err(error) => Err(map_error(error)),
+    }
+}
+
+impl ReleaseHoldInput {
+    pub fn to_domain(self) -> ServiceInput {
+        let ReleaseHoldInput {
+            record_type,
+            record_id,
+        } = self;
+
+        ServiceInput {
+            record_type: record_type.to_domain(),
+            record_id,
+        }
+    }
+}
+
+fn map_error(error: ServiceError) -> async_graphql::Error {
+    use StandardGraphqlError::*;
+    let formatted_error = format!("{:#?}", error);
+
+    let graphql_error = match error {
+        ServiceError::RecordDoesNotExist
+        | ServiceError::RecordDoesNotBelongToStore
+        | ServiceError::RecordIsNotOnHold
+        | ServiceError::StockLineIsPendingInspection => BadUserInput(formatted_error),
+        ServiceError::DatabaseError(_) => InternalError(formatted_error),
+    };
+
+    graphql_error.extend()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::StockLineMutations;
+    use async_graphql::EmptyMutation;
+    use graphql_core::{
+        assert_graphql_query, assert_standard_graphql_error, test_helpers::setup_graphl_test,
+    };
+    use repository::{
+        mock::{mock_location_1, MockDataInserts},
+        HoldRecordType, StorageConnectionManager,
+    };
+    use serde_json::json;
+
+    use service::{
+        hold::{
+            release::{ReleaseHold as ServiceInput, ReleaseHoldError as ServiceError},
+            HoldServiceTrait,
+        },
+        service_provider::{ServiceContext, ServiceProvider},
+    };
+
+    type ReleaseHoldMethod = dyn Fn(ServiceInput) -> Result<(), ServiceError> + Sync + Send;
+
+    pub struct TestService(pub Box<ReleaseHoldMethod>);
+
+    impl HoldServiceTrait for TestService {
+        fn release_hold(
+            &self,
+            _: &ServiceContext,
+            input: ServiceInput,
+        ) -> Result<(), ServiceError> {
+            self.0(input)
+        }
+    }
+
+    fn service_provider(
+        test_service: TestService,
+        connection_manager: &StorageConnectionManager,
+    ) -> ServiceProvider {
+        let mut service_provider = ServiceProvider::new(connection_manager.clone(), "app_data");
+        service_provider.hold_service = Box::new(test_service);
+        service_provider
+    }
+
+    #[actix_rt::test]
+    async fn test_graphql_release_hold() {
+        let (_, _, connection_manager, settings) = setup_graphl_test(
+            EmptyMutation,
+            StockLineMutations,
+            "test_graphql_release_hold",
+            MockDataInserts::all(),
+        )
+        .await;
+
+        let mutation = r#"
+        mutation ($input: ReleaseHoldInput!, $storeId: String) {
+            releaseHold(storeId: $storeId, input: $input) {
+              ... on DeleteResponse {
+                id
+              }
+            }
+          }
+        "#;
+        let variables = json!({
+          "input": {
+            "recordType": "LOCATION",
+            "recordId": mock_location_1().id
+          },
+          "storeId": "store_a"
+        });
+
+        // RecordIsNotOnHold
+        let test_service = TestService(Box::new(|_| Err(ServiceError::RecordIsNotOnHold)));
+        let expected_message = "Bad user input";
+        assert_standard_graphql_error!(
+            &settings,
+            &mutation,
+            &Some(variables.clone()),
+            &expected_message,
+            None,
+            Some(service_provider(test_service, &connection_manager))
+        );
+
+        // StockLineIsPendingInspection
+        let test_service = TestService(Box::new(|_| {
+            Err(ServiceError::StockLineIsPendingInspection)
+        }));
+        let expected_message = "Bad user input";
+        assert_standard_graphql_error!(
+            &settings,
+            &mutation,
+            &Some(variables.clone()),
+            &expected_message,
+            None,
+            Some(service_provider(test_service, &connection_manager))
+        );
+
+        // Success
+        let test_service = TestService(Box::new(|input| {
+            assert_eq!(
+                input,
+                ServiceInput {
+                    record_type: HoldRecordType::Location,
+                    record_id: mock_location_1().id,
+                }
+            );
+            Ok(())
+        }));
+        let expected = json!({
+            "releaseHold": {
+                "id": mock_location_1().id
+            }
+          }
+        );
+        assert_graphql_query!(
+            &settings,
+            mutation,
+            &Some(variables),
+            &expected,
+            Some(service_provider(test_service, &connection_manager))
+        );
+    }
+}
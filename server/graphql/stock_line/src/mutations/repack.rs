@@ -0,0 +1,283 @@
+use async_graphql::*;
+use graphql_core::{
+    simple_generic_errors::RecordNotFound,
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use graphql_types::types::StockLineNode;
+use repository::StockLine;
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    stock_line::{RepackStockLine as ServiceInput, RepackStockLineError as ServiceError},
+};
+
+use super::StockLineIsAllocated;
+
+#[derive(InputObject)]
+#[graphql(name = "RepackStockLineInput")]
+pub struct RepackInput {
+    pub id: String,
+    /// Number of packs of the stock line that are repacked
+    pub number_of_packs: f64,
+    pub new_pack_size: u32,
+    /// Existing stock line of the same batch and the new pack size receiving the repacked stock,
+    /// a new stock line is created when not set
+    pub new_stock_line_id: Option<String>,
+    /// Location of the new stock line, defaults to the location of the existing stock line
+    pub location_id: Option<String>,
+}
+
+pub struct NewStockLineDoesNotMatch;
+#[Object]
+impl NewStockLineDoesNotMatch {
+    pub async fn description(&self) -> &'static str {
+        "Stock line must match the item, batch, expiry, supplier, hold status and new pack size"
+    }
+}
+
+#[derive(Interface)]
+#[graphql(name = "RepackStockLineErrorInterface")]
+#[graphql(field(name = "description", type = "String"))]
+pub enum RepackErrorInterface {
+    RecordNotFound(RecordNotFound),
+    StockLineIsAllocated(StockLineIsAllocated),
+    NewStockLineDoesNotMatch(NewStockLineDoesNotMatch),
+}
+
+#[derive(SimpleObject)]
+#[graphql(name = "RepackStockLineError")]
+pub struct RepackError {
+    pub error: RepackErrorInterface,
+}
+
+#[derive(Union)]
+#[graphql(name = "RepackStockLineResponse")]
+pub enum RepackResponse {
+    Error(RepackError),
+    Response(StockLineNode),
+}
+
+pub fn repack(ctx: &Context<'_>, store_id: &str, input: RepackInput) -> Result<RepackResponse> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateStockLine,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+
+    map_response(
+        service_provider
+            .stock_line_service
+            .repack_stock_line(&service_context, input.to_domain()),
+    )
+}
+
+pub fn map_response(from: Result<StockLine, ServiceError>) -> Result<RepackResponse> {
+    let result = match from {
+        Ok(stock_line) => RepackResponse::Response(StockLineNode::from_domain(stock_line)),
+        Err(error) => RepackResponse::Error(RepackError {
+            error: map_error(error)?,
+        }),
+    };
+
+    Ok(result)
+}
+
+impl RepackInput {
+    pub fn to_domain(self) -> ServiceInput {
+        let RepackInput {
+            id,
+            number_of_packs,
+            new_pack_size,
+            new_stock_line_id,
+            location_id,
+        } = self;
+
+        ServiceInput {
+            id,
+            number_of_packs,
+            new_pack_size,
+            new_stock_line_id,
+            location_id,
+        }
+    }
+}
+
+fn map_error(error: ServiceError) -> Result<RepackErrorInterface> {
+    use StandardGraphqlError::*;
+    let formatted_error = format!("{:#?}", error);
+
+    let graphql_error = match error {
+        // Structured Errors
+        ServiceError::StockDoesNotExist => {
+            return Ok(RepackErrorInterface::RecordNotFound(RecordNotFound {}))
+        }
+        ServiceError::StockLineIsAllocated => {
+            return Ok(RepackErrorInterface::StockLineIsAllocated(
+                StockLineIsAllocated {},
+            ))
+        }
+        ServiceError::NewStockLineDoesNotMatch => {
+            return Ok(RepackErrorInterface::NewStockLineDoesNotMatch(
+                NewStockLineDoesNotMatch {},
+            ))
+        }
+        // Standard Graphql Errors
+        ServiceError::StockDoesNotBelongToStore => BadUserInput(formatted_error),
+        ServiceError::LocationDoesNotExist => BadUserInput(formatted_error),
+        ServiceError::InvalidNumberOfPacks => BadUserInput(formatted_error),
+        ServiceError::InvalidPackSize => BadUserInput(formatted_error),
+        ServiceError::StockLineIsPendingInspection => BadUserInput(formatted_error),
+        ServiceError::NewStockLineNotFound => InternalError(formatted_error),
+        ServiceError::InternalError(_) => InternalError(formatted_error),
+        ServiceError::DatabaseError(_) => InternalError(formatted_error),
+    };
+
+    Err(graphql_error.extend())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::StockLineMutations;
+    use async_graphql::EmptyMutation;
+    use graphql_core::{
+        assert_graphql_query, assert_standard_graphql_error, test_helpers::setup_graphl_test,
+    };
+    use repository::{
+        mock::{mock_item_a, mock_stock_line_a, MockDataInserts},
+        StockLine, StorageConnectionManager,
+    };
+    use serde_json::json;
+
+    use service::{
+        service_provider::{ServiceContext, ServiceProvider},
+        stock_line::{
+            RepackStockLine as ServiceInput, RepackStockLineError as ServiceError,
+            StockLineServiceTrait,
+        },
+    };
+
+    type RepackLineMethod = dyn Fn(ServiceInput) -> Result<StockLine, ServiceError> + Sync + Send;
+
+    pub struct TestService(pub Box<RepackLineMethod>);
+
+    impl StockLineServiceTrait for TestService {
+        fn repack_stock_line(
+            &self,
+            _: &ServiceContext,
+            input: ServiceInput,
+        ) -> Result<StockLine, ServiceError> {
+            self.0(input)
+        }
+    }
+
+    fn service_provider(
+        test_service: TestService,
+        connection_manager: &StorageConnectionManager,
+    ) -> ServiceProvider {
+        let mut service_provider = ServiceProvider::new(connection_manager.clone(), "app_data");
+        service_provider.stock_line_service = Box::new(test_service);
+        service_provider
+    }
+
+    #[actix_rt::test]
+    async fn test_graphql_repack_stock_line() {
+        let (_, _, connection_manager, settings) = setup_graphl_test(
+            EmptyMutation,
+            StockLineMutations,
+            "test_graphql_repack_stock_line",
+            MockDataInserts::all(),
+        )
+        .await;
+
+        let mutation = r#"
+        mutation ($input: RepackStockLineInput!, $storeId: String) {
+            repackStockLine(storeId: $storeId, input: $input) {
+              ... on RepackStockLineError {
+                error {
+                  __typename
+                }
+              }
+              ... on StockLineNode {
+                id
+              }
+            }
+          }
+        "#;
+        let variables = json!({
+          "input": {
+            "id": mock_stock_line_a().id,
+            "numberOfPacks": 2.0,
+            "newPackSize": 5
+          },
+          "storeId": "store_a"
+        });
+
+        // NewStockLineDoesNotMatch
+        let test_service = TestService(Box::new(|_| Err(ServiceError::NewStockLineDoesNotMatch)));
+        let expected = json!({
+            "repackStockLine": {
+              "error": {
+                "__typename": "NewStockLineDoesNotMatch"
+              }
+            }
+          }
+        );
+        assert_graphql_query!(
+            &settings,
+            mutation,
+            &Some(variables.clone()),
+            &expected,
+            Some(service_provider(test_service, &connection_manager))
+        );
+
+        // InvalidPackSize
+        let test_service = TestService(Box::new(|_| Err(ServiceError::InvalidPackSize)));
+        let expected_message = "Bad user input";
+        assert_standard_graphql_error!(
+            &settings,
+            &mutation,
+            &Some(variables.clone()),
+            &expected_message,
+            None,
+            Some(service_provider(test_service, &connection_manager))
+        );
+
+        // Success
+        let test_service = TestService(Box::new(|input| {
+            assert_eq!(
+                input,
+                ServiceInput {
+                    id: mock_stock_line_a().id,
+                    number_of_packs: 2.0,
+                    new_pack_size: 5,
+                    new_stock_line_id: None,
+                    location_id: None,
+                }
+            );
+            Ok(StockLine {
+                stock_line_row: mock_stock_line_a(),
+                item_row: mock_item_a(),
+                location_row: None,
+                name_row: None,
+            })
+        }));
+        let expected = json!({
+            "repackStockLine": {
+                "id": mock_stock_line_a().id,
+            }
+          }
+        );
+        assert_graphql_query!(
+            &settings,
+            mutation,
+            &Some(variables),
+            &expected,
+            Some(service_provider(test_service, &connection_manager))
+        );
+    }
+}
@@ -0,0 +1,296 @@
+use async_graphql::*;
+use graphql_core::{
+    simple_generic_errors::RecordNotFound,
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use graphql_types::types::StockLineNode;
+use repository::StockLine;
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    stock_line::{SplitStockLine as ServiceInput, SplitStockLineError as ServiceError},
+};
+
+#[derive(InputObject)]
+#[graphql(name = "SplitStockLineInput")]
+pub struct SplitInput {
+    pub id: String,
+    /// Number of packs moved to the new stock line
+    pub number_of_packs: f64,
+    /// Location of the new stock line, defaults to the location of the existing stock line
+    pub location_id: Option<String>,
+    /// Hold status of the new stock line, defaults to the hold status of the existing stock line
+    pub on_hold: Option<bool>,
+}
+
+pub struct StockLineIsAllocated;
+#[Object]
+impl StockLineIsAllocated {
+    pub async fn description(&self) -> &'static str {
+        "Stock line exists in outbound shipments that haven't been shipped"
+    }
+}
+
+#[derive(Interface)]
+#[graphql(name = "SplitStockLineErrorInterface")]
+#[graphql(field(name = "description", type = "String"))]
+pub enum SplitErrorInterface {
+    RecordNotFound(RecordNotFound),
+    StockLineIsAllocated(StockLineIsAllocated),
+}
+
+#[derive(SimpleObject)]
+#[graphql(name = "SplitStockLineError")]
+pub struct SplitError {
+    pub error: SplitErrorInterface,
+}
+
+#[derive(Union)]
+#[graphql(name = "SplitStockLineResponse")]
+pub enum SplitResponse {
+    Error(SplitError),
+    Response(StockLineNode),
+}
+
+pub fn split(ctx: &Context<'_>, store_id: &str, input: SplitInput) -> Result<SplitResponse> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateStockLine,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+
+    map_response(
+        service_provider
+            .stock_line_service
+            .split_stock_line(&service_context, input.to_domain()),
+    )
+}
+
+pub fn map_response(from: Result<StockLine, ServiceError>) -> Result<SplitResponse> {
+    let result = match from {
+        Ok(stock_line) => SplitResponse::Response(StockLineNode::from_domain(stock_line)),
+        Err(error) => SplitResponse::Error(SplitError {
+            error: map_error(error)?,
+        }),
+    };
+
+    Ok(result)
+}
+
+impl SplitInput {
+    pub fn to_domain(self) -> ServiceInput {
+        let SplitInput {
+            id,
+            number_of_packs,
+            location_id,
+            on_hold,
+        } = self;
+
+        ServiceInput {
+            id,
+            number_of_packs,
+            location_id,
+            on_hold,
+        }
+    }
+}
+
+fn map_error(error: ServiceError) -> Result<SplitErrorInterface> {
+    use StandardGraphqlError::*;
+    let formatted_error = format!("{:#?}", error);
+
+    let graphql_error = match error {
+        // Structured Errors
+        ServiceError::StockDoesNotExist => {
+            return Ok(SplitErrorInterface::RecordNotFound(RecordNotFound {}))
+        }
+        ServiceError::StockLineIsAllocated => {
+            return Ok(SplitErrorInterface::StockLineIsAllocated(
+                StockLineIsAllocated {},
+            ))
+        }
+        // Standard Graphql Errors
+        ServiceError::StockDoesNotBelongToStore => BadUserInput(formatted_error),
+        ServiceError::LocationDoesNotExist => BadUserInput(formatted_error),
+        ServiceError::InvalidNumberOfPacks => BadUserInput(formatted_error),
+        ServiceError::CannotReleaseHold => BadUserInput(formatted_error),
+        ServiceError::StockLineIsPendingInspection => BadUserInput(formatted_error),
+        ServiceError::NewStockLineNotFound => InternalError(formatted_error),
+        ServiceError::InternalError(_) => InternalError(formatted_error),
+        ServiceError::DatabaseError(_) => InternalError(formatted_error),
+    };
+
+    Err(graphql_error.extend())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::StockLineMutations;
+    use async_graphql::EmptyMutation;
+    use graphql_core::{
+        assert_graphql_query, assert_standard_graphql_error, test_helpers::setup_graphl_test,
+    };
+    use repository::{
+        mock::{mock_item_a, mock_stock_line_a, MockDataInserts},
+        StockLine, StorageConnectionManager,
+    };
+    use serde_json::json;
+
+    use service::{
+        service_provider::{ServiceContext, ServiceProvider},
+        stock_line::{
+            SplitStockLine as ServiceInput, SplitStockLineError as ServiceError,
+            StockLineServiceTrait,
+        },
+    };
+
+    type SplitLineMethod = dyn Fn(ServiceInput) -> Result<StockLine, ServiceError> + Sync + Send;
+
+    pub struct TestService(pub Box<SplitLineMethod>);
+
+    impl StockLineServiceTrait for TestService {
+        fn split_stock_line(
+            &self,
+            _: &ServiceContext,
+            input: ServiceInput,
+        ) -> Result<StockLine, ServiceError> {
+            self.0(input)
+        }
+    }
+
+    fn service_provider(
+        test_service: TestService,
+        connection_manager: &StorageConnectionManager,
+    ) -> ServiceProvider {
+        let mut service_provider = ServiceProvider::new(connection_manager.clone(), "app_data");
+        service_provider.stock_line_service = Box::new(test_service);
+        service_provider
+    }
+
+    #[actix_rt::test]
+    async fn test_graphql_split_stock_line() {
+        let (_, _, connection_manager, settings) = setup_graphl_test(
+            EmptyMutation,
+            StockLineMutations,
+            "test_graphql_split_stock_line",
+            MockDataInserts::all(),
+        )
+        .await;
+
+        let mutation = r#"
+        mutation ($input: SplitStockLineInput!, $storeId: String) {
+            splitStockLine(storeId: $storeId, input: $input) {
+              ... on SplitStockLineError {
+                error {
+                  __typename
+                }
+              }
+              ... on StockLineNode {
+                id
+              }
+            }
+          }
+        "#;
+        let variables = json!({
+          "input": {
+            "id": mock_stock_line_a().id,
+            "numberOfPacks": 2.0,
+            "onHold": true
+          },
+          "storeId": "store_a"
+        });
+
+        // StockLineIsAllocated
+        let test_service = TestService(Box::new(|_| Err(ServiceError::StockLineIsAllocated)));
+        let expected = json!({
+            "splitStockLine": {
+              "error": {
+                "__typename": "StockLineIsAllocated"
+              }
+            }
+          }
+        );
+        assert_graphql_query!(
+            &settings,
+            mutation,
+            &Some(variables.clone()),
+            &expected,
+            Some(service_provider(test_service, &connection_manager))
+        );
+
+        // InvalidNumberOfPacks
+        let test_service = TestService(Box::new(|_| Err(ServiceError::InvalidNumberOfPacks)));
+        let expected_message = "Bad user input";
+        assert_standard_graphql_error!(
+            &settings,
+            &mutation,
+            &Some(variables.clone()),
+            &expected_message,
+            None,
+            Some(service_provider(test_service, &connection_manager))
+        );
+
+        // CannotReleaseHold
+        let test_service = TestService(Box::new(|_| Err(ServiceError::CannotReleaseHold)));
+        let expected_message = "Bad user input";
+        assert_standard_graphql_error!(
+            &settings,
+            &mutation,
+            &Some(variables.clone()),
+            &expected_message,
+            None,
+            Some(service_provider(test_service, &connection_manager))
+        );
+
+        // StockLineIsPendingInspection
+        let test_service = TestService(Box::new(|_| {
+            Err(ServiceError::StockLineIsPendingInspection)
+        }));
+        let expected_message = "Bad user input";
+        assert_standard_graphql_error!(
+            &settings,
+            &mutation,
+            &Some(variables.clone()),
+            &expected_message,
+            None,
+            Some(service_provider(test_service, &connection_manager))
+        );
+
+        // Success
+        let test_service = TestService(Box::new(|input| {
+            assert_eq!(
+                input,
+                ServiceInput {
+                    id: mock_stock_line_a().id,
+                    number_of_packs: 2.0,
+                    location_id: None,
+                    on_hold: Some(true),
+                }
+            );
+            Ok(StockLine {
+                stock_line_row: mock_stock_line_a(),
+                item_row: mock_item_a(),
+                location_row: None,
+                name_row: None,
+            })
+        }));
+        let expected = json!({
+            "splitStockLine": {
+                "id": mock_stock_line_a().id,
+            }
+          }
+        );
+        assert_graphql_query!(
+            &settings,
+            mutation,
+            &Some(variables),
+            &expected,
+            Some(service_provider(test_service, &connection_manager))
+        );
+    }
+}
@@ -115,6 +115,7 @@ fn map_error(error: ServiceError) -> Result<UpdateErrorInterface> {
         // Standard Graphql Errors
         ServiceError::StockDoesNotBelongToStore => BadUserInput(formatted_error),
         ServiceError::LocationDoesNotExist => BadUserInput(formatted_error),
+        ServiceError::StockLineIsPendingInspection => BadUserInput(formatted_error),
         ServiceError::UpdatedStockNotFound => InternalError(formatted_error),
         ServiceError::DatabaseError(_) => InternalError(formatted_error),
     };
@@ -227,6 +228,20 @@ mod test {
             None,
             Some(service_provider(test_service, &connection_manager))
         );
+
+        // StockLineIsPendingInspection
+        let test_service = TestService(Box::new(|_| {
+            Err(ServiceError::StockLineIsPendingInspection)
+        }));
+        let expected_message = "Bad user input";
+        assert_standard_graphql_error!(
+            &settings,
+            &mutation,
+            &Some(empty_variables()),
+            &expected_message,
+            None,
+            Some(service_provider(test_service, &connection_manager))
+        );
     }
 
     #[actix_rt::test]
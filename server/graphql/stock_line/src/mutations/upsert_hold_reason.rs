@@ -0,0 +1,85 @@
+use async_graphql::*;
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use graphql_types::types::HoldReasonNode;
+use repository::HoldReasonRow;
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    hold::reason::{UpsertHoldReason as ServiceInput, UpsertHoldReasonError as ServiceError},
+};
+
+#[derive(InputObject)]
+#[graphql(name = "UpsertHoldReasonInput")]
+pub struct UpsertHoldReasonInput {
+    pub id: String,
+    pub reason: String,
+    /// Inactive reasons can't be used for new holds
+    pub is_active: bool,
+}
+
+#[derive(Union)]
+#[graphql(name = "UpsertHoldReasonResponse")]
+pub enum UpsertHoldReasonResponse {
+    Response(HoldReasonNode),
+}
+
+pub fn upsert_hold_reason(
+    ctx: &Context<'_>,
+    input: UpsertHoldReasonInput,
+) -> Result<UpsertHoldReasonResponse> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::ServerAdmin,
+            store_id: None,
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context("".to_string(), user.user_id)?;
+
+    map_response(
+        service_provider
+            .hold_service
+            .upsert_hold_reason(&service_context, input.to_domain()),
+    )
+}
+
+pub fn map_response(from: Result<HoldReasonRow, ServiceError>) -> Result<UpsertHoldReasonResponse> {
+    match from {
+        Ok(hold_reason) => Ok(UpsertHoldReasonResponse::Response(
+            HoldReasonNode::from_domain(hold_reason),
+        )),
+        Err(error) => Err(map_error(error)),
+    }
+}
+
+impl UpsertHoldReasonInput {
+    pub fn to_domain(self) -> ServiceInput {
+        let UpsertHoldReasonInput {
+            id,
+            reason,
+            is_active,
+        } = self;
+
+        ServiceInput {
+            id,
+            reason,
+            is_active,
+        }
+    }
+}
+
+fn map_error(error: ServiceError) -> async_graphql::Error {
+    use StandardGraphqlError::*;
+    let formatted_error = format!("{:#?}", error);
+
+    let graphql_error = match error {
+        ServiceError::ReasonNotProvided => BadUserInput(formatted_error),
+        ServiceError::DatabaseError(_) => InternalError(formatted_error),
+    };
+
+    graphql_error.extend()
+}
@@ -0,0 +1,281 @@
+use async_graphql::*;
+use graphql_core::{
+    simple_generic_errors::RecordNotFound,
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use graphql_types::{
+    generic_errors::{AdjustmentReasonNotValid, AdjustmentReasonRequired},
+    types::InvoiceNode,
+};
+use repository::Invoice;
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    expired_stock::write_off::{
+        WriteOffExpiredStock as ServiceInput, WriteOffExpiredStockError as ServiceError,
+    },
+};
+
+use super::StockLineIsAllocated;
+
+#[derive(InputObject)]
+#[graphql(name = "WriteOffExpiredStockInput")]
+pub struct WriteOffExpiredStockInput {
+    pub stock_line_ids: Vec<String>,
+    /// Defaults to the active reduction reason mentioning expiry, e.g. "Expired"
+    pub inventory_adjustment_reason_id: Option<String>,
+    pub comment: Option<String>,
+}
+
+pub struct StockLineNotExpired;
+#[Object]
+impl StockLineNotExpired {
+    pub async fn description(&self) -> &'static str {
+        "Stock line has no expiry date or hasn't expired yet"
+    }
+}
+
+#[derive(Interface)]
+#[graphql(name = "WriteOffExpiredStockErrorInterface")]
+#[graphql(field(name = "description", type = "String"))]
+pub enum WriteOffExpiredStockErrorInterface {
+    RecordNotFound(RecordNotFound),
+    StockLineNotExpired(StockLineNotExpired),
+    StockLineIsAllocated(StockLineIsAllocated),
+    AdjustmentReasonRequired(AdjustmentReasonRequired),
+    AdjustmentReasonNotValid(AdjustmentReasonNotValid),
+}
+
+#[derive(SimpleObject)]
+#[graphql(name = "WriteOffExpiredStockError")]
+pub struct WriteOffExpiredStockError {
+    pub error: WriteOffExpiredStockErrorInterface,
+}
+
+#[derive(Union)]
+#[graphql(name = "WriteOffExpiredStockResponse")]
+pub enum WriteOffExpiredStockResponse {
+    Error(WriteOffExpiredStockError),
+    Response(InvoiceNode),
+}
+
+pub fn write_off_expired_stock(
+    ctx: &Context<'_>,
+    store_id: &str,
+    input: WriteOffExpiredStockInput,
+) -> Result<WriteOffExpiredStockResponse> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateStockLine,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+
+    map_response(
+        service_provider
+            .expired_stock_service
+            .write_off_expired_stock(&service_context, input.to_domain()),
+    )
+}
+
+pub fn map_response(from: Result<Invoice, ServiceError>) -> Result<WriteOffExpiredStockResponse> {
+    let result = match from {
+        Ok(invoice) => WriteOffExpiredStockResponse::Response(InvoiceNode::from_domain(invoice)),
+        Err(error) => WriteOffExpiredStockResponse::Error(WriteOffExpiredStockError {
+            error: map_error(error)?,
+        }),
+    };
+
+    Ok(result)
+}
+
+impl WriteOffExpiredStockInput {
+    pub fn to_domain(self) -> ServiceInput {
+        let WriteOffExpiredStockInput {
+            stock_line_ids,
+            inventory_adjustment_reason_id,
+            comment,
+        } = self;
+
+        ServiceInput {
+            stock_line_ids,
+            inventory_adjustment_reason_id,
+            comment,
+        }
+    }
+}
+
+fn map_error(error: ServiceError) -> Result<WriteOffExpiredStockErrorInterface> {
+    use StandardGraphqlError::*;
+    use WriteOffExpiredStockErrorInterface as OutError;
+    let formatted_error = format!("{:#?}", error);
+
+    let graphql_error = match error {
+        // Structured Errors
+        ServiceError::StockDoesNotExist => return Ok(OutError::RecordNotFound(RecordNotFound {})),
+        ServiceError::StockLineNotExpired => {
+            return Ok(OutError::StockLineNotExpired(StockLineNotExpired))
+        }
+        ServiceError::StockLineIsAllocated => {
+            return Ok(OutError::StockLineIsAllocated(StockLineIsAllocated))
+        }
+        ServiceError::AdjustmentReasonNotProvided => {
+            return Ok(OutError::AdjustmentReasonRequired(AdjustmentReasonRequired))
+        }
+        ServiceError::AdjustmentReasonNotValid => {
+            return Ok(OutError::AdjustmentReasonNotValid(AdjustmentReasonNotValid))
+        }
+        // Standard Graphql Errors
+        ServiceError::NoStockLines => BadUserInput(formatted_error),
+        ServiceError::StockDoesNotBelongToStore => BadUserInput(formatted_error),
+        ServiceError::StockLineHasNoStock => BadUserInput(formatted_error),
+        ServiceError::NewlyCreatedInvoiceDoesNotExist => InternalError(formatted_error),
+        ServiceError::InternalError(_) => InternalError(formatted_error),
+        ServiceError::DatabaseError(_) => InternalError(formatted_error),
+    };
+
+    Err(graphql_error.extend())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::StockLineMutations;
+    use async_graphql::EmptyMutation;
+    use graphql_core::{
+        assert_graphql_query, assert_standard_graphql_error, test_helpers::setup_graphl_test,
+    };
+    use repository::{
+        mock::{mock_inbound_shipment_a, mock_stock_line_a, MockDataInserts},
+        Invoice, StorageConnectionManager,
+    };
+    use serde_json::json;
+
+    use service::{
+        expired_stock::{
+            write_off::{
+                WriteOffExpiredStock as ServiceInput, WriteOffExpiredStockError as ServiceError,
+            },
+            ExpiredStockServiceTrait,
+        },
+        service_provider::{ServiceContext, ServiceProvider},
+    };
+
+    type WriteOffMethod = dyn Fn(ServiceInput) -> Result<Invoice, ServiceError> + Sync + Send;
+
+    pub struct TestService(pub Box<WriteOffMethod>);
+
+    impl ExpiredStockServiceTrait for TestService {
+        fn write_off_expired_stock(
+            &self,
+            _: &ServiceContext,
+            input: ServiceInput,
+        ) -> Result<Invoice, ServiceError> {
+            self.0(input)
+        }
+    }
+
+    fn service_provider(
+        test_service: TestService,
+        connection_manager: &StorageConnectionManager,
+    ) -> ServiceProvider {
+        let mut service_provider = ServiceProvider::new(connection_manager.clone(), "app_data");
+        service_provider.expired_stock_service = Box::new(test_service);
+        service_provider
+    }
+
+    #[actix_rt::test]
+    async fn test_graphql_write_off_expired_stock() {
+        let (_, _, connection_manager, settings) = setup_graphl_test(
+            EmptyMutation,
+            StockLineMutations,
+            "test_graphql_write_off_expired_stock",
+            MockDataInserts::all(),
+        )
+        .await;
+
+        let mutation = r#"
+        mutation ($input: WriteOffExpiredStockInput!, $storeId: String) {
+            writeOffExpiredStock(storeId: $storeId, input: $input) {
+              ... on WriteOffExpiredStockError {
+                error {
+                  __typename
+                }
+              }
+              ... on InvoiceNode {
+                id
+              }
+            }
+          }
+        "#;
+        let variables = json!({
+          "input": {
+            "stockLineIds": [mock_stock_line_a().id],
+            "comment": "Incinerated"
+          },
+          "storeId": "store_a"
+        });
+
+        // StockLineNotExpired
+        let test_service = TestService(Box::new(|_| Err(ServiceError::StockLineNotExpired)));
+        let expected = json!({
+            "writeOffExpiredStock": {
+              "error": {
+                "__typename": "StockLineNotExpired"
+              }
+            }
+          }
+        );
+        assert_graphql_query!(
+            &settings,
+            mutation,
+            &Some(variables.clone()),
+            &expected,
+            Some(service_provider(test_service, &connection_manager))
+        );
+
+        // StockLineHasNoStock
+        let test_service = TestService(Box::new(|_| Err(ServiceError::StockLineHasNoStock)));
+        let expected_message = "Bad user input";
+        assert_standard_graphql_error!(
+            &settings,
+            &mutation,
+            &Some(variables.clone()),
+            &expected_message,
+            None,
+            Some(service_provider(test_service, &connection_manager))
+        );
+
+        // Success
+        let test_service = TestService(Box::new(|input| {
+            assert_eq!(
+                input,
+                ServiceInput {
+                    stock_line_ids: vec![mock_stock_line_a().id],
+                    inventory_adjustment_reason_id: None,
+                    comment: Some("Incinerated".to_string()),
+                }
+            );
+            Ok(Invoice {
+                invoice_row: mock_inbound_shipment_a(),
+                ..Default::default()
+            })
+        }));
+        let expected = json!({
+            "writeOffExpiredStock": {
+                "id": mock_inbound_shipment_a().id,
+            }
+          }
+        );
+        assert_graphql_query!(
+            &settings,
+            mutation,
+            &Some(variables),
+            &expected,
+            Some(service_provider(test_service, &connection_manager))
+        );
+    }
+}
@@ -0,0 +1,82 @@
+use async_graphql::*;
+use chrono::NaiveDate;
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use graphql_types::types::{CycleCountAccuracyNode, CycleCountConfigNode, ItemAbcClassNode};
+use service::auth::{Resource, ResourceAccessRequest};
+
+pub fn item_abc_classes(ctx: &Context<'_>, store_id: &str) -> Result<Vec<ItemAbcClassNode>> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::QueryStocktake,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+
+    let rows = service_provider
+        .cycle_count_service
+        .get_item_abc_classes(&service_context)
+        .map_err(|error| StandardGraphqlError::InternalError(format!("{:#?}", error)).extend())?;
+
+    Ok(rows
+        .into_iter()
+        .map(ItemAbcClassNode::from_domain)
+        .collect())
+}
+
+pub fn cycle_count_configs(ctx: &Context<'_>, store_id: &str) -> Result<Vec<CycleCountConfigNode>> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::QueryStocktake,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+
+    let rows = service_provider
+        .cycle_count_service
+        .get_cycle_count_configs(&service_context)
+        .map_err(|error| StandardGraphqlError::InternalError(format!("{:#?}", error)).extend())?;
+
+    Ok(rows
+        .into_iter()
+        .map(CycleCountConfigNode::from_domain)
+        .collect())
+}
+
+pub fn cycle_count_accuracy(
+    ctx: &Context<'_>,
+    store_id: &str,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<Vec<CycleCountAccuracyNode>> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::QueryStocktake,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+
+    let accuracy = service_provider
+        .cycle_count_service
+        .get_cycle_count_accuracy(&service_context, from, to)
+        .map_err(|error| StandardGraphqlError::InternalError(format!("{:#?}", error)).extend())?;
+
+    Ok(accuracy
+        .into_iter()
+        .map(CycleCountAccuracyNode::from_domain)
+        .collect())
+}
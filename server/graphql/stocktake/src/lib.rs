@@ -1,8 +1,15 @@
+mod cycle_count_queries;
 pub mod mutations;
 mod stocktake_queries;
+use self::cycle_count_queries::*;
 use self::stocktake_queries::*;
+pub use self::stocktake_queries::StocktakeFilterInput;
 use async_graphql::*;
+use chrono::NaiveDate;
 use graphql_core::pagination::PaginationInput;
+use graphql_types::types::{
+    CycleCountAccuracyNode, CycleCountConfigNode, ItemAbcClassNode, StocktakeNode,
+};
 
 #[derive(Default, Clone)]
 pub struct StocktakeQueries;
@@ -37,6 +44,34 @@ impl StocktakeQueries {
     ) -> Result<StocktakesResponse> {
         stocktakes(ctx, &store_id, page, filter, sort)
     }
+
+    /// ABC classification of the store's items, highest consumption value first
+    pub async fn item_abc_classes(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+    ) -> Result<Vec<ItemAbcClassNode>> {
+        item_abc_classes(ctx, &store_id)
+    }
+
+    pub async fn cycle_count_configs(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+    ) -> Result<Vec<CycleCountConfigNode>> {
+        cycle_count_configs(ctx, &store_id)
+    }
+
+    /// Count accuracy per ABC class of stocktakes finalised in the period
+    pub async fn cycle_count_accuracy(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<CycleCountAccuracyNode>> {
+        cycle_count_accuracy(ctx, &store_id, from, to)
+    }
 }
 
 #[derive(Default, Clone)]
@@ -62,6 +97,17 @@ impl StocktakeMutations {
         mutations::update(ctx, &store_id, input)
     }
 
+    /// Updates the snapshot to the current stock, instead of the finalisation failing due to stock
+    /// movements since the snapshot was taken
+    async fn refresh_stocktake_snapshot(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: mutations::RefreshInput,
+    ) -> Result<mutations::RefreshResponse> {
+        mutations::refresh(ctx, &store_id, input)
+    }
+
     async fn delete_stocktake(
         &self,
         ctx: &Context<'_>,
@@ -70,4 +116,43 @@ impl StocktakeMutations {
     ) -> Result<mutations::DeleteResponse> {
         mutations::delete(ctx, &store_id, input)
     }
+
+    /// Classifies the store's items by consumption value, manually set classes are kept
+    async fn classify_items(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: mutations::ClassifyItemsInput,
+    ) -> Result<Vec<ItemAbcClassNode>> {
+        mutations::classify_items(ctx, &store_id, input)
+    }
+
+    async fn set_item_abc_class(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: mutations::SetItemAbcClassInput,
+    ) -> Result<ItemAbcClassNode> {
+        mutations::set_item_abc_class(ctx, &store_id, input)
+    }
+
+    async fn upsert_cycle_count_config(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: mutations::UpsertCycleCountConfigInput,
+    ) -> Result<CycleCountConfigNode> {
+        mutations::upsert_cycle_count_config(ctx, &store_id, input)
+    }
+
+    /// Generates the cycle count stocktakes that are due, these are also generated daily by the
+    /// server
+    async fn generate_cycle_count_stocktakes(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: mutations::GenerateCycleCountStocktakesInput,
+    ) -> Result<Vec<StocktakeNode>> {
+        mutations::generate_cycle_count_stocktakes(ctx, &store_id, input)
+    }
 }
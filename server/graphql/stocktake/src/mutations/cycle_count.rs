@@ -0,0 +1,308 @@
+use async_graphql::*;
+use chrono::{NaiveDate, Utc};
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use graphql_types::types::{
+    AbcClassNodeType, CycleCountConfigNode, ItemAbcClassNode, StocktakeNode,
+};
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    cycle_count::{
+        classify::{SetItemAbcClass, SetItemAbcClassError},
+        config::{UpsertCycleCountConfig, UpsertCycleCountConfigError},
+        generate::GenerateCycleCountStocktakesError,
+    },
+};
+
+#[derive(InputObject)]
+pub struct ClassifyItemsInput {
+    /// Consumption of the year before this date is used, defaults to today
+    pub reference_date: Option<NaiveDate>,
+}
+
+#[derive(InputObject)]
+pub struct SetItemAbcClassInput {
+    pub item_id: String,
+    /// Null returns the item to its calculated class on the next classification
+    pub abc_class: Option<AbcClassNodeType>,
+}
+
+#[derive(InputObject)]
+pub struct UpsertCycleCountConfigInput {
+    pub abc_class: AbcClassNodeType,
+    pub count_frequency_days: i32,
+}
+
+#[derive(InputObject)]
+pub struct GenerateCycleCountStocktakesInput {
+    /// Defaults to today
+    pub date: Option<NaiveDate>,
+}
+
+pub fn classify_items(
+    ctx: &Context<'_>,
+    store_id: &str,
+    input: ClassifyItemsInput,
+) -> Result<Vec<ItemAbcClassNode>> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateStocktake,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+
+    let rows = service_provider
+        .cycle_count_service
+        .classify_items(
+            &service_context,
+            input
+                .reference_date
+                .unwrap_or_else(|| Utc::now().naive_utc().date()),
+        )
+        .map_err(|error| StandardGraphqlError::InternalError(format!("{:#?}", error)).extend())?;
+
+    Ok(rows
+        .into_iter()
+        .map(ItemAbcClassNode::from_domain)
+        .collect())
+}
+
+pub fn set_item_abc_class(
+    ctx: &Context<'_>,
+    store_id: &str,
+    input: SetItemAbcClassInput,
+) -> Result<ItemAbcClassNode> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateStocktake,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+
+    let input = SetItemAbcClass {
+        item_id: input.item_id,
+        abc_class: input.abc_class.map(AbcClassNodeType::to_domain),
+    };
+    match service_provider
+        .cycle_count_service
+        .set_item_abc_class(&service_context, input)
+    {
+        Ok(row) => Ok(ItemAbcClassNode::from_domain(row)),
+        Err(error) => {
+            let formatted_error = format!("{:#?}", error);
+            let graphql_error = match error {
+                SetItemAbcClassError::ItemDoesNotExist => {
+                    StandardGraphqlError::BadUserInput(formatted_error)
+                }
+                SetItemAbcClassError::DatabaseError(_) => {
+                    StandardGraphqlError::InternalError(formatted_error)
+                }
+            };
+            Err(graphql_error.extend())
+        }
+    }
+}
+
+pub fn upsert_cycle_count_config(
+    ctx: &Context<'_>,
+    store_id: &str,
+    input: UpsertCycleCountConfigInput,
+) -> Result<CycleCountConfigNode> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateStocktake,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+
+    let input = UpsertCycleCountConfig {
+        abc_class: input.abc_class.to_domain(),
+        count_frequency_days: input.count_frequency_days,
+    };
+    match service_provider
+        .cycle_count_service
+        .upsert_cycle_count_config(&service_context, input)
+    {
+        Ok(row) => Ok(CycleCountConfigNode::from_domain(row)),
+        Err(error) => {
+            let formatted_error = format!("{:#?}", error);
+            let graphql_error = match error {
+                UpsertCycleCountConfigError::InvalidCountFrequency => {
+                    StandardGraphqlError::BadUserInput(formatted_error)
+                }
+                UpsertCycleCountConfigError::DatabaseError(_) => {
+                    StandardGraphqlError::InternalError(formatted_error)
+                }
+            };
+            Err(graphql_error.extend())
+        }
+    }
+}
+
+pub fn generate_cycle_count_stocktakes(
+    ctx: &Context<'_>,
+    store_id: &str,
+    input: GenerateCycleCountStocktakesInput,
+) -> Result<Vec<StocktakeNode>> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateStocktake,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+
+    let stocktakes = service_provider
+        .cycle_count_service
+        .generate_cycle_count_stocktakes(
+            &service_context,
+            input.date.unwrap_or_else(|| Utc::now().naive_utc().date()),
+        )
+        .map_err(|error| {
+            let formatted_error = format!("{:#?}", error);
+            match error {
+                GenerateCycleCountStocktakesError::InsertStocktakeError(_)
+                | GenerateCycleCountStocktakesError::DatabaseError(_) => {
+                    StandardGraphqlError::InternalError(formatted_error).extend()
+                }
+            }
+        })?;
+
+    Ok(stocktakes
+        .into_iter()
+        .map(StocktakeNode::from_domain)
+        .collect())
+}
+
+#[cfg(test)]
+mod test {
+    use async_graphql::EmptyMutation;
+    use graphql_core::{
+        assert_graphql_query, assert_standard_graphql_error, test_helpers::setup_graphl_test,
+    };
+    use repository::{
+        mock::{mock_item_a, MockDataInserts},
+        AbcClass, ItemAbcClassRow, StorageConnectionManager,
+    };
+    use serde_json::json;
+    use service::{
+        cycle_count::{
+            classify::{SetItemAbcClass, SetItemAbcClassError},
+            CycleCountServiceTrait,
+        },
+        service_provider::{ServiceContext, ServiceProvider},
+    };
+
+    use crate::StocktakeMutations;
+
+    type SetClassMethod =
+        dyn Fn(SetItemAbcClass) -> Result<ItemAbcClassRow, SetItemAbcClassError> + Sync + Send;
+
+    struct TestService(pub Box<SetClassMethod>);
+
+    impl CycleCountServiceTrait for TestService {
+        fn set_item_abc_class(
+            &self,
+            _: &ServiceContext,
+            input: SetItemAbcClass,
+        ) -> Result<ItemAbcClassRow, SetItemAbcClassError> {
+            self.0(input)
+        }
+    }
+
+    fn service_provider(
+        test_service: TestService,
+        connection_manager: &StorageConnectionManager,
+    ) -> ServiceProvider {
+        let mut service_provider = ServiceProvider::new(connection_manager.clone(), "app_data");
+        service_provider.cycle_count_service = Box::new(test_service);
+        service_provider
+    }
+
+    #[actix_rt::test]
+    async fn test_graphql_set_item_abc_class() {
+        let (_, _, connection_manager, settings) = setup_graphl_test(
+            EmptyMutation,
+            StocktakeMutations,
+            "test_graphql_set_item_abc_class",
+            MockDataInserts::all(),
+        )
+        .await;
+
+        let query = r#"mutation SetItemAbcClass($storeId: String, $input: SetItemAbcClassInput!) {
+            setItemAbcClass(storeId: $storeId, input: $input) {
+                itemId
+                abcClass
+                isManual
+            }
+        }"#;
+        let variables = Some(json!({
+            "storeId": "store_a",
+            "input": {
+                "itemId": mock_item_a().id,
+                "abcClass": "A"
+            }
+        }));
+
+        // ItemDoesNotExist
+        let test_service = TestService(Box::new(|_| Err(SetItemAbcClassError::ItemDoesNotExist)));
+        assert_standard_graphql_error!(
+            &settings,
+            query,
+            &variables,
+            "Bad user input",
+            None,
+            Some(service_provider(test_service, &connection_manager))
+        );
+
+        // Success
+        let test_service = TestService(Box::new(|input| {
+            assert_eq!(
+                input,
+                SetItemAbcClass {
+                    item_id: mock_item_a().id,
+                    abc_class: Some(AbcClass::A),
+                }
+            );
+            Ok(ItemAbcClassRow {
+                id: "item_abc_class".to_string(),
+                store_id: "store_a".to_string(),
+                item_id: mock_item_a().id,
+                abc_class: AbcClass::A,
+                consumption_value: 0.0,
+                is_manual: true,
+            })
+        }));
+        let expected = json!({
+            "setItemAbcClass": {
+                "itemId": mock_item_a().id,
+                "abcClass": "A",
+                "isManual": true
+            }
+        });
+        assert_graphql_query!(
+            &settings,
+            query,
+            &variables,
+            &expected,
+            Some(service_provider(test_service, &connection_manager))
+        );
+    }
+}
@@ -18,10 +18,24 @@ pub struct InsertInput {
     pub comment: Option<String>,
     pub description: Option<String>,
     pub is_locked: Option<bool>,
+    /// Hides the snapshot from counters and requires agreeing counts by multiple counters
+    pub is_blind_count: Option<bool>,
     pub stocktake_date: Option<NaiveDate>,
     pub master_list_id: Option<String>,
     pub location_id: Option<String>,
+    /// Generate lines for the given items
+    pub item_ids: Option<Vec<String>>,
     pub items_have_stock: Option<bool>,
+    /// Add stock expiring on or before the date
+    pub expires_before: Option<NaiveDate>,
+    /// Add stock lines that are on hold
+    pub stock_on_hold: Option<bool>,
+    /// Add stock of items with a code or name containing the text
+    pub item_code_or_name: Option<String>,
+    /// Add items in the store's master lists that have no stock
+    pub master_list_items_without_stock: Option<bool>,
+    /// Leave out stock counted in a stocktake finalised within this many days
+    pub not_counted_in_days: Option<u32>,
 }
 
 #[derive(Union)]
@@ -93,9 +107,16 @@ impl InsertInput {
             description,
             stocktake_date,
             is_locked,
+            is_blind_count,
             location_id,
             master_list_id,
+            item_ids,
             items_have_stock,
+            expires_before,
+            stock_on_hold,
+            item_code_or_name,
+            master_list_items_without_stock,
+            not_counted_in_days,
         } = self;
 
         ServiceInput {
@@ -104,9 +125,16 @@ impl InsertInput {
             description,
             stocktake_date,
             is_locked,
+            is_blind_count,
             location_id,
             master_list_id,
+            item_ids,
             items_have_stock,
+            expires_before,
+            stock_on_hold,
+            item_code_or_name,
+            master_list_items_without_stock,
+            not_counted_in_days,
         }
     }
 }
@@ -181,9 +209,16 @@ mod test {
                     description: Some("description".to_string()),
                     stocktake_date: Some(NaiveDate::from_ymd_opt(2022, 01, 03).unwrap()),
                     is_locked: Some(true),
+                    is_blind_count: None,
                     location_id: None,
                     master_list_id: None,
+                    item_ids: None,
                     items_have_stock: None,
+                    expires_before: None,
+                    stock_on_hold: None,
+                    item_code_or_name: None,
+                    master_list_items_without_stock: None,
+                    not_counted_in_days: None,
                 }
             );
             // StocktakeNode result is checked in queries
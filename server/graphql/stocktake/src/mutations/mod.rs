@@ -6,3 +6,9 @@ pub use delete::*;
 
 pub mod update;
 pub use update::*;
+
+pub mod refresh;
+pub use refresh::*;
+
+pub mod cycle_count;
+pub use cycle_count::*;
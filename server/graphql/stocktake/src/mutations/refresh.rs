@@ -0,0 +1,260 @@
+use async_graphql::*;
+
+use graphql_core::simple_generic_errors::{CannotEditStocktake, StocktakeIsLocked};
+use graphql_core::standard_graphql_error::{validate_auth, StandardGraphqlError};
+use graphql_core::ContextExt;
+use graphql_types::types::{RefreshedStocktakeLineNode, StocktakeLineConnector};
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    stocktake::{RefreshStocktakeSnapshotError as ServiceError, RefreshStocktakeSnapshotResult},
+};
+
+#[derive(InputObject)]
+#[graphql(name = "RefreshStocktakeSnapshotInput")]
+pub struct RefreshInput {
+    pub id: String,
+}
+
+pub struct RefreshStocktakeSnapshotNode {
+    pub result: RefreshStocktakeSnapshotResult,
+}
+
+#[Object]
+impl RefreshStocktakeSnapshotNode {
+    /// Lines where the stock moved since the snapshot was taken, counts are moved by the same
+    /// number of packs
+    pub async fn updated_lines(&self) -> Vec<RefreshedStocktakeLineNode> {
+        self.result
+            .updated_lines
+            .clone()
+            .into_iter()
+            .map(RefreshedStocktakeLineNode::from_domain)
+            .collect()
+    }
+
+    /// Lines added for stock received since the snapshot was taken
+    pub async fn inserted_lines(&self) -> StocktakeLineConnector {
+        StocktakeLineConnector::from_domain_vec(self.result.inserted_lines.clone())
+    }
+}
+
+#[derive(Interface)]
+#[graphql(name = "RefreshStocktakeSnapshotErrorInterface")]
+#[graphql(field(name = "description", type = "String"))]
+pub enum RefreshErrorInterface {
+    CannotEditStocktake(CannotEditStocktake),
+    StocktakeIsLocked(StocktakeIsLocked),
+}
+
+#[derive(SimpleObject)]
+#[graphql(name = "RefreshStocktakeSnapshotError")]
+pub struct RefreshError {
+    pub error: RefreshErrorInterface,
+}
+
+#[derive(Union)]
+#[graphql(name = "RefreshStocktakeSnapshotResponse")]
+pub enum RefreshResponse {
+    Error(RefreshError),
+    Response(RefreshStocktakeSnapshotNode),
+}
+
+pub fn refresh(ctx: &Context<'_>, store_id: &str, input: RefreshInput) -> Result<RefreshResponse> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateStocktake,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+    map_response(
+        service_provider
+            .stocktake_service
+            .refresh_stocktake_snapshot(&service_context, &input.id),
+    )
+}
+
+pub fn map_response(
+    from: Result<RefreshStocktakeSnapshotResult, ServiceError>,
+) -> Result<RefreshResponse> {
+    let result = match from {
+        Ok(result) => RefreshResponse::Response(RefreshStocktakeSnapshotNode { result }),
+        Err(error) => RefreshResponse::Error(RefreshError {
+            error: map_error(error)?,
+        }),
+    };
+
+    Ok(result)
+}
+
+fn map_error(error: ServiceError) -> Result<RefreshErrorInterface> {
+    use StandardGraphqlError::*;
+    let formatted_error = format!("{:#?}", error);
+
+    let graphql_error = match error {
+        // Structured Errors
+        ServiceError::CannotEditFinalised => {
+            return Ok(RefreshErrorInterface::CannotEditStocktake(
+                CannotEditStocktake {},
+            ))
+        }
+        ServiceError::StocktakeIsLocked => {
+            return Ok(RefreshErrorInterface::StocktakeIsLocked(
+                StocktakeIsLocked {},
+            ))
+        }
+        // Standard Graphql Errors
+        ServiceError::InvalidStore => BadUserInput(formatted_error),
+        ServiceError::StocktakeDoesNotExist => BadUserInput(formatted_error),
+        ServiceError::InternalError(err) => InternalError(err),
+        ServiceError::DatabaseError(_) => InternalError(formatted_error),
+    };
+
+    Err(graphql_error.extend())
+}
+
+#[cfg(test)]
+mod test {
+    use async_graphql::EmptyMutation;
+    use graphql_core::{assert_graphql_query, test_helpers::setup_graphl_test};
+    use repository::{
+        mock::{mock_stocktake_line_a, MockDataInserts},
+        StocktakeLine, StorageConnectionManager,
+    };
+    use serde_json::json;
+    use service::{
+        service_provider::{ServiceContext, ServiceProvider},
+        stocktake::{
+            RefreshStocktakeSnapshotError, RefreshStocktakeSnapshotResult, RefreshedStocktakeLine,
+            StocktakeServiceTrait,
+        },
+    };
+
+    use crate::StocktakeMutations;
+
+    type ServiceMethod = dyn Fn(&str) -> Result<RefreshStocktakeSnapshotResult, RefreshStocktakeSnapshotError>
+        + Sync
+        + Send;
+
+    pub struct TestService(pub Box<ServiceMethod>);
+
+    impl StocktakeServiceTrait for TestService {
+        fn refresh_stocktake_snapshot(
+            &self,
+            _: &ServiceContext,
+            stocktake_id: &str,
+        ) -> Result<RefreshStocktakeSnapshotResult, RefreshStocktakeSnapshotError> {
+            (self.0)(stocktake_id)
+        }
+    }
+
+    fn service_provider(
+        test_service: TestService,
+        connection_manager: &StorageConnectionManager,
+    ) -> ServiceProvider {
+        let mut service_provider = ServiceProvider::new(connection_manager.clone(), "app_data");
+        service_provider.stocktake_service = Box::new(test_service);
+        service_provider
+    }
+
+    #[actix_rt::test]
+    async fn test_graphql_stocktake_refresh_snapshot() {
+        let (_, _, connection_manager, settings) = setup_graphl_test(
+            EmptyMutation,
+            StocktakeMutations,
+            "omsupply-database-gql-stocktake_refresh_snapshot",
+            MockDataInserts::all(),
+        )
+        .await;
+
+        let query = r#"mutation RefreshStocktakeSnapshot($storeId: String, $input: RefreshStocktakeSnapshotInput!) {
+            refreshStocktakeSnapshot(storeId: $storeId, input: $input) {
+                ... on RefreshStocktakeSnapshotError {
+                    error {
+                        __typename
+                    }
+                }
+                ... on RefreshStocktakeSnapshotNode {
+                    updatedLines {
+                        line {
+                            id
+                            snapshotNumberOfPacks
+                        }
+                        previousSnapshotNumberOfPacks
+                        previousCountedNumberOfPacks
+                    }
+                    insertedLines {
+                        totalCount
+                    }
+                }
+            }
+        }"#;
+        let variables = Some(json!({
+            "storeId": "store_a",
+            "input": {
+                "id": "stocktake_a"
+            }
+        }));
+
+        // StocktakeIsLocked
+        let test_service = TestService(Box::new(|_| {
+            Err(RefreshStocktakeSnapshotError::StocktakeIsLocked)
+        }));
+        let expected = json!({
+            "refreshStocktakeSnapshot": {
+                "error": {
+                    "__typename": "StocktakeIsLocked"
+                }
+            }
+        });
+        assert_graphql_query!(
+            &settings,
+            query,
+            &variables,
+            &expected,
+            Some(service_provider(test_service, &connection_manager))
+        );
+
+        // success
+        let test_service = TestService(Box::new(|stocktake_id| {
+            assert_eq!(stocktake_id, "stocktake_a");
+            Ok(RefreshStocktakeSnapshotResult {
+                updated_lines: vec![RefreshedStocktakeLine {
+                    line: StocktakeLine {
+                        line: mock_stocktake_line_a(),
+                        stock_line: None,
+                        location: None,
+                    },
+                    previous_snapshot_number_of_packs: 5.0,
+                    previous_counted_number_of_packs: Some(3.0),
+                }],
+                inserted_lines: Vec::new(),
+            })
+        }));
+        let expected = json!({
+            "refreshStocktakeSnapshot": {
+                "updatedLines": [{
+                    "line": {
+                        "id": mock_stocktake_line_a().id,
+                        "snapshotNumberOfPacks": mock_stocktake_line_a().snapshot_number_of_packs,
+                    },
+                    "previousSnapshotNumberOfPacks": 5.0,
+                    "previousCountedNumberOfPacks": 3.0,
+                }],
+                "insertedLines": {
+                    "totalCount": 0
+                }
+            }
+        });
+        assert_graphql_query!(
+            &settings,
+            query,
+            &variables,
+            &expected,
+            Some(service_provider(test_service, &connection_manager))
+        );
+    }
+}
@@ -41,6 +41,18 @@ impl SnapshotCountCurrentCountMismatch {
     }
 }
 
+pub struct CountsNotReconciled(StocktakeLineConnector);
+#[Object]
+impl CountsNotReconciled {
+    pub async fn description(&self) -> &'static str {
+        "Blind stocktake lines don't have an agreed count"
+    }
+
+    pub async fn lines(&self) -> &StocktakeLineConnector {
+        &self.0
+    }
+}
+
 pub struct StockLinesReducedBelowZero(pub Vec<StockLine>);
 
 #[Object]
@@ -66,6 +78,7 @@ pub enum UpdateErrorInterface {
     StocktakeIsLocked(StocktakeIsLocked),
     CannotEditStocktake(CannotEditStocktake),
     StockLinesReducedBelowZero(StockLinesReducedBelowZero),
+    CountsNotReconciled(CountsNotReconciled),
 }
 
 #[derive(SimpleObject)]
@@ -135,6 +148,11 @@ fn map_error(err: ServiceError) -> Result<UpdateErrorInterface> {
                 StockLinesReducedBelowZero(lines),
             ))
         }
+        ServiceError::CountsNotReconciled(lines) => {
+            return Ok(UpdateErrorInterface::CountsNotReconciled(
+                CountsNotReconciled(StocktakeLineConnector::from_domain_vec(lines)),
+            ))
+        }
         // Standard Graphql Errors
         // TODO some are structured errors (where can be changed concurrently)
         ServiceError::InvalidStore => BadUserInput(formatted_error),
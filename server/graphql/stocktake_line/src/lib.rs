@@ -25,6 +25,16 @@ impl StocktakeLineMutations {
         mutations::update(ctx, &store_id, input)
     }
 
+    /// Count of the current user for a line of a blind stocktake
+    async fn insert_stocktake_line_count(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: mutations::InsertCountInput,
+    ) -> Result<mutations::InsertCountResponse> {
+        mutations::insert_count(ctx, &store_id, input)
+    }
+
     async fn delete_stocktake_line(
         &self,
         ctx: &Context<'_>,
@@ -0,0 +1,251 @@
+use async_graphql::*;
+
+use graphql_core::simple_generic_errors::CannotEditStocktake;
+use graphql_core::standard_graphql_error::{validate_auth, StandardGraphqlError};
+use graphql_core::ContextExt;
+use graphql_types::types::StocktakeLineNode;
+use repository::StocktakeLine;
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    stocktake_line::{
+        InsertStocktakeLineCount as ServiceInput, InsertStocktakeLineCountError as ServiceError,
+    },
+};
+
+#[derive(InputObject)]
+#[graphql(name = "InsertStocktakeLineCountInput")]
+pub struct InsertCountInput {
+    pub stocktake_line_id: String,
+    pub counted_number_of_packs: f64,
+}
+
+#[derive(Union)]
+#[graphql(name = "InsertStocktakeLineCountResponse")]
+pub enum InsertCountResponse {
+    Error(InsertCountError),
+    Response(StocktakeLineNode),
+}
+
+#[derive(Interface)]
+#[graphql(name = "InsertStocktakeLineCountErrorInterface")]
+#[graphql(field(name = "description", type = "String"))]
+pub enum InsertCountErrorInterface {
+    CannotEditStocktake(CannotEditStocktake),
+}
+
+#[derive(SimpleObject)]
+#[graphql(name = "InsertStocktakeLineCountError")]
+pub struct InsertCountError {
+    pub error: InsertCountErrorInterface,
+}
+
+pub fn insert_count(
+    ctx: &Context<'_>,
+    store_id: &str,
+    input: InsertCountInput,
+) -> Result<InsertCountResponse> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateStocktake,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+    map_response(
+        service_provider
+            .stocktake_line_service
+            .insert_stocktake_line_count(&service_context, input.to_domain()),
+    )
+}
+
+pub fn map_response(from: Result<StocktakeLine, ServiceError>) -> Result<InsertCountResponse> {
+    let result = match from {
+        Ok(line) => InsertCountResponse::Response(StocktakeLineNode::from_domain(line)),
+        Err(error) => InsertCountResponse::Error(InsertCountError {
+            error: map_error(error)?,
+        }),
+    };
+
+    Ok(result)
+}
+
+impl InsertCountInput {
+    pub fn to_domain(self) -> ServiceInput {
+        let InsertCountInput {
+            stocktake_line_id,
+            counted_number_of_packs,
+        } = self;
+
+        ServiceInput {
+            stocktake_line_id,
+            counted_number_of_packs,
+        }
+    }
+}
+
+fn map_error(error: ServiceError) -> Result<InsertCountErrorInterface> {
+    use StandardGraphqlError::*;
+    let formatted_error = format!("{:#?}", error);
+
+    let graphql_error = match error {
+        // Structured Errors
+        ServiceError::CannotEditFinalised => {
+            return Ok(InsertCountErrorInterface::CannotEditStocktake(
+                CannotEditStocktake {},
+            ))
+        }
+        // Standard Graphql Errors
+        ServiceError::InvalidStore => BadUserInput(formatted_error),
+        ServiceError::StocktakeLineDoesNotExist => BadUserInput(formatted_error),
+        ServiceError::StocktakeIsLocked => BadUserInput(formatted_error),
+        ServiceError::NotABlindCount => BadUserInput(formatted_error),
+        ServiceError::InvalidCount => BadUserInput(formatted_error),
+        ServiceError::DatabaseError(_) => InternalError(formatted_error),
+        ServiceError::InternalError(err) => InternalError(err),
+    };
+
+    Err(graphql_error.extend())
+}
+
+#[cfg(test)]
+mod test {
+    use async_graphql::EmptyMutation;
+    use graphql_core::{
+        assert_graphql_query, assert_standard_graphql_error, test_helpers::setup_graphl_test,
+    };
+    use repository::{
+        mock::{mock_stocktake_line_a, MockDataInserts},
+        StocktakeLine, StorageConnectionManager,
+    };
+    use serde_json::json;
+    use service::{
+        service_provider::{ServiceContext, ServiceProvider},
+        stocktake_line::*,
+    };
+
+    use crate::StocktakeLineMutations;
+
+    type ServiceMethod = dyn Fn(
+            &ServiceContext,
+            InsertStocktakeLineCount,
+        ) -> Result<StocktakeLine, InsertStocktakeLineCountError>
+        + Sync
+        + Send;
+
+    pub struct TestService(pub Box<ServiceMethod>);
+
+    impl StocktakeLineServiceTrait for TestService {
+        fn insert_stocktake_line_count(
+            &self,
+            ctx: &ServiceContext,
+            input: InsertStocktakeLineCount,
+        ) -> Result<StocktakeLine, InsertStocktakeLineCountError> {
+            (self.0)(ctx, input)
+        }
+    }
+
+    pub fn service_provider(
+        test_service: TestService,
+        connection_manager: &StorageConnectionManager,
+    ) -> ServiceProvider {
+        let mut service_provider = ServiceProvider::new(connection_manager.clone(), "app_data");
+        service_provider.stocktake_line_service = Box::new(test_service);
+        service_provider
+    }
+
+    #[actix_rt::test]
+    async fn test_graphql_stocktake_line_count_insert() {
+        let (_, _, connection_manager, settings) = setup_graphl_test(
+            EmptyMutation,
+            StocktakeLineMutations,
+            "omsupply-database-gql-stocktake_line_count_insert",
+            MockDataInserts::all(),
+        )
+        .await;
+
+        let query = r#"mutation InsertStocktakeLineCount($storeId: String, $input: InsertStocktakeLineCountInput!) {
+          insertStocktakeLineCount(storeId: $storeId, input: $input) {
+              ... on InsertStocktakeLineCountError {
+                error {
+                  __typename
+                }
+              }
+              ... on StocktakeLineNode {
+                id
+              }
+          }
+      }"#;
+
+        let variables = Some(json!({
+            "storeId": "store id",
+            "input": {
+                "stocktakeLineId": mock_stocktake_line_a().id,
+                "countedNumberOfPacks": 20
+            }
+        }));
+
+        // CannotEditStocktake
+        let test_service = TestService(Box::new(|_, _| {
+            Err(InsertStocktakeLineCountError::CannotEditFinalised)
+        }));
+        let expected = json!({
+            "insertStocktakeLineCount": {
+              "error": {
+                "__typename": "CannotEditStocktake"
+              }
+            }
+        });
+        assert_graphql_query!(
+            &settings,
+            query,
+            &variables,
+            &expected,
+            Some(service_provider(test_service, &connection_manager))
+        );
+
+        // NotABlindCount
+        let test_service = TestService(Box::new(|_, _| {
+            Err(InsertStocktakeLineCountError::NotABlindCount)
+        }));
+        let expected_message = "Bad user input";
+        assert_standard_graphql_error!(
+            &settings,
+            &query,
+            &variables,
+            &expected_message,
+            None,
+            Some(service_provider(test_service, &connection_manager))
+        );
+
+        // success
+        let test_service = TestService(Box::new(|_, input| {
+            assert_eq!(
+                input,
+                InsertStocktakeLineCount {
+                    stocktake_line_id: mock_stocktake_line_a().id,
+                    counted_number_of_packs: 20.0,
+                }
+            );
+            Ok(StocktakeLine {
+                line: mock_stocktake_line_a(),
+                stock_line: None,
+                location: None,
+            })
+        }));
+        let expected = json!({
+            "insertStocktakeLineCount": {
+              "id": mock_stocktake_line_a().id,
+            }
+        });
+        assert_graphql_query!(
+            &settings,
+            query,
+            &variables,
+            &expected,
+            Some(service_provider(test_service, &connection_manager))
+        );
+    }
+}
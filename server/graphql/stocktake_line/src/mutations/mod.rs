@@ -5,6 +5,9 @@ pub use insert::*;
 pub mod delete;
 pub use delete::*;
 
+pub mod count;
+pub use count::*;
+
 pub mod update;
 pub use update::*;
 pub struct AdjustmentReasonNotProvided;
@@ -15,11 +18,4 @@ impl AdjustmentReasonNotProvided {
         "Stocktake line has no adjustment reason"
     }
 }
-pub struct AdjustmentReasonNotValid;
-
-#[Object]
-impl AdjustmentReasonNotValid {
-    pub async fn description(&self) -> &'static str {
-        "Adjustment reason is not valid for adjustment direction"
-    }
-}
+pub use graphql_types::generic_errors::AdjustmentReasonNotValid;
@@ -7,10 +7,11 @@ mod tests {
     use graphql_invoice::InvoiceQueries;
     use graphql_location::LocationQueries;
     use graphql_requisition::RequisitionQueries;
+    use graphql_stock_line::StockLineQueries;
     use graphql_stocktake::StocktakeQueries;
     use repository::mock::{
-        mock_outbound_shipment_a, mock_request_draft_requisition_all_fields, mock_stocktake_a,
-        MockDataInserts,
+        mock_item_a, mock_outbound_shipment_a, mock_request_draft_requisition_all_fields,
+        mock_stocktake_a, MockDataInserts,
     };
     use serde_json::json;
     use service::report::{default_queries::get_default_gql_query, definition::DefaultQuery};
@@ -22,6 +23,7 @@ mod tests {
         pub StocktakeQueries,
         pub GeneralQueries,
         pub RequisitionQueries,
+        pub StockLineQueries,
     );
 
     fn full_query() -> FullQuery {
@@ -31,6 +33,7 @@ mod tests {
             StocktakeQueries,
             GeneralQueries,
             RequisitionQueries,
+            StockLineQueries,
         )
     }
 
@@ -94,5 +97,107 @@ mod tests {
             "dataId": mock_requisition.id,
         }));
         assert_graphql_query!(&settings, &query, &variables, &expected, None);
+
+        // stock by location
+        let query = get_default_gql_query(DefaultQuery::StockByLocation).query;
+        let expected = json!({
+          "store": {
+            "id": "store_a"
+          }
+        });
+        let variables = Some(json!({
+            "storeId": "store_a",
+        }));
+        assert_graphql_query!(&settings, &query, &variables, &expected, None);
+
+        // item ledger
+        let query = get_default_gql_query(DefaultQuery::ItemLedger).query;
+        let expected = json!({
+          "item": {
+            "nodes": [{
+              "id": mock_item_a().id
+            }]
+          },
+          "store": {
+            "id": "store_a"
+          }
+        });
+        let variables = Some(json!({
+            "storeId": "store_a",
+            "dataId": mock_item_a().id,
+            "toDatetime": "2022-01-01T00:00:00Z"
+        }));
+        assert_graphql_query!(&settings, &query, &variables, &expected, None);
+
+        // expiring stock
+        let query = get_default_gql_query(DefaultQuery::ExpiringStock).query;
+        let expected = json!({
+          "store": {
+            "id": "store_a"
+          }
+        });
+        let variables = Some(json!({
+            "storeId": "store_a",
+            "expiryDate": "2022-01-01"
+        }));
+        assert_graphql_query!(&settings, &query, &variables, &expected, None);
+
+        // location movement history
+        let query = get_default_gql_query(DefaultQuery::LocationMovementHistory).query;
+        let expected = json!({
+          "store": {
+            "id": "store_a"
+          }
+        });
+        let variables = Some(json!({
+            "storeId": "store_a",
+            "fromDatetime": "2021-01-01T00:00:00Z"
+        }));
+        assert_graphql_query!(&settings, &query, &variables, &expected, None);
+
+        // requisition period summary
+        let query = get_default_gql_query(DefaultQuery::RequisitionPeriodSummary).query;
+        let expected = json!({
+          "store": {
+            "id": "store_a"
+          }
+        });
+        let variables = Some(json!({
+            "storeId": "store_a",
+            "type": "REQUEST"
+        }));
+        assert_graphql_query!(&settings, &query, &variables, &expected, None);
+
+        // historical stock
+        let query = get_default_gql_query(DefaultQuery::HistoricalStock).query;
+        let expected = json!({
+          "store": {
+            "id": "store_a"
+          }
+        });
+        let variables = Some(json!({
+            "storeId": "store_a",
+            "datetime": "2022-01-01T00:00:00Z",
+            "groupBy": "location",
+            "valuationMethod": "fifo"
+        }));
+        assert_graphql_query!(&settings, &query, &variables, &expected, None);
+
+        // destruction certificate
+        let query = get_default_gql_query(DefaultQuery::DestructionCertificate).query;
+        let mock_invoice = mock_outbound_shipment_a();
+        let expected = json!({
+          "invoice": {
+            "id": mock_invoice.id
+          },
+          "store": {
+            "id": mock_invoice.store_id
+          }
+        });
+        let variables = Some(json!({
+            "storeId": mock_invoice.store_id,
+            "dataId": mock_invoice.id
+        }));
+        assert_graphql_query!(&settings, &query, &variables, &expected, None);
     }
 }
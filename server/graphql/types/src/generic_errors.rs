@@ -32,3 +32,19 @@ impl StockLineReducedBelowZero {
         &self.0
     }
 }
+
+pub struct AdjustmentReasonRequired;
+#[Object]
+impl AdjustmentReasonRequired {
+    pub async fn description(&self) -> &'static str {
+        "Adjustment reason is required"
+    }
+}
+
+pub struct AdjustmentReasonNotValid;
+#[Object]
+impl AdjustmentReasonNotValid {
+    pub async fn description(&self) -> &'static str {
+        "Adjustment reason is not valid for adjustment direction"
+    }
+}
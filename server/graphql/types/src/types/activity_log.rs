@@ -47,6 +47,13 @@ pub enum ActivityLogNodeType {
     StockBatchChange,
     StockOnHold,
     StockOffHold,
+    InventoryAdjustment,
+    Repack,
+    StockRecall,
+    LocationOnHold,
+    LocationOffHold,
+    InvoiceOnHold,
+    InvoiceOffHold,
 }
 
 #[Object]
@@ -143,6 +150,13 @@ impl ActivityLogNodeType {
             from::StockBatchChange => to::StockBatchChange,
             from::StockOnHold => to::StockOnHold,
             from::StockOffHold => to::StockOffHold,
+            from::InventoryAdjustment => to::InventoryAdjustment,
+            from::Repack => to::Repack,
+            from::StockRecall => to::StockRecall,
+            from::LocationOnHold => to::LocationOnHold,
+            from::LocationOffHold => to::LocationOffHold,
+            from::InvoiceOnHold => to::InvoiceOnHold,
+            from::InvoiceOffHold => to::InvoiceOffHold,
             from::InvoiceNumberAllocated => to::InvoiceNumberAllocated,
             from::RequisitionNumberAllocated => to::RequisitionNumberAllocated,
         }
@@ -175,6 +189,13 @@ impl ActivityLogNodeType {
             from::StockBatchChange => to::StockBatchChange,
             from::StockOnHold => to::StockOnHold,
             from::StockOffHold => to::StockOffHold,
+            from::InventoryAdjustment => to::InventoryAdjustment,
+            from::Repack => to::Repack,
+            from::StockRecall => to::StockRecall,
+            from::LocationOnHold => to::LocationOnHold,
+            from::LocationOffHold => to::LocationOffHold,
+            from::InvoiceOnHold => to::InvoiceOnHold,
+            from::InvoiceOffHold => to::InvoiceOffHold,
             from::InvoiceNumberAllocated => to::InvoiceNumberAllocated,
             from::RequisitionNumberAllocated => to::RequisitionNumberAllocated,
         }
@@ -0,0 +1,100 @@
+use super::{InvoiceNode, StockLineNode};
+use async_graphql::*;
+use service::{
+    traceability::trace::{BatchTrace, BatchTraceShipment, BatchTraceStockLine},
+    usize_to_u32,
+};
+
+#[derive(PartialEq, Debug)]
+pub struct BatchTraceStockLineNode {
+    pub line: BatchTraceStockLine,
+}
+
+#[derive(PartialEq, Debug)]
+pub struct BatchTraceShipmentNode {
+    pub shipment: BatchTraceShipment,
+}
+
+#[derive(SimpleObject)]
+pub struct BatchTraceNode {
+    /// Units still on hand in the starting and downstream stock lines
+    units_on_hand: f64,
+    total_count: u32,
+    stock_lines: Vec<BatchTraceStockLineNode>,
+    shipments: Vec<BatchTraceShipmentNode>,
+}
+
+#[Object]
+impl BatchTraceStockLineNode {
+    pub async fn stock_line(&self) -> StockLineNode {
+        StockLineNode::from_domain(self.line.stock_line.clone())
+    }
+
+    /// 0 for the stock lines the trace started from, negative for the stock lines they were
+    /// received from and positive for the stock lines they were distributed to
+    pub async fn depth(&self) -> i32 {
+        self.line.depth
+    }
+
+    /// Outbound shipment through which the stock line was received
+    pub async fn received_through_invoice_id(&self) -> &Option<String> {
+        &self.line.received_through_invoice_id
+    }
+}
+
+#[Object]
+impl BatchTraceShipmentNode {
+    pub async fn outbound_shipment(&self) -> InvoiceNode {
+        InvoiceNode::from_domain(self.shipment.outbound_shipment.clone())
+    }
+
+    /// Inbound shipment of the receiving store, not set when shipped to a customer
+    pub async fn inbound_shipment(&self) -> Option<InvoiceNode> {
+        self.shipment
+            .inbound_shipment
+            .clone()
+            .map(InvoiceNode::from_domain)
+    }
+
+    pub async fn from_stock_line_id(&self) -> &str {
+        &self.shipment.from_stock_line_id
+    }
+
+    /// Stock lines created when the inbound shipment was delivered
+    pub async fn received_stock_line_ids(&self) -> &Vec<String> {
+        &self.shipment.received_stock_line_ids
+    }
+
+    pub async fn number_of_units(&self) -> f64 {
+        self.shipment.number_of_units
+    }
+
+    /// Depth of the stock line the shipment was made from
+    pub async fn depth(&self) -> i32 {
+        self.shipment.depth
+    }
+}
+
+#[derive(Union)]
+pub enum BatchTraceResponse {
+    Response(BatchTraceNode),
+}
+
+impl BatchTraceNode {
+    pub fn from_domain(trace: BatchTrace) -> BatchTraceNode {
+        BatchTraceNode {
+            units_on_hand: trace.units_on_hand(),
+            total_count: usize_to_u32(trace.stock_lines.len()),
+            stock_lines: trace
+                .stock_lines
+                .into_iter()
+                .map(|line| BatchTraceStockLineNode { line })
+                .collect(),
+            shipments: trace
+                .shipments
+                .into_iter()
+                .map(|shipment| BatchTraceShipmentNode { shipment })
+                .collect(),
+        }
+    }
+}
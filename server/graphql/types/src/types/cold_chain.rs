@@ -0,0 +1,518 @@
+use super::{LocationNode, StockLineConnector};
+use async_graphql::*;
+use async_graphql::{dataloader::DataLoader, Context};
+use chrono::{DateTime, Utc};
+use graphql_core::generic_filters::{DatetimeFilterInput, EqualFilterStringInput};
+use graphql_core::loader::LocationByIdLoader;
+use graphql_core::standard_graphql_error::StandardGraphqlError;
+use graphql_core::{map_filter, ContextExt};
+use repository::{
+    DatetimeFilter, EqualFilter, Sensor, SensorFilter, SensorRow, SensorSort, SensorSortField,
+    TemperatureBreach, TemperatureBreachConfigRow, TemperatureBreachFilter, TemperatureBreachRow,
+    TemperatureBreachSort, TemperatureBreachSortField, TemperatureBreachType, TemperatureLog,
+    TemperatureLogFilter, TemperatureLogRow, TemperatureLogSort, TemperatureLogSortField,
+};
+use service::ListResult;
+
+// Sensor
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq)]
+#[graphql(rename_items = "camelCase")]
+pub enum SensorSortFieldInput {
+    Name,
+    Serial,
+}
+
+#[derive(InputObject)]
+pub struct SensorSortInput {
+    /// Sort query result by `key`
+    key: SensorSortFieldInput,
+    /// Sort query result is sorted descending or ascending (if not provided the default is
+    /// ascending)
+    desc: Option<bool>,
+}
+
+#[derive(InputObject, Clone)]
+pub struct SensorFilterInput {
+    pub id: Option<EqualFilterStringInput>,
+    pub location_id: Option<EqualFilterStringInput>,
+    pub serial: Option<EqualFilterStringInput>,
+    pub is_active: Option<bool>,
+}
+
+impl From<SensorFilterInput> for SensorFilter {
+    fn from(f: SensorFilterInput) -> Self {
+        SensorFilter {
+            id: f.id.map(EqualFilter::from),
+            store_id: None,
+            location_id: f.location_id.map(EqualFilter::from),
+            serial: f.serial.map(EqualFilter::from),
+            is_active: f.is_active,
+        }
+    }
+}
+
+#[derive(PartialEq, Debug)]
+pub struct SensorNode {
+    pub sensor: Sensor,
+}
+
+#[derive(SimpleObject)]
+pub struct SensorConnector {
+    total_count: u32,
+    nodes: Vec<SensorNode>,
+}
+
+#[Object]
+impl SensorNode {
+    pub async fn id(&self) -> &str {
+        &self.row().id
+    }
+
+    pub async fn name(&self) -> &str {
+        &self.row().name
+    }
+
+    pub async fn serial(&self) -> &str {
+        &self.row().serial
+    }
+
+    pub async fn location_id(&self) -> &Option<String> {
+        &self.row().location_id
+    }
+
+    pub async fn is_active(&self) -> bool {
+        self.row().is_active
+    }
+
+    pub async fn location(&self, ctx: &Context<'_>) -> Result<Option<LocationNode>> {
+        load_location(ctx, &self.row().location_id).await
+    }
+}
+
+#[derive(Union)]
+pub enum SensorsResponse {
+    Response(SensorConnector),
+}
+
+impl SensorNode {
+    pub fn from_domain(sensor: Sensor) -> SensorNode {
+        SensorNode { sensor }
+    }
+
+    pub fn row(&self) -> &SensorRow {
+        &self.sensor.sensor_row
+    }
+}
+
+impl SensorConnector {
+    pub fn from_domain(sensors: ListResult<Sensor>) -> SensorConnector {
+        SensorConnector {
+            total_count: sensors.count,
+            nodes: sensors
+                .rows
+                .into_iter()
+                .map(SensorNode::from_domain)
+                .collect(),
+        }
+    }
+}
+
+impl SensorSortInput {
+    pub fn to_domain(self) -> SensorSort {
+        use SensorSortField as to;
+        use SensorSortFieldInput as from;
+        let key = match self.key {
+            from::Name => to::Name,
+            from::Serial => to::Serial,
+        };
+
+        SensorSort {
+            key,
+            desc: self.desc,
+        }
+    }
+}
+
+// Temperature breach config
+
+#[derive(PartialEq, Debug)]
+pub struct TemperatureBreachConfigNode {
+    pub temperature_breach_config: TemperatureBreachConfigRow,
+}
+
+#[Object]
+impl TemperatureBreachConfigNode {
+    pub async fn id(&self) -> &str {
+        &self.temperature_breach_config.id
+    }
+
+    /// Null if the range applies to all locations of the store
+    pub async fn location_id(&self) -> &Option<String> {
+        &self.temperature_breach_config.location_id
+    }
+
+    pub async fn description(&self) -> &str {
+        &self.temperature_breach_config.description
+    }
+
+    pub async fn minimum_temperature(&self) -> f64 {
+        self.temperature_breach_config.minimum_temperature
+    }
+
+    pub async fn maximum_temperature(&self) -> f64 {
+        self.temperature_breach_config.maximum_temperature
+    }
+
+    /// How long the temperature has to stay out of range to be recorded as a breach
+    pub async fn duration_seconds(&self) -> i32 {
+        self.temperature_breach_config.duration_seconds
+    }
+
+    pub async fn is_active(&self) -> bool {
+        self.temperature_breach_config.is_active
+    }
+}
+
+impl TemperatureBreachConfigNode {
+    pub fn from_domain(
+        temperature_breach_config: TemperatureBreachConfigRow,
+    ) -> TemperatureBreachConfigNode {
+        TemperatureBreachConfigNode {
+            temperature_breach_config,
+        }
+    }
+}
+
+// Temperature log
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq)]
+#[graphql(rename_items = "camelCase")]
+pub enum TemperatureLogSortFieldInput {
+    Datetime,
+    Temperature,
+}
+
+#[derive(InputObject)]
+pub struct TemperatureLogSortInput {
+    /// Sort query result by `key`
+    key: TemperatureLogSortFieldInput,
+    /// Sort query result is sorted descending or ascending (if not provided the default is
+    /// ascending)
+    desc: Option<bool>,
+}
+
+#[derive(InputObject, Clone)]
+pub struct TemperatureLogFilterInput {
+    pub id: Option<EqualFilterStringInput>,
+    pub sensor_id: Option<EqualFilterStringInput>,
+    pub location_id: Option<EqualFilterStringInput>,
+    pub temperature_breach_id: Option<EqualFilterStringInput>,
+    pub datetime: Option<DatetimeFilterInput>,
+}
+
+impl From<TemperatureLogFilterInput> for TemperatureLogFilter {
+    fn from(f: TemperatureLogFilterInput) -> Self {
+        TemperatureLogFilter {
+            id: f.id.map(EqualFilter::from),
+            store_id: None,
+            sensor_id: f.sensor_id.map(EqualFilter::from),
+            location_id: f.location_id.map(EqualFilter::from),
+            temperature_breach_id: f.temperature_breach_id.map(EqualFilter::from),
+            datetime: f.datetime.map(DatetimeFilter::from),
+        }
+    }
+}
+
+#[derive(PartialEq, Debug)]
+pub struct TemperatureLogNode {
+    pub temperature_log: TemperatureLog,
+}
+
+#[derive(SimpleObject)]
+pub struct TemperatureLogConnector {
+    total_count: u32,
+    nodes: Vec<TemperatureLogNode>,
+}
+
+#[Object]
+impl TemperatureLogNode {
+    pub async fn id(&self) -> &str {
+        &self.row().id
+    }
+
+    pub async fn sensor_id(&self) -> &str {
+        &self.row().sensor_id
+    }
+
+    pub async fn location_id(&self) -> &Option<String> {
+        &self.row().location_id
+    }
+
+    pub async fn temperature(&self) -> f64 {
+        self.row().temperature
+    }
+
+    pub async fn datetime(&self) -> DateTime<Utc> {
+        DateTime::<Utc>::from_utc(self.row().datetime, Utc)
+    }
+
+    pub async fn temperature_breach_id(&self) -> &Option<String> {
+        &self.row().temperature_breach_id
+    }
+}
+
+#[derive(Union)]
+pub enum TemperatureLogsResponse {
+    Response(TemperatureLogConnector),
+}
+
+impl TemperatureLogNode {
+    pub fn from_domain(temperature_log: TemperatureLog) -> TemperatureLogNode {
+        TemperatureLogNode { temperature_log }
+    }
+
+    pub fn row(&self) -> &TemperatureLogRow {
+        &self.temperature_log.temperature_log_row
+    }
+}
+
+impl TemperatureLogConnector {
+    pub fn from_domain(temperature_logs: ListResult<TemperatureLog>) -> TemperatureLogConnector {
+        TemperatureLogConnector {
+            total_count: temperature_logs.count,
+            nodes: temperature_logs
+                .rows
+                .into_iter()
+                .map(TemperatureLogNode::from_domain)
+                .collect(),
+        }
+    }
+}
+
+impl TemperatureLogSortInput {
+    pub fn to_domain(self) -> TemperatureLogSort {
+        use TemperatureLogSortField as to;
+        use TemperatureLogSortFieldInput as from;
+        let key = match self.key {
+            from::Datetime => to::Datetime,
+            from::Temperature => to::Temperature,
+        };
+
+        TemperatureLogSort {
+            key,
+            desc: self.desc,
+        }
+    }
+}
+
+// Temperature breach
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq, Debug)]
+pub enum TemperatureBreachNodeType {
+    Cold,
+    Hot,
+}
+
+#[derive(InputObject, Clone)]
+pub struct EqualFilterTemperatureBreachTypeInput {
+    pub equal_to: Option<TemperatureBreachNodeType>,
+    pub equal_any: Option<Vec<TemperatureBreachNodeType>>,
+    pub not_equal_to: Option<TemperatureBreachNodeType>,
+}
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq)]
+#[graphql(rename_items = "camelCase")]
+pub enum TemperatureBreachSortFieldInput {
+    StartDatetime,
+    ExtremeTemperature,
+}
+
+#[derive(InputObject)]
+pub struct TemperatureBreachSortInput {
+    /// Sort query result by `key`
+    key: TemperatureBreachSortFieldInput,
+    /// Sort query result is sorted descending or ascending (if not provided the default is
+    /// ascending)
+    desc: Option<bool>,
+}
+
+#[derive(InputObject, Clone)]
+pub struct TemperatureBreachFilterInput {
+    pub id: Option<EqualFilterStringInput>,
+    pub sensor_id: Option<EqualFilterStringInput>,
+    pub location_id: Option<EqualFilterStringInput>,
+    pub r#type: Option<EqualFilterTemperatureBreachTypeInput>,
+    pub start_datetime: Option<DatetimeFilterInput>,
+    /// Set to false to only return breaches that still need to be reviewed
+    pub acknowledged: Option<bool>,
+}
+
+impl From<TemperatureBreachFilterInput> for TemperatureBreachFilter {
+    fn from(f: TemperatureBreachFilterInput) -> Self {
+        TemperatureBreachFilter {
+            id: f.id.map(EqualFilter::from),
+            store_id: None,
+            sensor_id: f.sensor_id.map(EqualFilter::from),
+            location_id: f.location_id.map(EqualFilter::from),
+            temperature_breach_config_id: None,
+            r#type: f
+                .r#type
+                .map(|t| map_filter!(t, TemperatureBreachNodeType::to_domain)),
+            start_datetime: f.start_datetime.map(DatetimeFilter::from),
+            acknowledged: f.acknowledged,
+        }
+    }
+}
+
+#[derive(PartialEq, Debug)]
+pub struct TemperatureBreachNode {
+    pub temperature_breach: TemperatureBreach,
+}
+
+#[derive(SimpleObject)]
+pub struct TemperatureBreachConnector {
+    total_count: u32,
+    nodes: Vec<TemperatureBreachNode>,
+}
+
+#[Object]
+impl TemperatureBreachNode {
+    pub async fn id(&self) -> &str {
+        &self.row().id
+    }
+
+    pub async fn sensor_id(&self) -> &str {
+        &self.row().sensor_id
+    }
+
+    pub async fn location_id(&self) -> &Option<String> {
+        &self.row().location_id
+    }
+
+    pub async fn temperature_breach_config_id(&self) -> &str {
+        &self.row().temperature_breach_config_id
+    }
+
+    pub async fn r#type(&self) -> TemperatureBreachNodeType {
+        TemperatureBreachNodeType::from_domain(&self.row().r#type)
+    }
+
+    pub async fn start_datetime(&self) -> DateTime<Utc> {
+        DateTime::<Utc>::from_utc(self.row().start_datetime, Utc)
+    }
+
+    /// Null while the temperature is still out of range
+    pub async fn end_datetime(&self) -> Option<DateTime<Utc>> {
+        self.row()
+            .end_datetime
+            .map(|v| DateTime::<Utc>::from_utc(v, Utc))
+    }
+
+    /// Highest temperature of a hot breach, lowest temperature of a cold breach
+    pub async fn extreme_temperature(&self) -> f64 {
+        self.row().extreme_temperature
+    }
+
+    pub async fn acknowledged(&self) -> bool {
+        self.row().acknowledged
+    }
+
+    pub async fn comment(&self) -> &Option<String> {
+        &self.row().comment
+    }
+
+    pub async fn location(&self, ctx: &Context<'_>) -> Result<Option<LocationNode>> {
+        load_location(ctx, &self.row().location_id).await
+    }
+
+    /// Stock that was in the location during the breach and should be reviewed
+    pub async fn stock_lines(&self, ctx: &Context<'_>) -> Result<StockLineConnector> {
+        let service_provider = ctx.service_provider();
+        let service_context = service_provider.basic_context()?;
+        let stock_lines = service_provider
+            .cold_chain_service
+            .get_temperature_breach_stock_lines(&service_context, &self.row().id)
+            .map_err(|error| {
+                StandardGraphqlError::InternalError(format!("{:#?}", error)).extend()
+            })?;
+
+        Ok(StockLineConnector::from_vec(stock_lines))
+    }
+}
+
+#[derive(Union)]
+pub enum TemperatureBreachesResponse {
+    Response(TemperatureBreachConnector),
+}
+
+impl TemperatureBreachNode {
+    pub fn from_domain(temperature_breach: TemperatureBreach) -> TemperatureBreachNode {
+        TemperatureBreachNode { temperature_breach }
+    }
+
+    pub fn row(&self) -> &TemperatureBreachRow {
+        &self.temperature_breach.temperature_breach_row
+    }
+}
+
+impl TemperatureBreachConnector {
+    pub fn from_domain(
+        temperature_breaches: ListResult<TemperatureBreach>,
+    ) -> TemperatureBreachConnector {
+        TemperatureBreachConnector {
+            total_count: temperature_breaches.count,
+            nodes: temperature_breaches
+                .rows
+                .into_iter()
+                .map(TemperatureBreachNode::from_domain)
+                .collect(),
+        }
+    }
+}
+
+impl TemperatureBreachSortInput {
+    pub fn to_domain(self) -> TemperatureBreachSort {
+        use TemperatureBreachSortField as to;
+        use TemperatureBreachSortFieldInput as from;
+        let key = match self.key {
+            from::StartDatetime => to::StartDatetime,
+            from::ExtremeTemperature => to::ExtremeTemperature,
+        };
+
+        TemperatureBreachSort {
+            key,
+            desc: self.desc,
+        }
+    }
+}
+
+impl TemperatureBreachNodeType {
+    pub fn to_domain(self) -> TemperatureBreachType {
+        match self {
+            TemperatureBreachNodeType::Cold => TemperatureBreachType::Cold,
+            TemperatureBreachNodeType::Hot => TemperatureBreachType::Hot,
+        }
+    }
+
+    pub fn from_domain(r#type: &TemperatureBreachType) -> TemperatureBreachNodeType {
+        match r#type {
+            TemperatureBreachType::Cold => TemperatureBreachNodeType::Cold,
+            TemperatureBreachType::Hot => TemperatureBreachNodeType::Hot,
+        }
+    }
+}
+
+async fn load_location(
+    ctx: &Context<'_>,
+    location_id: &Option<String>,
+) -> Result<Option<LocationNode>> {
+    let location_id = match location_id {
+        Some(location_id) => location_id,
+        None => return Ok(None),
+    };
+    let loader = ctx.get_loader::<DataLoader<LocationByIdLoader>>();
+    let result = loader.load_one(location_id.clone()).await?;
+
+    Ok(result.map(LocationNode::from_domain))
+}
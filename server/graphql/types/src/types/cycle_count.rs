@@ -0,0 +1,157 @@
+use super::ItemNode;
+use async_graphql::*;
+use async_graphql::{dataloader::DataLoader, Context};
+use chrono::NaiveDate;
+use graphql_core::loader::ItemLoader;
+use graphql_core::standard_graphql_error::StandardGraphqlError;
+use graphql_core::ContextExt;
+use repository::{AbcClass, CycleCountConfigRow, ItemAbcClassRow};
+use service::cycle_count::accuracy::CycleCountAccuracy;
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq, Debug)]
+pub enum AbcClassNodeType {
+    A,
+    B,
+    C,
+}
+
+pub struct ItemAbcClassNode {
+    pub item_abc_class: ItemAbcClassRow,
+}
+
+#[Object]
+impl ItemAbcClassNode {
+    pub async fn id(&self) -> &str {
+        &self.item_abc_class.id
+    }
+
+    pub async fn item_id(&self) -> &str {
+        &self.item_abc_class.item_id
+    }
+
+    pub async fn item(&self, ctx: &Context<'_>) -> Result<ItemNode> {
+        let loader = ctx.get_loader::<DataLoader<ItemLoader>>();
+        let item_option = loader.load_one(self.item_abc_class.item_id.clone()).await?;
+
+        item_option.map(ItemNode::from_domain).ok_or(
+            StandardGraphqlError::InternalError(format!(
+                "Cannot find item_id {} for item abc class id {}",
+                self.item_abc_class.item_id, self.item_abc_class.id
+            ))
+            .extend(),
+        )
+    }
+
+    pub async fn abc_class(&self) -> AbcClassNodeType {
+        AbcClassNodeType::from_domain(&self.item_abc_class.abc_class)
+    }
+
+    /// Consumption of the last year times the average cost per unit
+    pub async fn consumption_value(&self) -> f64 {
+        self.item_abc_class.consumption_value
+    }
+
+    /// Class was set by hand and is kept when items are reclassified
+    pub async fn is_manual(&self) -> bool {
+        self.item_abc_class.is_manual
+    }
+}
+
+pub struct CycleCountConfigNode {
+    pub cycle_count_config: CycleCountConfigRow,
+}
+
+#[Object]
+impl CycleCountConfigNode {
+    pub async fn id(&self) -> &str {
+        &self.cycle_count_config.id
+    }
+
+    pub async fn abc_class(&self) -> AbcClassNodeType {
+        AbcClassNodeType::from_domain(&self.cycle_count_config.abc_class)
+    }
+
+    /// Every item of the class is counted at least once in this many days
+    pub async fn count_frequency_days(&self) -> i32 {
+        self.cycle_count_config.count_frequency_days
+    }
+
+    pub async fn last_generated_date(&self) -> &Option<NaiveDate> {
+        &self.cycle_count_config.last_generated_date
+    }
+}
+
+pub struct CycleCountAccuracyNode {
+    pub accuracy: CycleCountAccuracy,
+}
+
+#[Object]
+impl CycleCountAccuracyNode {
+    pub async fn abc_class(&self) -> AbcClassNodeType {
+        AbcClassNodeType::from_domain(&self.accuracy.abc_class)
+    }
+
+    pub async fn number_of_items(&self) -> u32 {
+        self.accuracy.number_of_items
+    }
+
+    /// Items of the class counted in the period
+    pub async fn number_of_counted_items(&self) -> u32 {
+        self.accuracy.number_of_counted_items
+    }
+
+    pub async fn number_of_counted_lines(&self) -> u32 {
+        self.accuracy.number_of_counted_lines
+    }
+
+    /// Counted lines matching the snapshot
+    pub async fn number_of_accurate_lines(&self) -> u32 {
+        self.accuracy.number_of_accurate_lines
+    }
+
+    /// Share of accurate lines (0 to 1), null when no lines were counted
+    pub async fn accuracy(&self) -> Option<f64> {
+        self.accuracy.accuracy
+    }
+
+    /// Sum of the absolute differences between counted and snapshot packs
+    pub async fn total_absolute_variance(&self) -> f64 {
+        self.accuracy.total_absolute_variance
+    }
+}
+
+impl ItemAbcClassNode {
+    pub fn from_domain(item_abc_class: ItemAbcClassRow) -> ItemAbcClassNode {
+        ItemAbcClassNode { item_abc_class }
+    }
+}
+
+impl CycleCountConfigNode {
+    pub fn from_domain(cycle_count_config: CycleCountConfigRow) -> CycleCountConfigNode {
+        CycleCountConfigNode { cycle_count_config }
+    }
+}
+
+impl CycleCountAccuracyNode {
+    pub fn from_domain(accuracy: CycleCountAccuracy) -> CycleCountAccuracyNode {
+        CycleCountAccuracyNode { accuracy }
+    }
+}
+
+impl AbcClassNodeType {
+    pub fn to_domain(self) -> AbcClass {
+        match self {
+            AbcClassNodeType::A => AbcClass::A,
+            AbcClassNodeType::B => AbcClass::B,
+            AbcClassNodeType::C => AbcClass::C,
+        }
+    }
+
+    pub fn from_domain(abc_class: &AbcClass) -> AbcClassNodeType {
+        match abc_class {
+            AbcClass::A => AbcClassNodeType::A,
+            AbcClass::B => AbcClassNodeType::B,
+            AbcClass::C => AbcClassNodeType::C,
+        }
+    }
+}
@@ -0,0 +1,105 @@
+use async_graphql::{dataloader::DataLoader, *};
+use chrono::{DateTime, Utc};
+use graphql_core::{loader::UserLoader, ContextExt};
+use repository::{unknown_user, GoodsReceiptInspectionRow, GoodsReceiptInspectionStatus};
+
+use super::UserNode;
+
+#[derive(PartialEq, Debug)]
+pub struct GoodsReceiptInspectionNode {
+    inspection: GoodsReceiptInspectionRow,
+}
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq, Debug)]
+pub enum GoodsReceiptInspectionStatusNode {
+    Pending,
+    Passed,
+    Failed,
+}
+
+#[Object]
+impl GoodsReceiptInspectionNode {
+    pub async fn id(&self) -> &str {
+        &self.inspection.id
+    }
+
+    pub async fn invoice_line_id(&self) -> &str {
+        &self.inspection.invoice_line_id
+    }
+
+    pub async fn stock_line_id(&self) -> &str {
+        &self.inspection.stock_line_id
+    }
+
+    pub async fn status(&self) -> GoodsReceiptInspectionStatusNode {
+        GoodsReceiptInspectionStatusNode::from_domain(&self.inspection.status)
+    }
+
+    pub async fn note(&self) -> &Option<String> {
+        &self.inspection.note
+    }
+
+    pub async fn inspected_datetime(&self) -> Option<DateTime<Utc>> {
+        self.inspection
+            .inspected_datetime
+            .map(|datetime| DateTime::<Utc>::from_utc(datetime, Utc))
+    }
+
+    /// User who recorded the inspection result
+    pub async fn user(&self, ctx: &Context<'_>) -> Result<Option<UserNode>> {
+        let loader = ctx.get_loader::<DataLoader<UserLoader>>();
+
+        let user_id = match &self.inspection.user_id {
+            Some(user_id) => user_id,
+            None => return Ok(None),
+        };
+
+        let result = loader
+            .load_one(user_id.clone())
+            .await?
+            .unwrap_or(unknown_user());
+
+        Ok(Some(UserNode::from_domain(result)))
+    }
+}
+
+impl GoodsReceiptInspectionNode {
+    pub fn from_domain(inspection: GoodsReceiptInspectionRow) -> Self {
+        GoodsReceiptInspectionNode { inspection }
+    }
+}
+
+impl GoodsReceiptInspectionStatusNode {
+    pub fn from_domain(from: &GoodsReceiptInspectionStatus) -> GoodsReceiptInspectionStatusNode {
+        use GoodsReceiptInspectionStatus as from;
+        use GoodsReceiptInspectionStatusNode as to;
+
+        match from {
+            from::Pending => to::Pending,
+            from::Passed => to::Passed,
+            from::Failed => to::Failed,
+        }
+    }
+
+    /// Overall status of the inspections of a shipment, pending until every line is inspected
+    /// and failed if any line failed
+    pub fn from_inspections(
+        inspections: &[GoodsReceiptInspectionRow],
+    ) -> Option<GoodsReceiptInspectionStatusNode> {
+        let has_status = |status: GoodsReceiptInspectionStatus| {
+            inspections
+                .iter()
+                .any(|inspection| inspection.status == status)
+        };
+
+        if inspections.is_empty() {
+            None
+        } else if has_status(GoodsReceiptInspectionStatus::Pending) {
+            Some(GoodsReceiptInspectionStatusNode::Pending)
+        } else if has_status(GoodsReceiptInspectionStatus::Failed) {
+            Some(GoodsReceiptInspectionStatusNode::Failed)
+        } else {
+            Some(GoodsReceiptInspectionStatusNode::Passed)
+        }
+    }
+}
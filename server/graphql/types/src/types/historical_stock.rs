@@ -0,0 +1,148 @@
+use super::{ItemNode, LocationNode};
+use async_graphql::dataloader::DataLoader;
+use async_graphql::*;
+use chrono::NaiveDate;
+use graphql_core::{
+    generic_filters::EqualFilterStringInput,
+    loader::{ItemLoader, LocationByIdLoader},
+    standard_graphql_error::StandardGraphqlError,
+    ContextExt,
+};
+use service::{
+    historical_stock::{
+        HistoricalStock, HistoricalStockGroupBy, HistoricalStockLine, StockValuationMethod,
+    },
+    usize_to_u32,
+};
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq)]
+#[graphql(rename_items = "camelCase")]
+pub enum HistoricalStockGroupByInput {
+    Item,
+    Batch,
+    Location,
+}
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq)]
+#[graphql(rename_items = "camelCase")]
+pub enum StockValuationMethodInput {
+    /// Average unit cost of all receipts of the item up to the date
+    WeightedAverage,
+    /// Cost of the most recent receipts of each batch
+    Fifo,
+}
+
+#[derive(InputObject, Clone)]
+pub struct HistoricalStockFilterInput {
+    pub item_id: Option<EqualFilterStringInput>,
+}
+
+#[derive(PartialEq, Debug)]
+pub struct HistoricalStockNode {
+    pub line: HistoricalStockLine,
+}
+
+#[derive(SimpleObject)]
+pub struct HistoricalStockConnector {
+    total_count: u32,
+    total_value: f64,
+    nodes: Vec<HistoricalStockNode>,
+}
+
+#[Object]
+impl HistoricalStockNode {
+    pub async fn item_id(&self) -> &str {
+        &self.line.item_id
+    }
+
+    pub async fn item(&self, ctx: &Context<'_>) -> Result<ItemNode> {
+        let loader = ctx.get_loader::<DataLoader<ItemLoader>>();
+        let item_option = loader.load_one(self.line.item_id.clone()).await?;
+
+        item_option.map(ItemNode::from_domain).ok_or(
+            StandardGraphqlError::InternalError(format!(
+                "Cannot find item ({}) of historical stock",
+                &self.line.item_id
+            ))
+            .extend(),
+        )
+    }
+
+    /// Only set when grouped by batch
+    pub async fn stock_line_id(&self) -> &Option<String> {
+        &self.line.stock_line_id
+    }
+
+    pub async fn batch(&self) -> &Option<String> {
+        &self.line.batch
+    }
+
+    pub async fn expiry_date(&self) -> &Option<NaiveDate> {
+        &self.line.expiry_date
+    }
+
+    /// Location at the requested datetime, not set when grouped by item
+    pub async fn location_id(&self) -> &Option<String> {
+        &self.line.location_id
+    }
+
+    pub async fn location(&self, ctx: &Context<'_>) -> Result<Option<LocationNode>> {
+        let loader = ctx.get_loader::<DataLoader<LocationByIdLoader>>();
+
+        let location_id = match &self.line.location_id {
+            None => return Ok(None),
+            Some(location_id) => location_id,
+        };
+
+        let result = loader.load_one(location_id.clone()).await?;
+
+        Ok(result.map(LocationNode::from_domain))
+    }
+
+    /// Units on hand
+    pub async fn quantity(&self) -> f64 {
+        self.line.quantity
+    }
+
+    pub async fn value(&self) -> f64 {
+        self.line.value
+    }
+}
+
+#[derive(Union)]
+pub enum HistoricalStockResponse {
+    Response(HistoricalStockConnector),
+}
+
+impl HistoricalStockConnector {
+    pub fn from_domain(stock: HistoricalStock) -> HistoricalStockConnector {
+        HistoricalStockConnector {
+            total_count: usize_to_u32(stock.lines.len()),
+            total_value: stock.total_value,
+            nodes: stock
+                .lines
+                .into_iter()
+                .map(|line| HistoricalStockNode { line })
+                .collect(),
+        }
+    }
+}
+
+impl HistoricalStockGroupByInput {
+    pub fn to_domain(self) -> HistoricalStockGroupBy {
+        match self {
+            HistoricalStockGroupByInput::Item => HistoricalStockGroupBy::Item,
+            HistoricalStockGroupByInput::Batch => HistoricalStockGroupBy::Batch,
+            HistoricalStockGroupByInput::Location => HistoricalStockGroupBy::Location,
+        }
+    }
+}
+
+impl StockValuationMethodInput {
+    pub fn to_domain(self) -> StockValuationMethod {
+        match self {
+            StockValuationMethodInput::WeightedAverage => StockValuationMethod::WeightedAverage,
+            StockValuationMethodInput::Fifo => StockValuationMethod::Fifo,
+        }
+    }
+}
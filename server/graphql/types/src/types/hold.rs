@@ -0,0 +1,157 @@
+use async_graphql::{dataloader::DataLoader, *};
+use chrono::{DateTime, NaiveDate, Utc};
+use graphql_core::{
+    loader::{HoldByRecordIdLoader, UserLoader},
+    ContextExt,
+};
+use repository::{unknown_user, Hold, HoldReasonRow, HoldRecordType, HoldRow};
+use service::usize_to_u32;
+
+use super::UserNode;
+
+#[derive(PartialEq, Debug)]
+pub struct HoldReasonNode {
+    hold_reason: HoldReasonRow,
+}
+
+#[derive(SimpleObject)]
+pub struct HoldReasonConnector {
+    total_count: u32,
+    nodes: Vec<HoldReasonNode>,
+}
+
+#[derive(PartialEq, Debug)]
+pub struct HoldNode {
+    hold: Hold,
+}
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq, Debug)]
+pub enum HoldRecordTypeNode {
+    StockLine,
+    Location,
+    Invoice,
+}
+
+#[Object]
+impl HoldReasonNode {
+    pub async fn id(&self) -> &str {
+        &self.hold_reason.id
+    }
+
+    pub async fn reason(&self) -> &str {
+        &self.hold_reason.reason
+    }
+
+    pub async fn is_active(&self) -> bool {
+        self.hold_reason.is_active
+    }
+}
+
+#[Object]
+impl HoldNode {
+    pub async fn id(&self) -> &str {
+        &self.row().id
+    }
+
+    pub async fn record_type(&self) -> HoldRecordTypeNode {
+        HoldRecordTypeNode::from_domain(&self.row().record_type)
+    }
+
+    pub async fn record_id(&self) -> &str {
+        &self.row().record_id
+    }
+
+    pub async fn reason(&self) -> HoldReasonNode {
+        HoldReasonNode::from_domain(self.hold.hold_reason_row.clone())
+    }
+
+    /// When the record was put on hold
+    pub async fn datetime(&self) -> DateTime<Utc> {
+        DateTime::<Utc>::from_utc(self.row().datetime, Utc)
+    }
+
+    /// Date the hold is expected to be released
+    pub async fn release_date(&self) -> &Option<NaiveDate> {
+        &self.row().release_date
+    }
+
+    /// User who put the record on hold
+    pub async fn user(&self, ctx: &Context<'_>) -> Result<Option<UserNode>> {
+        let loader = ctx.get_loader::<DataLoader<UserLoader>>();
+
+        let user_id = match &self.row().user_id {
+            Some(user_id) => user_id,
+            None => return Ok(None),
+        };
+
+        let result = loader
+            .load_one(user_id.clone())
+            .await?
+            .unwrap_or(unknown_user());
+
+        Ok(Some(UserNode::from_domain(result)))
+    }
+}
+
+impl HoldReasonNode {
+    pub fn from_domain(hold_reason: HoldReasonRow) -> Self {
+        HoldReasonNode { hold_reason }
+    }
+}
+
+impl HoldReasonConnector {
+    pub fn from_vec(hold_reasons: Vec<HoldReasonRow>) -> Self {
+        HoldReasonConnector {
+            total_count: usize_to_u32(hold_reasons.len()),
+            nodes: hold_reasons
+                .into_iter()
+                .map(HoldReasonNode::from_domain)
+                .collect(),
+        }
+    }
+}
+
+impl HoldNode {
+    pub fn from_domain(hold: Hold) -> Self {
+        HoldNode { hold }
+    }
+
+    pub fn row(&self) -> &HoldRow {
+        &self.hold.hold_row
+    }
+
+    /// Hold of a record, None when the record isn't on hold
+    pub async fn load(ctx: &Context<'_>, record_id: &str, on_hold: bool) -> Result<Option<Self>> {
+        if !on_hold {
+            return Ok(None);
+        }
+        let loader = ctx.get_loader::<DataLoader<HoldByRecordIdLoader>>();
+        let result = loader.load_one(record_id.to_string()).await?;
+
+        Ok(result.map(HoldNode::from_domain))
+    }
+}
+
+impl HoldRecordTypeNode {
+    pub fn from_domain(from: &HoldRecordType) -> HoldRecordTypeNode {
+        use HoldRecordType as from;
+        use HoldRecordTypeNode as to;
+
+        match from {
+            from::StockLine => to::StockLine,
+            from::Location => to::Location,
+            from::Invoice => to::Invoice,
+        }
+    }
+
+    pub fn to_domain(self) -> HoldRecordType {
+        use HoldRecordType as to;
+        use HoldRecordTypeNode as from;
+
+        match self {
+            from::StockLine => to::StockLine,
+            from::Location => to::Location,
+            from::Invoice => to::Invoice,
+        }
+    }
+}
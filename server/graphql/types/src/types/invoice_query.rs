@@ -1,10 +1,14 @@
-use super::{InvoiceLineConnector, NameNode, RequisitionNode, StoreNode, UserNode};
+use super::{
+    GoodsReceiptInspectionNode, GoodsReceiptInspectionStatusNode, HoldNode, InvoiceLineConnector,
+    NameNode, RequisitionNode, StoreNode, UserNode,
+};
 use async_graphql::*;
 use chrono::{DateTime, Utc};
 use dataloader::DataLoader;
 
 use graphql_core::loader::{
-    InvoiceByIdLoader, InvoiceLineByInvoiceIdLoader, NameByIdLoaderInput, UserLoader,
+    GoodsReceiptInspectionByInvoiceIdLoader, InvoiceByIdLoader, InvoiceLineByInvoiceIdLoader,
+    NameByIdLoaderInput, UserLoader,
 };
 use graphql_core::{
     loader::{InvoiceStatsLoader, NameByIdLoader, RequisitionsByIdLoader, StoreByIdLoader},
@@ -145,6 +149,38 @@ impl InvoiceNode {
     pub async fn on_hold(&self) -> bool {
         self.row().on_hold
     }
+    /// Reason and details of the current hold, if the invoice is on hold
+    pub async fn hold(&self, ctx: &Context<'_>) -> Result<Option<HoldNode>> {
+        HoldNode::load(ctx, &self.row().id, self.row().on_hold).await
+    }
+
+    /// QA inspections of received inbound shipment lines
+    pub async fn goods_receipt_inspections(
+        &self,
+        ctx: &Context<'_>,
+    ) -> Result<Vec<GoodsReceiptInspectionNode>> {
+        let loader = ctx.get_loader::<DataLoader<GoodsReceiptInspectionByInvoiceIdLoader>>();
+        let result = loader.load_one(self.row().id.clone()).await?;
+
+        Ok(result
+            .unwrap_or_default()
+            .into_iter()
+            .map(GoodsReceiptInspectionNode::from_domain)
+            .collect())
+    }
+
+    /// Overall QA inspection status of received stock, null when no stock is inspected
+    pub async fn goods_receipt_inspection_status(
+        &self,
+        ctx: &Context<'_>,
+    ) -> Result<Option<GoodsReceiptInspectionStatusNode>> {
+        let loader = ctx.get_loader::<DataLoader<GoodsReceiptInspectionByInvoiceIdLoader>>();
+        let result = loader.load_one(self.row().id.clone()).await?;
+
+        Ok(GoodsReceiptInspectionStatusNode::from_inspections(
+            &result.unwrap_or_default(),
+        ))
+    }
 
     pub async fn created_datetime(&self) -> DateTime<Utc> {
         DateTime::<Utc>::from_utc(self.row().created_datetime, Utc)
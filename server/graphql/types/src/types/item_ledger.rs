@@ -0,0 +1,132 @@
+use async_graphql::*;
+use chrono::{DateTime, NaiveDate, Utc};
+use graphql_core::generic_filters::{DatetimeFilterInput, EqualFilterStringInput};
+use repository::{DatetimeFilter, EqualFilter, ItemLedgerFilter};
+use service::item_ledger::{ItemLedger, ItemLedgerLine};
+
+use super::InvoiceNodeType;
+
+#[derive(InputObject, Clone)]
+pub struct ItemLedgerFilterInput {
+    pub item_id: Option<EqualFilterStringInput>,
+    pub stock_line_id: Option<EqualFilterStringInput>,
+    pub datetime: Option<DatetimeFilterInput>,
+}
+
+impl From<ItemLedgerFilterInput> for ItemLedgerFilter {
+    fn from(f: ItemLedgerFilterInput) -> Self {
+        ItemLedgerFilter {
+            item_id: f.item_id.map(EqualFilter::from),
+            store_id: None,
+            stock_line_id: f.stock_line_id.map(EqualFilter::from),
+            invoice_type: None,
+            datetime: f.datetime.map(DatetimeFilter::from),
+        }
+    }
+}
+
+#[derive(PartialEq, Debug)]
+pub struct ItemLedgerNode {
+    pub line: ItemLedgerLine,
+}
+
+#[derive(SimpleObject)]
+pub struct ItemLedgerConnector {
+    total_count: u32,
+    /// Balance in units before the first node
+    opening_balance: f64,
+    nodes: Vec<ItemLedgerNode>,
+}
+
+#[Object]
+impl ItemLedgerNode {
+    /// Id of the invoice line
+    pub async fn id(&self) -> &str {
+        &self.line.ledger.id
+    }
+
+    pub async fn item_id(&self) -> &str {
+        &self.line.ledger.item_id
+    }
+
+    pub async fn stock_line_id(&self) -> &Option<String> {
+        &self.line.ledger.stock_line_id
+    }
+
+    pub async fn invoice_id(&self) -> &str {
+        &self.line.ledger.invoice_id
+    }
+
+    pub async fn invoice_number(&self) -> i64 {
+        self.line.ledger.invoice_number
+    }
+
+    pub async fn invoice_type(&self) -> InvoiceNodeType {
+        InvoiceNodeType::from_domain(&self.line.ledger.invoice_type)
+    }
+
+    /// Name of the other party, e.g. the supplier or customer
+    pub async fn name(&self) -> &str {
+        &self.line.ledger.name
+    }
+
+    pub async fn batch(&self) -> &Option<String> {
+        &self.line.ledger.batch
+    }
+
+    pub async fn expiry_date(&self) -> &Option<NaiveDate> {
+        &self.line.ledger.expiry_date
+    }
+
+    pub async fn pack_size(&self) -> i32 {
+        self.line.ledger.pack_size
+    }
+
+    pub async fn number_of_packs(&self) -> f64 {
+        self.line.ledger.number_of_packs
+    }
+
+    /// Quantity in units, positive for stock coming in and negative for stock going out
+    pub async fn quantity(&self) -> f64 {
+        self.line.ledger.quantity
+    }
+
+    /// Units coming in, 0 for stock going out
+    pub async fn quantity_in(&self) -> f64 {
+        self.line.ledger.quantity.max(0.0)
+    }
+
+    /// Units going out, 0 for stock coming in
+    pub async fn quantity_out(&self) -> f64 {
+        (-self.line.ledger.quantity).max(0.0)
+    }
+
+    /// Balance in units after this movement
+    pub async fn running_balance(&self) -> f64 {
+        self.line.running_balance
+    }
+
+    pub async fn datetime(&self) -> DateTime<Utc> {
+        DateTime::<Utc>::from_utc(self.line.ledger.datetime, Utc)
+    }
+}
+
+#[derive(Union)]
+pub enum ItemLedgerResponse {
+    Response(ItemLedgerConnector),
+}
+
+impl ItemLedgerConnector {
+    pub fn from_domain(ledger: ItemLedger) -> ItemLedgerConnector {
+        ItemLedgerConnector {
+            total_count: ledger.lines.count,
+            opening_balance: ledger.opening_balance,
+            nodes: ledger
+                .lines
+                .rows
+                .into_iter()
+                .map(|line| ItemLedgerNode { line })
+                .collect(),
+        }
+    }
+}
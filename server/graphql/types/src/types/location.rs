@@ -1,13 +1,18 @@
-use super::StockLineConnector;
+use super::{HoldNode, StockLineConnector};
 use async_graphql::*;
 use async_graphql::{dataloader::DataLoader, Context};
 use graphql_core::generic_filters::EqualFilterStringInput;
 use graphql_core::simple_generic_errors::NodeError;
-use graphql_core::{loader::StockLineByLocationIdLoader, ContextExt};
+use graphql_core::standard_graphql_error::StandardGraphqlError;
+use graphql_core::{
+    loader::{LocationByIdLoader, StockLineByLocationIdLoader},
+    map_filter, ContextExt,
+};
 use repository::{
     EqualFilter, Location, LocationFilter, LocationRow, LocationSort, LocationSortField,
+    LocationType,
 };
-use service::{usize_to_u32, ListResult};
+use service::{location::hierarchy::LocationStock, usize_to_u32, ListResult};
 
 #[derive(Enum, Copy, Clone, PartialEq, Eq)]
 #[graphql(rename_items = "camelCase")]
@@ -24,12 +29,29 @@ pub struct LocationSortInput {
     desc: Option<bool>,
 }
 
+#[derive(Enum, Copy, Clone, PartialEq, Eq, Debug)]
+pub enum LocationNodeType {
+    Ambient,
+    Cold,
+    Frozen,
+    Quarantine,
+}
+
+#[derive(InputObject, Clone)]
+pub struct EqualFilterLocationTypeInput {
+    pub equal_to: Option<LocationNodeType>,
+    pub equal_any: Option<Vec<LocationNodeType>>,
+    pub not_equal_to: Option<LocationNodeType>,
+}
+
 #[derive(InputObject, Clone)]
 pub struct LocationFilterInput {
     pub name: Option<EqualFilterStringInput>,
     pub code: Option<EqualFilterStringInput>,
     pub on_hold: Option<bool>,
     pub id: Option<EqualFilterStringInput>,
+    pub parent_id: Option<EqualFilterStringInput>,
+    pub location_type: Option<EqualFilterLocationTypeInput>,
 }
 
 impl From<LocationFilterInput> for LocationFilter {
@@ -40,6 +62,10 @@ impl From<LocationFilterInput> for LocationFilter {
             id: f.id.map(EqualFilter::from),
             store_id: None,
             on_hold: f.on_hold,
+            parent_id: f.parent_id.map(EqualFilter::from),
+            location_type: f
+                .location_type
+                .map(|t| map_filter!(t, LocationNodeType::to_domain)),
         }
     }
 }
@@ -72,6 +98,51 @@ impl LocationNode {
     pub async fn on_hold(&self) -> bool {
         self.row().on_hold
     }
+    /// Reason and details of the current hold, if the location is on hold
+    pub async fn hold(&self, ctx: &Context<'_>) -> Result<Option<HoldNode>> {
+        HoldNode::load(ctx, &self.row().id, self.row().on_hold).await
+    }
+
+    pub async fn parent_id(&self) -> &Option<String> {
+        &self.row().parent_id
+    }
+
+    pub async fn location_type(&self) -> LocationNodeType {
+        LocationNodeType::from_domain(&self.row().location_type)
+    }
+
+    pub async fn volume_capacity(&self) -> Option<f64> {
+        self.row().volume_capacity
+    }
+
+    pub async fn pack_capacity(&self) -> Option<f64> {
+        self.row().pack_capacity
+    }
+
+    pub async fn parent(&self, ctx: &Context<'_>) -> Result<Option<LocationNode>> {
+        let parent_id = match &self.row().parent_id {
+            Some(parent_id) => parent_id,
+            None => return Ok(None),
+        };
+        let loader = ctx.get_loader::<DataLoader<LocationByIdLoader>>();
+        let result = loader.load_one(parent_id.clone()).await?;
+
+        Ok(result.map(LocationNode::from_domain))
+    }
+
+    /// Stock of this location and all locations nested under it
+    pub async fn subtree_stock(&self, ctx: &Context<'_>) -> Result<LocationStockNode> {
+        let service_provider = ctx.service_provider();
+        let service_context = service_provider.basic_context()?;
+        let location_stock = service_provider
+            .location_service
+            .get_location_stock(&service_context, self.row().id.clone())
+            .map_err(|error| {
+                StandardGraphqlError::InternalError(format!("{:#?}", error)).extend()
+            })?;
+
+        Ok(LocationStockNode { location_stock })
+    }
 
     pub async fn stock(&self, ctx: &Context<'_>) -> Result<StockLineConnector> {
         let loader = ctx.get_loader::<DataLoader<StockLineByLocationIdLoader>>();
@@ -83,6 +154,39 @@ impl LocationNode {
     }
 }
 
+pub struct LocationStockNode {
+    pub location_stock: LocationStock,
+}
+
+#[Object]
+impl LocationStockNode {
+    /// Number of locations in the subtree, including the location itself
+    pub async fn location_count(&self) -> u32 {
+        self.location_stock.location_count
+    }
+
+    pub async fn stock_line_count(&self) -> u32 {
+        self.location_stock.stock_line_count
+    }
+
+    pub async fn total_number_of_packs(&self) -> f64 {
+        self.location_stock.total_number_of_packs
+    }
+
+    pub async fn available_number_of_packs(&self) -> f64 {
+        self.location_stock.available_number_of_packs
+    }
+
+    pub async fn total_number_of_units(&self) -> f64 {
+        self.location_stock.total_number_of_units
+    }
+
+    /// Sum of the pack capacities in the subtree
+    pub async fn pack_capacity(&self) -> Option<f64> {
+        self.location_stock.pack_capacity
+    }
+}
+
 #[derive(Union)]
 pub enum LocationsResponse {
     Response(LocationConnector),
@@ -142,3 +246,25 @@ impl LocationSortInput {
         }
     }
 }
+
+impl LocationNodeType {
+    pub fn to_domain(self) -> LocationType {
+        use LocationNodeType::*;
+        match self {
+            Ambient => LocationType::Ambient,
+            Cold => LocationType::Cold,
+            Frozen => LocationType::Frozen,
+            Quarantine => LocationType::Quarantine,
+        }
+    }
+
+    pub fn from_domain(location_type: &LocationType) -> LocationNodeType {
+        use LocationType::*;
+        match location_type {
+            Ambient => LocationNodeType::Ambient,
+            Cold => LocationNodeType::Cold,
+            Frozen => LocationNodeType::Frozen,
+            Quarantine => LocationNodeType::Quarantine,
+        }
+    }
+}
@@ -0,0 +1,154 @@
+use super::{LocationNode, StockLineNode};
+use async_graphql::*;
+use async_graphql::{dataloader::DataLoader, Context};
+use chrono::{DateTime, Utc};
+use graphql_core::generic_filters::{DatetimeFilterInput, EqualFilterStringInput};
+use graphql_core::loader::{LocationByIdLoader, StockLineByIdLoader};
+use graphql_core::ContextExt;
+use repository::{
+    DatetimeFilter, EqualFilter, LocationMovement, LocationMovementFilter, LocationMovementRow,
+    LocationMovementSort, LocationMovementSortField,
+};
+use service::ListResult;
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq)]
+#[graphql(rename_items = "camelCase")]
+pub enum LocationMovementSortFieldInput {
+    EnterDatetime,
+    ExitDatetime,
+}
+#[derive(InputObject)]
+pub struct LocationMovementSortInput {
+    /// Sort query result by `key`
+    key: LocationMovementSortFieldInput,
+    /// Sort query result is sorted descending or ascending (if not provided the default is
+    /// ascending)
+    desc: Option<bool>,
+}
+
+#[derive(InputObject, Clone)]
+pub struct LocationMovementFilterInput {
+    pub id: Option<EqualFilterStringInput>,
+    pub location_id: Option<EqualFilterStringInput>,
+    pub stock_line_id: Option<EqualFilterStringInput>,
+    pub enter_datetime: Option<DatetimeFilterInput>,
+    pub exit_datetime: Option<DatetimeFilterInput>,
+}
+
+impl From<LocationMovementFilterInput> for LocationMovementFilter {
+    fn from(f: LocationMovementFilterInput) -> Self {
+        LocationMovementFilter {
+            id: f.id.map(EqualFilter::from),
+            store_id: None,
+            location_id: f.location_id.map(EqualFilter::from),
+            stock_line_id: f.stock_line_id.map(EqualFilter::from),
+            enter_datetime: f.enter_datetime.map(DatetimeFilter::from),
+            exit_datetime: f.exit_datetime.map(DatetimeFilter::from),
+        }
+    }
+}
+
+#[derive(PartialEq, Debug)]
+pub struct LocationMovementNode {
+    pub location_movement: LocationMovement,
+}
+
+#[derive(SimpleObject)]
+pub struct LocationMovementConnector {
+    total_count: u32,
+    nodes: Vec<LocationMovementNode>,
+}
+
+#[Object]
+impl LocationMovementNode {
+    pub async fn id(&self) -> &str {
+        &self.row().id
+    }
+
+    pub async fn location_id(&self) -> &Option<String> {
+        &self.row().location_id
+    }
+
+    pub async fn stock_line_id(&self) -> &str {
+        &self.row().stock_line_id
+    }
+
+    pub async fn enter_datetime(&self) -> Option<DateTime<Utc>> {
+        self.row()
+            .enter_datetime
+            .map(|v| DateTime::<Utc>::from_utc(v, Utc))
+    }
+
+    /// Null if the stock line is still in the location
+    pub async fn exit_datetime(&self) -> Option<DateTime<Utc>> {
+        self.row()
+            .exit_datetime
+            .map(|v| DateTime::<Utc>::from_utc(v, Utc))
+    }
+
+    pub async fn location(&self, ctx: &Context<'_>) -> Result<Option<LocationNode>> {
+        let loader = ctx.get_loader::<DataLoader<LocationByIdLoader>>();
+
+        let location_id = match &self.row().location_id {
+            None => return Ok(None),
+            Some(location_id) => location_id,
+        };
+
+        let result = loader.load_one(location_id.clone()).await?;
+
+        Ok(result.map(LocationNode::from_domain))
+    }
+
+    pub async fn stock_line(&self, ctx: &Context<'_>) -> Result<Option<StockLineNode>> {
+        let loader = ctx.get_loader::<DataLoader<StockLineByIdLoader>>();
+        let result = loader.load_one(self.row().stock_line_id.clone()).await?;
+
+        Ok(result.map(StockLineNode::from_domain))
+    }
+}
+
+#[derive(Union)]
+pub enum LocationMovementsResponse {
+    Response(LocationMovementConnector),
+}
+
+impl LocationMovementNode {
+    pub fn from_domain(location_movement: LocationMovement) -> LocationMovementNode {
+        LocationMovementNode { location_movement }
+    }
+
+    pub fn row(&self) -> &LocationMovementRow {
+        &self.location_movement.location_movement_row
+    }
+}
+
+impl LocationMovementConnector {
+    pub fn from_domain(
+        location_movements: ListResult<LocationMovement>,
+    ) -> LocationMovementConnector {
+        LocationMovementConnector {
+            total_count: location_movements.count,
+            nodes: location_movements
+                .rows
+                .into_iter()
+                .map(LocationMovementNode::from_domain)
+                .collect(),
+        }
+    }
+}
+
+impl LocationMovementSortInput {
+    pub fn to_domain(self) -> LocationMovementSort {
+        use LocationMovementSortField as to;
+        use LocationMovementSortFieldInput as from;
+        let key = match self.key {
+            from::EnterDatetime => to::EnterDatetime,
+            from::ExitDatetime => to::ExitDatetime,
+        };
+
+        LocationMovementSort {
+            key,
+            desc: self.desc,
+        }
+    }
+}
@@ -18,9 +18,26 @@ pub use self::requisition_line::*;
 pub mod stock_line;
 pub use self::stock_line::*;
 
+pub mod stock_movement;
+pub use self::stock_movement::*;
+
+pub mod item_ledger;
+pub use self::item_ledger::*;
+pub mod historical_stock;
+pub use self::historical_stock::*;
+pub mod batch_trace;
+pub use self::batch_trace::*;
+pub mod hold;
+pub use self::hold::*;
+pub mod goods_receipt_inspection;
+pub use self::goods_receipt_inspection::*;
+
 pub mod location;
 pub use self::location::*;
 
+pub mod location_movement;
+pub use self::location_movement::*;
+
 pub mod master_list;
 pub use self::master_list::*;
 
@@ -66,6 +83,12 @@ pub use self::inventory_adjustment_reason::*;
 pub mod barcode;
 pub use self::barcode::*;
 
+pub mod cold_chain;
+pub use self::cold_chain::*;
+
+pub mod cycle_count;
+pub use self::cycle_count::*;
+
 pub mod store_preference;
 pub use self::store_preference::*;
 
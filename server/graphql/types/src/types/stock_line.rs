@@ -1,4 +1,4 @@
-use super::{ItemNode, LocationNode};
+use super::{HoldNode, ItemNode, LocationNode};
 use async_graphql::dataloader::DataLoader;
 use async_graphql::*;
 use chrono::NaiveDate;
@@ -58,6 +58,10 @@ impl StockLineNode {
     pub async fn on_hold(&self) -> bool {
         self.row().on_hold
     }
+    /// Reason and details of the current hold, if the stock line is on hold
+    pub async fn hold(&self, ctx: &Context<'_>) -> Result<Option<HoldNode>> {
+        HoldNode::load(ctx, &self.row().id, self.row().on_hold).await
+    }
     pub async fn note(&self) -> &Option<String> {
         &self.row().note
     }
@@ -0,0 +1,70 @@
+use async_graphql::*;
+use chrono::{DateTime, Utc};
+use graphql_core::generic_filters::{DatetimeFilterInput, EqualFilterStringInput};
+use repository::{DatetimeFilter, EqualFilter, StockMovementFilter, StockMovementRow};
+use service::ListResult;
+
+#[derive(InputObject, Clone)]
+pub struct StockMovementFilterInput {
+    pub item_id: Option<EqualFilterStringInput>,
+    pub datetime: Option<DatetimeFilterInput>,
+}
+
+impl From<StockMovementFilterInput> for StockMovementFilter {
+    fn from(f: StockMovementFilterInput) -> Self {
+        StockMovementFilter {
+            item_id: f.item_id.map(EqualFilter::from),
+            store_id: None,
+            datetime: f.datetime.map(DatetimeFilter::from),
+        }
+    }
+}
+
+#[derive(PartialEq, Debug)]
+pub struct StockMovementNode {
+    pub stock_movement: StockMovementRow,
+}
+
+#[derive(SimpleObject)]
+pub struct StockMovementConnector {
+    total_count: u32,
+    nodes: Vec<StockMovementNode>,
+}
+
+#[Object]
+impl StockMovementNode {
+    pub async fn item_id(&self) -> &str {
+        &self.stock_movement.item_id
+    }
+
+    pub async fn store_id(&self) -> &str {
+        &self.stock_movement.store_id
+    }
+
+    /// Quantity in units, positive for stock coming in and negative for stock going out
+    pub async fn quantity(&self) -> &i64 {
+        &self.stock_movement.quantity
+    }
+
+    pub async fn datetime(&self) -> DateTime<Utc> {
+        DateTime::<Utc>::from_utc(self.stock_movement.datetime, Utc)
+    }
+}
+
+#[derive(Union)]
+pub enum StockMovementsResponse {
+    Response(StockMovementConnector),
+}
+
+impl StockMovementConnector {
+    pub fn from_domain(stock_movements: ListResult<StockMovementRow>) -> StockMovementConnector {
+        StockMovementConnector {
+            total_count: stock_movements.count,
+            nodes: stock_movements
+                .rows
+                .into_iter()
+                .map(|stock_movement| StockMovementNode { stock_movement })
+                .collect(),
+        }
+    }
+}
@@ -60,6 +60,11 @@ impl StocktakeNode {
         self.stocktake.is_locked
     }
 
+    /// Counters don't see the snapshot and every line needs agreeing counts by multiple counters
+    pub async fn is_blind_count(&self) -> bool {
+        self.stocktake.is_blind_count
+    }
+
     pub async fn status(&self) -> StocktakeNodeStatus {
         StocktakeNodeStatus::from_domain(&self.stocktake.status)
     }
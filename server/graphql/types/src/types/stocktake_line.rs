@@ -1,16 +1,22 @@
 use async_graphql::*;
-use chrono::NaiveDate;
+use chrono::{DateTime, NaiveDate, Utc};
 use dataloader::DataLoader;
-use repository::{Location, StocktakeLine};
-use service::{i32_to_u32, usize_to_u32};
+use repository::{unknown_user, Location, StocktakeLine, StocktakeLineCountRow, StocktakeStatus};
+use service::{
+    i32_to_u32, stocktake::RefreshedStocktakeLine, stocktake_line::counts_need_recount,
+    usize_to_u32,
+};
 
 use graphql_core::{
-    loader::{InventoryAdjustmentReasonByIdLoader, ItemLoader, StockLineByIdLoader},
+    loader::{
+        InventoryAdjustmentReasonByIdLoader, ItemLoader, StockLineByIdLoader, StocktakeByIdLoader,
+        StocktakeLineCountByStocktakeLineIdLoader, UserLoader,
+    },
     standard_graphql_error::StandardGraphqlError,
     ContextExt,
 };
 
-use super::{InventoryAdjustmentReasonNode, ItemNode, LocationNode, StockLineNode};
+use super::{InventoryAdjustmentReasonNode, ItemNode, LocationNode, StockLineNode, UserNode};
 
 pub struct StocktakeLineNode {
     pub line: StocktakeLine,
@@ -26,7 +32,11 @@ impl StocktakeLineNode {
         &self.line.line.stocktake_id
     }
 
+    /// Hidden from the counters of a blind stocktake until it's finalised
     pub async fn stock_line(&self, ctx: &Context<'_>) -> Result<Option<StockLineNode>> {
+        if self.is_blind_count_in_progress(ctx).await? {
+            return Ok(None);
+        }
         if let Some(ref stock_line) = self.line.stock_line {
             let loader = ctx.get_loader::<DataLoader<StockLineByIdLoader>>();
             let stock_line = loader.load_one(stock_line.id.clone()).await?.ok_or(
@@ -53,14 +63,35 @@ impl StocktakeLineNode {
         self.line.line.comment.clone()
     }
 
-    pub async fn snapshot_number_of_packs(&self) -> f64 {
-        self.line.line.snapshot_number_of_packs
+    /// Hidden from the counters of a blind stocktake until it's finalised
+    pub async fn snapshot_number_of_packs(&self, ctx: &Context<'_>) -> Result<Option<f64>> {
+        if self.is_blind_count_in_progress(ctx).await? {
+            return Ok(None);
+        }
+        Ok(Some(self.line.line.snapshot_number_of_packs))
     }
 
+    /// For blind stocktakes this is the agreed count of the counters
     pub async fn counted_number_of_packs(&self) -> Option<f64> {
         self.line.line.counted_number_of_packs
     }
 
+    /// Counts of a blind stocktake line by the individual counters
+    pub async fn counts(&self, ctx: &Context<'_>) -> Result<Vec<StocktakeLineCountNode>> {
+        let is_hidden = self.is_blind_count_in_progress(ctx).await?;
+        Ok(self
+            .load_counts(ctx)
+            .await?
+            .into_iter()
+            .map(|count| StocktakeLineCountNode { count, is_hidden })
+            .collect())
+    }
+
+    /// Counts of a blind stocktake line disagree
+    pub async fn needs_recount(&self, ctx: &Context<'_>) -> Result<bool> {
+        Ok(counts_need_recount(&self.load_counts(ctx).await?))
+    }
+
     pub async fn item_id(&self) -> &str {
         &self.line.line.item_id
     }
@@ -153,4 +184,110 @@ impl StocktakeLineNode {
     pub fn from_domain(line: StocktakeLine) -> StocktakeLineNode {
         StocktakeLineNode { line }
     }
+
+    pub async fn is_blind_count_in_progress(&self, ctx: &Context<'_>) -> Result<bool> {
+        let loader = ctx.get_loader::<DataLoader<StocktakeByIdLoader>>();
+        let stocktake = loader
+            .load_one(self.line.line.stocktake_id.clone())
+            .await?
+            .ok_or(
+                StandardGraphqlError::InternalError(format!(
+                    "Cannot find stocktake {} for stocktake line id {}",
+                    self.line.line.stocktake_id, self.line.line.id
+                ))
+                .extend(),
+            )?;
+
+        Ok(stocktake.is_blind_count && stocktake.status == StocktakeStatus::New)
+    }
+
+    async fn load_counts(&self, ctx: &Context<'_>) -> Result<Vec<StocktakeLineCountRow>> {
+        let loader = ctx.get_loader::<DataLoader<StocktakeLineCountByStocktakeLineIdLoader>>();
+        Ok(loader
+            .load_one(self.line.line.id.clone())
+            .await?
+            .unwrap_or_default())
+    }
+}
+
+pub struct StocktakeLineCountNode {
+    pub count: StocktakeLineCountRow,
+    /// Counts are hidden while the blind stocktake is being counted
+    pub is_hidden: bool,
+}
+
+#[Object]
+impl StocktakeLineCountNode {
+    pub async fn id(&self) -> &str {
+        &self.count.id
+    }
+
+    pub async fn user_id(&self) -> &str {
+        &self.count.user_id
+    }
+
+    pub async fn user(&self, ctx: &Context<'_>) -> Result<UserNode> {
+        let loader = ctx.get_loader::<DataLoader<UserLoader>>();
+
+        let user = loader
+            .load_one(self.count.user_id.clone())
+            .await?
+            .unwrap_or(unknown_user());
+
+        Ok(UserNode::from_domain(user))
+    }
+
+    /// Hidden until the blind stocktake is finalised
+    pub async fn counted_number_of_packs(&self) -> Option<f64> {
+        (!self.is_hidden).then_some(self.count.counted_number_of_packs)
+    }
+
+    pub async fn datetime(&self) -> DateTime<Utc> {
+        DateTime::<Utc>::from_utc(self.count.datetime, Utc)
+    }
+}
+
+/// Stocktake line where the stock moved since the snapshot was taken
+pub struct RefreshedStocktakeLineNode {
+    pub line: StocktakeLineNode,
+    pub previous_snapshot_number_of_packs: f64,
+    pub previous_counted_number_of_packs: Option<f64>,
+}
+
+#[Object]
+impl RefreshedStocktakeLineNode {
+    pub async fn line(&self) -> &StocktakeLineNode {
+        &self.line
+    }
+
+    /// Hidden from the counters of a blind stocktake
+    pub async fn previous_snapshot_number_of_packs(
+        &self,
+        ctx: &Context<'_>,
+    ) -> Result<Option<f64>> {
+        if self.line.is_blind_count_in_progress(ctx).await? {
+            return Ok(None);
+        }
+        Ok(Some(self.previous_snapshot_number_of_packs))
+    }
+
+    pub async fn previous_counted_number_of_packs(&self) -> Option<f64> {
+        self.previous_counted_number_of_packs
+    }
+}
+
+impl RefreshedStocktakeLineNode {
+    pub fn from_domain(
+        RefreshedStocktakeLine {
+            line,
+            previous_snapshot_number_of_packs,
+            previous_counted_number_of_packs,
+        }: RefreshedStocktakeLine,
+    ) -> RefreshedStocktakeLineNode {
+        RefreshedStocktakeLineNode {
+            line: StocktakeLineNode::from_domain(line),
+            previous_snapshot_number_of_packs,
+            previous_counted_number_of_packs,
+        }
+    }
 }
@@ -26,6 +26,18 @@ impl StorePreferenceNode {
             .store_preference
             .request_requisition_requires_authorisation
     }
+    /// IANA timezone name of the store, e.g. "Pacific/Auckland"
+    pub async fn timezone(&self) -> &Option<String> {
+        &self.store_preference.timezone
+    }
+    /// ISO 4217 currency code of the store, e.g. "NZD"
+    pub async fn currency_code(&self) -> &Option<String> {
+        &self.store_preference.currency_code
+    }
+    /// Received stock is held until it passes QA inspection
+    pub async fn requires_goods_receipt_inspection(&self) -> &bool {
+        &self.store_preference.requires_goods_receipt_inspection
+    }
 }
 
 impl StorePreferenceNode {
@@ -1,7 +1,7 @@
 use anyhow::Result;
 use service::report::definition::{
     DefaultQuery, GraphQlQuery, ReportDefinition, ReportDefinitionEntry, ReportDefinitionIndex,
-    ReportOutputType, TeraTemplate,
+    ReportOutputType, ReportTranslations, SqlQuery, TeraTemplate,
 };
 use std::{
     self,
@@ -12,21 +12,65 @@ use std::{
 
 use crate::BuildArgs;
 
-fn find_project_files(dir: &Path) -> anyhow::Result<HashMap<String, PathBuf>> {
-    let mut map = HashMap::new();
+const DEFAULT_OUTPUT_PATH: &str = "./generated/output.json";
+
+/// Recursively collects all project files.
+/// Files are keyed by their path relative to the project dir, e.g. `images/logo.png`.
+fn find_project_files(
+    dir: &Path,
+    prefix: Option<&str>,
+    exclude: &Option<PathBuf>,
+    map: &mut HashMap<String, PathBuf>,
+) -> anyhow::Result<()> {
     let paths = std::fs::read_dir(dir)?;
     for path in paths {
         let entry = path?;
         let metadata = entry.metadata()?;
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        let name = match prefix {
+            Some(prefix) => format!("{}/{}", prefix, name),
+            None => name.to_string(),
+        };
+        if metadata.is_dir() {
+            find_project_files(&entry.path(), Some(&name), exclude, map)?;
+            continue;
+        }
         if !metadata.is_file() {
             continue;
         }
+        if let Some(exclude) = exclude {
+            if fs::canonicalize(entry.path())? == *exclude {
+                continue;
+            }
+        }
 
-        let name = entry.file_name();
-        let name = name.to_string_lossy();
-        map.insert(name.to_string(), entry.path());
+        map.insert(name, entry.path());
     }
-    Ok(map)
+    Ok(())
+}
+
+/// Returns the mime type of binary assets that are embedded as data URIs
+fn binary_asset_mime_type(name: &str) -> Option<&'static str> {
+    let extension = Path::new(name)
+        .extension()?
+        .to_string_lossy()
+        .to_lowercase();
+    let mime_type = match extension.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "bmp" => "image/bmp",
+        "webp" => "image/webp",
+        "ico" => "image/x-icon",
+        "svg" => "image/svg+xml",
+        "ttf" => "font/ttf",
+        "otf" => "font/otf",
+        "woff" => "font/woff",
+        "woff2" => "font/woff2",
+        _ => return None,
+    };
+    Some(mime_type)
 }
 
 fn parse_default_query(input: &str) -> anyhow::Result<DefaultQuery> {
@@ -34,6 +78,13 @@ fn parse_default_query(input: &str) -> anyhow::Result<DefaultQuery> {
         "invoice" => DefaultQuery::Invoice,
         "stocktake" => DefaultQuery::Stocktake,
         "requisition" => DefaultQuery::Requisition,
+        "stock-by-location" => DefaultQuery::StockByLocation,
+        "item-ledger" => DefaultQuery::ItemLedger,
+        "expiring-stock" => DefaultQuery::ExpiringStock,
+        "location-movement-history" => DefaultQuery::LocationMovementHistory,
+        "requisition-period-summary" => DefaultQuery::RequisitionPeriodSummary,
+        "historical-stock" => DefaultQuery::HistoricalStock,
+        "destruction-certificate" => DefaultQuery::DestructionCertificate,
         _ => {
             return Err(anyhow::Error::msg(format!(
                 "Invalid default query: {}",
@@ -44,6 +95,17 @@ fn parse_default_query(input: &str) -> anyhow::Result<DefaultQuery> {
     Ok(query)
 }
 
+/// Label printer templates (.zpl, .prn or .escpos) produce raw text, everything else HTML
+fn template_output_type(name: &str) -> ReportOutputType {
+    let extension = Path::new(name)
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase());
+    match extension.as_deref() {
+        Some("zpl") | Some("prn") | Some("escpos") => ReportOutputType::RawText,
+        _ => ReportOutputType::Html,
+    }
+}
+
 fn make_report(args: &BuildArgs, mut files: HashMap<String, PathBuf>) -> Result<ReportDefinition> {
     let mut index = ReportDefinitionIndex {
         template: Some(args.template.clone()),
@@ -62,7 +124,7 @@ fn make_report(args: &BuildArgs, mut files: HashMap<String, PathBuf>) -> Result<
     entries.insert(
         args.template.clone(),
         ReportDefinitionEntry::TeraTemplate(TeraTemplate {
-            output: ReportOutputType::Html,
+            output: template_output_type(&args.template),
             template: data,
         }),
     );
@@ -122,16 +184,59 @@ fn make_report(args: &BuildArgs, mut files: HashMap<String, PathBuf>) -> Result<
             "query_default".to_string(),
             ReportDefinitionEntry::DefaultQuery(parse_default_query(&query_default)?),
         );
+    } else if args.query_sqlite.is_some() || args.query_postgres.is_some() {
+        let mut load_sql_query = |name: &Option<String>| -> Result<Option<String>> {
+            let name = match name {
+                Some(name) => name,
+                None => return Ok(None),
+            };
+            let file_path = files
+                .remove(name)
+                .ok_or(anyhow::Error::msg("SQL query file does not exist"))?;
+            let query = fs::read_to_string(file_path).map_err(|err| {
+                anyhow::Error::msg(format!("Failed to load SQL query file: {}", err))
+            })?;
+            Ok(Some(query))
+        };
+        let sqlite = load_sql_query(&args.query_sqlite)?;
+        let postgres = load_sql_query(&args.query_postgres)?;
+        let columns = args.query_columns.as_ref().map(|columns| {
+            columns
+                .split(',')
+                .map(|column| column.trim().to_string())
+                .collect()
+        });
+        index.query = Some("query_sql".to_string());
+        entries.insert(
+            "query_sql".to_string(),
+            ReportDefinitionEntry::SqlQuery(SqlQuery {
+                sqlite,
+                postgres,
+                columns,
+                variables: None,
+            }),
+        );
     } else {
         return Err(anyhow::Error::msg(
-            "No query specified, e.g. --query-gql or --query-default",
+            "No query specified, e.g. --query-gql, --query-default or --query-sqlite",
         ));
     }
 
     // resources: try to use remaining files as resources
     for (name, path) in files {
-        if name.ends_with(".graphql") {
-            // ignore graphql files (they are included using the query argument)
+        if name.ends_with(".graphql") || name.ends_with(".sql") {
+            // ignore query files (they are included using the query arguments)
+            continue;
+        }
+        if let Some(mime_type) = binary_asset_mime_type(&name) {
+            let data = fs::read(&path).map_err(|err| {
+                anyhow::Error::msg(format!("Failed to load asset {}: {}", name, err))
+            })?;
+            let data_uri = format!("data:{};base64,{}", mime_type, base64::encode(data));
+            entries.insert(
+                name,
+                ReportDefinitionEntry::Resource(serde_json::Value::String(data_uri)),
+            );
             continue;
         }
         let data = match fs::read_to_string(&path) {
@@ -148,6 +253,32 @@ fn make_report(args: &BuildArgs, mut files: HashMap<String, PathBuf>) -> Result<
             })?;
             let name = name.strip_suffix(".ref.json").unwrap();
             (name.to_string(), ReportDefinitionEntry::Ref(data))
+        } else if name.ends_with("page-setup.json") {
+            // add page setup, e.g. page-setup.json or a5.page-setup.json
+            let data = serde_json::from_str(&data).map_err(|err| {
+                anyhow::Error::msg(format!("Failed to parse page setup {}: {}", name, err))
+            })?;
+            let name = name.strip_suffix(".json").unwrap();
+            (name.to_string(), ReportDefinitionEntry::PageSetup(data))
+        } else if name.ends_with(".translations.json") {
+            // add translations, the file name is the language code, e.g. fr.translations.json
+            let strings = serde_json::from_str(&data).map_err(|err| {
+                anyhow::Error::msg(format!("Failed to parse translations {}: {}", name, err))
+            })?;
+            let name = name.strip_suffix(".json").unwrap();
+            let language = name
+                .rsplit('/')
+                .next()
+                .unwrap()
+                .strip_suffix(".translations")
+                .unwrap();
+            (
+                name.to_string(),
+                ReportDefinitionEntry::Translations(ReportTranslations {
+                    language: language.to_string(),
+                    strings,
+                }),
+            )
         } else if name.ends_with(".json") {
             // add data as json
             let data = serde_json::from_str(&data).map_err(|err| {
@@ -168,12 +299,20 @@ fn make_report(args: &BuildArgs, mut files: HashMap<String, PathBuf>) -> Result<
     Ok(ReportDefinition { index, entries })
 }
 
-pub fn build(args: BuildArgs) -> anyhow::Result<()> {
+/// Builds the report definition from the project directory
+pub fn build_report_definition(args: &BuildArgs) -> anyhow::Result<ReportDefinition> {
     let project_dir = Path::new(&args.dir);
-    let files = find_project_files(&project_dir)?;
-    let definition = make_report(&args, files)?;
+    // don't include a previously generated output file in the report
+    let output_path = fs::canonicalize(args.output.as_deref().unwrap_or(DEFAULT_OUTPUT_PATH)).ok();
+    let mut files = HashMap::new();
+    find_project_files(&project_dir, None, &output_path, &mut files)?;
+    make_report(args, files)
+}
+
+pub fn build(args: BuildArgs) -> anyhow::Result<()> {
+    let definition = build_report_definition(&args)?;
 
-    let output_path = args.output.unwrap_or("./generated/output.json".to_string());
+    let output_path = args.output.unwrap_or(DEFAULT_OUTPUT_PATH.to_string());
     let output_path = Path::new(&output_path);
     fs::create_dir_all(
         output_path
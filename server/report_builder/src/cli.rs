@@ -1,5 +1,5 @@
 use clap::Parser;
-use report_builder::{build::build, print::print_report, Action, Args};
+use report_builder::{build::build, print::print_report, serve::serve, Action, Args};
 
 fn main() -> anyhow::Result<()> {
     let args = Args::parse();
@@ -14,8 +14,12 @@ fn main() -> anyhow::Result<()> {
                 args.output,
                 args.report,
                 args.data_id,
+                args.arguments,
             )?;
         }
+        Action::Serve(args) => {
+            serve(args)?;
+        }
     };
 
     Ok(())
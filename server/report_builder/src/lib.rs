@@ -1,5 +1,6 @@
 pub mod build;
 pub mod print;
+pub mod serve;
 
 use clap::{Parser, Subcommand};
 
@@ -14,6 +15,8 @@ pub struct Args {
 pub enum Action {
     Build(BuildArgs),
     Print(PrintArgs),
+    /// Watch the project directory and serve a live preview of the report
+    Serve(ServeArgs),
 }
 
 #[derive(clap::Args)]
@@ -35,9 +38,20 @@ pub struct BuildArgs {
     /// Name of the file containing a graphql query
     #[clap(long)]
     pub query_gql: Option<String>,
-    /// Default query type, one of: "invoice" | "stocktake" | "requisition",
+    /// Default query type, one of: "invoice" | "stocktake" | "requisition" | "stock-by-location" |
+    /// "item-ledger" | "expiring-stock" | "location-movement-history" |
+    /// "requisition-period-summary" | "historical-stock" | "destruction-certificate"
     #[clap(long)]
     pub query_default: Option<String>,
+    /// Name of the file containing a SQL query for sqlite
+    #[clap(long)]
+    pub query_sqlite: Option<String>,
+    /// Name of the file containing a SQL query for postgres
+    #[clap(long)]
+    pub query_postgres: Option<String>,
+    /// Comma separated list of the SQL query result columns (inferred from the query if not set)
+    #[clap(long)]
+    pub query_columns: Option<String>,
 }
 
 #[derive(clap::Args)]
@@ -49,7 +63,10 @@ pub struct PrintArgs {
     pub store_id: String,
     /// The data to be printed
     #[clap(long)]
-    pub data_id: String,
+    pub data_id: Option<String>,
+    /// Optional report arguments as JSON object, e.g. '{"expiryDate": "2022-12-31"}'
+    #[clap(long)]
+    pub arguments: Option<String>,
     /// The output file path
     #[clap(long)]
     pub output: Option<String>,
@@ -61,3 +78,48 @@ pub struct PrintArgs {
     #[clap(long)]
     pub config: String,
 }
+
+#[derive(clap::Args)]
+pub struct ServeArgs {
+    #[clap(flatten)]
+    pub build: BuildArgs,
+    #[clap(long)]
+    pub store_id: String,
+    /// The data to be previewed
+    #[clap(long)]
+    pub data_id: Option<String>,
+    /// Optional report arguments as JSON object, e.g. '{"expiryDate": "2022-12-31"}'
+    #[clap(long)]
+    pub arguments: Option<String>,
+    /// The YAML config data to connected to the remote server (same as for the print command)
+    #[clap(long)]
+    pub config: String,
+    /// Local port the preview is served on
+    #[clap(long, default_value = "3030")]
+    pub port: u16,
+    /// Language code used for translations and formatting, e.g. "fr"
+    #[clap(long, default_value = "en")]
+    pub language: String,
+    /// IANA timezone name of the store, e.g. "Pacific/Auckland" (UTC if not specified)
+    #[clap(long)]
+    pub timezone: Option<String>,
+    /// ISO 4217 currency code of the store, e.g. "NZD"
+    #[clap(long)]
+    pub currency_code: Option<String>,
+}
+
+/// Parses the report arguments passed on the command line
+pub(crate) fn parse_arguments(
+    arguments: &Option<String>,
+) -> anyhow::Result<Option<serde_json::Value>> {
+    let arguments = match arguments {
+        Some(arguments) => arguments,
+        None => return Ok(None),
+    };
+    let value: serde_json::Value = serde_json::from_str(arguments)
+        .map_err(|err| anyhow::Error::msg(format!("Failed to parse report arguments: {}", err)))?;
+    if !value.is_object() {
+        return Err(anyhow::Error::msg("Report arguments must be a JSON object"));
+    }
+    Ok(Some(value))
+}
@@ -4,6 +4,8 @@ use serde::{Deserialize, Serialize};
 
 use std::{fs, path::Path};
 
+use crate::parse_arguments;
+
 const AUTH_QUERY: &str = r#"
 query AuthToken($username: String!, $password: String) {
   authToken(password: $password, username: $username) {
@@ -22,8 +24,8 @@ query AuthToken($username: String!, $password: String) {
 "#;
 
 const PRINT_QUERY: &str = r#"
-query PrintReportDefinition($storeId: String!, $name: String, $report: JSON!, $dataId: String!) {
-  printReportDefinition(dataId: $dataId, name: $name, report: $report, storeId: $storeId) {
+query PrintReportDefinition($storeId: String!, $name: String, $report: JSON!, $dataId: String, $arguments: JSON) {
+  printReportDefinition(dataId: $dataId, name: $name, report: $report, storeId: $storeId, arguments: $arguments) {
     ... on PrintReportNode {
       __typename
       fileId
@@ -44,20 +46,28 @@ query PrintReportDefinition($storeId: String!, $name: String, $report: JSON!, $d
 "#;
 
 #[derive(Debug, Serialize, Deserialize)]
-struct Config {
-    url: String,
+pub(crate) struct Config {
+    pub url: String,
     username: String,
     password: String,
 }
 
 #[allow(dead_code)]
 #[derive(Debug, Deserialize)]
-struct GraphQlResponse {
-    data: serde_json::Value,
-    errors: Option<serde_json::Value>,
+pub(crate) struct GraphQlResponse {
+    pub data: serde_json::Value,
+    pub errors: Option<serde_json::Value>,
 }
 
-fn token_request(url: Url, config: &Config) -> anyhow::Result<String> {
+pub(crate) fn load_config(config_path: &str) -> anyhow::Result<Config> {
+    let config_data = fs::read_to_string(config_path)
+        .map_err(|err| anyhow::Error::msg(format!("Failed to load config file: {}", err)))?;
+    let config: Config = serde_yaml::from_str(&config_data)
+        .map_err(|err| anyhow::Error::msg(format!("Failed to parse config file: {}", err)))?;
+    Ok(config)
+}
+
+pub(crate) fn token_request(url: Url, config: &Config) -> anyhow::Result<String> {
     let body = serde_json::json!({
       "query": AUTH_QUERY,
       "variables": {
@@ -86,7 +96,8 @@ fn print_request(
     store_id: &str,
     name: &Option<String>,
     report: serde_json::Value,
-    data_id: &str,
+    data_id: &Option<String>,
+    arguments: &Option<serde_json::Value>,
 ) -> anyhow::Result<String> {
     let body = serde_json::json!({
       "query": PRINT_QUERY,
@@ -94,7 +105,8 @@ fn print_request(
         "storeId": store_id,
         "dataId": data_id,
         "name": name,
-        "report": report
+        "report": report,
+        "arguments": arguments
       }
     });
     let response = reqwest::blocking::Client::new()
@@ -165,8 +177,10 @@ pub fn print_report(
     store_id: String,
     output_filename: Option<String>,
     report_file: String,
-    data_id: String,
+    data_id: Option<String>,
+    arguments: Option<String>,
 ) -> anyhow::Result<()> {
+    let arguments = parse_arguments(&arguments)?;
     println!("> Load report data from: {}", report_file);
     let report_data = fs::read_to_string(report_file).map_err(|err| {
         anyhow::Error::msg(format!("Failed to load report definition file: {}", err))
@@ -176,10 +190,7 @@ pub fn print_report(
     })?;
 
     println!("> Load remote server config from: {}", config_path);
-    let config_data = fs::read_to_string(config_path)
-        .map_err(|err| anyhow::Error::msg(format!("Failed to load config file: {}", err)))?;
-    let config: Config = serde_yaml::from_str(&config_data)
-        .map_err(|err| anyhow::Error::msg(format!("Failed to parse config file: {}", err)))?;
+    let config = load_config(&config_path)?;
 
     let base_url = Url::parse(&config.url)
         .map_err(|err| anyhow::Error::msg(format!("Invalid base url: {}", err)))?;
@@ -211,6 +222,7 @@ pub fn print_report(
         &file_name,
         report,
         &data_id,
+        &arguments,
     )
     .map_err(|err| anyhow::Error::msg(format!("Failed to fetch report data: {}", err)))?;
 
@@ -0,0 +1,346 @@
+use notify::{DebouncedEvent, RecursiveMode, Watcher};
+use regex::Regex;
+use reqwest::Url;
+use service::report::{
+    definition::{ReportDefinitionEntry, ReportOutputType},
+    locale::ReportLocale,
+    report_service::{
+        format_html_document, generate_report, resolve_loaded_report_definition, ReportError,
+        ReportQuery,
+    },
+};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::{mpsc::channel, Arc, Mutex},
+    thread,
+    time::Duration,
+};
+use tiny_http::{Header, Response, Server};
+
+use crate::{
+    build::build_report_definition,
+    parse_arguments,
+    print::{load_config, token_request, GraphQlResponse},
+    BuildArgs, ServeArgs,
+};
+
+/// Polls the preview version and reloads the page when the report has been rebuilt
+const RELOAD_SCRIPT: &str = r#"<script>
+  setInterval(function () {
+    fetch("/version")
+      .then(function (response) { return response.text(); })
+      .then(function (version) {
+        if (version !== "{version}") window.location.reload();
+      })
+      .catch(function () {});
+  }, 1000);
+</script>"#;
+
+/// Number of template lines shown before and after the line of a template error
+const ERROR_CONTEXT_LINES: usize = 3;
+
+struct Preview {
+    /// Incremented every time the report is rebuilt
+    version: u64,
+    html: String,
+}
+
+struct PreviewSource {
+    build_args: BuildArgs,
+    gql_url: Url,
+    token: String,
+    store_id: String,
+    data_id: Option<String>,
+    arguments: Option<serde_json::Value>,
+    locale: ReportLocale,
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn inject_reload_script(html: &str, version: u64) -> String {
+    let script = RELOAD_SCRIPT.replace("{version}", &version.to_string());
+    match html.rfind("</body>") {
+        Some(pos) => format!("{}{}{}", &html[..pos], script, &html[pos..]),
+        None => format!("{}{}", html, script),
+    }
+}
+
+/// Tries to find the template file and line a Tera error message is referring to.
+///
+/// Syntax errors contain the exact position, e.g. "--> 3:5". Render errors only name the template
+/// and the failing variable, in this case the first line containing the variable is used.
+fn find_error_location(project_dir: &Path, message: &str) -> Option<(String, usize)> {
+    let template_re = Regex::new(r"'([^']+)'").unwrap();
+    let (template_name, template) = template_re
+        .captures_iter(message)
+        .filter_map(|capture| {
+            let name = capture.get(1)?.as_str().to_string();
+            let template = fs::read_to_string(project_dir.join(&name)).ok()?;
+            Some((name, template))
+        })
+        .last()?;
+
+    let position_re = Regex::new(r"--> (\d+):(\d+)").unwrap();
+    if let Some(capture) = position_re.captures(message) {
+        let line = capture.get(1)?.as_str().parse::<usize>().ok()?;
+        return Some((template_name, line));
+    }
+
+    let variable_re = Regex::new(r"Variable `([^`]+)` not found").unwrap();
+    let variable = variable_re.captures(message)?.get(1)?.as_str().to_string();
+    let line = template
+        .lines()
+        .position(|line| line.contains(&variable))
+        .map(|index| index + 1)?;
+    Some((template_name, line))
+}
+
+fn error_excerpt(project_dir: &Path, template_name: &str, line: usize) -> String {
+    let template = match fs::read_to_string(project_dir.join(template_name)) {
+        Ok(template) => template,
+        Err(_) => return "".to_string(),
+    };
+    let first_line = line.saturating_sub(ERROR_CONTEXT_LINES).max(1);
+    template
+        .lines()
+        .enumerate()
+        .map(|(index, text)| (index + 1, text))
+        .filter(|(number, _)| *number >= first_line && *number <= line + ERROR_CONTEXT_LINES)
+        .map(|(number, text)| {
+            let text = format!("{:>4} | {}", number, escape_html(text));
+            if number == line {
+                format!("<mark>{}</mark>", text)
+            } else {
+                text
+            }
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+fn error_page(project_dir: &Path, title: &str, message: &str) -> String {
+    let location = match find_error_location(project_dir, message) {
+        Some((template_name, line)) => format!(
+            "<h3>{} (line {})</h3><pre>{}</pre>",
+            escape_html(&template_name),
+            line,
+            error_excerpt(project_dir, &template_name, line)
+        ),
+        None => "".to_string(),
+    };
+    format!(
+        "<html><body><h2>{}</h2>{}<pre>{}</pre></body></html>",
+        escape_html(title),
+        location,
+        escape_html(message)
+    )
+}
+
+fn fetch_report_data(
+    source: &PreviewSource,
+    query: &str,
+    variables: serde_json::Value,
+) -> anyhow::Result<serde_json::Value> {
+    let body = serde_json::json!({
+      "query": query,
+      "variables": variables,
+    });
+    let response: GraphQlResponse = reqwest::blocking::Client::new()
+        .post(source.gql_url.clone())
+        .bearer_auth(&source.token)
+        .json(&body)
+        .send()?
+        .json()?;
+    if let Some(errors) = response.errors {
+        return Err(anyhow::Error::msg(serde_json::to_string_pretty(&errors)?));
+    }
+    Ok(response.data)
+}
+
+/// Rebuilds the report definition and renders it, returns an error page if anything fails
+fn render_preview(source: &PreviewSource) -> String {
+    let project_dir = Path::new(&source.build_args.dir);
+    let definition = match build_report_definition(&source.build_args) {
+        Ok(definition) => definition,
+        Err(err) => return error_page(project_dir, "Failed to build report", &format!("{}", err)),
+    };
+    if definition
+        .entries
+        .values()
+        .any(|entry| matches!(entry, ReportDefinitionEntry::Ref(_)))
+    {
+        return error_page(
+            project_dir,
+            "Failed to build report",
+            "References to other report definitions (*.ref.json) are not supported in the preview",
+        );
+    }
+    if let Some(output) = &source.build_args.output {
+        if let Err(err) = serde_json::to_string_pretty(&definition)
+            .map_err(anyhow::Error::from)
+            .and_then(|data| fs::write(output, data).map_err(anyhow::Error::from))
+        {
+            log::warn!("Failed to write to {}: {}", output, err);
+        }
+    }
+
+    let report = match resolve_loaded_report_definition(
+        "preview".to_string(),
+        definition,
+        source.locale.clone(),
+    ) {
+        Ok(report) => report,
+        Err(err) => {
+            return error_page(
+                project_dir,
+                "Invalid report definition",
+                &format!("{:#?}", err),
+            )
+        }
+    };
+    let query = match &report.query {
+        ReportQuery::GraphQlQuery(query) => query,
+        ReportQuery::SqlQuery(_) => {
+            return error_page(
+                project_dir,
+                "Failed to fetch report data",
+                "SQL queries can't be previewed, use the print command instead",
+            )
+        }
+    };
+    let variables = query.query_variables(
+        &source.store_id,
+        source.data_id.as_deref(),
+        source.arguments.as_ref(),
+    );
+    let report_data = match fetch_report_data(source, &query.query, variables.clone()) {
+        Ok(report_data) => report_data,
+        Err(err) => {
+            return error_page(
+                project_dir,
+                "Failed to fetch report data",
+                &format!("{}", err),
+            )
+        }
+    };
+
+    match generate_report(&report, report_data, Some(variables)) {
+        Ok(document) if report.output_type() == ReportOutputType::RawText => format!(
+            "<html><body><pre>{}</pre></body></html>",
+            escape_html(&document.document)
+        ),
+        Ok(document) => format_html_document(document, report.page_setup.as_ref()),
+        Err(ReportError::DocGenerationError(message)) => {
+            error_page(project_dir, "Failed to render report", &message)
+        }
+        Err(err) => error_page(
+            project_dir,
+            "Failed to render report",
+            &format!("{:#?}", err),
+        ),
+    }
+}
+
+fn event_path(event: &DebouncedEvent) -> Option<&PathBuf> {
+    match event {
+        DebouncedEvent::Create(path)
+        | DebouncedEvent::Write(path)
+        | DebouncedEvent::Remove(path)
+        | DebouncedEvent::Rename(_, path) => Some(path),
+        _ => None,
+    }
+}
+
+pub fn serve(args: ServeArgs) -> anyhow::Result<()> {
+    let arguments = parse_arguments(&args.arguments)?;
+    println!("> Load remote server config from: {}", args.config);
+    let config = load_config(&args.config)?;
+    let base_url = Url::parse(&config.url)
+        .map_err(|err| anyhow::Error::msg(format!("Invalid base url: {}", err)))?;
+    let gql_url = base_url.join("graphql")?;
+
+    println!("> Authenticate with remote server");
+    let token = token_request(gql_url.clone(), &config).map_err(|err| {
+        anyhow::Error::msg(format!(
+            "Failed to authenticate with remote server: {}",
+            err
+        ))
+    })?;
+
+    let project_dir = fs::canonicalize(&args.build.dir)?;
+    // writing the output definition must not trigger another rebuild
+    let current_dir = std::env::current_dir()?;
+    let output_path = args
+        .build
+        .output
+        .as_ref()
+        .map(|output| current_dir.join(output));
+    let source = PreviewSource {
+        build_args: args.build,
+        gql_url,
+        token,
+        store_id: args.store_id,
+        data_id: args.data_id,
+        arguments,
+        locale: ReportLocale {
+            language: args.language,
+            timezone: args.timezone,
+            currency_code: args.currency_code,
+        },
+    };
+
+    let preview = Arc::new(Mutex::new(Preview {
+        version: 0,
+        html: render_preview(&source),
+    }));
+
+    let (tx, rx) = channel();
+    let mut watcher = notify::watcher(tx, Duration::from_millis(200))?;
+    watcher.watch(&project_dir, RecursiveMode::Recursive)?;
+    let watched_preview = preview.clone();
+    thread::spawn(move || {
+        // keep the watcher alive for as long as events are received
+        let _watcher = watcher;
+        for event in rx {
+            let path = match event_path(&event) {
+                Some(path) => path,
+                None => continue,
+            };
+            if Some(path) == output_path.as_ref() {
+                continue;
+            }
+            println!("> {:?} changed, rebuild report", path);
+            let html = render_preview(&source);
+            let mut preview = watched_preview.lock().unwrap();
+            preview.version += 1;
+            preview.html = html;
+        }
+    });
+
+    let server = Server::http(("127.0.0.1", args.port))
+        .map_err(|err| anyhow::Error::msg(format!("Failed to start preview server: {}", err)))?;
+    println!("> Serve report preview on: http://127.0.0.1:{}", args.port);
+    let html_header = Header::from_bytes(&b"Content-Type"[..], &b"text/html; charset=utf-8"[..])
+        .map_err(|_| anyhow::Error::msg("Invalid header"))?;
+    for request in server.incoming_requests() {
+        let response = {
+            let preview = preview.lock().unwrap();
+            match request.url() {
+                "/version" => Response::from_string(preview.version.to_string()),
+                _ => Response::from_string(inject_reload_script(&preview.html, preview.version))
+                    .with_header(html_header.clone()),
+            }
+        };
+        if let Err(err) = request.respond(response) {
+            log::warn!("Failed to respond to preview request: {}", err);
+        }
+    }
+
+    Ok(())
+}
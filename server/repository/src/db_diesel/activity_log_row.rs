@@ -49,6 +49,13 @@ pub enum ActivityLogType {
     StockBatchChange,
     StockOnHold,
     StockOffHold,
+    InventoryAdjustment,
+    Repack,
+    StockRecall,
+    LocationOnHold,
+    LocationOffHold,
+    InvoiceOnHold,
+    InvoiceOffHold,
 }
 
 #[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq)]
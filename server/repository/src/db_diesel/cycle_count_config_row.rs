@@ -0,0 +1,88 @@
+use super::{
+    cycle_count_config_row::cycle_count_config::dsl as cycle_count_config_dsl, store_row::store,
+    AbcClass, StorageConnection,
+};
+
+use crate::repository_error::RepositoryError;
+
+use chrono::NaiveDate;
+use diesel::prelude::*;
+
+table! {
+    cycle_count_config (id) {
+        id -> Text,
+        store_id -> Text,
+        abc_class -> crate::db_diesel::item_abc_class_row::AbcClassMapping,
+        count_frequency_days -> Integer,
+        last_generated_date -> Nullable<Date>,
+    }
+}
+
+joinable!(cycle_count_config -> store (store_id));
+
+/// How often items of an ABC class are counted in a store
+#[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Default)]
+#[changeset_options(treat_none_as_null = "true")]
+#[table_name = "cycle_count_config"]
+pub struct CycleCountConfigRow {
+    pub id: String,
+    pub store_id: String,
+    pub abc_class: AbcClass,
+    /// Every item of the class is counted at least once in this many days
+    pub count_frequency_days: i32,
+    /// Date cycle count stocktakes were last generated for the class
+    pub last_generated_date: Option<NaiveDate>,
+}
+
+pub struct CycleCountConfigRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> CycleCountConfigRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        CycleCountConfigRowRepository { connection }
+    }
+
+    #[cfg(feature = "postgres")]
+    pub fn upsert_one(&self, row: &CycleCountConfigRow) -> Result<(), RepositoryError> {
+        diesel::insert_into(cycle_count_config_dsl::cycle_count_config)
+            .values(row)
+            .on_conflict(cycle_count_config_dsl::id)
+            .do_update()
+            .set(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    #[cfg(not(feature = "postgres"))]
+    pub fn upsert_one(&self, row: &CycleCountConfigRow) -> Result<(), RepositoryError> {
+        diesel::replace_into(cycle_count_config_dsl::cycle_count_config)
+            .values(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    pub fn find_one_by_store_and_class(
+        &self,
+        store_id: &str,
+        abc_class: &AbcClass,
+    ) -> Result<Option<CycleCountConfigRow>, RepositoryError> {
+        let result = cycle_count_config_dsl::cycle_count_config
+            .filter(cycle_count_config_dsl::store_id.eq(store_id))
+            .filter(cycle_count_config_dsl::abc_class.eq(abc_class))
+            .first(&self.connection.connection)
+            .optional()?;
+        Ok(result)
+    }
+
+    pub fn find_many_by_store_id(
+        &self,
+        store_id: &str,
+    ) -> Result<Vec<CycleCountConfigRow>, RepositoryError> {
+        let result = cycle_count_config_dsl::cycle_count_config
+            .filter(cycle_count_config_dsl::store_id.eq(store_id))
+            .order(cycle_count_config_dsl::abc_class.asc())
+            .load(&self.connection.connection)?;
+        Ok(result)
+    }
+}
@@ -0,0 +1,119 @@
+use super::{
+    goods_receipt_inspection_row::goods_receipt_inspection::dsl as goods_receipt_inspection_dsl,
+    StorageConnection,
+};
+
+use crate::repository_error::RepositoryError;
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use diesel_derive_enum::DbEnum;
+use serde::{Deserialize, Serialize};
+
+table! {
+    goods_receipt_inspection (id) {
+        id -> Text,
+        invoice_id -> Text,
+        invoice_line_id -> Text,
+        stock_line_id -> Text,
+        status -> crate::db_diesel::goods_receipt_inspection_row::GoodsReceiptInspectionStatusMapping,
+        note -> Nullable<Text>,
+        user_id -> Nullable<Text>,
+        inspected_datetime -> Nullable<Timestamp>,
+    }
+}
+
+#[derive(DbEnum, Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[DbValueStyle = "SCREAMING_SNAKE_CASE"]
+pub enum GoodsReceiptInspectionStatus {
+    #[default]
+    Pending,
+    Passed,
+    Failed,
+}
+
+/// QA inspection of a received inbound shipment line, stock of a pending inspection is on hold
+#[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Default)]
+#[changeset_options(treat_none_as_null = "true")]
+#[table_name = "goods_receipt_inspection"]
+pub struct GoodsReceiptInspectionRow {
+    pub id: String,
+    pub invoice_id: String,
+    pub invoice_line_id: String,
+    pub stock_line_id: String,
+    pub status: GoodsReceiptInspectionStatus,
+    pub note: Option<String>,
+    /// User who recorded the inspection result
+    pub user_id: Option<String>,
+    pub inspected_datetime: Option<NaiveDateTime>,
+}
+
+pub struct GoodsReceiptInspectionRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> GoodsReceiptInspectionRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        GoodsReceiptInspectionRowRepository { connection }
+    }
+
+    #[cfg(feature = "postgres")]
+    pub fn upsert_one(&self, row: &GoodsReceiptInspectionRow) -> Result<(), RepositoryError> {
+        diesel::insert_into(goods_receipt_inspection_dsl::goods_receipt_inspection)
+            .values(row)
+            .on_conflict(goods_receipt_inspection_dsl::id)
+            .do_update()
+            .set(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    #[cfg(not(feature = "postgres"))]
+    pub fn upsert_one(&self, row: &GoodsReceiptInspectionRow) -> Result<(), RepositoryError> {
+        diesel::replace_into(goods_receipt_inspection_dsl::goods_receipt_inspection)
+            .values(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    pub fn find_one_by_invoice_line_id(
+        &self,
+        invoice_line_id: &str,
+    ) -> Result<Option<GoodsReceiptInspectionRow>, RepositoryError> {
+        let result = goods_receipt_inspection_dsl::goods_receipt_inspection
+            .filter(goods_receipt_inspection_dsl::invoice_line_id.eq(invoice_line_id))
+            .first(&self.connection.connection)
+            .optional()?;
+        Ok(result)
+    }
+
+    pub fn find_pending_by_stock_line_id(
+        &self,
+        stock_line_id: &str,
+    ) -> Result<Option<GoodsReceiptInspectionRow>, RepositoryError> {
+        let result = goods_receipt_inspection_dsl::goods_receipt_inspection
+            .filter(goods_receipt_inspection_dsl::stock_line_id.eq(stock_line_id))
+            .filter(goods_receipt_inspection_dsl::status.eq(GoodsReceiptInspectionStatus::Pending))
+            .first(&self.connection.connection)
+            .optional()?;
+        Ok(result)
+    }
+
+    pub fn find_many_by_invoice_ids(
+        &self,
+        invoice_ids: &[String],
+    ) -> Result<Vec<GoodsReceiptInspectionRow>, RepositoryError> {
+        let result = goods_receipt_inspection_dsl::goods_receipt_inspection
+            .filter(goods_receipt_inspection_dsl::invoice_id.eq_any(invoice_ids))
+            .order(goods_receipt_inspection_dsl::id.asc())
+            .load(&self.connection.connection)?;
+        Ok(result)
+    }
+
+    pub fn delete_by_invoice_line_id(&self, invoice_line_id: &str) -> Result<(), RepositoryError> {
+        diesel::delete(goods_receipt_inspection_dsl::goods_receipt_inspection)
+            .filter(goods_receipt_inspection_dsl::invoice_line_id.eq(invoice_line_id))
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,108 @@
+use super::{
+    hold_reason_row::{hold_reason, hold_reason::dsl as hold_reason_dsl},
+    hold_row::{hold, hold::dsl as hold_dsl},
+    DBType, HoldReasonRow, HoldRecordType, HoldRow, StorageConnection,
+};
+
+use crate::{diesel_macros::apply_equal_filter, repository_error::RepositoryError, EqualFilter};
+
+use diesel::{
+    dsl::{InnerJoin, IntoBoxed},
+    prelude::*,
+};
+use util::inline_init;
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Hold {
+    pub hold_row: HoldRow,
+    pub hold_reason_row: HoldReasonRow,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct HoldFilter {
+    pub record_type: Option<EqualFilter<HoldRecordType>>,
+    pub record_id: Option<EqualFilter<String>>,
+    pub hold_reason_id: Option<EqualFilter<String>>,
+    pub store_id: Option<EqualFilter<String>>,
+}
+
+type HoldJoin = (HoldRow, HoldReasonRow);
+
+type BoxedHoldQuery = IntoBoxed<'static, InnerJoin<hold::table, hold_reason::table>, DBType>;
+
+pub struct HoldRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> HoldRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        HoldRepository { connection }
+    }
+
+    pub fn query_by_filter(&self, filter: HoldFilter) -> Result<Vec<Hold>, RepositoryError> {
+        let result = create_filtered_query(filter)
+            .order(hold_dsl::datetime.asc())
+            .load::<HoldJoin>(&self.connection.connection)?;
+
+        Ok(result.into_iter().map(to_domain).collect())
+    }
+}
+
+fn create_filtered_query(filter: HoldFilter) -> BoxedHoldQuery {
+    let mut query = hold_dsl::hold
+        .inner_join(hold_reason_dsl::hold_reason)
+        .into_boxed();
+
+    let HoldFilter {
+        record_type,
+        record_id,
+        hold_reason_id,
+        store_id,
+    } = filter;
+
+    apply_equal_filter!(query, record_type, hold_dsl::record_type);
+    apply_equal_filter!(query, record_id, hold_dsl::record_id);
+    apply_equal_filter!(query, hold_reason_id, hold_dsl::hold_reason_id);
+    apply_equal_filter!(query, store_id, hold_dsl::store_id);
+
+    query
+}
+
+fn to_domain((hold_row, hold_reason_row): HoldJoin) -> Hold {
+    Hold {
+        hold_row,
+        hold_reason_row,
+    }
+}
+
+impl HoldFilter {
+    pub fn new() -> HoldFilter {
+        Self::default()
+    }
+
+    pub fn record_type(mut self, filter: EqualFilter<HoldRecordType>) -> Self {
+        self.record_type = Some(filter);
+        self
+    }
+
+    pub fn record_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.record_id = Some(filter);
+        self
+    }
+
+    pub fn hold_reason_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.hold_reason_id = Some(filter);
+        self
+    }
+
+    pub fn store_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.store_id = Some(filter);
+        self
+    }
+}
+
+impl HoldRecordType {
+    pub fn equal_to(&self) -> EqualFilter<Self> {
+        inline_init(|r: &mut EqualFilter<Self>| r.equal_to = Some(self.clone()))
+    }
+}
@@ -0,0 +1,77 @@
+use super::{hold_reason_row::hold_reason::dsl as hold_reason_dsl, StorageConnection};
+
+use crate::repository_error::RepositoryError;
+
+use diesel::prelude::*;
+
+table! {
+    hold_reason (id) {
+        id -> Text,
+        reason -> Text,
+        is_active -> Bool,
+    }
+}
+
+/// Ids of the hold reasons created by migration
+pub const HOLD_REASON_QUARANTINE_ID: &str = "QUARANTINE";
+pub const HOLD_REASON_QA_PENDING_ID: &str = "QA_PENDING";
+pub const HOLD_REASON_RECALL_ID: &str = "RECALL";
+pub const HOLD_REASON_DAMAGED_ID: &str = "DAMAGED";
+pub const HOLD_REASON_RESERVED_ID: &str = "RESERVED";
+
+#[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Default)]
+#[table_name = "hold_reason"]
+pub struct HoldReasonRow {
+    pub id: String,
+    pub reason: String,
+    pub is_active: bool,
+}
+
+pub struct HoldReasonRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> HoldReasonRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        HoldReasonRowRepository { connection }
+    }
+
+    #[cfg(feature = "postgres")]
+    pub fn upsert_one(&self, row: &HoldReasonRow) -> Result<(), RepositoryError> {
+        diesel::insert_into(hold_reason_dsl::hold_reason)
+            .values(row)
+            .on_conflict(hold_reason_dsl::id)
+            .do_update()
+            .set(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    #[cfg(not(feature = "postgres"))]
+    pub fn upsert_one(&self, row: &HoldReasonRow) -> Result<(), RepositoryError> {
+        diesel::replace_into(hold_reason_dsl::hold_reason)
+            .values(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    pub fn find_one_by_id(&self, id: &str) -> Result<Option<HoldReasonRow>, RepositoryError> {
+        let result = hold_reason_dsl::hold_reason
+            .filter(hold_reason_dsl::id.eq(id))
+            .first(&self.connection.connection)
+            .optional()?;
+        Ok(result)
+    }
+
+    /// Returns hold reasons ordered by reason, only active ones if `active_only` is set
+    pub fn find_all(&self, active_only: bool) -> Result<Vec<HoldReasonRow>, RepositoryError> {
+        let mut query = hold_reason_dsl::hold_reason.into_boxed();
+        if active_only {
+            query = query.filter(hold_reason_dsl::is_active.eq(true));
+        }
+        let result = query
+            .order(hold_reason_dsl::reason.asc())
+            .load(&self.connection.connection)?;
+        Ok(result)
+    }
+}
@@ -0,0 +1,116 @@
+use super::{
+    hold_reason_row::hold_reason, hold_row::hold::dsl as hold_dsl, item_row::item,
+    location_row::location, name_row::name, stock_line_row::stock_line, store_row::store,
+    StorageConnection,
+};
+
+use crate::repository_error::RepositoryError;
+
+use chrono::{NaiveDate, NaiveDateTime};
+use diesel::prelude::*;
+use diesel_derive_enum::DbEnum;
+use serde::{Deserialize, Serialize};
+
+table! {
+    hold (id) {
+        id -> Text,
+        record_type -> crate::db_diesel::hold_row::HoldRecordTypeMapping,
+        record_id -> Text,
+        hold_reason_id -> Text,
+        store_id -> Text,
+        user_id -> Nullable<Text>,
+        datetime -> Timestamp,
+        release_date -> Nullable<Date>,
+    }
+}
+
+joinable!(hold -> hold_reason (hold_reason_id));
+joinable!(hold -> store (store_id));
+
+allow_tables_to_appear_in_same_query!(hold, hold_reason);
+allow_tables_to_appear_in_same_query!(hold, store);
+// Stock line queries filter by hold reason
+allow_tables_to_appear_in_same_query!(hold, stock_line);
+allow_tables_to_appear_in_same_query!(hold, item);
+allow_tables_to_appear_in_same_query!(hold, location);
+allow_tables_to_appear_in_same_query!(hold, name);
+
+#[derive(DbEnum, Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[DbValueStyle = "SCREAMING_SNAKE_CASE"]
+pub enum HoldRecordType {
+    #[default]
+    StockLine,
+    Location,
+    Invoice,
+}
+
+/// Details of the current hold of a record, the on_hold flag of the record is the source of
+/// truth and the hold row is removed when the record comes off hold
+#[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Default)]
+#[changeset_options(treat_none_as_null = "true")]
+#[table_name = "hold"]
+pub struct HoldRow {
+    pub id: String,
+    pub record_type: HoldRecordType,
+    pub record_id: String,
+    pub hold_reason_id: String,
+    pub store_id: String,
+    pub user_id: Option<String>,
+    pub datetime: NaiveDateTime,
+    /// Date the hold is expected to be released, informational only
+    pub release_date: Option<NaiveDate>,
+}
+
+pub struct HoldRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> HoldRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        HoldRowRepository { connection }
+    }
+
+    #[cfg(feature = "postgres")]
+    pub fn upsert_one(&self, row: &HoldRow) -> Result<(), RepositoryError> {
+        diesel::insert_into(hold_dsl::hold)
+            .values(row)
+            .on_conflict(hold_dsl::id)
+            .do_update()
+            .set(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    #[cfg(not(feature = "postgres"))]
+    pub fn upsert_one(&self, row: &HoldRow) -> Result<(), RepositoryError> {
+        diesel::replace_into(hold_dsl::hold)
+            .values(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    pub fn find_one_by_record(
+        &self,
+        record_type: &HoldRecordType,
+        record_id: &str,
+    ) -> Result<Option<HoldRow>, RepositoryError> {
+        let result = hold_dsl::hold
+            .filter(hold_dsl::record_type.eq(record_type))
+            .filter(hold_dsl::record_id.eq(record_id))
+            .first(&self.connection.connection)
+            .optional()?;
+        Ok(result)
+    }
+
+    pub fn delete_by_record(
+        &self,
+        record_type: &HoldRecordType,
+        record_id: &str,
+    ) -> Result<(), RepositoryError> {
+        diesel::delete(hold_dsl::hold)
+            .filter(hold_dsl::record_type.eq(record_type))
+            .filter(hold_dsl::record_id.eq(record_id))
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+}
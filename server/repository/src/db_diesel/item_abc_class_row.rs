@@ -0,0 +1,101 @@
+use super::{
+    item_abc_class_row::item_abc_class::dsl as item_abc_class_dsl, item_row::item,
+    store_row::store, StorageConnection,
+};
+
+use crate::repository_error::RepositoryError;
+
+use diesel::prelude::*;
+use diesel_derive_enum::DbEnum;
+use serde::{Deserialize, Serialize};
+
+table! {
+    item_abc_class (id) {
+        id -> Text,
+        store_id -> Text,
+        item_id -> Text,
+        abc_class -> crate::db_diesel::item_abc_class_row::AbcClassMapping,
+        consumption_value -> Double,
+        is_manual -> Bool,
+    }
+}
+
+joinable!(item_abc_class -> store (store_id));
+joinable!(item_abc_class -> item (item_id));
+
+#[derive(DbEnum, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
+#[DbValueStyle = "SCREAMING_SNAKE_CASE"]
+pub enum AbcClass {
+    A,
+    B,
+    #[default]
+    C,
+}
+
+/// ABC classification of an item in a store
+#[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Default)]
+#[table_name = "item_abc_class"]
+pub struct ItemAbcClassRow {
+    pub id: String,
+    pub store_id: String,
+    pub item_id: String,
+    pub abc_class: AbcClass,
+    /// Consumption value (quantity consumed times unit cost) of the last classification
+    pub consumption_value: f64,
+    /// Class was set by hand and is kept when items are reclassified
+    pub is_manual: bool,
+}
+
+pub struct ItemAbcClassRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> ItemAbcClassRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        ItemAbcClassRowRepository { connection }
+    }
+
+    #[cfg(feature = "postgres")]
+    pub fn upsert_one(&self, row: &ItemAbcClassRow) -> Result<(), RepositoryError> {
+        diesel::insert_into(item_abc_class_dsl::item_abc_class)
+            .values(row)
+            .on_conflict(item_abc_class_dsl::id)
+            .do_update()
+            .set(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    #[cfg(not(feature = "postgres"))]
+    pub fn upsert_one(&self, row: &ItemAbcClassRow) -> Result<(), RepositoryError> {
+        diesel::replace_into(item_abc_class_dsl::item_abc_class)
+            .values(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    pub fn find_one_by_store_and_item_id(
+        &self,
+        store_id: &str,
+        item_id: &str,
+    ) -> Result<Option<ItemAbcClassRow>, RepositoryError> {
+        let result = item_abc_class_dsl::item_abc_class
+            .filter(item_abc_class_dsl::store_id.eq(store_id))
+            .filter(item_abc_class_dsl::item_id.eq(item_id))
+            .first(&self.connection.connection)
+            .optional()?;
+        Ok(result)
+    }
+
+    /// Returns classifications of a store, highest consumption value first
+    pub fn find_many_by_store_id(
+        &self,
+        store_id: &str,
+    ) -> Result<Vec<ItemAbcClassRow>, RepositoryError> {
+        let result = item_abc_class_dsl::item_abc_class
+            .filter(item_abc_class_dsl::store_id.eq(store_id))
+            .order(item_abc_class_dsl::consumption_value.desc())
+            .load(&self.connection.connection)?;
+        Ok(result)
+    }
+}
@@ -0,0 +1,328 @@
+use super::{
+    invoice_row::InvoiceRowType,
+    item_ledger::item_ledger::dsl as item_ledger_dsl,
+    StorageConnection,
+};
+
+use crate::{
+    diesel_macros::{apply_date_time_filter, apply_equal_filter},
+    DBType, DatetimeFilter, EqualFilter, Pagination, RepositoryError,
+};
+use chrono::{NaiveDate, NaiveDateTime};
+use diesel::prelude::*;
+
+table! {
+    item_ledger (id) {
+        id -> Text,
+        item_id -> Text,
+        store_id -> Text,
+        stock_line_id -> Nullable<Text>,
+        invoice_id -> Text,
+        invoice_number -> BigInt,
+        invoice_type -> crate::db_diesel::invoice_row::InvoiceRowTypeMapping,
+        name_id -> Text,
+        name -> Text,
+        batch -> Nullable<Text>,
+        expiry_date -> Nullable<Date>,
+        pack_size -> Integer,
+        number_of_packs -> Double,
+        quantity -> Double,
+        datetime -> Timestamp,
+        location_id -> Nullable<Text>,
+        cost_price_per_pack -> Double,
+    }
+}
+
+/// Stock movement of an invoice line, the id is the invoice line id
+#[derive(Clone, Queryable, Debug, PartialEq)]
+pub struct ItemLedgerRow {
+    pub id: String,
+    pub item_id: String,
+    pub store_id: String,
+    pub stock_line_id: Option<String>,
+    pub invoice_id: String,
+    pub invoice_number: i64,
+    pub invoice_type: InvoiceRowType,
+    /// Name of the other party, e.g. the supplier or customer
+    pub name_id: String,
+    pub name: String,
+    pub batch: Option<String>,
+    pub expiry_date: Option<NaiveDate>,
+    pub pack_size: i32,
+    pub number_of_packs: f64,
+    /// Quantity in units, positive for stock coming in and negative for stock going out
+    pub quantity: f64,
+    /// Picked datetime for outbound shipments, delivered datetime for inbound shipments and
+    /// verified datetime for inventory adjustments
+    pub datetime: NaiveDateTime,
+    /// Location of the stock at the time of the movement
+    pub location_id: Option<String>,
+    pub cost_price_per_pack: f64,
+}
+
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct ItemLedgerFilter {
+    pub item_id: Option<EqualFilter<String>>,
+    pub store_id: Option<EqualFilter<String>>,
+    pub stock_line_id: Option<EqualFilter<String>>,
+    pub invoice_type: Option<EqualFilter<InvoiceRowType>>,
+    pub datetime: Option<DatetimeFilter>,
+}
+
+pub struct ItemLedgerRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> ItemLedgerRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        ItemLedgerRepository { connection }
+    }
+
+    pub fn count(&self, filter: Option<ItemLedgerFilter>) -> Result<i64, RepositoryError> {
+        let query = create_filtered_query(filter);
+
+        Ok(query.count().get_result(&self.connection.connection)?)
+    }
+
+    pub fn query_by_filter(
+        &self,
+        filter: ItemLedgerFilter,
+    ) -> Result<Vec<ItemLedgerRow>, RepositoryError> {
+        self.query(Pagination::all(), Some(filter))
+    }
+
+    /// Ledger entries ordered by datetime (and id for entries with the same datetime)
+    pub fn query(
+        &self,
+        pagination: Pagination,
+        filter: Option<ItemLedgerFilter>,
+    ) -> Result<Vec<ItemLedgerRow>, RepositoryError> {
+        let query = create_filtered_query(filter)
+            .order((item_ledger_dsl::datetime.asc(), item_ledger_dsl::id.asc()))
+            .offset(pagination.offset as i64)
+            .limit(pagination.limit as i64);
+
+        Ok(query.load::<ItemLedgerRow>(&self.connection.connection)?)
+    }
+
+    /// Balance before the first entry of a page, i.e. the sum of the quantities of all entries
+    /// matching the filter (ignoring the datetime filter) that come before the page.
+    pub fn opening_balance(
+        &self,
+        filter: ItemLedgerFilter,
+        offset: u32,
+    ) -> Result<f64, RepositoryError> {
+        let start = filter
+            .datetime
+            .as_ref()
+            .and_then(|datetime| datetime.after_or_equal_to.or(datetime.equal_to));
+
+        let before_range = match start {
+            Some(start) => {
+                let query = create_filtered_query(Some(ItemLedgerFilter {
+                    datetime: None,
+                    ..filter.clone()
+                }))
+                .filter(item_ledger_dsl::datetime.lt(start))
+                .select(item_ledger_dsl::quantity);
+                query
+                    .load::<f64>(&self.connection.connection)?
+                    .into_iter()
+                    .sum()
+            }
+            None => 0.0,
+        };
+
+        let before_page: f64 = if offset > 0 {
+            create_filtered_query(Some(filter))
+                .order((item_ledger_dsl::datetime.asc(), item_ledger_dsl::id.asc()))
+                .limit(offset as i64)
+                .select(item_ledger_dsl::quantity)
+                .load::<f64>(&self.connection.connection)?
+                .into_iter()
+                .sum()
+        } else {
+            0.0
+        };
+
+        Ok(before_range + before_page)
+    }
+}
+
+type BoxedItemLedgerQuery = item_ledger::BoxedQuery<'static, DBType>;
+
+fn create_filtered_query(filter: Option<ItemLedgerFilter>) -> BoxedItemLedgerQuery {
+    let mut query = item_ledger::table.into_boxed();
+
+    if let Some(f) = filter {
+        let ItemLedgerFilter {
+            item_id,
+            store_id,
+            stock_line_id,
+            invoice_type,
+            datetime,
+        } = f;
+
+        apply_equal_filter!(query, item_id, item_ledger_dsl::item_id);
+        apply_equal_filter!(query, store_id, item_ledger_dsl::store_id);
+        apply_equal_filter!(query, stock_line_id, item_ledger_dsl::stock_line_id);
+        apply_equal_filter!(query, invoice_type, item_ledger_dsl::invoice_type);
+        apply_date_time_filter!(query, datetime, item_ledger_dsl::datetime);
+    }
+
+    query
+}
+
+impl ItemLedgerFilter {
+    pub fn new() -> ItemLedgerFilter {
+        ItemLedgerFilter::default()
+    }
+
+    pub fn item_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.item_id = Some(filter);
+        self
+    }
+
+    pub fn store_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.store_id = Some(filter);
+        self
+    }
+
+    pub fn stock_line_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.stock_line_id = Some(filter);
+        self
+    }
+
+    pub fn invoice_type(mut self, filter: EqualFilter<InvoiceRowType>) -> Self {
+        self.invoice_type = Some(filter);
+        self
+    }
+
+    pub fn datetime(mut self, filter: DatetimeFilter) -> Self {
+        self.datetime = Some(filter);
+        self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::NaiveDate;
+    use util::{inline_edit, inline_init};
+
+    use crate::{
+        mock::{mock_item_a, mock_name_a, mock_store_a, MockData, MockDataInserts},
+        test_db::setup_all_with_data,
+        DatetimeFilter, EqualFilter, InvoiceLineRow, InvoiceLineRowType, InvoiceRow,
+        InvoiceRowType, Pagination,
+    };
+
+    use super::*;
+
+    fn datetime(day: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2022, 1, day)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap()
+    }
+
+    fn ledger_point(id: &str) -> MockData {
+        inline_init(|r: &mut MockData| {
+            r.invoices = vec![inline_init(|r: &mut InvoiceRow| {
+                r.id = id.to_string();
+                r.store_id = mock_store_a().id;
+                r.name_id = mock_name_a().id;
+                r.r#type = InvoiceRowType::InboundShipment;
+            })];
+            r.invoice_lines = vec![inline_init(|r: &mut InvoiceLineRow| {
+                r.id = format!("{}_line", id);
+                r.invoice_id = id.to_string();
+                r.item_id = "ledger_item".to_string();
+                r.r#type = InvoiceLineRowType::StockIn;
+                r.pack_size = 10;
+                r.number_of_packs = 1.0;
+            })];
+        })
+    }
+
+    #[actix_rt::test]
+    async fn item_ledger_repository() {
+        let (_, connection, _, _) = setup_all_with_data(
+            "item_ledger_repository",
+            MockDataInserts::all(),
+            inline_init(|r: &mut MockData| {
+                r.items = vec![inline_edit(&mock_item_a(), |mut u| {
+                    u.id = "ledger_item".to_string();
+                    u.code = "ledger_item".to_string();
+                    u
+                })];
+            })
+            .join(inline_edit(&ledger_point("in1"), |mut u| {
+                u.invoices[0].delivered_datetime = Some(datetime(1));
+                u.invoice_lines[0].number_of_packs = 5.0;
+                u
+            }))
+            .join(inline_edit(&ledger_point("out1"), |mut u| {
+                u.invoices[0].r#type = InvoiceRowType::OutboundShipment;
+                u.invoices[0].picked_datetime = Some(datetime(2));
+                u.invoice_lines[0].r#type = InvoiceLineRowType::StockOut;
+                u.invoice_lines[0].number_of_packs = 2.0;
+                u
+            }))
+            .join(inline_edit(&ledger_point("out2"), |mut u| {
+                // Not picked, should not be in the ledger
+                u.invoices[0].r#type = InvoiceRowType::OutboundShipment;
+                u.invoice_lines[0].r#type = InvoiceLineRowType::StockOut;
+                u
+            }))
+            .join(inline_edit(&ledger_point("reduction1"), |mut u| {
+                u.invoices[0].r#type = InvoiceRowType::InventoryReduction;
+                u.invoices[0].verified_datetime = Some(datetime(3));
+                u.invoice_lines[0].r#type = InvoiceLineRowType::StockOut;
+                u
+            }))
+            .join(inline_edit(&ledger_point("in2"), |mut u| {
+                u.invoices[0].delivered_datetime = Some(datetime(4));
+                u.invoice_lines[0].number_of_packs = 3.0;
+                u
+            })),
+        )
+        .await;
+
+        let repo = ItemLedgerRepository::new(&connection);
+        let filter = ItemLedgerFilter::new()
+            .item_id(EqualFilter::equal_to("ledger_item"))
+            .store_id(EqualFilter::equal_to(&mock_store_a().id));
+
+        let rows = repo.query_by_filter(filter.clone()).unwrap();
+        assert_eq!(
+            rows.iter()
+                .map(|row| (row.invoice_id.as_str(), row.quantity))
+                .collect::<Vec<(&str, f64)>>(),
+            vec![
+                ("in1", 50.0),
+                ("out1", -20.0),
+                ("reduction1", -10.0),
+                ("in2", 30.0)
+            ]
+        );
+        assert_eq!(rows[0].name, mock_name_a().name);
+        assert_eq!(rows[0].datetime, datetime(1));
+        assert_eq!(repo.count(Some(filter.clone())).unwrap(), 4);
+
+        // opening balance of the first page of a date range
+        let filter = filter.datetime(DatetimeFilter::date_range(datetime(2), datetime(4)));
+        assert_eq!(repo.opening_balance(filter.clone(), 0).unwrap(), 50.0);
+        // opening balance of the second page
+        let page = repo
+            .query(
+                Pagination {
+                    offset: 1,
+                    limit: 10,
+                },
+                Some(filter.clone()),
+            )
+            .unwrap();
+        assert_eq!(page[0].invoice_id, "reduction1");
+        assert_eq!(repo.opening_balance(filter, 1).unwrap(), 30.0);
+    }
+}
@@ -1,12 +1,13 @@
 use super::{
     location_row::{location, location::dsl as location_dsl},
-    LocationRow, StorageConnection,
+    LocationRow, LocationType, StorageConnection,
 };
 
 use crate::diesel_macros::{apply_equal_filter, apply_sort_no_case};
 
 use crate::{repository_error::RepositoryError, DBType, EqualFilter, Pagination, Sort};
 use diesel::prelude::*;
+use util::inline_init;
 
 #[derive(PartialEq, Debug, Clone)]
 pub struct Location {
@@ -20,6 +21,8 @@ pub struct LocationFilter {
     pub code: Option<EqualFilter<String>>,
     pub on_hold: Option<bool>,
     pub store_id: Option<EqualFilter<String>>,
+    pub parent_id: Option<EqualFilter<String>>,
+    pub location_type: Option<EqualFilter<LocationType>>,
 }
 
 #[derive(PartialEq, Debug)]
@@ -99,6 +102,8 @@ fn create_filtered_query(filter: Option<LocationFilter>) -> BoxedLocationQuery {
         }
 
         apply_equal_filter!(query, filter.store_id, location_dsl::store_id);
+        apply_equal_filter!(query, filter.parent_id, location_dsl::parent_id);
+        apply_equal_filter!(query, filter.location_type, location_dsl::location_type);
     }
 
     query
@@ -116,6 +121,8 @@ impl LocationFilter {
             code: None,
             on_hold: None,
             store_id: None,
+            parent_id: None,
+            location_type: None,
         }
     }
 
@@ -143,4 +150,24 @@ impl LocationFilter {
         self.store_id = Some(filter);
         self
     }
+
+    pub fn parent_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.parent_id = Some(filter);
+        self
+    }
+
+    pub fn location_type(mut self, filter: EqualFilter<LocationType>) -> Self {
+        self.location_type = Some(filter);
+        self
+    }
+}
+
+impl LocationType {
+    pub fn equal_to(&self) -> EqualFilter<Self> {
+        inline_init(|r: &mut EqualFilter<Self>| r.equal_to = Some(self.clone()))
+    }
+
+    pub fn equal_any(value: Vec<Self>) -> EqualFilter<Self> {
+        inline_init(|r: &mut EqualFilter<Self>| r.equal_any = Some(value))
+    }
 }
@@ -3,6 +3,8 @@ use super::{location_row::location::dsl as location_dsl, store_row::store, Stora
 use crate::repository_error::RepositoryError;
 
 use diesel::prelude::*;
+use diesel_derive_enum::DbEnum;
+use serde::{Deserialize, Serialize};
 
 table! {
     location (id) {
@@ -11,11 +13,25 @@ table! {
         code -> Text,
         on_hold -> Bool,
         store_id -> Text,
+        parent_id -> Nullable<Text>,
+        location_type -> crate::db_diesel::location_row::LocationTypeMapping,
+        volume_capacity -> Nullable<Double>,
+        pack_capacity -> Nullable<Double>,
     }
 }
 
 joinable!(location -> store (store_id));
 
+#[derive(DbEnum, Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[DbValueStyle = "SCREAMING_SNAKE_CASE"]
+pub enum LocationType {
+    #[default]
+    Ambient,
+    Cold,
+    Frozen,
+    Quarantine,
+}
+
 #[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Default)]
 #[table_name = "location"]
 pub struct LocationRow {
@@ -24,6 +40,12 @@ pub struct LocationRow {
     pub code: String,
     pub on_hold: bool,
     pub store_id: String,
+    /// Location containing this location, e.g. the aisle of a shelf
+    pub parent_id: Option<String>,
+    pub location_type: LocationType,
+    pub volume_capacity: Option<f64>,
+    /// Maximum number of packs the location can hold
+    pub pack_capacity: Option<f64>,
 }
 
 pub struct LocationRowRepository<'a> {
@@ -6,14 +6,21 @@ mod barcode;
 mod barcode_row;
 mod changelog;
 mod consumption;
+mod cycle_count_config_row;
 pub mod diesel_schema;
 mod filter_sort_pagination;
+mod goods_receipt_inspection_row;
+mod hold;
+mod hold_reason_row;
+mod hold_row;
 mod inventory_adjustment_reason;
 mod inventory_adjustment_reason_row;
 mod invoice;
 mod invoice_line;
 mod invoice_line_row;
 mod invoice_row;
+mod item_abc_class_row;
+mod item_ledger;
 mod item;
 mod item_row;
 mod key_value_store;
@@ -37,14 +44,18 @@ mod period;
 mod program_requisition;
 mod report;
 mod report_row;
+mod report_sql_query;
 mod requisition;
 mod requisition_line;
+mod sensor;
+mod sensor_row;
 mod stock_line;
 mod stock_line_row;
 mod stock_movement;
 mod stock_on_hand;
 mod stocktake;
 mod stocktake_line;
+mod stocktake_line_count_row;
 mod stocktake_line_row;
 mod stocktake_row;
 mod storage_connection;
@@ -54,6 +65,12 @@ mod store_row;
 mod sync_buffer;
 mod sync_log;
 mod sync_log_row;
+mod temperature_breach;
+mod temperature_breach_config_row;
+mod temperature_breach_row;
+mod temperature_breach_stock_line_row;
+mod temperature_log;
+mod temperature_log_row;
 mod unit_row;
 mod user;
 mod user_permission;
@@ -67,13 +84,20 @@ pub use barcode::*;
 pub use barcode_row::*;
 pub use changelog::*;
 pub use consumption::*;
+pub use cycle_count_config_row::*;
 pub use filter_sort_pagination::*;
+pub use goods_receipt_inspection_row::*;
+pub use hold::*;
+pub use hold_reason_row::*;
+pub use hold_row::*;
 pub use inventory_adjustment_reason::*;
 pub use inventory_adjustment_reason_row::*;
 pub use invoice::*;
 pub use invoice_line::*;
 pub use invoice_line_row::*;
 pub use invoice_row::*;
+pub use item_abc_class_row::*;
+pub use item_ledger::*;
 pub use item::*;
 pub use item_row::*;
 pub use key_value_store::*;
@@ -97,14 +121,18 @@ pub use period::*;
 pub use program_requisition::*;
 pub use report::*;
 pub use report_row::*;
+pub use report_sql_query::*;
 pub use requisition::*;
 pub use requisition_line::*;
+pub use sensor::*;
+pub use sensor_row::*;
 pub use stock_line::*;
 pub use stock_line_row::*;
 pub use stock_movement::*;
 pub use stock_on_hand::*;
 pub use stocktake::*;
 pub use stocktake_line::*;
+pub use stocktake_line_count_row::*;
 pub use stocktake_line_row::*;
 pub use stocktake_row::*;
 pub use storage_connection::*;
@@ -114,6 +142,12 @@ pub use store_row::*;
 pub use sync_buffer::*;
 pub use sync_log::*;
 pub use sync_log_row::*;
+pub use temperature_breach::*;
+pub use temperature_breach_config_row::*;
+pub use temperature_breach_row::*;
+pub use temperature_breach_stock_line_row::*;
+pub use temperature_log::*;
+pub use temperature_log_row::*;
 pub use unit_row::*;
 pub use user::*;
 pub use user_permission::*;
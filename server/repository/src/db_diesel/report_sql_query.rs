@@ -0,0 +1,233 @@
+use super::{DBBackendConnection, StorageConnection};
+
+use crate::RepositoryError;
+use diesel::{sql_query, sql_types::Text, RunQueryDsl};
+
+/// Tables and views that can be read by report SQL queries
+pub const REPORT_SQL_TABLES: &[&str] = &[
+    "consumption",
+    "inbound_shipment_stock_movement",
+    "inventory_adjustment_stock_movement",
+    "invoice",
+    "invoice_line",
+    "invoice_line_stock_movement",
+    "invoice_stats",
+    "item",
+    "location",
+    "location_movement",
+    "master_list",
+    "master_list_line",
+    "name",
+    "outbound_shipment_stock_movement",
+    "requisition",
+    "requisition_line",
+    "requisitions_in_period",
+    "stock_line",
+    "stock_movement",
+    "stocktake",
+    "stocktake_line",
+    "store",
+    "unit",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SqlDialect {
+    Sqlite,
+    Postgres,
+}
+
+/// Dialect of the database backend the server has been built for
+#[cfg(not(feature = "postgres"))]
+pub const SQL_DIALECT: SqlDialect = SqlDialect::Sqlite;
+#[cfg(feature = "postgres")]
+pub const SQL_DIALECT: SqlDialect = SqlDialect::Postgres;
+
+/// Time to wait for a lock held by a writer, same as for the pooled connections
+#[cfg(not(feature = "postgres"))]
+const READ_ONLY_BUSY_TIMEOUT_MS: u32 = 5000;
+
+#[derive(QueryableByName)]
+struct JsonRows {
+    #[sql_type = "Text"]
+    json: String,
+}
+
+#[cfg(not(feature = "postgres"))]
+#[derive(QueryableByName)]
+struct DatabaseFile {
+    #[sql_type = "Text"]
+    file: String,
+}
+
+/// Runs report SQL queries in read only mode, i.e. on a separate read only connection for sqlite
+/// and in a read only transaction for Postgres.
+///
+/// The query must have been validated against the `REPORT_SQL_TABLES` beforehand, the read only
+/// mode is an additional safe guard.
+pub struct ReportSqlQueryRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> ReportSqlQueryRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        ReportSqlQueryRepository { connection }
+    }
+
+    /// Expression to read a named parameter from the query parameters (which are bound as a json
+    /// object). Postgres parameters are text and need to be cast if used as a different type.
+    pub fn parameter_placeholder(name: &str) -> String {
+        match SQL_DIALECT {
+            SqlDialect::Sqlite => format!("json_extract(?1, '$.\"{}\"')", name),
+            SqlDialect::Postgres => format!("($1::jsonb ->> '{}')", name),
+        }
+    }
+
+    /// Runs the query and returns the result rows as a json array of objects with the given
+    /// column names as keys.
+    ///
+    /// # Arguments
+    /// * `columns` - column names of the query result, must be valid identifiers
+    /// * `parameters` - json object with the values of the parameter placeholders
+    pub fn query_json_rows(
+        &self,
+        query: &str,
+        columns: &[String],
+        parameters: &serde_json::Value,
+    ) -> Result<serde_json::Value, RepositoryError> {
+        let sql = match SQL_DIALECT {
+            SqlDialect::Sqlite => format!(
+                "SELECT json_group_array(json_object({})) AS json FROM ({}) AS report_rows",
+                json_object_arguments(columns),
+                query
+            ),
+            SqlDialect::Postgres => format!(
+                "SELECT COALESCE(json_agg(json_build_object({})), '[]'::json)::text AS json FROM ({}) AS report_rows",
+                json_object_arguments(columns),
+                query
+            ),
+        };
+        let parameters = parameters.to_string();
+        let rows = self.read_only(|connection| {
+            sql_query(&sql)
+                .bind::<Text, _>(&parameters)
+                .get_result::<JsonRows>(connection)
+                .map_err(RepositoryError::from)
+        })?;
+        serde_json::from_str(&rows.json)
+            .map_err(|err| RepositoryError::as_db_error("Invalid json result", err))
+    }
+
+    #[cfg(not(feature = "postgres"))]
+    fn read_only<T, F>(&self, f: F) -> Result<T, RepositoryError>
+    where
+        F: FnOnce(&DBBackendConnection) -> Result<T, RepositoryError>,
+    {
+        use diesel::Connection;
+
+        let database = sql_query("SELECT file FROM pragma_database_list WHERE name = 'main'")
+            .get_result::<DatabaseFile>(&self.connection.connection)?;
+        // in-memory databases can't be opened a second time by file name
+        if database.file.is_empty() {
+            return Err(RepositoryError::as_db_error(
+                "Report SQL queries are not supported for in-memory databases",
+                "",
+            ));
+        }
+        let url = format!(
+            "file:{}?mode=ro",
+            database
+                .file
+                .replace('%', "%25")
+                .replace('?', "%3f")
+                .replace('#', "%23")
+        );
+        let connection = DBBackendConnection::establish(&url).map_err(|err| {
+            RepositoryError::as_db_error("Failed to open read only connection", err)
+        })?;
+        sql_query(format!(
+            "PRAGMA busy_timeout = {}",
+            READ_ONLY_BUSY_TIMEOUT_MS
+        ))
+        .execute(&connection)?;
+        f(&connection)
+    }
+
+    #[cfg(feature = "postgres")]
+    fn read_only<T, F>(&self, f: F) -> Result<T, RepositoryError>
+    where
+        F: FnOnce(&DBBackendConnection) -> Result<T, RepositoryError>,
+    {
+        self.connection
+            .transaction_sync_etc(
+                |connection| {
+                    sql_query("SET TRANSACTION READ ONLY").execute(&connection.connection)?;
+                    f(&connection.connection)
+                },
+                false,
+            )
+            .map_err(|err| err.to_inner_error())
+    }
+}
+
+fn json_object_arguments(columns: &[String]) -> String {
+    columns
+        .iter()
+        .map(|column| format!("'{}', report_rows.\"{}\"", column, column))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod test {
+    use diesel::{sql_query, RunQueryDsl};
+    use serde_json::json;
+
+    use crate::{mock::MockDataInserts, test_db::setup_all, RepositoryError};
+
+    use super::ReportSqlQueryRepository;
+
+    #[actix_rt::test]
+    async fn report_sql_query() {
+        let (_, connection, _, _) =
+            setup_all("report_sql_query", MockDataInserts::none().names().stores()).await;
+        let repo = ReportSqlQueryRepository::new(&connection);
+
+        let query = format!(
+            "SELECT id, name_id FROM store WHERE id = {} ORDER BY id",
+            ReportSqlQueryRepository::parameter_placeholder("storeId")
+        );
+        let rows = repo
+            .query_json_rows(
+                &query,
+                &["id".to_string(), "name_id".to_string()],
+                &json!({ "storeId": "store_a" }),
+            )
+            .unwrap();
+        assert_eq!(
+            rows,
+            json!([{ "id": "store_a", "name_id": "name_store_a" }])
+        );
+
+        // no rows
+        let rows = repo
+            .query_json_rows(
+                &query,
+                &["id".to_string(), "name_id".to_string()],
+                &json!({ "storeId": "unknown" }),
+            )
+            .unwrap();
+        assert_eq!(rows, json!([]));
+
+        // writes are rejected
+        let result = repo.read_only(|connection| {
+            sql_query("DELETE FROM store")
+                .execute(connection)
+                .map_err(RepositoryError::from)
+        });
+        assert!(result.is_err());
+        // the pooled connection is still writable
+        sql_query("UPDATE store SET code = code")
+            .execute(&connection.connection)
+            .unwrap();
+    }
+}
@@ -0,0 +1,131 @@
+use diesel::prelude::*;
+
+use super::{
+    sensor_row::sensor::{self, dsl as sensor_dsl},
+    SensorRow, StorageConnection,
+};
+use crate::{
+    diesel_macros::{apply_equal_filter, apply_sort_no_case},
+    DBType, EqualFilter, Pagination, RepositoryError, Sort,
+};
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct Sensor {
+    pub sensor_row: SensorRow,
+}
+
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct SensorFilter {
+    pub id: Option<EqualFilter<String>>,
+    pub store_id: Option<EqualFilter<String>>,
+    pub location_id: Option<EqualFilter<String>>,
+    pub serial: Option<EqualFilter<String>>,
+    pub is_active: Option<bool>,
+}
+
+#[derive(PartialEq, Debug)]
+pub enum SensorSortField {
+    Name,
+    Serial,
+}
+
+pub type SensorSort = Sort<SensorSortField>;
+
+pub struct SensorRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> SensorRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        SensorRepository { connection }
+    }
+
+    pub fn count(&self, filter: Option<SensorFilter>) -> Result<i64, RepositoryError> {
+        let query = create_filtered_query(filter);
+
+        Ok(query.count().get_result(&self.connection.connection)?)
+    }
+
+    pub fn query_by_filter(&self, filter: SensorFilter) -> Result<Vec<Sensor>, RepositoryError> {
+        self.query(Pagination::all(), Some(filter), None)
+    }
+
+    pub fn query(
+        &self,
+        pagination: Pagination,
+        filter: Option<SensorFilter>,
+        sort: Option<SensorSort>,
+    ) -> Result<Vec<Sensor>, RepositoryError> {
+        let mut query = create_filtered_query(filter);
+
+        if let Some(sort) = sort {
+            match sort.key {
+                SensorSortField::Name => apply_sort_no_case!(query, sort, sensor_dsl::name),
+                SensorSortField::Serial => apply_sort_no_case!(query, sort, sensor_dsl::serial),
+            }
+        } else {
+            query = query.order(sensor_dsl::id.asc())
+        }
+
+        let result = query
+            .offset(pagination.offset as i64)
+            .limit(pagination.limit as i64)
+            .load::<SensorRow>(&self.connection.connection)?;
+
+        Ok(result.into_iter().map(to_domain).collect())
+    }
+}
+
+type BoxedSensorQuery = sensor::BoxedQuery<'static, DBType>;
+
+fn create_filtered_query(filter: Option<SensorFilter>) -> BoxedSensorQuery {
+    let mut query = sensor::table.into_boxed();
+
+    if let Some(filter) = filter {
+        apply_equal_filter!(query, filter.id, sensor_dsl::id);
+        apply_equal_filter!(query, filter.store_id, sensor_dsl::store_id);
+        apply_equal_filter!(query, filter.location_id, sensor_dsl::location_id);
+        apply_equal_filter!(query, filter.serial, sensor_dsl::serial);
+
+        if let Some(is_active) = filter.is_active {
+            query = query.filter(sensor_dsl::is_active.eq(is_active));
+        }
+    }
+
+    query
+}
+
+pub fn to_domain(sensor_row: SensorRow) -> Sensor {
+    Sensor { sensor_row }
+}
+
+impl SensorFilter {
+    pub fn new() -> SensorFilter {
+        SensorFilter::default()
+    }
+
+    pub fn id(mut self, filter: EqualFilter<String>) -> Self {
+        self.id = Some(filter);
+        self
+    }
+
+    pub fn store_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.store_id = Some(filter);
+        self
+    }
+
+    pub fn location_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.location_id = Some(filter);
+        self
+    }
+
+    pub fn serial(mut self, filter: EqualFilter<String>) -> Self {
+        self.serial = Some(filter);
+        self
+    }
+
+    pub fn is_active(mut self, value: bool) -> Self {
+        self.is_active = Some(value);
+        self
+    }
+}
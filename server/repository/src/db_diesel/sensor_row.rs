@@ -0,0 +1,73 @@
+use super::{
+    location_row::location, sensor_row::sensor::dsl as sensor_dsl, store_row::store,
+    StorageConnection,
+};
+
+use crate::repository_error::RepositoryError;
+
+use diesel::prelude::*;
+
+table! {
+    sensor (id) {
+        id -> Text,
+        name -> Text,
+        serial -> Text,
+        store_id -> Text,
+        location_id -> Nullable<Text>,
+        is_active -> Bool,
+    }
+}
+
+joinable!(sensor -> store (store_id));
+joinable!(sensor -> location (location_id));
+
+#[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Default)]
+#[changeset_options(treat_none_as_null = "true")]
+#[table_name = "sensor"]
+pub struct SensorRow {
+    pub id: String,
+    pub name: String,
+    /// Serial number of the logger, used to match imported log files
+    pub serial: String,
+    pub store_id: String,
+    /// Location the sensor is monitoring
+    pub location_id: Option<String>,
+    pub is_active: bool,
+}
+
+pub struct SensorRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> SensorRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        SensorRowRepository { connection }
+    }
+
+    #[cfg(feature = "postgres")]
+    pub fn upsert_one(&self, row: &SensorRow) -> Result<(), RepositoryError> {
+        diesel::insert_into(sensor_dsl::sensor)
+            .values(row)
+            .on_conflict(sensor_dsl::id)
+            .do_update()
+            .set(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    #[cfg(not(feature = "postgres"))]
+    pub fn upsert_one(&self, row: &SensorRow) -> Result<(), RepositoryError> {
+        diesel::replace_into(sensor_dsl::sensor)
+            .values(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    pub fn find_one_by_id(&self, id: &str) -> Result<Option<SensorRow>, RepositoryError> {
+        let result = sensor_dsl::sensor
+            .filter(sensor_dsl::id.eq(id))
+            .first(&self.connection.connection)
+            .optional()?;
+        Ok(result)
+    }
+}
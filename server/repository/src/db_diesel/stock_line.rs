@@ -1,9 +1,10 @@
 use super::{
+    hold_row::hold::dsl as hold_dsl,
     item_row::{item, item::dsl as item_dsl},
     location_row::{location, location::dsl as location_dsl},
     name_row::{name, name::dsl as name_dsl},
     stock_line_row::{stock_line, stock_line::dsl as stock_line_dsl},
-    DBType, LocationRow, StockLineRow, StorageConnection,
+    DBType, HoldRecordType, LocationRow, StockLineRow, StorageConnection,
 };
 
 use crate::{
@@ -49,6 +50,9 @@ pub struct StockLineFilter {
     pub expiry_date: Option<DateFilter>,
     pub store_id: Option<EqualFilter<String>>,
     pub has_packs_in_store: Option<bool>,
+    pub is_on_hold: Option<bool>,
+    /// Stock lines held with one of the given hold reasons
+    pub hold_reason_id: Option<EqualFilter<String>>,
 }
 
 pub type StockLineSort = Sort<StockLineSortField>;
@@ -170,6 +174,8 @@ fn create_filtered_query(filter: Option<StockLineFilter>) -> BoxedStockLineQuery
             location_id,
             store_id,
             has_packs_in_store,
+            is_on_hold,
+            hold_reason_id,
         } = f;
 
         apply_equal_filter!(query, id, stock_line_dsl::id);
@@ -184,6 +190,21 @@ fn create_filtered_query(filter: Option<StockLineFilter>) -> BoxedStockLineQuery
             None => query,
         };
 
+        if let Some(is_on_hold) = is_on_hold {
+            query = query.filter(stock_line_dsl::on_hold.eq(is_on_hold));
+        }
+
+        if hold_reason_id.is_some() {
+            let mut hold_query = hold_dsl::hold
+                .filter(hold_dsl::record_type.eq(HoldRecordType::StockLine))
+                .into_boxed();
+            apply_equal_filter!(hold_query, hold_reason_id, hold_dsl::hold_reason_id);
+
+            query = query
+                .filter(stock_line_dsl::on_hold.eq(true))
+                .filter(stock_line_dsl::id.eq_any(hold_query.select(hold_dsl::record_id)));
+        }
+
         query = match is_available {
             Some(true) => query.filter(stock_line_dsl::available_number_of_packs.gt(0.0)),
             Some(false) => query.filter(stock_line_dsl::available_number_of_packs.le(0.0)),
@@ -236,6 +257,8 @@ impl StockLineFilter {
             location_id: None,
             store_id: None,
             has_packs_in_store: None,
+            is_on_hold: None,
+            hold_reason_id: None,
         }
     }
 
@@ -249,6 +272,11 @@ impl StockLineFilter {
         self
     }
 
+    pub fn item_code_or_name(mut self, filter: SimpleStringFilter) -> Self {
+        self.item_code_or_name = Some(filter);
+        self
+    }
+
     pub fn location_id(mut self, filter: EqualFilter<String>) -> Self {
         self.location_id = Some(filter);
         self
@@ -273,6 +301,16 @@ impl StockLineFilter {
         self.has_packs_in_store = Some(filter);
         self
     }
+
+    pub fn is_on_hold(mut self, filter: bool) -> Self {
+        self.is_on_hold = Some(filter);
+        self
+    }
+
+    pub fn hold_reason_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.hold_reason_id = Some(filter);
+        self
+    }
 }
 
 impl StockLine {
@@ -2,7 +2,7 @@ use super::{stock_movement::stock_movement::dsl as stock_movement_dsl, StorageCo
 
 use crate::{
     diesel_macros::{apply_date_time_filter, apply_equal_filter},
-    DatetimeFilter, EqualFilter, RepositoryError,
+    DBType, DatetimeFilter, EqualFilter, Pagination, RepositoryError,
 };
 use chrono::NaiveDateTime;
 use diesel::prelude::*;
@@ -57,31 +57,35 @@ impl<'a> StockMovementRepository<'a> {
         StockMovementRepository { connection }
     }
 
+    pub fn count(&self, filter: Option<StockMovementFilter>) -> Result<i64, RepositoryError> {
+        let query = create_filtered_query(filter);
+
+        Ok(query.count().get_result(&self.connection.connection)?)
+    }
+
     pub fn query_one(
         &self,
         filter: StockMovementFilter,
     ) -> Result<Option<StockMovementRow>, RepositoryError> {
-        Ok(self.query(Some(filter))?.pop())
+        Ok(self.query_by_filter(filter)?.pop())
+    }
+
+    pub fn query_by_filter(
+        &self,
+        filter: StockMovementFilter,
+    ) -> Result<Vec<StockMovementRow>, RepositoryError> {
+        self.query(Pagination::all(), Some(filter))
     }
 
     pub fn query(
         &self,
+        pagination: Pagination,
         filter: Option<StockMovementFilter>,
     ) -> Result<Vec<StockMovementRow>, RepositoryError> {
-        // Query StockMovement
-        let mut query = stock_movement_dsl::stock_movement.into_boxed();
-
-        if let Some(f) = filter {
-            let StockMovementFilter {
-                item_id,
-                datetime,
-                store_id,
-            } = f;
-
-            apply_equal_filter!(query, item_id, stock_movement_dsl::item_id);
-            apply_equal_filter!(query, store_id, stock_movement_dsl::store_id);
-            apply_date_time_filter!(query, datetime, stock_movement_dsl::datetime);
-        }
+        let query = create_filtered_query(filter)
+            .order(stock_movement_dsl::datetime.asc())
+            .offset(pagination.offset as i64)
+            .limit(pagination.limit as i64);
 
         // Debug diesel query
         // println!(
@@ -93,6 +97,26 @@ impl<'a> StockMovementRepository<'a> {
     }
 }
 
+type BoxedStockMovementQuery = stock_movement::BoxedQuery<'static, DBType>;
+
+fn create_filtered_query(filter: Option<StockMovementFilter>) -> BoxedStockMovementQuery {
+    let mut query = stock_movement_dsl::stock_movement.into_boxed();
+
+    if let Some(f) = filter {
+        let StockMovementFilter {
+            item_id,
+            datetime,
+            store_id,
+        } = f;
+
+        apply_equal_filter!(query, item_id, stock_movement_dsl::item_id);
+        apply_equal_filter!(query, store_id, stock_movement_dsl::store_id);
+        apply_date_time_filter!(query, datetime, stock_movement_dsl::datetime);
+    }
+
+    query
+}
+
 impl StockMovementFilter {
     pub fn new() -> StockMovementFilter {
         StockMovementFilter::default()
@@ -254,11 +278,11 @@ mod test {
 
         let repo = StockMovementRepository::new(&connection);
         let mut rows = repo
-            .query(Some(StockMovementFilter {
+            .query_by_filter(StockMovementFilter {
                 store_id: Some(EqualFilter::equal_to(&store().id)),
                 item_id: Some(EqualFilter::equal_to(&mock_item_a().id)),
                 datetime: None,
-            }))
+            })
             .unwrap();
 
         rows.sort_by(|a, b| a.datetime.cmp(&b.datetime));
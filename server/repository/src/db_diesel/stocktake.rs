@@ -13,6 +13,7 @@ use crate::{
 };
 
 use diesel::{dsl::IntoBoxed, prelude::*};
+use util::inline_init;
 
 #[derive(Clone)]
 pub struct StocktakeFilter {
@@ -102,6 +103,12 @@ impl StocktakeFilter {
     }
 }
 
+impl StocktakeStatus {
+    pub fn equal_to(&self) -> EqualFilter<Self> {
+        inline_init(|r: &mut EqualFilter<Self>| r.equal_to = Some(self.clone()))
+    }
+}
+
 pub enum StocktakeSortField {
     Status,
     CreatedDatetime,
@@ -118,34 +125,6 @@ pub type StocktakeSort = Sort<StocktakeSortField>;
 
 type BoxedStocktakeQuery = IntoBoxed<'static, stocktake::table, DBType>;
 
-fn create_filtered_query<'a>(filter: Option<StocktakeFilter>) -> BoxedStocktakeQuery {
-    let mut query = stocktake_dsl::stocktake.into_boxed();
-
-    if let Some(f) = filter {
-        apply_equal_filter!(query, f.id, stocktake::id);
-        apply_equal_filter!(query, f.store_id, stocktake::store_id);
-        apply_equal_filter!(query, f.user_id, stocktake::user_id);
-        apply_equal_filter!(query, f.stocktake_number, stocktake::stocktake_number);
-        apply_simple_string_filter!(query, f.comment, stocktake::comment);
-        apply_simple_string_filter!(query, f.description, stocktake::description);
-
-        if let Some(value) = f.status {
-            if let Some(eq) = value.equal_to {
-                query = query.filter(stocktake::status.eq(eq));
-            }
-        }
-
-        apply_date_time_filter!(query, f.created_datetime, stocktake::created_datetime);
-        apply_date_filter!(query, f.stocktake_date, stocktake::stocktake_date);
-        apply_date_time_filter!(query, f.finalised_datetime, stocktake::finalised_datetime);
-
-        if let Some(value) = f.is_locked {
-            query = query.filter(stocktake::is_locked.eq(value));
-        }
-    }
-    query
-}
-
 pub struct StocktakeRepository<'a> {
     connection: &'a StorageConnection,
 }
@@ -155,9 +134,37 @@ impl<'a> StocktakeRepository<'a> {
         StocktakeRepository { connection }
     }
 
+    pub fn create_filtered_query(filter: Option<StocktakeFilter>) -> BoxedStocktakeQuery {
+        let mut query = stocktake_dsl::stocktake.into_boxed();
+
+        if let Some(f) = filter {
+            apply_equal_filter!(query, f.id, stocktake::id);
+            apply_equal_filter!(query, f.store_id, stocktake::store_id);
+            apply_equal_filter!(query, f.user_id, stocktake::user_id);
+            apply_equal_filter!(query, f.stocktake_number, stocktake::stocktake_number);
+            apply_simple_string_filter!(query, f.comment, stocktake::comment);
+            apply_simple_string_filter!(query, f.description, stocktake::description);
+
+            if let Some(value) = f.status {
+                if let Some(eq) = value.equal_to {
+                    query = query.filter(stocktake::status.eq(eq));
+                }
+            }
+
+            apply_date_time_filter!(query, f.created_datetime, stocktake::created_datetime);
+            apply_date_filter!(query, f.stocktake_date, stocktake::stocktake_date);
+            apply_date_time_filter!(query, f.finalised_datetime, stocktake::finalised_datetime);
+
+            if let Some(value) = f.is_locked {
+                query = query.filter(stocktake::is_locked.eq(value));
+            }
+        }
+        query
+    }
+
     pub fn count(&self, filter: Option<StocktakeFilter>) -> Result<i64, RepositoryError> {
         // TODO (beyond M1), check that store_id matches current store
-        let query = create_filtered_query(filter);
+        let query = Self::create_filtered_query(filter);
 
         Ok(query.count().get_result(&self.connection.connection)?)
     }
@@ -176,7 +183,7 @@ impl<'a> StocktakeRepository<'a> {
         filter: Option<StocktakeFilter>,
         sort: Option<StocktakeSort>,
     ) -> Result<Vec<Stocktake>, RepositoryError> {
-        let mut query = create_filtered_query(filter);
+        let mut query = Self::create_filtered_query(filter);
 
         if let Some(sort) = sort {
             match sort.key {
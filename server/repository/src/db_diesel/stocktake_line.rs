@@ -2,7 +2,9 @@ use super::{
     location_row::{location, location::dsl as location_dsl},
     stock_line_row::{stock_line, stock_line::dsl as stock_line_dsl},
     stocktake_line_row::stocktake_line::{self, dsl as stocktake_line_dsl},
-    LocationRow, StockLineRow, StocktakeLineRow, StorageConnection,
+    stocktake_row::stocktake::dsl as stocktake_dsl,
+    LocationRow, StockLineRow, StocktakeFilter, StocktakeLineRow, StocktakeRepository,
+    StorageConnection,
 };
 
 use diesel::{
@@ -19,6 +21,7 @@ pub struct StocktakeLineFilter {
     pub id: Option<EqualFilter<String>>,
     pub stocktake_id: Option<EqualFilter<String>>,
     pub location_id: Option<EqualFilter<String>>,
+    pub stocktake: Option<StocktakeFilter>,
 }
 
 impl StocktakeLineFilter {
@@ -27,6 +30,7 @@ impl StocktakeLineFilter {
             id: None,
             stocktake_id: None,
             location_id: None,
+            stocktake: None,
         }
     }
 
@@ -44,6 +48,11 @@ impl StocktakeLineFilter {
         self.location_id = Some(filter);
         self
     }
+
+    pub fn stocktake(mut self, filter: StocktakeFilter) -> Self {
+        self.stocktake = Some(filter);
+        self
+    }
 }
 
 pub type StocktakeLineSort = Sort<()>;
@@ -118,6 +127,12 @@ fn create_filtered_query(filter: Option<StocktakeLineFilter>) -> BoxedStocktakeL
         apply_equal_filter!(query, f.id, stocktake_line_dsl::id);
         apply_equal_filter!(query, f.stocktake_id, stocktake_line_dsl::stocktake_id);
         apply_equal_filter!(query, f.location_id, stocktake_line_dsl::location_id);
+
+        if f.stocktake.is_some() {
+            let stocktake_ids =
+                StocktakeRepository::create_filtered_query(f.stocktake).select(stocktake_dsl::id);
+            query = query.filter(stocktake_line_dsl::stocktake_id.eq_any(stocktake_ids));
+        }
     }
 
     query
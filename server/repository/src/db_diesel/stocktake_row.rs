@@ -25,6 +25,8 @@ table! {
         inventory_addition_id -> Nullable<Text>,
         inventory_reduction_id -> Nullable<Text>,
         is_locked -> Bool,
+        is_blind_count -> Bool,
+        location_id -> Nullable<Text>,
     }
 }
 
@@ -54,6 +56,10 @@ pub struct StocktakeRow {
     pub inventory_addition_id: Option<String>,
     pub inventory_reduction_id: Option<String>,
     pub is_locked: bool,
+    /// Counters don't see the snapshot, each line needs agreeing counts by multiple counters
+    pub is_blind_count: bool,
+    /// Location the stocktake was created for (including nested locations)
+    pub location_id: Option<String>,
 }
 
 impl Default for StocktakeStatus {
@@ -79,6 +85,8 @@ impl Default for StocktakeRow {
             inventory_addition_id: Default::default(),
             inventory_reduction_id: Default::default(),
             is_locked: Default::default(),
+            is_blind_count: Default::default(),
+            location_id: Default::default(),
         }
     }
 }
@@ -17,6 +17,9 @@ table! {
         pack_to_one -> Bool,
         response_requisition_requires_authorisation -> Bool,
         request_requisition_requires_authorisation -> Bool,
+        timezone -> Nullable<Text>,
+        currency_code -> Nullable<Text>,
+        requires_goods_receipt_inspection -> Bool,
     }
 }
 
@@ -41,6 +44,12 @@ pub struct StorePreferenceRow {
     pub pack_to_one: bool,
     pub response_requisition_requires_authorisation: bool,
     pub request_requisition_requires_authorisation: bool,
+    /// Local setting (not synced), IANA timezone name of the store, e.g. "Pacific/Auckland"
+    pub timezone: Option<String>,
+    /// Local setting (not synced), ISO 4217 currency code of the store, e.g. "NZD"
+    pub currency_code: Option<String>,
+    /// Local setting (not synced), received stock is held until it passes QA inspection
+    pub requires_goods_receipt_inspection: bool,
 }
 
 impl Default for StorePreferenceRow {
@@ -51,6 +60,9 @@ impl Default for StorePreferenceRow {
             pack_to_one: Default::default(),
             response_requisition_requires_authorisation: Default::default(),
             request_requisition_requires_authorisation: Default::default(),
+            timezone: None,
+            currency_code: None,
+            requires_goods_receipt_inspection: false,
         }
     }
 }
@@ -0,0 +1,184 @@
+use diesel::prelude::*;
+
+use super::{
+    temperature_breach_row::temperature_breach::{self, dsl as temperature_breach_dsl},
+    StorageConnection, TemperatureBreachRow, TemperatureBreachType,
+};
+use crate::{
+    diesel_macros::{apply_date_time_filter, apply_equal_filter, apply_sort},
+    DBType, DatetimeFilter, EqualFilter, Pagination, RepositoryError, Sort,
+};
+use util::inline_init;
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct TemperatureBreach {
+    pub temperature_breach_row: TemperatureBreachRow,
+}
+
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct TemperatureBreachFilter {
+    pub id: Option<EqualFilter<String>>,
+    pub store_id: Option<EqualFilter<String>>,
+    pub sensor_id: Option<EqualFilter<String>>,
+    pub location_id: Option<EqualFilter<String>>,
+    pub temperature_breach_config_id: Option<EqualFilter<String>>,
+    pub r#type: Option<EqualFilter<TemperatureBreachType>>,
+    pub start_datetime: Option<DatetimeFilter>,
+    pub acknowledged: Option<bool>,
+}
+
+#[derive(PartialEq, Debug)]
+pub enum TemperatureBreachSortField {
+    StartDatetime,
+    ExtremeTemperature,
+}
+
+pub type TemperatureBreachSort = Sort<TemperatureBreachSortField>;
+
+pub struct TemperatureBreachRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> TemperatureBreachRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        TemperatureBreachRepository { connection }
+    }
+
+    pub fn count(&self, filter: Option<TemperatureBreachFilter>) -> Result<i64, RepositoryError> {
+        let query = create_filtered_query(filter);
+
+        Ok(query.count().get_result(&self.connection.connection)?)
+    }
+
+    pub fn query_by_filter(
+        &self,
+        filter: TemperatureBreachFilter,
+    ) -> Result<Vec<TemperatureBreach>, RepositoryError> {
+        self.query(Pagination::all(), Some(filter), None)
+    }
+
+    pub fn query(
+        &self,
+        pagination: Pagination,
+        filter: Option<TemperatureBreachFilter>,
+        sort: Option<TemperatureBreachSort>,
+    ) -> Result<Vec<TemperatureBreach>, RepositoryError> {
+        let mut query = create_filtered_query(filter);
+
+        if let Some(sort) = sort {
+            match sort.key {
+                TemperatureBreachSortField::StartDatetime => {
+                    apply_sort!(query, sort, temperature_breach_dsl::start_datetime)
+                }
+                TemperatureBreachSortField::ExtremeTemperature => {
+                    apply_sort!(query, sort, temperature_breach_dsl::extreme_temperature)
+                }
+            }
+        } else {
+            query = query.order(temperature_breach_dsl::start_datetime.desc())
+        }
+
+        let result = query
+            .offset(pagination.offset as i64)
+            .limit(pagination.limit as i64)
+            .load::<TemperatureBreachRow>(&self.connection.connection)?;
+
+        Ok(result.into_iter().map(to_domain).collect())
+    }
+}
+
+type BoxedTemperatureBreachQuery = temperature_breach::BoxedQuery<'static, DBType>;
+
+fn create_filtered_query(filter: Option<TemperatureBreachFilter>) -> BoxedTemperatureBreachQuery {
+    let mut query = temperature_breach::table.into_boxed();
+
+    if let Some(filter) = filter {
+        apply_equal_filter!(query, filter.id, temperature_breach_dsl::id);
+        apply_equal_filter!(query, filter.store_id, temperature_breach_dsl::store_id);
+        apply_equal_filter!(query, filter.sensor_id, temperature_breach_dsl::sensor_id);
+        apply_equal_filter!(
+            query,
+            filter.location_id,
+            temperature_breach_dsl::location_id
+        );
+        apply_equal_filter!(
+            query,
+            filter.temperature_breach_config_id,
+            temperature_breach_dsl::temperature_breach_config_id
+        );
+        apply_equal_filter!(query, filter.r#type, temperature_breach_dsl::type_);
+        apply_date_time_filter!(
+            query,
+            filter.start_datetime,
+            temperature_breach_dsl::start_datetime
+        );
+
+        if let Some(acknowledged) = filter.acknowledged {
+            query = query.filter(temperature_breach_dsl::acknowledged.eq(acknowledged));
+        }
+    }
+
+    query
+}
+
+pub fn to_domain(temperature_breach_row: TemperatureBreachRow) -> TemperatureBreach {
+    TemperatureBreach {
+        temperature_breach_row,
+    }
+}
+
+impl TemperatureBreachFilter {
+    pub fn new() -> TemperatureBreachFilter {
+        TemperatureBreachFilter::default()
+    }
+
+    pub fn id(mut self, filter: EqualFilter<String>) -> Self {
+        self.id = Some(filter);
+        self
+    }
+
+    pub fn store_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.store_id = Some(filter);
+        self
+    }
+
+    pub fn sensor_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.sensor_id = Some(filter);
+        self
+    }
+
+    pub fn location_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.location_id = Some(filter);
+        self
+    }
+
+    pub fn temperature_breach_config_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.temperature_breach_config_id = Some(filter);
+        self
+    }
+
+    pub fn r#type(mut self, filter: EqualFilter<TemperatureBreachType>) -> Self {
+        self.r#type = Some(filter);
+        self
+    }
+
+    pub fn start_datetime(mut self, filter: DatetimeFilter) -> Self {
+        self.start_datetime = Some(filter);
+        self
+    }
+
+    pub fn acknowledged(mut self, value: bool) -> Self {
+        self.acknowledged = Some(value);
+        self
+    }
+}
+
+impl TemperatureBreachType {
+    pub fn equal_to(&self) -> EqualFilter<Self> {
+        inline_init(|r: &mut EqualFilter<Self>| r.equal_to = Some(self.clone()))
+    }
+
+    pub fn equal_any(value: Vec<Self>) -> EqualFilter<Self> {
+        inline_init(|r: &mut EqualFilter<Self>| r.equal_any = Some(value))
+    }
+}
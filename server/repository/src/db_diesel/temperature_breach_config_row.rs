@@ -0,0 +1,92 @@
+use super::{
+    location_row::location, store_row::store,
+    temperature_breach_config_row::temperature_breach_config::dsl as temperature_breach_config_dsl,
+    StorageConnection,
+};
+
+use crate::repository_error::RepositoryError;
+
+use diesel::prelude::*;
+
+table! {
+    temperature_breach_config (id) {
+        id -> Text,
+        store_id -> Text,
+        location_id -> Nullable<Text>,
+        description -> Text,
+        minimum_temperature -> Double,
+        maximum_temperature -> Double,
+        duration_seconds -> Integer,
+        is_active -> Bool,
+    }
+}
+
+joinable!(temperature_breach_config -> store (store_id));
+joinable!(temperature_breach_config -> location (location_id));
+
+#[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Default)]
+#[changeset_options(treat_none_as_null = "true")]
+#[table_name = "temperature_breach_config"]
+pub struct TemperatureBreachConfigRow {
+    pub id: String,
+    pub store_id: String,
+    /// Location the range applies to, applies to all locations of the store if `None`
+    pub location_id: Option<String>,
+    pub description: String,
+    pub minimum_temperature: f64,
+    pub maximum_temperature: f64,
+    /// How long the temperature has to stay out of range before it's considered a breach
+    pub duration_seconds: i32,
+    pub is_active: bool,
+}
+
+pub struct TemperatureBreachConfigRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> TemperatureBreachConfigRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        TemperatureBreachConfigRowRepository { connection }
+    }
+
+    #[cfg(feature = "postgres")]
+    pub fn upsert_one(&self, row: &TemperatureBreachConfigRow) -> Result<(), RepositoryError> {
+        diesel::insert_into(temperature_breach_config_dsl::temperature_breach_config)
+            .values(row)
+            .on_conflict(temperature_breach_config_dsl::id)
+            .do_update()
+            .set(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    #[cfg(not(feature = "postgres"))]
+    pub fn upsert_one(&self, row: &TemperatureBreachConfigRow) -> Result<(), RepositoryError> {
+        diesel::replace_into(temperature_breach_config_dsl::temperature_breach_config)
+            .values(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    pub fn find_one_by_id(
+        &self,
+        id: &str,
+    ) -> Result<Option<TemperatureBreachConfigRow>, RepositoryError> {
+        let result = temperature_breach_config_dsl::temperature_breach_config
+            .filter(temperature_breach_config_dsl::id.eq(id))
+            .first(&self.connection.connection)
+            .optional()?;
+        Ok(result)
+    }
+
+    pub fn find_many_by_store_id(
+        &self,
+        store_id: &str,
+    ) -> Result<Vec<TemperatureBreachConfigRow>, RepositoryError> {
+        let result = temperature_breach_config_dsl::temperature_breach_config
+            .filter(temperature_breach_config_dsl::store_id.eq(store_id))
+            .order(temperature_breach_config_dsl::id.asc())
+            .load(&self.connection.connection)?;
+        Ok(result)
+    }
+}
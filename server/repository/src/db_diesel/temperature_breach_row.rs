@@ -0,0 +1,103 @@
+use super::{
+    location_row::location, sensor_row::sensor, store_row::store,
+    temperature_breach_config_row::temperature_breach_config,
+    temperature_breach_row::temperature_breach::dsl as temperature_breach_dsl, StorageConnection,
+};
+
+use crate::repository_error::RepositoryError;
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use diesel_derive_enum::DbEnum;
+use serde::{Deserialize, Serialize};
+
+table! {
+    temperature_breach (id) {
+        id -> Text,
+        store_id -> Text,
+        sensor_id -> Text,
+        location_id -> Nullable<Text>,
+        temperature_breach_config_id -> Text,
+        #[sql_name = "type"] type_ -> crate::db_diesel::temperature_breach_row::TemperatureBreachTypeMapping,
+        start_datetime -> Timestamp,
+        end_datetime -> Nullable<Timestamp>,
+        extreme_temperature -> Double,
+        acknowledged -> Bool,
+        comment -> Nullable<Text>,
+    }
+}
+
+joinable!(temperature_breach -> store (store_id));
+joinable!(temperature_breach -> sensor (sensor_id));
+joinable!(temperature_breach -> location (location_id));
+joinable!(temperature_breach -> temperature_breach_config (temperature_breach_config_id));
+
+#[derive(DbEnum, Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[DbValueStyle = "SCREAMING_SNAKE_CASE"]
+pub enum TemperatureBreachType {
+    #[default]
+    Cold,
+    Hot,
+}
+
+#[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Default)]
+#[changeset_options(treat_none_as_null = "true")]
+#[table_name = "temperature_breach"]
+pub struct TemperatureBreachRow {
+    pub id: String,
+    pub store_id: String,
+    pub sensor_id: String,
+    pub location_id: Option<String>,
+    pub temperature_breach_config_id: String,
+    #[column_name = "type_"]
+    pub r#type: TemperatureBreachType,
+    /// Datetime of the first log out of range
+    pub start_datetime: NaiveDateTime,
+    /// Datetime of the last log out of range, `None` while the breach is ongoing
+    pub end_datetime: Option<NaiveDateTime>,
+    /// Highest temperature of a hot breach or lowest temperature of a cold breach
+    pub extreme_temperature: f64,
+    /// Set once staff have reviewed the breach and the affected stock
+    pub acknowledged: bool,
+    pub comment: Option<String>,
+}
+
+pub struct TemperatureBreachRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> TemperatureBreachRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        TemperatureBreachRowRepository { connection }
+    }
+
+    #[cfg(feature = "postgres")]
+    pub fn upsert_one(&self, row: &TemperatureBreachRow) -> Result<(), RepositoryError> {
+        diesel::insert_into(temperature_breach_dsl::temperature_breach)
+            .values(row)
+            .on_conflict(temperature_breach_dsl::id)
+            .do_update()
+            .set(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    #[cfg(not(feature = "postgres"))]
+    pub fn upsert_one(&self, row: &TemperatureBreachRow) -> Result<(), RepositoryError> {
+        diesel::replace_into(temperature_breach_dsl::temperature_breach)
+            .values(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    pub fn find_one_by_id(
+        &self,
+        id: &str,
+    ) -> Result<Option<TemperatureBreachRow>, RepositoryError> {
+        let result = temperature_breach_dsl::temperature_breach
+            .filter(temperature_breach_dsl::id.eq(id))
+            .first(&self.connection.connection)
+            .optional()?;
+        Ok(result)
+    }
+}
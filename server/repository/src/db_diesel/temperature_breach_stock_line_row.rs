@@ -0,0 +1,71 @@
+use super::{
+    stock_line_row::stock_line, temperature_breach_row::temperature_breach,
+    temperature_breach_stock_line_row::temperature_breach_stock_line::dsl as temperature_breach_stock_line_dsl,
+    StorageConnection,
+};
+
+use crate::repository_error::RepositoryError;
+
+use diesel::prelude::*;
+
+table! {
+    temperature_breach_stock_line (id) {
+        id -> Text,
+        temperature_breach_id -> Text,
+        stock_line_id -> Text,
+    }
+}
+
+joinable!(temperature_breach_stock_line -> temperature_breach (temperature_breach_id));
+joinable!(temperature_breach_stock_line -> stock_line (stock_line_id));
+
+/// Stock line that was in the breached location during a temperature breach
+#[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Default)]
+#[table_name = "temperature_breach_stock_line"]
+pub struct TemperatureBreachStockLineRow {
+    pub id: String,
+    pub temperature_breach_id: String,
+    pub stock_line_id: String,
+}
+
+pub struct TemperatureBreachStockLineRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> TemperatureBreachStockLineRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        TemperatureBreachStockLineRowRepository { connection }
+    }
+
+    #[cfg(feature = "postgres")]
+    pub fn upsert_one(&self, row: &TemperatureBreachStockLineRow) -> Result<(), RepositoryError> {
+        diesel::insert_into(temperature_breach_stock_line_dsl::temperature_breach_stock_line)
+            .values(row)
+            .on_conflict(temperature_breach_stock_line_dsl::id)
+            .do_update()
+            .set(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    #[cfg(not(feature = "postgres"))]
+    pub fn upsert_one(&self, row: &TemperatureBreachStockLineRow) -> Result<(), RepositoryError> {
+        diesel::replace_into(temperature_breach_stock_line_dsl::temperature_breach_stock_line)
+            .values(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    pub fn find_many_by_temperature_breach_id(
+        &self,
+        temperature_breach_id: &str,
+    ) -> Result<Vec<TemperatureBreachStockLineRow>, RepositoryError> {
+        let result = temperature_breach_stock_line_dsl::temperature_breach_stock_line
+            .filter(
+                temperature_breach_stock_line_dsl::temperature_breach_id.eq(temperature_breach_id),
+            )
+            .order(temperature_breach_stock_line_dsl::stock_line_id.asc())
+            .load(&self.connection.connection)?;
+        Ok(result)
+    }
+}
@@ -0,0 +1,149 @@
+use diesel::prelude::*;
+
+use super::{
+    temperature_log_row::temperature_log::{self, dsl as temperature_log_dsl},
+    StorageConnection, TemperatureLogRow,
+};
+use crate::{
+    diesel_macros::{apply_date_time_filter, apply_equal_filter, apply_sort},
+    DBType, DatetimeFilter, EqualFilter, Pagination, RepositoryError, Sort,
+};
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct TemperatureLog {
+    pub temperature_log_row: TemperatureLogRow,
+}
+
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct TemperatureLogFilter {
+    pub id: Option<EqualFilter<String>>,
+    pub store_id: Option<EqualFilter<String>>,
+    pub sensor_id: Option<EqualFilter<String>>,
+    pub location_id: Option<EqualFilter<String>>,
+    pub temperature_breach_id: Option<EqualFilter<String>>,
+    pub datetime: Option<DatetimeFilter>,
+}
+
+#[derive(PartialEq, Debug)]
+pub enum TemperatureLogSortField {
+    Datetime,
+    Temperature,
+}
+
+pub type TemperatureLogSort = Sort<TemperatureLogSortField>;
+
+pub struct TemperatureLogRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> TemperatureLogRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        TemperatureLogRepository { connection }
+    }
+
+    pub fn count(&self, filter: Option<TemperatureLogFilter>) -> Result<i64, RepositoryError> {
+        let query = create_filtered_query(filter);
+
+        Ok(query.count().get_result(&self.connection.connection)?)
+    }
+
+    /// Returns all matching logs, oldest first
+    pub fn query_by_filter(
+        &self,
+        filter: TemperatureLogFilter,
+    ) -> Result<Vec<TemperatureLog>, RepositoryError> {
+        self.query(Pagination::all(), Some(filter), None)
+    }
+
+    pub fn query(
+        &self,
+        pagination: Pagination,
+        filter: Option<TemperatureLogFilter>,
+        sort: Option<TemperatureLogSort>,
+    ) -> Result<Vec<TemperatureLog>, RepositoryError> {
+        let mut query = create_filtered_query(filter);
+
+        if let Some(sort) = sort {
+            match sort.key {
+                TemperatureLogSortField::Datetime => {
+                    apply_sort!(query, sort, temperature_log_dsl::datetime)
+                }
+                TemperatureLogSortField::Temperature => {
+                    apply_sort!(query, sort, temperature_log_dsl::temperature)
+                }
+            }
+        } else {
+            query = query.order(temperature_log_dsl::datetime.asc())
+        }
+
+        let result = query
+            .offset(pagination.offset as i64)
+            .limit(pagination.limit as i64)
+            .load::<TemperatureLogRow>(&self.connection.connection)?;
+
+        Ok(result.into_iter().map(to_domain).collect())
+    }
+}
+
+type BoxedTemperatureLogQuery = temperature_log::BoxedQuery<'static, DBType>;
+
+fn create_filtered_query(filter: Option<TemperatureLogFilter>) -> BoxedTemperatureLogQuery {
+    let mut query = temperature_log::table.into_boxed();
+
+    if let Some(filter) = filter {
+        apply_equal_filter!(query, filter.id, temperature_log_dsl::id);
+        apply_equal_filter!(query, filter.store_id, temperature_log_dsl::store_id);
+        apply_equal_filter!(query, filter.sensor_id, temperature_log_dsl::sensor_id);
+        apply_equal_filter!(query, filter.location_id, temperature_log_dsl::location_id);
+        apply_equal_filter!(
+            query,
+            filter.temperature_breach_id,
+            temperature_log_dsl::temperature_breach_id
+        );
+        apply_date_time_filter!(query, filter.datetime, temperature_log_dsl::datetime);
+    }
+
+    query
+}
+
+pub fn to_domain(temperature_log_row: TemperatureLogRow) -> TemperatureLog {
+    TemperatureLog {
+        temperature_log_row,
+    }
+}
+
+impl TemperatureLogFilter {
+    pub fn new() -> TemperatureLogFilter {
+        TemperatureLogFilter::default()
+    }
+
+    pub fn id(mut self, filter: EqualFilter<String>) -> Self {
+        self.id = Some(filter);
+        self
+    }
+
+    pub fn store_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.store_id = Some(filter);
+        self
+    }
+
+    pub fn sensor_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.sensor_id = Some(filter);
+        self
+    }
+
+    pub fn location_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.location_id = Some(filter);
+        self
+    }
+
+    pub fn temperature_breach_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.temperature_breach_id = Some(filter);
+        self
+    }
+
+    pub fn datetime(mut self, filter: DatetimeFilter) -> Self {
+        self.datetime = Some(filter);
+        self
+    }
+}
@@ -0,0 +1,79 @@
+use super::{
+    location_row::location, sensor_row::sensor, store_row::store,
+    temperature_breach_row::temperature_breach,
+    temperature_log_row::temperature_log::dsl as temperature_log_dsl, StorageConnection,
+};
+
+use crate::repository_error::RepositoryError;
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+
+table! {
+    temperature_log (id) {
+        id -> Text,
+        store_id -> Text,
+        sensor_id -> Text,
+        location_id -> Nullable<Text>,
+        temperature -> Double,
+        datetime -> Timestamp,
+        temperature_breach_id -> Nullable<Text>,
+    }
+}
+
+joinable!(temperature_log -> store (store_id));
+joinable!(temperature_log -> sensor (sensor_id));
+joinable!(temperature_log -> location (location_id));
+joinable!(temperature_log -> temperature_breach (temperature_breach_id));
+
+#[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Default)]
+#[changeset_options(treat_none_as_null = "true")]
+#[table_name = "temperature_log"]
+pub struct TemperatureLogRow {
+    pub id: String,
+    pub store_id: String,
+    pub sensor_id: String,
+    /// Location of the sensor when the temperature was logged
+    pub location_id: Option<String>,
+    pub temperature: f64,
+    pub datetime: NaiveDateTime,
+    /// Breach this log is part of
+    pub temperature_breach_id: Option<String>,
+}
+
+pub struct TemperatureLogRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> TemperatureLogRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        TemperatureLogRowRepository { connection }
+    }
+
+    #[cfg(feature = "postgres")]
+    pub fn upsert_one(&self, row: &TemperatureLogRow) -> Result<(), RepositoryError> {
+        diesel::insert_into(temperature_log_dsl::temperature_log)
+            .values(row)
+            .on_conflict(temperature_log_dsl::id)
+            .do_update()
+            .set(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    #[cfg(not(feature = "postgres"))]
+    pub fn upsert_one(&self, row: &TemperatureLogRow) -> Result<(), RepositoryError> {
+        diesel::replace_into(temperature_log_dsl::temperature_log)
+            .values(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    pub fn find_one_by_id(&self, id: &str) -> Result<Option<TemperatureLogRow>, RepositoryError> {
+        let result = temperature_log_dsl::temperature_log
+            .filter(temperature_log_dsl::id.eq(id))
+            .first(&self.connection.connection)
+            .optional()?;
+        Ok(result)
+    }
+}
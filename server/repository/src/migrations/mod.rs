@@ -5,6 +5,19 @@ mod v1_01_02;
 mod v1_01_03;
 mod v1_01_05;
 mod v1_01_11;
+mod v1_01_12;
+mod v1_01_13;
+mod v1_01_14;
+mod v1_01_15;
+mod v1_01_16;
+mod v1_01_17;
+mod v1_01_18;
+mod v1_01_19;
+mod v1_01_20;
+mod v1_01_21;
+mod v1_01_22;
+mod v1_01_23;
+mod v1_01_24;
 mod version;
 pub(crate) use self::types::*;
 use self::v1_00_04::V1_00_04;
@@ -66,6 +79,19 @@ pub fn migrate(
         Box::new(V1_01_03),
         Box::new(v1_01_05::V1_01_05),
         Box::new(v1_01_11::V1_01_11),
+        Box::new(v1_01_12::V1_01_12),
+        Box::new(v1_01_13::V1_01_13),
+        Box::new(v1_01_14::V1_01_14),
+        Box::new(v1_01_15::V1_01_15),
+        Box::new(v1_01_16::V1_01_16),
+        Box::new(v1_01_17::V1_01_17),
+        Box::new(v1_01_18::V1_01_18),
+        Box::new(v1_01_19::V1_01_19),
+        Box::new(v1_01_20::V1_01_20),
+        Box::new(v1_01_21::V1_01_21),
+        Box::new(v1_01_22::V1_01_22),
+        Box::new(v1_01_23::V1_01_23),
+        Box::new(v1_01_24::V1_01_24),
     ];
 
     // Historic diesel migrations
@@ -0,0 +1,14 @@
+use crate::{migrations::sql, StorageConnection};
+
+pub(crate) fn migrate(connection: &StorageConnection) -> anyhow::Result<()> {
+    // Local store settings, not part of the synced legacy store preferences
+    sql!(
+        connection,
+        r#"
+            ALTER TABLE store_preference ADD COLUMN timezone TEXT;
+            ALTER TABLE store_preference ADD COLUMN currency_code TEXT;
+        "#
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,45 @@
+use crate::{migrations::sql, StorageConnection};
+
+pub(crate) fn migrate(connection: &StorageConnection) -> anyhow::Result<()> {
+    // Stock movements with a link to the source document, same datetimes as the stock_movement view
+    sql!(
+        connection,
+        r#"
+            CREATE VIEW item_ledger AS
+            SELECT * FROM (
+                SELECT
+                    invoice_line.id AS id,
+                    invoice_line.item_id AS item_id,
+                    invoice.store_id AS store_id,
+                    invoice_line.stock_line_id AS stock_line_id,
+                    invoice.id AS invoice_id,
+                    invoice.invoice_number AS invoice_number,
+                    invoice.type AS invoice_type,
+                    invoice.name_id AS name_id,
+                    name.name AS name,
+                    invoice_line.batch AS batch,
+                    invoice_line.expiry_date AS expiry_date,
+                    invoice_line.pack_size AS pack_size,
+                    invoice_line.number_of_packs AS number_of_packs,
+                    CASE
+                        WHEN invoice_line.type = 'STOCK_IN' THEN invoice_line.number_of_packs * invoice_line.pack_size
+                        ELSE invoice_line.number_of_packs * invoice_line.pack_size * -1
+                    END AS quantity,
+                    CASE
+                        WHEN invoice.type = 'OUTBOUND_SHIPMENT' THEN invoice.picked_datetime
+                        WHEN invoice.type = 'INBOUND_SHIPMENT' THEN invoice.delivered_datetime
+                        ELSE invoice.verified_datetime
+                    END AS datetime
+                FROM invoice_line
+                JOIN invoice ON invoice.id = invoice_line.invoice_id
+                JOIN name ON name.id = invoice.name_id
+                WHERE invoice_line.number_of_packs > 0
+                    AND invoice_line.type IN ('STOCK_IN', 'STOCK_OUT')
+                    AND invoice.type IN ('OUTBOUND_SHIPMENT', 'INBOUND_SHIPMENT', 'INVENTORY_ADDITION', 'INVENTORY_REDUCTION')
+            ) AS ledger
+            WHERE datetime IS NOT NULL;
+        "#
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,53 @@
+use crate::{migrations::sql, StorageConnection};
+
+pub(crate) fn migrate(connection: &StorageConnection) -> anyhow::Result<()> {
+    // Add location and cost price to the item_ledger view, used to reconstruct and value stock on
+    // hand at a past date. New columns are appended so postgres can replace the view in place.
+    let create_or_replace_view = if cfg!(feature = "postgres") {
+        "CREATE OR REPLACE VIEW"
+    } else {
+        "DROP VIEW item_ledger; CREATE VIEW"
+    };
+    sql!(
+        connection,
+        r#"
+            {create_or_replace_view} item_ledger AS
+            SELECT * FROM (
+                SELECT
+                    invoice_line.id AS id,
+                    invoice_line.item_id AS item_id,
+                    invoice.store_id AS store_id,
+                    invoice_line.stock_line_id AS stock_line_id,
+                    invoice.id AS invoice_id,
+                    invoice.invoice_number AS invoice_number,
+                    invoice.type AS invoice_type,
+                    invoice.name_id AS name_id,
+                    name.name AS name,
+                    invoice_line.batch AS batch,
+                    invoice_line.expiry_date AS expiry_date,
+                    invoice_line.pack_size AS pack_size,
+                    invoice_line.number_of_packs AS number_of_packs,
+                    CASE
+                        WHEN invoice_line.type = 'STOCK_IN' THEN invoice_line.number_of_packs * invoice_line.pack_size
+                        ELSE invoice_line.number_of_packs * invoice_line.pack_size * -1
+                    END AS quantity,
+                    CASE
+                        WHEN invoice.type = 'OUTBOUND_SHIPMENT' THEN invoice.picked_datetime
+                        WHEN invoice.type = 'INBOUND_SHIPMENT' THEN invoice.delivered_datetime
+                        ELSE invoice.verified_datetime
+                    END AS datetime,
+                    invoice_line.location_id AS location_id,
+                    invoice_line.cost_price_per_pack AS cost_price_per_pack
+                FROM invoice_line
+                JOIN invoice ON invoice.id = invoice_line.invoice_id
+                JOIN name ON name.id = invoice.name_id
+                WHERE invoice_line.number_of_packs > 0
+                    AND invoice_line.type IN ('STOCK_IN', 'STOCK_OUT')
+                    AND invoice.type IN ('OUTBOUND_SHIPMENT', 'INBOUND_SHIPMENT', 'INVENTORY_ADDITION', 'INVENTORY_REDUCTION')
+            ) AS ledger
+            WHERE datetime IS NOT NULL;
+        "#
+    )?;
+
+    Ok(())
+}
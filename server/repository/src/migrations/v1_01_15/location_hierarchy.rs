@@ -0,0 +1,37 @@
+use crate::{
+    migrations::{sql, DOUBLE},
+    StorageConnection,
+};
+
+pub(crate) fn migrate(connection: &StorageConnection) -> anyhow::Result<()> {
+    // POSTGRES
+    #[cfg(feature = "postgres")]
+    const LOCATION_TYPE: &str = "location_type";
+    #[cfg(feature = "postgres")]
+    sql!(
+        connection,
+        r#"
+                CREATE TYPE {LOCATION_TYPE} AS ENUM (
+                    'AMBIENT',
+                    'COLD',
+                    'FROZEN',
+                    'QUARANTINE'
+                );
+            "#
+    )?;
+    // SQLITE
+    #[cfg(not(feature = "postgres"))]
+    const LOCATION_TYPE: &str = "TEXT";
+
+    sql!(
+        connection,
+        r#"
+            ALTER TABLE location ADD parent_id TEXT;
+            ALTER TABLE location ADD location_type {LOCATION_TYPE} NOT NULL DEFAULT 'AMBIENT';
+            ALTER TABLE location ADD volume_capacity {DOUBLE};
+            ALTER TABLE location ADD pack_capacity {DOUBLE};
+        "#
+    )?;
+
+    Ok(())
+}
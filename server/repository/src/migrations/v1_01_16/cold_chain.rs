@@ -0,0 +1,78 @@
+use crate::{
+    migrations::{sql, DATETIME, DOUBLE},
+    StorageConnection,
+};
+
+pub(crate) fn migrate(connection: &StorageConnection) -> anyhow::Result<()> {
+    // POSTGRES
+    #[cfg(feature = "postgres")]
+    const TEMPERATURE_BREACH_TYPE: &str = "temperature_breach_type";
+    #[cfg(feature = "postgres")]
+    sql!(
+        connection,
+        r#"
+                CREATE TYPE {TEMPERATURE_BREACH_TYPE} AS ENUM (
+                    'COLD',
+                    'HOT'
+                );
+            "#
+    )?;
+    // SQLITE
+    #[cfg(not(feature = "postgres"))]
+    const TEMPERATURE_BREACH_TYPE: &str = "TEXT";
+
+    // Local cold chain records, not synced
+    sql!(
+        connection,
+        r#"
+            CREATE TABLE sensor (
+                id TEXT NOT NULL PRIMARY KEY,
+                name TEXT NOT NULL,
+                serial TEXT NOT NULL,
+                store_id TEXT NOT NULL REFERENCES store(id),
+                location_id TEXT REFERENCES location(id),
+                is_active BOOLEAN NOT NULL
+            );
+            CREATE TABLE temperature_breach_config (
+                id TEXT NOT NULL PRIMARY KEY,
+                store_id TEXT NOT NULL REFERENCES store(id),
+                location_id TEXT REFERENCES location(id),
+                description TEXT NOT NULL,
+                minimum_temperature {DOUBLE} NOT NULL,
+                maximum_temperature {DOUBLE} NOT NULL,
+                duration_seconds INTEGER NOT NULL,
+                is_active BOOLEAN NOT NULL
+            );
+            CREATE TABLE temperature_breach (
+                id TEXT NOT NULL PRIMARY KEY,
+                store_id TEXT NOT NULL REFERENCES store(id),
+                sensor_id TEXT NOT NULL REFERENCES sensor(id),
+                location_id TEXT REFERENCES location(id),
+                temperature_breach_config_id TEXT NOT NULL REFERENCES temperature_breach_config(id),
+                type {TEMPERATURE_BREACH_TYPE} NOT NULL,
+                start_datetime {DATETIME} NOT NULL,
+                end_datetime {DATETIME},
+                extreme_temperature {DOUBLE} NOT NULL,
+                acknowledged BOOLEAN NOT NULL,
+                comment TEXT
+            );
+            CREATE TABLE temperature_log (
+                id TEXT NOT NULL PRIMARY KEY,
+                store_id TEXT NOT NULL REFERENCES store(id),
+                sensor_id TEXT NOT NULL REFERENCES sensor(id),
+                location_id TEXT REFERENCES location(id),
+                temperature {DOUBLE} NOT NULL,
+                datetime {DATETIME} NOT NULL,
+                temperature_breach_id TEXT REFERENCES temperature_breach(id)
+            );
+            CREATE INDEX index_temperature_log_sensor_id_datetime ON temperature_log (sensor_id, datetime);
+            CREATE TABLE temperature_breach_stock_line (
+                id TEXT NOT NULL PRIMARY KEY,
+                temperature_breach_id TEXT NOT NULL REFERENCES temperature_breach(id),
+                stock_line_id TEXT NOT NULL REFERENCES stock_line(id)
+            );
+        "#
+    )?;
+
+    Ok(())
+}
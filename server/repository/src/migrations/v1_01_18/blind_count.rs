@@ -0,0 +1,24 @@
+use crate::{
+    migrations::{sql, DATETIME, DOUBLE},
+    StorageConnection,
+};
+
+pub(crate) fn migrate(connection: &StorageConnection) -> anyhow::Result<()> {
+    // Counts are local records, the reconciled count is synced with the stocktake line
+    sql!(
+        connection,
+        r#"
+            ALTER TABLE stocktake ADD is_blind_count BOOLEAN NOT NULL DEFAULT false;
+            CREATE TABLE stocktake_line_count (
+                id TEXT NOT NULL PRIMARY KEY,
+                stocktake_line_id TEXT NOT NULL REFERENCES stocktake_line(id),
+                user_id TEXT NOT NULL,
+                counted_number_of_packs {DOUBLE} NOT NULL,
+                datetime {DATETIME} NOT NULL
+            );
+            CREATE INDEX index_stocktake_line_count_stocktake_line_id ON stocktake_line_count (stocktake_line_id);
+        "#
+    )?;
+
+    Ok(())
+}
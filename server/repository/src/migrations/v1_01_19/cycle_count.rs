@@ -0,0 +1,50 @@
+use crate::{
+    migrations::{sql, DATE, DOUBLE},
+    StorageConnection,
+};
+
+pub(crate) fn migrate(connection: &StorageConnection) -> anyhow::Result<()> {
+    // POSTGRES
+    #[cfg(feature = "postgres")]
+    const ABC_CLASS_TYPE: &str = "abc_class_type";
+    #[cfg(feature = "postgres")]
+    sql!(
+        connection,
+        r#"
+                CREATE TYPE {ABC_CLASS_TYPE} AS ENUM (
+                    'A',
+                    'B',
+                    'C'
+                );
+            "#
+    )?;
+    // SQLITE
+    #[cfg(not(feature = "postgres"))]
+    const ABC_CLASS_TYPE: &str = "TEXT";
+
+    // Local cycle count records, not synced
+    sql!(
+        connection,
+        r#"
+            CREATE TABLE item_abc_class (
+                id TEXT NOT NULL PRIMARY KEY,
+                store_id TEXT NOT NULL REFERENCES store(id),
+                item_id TEXT NOT NULL REFERENCES item(id),
+                abc_class {ABC_CLASS_TYPE} NOT NULL,
+                consumption_value {DOUBLE} NOT NULL,
+                is_manual BOOLEAN NOT NULL,
+                UNIQUE (store_id, item_id)
+            );
+            CREATE TABLE cycle_count_config (
+                id TEXT NOT NULL PRIMARY KEY,
+                store_id TEXT NOT NULL REFERENCES store(id),
+                abc_class {ABC_CLASS_TYPE} NOT NULL,
+                count_frequency_days INTEGER NOT NULL,
+                last_generated_date {DATE},
+                UNIQUE (store_id, abc_class)
+            );
+        "#
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,22 @@
+use crate::StorageConnection;
+
+#[cfg(feature = "postgres")]
+pub(crate) fn migrate(connection: &StorageConnection) -> anyhow::Result<()> {
+    use crate::migrations::sql;
+    sql!(
+        connection,
+        r#"
+            ALTER TYPE activity_log_type ADD VALUE 'LOCATION_ON_HOLD';
+            ALTER TYPE activity_log_type ADD VALUE 'LOCATION_OFF_HOLD';
+            ALTER TYPE activity_log_type ADD VALUE 'INVOICE_ON_HOLD';
+            ALTER TYPE activity_log_type ADD VALUE 'INVOICE_OFF_HOLD';
+        "#
+    )?;
+
+    Ok(())
+}
+
+#[cfg(not(feature = "postgres"))]
+pub(crate) fn migrate(_connection: &StorageConnection) -> anyhow::Result<()> {
+    Ok(())
+}
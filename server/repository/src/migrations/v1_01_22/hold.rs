@@ -0,0 +1,56 @@
+use crate::{
+    migrations::{sql, DATE, DATETIME},
+    StorageConnection,
+};
+
+pub(crate) fn migrate(connection: &StorageConnection) -> anyhow::Result<()> {
+    // POSTGRES
+    #[cfg(feature = "postgres")]
+    const HOLD_RECORD_TYPE: &str = "hold_record_type";
+    #[cfg(feature = "postgres")]
+    sql!(
+        connection,
+        r#"
+                CREATE TYPE {HOLD_RECORD_TYPE} AS ENUM (
+                    'STOCK_LINE',
+                    'LOCATION',
+                    'INVOICE'
+                );
+            "#
+    )?;
+    // SQLITE
+    #[cfg(not(feature = "postgres"))]
+    const HOLD_RECORD_TYPE: &str = "TEXT";
+
+    // Local hold records, not synced. The on_hold flag of the held record stays the
+    // source of truth, hold rows carry the details of the current hold
+    sql!(
+        connection,
+        r#"
+            CREATE TABLE hold_reason (
+                id TEXT NOT NULL PRIMARY KEY,
+                reason TEXT NOT NULL,
+                is_active BOOLEAN NOT NULL
+            );
+            INSERT INTO hold_reason (id, reason, is_active) VALUES
+                ('QUARANTINE', 'Quarantine', true),
+                ('QA_PENDING', 'QA pending', true),
+                ('RECALL', 'Recall', true),
+                ('DAMAGED', 'Damaged', true),
+                ('RESERVED', 'Reserved', true);
+            CREATE TABLE hold (
+                id TEXT NOT NULL PRIMARY KEY,
+                record_type {HOLD_RECORD_TYPE} NOT NULL,
+                record_id TEXT NOT NULL,
+                hold_reason_id TEXT NOT NULL REFERENCES hold_reason(id),
+                store_id TEXT NOT NULL REFERENCES store(id),
+                user_id TEXT,
+                datetime {DATETIME} NOT NULL,
+                release_date {DATE},
+                UNIQUE (record_type, record_id)
+            );
+        "#
+    )?;
+
+    Ok(())
+}
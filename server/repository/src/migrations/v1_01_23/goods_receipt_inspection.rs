@@ -0,0 +1,44 @@
+use crate::{
+    migrations::{sql, DATETIME},
+    StorageConnection,
+};
+
+pub(crate) fn migrate(connection: &StorageConnection) -> anyhow::Result<()> {
+    // POSTGRES
+    #[cfg(feature = "postgres")]
+    const GOODS_RECEIPT_INSPECTION_STATUS: &str = "goods_receipt_inspection_status";
+    #[cfg(feature = "postgres")]
+    sql!(
+        connection,
+        r#"
+                CREATE TYPE {GOODS_RECEIPT_INSPECTION_STATUS} AS ENUM (
+                    'PENDING',
+                    'PASSED',
+                    'FAILED'
+                );
+            "#
+    )?;
+    // SQLITE
+    #[cfg(not(feature = "postgres"))]
+    const GOODS_RECEIPT_INSPECTION_STATUS: &str = "TEXT";
+
+    // Local store setting and inspection records, not synced
+    sql!(
+        connection,
+        r#"
+            ALTER TABLE store_preference ADD COLUMN requires_goods_receipt_inspection BOOLEAN NOT NULL DEFAULT false;
+            CREATE TABLE goods_receipt_inspection (
+                id TEXT NOT NULL PRIMARY KEY,
+                invoice_id TEXT NOT NULL,
+                invoice_line_id TEXT NOT NULL UNIQUE,
+                stock_line_id TEXT NOT NULL,
+                status {GOODS_RECEIPT_INSPECTION_STATUS} NOT NULL,
+                note TEXT,
+                user_id TEXT,
+                inspected_datetime {DATETIME}
+            );
+        "#
+    )?;
+
+    Ok(())
+}
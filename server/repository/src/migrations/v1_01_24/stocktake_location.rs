@@ -0,0 +1,13 @@
+use crate::{migrations::sql, StorageConnection};
+
+pub(crate) fn migrate(connection: &StorageConnection) -> anyhow::Result<()> {
+    // Location the stocktake was created for, new stock added on refresh is limited to it
+    sql!(
+        connection,
+        r#"
+            ALTER TABLE stocktake ADD location_id TEXT REFERENCES location(id);
+        "#
+    )?;
+
+    Ok(())
+}
@@ -1,4 +1,4 @@
-use crate::LocationRow;
+use crate::{LocationRow, LocationType};
 
 pub fn mock_location_1() -> LocationRow {
     LocationRow {
@@ -7,6 +7,10 @@ pub fn mock_location_1() -> LocationRow {
         name: "name_location_1".to_owned(),
         on_hold: false,
         store_id: "store_a".to_string(),
+        parent_id: None,
+        location_type: LocationType::Ambient,
+        volume_capacity: None,
+        pack_capacity: None,
     }
 }
 
@@ -17,6 +21,10 @@ pub fn mock_location_on_hold() -> LocationRow {
         name: "name_location_on_hold".to_owned(),
         on_hold: true,
         store_id: "store_a".to_string(),
+        parent_id: None,
+        location_type: LocationType::Ambient,
+        volume_capacity: None,
+        pack_capacity: None,
     }
 }
 
@@ -28,6 +36,10 @@ pub fn mock_location_2() -> LocationRow {
         name: "name_LocAtIOn_2".to_owned(),
         on_hold: false,
         store_id: "store_a".to_string(),
+        parent_id: None,
+        location_type: LocationType::Ambient,
+        volume_capacity: None,
+        pack_capacity: None,
     }
 }
 
@@ -39,6 +51,10 @@ pub fn mock_location_in_another_store() -> LocationRow {
         name: "store_b_location_name".to_owned(),
         on_hold: false,
         store_id: "store_b".to_string(),
+        parent_id: None,
+        location_type: LocationType::Ambient,
+        volume_capacity: None,
+        pack_capacity: None,
     }
 }
 
@@ -1,7 +1,10 @@
 use chrono::NaiveDate;
+use util::inline_init;
 
 use crate::StockLineRow;
 
+use super::{mock_item_a, mock_store_a};
+
 pub fn mock_stock_line_a() -> StockLineRow {
     StockLineRow {
         id: String::from("item_a_line_a"),
@@ -298,3 +301,16 @@ pub fn mock_stock_lines() -> Vec<StockLineRow> {
     mock_stock_lines.extend(mock_stock_line_location_is_on_hold());
     mock_stock_lines
 }
+
+/// Stock line of item A in store A with all packs available, for tests that add their own stock
+/// lines to the mock data
+pub fn mock_item_a_stock_line(id: &str, pack_size: i32, number_of_packs: f64) -> StockLineRow {
+    inline_init(|r: &mut StockLineRow| {
+        r.id = id.to_string();
+        r.item_id = mock_item_a().id;
+        r.store_id = mock_store_a().id;
+        r.pack_size = pack_size;
+        r.total_number_of_packs = number_of_packs;
+        r.available_number_of_packs = number_of_packs;
+    })
+}
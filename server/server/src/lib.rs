@@ -19,6 +19,7 @@ use repository::{get_storage_connection_manager, migrations::migrate};
 
 use service::{
     auth_data::AuthData,
+    cycle_count,
     processors::Processors,
     service_provider::ServiceProvider,
     settings::{is_develop, ServerSettings, Settings},
@@ -236,6 +237,8 @@ pub async fn start_server(
     // START SERVER
     info!("Initialising http server..",);
     let processors_task = processors.spawn(service_provider.clone().into_inner());
+    // Runs until the server stops, generating errors are logged by the scheduler
+    cycle_count::scheduler::spawn(service_provider.clone().into_inner());
     let synchroniser_task = synchroniser_driver.run(
         service_provider.clone().into_inner(),
         force_trigger_sync_on_startup,
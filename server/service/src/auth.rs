@@ -60,6 +60,8 @@ pub enum Resource {
     MutateOutboundShipment,
     // inbound shipment
     MutateInboundShipment,
+    // putting outbound or inbound shipments on hold
+    MutateInvoiceHold,
     // reporting
     Report,
     // view/edit server setting
@@ -69,6 +71,7 @@ pub enum Resource {
     ManualSync,
     QueryInventoryAdjustmentReasons,
     QueryStorePreferences,
+    MutateStorePreferences,
 }
 
 fn all_permissions() -> HashMap<Resource, PermissionDSL> {
@@ -230,6 +233,16 @@ fn all_permissions() -> HashMap<Resource, PermissionDSL> {
             PermissionDSL::HasPermission(Permission::InboundShipmentMutate),
         ]),
     );
+    map.insert(
+        Resource::MutateInvoiceHold,
+        PermissionDSL::And(vec![
+            PermissionDSL::HasStoreAccess,
+            PermissionDSL::Any(vec![
+                PermissionDSL::HasPermission(Permission::OutboundShipmentMutate),
+                PermissionDSL::HasPermission(Permission::InboundShipmentMutate),
+            ]),
+        ]),
+    );
 
     // report
     map.insert(
@@ -257,6 +270,13 @@ fn all_permissions() -> HashMap<Resource, PermissionDSL> {
         Resource::QueryStorePreferences,
         PermissionDSL::HasStoreAccess,
     );
+    map.insert(
+        Resource::MutateStorePreferences,
+        PermissionDSL::And(vec![
+            PermissionDSL::HasStoreAccess,
+            PermissionDSL::HasPermission(Permission::ServerAdmin),
+        ]),
+    );
 
     map
 }
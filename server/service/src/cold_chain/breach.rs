@@ -0,0 +1,389 @@
+use chrono::{NaiveDateTime, Utc};
+use repository::{
+    DatetimeFilter, EqualFilter, LocationMovementFilter, LocationMovementRepository, Pagination,
+    PaginationOption, RepositoryError, SensorRow, StockLine, StockLineFilter, StockLineRepository,
+    StorageConnection, TemperatureBreach, TemperatureBreachConfigRow,
+    TemperatureBreachConfigRowRepository, TemperatureBreachFilter, TemperatureBreachRepository,
+    TemperatureBreachRow, TemperatureBreachRowRepository, TemperatureBreachSort,
+    TemperatureBreachStockLineRow, TemperatureBreachStockLineRowRepository, TemperatureBreachType,
+    TemperatureLogFilter, TemperatureLogRepository, TemperatureLogRow, TemperatureLogRowRepository,
+    TemperatureLogSort, TemperatureLogSortField,
+};
+use util::uuid::uuid;
+
+use crate::{
+    get_default_pagination, i64_to_u32, service_provider::ServiceContext, ListError, ListResult,
+    SingleRecordError,
+};
+
+pub const MAX_LIMIT: u32 = 1000;
+pub const MIN_LIMIT: u32 = 1;
+
+#[derive(PartialEq, Debug)]
+pub enum AcknowledgeTemperatureBreachError {
+    TemperatureBreachDoesNotExist,
+    TemperatureBreachDoesNotBelongToCurrentStore,
+    UpdatedRecordNotFound,
+    DatabaseError(RepositoryError),
+}
+
+pub struct AcknowledgeTemperatureBreach {
+    pub id: String,
+    pub comment: Option<String>,
+}
+
+pub fn get_temperature_breaches(
+    ctx: &ServiceContext,
+    pagination: Option<PaginationOption>,
+    filter: Option<TemperatureBreachFilter>,
+    sort: Option<TemperatureBreachSort>,
+) -> Result<ListResult<TemperatureBreach>, ListError> {
+    let pagination = get_default_pagination(pagination, MAX_LIMIT, MIN_LIMIT)?;
+    let repository = TemperatureBreachRepository::new(&ctx.connection);
+
+    Ok(ListResult {
+        rows: repository.query(pagination, filter.clone(), sort)?,
+        count: i64_to_u32(repository.count(filter)?),
+    })
+}
+
+pub fn get_temperature_breach(
+    ctx: &ServiceContext,
+    id: String,
+) -> Result<TemperatureBreach, SingleRecordError> {
+    let mut result = TemperatureBreachRepository::new(&ctx.connection)
+        .query_by_filter(TemperatureBreachFilter::new().id(EqualFilter::equal_to(&id)))?;
+
+    if let Some(record) = result.pop() {
+        Ok(record)
+    } else {
+        Err(SingleRecordError::NotFound(id))
+    }
+}
+
+/// Stock lines that were in the breached location during the breach
+pub fn get_temperature_breach_stock_lines(
+    ctx: &ServiceContext,
+    temperature_breach_id: &str,
+) -> Result<Vec<StockLine>, SingleRecordError> {
+    let connection = &ctx.connection;
+    let breach = TemperatureBreachRowRepository::new(connection)
+        .find_one_by_id(temperature_breach_id)?
+        .ok_or_else(|| SingleRecordError::NotFound(temperature_breach_id.to_string()))?;
+    let stock_line_ids = TemperatureBreachStockLineRowRepository::new(connection)
+        .find_many_by_temperature_breach_id(&breach.id)?
+        .into_iter()
+        .map(|row| row.stock_line_id)
+        .collect();
+
+    let stock_lines = StockLineRepository::new(connection).query_by_filter(
+        StockLineFilter::new().id(EqualFilter::equal_any(stock_line_ids)),
+        Some(breach.store_id),
+    )?;
+    Ok(stock_lines)
+}
+
+pub fn acknowledge_temperature_breach(
+    ctx: &ServiceContext,
+    input: AcknowledgeTemperatureBreach,
+) -> Result<TemperatureBreach, AcknowledgeTemperatureBreachError> {
+    use AcknowledgeTemperatureBreachError::*;
+
+    let breach = ctx
+        .connection
+        .transaction_sync(|connection| {
+            let repo = TemperatureBreachRowRepository::new(connection);
+            let mut breach_row = repo
+                .find_one_by_id(&input.id)?
+                .ok_or(TemperatureBreachDoesNotExist)?;
+            if breach_row.store_id != ctx.store_id {
+                return Err(TemperatureBreachDoesNotBelongToCurrentStore);
+            }
+
+            breach_row.acknowledged = true;
+            breach_row.comment = input.comment;
+            repo.upsert_one(&breach_row)?;
+
+            get_temperature_breach(ctx, input.id).map_err(|error| match error {
+                SingleRecordError::DatabaseError(error) => DatabaseError(error),
+                SingleRecordError::NotFound(_) => UpdatedRecordNotFound,
+            })
+        })
+        .map_err(|error| error.to_inner_error())?;
+    Ok(breach)
+}
+
+/// Consecutive logs outside of the configured range
+struct OutOfRangeRun {
+    r#type: TemperatureBreachType,
+    logs: Vec<TemperatureLogRow>,
+    /// Datetime of the first log back in range
+    end_datetime: Option<NaiveDateTime>,
+}
+
+impl OutOfRangeRun {
+    fn start_datetime(&self) -> NaiveDateTime {
+        self.logs[0].datetime
+    }
+
+    fn last_log_datetime(&self) -> NaiveDateTime {
+        self.logs[self.logs.len() - 1].datetime
+    }
+
+    fn duration_seconds(&self) -> i64 {
+        let last_datetime = self.end_datetime.unwrap_or(self.last_log_datetime());
+        (last_datetime - self.start_datetime()).num_seconds()
+    }
+
+    /// Whether the breach covers any of the run, breaches end at the first log back in range
+    fn overlaps(&self, breach: &TemperatureBreachRow) -> bool {
+        breach.start_datetime <= self.last_log_datetime()
+            && !matches!(breach.end_datetime, Some(end) if end <= self.start_datetime())
+    }
+
+    fn extreme_temperature(&self) -> f64 {
+        let temperatures = self.logs.iter().map(|log| log.temperature);
+        match self.r#type {
+            TemperatureBreachType::Hot => temperatures.fold(f64::MIN, f64::max),
+            TemperatureBreachType::Cold => temperatures.fold(f64::MAX, f64::min),
+        }
+    }
+}
+
+fn breach_type(
+    config: &TemperatureBreachConfigRow,
+    temperature: f64,
+) -> Option<TemperatureBreachType> {
+    if temperature > config.maximum_temperature {
+        Some(TemperatureBreachType::Hot)
+    } else if temperature < config.minimum_temperature {
+        Some(TemperatureBreachType::Cold)
+    } else {
+        None
+    }
+}
+
+/// Splits logs (oldest first) into runs of out of range temperatures
+fn out_of_range_runs(
+    config: &TemperatureBreachConfigRow,
+    logs: Vec<TemperatureLogRow>,
+) -> Vec<OutOfRangeRun> {
+    let mut runs: Vec<OutOfRangeRun> = Vec::new();
+    let mut current: Option<OutOfRangeRun> = None;
+
+    for log in logs {
+        match breach_type(config, log.temperature) {
+            Some(r#type) => match &mut current {
+                Some(run) if run.r#type == r#type => run.logs.push(log),
+                _ => {
+                    if let Some(mut run) = current.take() {
+                        run.end_datetime = Some(log.datetime);
+                        runs.push(run);
+                    }
+                    current = Some(OutOfRangeRun {
+                        r#type,
+                        logs: vec![log],
+                        end_datetime: None,
+                    });
+                }
+            },
+            None => {
+                if let Some(mut run) = current.take() {
+                    run.end_datetime = Some(log.datetime);
+                    runs.push(run);
+                }
+            }
+        }
+    }
+    runs.extend(current);
+
+    runs
+}
+
+/// Datetime of the latest log at or before `datetime` that is within the config range,
+/// breaches overlapping `datetime` are re-evaluated from this point
+fn last_in_range_datetime(
+    connection: &StorageConnection,
+    sensor_id: &str,
+    config: &TemperatureBreachConfigRow,
+    datetime: NaiveDateTime,
+) -> Result<Option<NaiveDateTime>, RepositoryError> {
+    const PAGE_SIZE: u32 = 100;
+    let repo = TemperatureLogRepository::new(connection);
+    let filter = TemperatureLogFilter::new()
+        .sensor_id(EqualFilter::equal_to(sensor_id))
+        .datetime(DatetimeFilter::before_or_equal_to(datetime));
+
+    let mut offset = 0;
+    loop {
+        let logs = repo.query(
+            Pagination {
+                offset,
+                limit: PAGE_SIZE,
+            },
+            Some(filter.clone()),
+            Some(TemperatureLogSort {
+                key: TemperatureLogSortField::Datetime,
+                desc: Some(true),
+            }),
+        )?;
+        if let Some(log) = logs
+            .iter()
+            .find(|log| breach_type(config, log.temperature_log_row.temperature).is_none())
+        {
+            return Ok(Some(log.temperature_log_row.datetime));
+        }
+        if (logs.len() as u32) < PAGE_SIZE {
+            return Ok(None);
+        }
+        offset += PAGE_SIZE;
+    }
+}
+
+/// Records breaches of the sensor's active temperature ranges in logs from `from_datetime`
+/// onwards, linking the logs and the stock lines that were in the location during the breach.
+/// Returns the breaches that were created or updated.
+pub(crate) fn detect_temperature_breaches(
+    connection: &StorageConnection,
+    sensor: &SensorRow,
+    from_datetime: NaiveDateTime,
+) -> Result<Vec<TemperatureBreach>, RepositoryError> {
+    let configs = TemperatureBreachConfigRowRepository::new(connection)
+        .find_many_by_store_id(&sensor.store_id)?
+        .into_iter()
+        .filter(|config| {
+            config.is_active
+                && (config.location_id.is_none() || config.location_id == sensor.location_id)
+        });
+
+    let mut result = Vec::new();
+    for config in configs {
+        let window_start = last_in_range_datetime(connection, &sensor.id, &config, from_datetime)?;
+        let mut filter = TemperatureLogFilter::new().sensor_id(EqualFilter::equal_to(&sensor.id));
+        if let Some(window_start) = window_start {
+            filter = filter.datetime(DatetimeFilter::after_or_equal_to(window_start));
+        }
+        let logs = TemperatureLogRepository::new(connection)
+            .query_by_filter(filter)?
+            .into_iter()
+            .map(|log| log.temperature_log_row)
+            .collect();
+
+        for run in out_of_range_runs(&config, logs) {
+            if run.duration_seconds() < config.duration_seconds as i64 {
+                continue;
+            }
+            let breach_row = upsert_breach(connection, sensor, &config, &run)?;
+            result.push(TemperatureBreach {
+                temperature_breach_row: breach_row,
+            });
+        }
+    }
+
+    Ok(result)
+}
+
+fn upsert_breach(
+    connection: &StorageConnection,
+    sensor: &SensorRow,
+    config: &TemperatureBreachConfigRow,
+    run: &OutOfRangeRun,
+) -> Result<TemperatureBreachRow, RepositoryError> {
+    // Logs imported late can move the start or end of a breach, so the existing breach is the
+    // one of the sensor overlapping the run
+    let existing = TemperatureBreachRepository::new(connection)
+        .query_by_filter(
+            TemperatureBreachFilter::new()
+                .sensor_id(EqualFilter::equal_to(&sensor.id))
+                .temperature_breach_config_id(EqualFilter::equal_to(&config.id))
+                .start_datetime(DatetimeFilter::before_or_equal_to(run.last_log_datetime())),
+        )?
+        .into_iter()
+        .map(|breach| breach.temperature_breach_row)
+        .filter(|breach| breach.r#type == run.r#type && run.overlaps(breach))
+        .min_by_key(|breach| breach.start_datetime);
+
+    let location_id = run.logs[0].location_id.clone();
+    let breach_row = TemperatureBreachRow {
+        store_id: sensor.store_id.clone(),
+        sensor_id: sensor.id.clone(),
+        location_id: location_id.clone(),
+        temperature_breach_config_id: config.id.clone(),
+        r#type: run.r#type.clone(),
+        start_datetime: run.start_datetime(),
+        end_datetime: run.end_datetime,
+        extreme_temperature: run.extreme_temperature(),
+        ..existing.unwrap_or_else(|| TemperatureBreachRow {
+            id: uuid(),
+            ..Default::default()
+        })
+    };
+    TemperatureBreachRowRepository::new(connection).upsert_one(&breach_row)?;
+
+    let log_repo = TemperatureLogRowRepository::new(connection);
+    for log in &run.logs {
+        log_repo.upsert_one(&TemperatureLogRow {
+            temperature_breach_id: Some(breach_row.id.clone()),
+            ..log.clone()
+        })?;
+    }
+
+    if let Some(location_id) = location_id {
+        link_stock_lines(connection, &breach_row, &location_id)?;
+    }
+
+    Ok(breach_row)
+}
+
+/// Links stock lines that were moved into the location before the breach ended and weren't
+/// moved out before it started
+fn link_stock_lines(
+    connection: &StorageConnection,
+    breach: &TemperatureBreachRow,
+    location_id: &str,
+) -> Result<(), RepositoryError> {
+    let start = breach.start_datetime;
+    let end = breach
+        .end_datetime
+        .unwrap_or_else(|| Utc::now().naive_utc());
+
+    let movements = LocationMovementRepository::new(connection).query_by_filter(
+        LocationMovementFilter::new()
+            .store_id(EqualFilter::equal_to(&breach.store_id))
+            .location_id(EqualFilter::equal_to(location_id)),
+    )?;
+
+    let repo = TemperatureBreachStockLineRowRepository::new(connection);
+    let mut stock_line_ids: Vec<String> = repo
+        .find_many_by_temperature_breach_id(&breach.id)?
+        .into_iter()
+        .map(|row| row.stock_line_id)
+        .collect();
+
+    for movement in movements {
+        let movement = movement.location_movement_row;
+        let entered_before_end = !matches!(movement.enter_datetime, Some(enter) if enter > end);
+        let exited_after_start = !matches!(movement.exit_datetime, Some(exit) if exit < start);
+        if !entered_before_end
+            || !exited_after_start
+            || stock_line_ids.contains(&movement.stock_line_id)
+        {
+            continue;
+        }
+
+        repo.upsert_one(&TemperatureBreachStockLineRow {
+            id: uuid(),
+            temperature_breach_id: breach.id.clone(),
+            stock_line_id: movement.stock_line_id.clone(),
+        })?;
+        stock_line_ids.push(movement.stock_line_id);
+    }
+
+    Ok(())
+}
+
+impl From<RepositoryError> for AcknowledgeTemperatureBreachError {
+    fn from(error: RepositoryError) -> Self {
+        AcknowledgeTemperatureBreachError::DatabaseError(error)
+    }
+}
@@ -0,0 +1,93 @@
+use repository::{
+    RepositoryError, TemperatureBreachConfigRow, TemperatureBreachConfigRowRepository,
+};
+
+use super::validate::check_location_exists_in_store;
+use crate::service_provider::ServiceContext;
+
+#[derive(PartialEq, Debug)]
+pub enum UpsertTemperatureBreachConfigError {
+    TemperatureBreachConfigDoesNotBelongToCurrentStore,
+    LocationDoesNotExist,
+    /// Minimum temperature is above maximum temperature
+    InvalidTemperatureRange,
+    InvalidDuration,
+    DatabaseError(RepositoryError),
+}
+
+#[derive(Default, Clone)]
+pub struct UpsertTemperatureBreachConfig {
+    pub id: String,
+    pub location_id: Option<String>,
+    pub description: String,
+    pub minimum_temperature: f64,
+    pub maximum_temperature: f64,
+    pub duration_seconds: i32,
+    pub is_active: bool,
+}
+
+pub fn get_temperature_breach_configs(
+    ctx: &ServiceContext,
+) -> Result<Vec<TemperatureBreachConfigRow>, RepositoryError> {
+    TemperatureBreachConfigRowRepository::new(&ctx.connection).find_many_by_store_id(&ctx.store_id)
+}
+
+pub fn upsert_temperature_breach_config(
+    ctx: &ServiceContext,
+    input: UpsertTemperatureBreachConfig,
+) -> Result<TemperatureBreachConfigRow, UpsertTemperatureBreachConfigError> {
+    use UpsertTemperatureBreachConfigError::*;
+
+    let config = ctx
+        .connection
+        .transaction_sync(|connection| {
+            let repo = TemperatureBreachConfigRowRepository::new(connection);
+            if let Some(existing) = repo.find_one_by_id(&input.id)? {
+                if existing.store_id != ctx.store_id {
+                    return Err(TemperatureBreachConfigDoesNotBelongToCurrentStore);
+                }
+            }
+            if let Some(location_id) = &input.location_id {
+                if !check_location_exists_in_store(location_id, &ctx.store_id, connection)? {
+                    return Err(LocationDoesNotExist);
+                }
+            }
+            if input.minimum_temperature > input.maximum_temperature {
+                return Err(InvalidTemperatureRange);
+            }
+            if input.duration_seconds < 0 {
+                return Err(InvalidDuration);
+            }
+
+            let UpsertTemperatureBreachConfig {
+                id,
+                location_id,
+                description,
+                minimum_temperature,
+                maximum_temperature,
+                duration_seconds,
+                is_active,
+            } = input;
+            let row = TemperatureBreachConfigRow {
+                id,
+                store_id: ctx.store_id.clone(),
+                location_id,
+                description,
+                minimum_temperature,
+                maximum_temperature,
+                duration_seconds,
+                is_active,
+            };
+            repo.upsert_one(&row)?;
+
+            Ok(row)
+        })
+        .map_err(|error| error.to_inner_error())?;
+    Ok(config)
+}
+
+impl From<RepositoryError> for UpsertTemperatureBreachConfigError {
+    fn from(error: RepositoryError) -> Self {
+        UpsertTemperatureBreachConfigError::DatabaseError(error)
+    }
+}
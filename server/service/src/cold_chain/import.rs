@@ -0,0 +1,267 @@
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime};
+use util::csv::parse_csv;
+
+use super::temperature_log::{
+    insert_temperature_logs, InsertTemperatureLogs, InsertTemperatureLogsError,
+    InsertTemperatureLogsResult, TemperatureLogInput,
+};
+use crate::service_provider::ServiceContext;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum TemperatureLogFileFormat {
+    /// Delimited export with a header row containing a date (or date and time) column and a
+    /// temperature column
+    Csv,
+    /// Fridge-tag style text report with daily minimum and maximum temperatures
+    FridgeTag,
+}
+
+pub struct ImportTemperatureLogs {
+    pub sensor_id: String,
+    pub format: TemperatureLogFileFormat,
+    pub content: String,
+}
+
+pub fn import_temperature_logs(
+    ctx: &ServiceContext,
+    input: ImportTemperatureLogs,
+) -> Result<InsertTemperatureLogsResult, InsertTemperatureLogsError> {
+    let logs = match input.format {
+        TemperatureLogFileFormat::Csv => parse_csv_logs(&input.content),
+        TemperatureLogFileFormat::FridgeTag => parse_fridge_tag_logs(&input.content),
+    }
+    .map_err(InsertTemperatureLogsError::InvalidFile)?;
+
+    insert_temperature_logs(
+        ctx,
+        InsertTemperatureLogs {
+            sensor_id: input.sensor_id,
+            logs,
+        },
+    )
+}
+
+const DATETIME_FORMATS: &[&str] = &[
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%d %H:%M",
+    "%Y-%m-%dT%H:%M:%S",
+    "%Y-%m-%dT%H:%M",
+    "%d/%m/%Y %H:%M:%S",
+    "%d/%m/%Y %H:%M",
+    "%d.%m.%Y %H:%M:%S",
+    "%d.%m.%Y %H:%M",
+];
+
+const DATE_FORMATS: &[&str] = &["%Y-%m-%d", "%d/%m/%Y", "%d.%m.%Y"];
+
+fn parse_datetime(value: &str) -> Option<NaiveDateTime> {
+    let value = value.trim();
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(value) {
+        return Some(datetime.naive_utc());
+    }
+    DATETIME_FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+}
+
+fn parse_date(value: &str) -> Option<NaiveDate> {
+    DATE_FORMATS
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(value.trim(), format).ok())
+}
+
+fn parse_time(value: &str) -> Option<NaiveTime> {
+    ["%H:%M:%S", "%H:%M"]
+        .iter()
+        .find_map(|format| NaiveTime::parse_from_str(value.trim(), format).ok())
+}
+
+/// Accepts decimal commas, a leading plus sign and a unit suffix, e.g. "+4,5 °C"
+fn parse_temperature(value: &str) -> Option<f64> {
+    value
+        .trim()
+        .trim_end_matches(|c: char| c.is_alphabetic() || c == '°' || c == ',' || c == ' ')
+        .trim_start_matches('+')
+        .replace(',', ".")
+        .parse()
+        .ok()
+}
+
+struct CsvColumns {
+    datetime: usize,
+    time: Option<usize>,
+    temperature: usize,
+}
+
+fn find_columns(header: &[String]) -> Option<CsvColumns> {
+    let position = |matches: &dyn Fn(&str) -> bool| {
+        header
+            .iter()
+            .position(|column| matches(&column.to_lowercase()))
+    };
+
+    let temperature = position(&|column| {
+        column.contains("temp") || column.contains("°c") || column.contains("celsius")
+    })?;
+    let datetime = position(&|column| column.contains("date") || column.contains("timestamp"))?;
+    let time = position(&|column| column == "time");
+
+    Some(CsvColumns {
+        datetime,
+        time,
+        temperature,
+    })
+}
+
+/// Logger exports often start with a preamble (device name, serial number etc.), logs are
+/// read from the first row that looks like a header
+fn parse_csv_logs(content: &str) -> Result<Vec<TemperatureLogInput>, String> {
+    let rows = parse_csv(content);
+    let (header_index, columns) = rows
+        .iter()
+        .enumerate()
+        .find_map(|(index, row)| find_columns(row).map(|columns| (index, columns)))
+        .ok_or_else(|| "Could not find date and temperature columns".to_string())?;
+
+    let mut logs = Vec::new();
+    for (index, row) in rows.iter().enumerate().skip(header_index + 1) {
+        let line = index + 1;
+        let field = |column: usize| row.get(column).map(String::as_str).unwrap_or_default();
+
+        let datetime = match columns.time {
+            Some(time_column) => parse_date(field(columns.datetime))
+                .zip(parse_time(field(time_column)))
+                .map(|(date, time)| date.and_time(time)),
+            None => parse_datetime(field(columns.datetime)),
+        }
+        .ok_or_else(|| format!("Invalid date on row {}", line))?;
+        let temperature = parse_temperature(field(columns.temperature))
+            .ok_or_else(|| format!("Invalid temperature on row {}", line))?;
+
+        logs.push(TemperatureLogInput {
+            datetime,
+            temperature,
+        });
+    }
+
+    Ok(logs)
+}
+
+/// Reads the daily history of Fridge-tag style reports, each day gives two logs: the minimum
+/// and maximum temperatures at the time they were reached
+fn parse_fridge_tag_logs(content: &str) -> Result<Vec<TemperatureLogInput>, String> {
+    #[derive(Default)]
+    struct Day {
+        date: Option<NaiveDate>,
+        min: Option<f64>,
+        min_time: Option<NaiveTime>,
+        max: Option<f64>,
+        max_time: Option<NaiveTime>,
+    }
+
+    fn push_day(logs: &mut Vec<TemperatureLogInput>, day: Day) {
+        let date = match day.date {
+            Some(date) => date,
+            None => return,
+        };
+        let midday = NaiveTime::from_hms_opt(12, 0, 0).unwrap();
+        let mut day_logs = [
+            (day.min, day.min_time.unwrap_or(midday)),
+            (day.max, day.max_time.unwrap_or(midday)),
+        ];
+        day_logs.sort_by_key(|(_, time)| *time);
+        for (temperature, time) in day_logs.iter() {
+            if let Some(temperature) = temperature {
+                logs.push(TemperatureLogInput {
+                    datetime: date.and_time(*time),
+                    temperature: *temperature,
+                });
+            }
+        }
+    }
+
+    let mut logs = Vec::new();
+    let mut day = Day::default();
+    for (index, line) in content.lines().enumerate() {
+        let (key, value) = match line.trim().split_once(':') {
+            Some(key_value) => key_value,
+            None => continue,
+        };
+        let invalid = |name: &str| format!("Invalid {} on line {}", name, index + 1);
+
+        match key.trim() {
+            "Date" => {
+                push_day(&mut logs, std::mem::take(&mut day));
+                day.date = Some(parse_date(value).ok_or_else(|| invalid("date"))?);
+            }
+            "Min T" => day.min = Some(parse_temperature(value).ok_or_else(|| invalid("minimum"))?),
+            "Max T" => day.max = Some(parse_temperature(value).ok_or_else(|| invalid("maximum"))?),
+            "TS Min T" => day.min_time = parse_time(value),
+            "TS Max T" => day.max_time = parse_time(value),
+            _ => {}
+        }
+    }
+    push_day(&mut logs, day);
+
+    if logs.is_empty() {
+        return Err("Could not find daily temperatures".to_string());
+    }
+    Ok(logs)
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::NaiveDate;
+
+    use super::{parse_csv_logs, parse_fridge_tag_logs, TemperatureLogInput};
+
+    fn log(day: u32, hour: u32, minute: u32, temperature: f64) -> TemperatureLogInput {
+        TemperatureLogInput {
+            datetime: NaiveDate::from_ymd_opt(2023, 1, day)
+                .and_then(|date| date.and_hms_opt(hour, minute, 0))
+                .unwrap(),
+            temperature,
+        }
+    }
+
+    #[test]
+    fn test_parse_csv_logs() {
+        let content = "Logger,Cold room 1\nSerial,ABC123\n\nTimestamp,Temperature (°C)\n\
+            2023-01-01 10:00,4.5\n2023-01-01T10:15:00,+8\n01/01/2023 10:30,\"9,5 °C\"\n";
+        assert_eq!(
+            parse_csv_logs(content),
+            Ok(vec![
+                log(1, 10, 0, 4.5),
+                log(1, 10, 15, 8.0),
+                log(1, 10, 30, 9.5)
+            ])
+        );
+
+        let content = "Date;Time;Temp\n01.01.2023;10:00;-2,5\n";
+        assert_eq!(parse_csv_logs(content), Ok(vec![log(1, 10, 0, -2.5)]));
+
+        assert_eq!(
+            parse_csv_logs("Date,Temperature\n2023-01-01 10:00,hot"),
+            Err("Invalid temperature on row 2".to_string())
+        );
+        assert!(parse_csv_logs("a,b\n1,2").is_err());
+    }
+
+    #[test]
+    fn test_parse_fridge_tag_logs() {
+        let content = "Hist:\n Date: 2023-01-01\n  Min T: +02.5,\n  TS Min T: 06:10\n  \
+            Max T: +09.1,\n  TS Max T: 14:20\n Date: 2023-01-02\n  Max T: +05.0,\n  \
+            TS Max T: 03:00\n  Min T: +03.0,\n  TS Min T: 18:45\n";
+        assert_eq!(
+            parse_fridge_tag_logs(content),
+            Ok(vec![
+                log(1, 6, 10, 2.5),
+                log(1, 14, 20, 9.1),
+                log(2, 3, 0, 5.0),
+                log(2, 18, 45, 3.0),
+            ])
+        );
+
+        assert!(parse_fridge_tag_logs("nothing here").is_err());
+    }
+}
@@ -0,0 +1,138 @@
+use self::{
+    breach::{
+        acknowledge_temperature_breach, get_temperature_breach_stock_lines,
+        get_temperature_breaches, AcknowledgeTemperatureBreach, AcknowledgeTemperatureBreachError,
+    },
+    breach_config::{
+        get_temperature_breach_configs, upsert_temperature_breach_config,
+        UpsertTemperatureBreachConfig, UpsertTemperatureBreachConfigError,
+    },
+    import::{import_temperature_logs, ImportTemperatureLogs},
+    sensor::{
+        get_sensors, insert_sensor, update_sensor, InsertSensor, InsertSensorError, UpdateSensor,
+        UpdateSensorError,
+    },
+    temperature_log::{
+        get_temperature_logs, insert_temperature_logs, InsertTemperatureLogs,
+        InsertTemperatureLogsError, InsertTemperatureLogsResult,
+    },
+};
+
+use super::{ListError, ListResult};
+use crate::{service_provider::ServiceContext, SingleRecordError};
+use repository::{
+    PaginationOption, RepositoryError, Sensor, SensorFilter, SensorSort, StockLine,
+    TemperatureBreach, TemperatureBreachConfigRow, TemperatureBreachFilter, TemperatureBreachSort,
+    TemperatureLog, TemperatureLogFilter, TemperatureLogSort,
+};
+
+pub mod breach;
+pub mod breach_config;
+pub mod import;
+pub mod sensor;
+pub mod temperature_log;
+mod validate;
+
+pub trait ColdChainServiceTrait: Sync + Send {
+    fn get_sensors(
+        &self,
+        ctx: &ServiceContext,
+        pagination: Option<PaginationOption>,
+        filter: Option<SensorFilter>,
+        sort: Option<SensorSort>,
+    ) -> Result<ListResult<Sensor>, ListError> {
+        get_sensors(ctx, pagination, filter, sort)
+    }
+
+    fn insert_sensor(
+        &self,
+        ctx: &ServiceContext,
+        input: InsertSensor,
+    ) -> Result<Sensor, InsertSensorError> {
+        insert_sensor(ctx, input)
+    }
+
+    fn update_sensor(
+        &self,
+        ctx: &ServiceContext,
+        input: UpdateSensor,
+    ) -> Result<Sensor, UpdateSensorError> {
+        update_sensor(ctx, input)
+    }
+
+    fn get_temperature_breach_configs(
+        &self,
+        ctx: &ServiceContext,
+    ) -> Result<Vec<TemperatureBreachConfigRow>, RepositoryError> {
+        get_temperature_breach_configs(ctx)
+    }
+
+    fn upsert_temperature_breach_config(
+        &self,
+        ctx: &ServiceContext,
+        input: UpsertTemperatureBreachConfig,
+    ) -> Result<TemperatureBreachConfigRow, UpsertTemperatureBreachConfigError> {
+        upsert_temperature_breach_config(ctx, input)
+    }
+
+    fn get_temperature_logs(
+        &self,
+        ctx: &ServiceContext,
+        pagination: Option<PaginationOption>,
+        filter: Option<TemperatureLogFilter>,
+        sort: Option<TemperatureLogSort>,
+    ) -> Result<ListResult<TemperatureLog>, ListError> {
+        get_temperature_logs(ctx, pagination, filter, sort)
+    }
+
+    /// Records sensor logs and the temperature breaches they cause
+    fn insert_temperature_logs(
+        &self,
+        ctx: &ServiceContext,
+        input: InsertTemperatureLogs,
+    ) -> Result<InsertTemperatureLogsResult, InsertTemperatureLogsError> {
+        insert_temperature_logs(ctx, input)
+    }
+
+    /// Same as insert_temperature_logs with logs read from a logger export file
+    fn import_temperature_logs(
+        &self,
+        ctx: &ServiceContext,
+        input: ImportTemperatureLogs,
+    ) -> Result<InsertTemperatureLogsResult, InsertTemperatureLogsError> {
+        import_temperature_logs(ctx, input)
+    }
+
+    fn get_temperature_breaches(
+        &self,
+        ctx: &ServiceContext,
+        pagination: Option<PaginationOption>,
+        filter: Option<TemperatureBreachFilter>,
+        sort: Option<TemperatureBreachSort>,
+    ) -> Result<ListResult<TemperatureBreach>, ListError> {
+        get_temperature_breaches(ctx, pagination, filter, sort)
+    }
+
+    fn get_temperature_breach_stock_lines(
+        &self,
+        ctx: &ServiceContext,
+        temperature_breach_id: &str,
+    ) -> Result<Vec<StockLine>, SingleRecordError> {
+        get_temperature_breach_stock_lines(ctx, temperature_breach_id)
+    }
+
+    /// Marks the breach (and the affected stock) as reviewed
+    fn acknowledge_temperature_breach(
+        &self,
+        ctx: &ServiceContext,
+        input: AcknowledgeTemperatureBreach,
+    ) -> Result<TemperatureBreach, AcknowledgeTemperatureBreachError> {
+        acknowledge_temperature_breach(ctx, input)
+    }
+}
+
+pub struct ColdChainService {}
+impl ColdChainServiceTrait for ColdChainService {}
+
+#[cfg(test)]
+mod tests;
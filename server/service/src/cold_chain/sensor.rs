@@ -0,0 +1,189 @@
+use repository::{
+    EqualFilter, PaginationOption, RepositoryError, Sensor, SensorFilter, SensorRepository,
+    SensorRow, SensorRowRepository, SensorSort,
+};
+
+use super::validate::{check_location_exists_in_store, check_sensor_exists};
+use crate::{
+    get_default_pagination, i64_to_u32, service_provider::ServiceContext, ListError, ListResult,
+    SingleRecordError,
+};
+
+pub const MAX_LIMIT: u32 = 1000;
+pub const MIN_LIMIT: u32 = 1;
+
+#[derive(PartialEq, Debug)]
+pub enum InsertSensorError {
+    SensorAlreadyExists,
+    SensorWithSerialAlreadyExists,
+    LocationDoesNotExist,
+    CreatedRecordNotFound,
+    DatabaseError(RepositoryError),
+}
+
+#[derive(PartialEq, Debug)]
+pub enum UpdateSensorError {
+    SensorDoesNotExist,
+    SensorDoesNotBelongToCurrentStore,
+    LocationDoesNotExist,
+    UpdatedRecordNotFound,
+    DatabaseError(RepositoryError),
+}
+
+#[derive(Default)]
+pub struct InsertSensor {
+    pub id: String,
+    pub name: String,
+    pub serial: String,
+    pub location_id: Option<String>,
+}
+
+#[derive(Default)]
+pub struct UpdateSensor {
+    pub id: String,
+    pub name: Option<String>,
+    pub location_id: Option<Option<String>>,
+    pub is_active: Option<bool>,
+}
+
+pub fn get_sensors(
+    ctx: &ServiceContext,
+    pagination: Option<PaginationOption>,
+    filter: Option<SensorFilter>,
+    sort: Option<SensorSort>,
+) -> Result<ListResult<Sensor>, ListError> {
+    let pagination = get_default_pagination(pagination, MAX_LIMIT, MIN_LIMIT)?;
+    let repository = SensorRepository::new(&ctx.connection);
+
+    Ok(ListResult {
+        rows: repository.query(pagination, filter.clone(), sort)?,
+        count: i64_to_u32(repository.count(filter)?),
+    })
+}
+
+pub fn get_sensor(ctx: &ServiceContext, id: String) -> Result<Sensor, SingleRecordError> {
+    let mut result = SensorRepository::new(&ctx.connection)
+        .query_by_filter(SensorFilter::new().id(EqualFilter::equal_to(&id)))?;
+
+    if let Some(record) = result.pop() {
+        Ok(record)
+    } else {
+        Err(SingleRecordError::NotFound(id))
+    }
+}
+
+pub fn insert_sensor(
+    ctx: &ServiceContext,
+    input: InsertSensor,
+) -> Result<Sensor, InsertSensorError> {
+    let sensor = ctx
+        .connection
+        .transaction_sync(|connection| {
+            if check_sensor_exists(&input.id, connection)?.is_some() {
+                return Err(InsertSensorError::SensorAlreadyExists);
+            }
+            let sensors_with_serial = SensorRepository::new(connection).count(Some(
+                SensorFilter::new()
+                    .store_id(EqualFilter::equal_to(&ctx.store_id))
+                    .serial(EqualFilter::equal_to(&input.serial)),
+            ))?;
+            if sensors_with_serial > 0 {
+                return Err(InsertSensorError::SensorWithSerialAlreadyExists);
+            }
+            if let Some(location_id) = &input.location_id {
+                if !check_location_exists_in_store(location_id, &ctx.store_id, connection)? {
+                    return Err(InsertSensorError::LocationDoesNotExist);
+                }
+            }
+
+            let InsertSensor {
+                id,
+                name,
+                serial,
+                location_id,
+            } = input;
+            SensorRowRepository::new(connection).upsert_one(&SensorRow {
+                id: id.clone(),
+                name,
+                serial,
+                store_id: ctx.store_id.clone(),
+                location_id,
+                is_active: true,
+            })?;
+
+            get_sensor(ctx, id).map_err(InsertSensorError::from)
+        })
+        .map_err(|error| error.to_inner_error())?;
+    Ok(sensor)
+}
+
+pub fn update_sensor(
+    ctx: &ServiceContext,
+    input: UpdateSensor,
+) -> Result<Sensor, UpdateSensorError> {
+    let sensor = ctx
+        .connection
+        .transaction_sync(|connection| {
+            let mut sensor_row = check_sensor_exists(&input.id, connection)?
+                .ok_or(UpdateSensorError::SensorDoesNotExist)?;
+            if sensor_row.store_id != ctx.store_id {
+                return Err(UpdateSensorError::SensorDoesNotBelongToCurrentStore);
+            }
+            if let Some(Some(location_id)) = &input.location_id {
+                if !check_location_exists_in_store(location_id, &ctx.store_id, connection)? {
+                    return Err(UpdateSensorError::LocationDoesNotExist);
+                }
+            }
+
+            let UpdateSensor {
+                id,
+                name,
+                location_id,
+                is_active,
+            } = input;
+            if let Some(name) = name {
+                sensor_row.name = name;
+            }
+            if let Some(location_id) = location_id {
+                sensor_row.location_id = location_id;
+            }
+            if let Some(is_active) = is_active {
+                sensor_row.is_active = is_active;
+            }
+            SensorRowRepository::new(connection).upsert_one(&sensor_row)?;
+
+            get_sensor(ctx, id).map_err(UpdateSensorError::from)
+        })
+        .map_err(|error| error.to_inner_error())?;
+    Ok(sensor)
+}
+
+impl From<RepositoryError> for InsertSensorError {
+    fn from(error: RepositoryError) -> Self {
+        InsertSensorError::DatabaseError(error)
+    }
+}
+
+impl From<SingleRecordError> for InsertSensorError {
+    fn from(error: SingleRecordError) -> Self {
+        match error {
+            SingleRecordError::DatabaseError(error) => InsertSensorError::DatabaseError(error),
+            SingleRecordError::NotFound(_) => InsertSensorError::CreatedRecordNotFound,
+        }
+    }
+}
+
+impl From<RepositoryError> for UpdateSensorError {
+    fn from(error: RepositoryError) -> Self {
+        UpdateSensorError::DatabaseError(error)
+    }
+}
+
+impl From<SingleRecordError> for UpdateSensorError {
+    fn from(error: SingleRecordError) -> Self {
+        match error {
+            SingleRecordError::DatabaseError(error) => UpdateSensorError::DatabaseError(error),
+            SingleRecordError::NotFound(_) => UpdateSensorError::UpdatedRecordNotFound,
+        }
+    }
+}
@@ -0,0 +1,141 @@
+use chrono::NaiveDateTime;
+use repository::{
+    DatetimeFilter, EqualFilter, PaginationOption, RepositoryError, TemperatureBreach,
+    TemperatureLog, TemperatureLogFilter, TemperatureLogRepository, TemperatureLogRow,
+    TemperatureLogRowRepository, TemperatureLogSort,
+};
+use util::uuid::uuid;
+
+use super::{breach::detect_temperature_breaches, validate::check_sensor_exists};
+use crate::{
+    get_default_pagination, i64_to_u32, service_provider::ServiceContext, ListError, ListResult,
+};
+
+pub const MAX_LIMIT: u32 = 5000;
+pub const MIN_LIMIT: u32 = 1;
+
+#[derive(PartialEq, Debug)]
+pub enum InsertTemperatureLogsError {
+    SensorDoesNotExist,
+    SensorDoesNotBelongToCurrentStore,
+    /// Imported file could not be read, with the reason
+    InvalidFile(String),
+    NoTemperatureLogs,
+    DatabaseError(RepositoryError),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TemperatureLogInput {
+    pub datetime: NaiveDateTime,
+    pub temperature: f64,
+}
+
+pub struct InsertTemperatureLogs {
+    pub sensor_id: String,
+    pub logs: Vec<TemperatureLogInput>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct InsertTemperatureLogsResult {
+    /// Logs already recorded for the sensor at the same datetime are skipped
+    pub number_of_logs_inserted: u32,
+    /// Breaches created or extended by the new logs, stock in the breached location should
+    /// be reviewed
+    pub temperature_breaches: Vec<TemperatureBreach>,
+}
+
+pub fn get_temperature_logs(
+    ctx: &ServiceContext,
+    pagination: Option<PaginationOption>,
+    filter: Option<TemperatureLogFilter>,
+    sort: Option<TemperatureLogSort>,
+) -> Result<ListResult<TemperatureLog>, ListError> {
+    let pagination = get_default_pagination(pagination, MAX_LIMIT, MIN_LIMIT)?;
+    let repository = TemperatureLogRepository::new(&ctx.connection);
+
+    Ok(ListResult {
+        rows: repository.query(pagination, filter.clone(), sort)?,
+        count: i64_to_u32(repository.count(filter)?),
+    })
+}
+
+pub fn insert_temperature_logs(
+    ctx: &ServiceContext,
+    input: InsertTemperatureLogs,
+) -> Result<InsertTemperatureLogsResult, InsertTemperatureLogsError> {
+    use InsertTemperatureLogsError::*;
+
+    let result = ctx
+        .connection
+        .transaction_sync(|connection| {
+            let sensor =
+                check_sensor_exists(&input.sensor_id, connection)?.ok_or(SensorDoesNotExist)?;
+            if sensor.store_id != ctx.store_id {
+                return Err(SensorDoesNotBelongToCurrentStore);
+            }
+            let min_datetime = input
+                .logs
+                .iter()
+                .map(|log| log.datetime)
+                .min()
+                .ok_or(NoTemperatureLogs)?;
+            let max_datetime = input.logs.iter().map(|log| log.datetime).max().unwrap();
+
+            let mut existing_datetimes: Vec<NaiveDateTime> =
+                TemperatureLogRepository::new(connection)
+                    .query_by_filter(
+                        TemperatureLogFilter::new()
+                            .sensor_id(EqualFilter::equal_to(&sensor.id))
+                            .datetime(DatetimeFilter::date_range(min_datetime, max_datetime)),
+                    )?
+                    .into_iter()
+                    .map(|log| log.temperature_log_row.datetime)
+                    .collect();
+
+            let repo = TemperatureLogRowRepository::new(connection);
+            let mut number_of_logs_inserted = 0;
+            let mut from_datetime: Option<NaiveDateTime> = None;
+            for TemperatureLogInput {
+                datetime,
+                temperature,
+            } in input.logs
+            {
+                if existing_datetimes.contains(&datetime) {
+                    continue;
+                }
+                repo.upsert_one(&TemperatureLogRow {
+                    id: uuid(),
+                    store_id: sensor.store_id.clone(),
+                    sensor_id: sensor.id.clone(),
+                    location_id: sensor.location_id.clone(),
+                    temperature,
+                    datetime,
+                    temperature_breach_id: None,
+                })?;
+                existing_datetimes.push(datetime);
+                number_of_logs_inserted += 1;
+                from_datetime = Some(from_datetime.map_or(datetime, |from| from.min(datetime)));
+            }
+
+            // Only breaches overlapping the new logs can change
+            let temperature_breaches = match from_datetime {
+                Some(from_datetime) => {
+                    detect_temperature_breaches(connection, &sensor, from_datetime)?
+                }
+                None => Vec::new(),
+            };
+
+            Ok(InsertTemperatureLogsResult {
+                number_of_logs_inserted,
+                temperature_breaches,
+            })
+        })
+        .map_err(|error| error.to_inner_error())?;
+    Ok(result)
+}
+
+impl From<RepositoryError> for InsertTemperatureLogsError {
+    fn from(error: RepositoryError) -> Self {
+        InsertTemperatureLogsError::DatabaseError(error)
+    }
+}
@@ -0,0 +1,291 @@
+#[cfg(test)]
+mod query {
+    use chrono::{NaiveDate, NaiveDateTime};
+    use repository::{
+        mock::{mock_item_a_stock_line, mock_store_a, MockData, MockDataInserts},
+        test_db::setup_all_with_data,
+        EqualFilter, LocationMovementRow, LocationMovementRowRepository, LocationRow,
+        TemperatureBreachFilter, TemperatureBreachType, TemperatureLogFilter,
+    };
+    use util::{inline_edit, inline_init};
+
+    use crate::{
+        cold_chain::{
+            breach::AcknowledgeTemperatureBreach,
+            breach_config::{UpsertTemperatureBreachConfig, UpsertTemperatureBreachConfigError},
+            import::{ImportTemperatureLogs, TemperatureLogFileFormat},
+            sensor::{InsertSensor, InsertSensorError},
+            temperature_log::{InsertTemperatureLogs, TemperatureLogInput},
+        },
+        service_provider::ServiceProvider,
+    };
+
+    fn datetime(day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2023, 1, day)
+            .and_then(|date| date.and_hms_opt(hour, minute, 0))
+            .unwrap()
+    }
+
+    fn logs(logs: &[(NaiveDateTime, f64)]) -> Vec<TemperatureLogInput> {
+        logs.iter()
+            .map(|(datetime, temperature)| TemperatureLogInput {
+                datetime: *datetime,
+                temperature: *temperature,
+            })
+            .collect()
+    }
+
+    fn movement(
+        stock_line_id: &str,
+        enter_datetime: NaiveDateTime,
+        exit_datetime: Option<NaiveDateTime>,
+    ) -> LocationMovementRow {
+        LocationMovementRow {
+            id: format!("{}_movement", stock_line_id),
+            store_id: mock_store_a().id,
+            stock_line_id: stock_line_id.to_string(),
+            location_id: Some("fridge".to_string()),
+            enter_datetime: Some(enter_datetime),
+            exit_datetime,
+        }
+    }
+
+    #[actix_rt::test]
+    async fn cold_chain_service_breaches() {
+        let (_, connection, connection_manager, _) = setup_all_with_data(
+            "cold_chain_service_breaches",
+            MockDataInserts::none().stores().items().names().units(),
+            inline_init(|r: &mut MockData| {
+                r.locations = vec![inline_init(|r: &mut LocationRow| {
+                    r.id = "fridge".to_string();
+                    r.code = "fridge".to_string();
+                    r.store_id = mock_store_a().id;
+                })];
+                r.stock_lines = ["in_fridge", "moved_out", "moved_in_later"]
+                    .iter()
+                    .map(|id| {
+                        inline_edit(&mock_item_a_stock_line(id, 1, 10.0), |mut u| {
+                            u.location_id = Some("fridge".to_string());
+                            u
+                        })
+                    })
+                    .collect();
+            }),
+        )
+        .await;
+
+        let movement_repo = LocationMovementRowRepository::new(&connection);
+        movement_repo
+            .upsert_one(&movement("in_fridge", datetime(1, 0, 0), None))
+            .unwrap();
+        movement_repo
+            .upsert_one(&movement(
+                "moved_out",
+                datetime(1, 0, 0),
+                Some(datetime(1, 9, 0)),
+            ))
+            .unwrap();
+        movement_repo
+            .upsert_one(&movement("moved_in_later", datetime(2, 0, 0), None))
+            .unwrap();
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider
+            .context(mock_store_a().id, "".to_string())
+            .unwrap();
+        let service = service_provider.cold_chain_service;
+
+        service
+            .insert_sensor(
+                &context,
+                InsertSensor {
+                    id: "sensor".to_string(),
+                    name: "Fridge sensor".to_string(),
+                    serial: "ABC123".to_string(),
+                    location_id: Some("fridge".to_string()),
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            service.insert_sensor(
+                &context,
+                InsertSensor {
+                    id: "other_sensor".to_string(),
+                    serial: "ABC123".to_string(),
+                    ..Default::default()
+                },
+            ),
+            Err(InsertSensorError::SensorWithSerialAlreadyExists)
+        );
+
+        let config = UpsertTemperatureBreachConfig {
+            id: "config".to_string(),
+            description: "Vaccines".to_string(),
+            minimum_temperature: 2.0,
+            maximum_temperature: 8.0,
+            duration_seconds: 30 * 60,
+            is_active: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            service.upsert_temperature_breach_config(
+                &context,
+                UpsertTemperatureBreachConfig {
+                    minimum_temperature: 10.0,
+                    ..config.clone()
+                }
+            ),
+            Err(UpsertTemperatureBreachConfigError::InvalidTemperatureRange)
+        );
+        service
+            .upsert_temperature_breach_config(&context, config)
+            .unwrap();
+
+        // Out of range for less than the configured duration
+        let result = service
+            .insert_temperature_logs(
+                &context,
+                InsertTemperatureLogs {
+                    sensor_id: "sensor".to_string(),
+                    logs: logs(&[
+                        (datetime(1, 10, 0), 5.0),
+                        (datetime(1, 10, 10), 9.0),
+                        (datetime(1, 10, 20), 9.5),
+                    ]),
+                },
+            )
+            .unwrap();
+        assert_eq!(result.number_of_logs_inserted, 3);
+        assert_eq!(result.temperature_breaches, vec![]);
+
+        // Later logs extend the out of range period past the duration
+        let result = service
+            .insert_temperature_logs(
+                &context,
+                InsertTemperatureLogs {
+                    sensor_id: "sensor".to_string(),
+                    logs: logs(&[
+                        (datetime(1, 10, 20), 9.5),
+                        (datetime(1, 10, 40), 10.5),
+                        (datetime(1, 11, 0), 5.0),
+                        (datetime(1, 12, 0), 1.0),
+                        (datetime(1, 12, 5), 5.0),
+                    ]),
+                },
+            )
+            .unwrap();
+        assert_eq!(result.number_of_logs_inserted, 4);
+        assert_eq!(result.temperature_breaches.len(), 1);
+        let hot_breach = result.temperature_breaches[0]
+            .temperature_breach_row
+            .clone();
+        assert_eq!(hot_breach.r#type, TemperatureBreachType::Hot);
+        assert_eq!(hot_breach.start_datetime, datetime(1, 10, 10));
+        assert_eq!(hot_breach.end_datetime, Some(datetime(1, 11, 0)));
+        assert_eq!(hot_breach.extreme_temperature, 10.5);
+        assert_eq!(hot_breach.location_id, Some("fridge".to_string()));
+
+        let breach_logs = service
+            .get_temperature_logs(
+                &context,
+                None,
+                Some(
+                    TemperatureLogFilter::new()
+                        .temperature_breach_id(EqualFilter::equal_to(&hot_breach.id)),
+                ),
+                None,
+            )
+            .unwrap();
+        assert_eq!(breach_logs.count, 3);
+
+        let stock_line_ids: Vec<String> = service
+            .get_temperature_breach_stock_lines(&context, &hot_breach.id)
+            .unwrap()
+            .into_iter()
+            .map(|stock_line| stock_line.stock_line_row.id)
+            .collect();
+        assert_eq!(stock_line_ids, vec!["in_fridge".to_string()]);
+
+        // Ongoing cold breach from an imported file, existing breach is not duplicated
+        let result = service
+            .import_temperature_logs(
+                &context,
+                ImportTemperatureLogs {
+                    sensor_id: "sensor".to_string(),
+                    format: TemperatureLogFileFormat::Csv,
+                    content: "Date,Temperature\n2023-01-01 10:40,10.5\n\
+                        2023-01-03 08:00,1.0\n2023-01-03 09:00,0.5\n"
+                        .to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(result.number_of_logs_inserted, 2);
+        assert_eq!(result.temperature_breaches.len(), 1);
+        let cold_breach = result.temperature_breaches[0]
+            .temperature_breach_row
+            .clone();
+        assert_eq!(cold_breach.r#type, TemperatureBreachType::Cold);
+        assert_eq!(cold_breach.end_datetime, None);
+        assert_eq!(cold_breach.extreme_temperature, 0.5);
+
+        let mut stock_line_ids: Vec<String> = service
+            .get_temperature_breach_stock_lines(&context, &cold_breach.id)
+            .unwrap()
+            .into_iter()
+            .map(|stock_line| stock_line.stock_line_row.id)
+            .collect();
+        stock_line_ids.sort();
+        assert_eq!(
+            stock_line_ids,
+            vec!["in_fridge".to_string(), "moved_in_later".to_string()]
+        );
+
+        // A late log moves the start of the hot breach, the existing breach is updated
+        let result = service
+            .insert_temperature_logs(
+                &context,
+                InsertTemperatureLogs {
+                    sensor_id: "sensor".to_string(),
+                    logs: logs(&[(datetime(1, 10, 5), 9.0)]),
+                },
+            )
+            .unwrap();
+        assert_eq!(result.number_of_logs_inserted, 1);
+        let moved_breach = result
+            .temperature_breaches
+            .iter()
+            .map(|breach| &breach.temperature_breach_row)
+            .find(|breach| breach.r#type == TemperatureBreachType::Hot)
+            .unwrap();
+        assert_eq!(moved_breach.id, hot_breach.id);
+        assert_eq!(moved_breach.start_datetime, datetime(1, 10, 5));
+        assert_eq!(
+            service
+                .get_temperature_breaches(&context, None, None, None)
+                .unwrap()
+                .count,
+            2
+        );
+
+        // Acknowledged breaches no longer need review
+        service
+            .acknowledge_temperature_breach(
+                &context,
+                AcknowledgeTemperatureBreach {
+                    id: hot_breach.id.clone(),
+                    comment: Some("Stock checked".to_string()),
+                },
+            )
+            .unwrap();
+        let to_review = service
+            .get_temperature_breaches(
+                &context,
+                None,
+                Some(TemperatureBreachFilter::new().acknowledged(false)),
+                None,
+            )
+            .unwrap();
+        assert_eq!(to_review.count, 1);
+        assert_eq!(to_review.rows[0].temperature_breach_row.id, cold_breach.id);
+    }
+}
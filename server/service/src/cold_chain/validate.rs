@@ -0,0 +1,20 @@
+use repository::{
+    LocationRowRepository, RepositoryError, SensorRow, SensorRowRepository, StorageConnection,
+};
+
+pub fn check_sensor_exists(
+    id: &str,
+    connection: &StorageConnection,
+) -> Result<Option<SensorRow>, RepositoryError> {
+    SensorRowRepository::new(connection).find_one_by_id(id)
+}
+
+pub fn check_location_exists_in_store(
+    location_id: &str,
+    store_id: &str,
+    connection: &StorageConnection,
+) -> Result<bool, RepositoryError> {
+    let location = LocationRowRepository::new(connection).find_one_by_id(location_id)?;
+
+    Ok(matches!(location, Some(location) if location.store_id == store_id))
+}
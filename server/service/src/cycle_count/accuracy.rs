@@ -0,0 +1,115 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::NaiveDate;
+use repository::{
+    AbcClass, DatetimeFilter, EqualFilter, ItemAbcClassRowRepository, Pagination, RepositoryError,
+    StocktakeFilter, StocktakeLineFilter, StocktakeLineRepository, StocktakeRepository,
+    StocktakeStatus,
+};
+
+use crate::service_provider::ServiceContext;
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct CycleCountAccuracy {
+    pub abc_class: AbcClass,
+    pub number_of_items: u32,
+    /// Items of the class counted in the period
+    pub number_of_counted_items: u32,
+    pub number_of_counted_lines: u32,
+    /// Counted lines matching the snapshot
+    pub number_of_accurate_lines: u32,
+    /// Share of accurate lines, `None` when no lines were counted
+    pub accuracy: Option<f64>,
+    /// Sum of the absolute differences between counted and snapshot packs
+    pub total_absolute_variance: f64,
+}
+
+/// Count accuracy per ABC class of the stocktakes finalised between `from` and `to` (inclusive).
+/// Items are grouped by their current class, unclassified items are not reported.
+pub fn get_cycle_count_accuracy(
+    ctx: &ServiceContext,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<Vec<CycleCountAccuracy>, RepositoryError> {
+    let connection = &ctx.connection;
+    let class_by_item: HashMap<String, AbcClass> = ItemAbcClassRowRepository::new(connection)
+        .find_many_by_store_id(&ctx.store_id)?
+        .into_iter()
+        .map(|row| (row.item_id, row.abc_class))
+        .collect();
+
+    let stocktake_ids: Vec<String> = StocktakeRepository::new(connection)
+        .query(
+            Pagination::all(),
+            Some(
+                StocktakeFilter::new()
+                    .store_id(EqualFilter::equal_to(&ctx.store_id))
+                    .status(StocktakeStatus::Finalised.equal_to())
+                    .finalised_datetime(DatetimeFilter::date_range(
+                        from.and_hms_opt(0, 0, 0).unwrap(),
+                        to.and_hms_opt(23, 59, 59).unwrap(),
+                    )),
+            ),
+            None,
+        )?
+        .into_iter()
+        .map(|stocktake| stocktake.id)
+        .collect();
+    let lines = StocktakeLineRepository::new(connection).query_by_filter(
+        StocktakeLineFilter::new().stocktake_id(EqualFilter::equal_any(stocktake_ids)),
+    )?;
+
+    let mut result: Vec<CycleCountAccuracy> = [AbcClass::A, AbcClass::B, AbcClass::C]
+        .iter()
+        .copied()
+        .map(|abc_class| CycleCountAccuracy {
+            abc_class,
+            number_of_items: class_by_item
+                .values()
+                .filter(|class| **class == abc_class)
+                .count() as u32,
+            number_of_counted_items: 0,
+            number_of_counted_lines: 0,
+            number_of_accurate_lines: 0,
+            accuracy: None,
+            total_absolute_variance: 0.0,
+        })
+        .collect();
+    let mut counted_items = HashSet::<String>::new();
+
+    for line in lines.into_iter().map(|line| line.line) {
+        let (abc_class, counted_number_of_packs) = match (
+            class_by_item.get(&line.item_id),
+            line.counted_number_of_packs,
+        ) {
+            (Some(abc_class), Some(counted_number_of_packs)) => {
+                (abc_class, counted_number_of_packs)
+            }
+            _ => continue,
+        };
+        let accuracy = match result.iter_mut().find(|r| r.abc_class == *abc_class) {
+            Some(accuracy) => accuracy,
+            None => continue,
+        };
+
+        let variance = (counted_number_of_packs - line.snapshot_number_of_packs).abs();
+        accuracy.number_of_counted_lines += 1;
+        if variance == 0.0 {
+            accuracy.number_of_accurate_lines += 1;
+        }
+        accuracy.total_absolute_variance += variance;
+        if counted_items.insert(line.item_id) {
+            accuracy.number_of_counted_items += 1;
+        }
+    }
+
+    for accuracy in result.iter_mut() {
+        if accuracy.number_of_counted_lines > 0 {
+            accuracy.accuracy = Some(
+                accuracy.number_of_accurate_lines as f64 / accuracy.number_of_counted_lines as f64,
+            );
+        }
+    }
+
+    Ok(result)
+}
@@ -0,0 +1,232 @@
+use std::{cmp::Ordering, collections::HashMap};
+
+use chrono::{Duration, NaiveDate};
+use repository::{
+    AbcClass, ConsumptionFilter, ConsumptionRepository, DateFilter, EqualFilter, ItemAbcClassRow,
+    ItemAbcClassRowRepository, ItemRowRepository, RepositoryError, StockLineFilter,
+    StockLineRepository, StorageConnection,
+};
+use util::uuid::uuid;
+
+use crate::service_provider::ServiceContext;
+
+/// Consumption of the previous year is used to calculate consumption values
+pub const CLASSIFICATION_LOOKBACK_DAYS: i64 = 365;
+/// Items making up the first 80% of the total consumption value are class A
+const CLASS_A_CUMULATIVE_SHARE: f64 = 0.8;
+/// Items making up the next 15% of the total consumption value are class B, the rest class C
+const CLASS_B_CUMULATIVE_SHARE: f64 = 0.95;
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct SetItemAbcClass {
+    pub item_id: String,
+    /// Manually set class, `None` returns the item to the calculated class on the next classification
+    pub abc_class: Option<AbcClass>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum SetItemAbcClassError {
+    ItemDoesNotExist,
+    DatabaseError(RepositoryError),
+}
+
+pub fn get_item_abc_classes(ctx: &ServiceContext) -> Result<Vec<ItemAbcClassRow>, RepositoryError> {
+    ItemAbcClassRowRepository::new(&ctx.connection).find_many_by_store_id(&ctx.store_id)
+}
+
+/// Classifies the items of the store by consumption value over the lookback period before
+/// `reference_date`. Manually classified items keep their class, but their consumption value is
+/// updated.
+pub fn classify_items(
+    ctx: &ServiceContext,
+    reference_date: NaiveDate,
+) -> Result<Vec<ItemAbcClassRow>, RepositoryError> {
+    ctx.connection
+        .transaction_sync(|connection| {
+            let consumption_values =
+                calculate_consumption_values(connection, &ctx.store_id, reference_date)?;
+            let repo = ItemAbcClassRowRepository::new(connection);
+            let existing: HashMap<String, ItemAbcClassRow> = repo
+                .find_many_by_store_id(&ctx.store_id)?
+                .into_iter()
+                .map(|row| (row.item_id.clone(), row))
+                .collect();
+
+            // Previously classified items without consumption or stock drop to class C
+            let mut values: Vec<(String, f64)> = consumption_values.into_iter().collect();
+            for item_id in existing.keys() {
+                if !values.iter().any(|(id, _)| id == item_id) {
+                    values.push((item_id.clone(), 0.0));
+                }
+            }
+            let value_by_item: HashMap<String, f64> = values.iter().cloned().collect();
+
+            for (item_id, abc_class) in calculate_abc_classes(&values) {
+                let consumption_value = value_by_item.get(&item_id).copied().unwrap_or(0.0);
+                let row = match existing.get(&item_id) {
+                    Some(row) => ItemAbcClassRow {
+                        abc_class: if row.is_manual {
+                            row.abc_class
+                        } else {
+                            abc_class
+                        },
+                        consumption_value,
+                        ..row.clone()
+                    },
+                    None => ItemAbcClassRow {
+                        id: uuid(),
+                        store_id: ctx.store_id.clone(),
+                        item_id,
+                        abc_class,
+                        consumption_value,
+                        is_manual: false,
+                    },
+                };
+                repo.upsert_one(&row)?;
+            }
+
+            repo.find_many_by_store_id(&ctx.store_id)
+        })
+        .map_err(|error| error.to_inner_error())
+}
+
+pub fn set_item_abc_class(
+    ctx: &ServiceContext,
+    input: SetItemAbcClass,
+) -> Result<ItemAbcClassRow, SetItemAbcClassError> {
+    let result = ctx
+        .connection
+        .transaction_sync(|connection| {
+            if ItemRowRepository::new(connection)
+                .find_one_by_id(&input.item_id)?
+                .is_none()
+            {
+                return Err(SetItemAbcClassError::ItemDoesNotExist);
+            }
+
+            let repo = ItemAbcClassRowRepository::new(connection);
+            let existing = repo.find_one_by_store_and_item_id(&ctx.store_id, &input.item_id)?;
+            let row = match (existing, input.abc_class) {
+                (Some(existing), Some(abc_class)) => ItemAbcClassRow {
+                    abc_class,
+                    is_manual: true,
+                    ..existing
+                },
+                (Some(existing), None) => ItemAbcClassRow {
+                    is_manual: false,
+                    ..existing
+                },
+                (None, abc_class) => ItemAbcClassRow {
+                    id: uuid(),
+                    store_id: ctx.store_id.clone(),
+                    item_id: input.item_id,
+                    is_manual: abc_class.is_some(),
+                    abc_class: abc_class.unwrap_or_default(),
+                    consumption_value: 0.0,
+                },
+            };
+            repo.upsert_one(&row)?;
+
+            Ok(row)
+        })
+        .map_err(|error| error.to_inner_error())?;
+
+    Ok(result)
+}
+
+/// Consumption value per item: units consumed in the lookback period times the average cost per
+/// unit of the item's stock in the store
+fn calculate_consumption_values(
+    connection: &StorageConnection,
+    store_id: &str,
+    reference_date: NaiveDate,
+) -> Result<HashMap<String, f64>, RepositoryError> {
+    let consumption_rows = ConsumptionRepository::new(connection).query(Some(
+        ConsumptionFilter::new()
+            .store_id(EqualFilter::equal_to(store_id))
+            .date(DateFilter::date_range(
+                &(reference_date - Duration::days(CLASSIFICATION_LOOKBACK_DAYS)),
+                &reference_date,
+            )),
+    ))?;
+    let mut quantities = HashMap::<String, i64>::new();
+    for row in consumption_rows {
+        *quantities.entry(row.item_id).or_insert(0) += row.quantity;
+    }
+
+    let stock_lines = StockLineRepository::new(connection).query_by_filter(
+        StockLineFilter::new().store_id(EqualFilter::equal_to(store_id)),
+        Some(store_id.to_string()),
+    )?;
+    // (cost of stock on hand, units on hand, sum of unit costs, number of stock lines)
+    let mut costs = HashMap::<String, (f64, f64, f64, f64)>::new();
+    for line in stock_lines {
+        let row = line.stock_line_row;
+        let pack_size = row.pack_size.max(1) as f64;
+        let entry = costs.entry(row.item_id).or_insert((0.0, 0.0, 0.0, 0.0));
+        entry.0 += row.cost_price_per_pack * row.total_number_of_packs;
+        entry.1 += pack_size * row.total_number_of_packs;
+        entry.2 += row.cost_price_per_pack / pack_size;
+        entry.3 += 1.0;
+    }
+
+    let mut result = HashMap::new();
+    for (item_id, (cost_on_hand, units_on_hand, unit_costs, line_count)) in costs {
+        // Weighted by stock on hand, or a plain average when all stock has been used
+        let unit_cost = if units_on_hand > 0.0 {
+            cost_on_hand / units_on_hand
+        } else {
+            unit_costs / line_count
+        };
+        let quantity = quantities.remove(&item_id).unwrap_or(0);
+        result.insert(item_id, quantity as f64 * unit_cost);
+    }
+    // Consumed items without stock lines in the store have no known cost
+    for (item_id, quantity) in quantities {
+        if quantity > 0 {
+            result.insert(item_id, 0.0);
+        }
+    }
+
+    Ok(result)
+}
+
+/// Pareto classification: items are ranked by consumption value, and classed by the share of the
+/// total consumption value of the items ranked above them
+pub(crate) fn calculate_abc_classes(
+    consumption_values: &[(String, f64)],
+) -> Vec<(String, AbcClass)> {
+    let mut ranked = consumption_values.to_vec();
+    ranked.sort_by(|(a_id, a_value), (b_id, b_value)| {
+        b_value
+            .partial_cmp(a_value)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| a_id.cmp(b_id))
+    });
+    let total: f64 = ranked.iter().map(|(_, value)| value).sum();
+
+    let mut cumulative = 0.0;
+    ranked
+        .into_iter()
+        .map(|(item_id, value)| {
+            let share_before = cumulative / total;
+            cumulative += value;
+            let abc_class = if value <= 0.0 {
+                AbcClass::C
+            } else if share_before < CLASS_A_CUMULATIVE_SHARE {
+                AbcClass::A
+            } else if share_before < CLASS_B_CUMULATIVE_SHARE {
+                AbcClass::B
+            } else {
+                AbcClass::C
+            };
+            (item_id, abc_class)
+        })
+        .collect()
+}
+
+impl From<RepositoryError> for SetItemAbcClassError {
+    fn from(error: RepositoryError) -> Self {
+        SetItemAbcClassError::DatabaseError(error)
+    }
+}
@@ -0,0 +1,65 @@
+use repository::{
+    AbcClass, CycleCountConfigRow, CycleCountConfigRowRepository, RepositoryError, TransactionError,
+};
+use util::uuid::uuid;
+
+use crate::service_provider::ServiceContext;
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct UpsertCycleCountConfig {
+    pub abc_class: AbcClass,
+    pub count_frequency_days: i32,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum UpsertCycleCountConfigError {
+    InvalidCountFrequency,
+    DatabaseError(RepositoryError),
+}
+
+pub fn get_cycle_count_configs(
+    ctx: &ServiceContext,
+) -> Result<Vec<CycleCountConfigRow>, RepositoryError> {
+    CycleCountConfigRowRepository::new(&ctx.connection).find_many_by_store_id(&ctx.store_id)
+}
+
+/// Sets the counting frequency of an ABC class in the store. Classes without a config are not
+/// cycle counted.
+pub fn upsert_cycle_count_config(
+    ctx: &ServiceContext,
+    input: UpsertCycleCountConfig,
+) -> Result<CycleCountConfigRow, UpsertCycleCountConfigError> {
+    if input.count_frequency_days < 1 {
+        return Err(UpsertCycleCountConfigError::InvalidCountFrequency);
+    }
+
+    let result = ctx
+        .connection
+        .transaction_sync(|connection| {
+            let repo = CycleCountConfigRowRepository::new(connection);
+            let row = match repo.find_one_by_store_and_class(&ctx.store_id, &input.abc_class)? {
+                Some(existing) => CycleCountConfigRow {
+                    count_frequency_days: input.count_frequency_days,
+                    ..existing
+                },
+                None => CycleCountConfigRow {
+                    id: uuid(),
+                    store_id: ctx.store_id.clone(),
+                    abc_class: input.abc_class,
+                    count_frequency_days: input.count_frequency_days,
+                    last_generated_date: None,
+                },
+            };
+            repo.upsert_one(&row)?;
+            Ok(row)
+        })
+        .map_err(|error: TransactionError<UpsertCycleCountConfigError>| error.to_inner_error())?;
+
+    Ok(result)
+}
+
+impl From<RepositoryError> for UpsertCycleCountConfigError {
+    fn from(error: RepositoryError) -> Self {
+        UpsertCycleCountConfigError::DatabaseError(error)
+    }
+}
@@ -0,0 +1,196 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::{Duration, NaiveDate, NaiveDateTime};
+use repository::{
+    CycleCountConfigRow, CycleCountConfigRowRepository, DatetimeFilter, EqualFilter,
+    ItemAbcClassRowRepository, Pagination, RepositoryError, Stocktake, StocktakeFilter,
+    StocktakeLineFilter, StocktakeLineRepository, StocktakeRepository, StocktakeStatus,
+    StorageConnection, TransactionError,
+};
+use util::uuid::uuid;
+
+use crate::{
+    service_provider::ServiceContext,
+    stocktake::{insert_stocktake, InsertStocktake, InsertStocktakeError},
+};
+
+#[derive(Debug, PartialEq)]
+pub enum GenerateCycleCountStocktakesError {
+    InsertStocktakeError(InsertStocktakeError),
+    DatabaseError(RepositoryError),
+}
+
+/// Generates a stocktake for each configured ABC class with the items due to be counted on `date`.
+///
+/// Items counted before are due once their count frequency has passed since their last finalised
+/// count. Items that were never counted are spread over the count frequency, so the first round of
+/// counts is a series of small stocktakes rather than one stocktake of the whole class. Items in a
+/// stocktake that hasn't been finalised are skipped. Each class is only generated once per day.
+pub fn generate_cycle_count_stocktakes(
+    ctx: &ServiceContext,
+    date: NaiveDate,
+) -> Result<Vec<Stocktake>, GenerateCycleCountStocktakesError> {
+    let result = ctx
+        .connection
+        .transaction_sync(|connection| {
+            let config_repo = CycleCountConfigRowRepository::new(connection);
+            let configs: Vec<CycleCountConfigRow> = config_repo
+                .find_many_by_store_id(&ctx.store_id)?
+                .into_iter()
+                .filter(|config| config.last_generated_date != Some(date))
+                .collect();
+            if configs.is_empty() {
+                return Ok(Vec::new());
+            }
+
+            let classes =
+                ItemAbcClassRowRepository::new(connection).find_many_by_store_id(&ctx.store_id)?;
+            let last_counted = get_last_counted_datetimes(connection, &ctx.store_id, date)?;
+            let in_progress = get_items_in_open_stocktakes(connection, &ctx.store_id)?;
+
+            let mut stocktakes = Vec::new();
+            for config in configs {
+                // Classes are ordered by consumption value, which orders items with equal due dates
+                let class_item_ids: Vec<&String> = classes
+                    .iter()
+                    .filter(|row| row.abc_class == config.abc_class)
+                    .map(|row| &row.item_id)
+                    .collect();
+                let item_ids = select_due_items(
+                    &class_item_ids,
+                    &last_counted,
+                    &in_progress,
+                    config.count_frequency_days,
+                    date,
+                );
+
+                if !item_ids.is_empty() {
+                    let stocktake = insert_stocktake(
+                        ctx,
+                        InsertStocktake {
+                            id: uuid(),
+                            description: Some(format!(
+                                "Cycle count - class {:?}",
+                                config.abc_class
+                            )),
+                            stocktake_date: Some(date),
+                            item_ids: Some(item_ids),
+                            ..Default::default()
+                        },
+                    )
+                    .map_err(GenerateCycleCountStocktakesError::InsertStocktakeError)?;
+                    stocktakes.push(stocktake);
+                }
+
+                config_repo.upsert_one(&CycleCountConfigRow {
+                    last_generated_date: Some(date),
+                    ..config
+                })?;
+            }
+
+            Ok(stocktakes)
+        })
+        .map_err(
+            |error: TransactionError<GenerateCycleCountStocktakesError>| error.to_inner_error(),
+        )?;
+
+    Ok(result)
+}
+
+fn select_due_items(
+    class_item_ids: &[&String],
+    last_counted: &HashMap<String, NaiveDateTime>,
+    in_progress: &HashSet<String>,
+    count_frequency_days: i32,
+    date: NaiveDate,
+) -> Vec<String> {
+    let frequency = Duration::days(count_frequency_days as i64);
+    let mut overdue: Vec<(&String, NaiveDate)> = Vec::new();
+    let mut never_counted: Vec<&String> = Vec::new();
+    for item_id in class_item_ids
+        .iter()
+        .filter(|id| !in_progress.contains(**id))
+    {
+        match last_counted.get(*item_id) {
+            Some(datetime) if datetime.date() + frequency <= date => {
+                overdue.push((item_id, datetime.date()))
+            }
+            Some(_) => {}
+            None => never_counted.push(item_id),
+        }
+    }
+    overdue.sort_by_key(|(_, last_counted_date)| *last_counted_date);
+
+    // Enough items per day to count the whole class within the count frequency
+    let daily_quota = (class_item_ids.len() as f64 / count_frequency_days as f64).ceil() as usize;
+    let never_counted_quota = daily_quota.saturating_sub(overdue.len());
+
+    overdue
+        .into_iter()
+        .map(|(item_id, _)| item_id.clone())
+        .chain(never_counted.into_iter().take(never_counted_quota).cloned())
+        .collect()
+}
+
+/// Finalised datetime of the last stocktake each item was counted in, up to the end of `date`
+pub(crate) fn get_last_counted_datetimes(
+    connection: &StorageConnection,
+    store_id: &str,
+    date: NaiveDate,
+) -> Result<HashMap<String, NaiveDateTime>, RepositoryError> {
+    let end_of_date =
+        (date + Duration::days(1)).and_hms_opt(0, 0, 0).unwrap() - Duration::milliseconds(1);
+    let finalised_filter = StocktakeFilter::new()
+        .store_id(EqualFilter::equal_to(store_id))
+        .status(StocktakeStatus::Finalised.equal_to())
+        .finalised_datetime(DatetimeFilter::before_or_equal_to(end_of_date));
+
+    let finalised_datetimes: HashMap<String, NaiveDateTime> = StocktakeRepository::new(connection)
+        .query(Pagination::all(), Some(finalised_filter.clone()), None)?
+        .into_iter()
+        .filter_map(|stocktake| {
+            stocktake
+                .finalised_datetime
+                .map(|datetime| (stocktake.id, datetime))
+        })
+        .collect();
+
+    let lines = StocktakeLineRepository::new(connection)
+        .query_by_filter(StocktakeLineFilter::new().stocktake(finalised_filter))?;
+
+    let mut result = HashMap::<String, NaiveDateTime>::new();
+    for line in lines.into_iter().map(|line| line.line) {
+        if line.counted_number_of_packs.is_none() {
+            continue;
+        }
+        if let Some(finalised_datetime) = finalised_datetimes.get(&line.stocktake_id) {
+            let last = result.entry(line.item_id).or_insert(*finalised_datetime);
+            if *last < *finalised_datetime {
+                *last = *finalised_datetime;
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+fn get_items_in_open_stocktakes(
+    connection: &StorageConnection,
+    store_id: &str,
+) -> Result<HashSet<String>, RepositoryError> {
+    let lines = StocktakeLineRepository::new(connection).query_by_filter(
+        StocktakeLineFilter::new().stocktake(
+            StocktakeFilter::new()
+                .store_id(EqualFilter::equal_to(store_id))
+                .status(StocktakeStatus::New.equal_to()),
+        ),
+    )?;
+
+    Ok(lines.into_iter().map(|line| line.line.item_id).collect())
+}
+
+impl From<RepositoryError> for GenerateCycleCountStocktakesError {
+    fn from(error: RepositoryError) -> Self {
+        GenerateCycleCountStocktakesError::DatabaseError(error)
+    }
+}
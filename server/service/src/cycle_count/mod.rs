@@ -0,0 +1,83 @@
+use self::{
+    accuracy::{get_cycle_count_accuracy, CycleCountAccuracy},
+    classify::{
+        classify_items, get_item_abc_classes, set_item_abc_class, SetItemAbcClass,
+        SetItemAbcClassError,
+    },
+    config::{
+        get_cycle_count_configs, upsert_cycle_count_config, UpsertCycleCountConfig,
+        UpsertCycleCountConfigError,
+    },
+    generate::{generate_cycle_count_stocktakes, GenerateCycleCountStocktakesError},
+};
+
+use crate::service_provider::ServiceContext;
+use chrono::NaiveDate;
+use repository::{CycleCountConfigRow, ItemAbcClassRow, RepositoryError, Stocktake};
+
+pub mod accuracy;
+pub mod classify;
+pub mod config;
+pub mod generate;
+pub mod scheduler;
+
+pub trait CycleCountServiceTrait: Sync + Send {
+    fn get_item_abc_classes(
+        &self,
+        ctx: &ServiceContext,
+    ) -> Result<Vec<ItemAbcClassRow>, RepositoryError> {
+        get_item_abc_classes(ctx)
+    }
+
+    fn classify_items(
+        &self,
+        ctx: &ServiceContext,
+        reference_date: NaiveDate,
+    ) -> Result<Vec<ItemAbcClassRow>, RepositoryError> {
+        classify_items(ctx, reference_date)
+    }
+
+    fn set_item_abc_class(
+        &self,
+        ctx: &ServiceContext,
+        input: SetItemAbcClass,
+    ) -> Result<ItemAbcClassRow, SetItemAbcClassError> {
+        set_item_abc_class(ctx, input)
+    }
+
+    fn get_cycle_count_configs(
+        &self,
+        ctx: &ServiceContext,
+    ) -> Result<Vec<CycleCountConfigRow>, RepositoryError> {
+        get_cycle_count_configs(ctx)
+    }
+
+    fn upsert_cycle_count_config(
+        &self,
+        ctx: &ServiceContext,
+        input: UpsertCycleCountConfig,
+    ) -> Result<CycleCountConfigRow, UpsertCycleCountConfigError> {
+        upsert_cycle_count_config(ctx, input)
+    }
+
+    fn generate_cycle_count_stocktakes(
+        &self,
+        ctx: &ServiceContext,
+        date: NaiveDate,
+    ) -> Result<Vec<Stocktake>, GenerateCycleCountStocktakesError> {
+        generate_cycle_count_stocktakes(ctx, date)
+    }
+
+    fn get_cycle_count_accuracy(
+        &self,
+        ctx: &ServiceContext,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<CycleCountAccuracy>, RepositoryError> {
+        get_cycle_count_accuracy(ctx, from, to)
+    }
+}
+
+pub struct CycleCountService {}
+impl CycleCountServiceTrait for CycleCountService {}
+mod tests;
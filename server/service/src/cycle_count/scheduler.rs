@@ -0,0 +1,53 @@
+use std::{sync::Arc, time::Duration};
+
+use chrono::Utc;
+use tokio::task::JoinHandle;
+use util::constants::SYSTEM_USER_ID;
+
+use crate::{service_provider::ServiceProvider, sync::ActiveStoresOnSite};
+
+const SCHEDULER_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Generates due cycle count stocktakes for the stores on this site. Runs hourly, stocktakes for
+/// each class are generated at most once per day.
+pub fn spawn(service_provider: Arc<ServiceProvider>) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(SCHEDULER_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(error) = generate_for_active_stores(&service_provider) {
+                log::error!("Error in cycle count scheduler {}", error);
+            }
+        }
+    })
+}
+
+fn generate_for_active_stores(service_provider: &ServiceProvider) -> Result<(), String> {
+    let ctx = service_provider
+        .basic_context()
+        .map_err(|error| format!("{:?}", error))?;
+    // Site is not initialised yet
+    let store_ids = match ActiveStoresOnSite::get(&ctx.connection) {
+        Ok(stores) => stores.store_ids(),
+        Err(_) => return Ok(()),
+    };
+    let today = Utc::now().naive_utc().date();
+
+    for store_id in store_ids {
+        let ctx = service_provider
+            .context(store_id.clone(), SYSTEM_USER_ID.to_string())
+            .map_err(|error| format!("{:?}", error))?;
+        if let Err(error) = service_provider
+            .cycle_count_service
+            .generate_cycle_count_stocktakes(&ctx, today)
+        {
+            log::error!(
+                "Failed to generate cycle counts for store {}: {:?}",
+                store_id,
+                error
+            );
+        }
+    }
+
+    Ok(())
+}
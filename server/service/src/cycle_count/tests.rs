@@ -0,0 +1,316 @@
+#[cfg(test)]
+mod query {
+    use chrono::NaiveDate;
+    use repository::{
+        mock::{
+            mock_item_a, mock_item_a_stock_line, mock_item_b, mock_item_c, mock_item_d,
+            mock_name_a, mock_store_a, MockData, MockDataInserts,
+        },
+        test_db::setup_all_with_data,
+        AbcClass, EqualFilter, InvoiceLineRow, InvoiceLineRowType, InvoiceRow, InvoiceRowType,
+        ItemAbcClassRow, StocktakeLineFilter, StocktakeLineRepository, StocktakeLineRowRepository,
+        StocktakeRowRepository, StocktakeStatus,
+    };
+    use util::{inline_edit, inline_init};
+
+    use crate::{
+        cycle_count::{
+            accuracy::CycleCountAccuracy,
+            classify::{calculate_abc_classes, SetItemAbcClass, SetItemAbcClassError},
+            config::{UpsertCycleCountConfig, UpsertCycleCountConfigError},
+        },
+        service_provider::ServiceProvider,
+    };
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2023, 6, day).unwrap()
+    }
+
+    fn issued_line(item_id: &str, number_of_packs: f64) -> InvoiceLineRow {
+        inline_init(|r: &mut InvoiceLineRow| {
+            r.id = format!("{}_issued", item_id);
+            r.invoice_id = "outbound_shipment".to_string();
+            r.item_id = item_id.to_string();
+            r.stock_line_id = Some(format!("{}_stock_line", item_id));
+            r.r#type = InvoiceLineRowType::StockOut;
+            r.pack_size = 1;
+            r.number_of_packs = number_of_packs;
+        })
+    }
+
+    fn class_of(rows: &[ItemAbcClassRow], item_id: &str) -> (AbcClass, bool) {
+        let row = rows.iter().find(|row| row.item_id == item_id).unwrap();
+        (row.abc_class, row.is_manual)
+    }
+
+    #[test]
+    fn cycle_count_calculate_abc_classes() {
+        let values = vec![
+            ("low".to_string(), 5.0),
+            ("high".to_string(), 80.0),
+            ("medium".to_string(), 15.0),
+            ("unused".to_string(), 0.0),
+        ];
+        assert_eq!(
+            calculate_abc_classes(&values),
+            vec![
+                ("high".to_string(), AbcClass::A),
+                ("medium".to_string(), AbcClass::B),
+                ("low".to_string(), AbcClass::C),
+                ("unused".to_string(), AbcClass::C),
+            ]
+        );
+        // The highest value item is always class A
+        assert_eq!(
+            calculate_abc_classes(&[("only".to_string(), 1.0)]),
+            vec![("only".to_string(), AbcClass::A)]
+        );
+    }
+
+    #[actix_rt::test]
+    async fn cycle_count_classify_items() {
+        let (_, _, connection_manager, _) = setup_all_with_data(
+            "cycle_count_classify_items",
+            MockDataInserts::none().stores().items().names().units(),
+            inline_init(|r: &mut MockData| {
+                r.stock_lines = vec![
+                    (mock_item_a().id, 10.0),
+                    (mock_item_b().id, 1.0),
+                    (mock_item_c().id, 1.0),
+                    (mock_item_d().id, 1.0),
+                ]
+                .into_iter()
+                .map(|(item_id, cost_price_per_pack)| {
+                    let id = format!("{}_stock_line", item_id);
+                    inline_edit(&mock_item_a_stock_line(&id, 1, 10.0), |mut u| {
+                        u.item_id = item_id;
+                        u.cost_price_per_pack = cost_price_per_pack;
+                        u
+                    })
+                })
+                .collect();
+                r.invoices = vec![inline_init(|r: &mut InvoiceRow| {
+                    r.id = "outbound_shipment".to_string();
+                    r.store_id = mock_store_a().id;
+                    r.name_id = mock_name_a().id;
+                    r.r#type = InvoiceRowType::OutboundShipment;
+                    r.picked_datetime = Some(date(1).and_hms_opt(10, 0, 0).unwrap());
+                })];
+                // Consumption values: a 1000, b 100, c 50, d none
+                r.invoice_lines = vec![
+                    issued_line(&mock_item_a().id, 100.0),
+                    issued_line(&mock_item_b().id, 100.0),
+                    issued_line(&mock_item_c().id, 50.0),
+                ];
+            }),
+        )
+        .await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider
+            .context(mock_store_a().id, "user".to_string())
+            .unwrap();
+        let service = service_provider.cycle_count_service;
+
+        let rows = service.classify_items(&context, date(30)).unwrap();
+        assert_eq!(rows.len(), 4);
+        assert_eq!(class_of(&rows, &mock_item_a().id), (AbcClass::A, false));
+        assert_eq!(class_of(&rows, &mock_item_b().id), (AbcClass::B, false));
+        assert_eq!(class_of(&rows, &mock_item_c().id), (AbcClass::C, false));
+        assert_eq!(class_of(&rows, &mock_item_d().id), (AbcClass::C, false));
+        assert_eq!(rows[0].item_id, mock_item_a().id);
+        assert_eq!(rows[0].consumption_value, 1000.0);
+
+        // Consumption outside the lookback period is ignored
+        let rows = service
+            .classify_items(&context, date(1) + chrono::Duration::days(400))
+            .unwrap();
+        assert!(rows.iter().all(|row| row.abc_class == AbcClass::C));
+
+        // Manual classes are kept when reclassifying
+        assert_eq!(
+            service.set_item_abc_class(
+                &context,
+                SetItemAbcClass {
+                    item_id: "invalid".to_string(),
+                    abc_class: Some(AbcClass::A),
+                },
+            ),
+            Err(SetItemAbcClassError::ItemDoesNotExist)
+        );
+        service
+            .set_item_abc_class(
+                &context,
+                SetItemAbcClass {
+                    item_id: mock_item_c().id,
+                    abc_class: Some(AbcClass::A),
+                },
+            )
+            .unwrap();
+        let rows = service.classify_items(&context, date(30)).unwrap();
+        assert_eq!(class_of(&rows, &mock_item_c().id), (AbcClass::A, true));
+
+        // Clearing the manual class returns the item to its calculated class
+        service
+            .set_item_abc_class(
+                &context,
+                SetItemAbcClass {
+                    item_id: mock_item_c().id,
+                    abc_class: None,
+                },
+            )
+            .unwrap();
+        let rows = service.classify_items(&context, date(30)).unwrap();
+        assert_eq!(class_of(&rows, &mock_item_c().id), (AbcClass::C, false));
+    }
+
+    #[actix_rt::test]
+    async fn cycle_count_generate_stocktakes() {
+        let (_, connection, connection_manager, _) = setup_all_with_data(
+            "cycle_count_generate_stocktakes",
+            MockDataInserts::none().stores().items().names().units(),
+            MockData::default(),
+        )
+        .await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider
+            .context(mock_store_a().id, "user".to_string())
+            .unwrap();
+        let service = service_provider.cycle_count_service;
+
+        for item_id in [
+            mock_item_a().id,
+            mock_item_b().id,
+            mock_item_c().id,
+            mock_item_d().id,
+        ] {
+            service
+                .set_item_abc_class(
+                    &context,
+                    SetItemAbcClass {
+                        item_id,
+                        abc_class: Some(AbcClass::A),
+                    },
+                )
+                .unwrap();
+        }
+
+        // Classes without config are not counted
+        assert_eq!(
+            service.generate_cycle_count_stocktakes(&context, date(1)),
+            Ok(vec![])
+        );
+
+        assert_eq!(
+            service.upsert_cycle_count_config(
+                &context,
+                UpsertCycleCountConfig {
+                    abc_class: AbcClass::A,
+                    count_frequency_days: 0,
+                },
+            ),
+            Err(UpsertCycleCountConfigError::InvalidCountFrequency)
+        );
+        service
+            .upsert_cycle_count_config(
+                &context,
+                UpsertCycleCountConfig {
+                    abc_class: AbcClass::A,
+                    count_frequency_days: 2,
+                },
+            )
+            .unwrap();
+
+        let line_item_ids = |stocktake_id: &str| -> Vec<String> {
+            let mut item_ids: Vec<String> = StocktakeLineRepository::new(&connection)
+                .query_by_filter(
+                    StocktakeLineFilter::new().stocktake_id(EqualFilter::equal_to(stocktake_id)),
+                )
+                .unwrap()
+                .into_iter()
+                .map(|line| line.line.item_id)
+                .collect();
+            item_ids.sort();
+            item_ids
+        };
+
+        // Four items to count every two days, two items a day
+        let stocktakes = service
+            .generate_cycle_count_stocktakes(&context, date(1))
+            .unwrap();
+        assert_eq!(stocktakes.len(), 1);
+        let first_stocktake = stocktakes[0].clone();
+        let first_items = line_item_ids(&first_stocktake.id);
+        assert_eq!(first_items.len(), 2);
+        assert_eq!(first_stocktake.stocktake_date, Some(date(1)));
+
+        // Only generated once a day
+        assert_eq!(
+            service.generate_cycle_count_stocktakes(&context, date(1)),
+            Ok(vec![])
+        );
+
+        // Items in the open stocktake are skipped
+        let stocktakes = service
+            .generate_cycle_count_stocktakes(&context, date(2))
+            .unwrap();
+        assert_eq!(stocktakes.len(), 1);
+        let second_items = line_item_ids(&stocktakes[0].id);
+        assert_eq!(second_items.len(), 2);
+        assert!(second_items.iter().all(|id| !first_items.contains(id)));
+
+        // Finalise the first stocktake, one line counted accurately
+        let line_repo = StocktakeLineRowRepository::new(&connection);
+        let lines = StocktakeLineRepository::new(&connection)
+            .query_by_filter(
+                StocktakeLineFilter::new().stocktake_id(EqualFilter::equal_to(&first_stocktake.id)),
+            )
+            .unwrap();
+        for (index, line) in lines.into_iter().enumerate() {
+            line_repo
+                .upsert_one(&inline_init(|r: &mut repository::StocktakeLineRow| {
+                    *r = line.line.clone();
+                    r.counted_number_of_packs = Some(index as f64 * 2.0);
+                }))
+                .unwrap();
+        }
+        StocktakeRowRepository::new(&connection)
+            .upsert_one(&inline_init(|r: &mut repository::StocktakeRow| {
+                *r = first_stocktake.clone();
+                r.status = StocktakeStatus::Finalised;
+                r.finalised_datetime = Some(date(1).and_hms_opt(12, 0, 0).unwrap());
+            }))
+            .unwrap();
+
+        // Counted items are due again after the count frequency
+        let stocktakes = service
+            .generate_cycle_count_stocktakes(&context, date(3))
+            .unwrap();
+        assert_eq!(stocktakes.len(), 1);
+        assert_eq!(line_item_ids(&stocktakes[0].id), first_items);
+
+        let accuracy = service
+            .get_cycle_count_accuracy(&context, date(1), date(3))
+            .unwrap();
+        assert_eq!(
+            accuracy[0],
+            CycleCountAccuracy {
+                abc_class: AbcClass::A,
+                number_of_items: 4,
+                number_of_counted_items: 2,
+                number_of_counted_lines: 2,
+                number_of_accurate_lines: 1,
+                accuracy: Some(0.5),
+                total_absolute_variance: 2.0,
+            }
+        );
+        assert_eq!(accuracy[1].abc_class, AbcClass::B);
+        assert_eq!(accuracy[1].accuracy, None);
+        // Outside the period
+        let accuracy = service
+            .get_cycle_count_accuracy(&context, date(2), date(3))
+            .unwrap();
+        assert_eq!(accuracy[0].number_of_counted_lines, 0);
+    }
+}
@@ -0,0 +1,48 @@
+use self::{
+    quarantine::{quarantine_expired_stock, QuarantineExpiredStock, QuarantineExpiredStockError},
+    query::get_expiring_stock,
+    write_off::{write_off_expired_stock, WriteOffExpiredStock, WriteOffExpiredStockError},
+};
+
+use crate::service_provider::ServiceContext;
+use chrono::NaiveDate;
+use repository::{Invoice, RepositoryError, StockLine};
+
+pub mod quarantine;
+pub mod query;
+pub mod write_off;
+
+pub trait ExpiredStockServiceTrait: Sync + Send {
+    /// Stock lines of the store with packs in store expiring on or before the date, ordered by
+    /// expiry date
+    fn get_expiring_stock(
+        &self,
+        ctx: &ServiceContext,
+        expiry_date: NaiveDate,
+    ) -> Result<Vec<StockLine>, RepositoryError> {
+        get_expiring_stock(ctx, expiry_date)
+    }
+
+    /// Moves stock lines to a quarantine location and/or puts them on hold
+    fn quarantine_expired_stock(
+        &self,
+        ctx: &ServiceContext,
+        input: QuarantineExpiredStock,
+    ) -> Result<Vec<StockLine>, QuarantineExpiredStockError> {
+        quarantine_expired_stock(ctx, input)
+    }
+
+    /// Writes off all packs of expired stock lines in a single inventory reduction
+    fn write_off_expired_stock(
+        &self,
+        ctx: &ServiceContext,
+        input: WriteOffExpiredStock,
+    ) -> Result<Invoice, WriteOffExpiredStockError> {
+        write_off_expired_stock(ctx, input)
+    }
+}
+
+pub struct ExpiredStockService {}
+impl ExpiredStockServiceTrait for ExpiredStockService {}
+
+mod tests;
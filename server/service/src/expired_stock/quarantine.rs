@@ -0,0 +1,144 @@
+use repository::{
+    EqualFilter, HoldRecordType, LocationFilter, LocationRepository, LocationType, RepositoryError,
+    StockLine, StockLineRow, StorageConnection, HOLD_REASON_QUARANTINE_ID,
+};
+
+use crate::{
+    hold::record_hold,
+    service_provider::ServiceContext,
+    stock_line::{
+        update_stock_line,
+        validate::{check_stock_line_exists, check_store},
+        UpdateStockLine, UpdateStockLineError,
+    },
+};
+
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct QuarantineExpiredStock {
+    pub stock_line_ids: Vec<String>,
+    /// Quarantine location the stock lines are moved to
+    pub location_id: Option<String>,
+    pub on_hold: bool,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum QuarantineExpiredStockError {
+    DatabaseError(RepositoryError),
+    NoStockLines,
+    /// Either a location or on hold must be set
+    NothingToUpdate,
+    StockDoesNotExist,
+    StockDoesNotBelongToStore,
+    LocationDoesNotExist,
+    /// Location must be a quarantine location of the store
+    LocationIsNotQuarantine,
+    StockLineIsPendingInspection,
+    UpdatedStockNotFound,
+    StockMovementNotFound,
+}
+
+type OutError = QuarantineExpiredStockError;
+
+/// Moves the stock lines to a quarantine location and/or puts them on hold, so they're no longer
+/// allocated while waiting to be written off. Changes are logged per stock line.
+pub fn quarantine_expired_stock(
+    ctx: &ServiceContext,
+    input: QuarantineExpiredStock,
+) -> Result<Vec<StockLine>, OutError> {
+    let result = ctx
+        .connection
+        .transaction_sync(|connection| {
+            let stock_lines = validate(connection, &ctx.store_id, &input)?;
+
+            stock_lines
+                .into_iter()
+                .map(|stock_line| {
+                    let updated = update_stock_line(
+                        ctx,
+                        UpdateStockLine {
+                            id: stock_line.id,
+                            // Keep the current location when only putting the stock on hold
+                            location_id: input.location_id.clone().or(stock_line.location_id),
+                            on_hold: input.on_hold.then_some(true),
+                            ..Default::default()
+                        },
+                    )?;
+                    if input.on_hold {
+                        record_hold(
+                            ctx,
+                            HoldRecordType::StockLine,
+                            &updated.stock_line_row.id,
+                            HOLD_REASON_QUARANTINE_ID,
+                            None,
+                        )?;
+                    }
+                    Ok(updated)
+                })
+                .collect::<Result<Vec<StockLine>, OutError>>()
+        })
+        .map_err(|error| error.to_inner_error())?;
+    Ok(result)
+}
+
+fn validate(
+    connection: &StorageConnection,
+    store_id: &str,
+    input: &QuarantineExpiredStock,
+) -> Result<Vec<StockLineRow>, OutError> {
+    use QuarantineExpiredStockError::*;
+
+    let mut ids = input.stock_line_ids.clone();
+    ids.sort();
+    ids.dedup();
+    if ids.is_empty() {
+        return Err(NoStockLines);
+    }
+    if input.location_id.is_none() && !input.on_hold {
+        return Err(NothingToUpdate);
+    }
+
+    if let Some(location_id) = &input.location_id {
+        let location = LocationRepository::new(connection)
+            .query_by_filter(
+                LocationFilter::new()
+                    .id(EqualFilter::equal_to(location_id))
+                    .store_id(EqualFilter::equal_to(store_id)),
+            )?
+            .pop()
+            .ok_or(LocationDoesNotExist)?;
+        if location.location_row.location_type != LocationType::Quarantine {
+            return Err(LocationIsNotQuarantine);
+        }
+    }
+
+    ids.iter()
+        .map(|id| {
+            let stock_line = check_stock_line_exists(connection, id)?.ok_or(StockDoesNotExist)?;
+            if !check_store(&stock_line, store_id) {
+                return Err(StockDoesNotBelongToStore);
+            }
+            Ok(stock_line)
+        })
+        .collect()
+}
+
+impl From<RepositoryError> for QuarantineExpiredStockError {
+    fn from(error: RepositoryError) -> Self {
+        QuarantineExpiredStockError::DatabaseError(error)
+    }
+}
+
+impl From<UpdateStockLineError> for QuarantineExpiredStockError {
+    fn from(error: UpdateStockLineError) -> Self {
+        use QuarantineExpiredStockError::*;
+        match error {
+            UpdateStockLineError::DatabaseError(error) => DatabaseError(error),
+            UpdateStockLineError::StockDoesNotBelongToStore => StockDoesNotBelongToStore,
+            UpdateStockLineError::StockDoesNotExist => StockDoesNotExist,
+            UpdateStockLineError::LocationDoesNotExist => LocationDoesNotExist,
+            UpdateStockLineError::StockLineIsPendingInspection => StockLineIsPendingInspection,
+            UpdateStockLineError::UpdatedStockNotFound => UpdatedStockNotFound,
+            UpdateStockLineError::StockMovementNotFound => StockMovementNotFound,
+        }
+    }
+}
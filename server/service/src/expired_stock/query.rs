@@ -0,0 +1,27 @@
+use chrono::NaiveDate;
+use repository::{
+    DateFilter, EqualFilter, Pagination, RepositoryError, StockLine, StockLineFilter,
+    StockLineRepository, StockLineSort, StockLineSortField,
+};
+
+use crate::service_provider::ServiceContext;
+
+pub fn get_expiring_stock(
+    ctx: &ServiceContext,
+    expiry_date: NaiveDate,
+) -> Result<Vec<StockLine>, RepositoryError> {
+    let filter = StockLineFilter::new()
+        .store_id(EqualFilter::equal_to(&ctx.store_id))
+        .expiry_date(DateFilter::before_or_equal_to(expiry_date))
+        .has_packs_in_store(true);
+
+    StockLineRepository::new(&ctx.connection).query(
+        Pagination::all(),
+        Some(filter),
+        Some(StockLineSort {
+            key: StockLineSortField::ExpiryDate,
+            desc: None,
+        }),
+        Some(ctx.store_id.clone()),
+    )
+}
@@ -0,0 +1,373 @@
+#[cfg(test)]
+mod query {
+    use chrono::NaiveDate;
+    use repository::{
+        mock::{mock_item_a_stock_line, mock_store_a, mock_store_b, MockData, MockDataInserts},
+        test_db::setup_all_with_data,
+        ActivityLogRowRepository, ActivityLogType, InventoryAdjustmentReasonRow,
+        InventoryAdjustmentReasonRowRepository, InventoryAdjustmentReasonType,
+        InvoiceLineRowRepository, InvoiceRowType, LocationRow, LocationType, StockLineRow,
+        StockLineRowRepository,
+    };
+    use util::{inline_edit, inline_init};
+
+    use crate::{
+        expired_stock::{
+            quarantine::{QuarantineExpiredStock, QuarantineExpiredStockError},
+            write_off::{WriteOffExpiredStock, WriteOffExpiredStockError},
+        },
+        service_provider::ServiceProvider,
+    };
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2023, 6, day).unwrap()
+    }
+
+    fn expired_a() -> StockLineRow {
+        inline_edit(&mock_item_a_stock_line("expired_a", 10, 5.0), |mut u| {
+            u.expiry_date = Some(date(1));
+            u
+        })
+    }
+
+    fn expired_b() -> StockLineRow {
+        inline_edit(&mock_item_a_stock_line("expired_b", 10, 5.0), |mut u| {
+            u.expiry_date = Some(date(10));
+            u
+        })
+    }
+
+    fn not_expired() -> StockLineRow {
+        inline_edit(&mock_item_a_stock_line("not_expired", 10, 5.0), |mut u| {
+            u.expiry_date = Some(NaiveDate::from_ymd_opt(2999, 1, 1).unwrap());
+            u
+        })
+    }
+
+    fn no_expiry() -> StockLineRow {
+        mock_item_a_stock_line("no_expiry", 10, 5.0)
+    }
+
+    fn empty_expired() -> StockLineRow {
+        inline_edit(
+            &mock_item_a_stock_line("empty_expired", 10, 0.0),
+            |mut u| {
+                u.expiry_date = Some(date(2));
+                u
+            },
+        )
+    }
+
+    fn quarantine_location() -> LocationRow {
+        inline_init(|r: &mut LocationRow| {
+            r.id = "quarantine_location".to_string();
+            r.store_id = mock_store_a().id;
+            r.location_type = LocationType::Quarantine;
+        })
+    }
+
+    fn ambient_location() -> LocationRow {
+        inline_init(|r: &mut LocationRow| {
+            r.id = "ambient_location".to_string();
+            r.store_id = mock_store_a().id;
+            r.location_type = LocationType::Ambient;
+        })
+    }
+
+    fn other_store_location() -> LocationRow {
+        inline_init(|r: &mut LocationRow| {
+            r.id = "other_store_location".to_string();
+            r.store_id = mock_store_b().id;
+            r.location_type = LocationType::Quarantine;
+        })
+    }
+
+    fn damaged_reason() -> InventoryAdjustmentReasonRow {
+        inline_init(|r: &mut InventoryAdjustmentReasonRow| {
+            r.id = "damaged_reason".to_string();
+            r.is_active = true;
+            r.r#type = InventoryAdjustmentReasonType::Negative;
+            r.reason = "Damaged".to_string();
+        })
+    }
+
+    fn expired_reason() -> InventoryAdjustmentReasonRow {
+        inline_init(|r: &mut InventoryAdjustmentReasonRow| {
+            r.id = "expired_reason".to_string();
+            r.is_active = true;
+            r.r#type = InventoryAdjustmentReasonType::Negative;
+            r.reason = "Stock Expired".to_string();
+        })
+    }
+
+    fn mock_data() -> MockData {
+        inline_init(|r: &mut MockData| {
+            r.stock_lines = vec![
+                expired_a(),
+                expired_b(),
+                not_expired(),
+                no_expiry(),
+                empty_expired(),
+            ];
+            r.locations = vec![
+                quarantine_location(),
+                ambient_location(),
+                other_store_location(),
+            ];
+        })
+    }
+
+    #[actix_rt::test]
+    async fn get_expiring_stock() {
+        let (_, _, connection_manager, _) = setup_all_with_data(
+            "get_expiring_stock",
+            MockDataInserts::none().stores().items().names().units(),
+            mock_data(),
+        )
+        .await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider
+            .context(mock_store_a().id, "user".to_string())
+            .unwrap();
+        let service = service_provider.expired_stock_service;
+
+        let ids = |expiry_date| {
+            service
+                .get_expiring_stock(&context, expiry_date)
+                .unwrap()
+                .into_iter()
+                .map(|stock_line| stock_line.stock_line_row.id)
+                .collect::<Vec<String>>()
+        };
+
+        // Ordered by expiry date, without empty stock lines or stock lines without expiry
+        assert_eq!(ids(date(10)), vec![expired_a().id, expired_b().id]);
+        assert_eq!(ids(date(9)), vec![expired_a().id]);
+    }
+
+    #[actix_rt::test]
+    async fn quarantine_expired_stock() {
+        let (_, connection, connection_manager, _) = setup_all_with_data(
+            "quarantine_expired_stock",
+            MockDataInserts::none().stores().items().names().units(),
+            mock_data(),
+        )
+        .await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider
+            .context(mock_store_a().id, "user".to_string())
+            .unwrap();
+        let service = service_provider.expired_stock_service;
+
+        let input = QuarantineExpiredStock {
+            stock_line_ids: vec![expired_a().id, expired_b().id],
+            location_id: Some(quarantine_location().id),
+            on_hold: false,
+        };
+
+        // Errors
+        assert_eq!(
+            service.quarantine_expired_stock(
+                &context,
+                QuarantineExpiredStock {
+                    stock_line_ids: vec![],
+                    ..input.clone()
+                }
+            ),
+            Err(QuarantineExpiredStockError::NoStockLines)
+        );
+        assert_eq!(
+            service.quarantine_expired_stock(
+                &context,
+                QuarantineExpiredStock {
+                    location_id: None,
+                    ..input.clone()
+                }
+            ),
+            Err(QuarantineExpiredStockError::NothingToUpdate)
+        );
+        assert_eq!(
+            service.quarantine_expired_stock(
+                &context,
+                QuarantineExpiredStock {
+                    location_id: Some(other_store_location().id),
+                    ..input.clone()
+                }
+            ),
+            Err(QuarantineExpiredStockError::LocationDoesNotExist)
+        );
+        assert_eq!(
+            service.quarantine_expired_stock(
+                &context,
+                QuarantineExpiredStock {
+                    location_id: Some(ambient_location().id),
+                    ..input.clone()
+                }
+            ),
+            Err(QuarantineExpiredStockError::LocationIsNotQuarantine)
+        );
+        assert_eq!(
+            service.quarantine_expired_stock(
+                &context,
+                QuarantineExpiredStock {
+                    stock_line_ids: vec![expired_a().id, "invalid".to_string()],
+                    ..input.clone()
+                }
+            ),
+            Err(QuarantineExpiredStockError::StockDoesNotExist)
+        );
+
+        // Move to quarantine
+        let result = service
+            .quarantine_expired_stock(&context, input.clone())
+            .unwrap();
+        assert_eq!(result.len(), 2);
+        let repo = StockLineRowRepository::new(&connection);
+        let updated = repo.find_one_by_id(&expired_a().id).unwrap();
+        assert_eq!(updated.location_id, Some(quarantine_location().id));
+        assert!(!updated.on_hold);
+
+        // Put on hold, keeping the location
+        service
+            .quarantine_expired_stock(
+                &context,
+                QuarantineExpiredStock {
+                    location_id: None,
+                    on_hold: true,
+                    ..input
+                },
+            )
+            .unwrap();
+        let updated = repo.find_one_by_id(&expired_b().id).unwrap();
+        assert_eq!(updated.location_id, Some(quarantine_location().id));
+        assert!(updated.on_hold);
+
+        let logs = ActivityLogRowRepository::new(&connection)
+            .find_many_by_record_id(&expired_b().id)
+            .unwrap();
+        assert!(logs
+            .iter()
+            .any(|log| log.r#type == ActivityLogType::StockOnHold));
+        assert!(logs
+            .iter()
+            .any(|log| log.r#type == ActivityLogType::StockLocationChange));
+    }
+
+    #[actix_rt::test]
+    async fn write_off_expired_stock() {
+        let (_, connection, connection_manager, _) = setup_all_with_data(
+            "write_off_expired_stock",
+            MockDataInserts::none().stores().items().names().units(),
+            MockData {
+                inventory_adjustment_reasons: vec![damaged_reason()],
+                ..mock_data()
+            },
+        )
+        .await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider
+            .context(mock_store_a().id, "user".to_string())
+            .unwrap();
+        let service = service_provider.expired_stock_service;
+
+        let input = WriteOffExpiredStock {
+            stock_line_ids: vec![expired_a().id, expired_b().id],
+            inventory_adjustment_reason_id: None,
+            comment: Some("Incinerated".to_string()),
+        };
+
+        // Errors
+        assert_eq!(
+            service.write_off_expired_stock(
+                &context,
+                WriteOffExpiredStock {
+                    stock_line_ids: vec![expired_a().id, not_expired().id],
+                    ..input.clone()
+                }
+            ),
+            Err(WriteOffExpiredStockError::StockLineNotExpired)
+        );
+        assert_eq!(
+            service.write_off_expired_stock(
+                &context,
+                WriteOffExpiredStock {
+                    stock_line_ids: vec![no_expiry().id],
+                    ..input.clone()
+                }
+            ),
+            Err(WriteOffExpiredStockError::StockLineNotExpired)
+        );
+        assert_eq!(
+            service.write_off_expired_stock(
+                &context,
+                WriteOffExpiredStock {
+                    stock_line_ids: vec![empty_expired().id],
+                    ..input.clone()
+                }
+            ),
+            Err(WriteOffExpiredStockError::StockLineHasNoStock)
+        );
+        // No reason mentioning expiry, and negative reasons exist
+        assert_eq!(
+            service.write_off_expired_stock(&context, input.clone()),
+            Err(WriteOffExpiredStockError::AdjustmentReasonNotProvided)
+        );
+        assert_eq!(
+            service.write_off_expired_stock(
+                &context,
+                WriteOffExpiredStock {
+                    inventory_adjustment_reason_id: Some("invalid".to_string()),
+                    ..input.clone()
+                }
+            ),
+            Err(WriteOffExpiredStockError::AdjustmentReasonNotValid)
+        );
+
+        // Success, defaulting to the expired reason
+        InventoryAdjustmentReasonRowRepository::new(&connection)
+            .upsert_one(&expired_reason())
+            .unwrap();
+        // Duplicate stock lines are only written off once
+        let invoice = service
+            .write_off_expired_stock(
+                &context,
+                WriteOffExpiredStock {
+                    stock_line_ids: vec![expired_a().id, expired_b().id, expired_a().id],
+                    ..input.clone()
+                },
+            )
+            .unwrap()
+            .invoice_row;
+        assert_eq!(invoice.r#type, InvoiceRowType::InventoryReduction);
+        assert_eq!(invoice.comment, Some("Incinerated".to_string()));
+
+        let lines = InvoiceLineRowRepository::new(&connection)
+            .find_many_by_invoice_id(&invoice.id)
+            .unwrap();
+        assert_eq!(lines.len(), 2);
+        assert!(lines.iter().all(|line| line.number_of_packs == 5.0
+            && line.inventory_adjustment_reason_id == Some(expired_reason().id)));
+
+        let repo = StockLineRowRepository::new(&connection);
+        for id in [expired_a().id, expired_b().id] {
+            let updated = repo.find_one_by_id(&id).unwrap();
+            assert_eq!(updated.total_number_of_packs, 0.0);
+            assert_eq!(updated.available_number_of_packs, 0.0);
+
+            let logs = ActivityLogRowRepository::new(&connection)
+                .find_many_by_record_id(&id)
+                .unwrap();
+            assert_eq!(logs.len(), 1);
+            assert_eq!(logs[0].r#type, ActivityLogType::InventoryAdjustment);
+        }
+
+        // Already written off
+        assert_eq!(
+            service.write_off_expired_stock(&context, input),
+            Err(WriteOffExpiredStockError::StockLineHasNoStock)
+        );
+    }
+}
@@ -0,0 +1,185 @@
+use chrono::{NaiveDate, Utc};
+use repository::{
+    ActivityLogType, InventoryAdjustmentReasonFilter, InventoryAdjustmentReasonRepository,
+    InventoryAdjustmentReasonType, Invoice, InvoiceLineRowRepository, InvoiceRowRepository,
+    LocationMovementRowRepository, RepositoryError, StockLineRow, StockLineRowRepository,
+    StorageConnection,
+};
+
+use crate::{
+    activity_log::activity_log_entry,
+    inventory_adjustment::{
+        insert::{
+            generate_adjustment_invoice, generate_adjustment_line, AdjustmentType,
+            InsertInventoryAdjustmentError,
+        },
+        validate::{check_reason_is_required, check_reason_is_valid},
+    },
+    invoice::query::get_invoice,
+    service_provider::ServiceContext,
+    stock_line::validate::{check_stock_line_exists, check_stock_line_not_allocated, check_store},
+};
+
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct WriteOffExpiredStock {
+    pub stock_line_ids: Vec<String>,
+    /// Defaults to the active reduction reason mentioning expiry, e.g. "Expired"
+    pub inventory_adjustment_reason_id: Option<String>,
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum WriteOffExpiredStockError {
+    DatabaseError(RepositoryError),
+    InternalError(String),
+    NoStockLines,
+    StockDoesNotExist,
+    StockDoesNotBelongToStore,
+    /// Stock line has no expiry date or expires after today
+    StockLineNotExpired,
+    /// Stock line has no packs in store
+    StockLineHasNoStock,
+    /// Stock line is used by an outbound shipment that hasn't been shipped yet
+    StockLineIsAllocated,
+    AdjustmentReasonNotProvided,
+    AdjustmentReasonNotValid,
+    NewlyCreatedInvoiceDoesNotExist,
+}
+
+type OutError = WriteOffExpiredStockError;
+
+/// Removes all packs of the expired stock lines with a single verified inventory reduction, which
+/// is what the destruction certificate report is printed from
+pub fn write_off_expired_stock(
+    ctx: &ServiceContext,
+    input: WriteOffExpiredStock,
+) -> Result<Invoice, OutError> {
+    let invoice = ctx
+        .connection
+        .transaction_sync(|connection| {
+            let today = Utc::now().naive_utc().date();
+            let (stock_lines, reason_id) = validate(connection, &ctx.store_id, &input, today)?;
+            let invoice =
+                generate_adjustment_invoice(ctx, &AdjustmentType::Reduction, input.comment)?;
+            InvoiceRowRepository::new(connection).upsert_one(&invoice)?;
+
+            for stock_line in stock_lines {
+                let number_of_packs = stock_line.total_number_of_packs;
+                let (invoice_line, stock_line, location_movement) = generate_adjustment_line(
+                    ctx,
+                    &invoice.id,
+                    &stock_line,
+                    &AdjustmentType::Reduction,
+                    number_of_packs,
+                    reason_id.clone(),
+                )?;
+                InvoiceLineRowRepository::new(connection).upsert_one(&invoice_line)?;
+                StockLineRowRepository::new(connection).upsert_one(&stock_line)?;
+                if let Some(location_movement) = location_movement {
+                    LocationMovementRowRepository::new(connection)
+                        .upsert_one(&location_movement)?;
+                }
+
+                activity_log_entry(
+                    ctx,
+                    ActivityLogType::InventoryAdjustment,
+                    Some(stock_line.id),
+                    Some(format!(
+                        "Written off {} packs of expired stock",
+                        number_of_packs
+                    )),
+                )?;
+            }
+
+            get_invoice(ctx, None, &invoice.id)?.ok_or(OutError::NewlyCreatedInvoiceDoesNotExist)
+        })
+        .map_err(|error| error.to_inner_error())?;
+    Ok(invoice)
+}
+
+fn validate(
+    connection: &StorageConnection,
+    store_id: &str,
+    input: &WriteOffExpiredStock,
+    today: NaiveDate,
+) -> Result<(Vec<StockLineRow>, Option<String>), OutError> {
+    use WriteOffExpiredStockError::*;
+
+    // a stock line listed twice would be written off twice
+    let mut ids = input.stock_line_ids.clone();
+    ids.sort();
+    ids.dedup();
+    if ids.is_empty() {
+        return Err(NoStockLines);
+    }
+
+    let stock_lines = ids
+        .iter()
+        .map(|id| {
+            let stock_line = check_stock_line_exists(connection, id)?.ok_or(StockDoesNotExist)?;
+            if !check_store(&stock_line, store_id) {
+                return Err(StockDoesNotBelongToStore);
+            }
+            if !matches!(stock_line.expiry_date, Some(expiry_date) if expiry_date <= today) {
+                return Err(StockLineNotExpired);
+            }
+            if stock_line.total_number_of_packs <= 0.0 {
+                return Err(StockLineHasNoStock);
+            }
+            if !check_stock_line_not_allocated(connection, id)? {
+                return Err(StockLineIsAllocated);
+            }
+            Ok(stock_line)
+        })
+        .collect::<Result<Vec<StockLineRow>, OutError>>()?;
+
+    let reason_id = match &input.inventory_adjustment_reason_id {
+        Some(reason_id) => {
+            if !check_reason_is_valid(connection, reason_id, &AdjustmentType::Reduction)? {
+                return Err(AdjustmentReasonNotValid);
+            }
+            Some(reason_id.clone())
+        }
+        None => match expired_reason_id(connection)? {
+            Some(reason_id) => Some(reason_id),
+            None if check_reason_is_required(connection, &AdjustmentType::Reduction)? => {
+                return Err(AdjustmentReasonNotProvided)
+            }
+            None => None,
+        },
+    };
+
+    Ok((stock_lines, reason_id))
+}
+
+/// Active reduction reason mentioning expiry, e.g. "Expired" or "Expiry"
+fn expired_reason_id(connection: &StorageConnection) -> Result<Option<String>, RepositoryError> {
+    let reasons = InventoryAdjustmentReasonRepository::new(connection).query_by_filter(
+        InventoryAdjustmentReasonFilter::new()
+            .r#type(InventoryAdjustmentReasonType::Negative.equal_to())
+            .is_active(true),
+    )?;
+
+    Ok(reasons
+        .into_iter()
+        .map(|reason| reason.inventory_adjustment_reason_row)
+        .find(|reason| reason.reason.to_lowercase().contains("expir"))
+        .map(|reason| reason.id))
+}
+
+impl From<RepositoryError> for WriteOffExpiredStockError {
+    fn from(error: RepositoryError) -> Self {
+        WriteOffExpiredStockError::DatabaseError(error)
+    }
+}
+
+impl From<InsertInventoryAdjustmentError> for WriteOffExpiredStockError {
+    fn from(error: InsertInventoryAdjustmentError) -> Self {
+        match error {
+            InsertInventoryAdjustmentError::DatabaseError(error) => {
+                WriteOffExpiredStockError::DatabaseError(error)
+            }
+            error => WriteOffExpiredStockError::InternalError(format!("{:?}", error)),
+        }
+    }
+}
@@ -0,0 +1,486 @@
+use std::collections::HashMap;
+
+use chrono::{NaiveDate, NaiveDateTime};
+use repository::{
+    DatetimeFilter, EqualFilter, ItemLedgerFilter, ItemLedgerRepository, ItemLedgerRow,
+    LocationMovementFilter, LocationMovementRepository, Pagination, RepositoryError,
+};
+
+use crate::service_provider::ServiceContext;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum HistoricalStockGroupBy {
+    Item,
+    /// One line per stock line
+    Batch,
+    /// One line per item and location
+    Location,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum StockValuationMethod {
+    /// Units are valued at the average unit cost of all receipts of the item up to the date
+    WeightedAverage,
+    /// Units of a batch are valued at the cost of its most recent receipts, i.e. the oldest
+    /// receipts are assumed to be issued first
+    Fifo,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct HistoricalStockInput {
+    pub datetime: NaiveDateTime,
+    pub group_by: HistoricalStockGroupBy,
+    pub valuation_method: StockValuationMethod,
+    pub item_id: Option<EqualFilter<String>>,
+}
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct HistoricalStockLine {
+    pub item_id: String,
+    /// Only set when grouped by batch
+    pub stock_line_id: Option<String>,
+    pub batch: Option<String>,
+    pub expiry_date: Option<NaiveDate>,
+    /// Location at the date, not set when grouped by item
+    pub location_id: Option<String>,
+    /// Units on hand
+    pub quantity: f64,
+    pub value: f64,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct HistoricalStock {
+    pub total_value: f64,
+    pub lines: Vec<HistoricalStockLine>,
+}
+
+pub trait HistoricalStockServiceTrait: Sync + Send {
+    /// Stock on hand of the current store at a past datetime, reconstructed from the stock
+    /// movements of invoices (including stocktake adjustments) up to that datetime
+    fn get_historical_stock(
+        &self,
+        ctx: &ServiceContext,
+        input: HistoricalStockInput,
+    ) -> Result<HistoricalStock, RepositoryError> {
+        get_historical_stock(ctx, input)
+    }
+}
+
+pub struct HistoricalStockService {}
+impl HistoricalStockServiceTrait for HistoricalStockService {}
+
+/// Quantity below which a balance is treated as zero (float rounding of fractional packs)
+const ZERO_QUANTITY: f64 = 0.000001;
+
+struct BatchBalance {
+    line: HistoricalStockLine,
+    /// Received units and their unit cost, in order of receipt
+    receipts: Vec<(f64, f64)>,
+}
+
+pub fn get_historical_stock(
+    ctx: &ServiceContext,
+    input: HistoricalStockInput,
+) -> Result<HistoricalStock, RepositoryError> {
+    let HistoricalStockInput {
+        datetime,
+        group_by,
+        valuation_method,
+        item_id,
+    } = input;
+
+    let mut filter = ItemLedgerFilter::new()
+        .store_id(EqualFilter::equal_to(&ctx.store_id))
+        .datetime(DatetimeFilter::before_or_equal_to(datetime));
+    filter.item_id = item_id;
+    let rows = ItemLedgerRepository::new(&ctx.connection).query_by_filter(filter)?;
+
+    let mut batches = balances_by_batch(rows);
+    // value before dropping empty batches, their receipts count towards the weighted average
+    value_batches(valuation_method, &mut batches);
+    batches.retain(|batch| batch.line.quantity.abs() > ZERO_QUANTITY);
+    locations_at(ctx, datetime, &mut batches)?;
+
+    let mut lines = group_lines(
+        group_by,
+        batches.into_iter().map(|batch| batch.line).collect(),
+    );
+    lines.sort_by(|a, b| {
+        (&a.item_id, &a.location_id, &a.batch, &a.stock_line_id).cmp(&(
+            &b.item_id,
+            &b.location_id,
+            &b.batch,
+            &b.stock_line_id,
+        ))
+    });
+
+    Ok(HistoricalStock {
+        total_value: lines.iter().map(|line| line.value).sum(),
+        lines,
+    })
+}
+
+fn balances_by_batch(rows: Vec<ItemLedgerRow>) -> Vec<BatchBalance> {
+    let mut batches: Vec<BatchBalance> = Vec::new();
+    let mut index: HashMap<(String, Option<String>), usize> = HashMap::new();
+
+    for row in rows {
+        let key = (row.item_id.clone(), row.stock_line_id.clone());
+        let position = *index.entry(key).or_insert_with(|| {
+            batches.push(BatchBalance {
+                line: HistoricalStockLine {
+                    item_id: row.item_id.clone(),
+                    stock_line_id: row.stock_line_id.clone(),
+                    ..Default::default()
+                },
+                receipts: Vec::new(),
+            });
+            batches.len() - 1
+        });
+        let batch = &mut batches[position];
+
+        // rows are ordered by datetime, the latest movement has the latest batch details
+        batch.line.batch = row.batch;
+        batch.line.expiry_date = row.expiry_date;
+        batch.line.location_id = row.location_id;
+        batch.line.quantity += row.quantity;
+        if row.quantity > 0.0 && row.pack_size > 0 {
+            batch
+                .receipts
+                .push((row.quantity, row.cost_price_per_pack / row.pack_size as f64));
+        }
+    }
+
+    batches
+}
+
+/// Use the location movements to find where each batch was at the datetime, stock lines without
+/// location movements keep the location of their last stock movement
+fn locations_at(
+    ctx: &ServiceContext,
+    datetime: NaiveDateTime,
+    batches: &mut [BatchBalance],
+) -> Result<(), RepositoryError> {
+    let stock_line_ids: Vec<String> = batches
+        .iter()
+        .filter_map(|batch| batch.line.stock_line_id.clone())
+        .collect();
+    if stock_line_ids.is_empty() {
+        return Ok(());
+    }
+
+    let movements = LocationMovementRepository::new(&ctx.connection).query(
+        Pagination::all(),
+        Some(
+            LocationMovementFilter::new()
+                .store_id(EqualFilter::equal_to(&ctx.store_id))
+                .stock_line_id(EqualFilter::equal_any(stock_line_ids))
+                .enter_datetime(DatetimeFilter::before_or_equal_to(datetime)),
+        ),
+        None,
+    )?;
+
+    // ordered by enter datetime, the last matching movement wins
+    let mut locations: HashMap<String, Option<String>> = HashMap::new();
+    for movement in movements {
+        let row = movement.location_movement_row;
+        if !matches!(row.exit_datetime, Some(exit) if exit <= datetime) {
+            locations.insert(row.stock_line_id, row.location_id);
+        }
+    }
+
+    for batch in batches.iter_mut() {
+        if let Some(location_id) = batch
+            .line
+            .stock_line_id
+            .as_ref()
+            .and_then(|id| locations.get(id))
+        {
+            batch.line.location_id = location_id.clone();
+        }
+    }
+
+    Ok(())
+}
+
+fn value_batches(method: StockValuationMethod, batches: &mut [BatchBalance]) {
+    match method {
+        StockValuationMethod::WeightedAverage => {
+            let mut totals: HashMap<String, (f64, f64)> = HashMap::new();
+            for batch in batches.iter() {
+                let (units, cost) = totals.entry(batch.line.item_id.clone()).or_default();
+                for (received, unit_cost) in &batch.receipts {
+                    *units += received;
+                    *cost += received * unit_cost;
+                }
+            }
+            for batch in batches.iter_mut() {
+                let average = match totals.get(&batch.line.item_id) {
+                    Some((units, cost)) if *units > 0.0 => cost / units,
+                    _ => 0.0,
+                };
+                batch.line.value = batch.line.quantity * average;
+            }
+        }
+        StockValuationMethod::Fifo => {
+            for batch in batches.iter_mut() {
+                let mut remaining = batch.line.quantity;
+                let mut value = 0.0;
+                for (received, unit_cost) in batch.receipts.iter().rev() {
+                    if remaining <= 0.0 {
+                        break;
+                    }
+                    let units = remaining.min(*received);
+                    value += units * unit_cost;
+                    remaining -= units;
+                }
+                // more units on hand than received (or a negative balance), use the oldest cost
+                if let Some((_, unit_cost)) = batch.receipts.first() {
+                    value += remaining * unit_cost;
+                }
+                batch.line.value = value;
+            }
+        }
+    }
+}
+
+fn group_lines(
+    group_by: HistoricalStockGroupBy,
+    lines: Vec<HistoricalStockLine>,
+) -> Vec<HistoricalStockLine> {
+    let key = |line: &HistoricalStockLine| match group_by {
+        HistoricalStockGroupBy::Batch => None,
+        HistoricalStockGroupBy::Item => Some((line.item_id.clone(), None)),
+        HistoricalStockGroupBy::Location => Some((line.item_id.clone(), line.location_id.clone())),
+    };
+
+    let mut result: Vec<HistoricalStockLine> = Vec::new();
+    let mut index: HashMap<(String, Option<String>), usize> = HashMap::new();
+    for line in lines {
+        let key = match key(&line) {
+            Some(key) => key,
+            None => {
+                result.push(line);
+                continue;
+            }
+        };
+        match index.get(&key) {
+            Some(position) => {
+                let grouped = &mut result[*position];
+                grouped.quantity += line.quantity;
+                grouped.value += line.value;
+            }
+            None => {
+                index.insert(key.clone(), result.len());
+                result.push(HistoricalStockLine {
+                    item_id: key.0,
+                    location_id: key.1,
+                    quantity: line.quantity,
+                    value: line.value,
+                    ..Default::default()
+                });
+            }
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::{NaiveDate, NaiveDateTime};
+    use repository::{
+        mock::{
+            mock_item_a, mock_location_1, mock_name_a, mock_store_a, MockData, MockDataInserts,
+        },
+        test_db::setup_all_with_data,
+        EqualFilter, InvoiceLineRow, InvoiceLineRowType, InvoiceRow, InvoiceRowType,
+        LocationMovementRow, LocationMovementRowRepository, StockLineRow,
+    };
+    use util::{inline_edit, inline_init};
+
+    use crate::service_provider::ServiceProvider;
+
+    use super::*;
+
+    fn datetime(day: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2022, 1, day)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap()
+    }
+
+    fn movement(id: &str, r#type: InvoiceRowType, day: u32, stock_line_id: &str) -> MockData {
+        inline_init(|r: &mut MockData| {
+            r.invoices = vec![inline_init(|r: &mut InvoiceRow| {
+                r.id = id.to_string();
+                r.store_id = mock_store_a().id;
+                r.name_id = mock_name_a().id;
+                r.r#type = r#type.clone();
+                match r#type {
+                    InvoiceRowType::InboundShipment => r.delivered_datetime = Some(datetime(day)),
+                    _ => r.picked_datetime = Some(datetime(day)),
+                }
+            })];
+            r.invoice_lines = vec![inline_init(|r: &mut InvoiceLineRow| {
+                r.id = format!("{}_line", id);
+                r.invoice_id = id.to_string();
+                r.item_id = "historical_item".to_string();
+                r.stock_line_id = Some(stock_line_id.to_string());
+                r.batch = Some(stock_line_id.to_string());
+                r.r#type = match r#type {
+                    InvoiceRowType::InboundShipment => InvoiceLineRowType::StockIn,
+                    _ => InvoiceLineRowType::StockOut,
+                };
+                r.pack_size = 1;
+            })];
+        })
+    }
+
+    fn stock_line(id: &str) -> StockLineRow {
+        inline_init(|r: &mut StockLineRow| {
+            r.id = id.to_string();
+            r.item_id = "historical_item".to_string();
+            r.store_id = mock_store_a().id;
+            r.pack_size = 1;
+        })
+    }
+
+    fn receipt(id: &str, day: u32, stock_line_id: &str, units: f64, cost: f64) -> MockData {
+        inline_edit(
+            &movement(id, InvoiceRowType::InboundShipment, day, stock_line_id),
+            |mut u| {
+                u.invoice_lines[0].number_of_packs = units;
+                u.invoice_lines[0].cost_price_per_pack = cost;
+                u
+            },
+        )
+    }
+
+    fn issue(id: &str, day: u32, stock_line_id: &str, units: f64) -> MockData {
+        inline_edit(
+            &movement(id, InvoiceRowType::OutboundShipment, day, stock_line_id),
+            |mut u| {
+                u.invoice_lines[0].number_of_packs = units;
+                u
+            },
+        )
+    }
+
+    #[actix_rt::test]
+    async fn historical_stock() {
+        let (_, _, connection_manager, _) = setup_all_with_data(
+            "historical_stock",
+            MockDataInserts::all(),
+            inline_init(|r: &mut MockData| {
+                r.items = vec![inline_edit(&mock_item_a(), |mut u| {
+                    u.id = "historical_item".to_string();
+                    u.code = "historical_item".to_string();
+                    u
+                })];
+                r.stock_lines = vec![stock_line("batch_a"), stock_line("batch_b")];
+            })
+            .join(receipt("receipt_a1", 1, "batch_a", 10.0, 1.0))
+            .join(receipt("receipt_a2", 2, "batch_a", 10.0, 2.0))
+            .join(issue("issue_a", 3, "batch_a", 15.0))
+            .join(receipt("receipt_b", 4, "batch_b", 20.0, 4.0))
+            .join(issue("issue_b", 10, "batch_b", 20.0)),
+        )
+        .await;
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider
+            .context(mock_store_a().id, "".to_string())
+            .unwrap();
+        LocationMovementRowRepository::new(&context.connection)
+            .upsert_one(&LocationMovementRow {
+                id: "historical_movement".to_string(),
+                store_id: mock_store_a().id,
+                stock_line_id: "batch_b".to_string(),
+                location_id: Some(mock_location_1().id),
+                enter_datetime: Some(datetime(5)),
+                exit_datetime: None,
+            })
+            .unwrap();
+        let service = service_provider.historical_stock_service;
+        let input = |day: u32, group_by, valuation_method| HistoricalStockInput {
+            datetime: datetime(day),
+            group_by,
+            valuation_method,
+            item_id: Some(EqualFilter::equal_to("historical_item")),
+        };
+
+        // FIFO, the 5 units left of batch a are from the second receipt
+        let result = service
+            .get_historical_stock(
+                &context,
+                input(6, HistoricalStockGroupBy::Batch, StockValuationMethod::Fifo),
+            )
+            .unwrap();
+        assert_eq!(
+            result
+                .lines
+                .iter()
+                .map(|line| (
+                    line.batch.clone().unwrap(),
+                    line.location_id.clone(),
+                    line.quantity,
+                    line.value
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                ("batch_a".to_string(), None, 5.0, 10.0),
+                (
+                    "batch_b".to_string(),
+                    Some(mock_location_1().id),
+                    20.0,
+                    80.0
+                ),
+            ]
+        );
+        assert_eq!(result.total_value, 90.0);
+
+        // Weighted average unit cost of the item: (10 + 20 + 80) / 40
+        let result = service
+            .get_historical_stock(
+                &context,
+                input(
+                    6,
+                    HistoricalStockGroupBy::Item,
+                    StockValuationMethod::WeightedAverage,
+                ),
+            )
+            .unwrap();
+        assert_eq!(result.lines.len(), 1);
+        assert_eq!(result.lines[0].quantity, 25.0);
+        assert_eq!(result.lines[0].value, 25.0 * 110.0 / 40.0);
+
+        // Before batch b was moved
+        let result = service
+            .get_historical_stock(
+                &context,
+                input(
+                    4,
+                    HistoricalStockGroupBy::Location,
+                    StockValuationMethod::Fifo,
+                ),
+            )
+            .unwrap();
+        assert_eq!(result.lines.len(), 1);
+        assert_eq!(result.lines[0].location_id, None);
+        assert_eq!(result.lines[0].quantity, 25.0);
+
+        // Batches with no stock left are not listed
+        let result = service
+            .get_historical_stock(
+                &context,
+                input(
+                    10,
+                    HistoricalStockGroupBy::Batch,
+                    StockValuationMethod::Fifo,
+                ),
+            )
+            .unwrap();
+        assert_eq!(result.lines.len(), 1);
+        assert_eq!(result.lines[0].batch, Some("batch_a".to_string()));
+    }
+}
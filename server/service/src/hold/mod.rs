@@ -0,0 +1,92 @@
+use self::{
+    place::{place_hold, PlaceHold, PlaceHoldError},
+    reason::{upsert_hold_reason, UpsertHoldReason, UpsertHoldReasonError},
+    release::{release_hold, ReleaseHold, ReleaseHoldError},
+};
+
+use crate::service_provider::ServiceContext;
+use chrono::{NaiveDate, Utc};
+use repository::{
+    Hold, HoldFilter, HoldReasonRow, HoldReasonRowRepository, HoldRecordType, HoldRepository,
+    HoldRow, HoldRowRepository, RepositoryError,
+};
+use util::uuid::uuid;
+
+pub mod place;
+pub mod reason;
+pub mod release;
+
+pub trait HoldServiceTrait: Sync + Send {
+    fn get_hold_reasons(
+        &self,
+        ctx: &ServiceContext,
+        active_only: bool,
+    ) -> Result<Vec<HoldReasonRow>, RepositoryError> {
+        HoldReasonRowRepository::new(&ctx.connection).find_all(active_only)
+    }
+
+    fn upsert_hold_reason(
+        &self,
+        ctx: &ServiceContext,
+        input: UpsertHoldReason,
+    ) -> Result<HoldReasonRow, UpsertHoldReasonError> {
+        upsert_hold_reason(ctx, input)
+    }
+
+    /// Details of the current holds, e.g. to show why stock is on hold
+    fn get_holds(
+        &self,
+        ctx: &ServiceContext,
+        filter: HoldFilter,
+    ) -> Result<Vec<Hold>, RepositoryError> {
+        HoldRepository::new(&ctx.connection).query_by_filter(filter)
+    }
+
+    /// Puts a stock line, location or invoice on hold with a reason, or changes the reason of an
+    /// existing hold
+    fn place_hold(&self, ctx: &ServiceContext, input: PlaceHold) -> Result<Hold, PlaceHoldError> {
+        place_hold(ctx, input)
+    }
+
+    fn release_hold(
+        &self,
+        ctx: &ServiceContext,
+        input: ReleaseHold,
+    ) -> Result<(), ReleaseHoldError> {
+        release_hold(ctx, input)
+    }
+}
+
+pub struct HoldService {}
+impl HoldServiceTrait for HoldService {}
+
+/// Records the reason a record was put on hold, replacing the details of a previous hold. The
+/// caller is responsible for setting the on_hold flag of the record.
+pub(crate) fn record_hold(
+    ctx: &ServiceContext,
+    record_type: HoldRecordType,
+    record_id: &str,
+    hold_reason_id: &str,
+    release_date: Option<NaiveDate>,
+) -> Result<HoldRow, RepositoryError> {
+    let repository = HoldRowRepository::new(&ctx.connection);
+    let id = repository
+        .find_one_by_record(&record_type, record_id)?
+        .map(|existing| existing.id)
+        .unwrap_or_else(uuid);
+
+    let hold = HoldRow {
+        id,
+        record_type,
+        record_id: record_id.to_string(),
+        hold_reason_id: hold_reason_id.to_string(),
+        store_id: ctx.store_id.clone(),
+        user_id: (!ctx.user_id.is_empty()).then(|| ctx.user_id.clone()),
+        datetime: Utc::now().naive_utc(),
+        release_date,
+    };
+    repository.upsert_one(&hold)?;
+    Ok(hold)
+}
+
+mod tests;
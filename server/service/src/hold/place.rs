@@ -0,0 +1,167 @@
+use chrono::{NaiveDate, Utc};
+use repository::{
+    ActivityLogType, EqualFilter, Hold, HoldFilter, HoldReasonRow, HoldReasonRowRepository,
+    HoldRecordType, HoldRepository, InvoiceRowRepository, LocationRowRepository, RepositoryError,
+    StockLineRowRepository, StorageConnection,
+};
+
+use crate::{activity_log::activity_log_entry, service_provider::ServiceContext};
+
+use super::record_hold;
+
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct PlaceHold {
+    pub record_type: HoldRecordType,
+    pub record_id: String,
+    pub hold_reason_id: String,
+    /// Date the hold is expected to be released
+    pub release_date: Option<NaiveDate>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum PlaceHoldError {
+    DatabaseError(RepositoryError),
+    RecordDoesNotExist,
+    RecordDoesNotBelongToStore,
+    HoldReasonDoesNotExist,
+    HoldReasonIsNotActive,
+    ReleaseDateInThePast,
+    CreatedHoldDoesNotExist,
+}
+
+type OutError = PlaceHoldError;
+
+pub fn place_hold(ctx: &ServiceContext, input: PlaceHold) -> Result<Hold, OutError> {
+    let result = ctx
+        .connection
+        .transaction_sync(|connection| {
+            let hold_reason = validate(connection, &ctx.store_id, &input)?;
+            set_on_hold(connection, &input.record_type, &input.record_id, true)?;
+            record_hold(
+                ctx,
+                input.record_type.clone(),
+                &input.record_id,
+                &hold_reason.id,
+                input.release_date,
+            )?;
+
+            let event = match input.release_date {
+                Some(release_date) => format!(
+                    "On hold: {}, expected release {}",
+                    hold_reason.reason, release_date
+                ),
+                None => format!("On hold: {}", hold_reason.reason),
+            };
+            activity_log_entry(
+                ctx,
+                on_hold_log_type(&input.record_type),
+                Some(input.record_id.clone()),
+                Some(event),
+            )?;
+
+            HoldRepository::new(connection)
+                .query_by_filter(
+                    HoldFilter::new()
+                        .record_type(input.record_type.equal_to())
+                        .record_id(EqualFilter::equal_to(&input.record_id)),
+                )?
+                .pop()
+                .ok_or(OutError::CreatedHoldDoesNotExist)
+        })
+        .map_err(|error| error.to_inner_error())?;
+    Ok(result)
+}
+
+fn validate(
+    connection: &StorageConnection,
+    store_id: &str,
+    input: &PlaceHold,
+) -> Result<HoldReasonRow, OutError> {
+    use PlaceHoldError::*;
+
+    match record_store_id(connection, &input.record_type, &input.record_id)? {
+        None => return Err(RecordDoesNotExist),
+        Some(record_store_id) if record_store_id != store_id => {
+            return Err(RecordDoesNotBelongToStore)
+        }
+        Some(_) => {}
+    }
+
+    let hold_reason = HoldReasonRowRepository::new(connection)
+        .find_one_by_id(&input.hold_reason_id)?
+        .ok_or(HoldReasonDoesNotExist)?;
+    if !hold_reason.is_active {
+        return Err(HoldReasonIsNotActive);
+    }
+
+    if matches!(input.release_date, Some(date) if date < Utc::now().naive_utc().date()) {
+        return Err(ReleaseDateInThePast);
+    }
+
+    Ok(hold_reason)
+}
+
+/// Store of the held record, None if the record doesn't exist
+pub(crate) fn record_store_id(
+    connection: &StorageConnection,
+    record_type: &HoldRecordType,
+    record_id: &str,
+) -> Result<Option<String>, RepositoryError> {
+    let store_id = match record_type {
+        HoldRecordType::StockLine => StockLineRowRepository::new(connection)
+            .find_one_by_id_option(record_id)?
+            .map(|row| row.store_id),
+        HoldRecordType::Location => LocationRowRepository::new(connection)
+            .find_one_by_id(record_id)?
+            .map(|row| row.store_id),
+        HoldRecordType::Invoice => InvoiceRowRepository::new(connection)
+            .find_one_by_id_option(record_id)?
+            .map(|row| row.store_id),
+    };
+    Ok(store_id)
+}
+
+/// Sets the on_hold flag of the held record
+pub(crate) fn set_on_hold(
+    connection: &StorageConnection,
+    record_type: &HoldRecordType,
+    record_id: &str,
+    on_hold: bool,
+) -> Result<(), RepositoryError> {
+    match record_type {
+        HoldRecordType::StockLine => {
+            let repository = StockLineRowRepository::new(connection);
+            let mut row = repository.find_one_by_id(record_id)?;
+            row.on_hold = on_hold;
+            repository.upsert_one(&row)
+        }
+        HoldRecordType::Location => {
+            let repository = LocationRowRepository::new(connection);
+            let mut row = repository
+                .find_one_by_id(record_id)?
+                .ok_or(RepositoryError::NotFound)?;
+            row.on_hold = on_hold;
+            repository.upsert_one(&row)
+        }
+        HoldRecordType::Invoice => {
+            let repository = InvoiceRowRepository::new(connection);
+            let mut row = repository.find_one_by_id(record_id)?;
+            row.on_hold = on_hold;
+            repository.upsert_one(&row)
+        }
+    }
+}
+
+fn on_hold_log_type(record_type: &HoldRecordType) -> ActivityLogType {
+    match record_type {
+        HoldRecordType::StockLine => ActivityLogType::StockOnHold,
+        HoldRecordType::Location => ActivityLogType::LocationOnHold,
+        HoldRecordType::Invoice => ActivityLogType::InvoiceOnHold,
+    }
+}
+
+impl From<RepositoryError> for PlaceHoldError {
+    fn from(error: RepositoryError) -> Self {
+        PlaceHoldError::DatabaseError(error)
+    }
+}
@@ -0,0 +1,41 @@
+use repository::{HoldReasonRow, HoldReasonRowRepository, RepositoryError};
+
+use crate::service_provider::ServiceContext;
+
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct UpsertHoldReason {
+    pub id: String,
+    pub reason: String,
+    pub is_active: bool,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum UpsertHoldReasonError {
+    DatabaseError(RepositoryError),
+    ReasonNotProvided,
+}
+
+/// Adds or edits a hold reason. Reasons that are in use are deactivated rather than deleted, so
+/// existing holds keep their reason.
+pub fn upsert_hold_reason(
+    ctx: &ServiceContext,
+    input: UpsertHoldReason,
+) -> Result<HoldReasonRow, UpsertHoldReasonError> {
+    if input.reason.trim().is_empty() {
+        return Err(UpsertHoldReasonError::ReasonNotProvided);
+    }
+
+    let row = HoldReasonRow {
+        id: input.id,
+        reason: input.reason.trim().to_string(),
+        is_active: input.is_active,
+    };
+    HoldReasonRowRepository::new(&ctx.connection).upsert_one(&row)?;
+    Ok(row)
+}
+
+impl From<RepositoryError> for UpsertHoldReasonError {
+    fn from(error: RepositoryError) -> Self {
+        UpsertHoldReasonError::DatabaseError(error)
+    }
+}
@@ -0,0 +1,124 @@
+use repository::{
+    ActivityLogType, HoldReasonRowRepository, HoldRecordType, HoldRowRepository,
+    InvoiceRowRepository, LocationRowRepository, RepositoryError, StockLineRowRepository,
+    StorageConnection,
+};
+
+use crate::{
+    activity_log::activity_log_entry, service_provider::ServiceContext,
+    stock_line::validate::check_stock_line_not_pending_inspection,
+};
+
+use super::place::{record_store_id, set_on_hold};
+
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct ReleaseHold {
+    pub record_type: HoldRecordType,
+    pub record_id: String,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ReleaseHoldError {
+    DatabaseError(RepositoryError),
+    RecordDoesNotExist,
+    RecordDoesNotBelongToStore,
+    RecordIsNotOnHold,
+    /// Stock waiting for its goods receipt inspection is released by recording the inspection
+    StockLineIsPendingInspection,
+}
+
+type OutError = ReleaseHoldError;
+
+/// Takes the record off hold, the reason of the released hold is kept in the activity log
+pub fn release_hold(ctx: &ServiceContext, input: ReleaseHold) -> Result<(), OutError> {
+    ctx.connection
+        .transaction_sync(|connection| {
+            validate(connection, &ctx.store_id, &input)?;
+
+            let hold_repository = HoldRowRepository::new(connection);
+            let hold = hold_repository.find_one_by_record(&input.record_type, &input.record_id)?;
+            let hold_reason = match &hold {
+                Some(hold) => {
+                    HoldReasonRowRepository::new(connection).find_one_by_id(&hold.hold_reason_id)?
+                }
+                None => None,
+            };
+
+            set_on_hold(connection, &input.record_type, &input.record_id, false)?;
+            hold_repository.delete_by_record(&input.record_type, &input.record_id)?;
+
+            activity_log_entry(
+                ctx,
+                off_hold_log_type(&input.record_type),
+                Some(input.record_id.clone()),
+                hold_reason
+                    .map(|hold_reason| format!("Released from hold: {}", hold_reason.reason)),
+            )?;
+            Ok(())
+        })
+        .map_err(|error| error.to_inner_error())
+}
+
+fn validate(
+    connection: &StorageConnection,
+    store_id: &str,
+    input: &ReleaseHold,
+) -> Result<(), OutError> {
+    use ReleaseHoldError::*;
+
+    match record_store_id(connection, &input.record_type, &input.record_id)? {
+        None => return Err(RecordDoesNotExist),
+        Some(record_store_id) if record_store_id != store_id => {
+            return Err(RecordDoesNotBelongToStore)
+        }
+        Some(_) => {}
+    }
+
+    if !is_on_hold(connection, &input.record_type, &input.record_id)? {
+        return Err(RecordIsNotOnHold);
+    }
+    if input.record_type == HoldRecordType::StockLine
+        && !check_stock_line_not_pending_inspection(connection, &input.record_id)?
+    {
+        return Err(StockLineIsPendingInspection);
+    }
+
+    Ok(())
+}
+
+fn is_on_hold(
+    connection: &StorageConnection,
+    record_type: &HoldRecordType,
+    record_id: &str,
+) -> Result<bool, RepositoryError> {
+    let on_hold = match record_type {
+        HoldRecordType::StockLine => {
+            StockLineRowRepository::new(connection)
+                .find_one_by_id(record_id)?
+                .on_hold
+        }
+        HoldRecordType::Location => LocationRowRepository::new(connection)
+            .find_one_by_id(record_id)?
+            .is_some_and(|row| row.on_hold),
+        HoldRecordType::Invoice => {
+            InvoiceRowRepository::new(connection)
+                .find_one_by_id(record_id)?
+                .on_hold
+        }
+    };
+    Ok(on_hold)
+}
+
+fn off_hold_log_type(record_type: &HoldRecordType) -> ActivityLogType {
+    match record_type {
+        HoldRecordType::StockLine => ActivityLogType::StockOffHold,
+        HoldRecordType::Location => ActivityLogType::LocationOffHold,
+        HoldRecordType::Invoice => ActivityLogType::InvoiceOffHold,
+    }
+}
+
+impl From<RepositoryError> for ReleaseHoldError {
+    fn from(error: RepositoryError) -> Self {
+        ReleaseHoldError::DatabaseError(error)
+    }
+}
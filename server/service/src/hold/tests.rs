@@ -0,0 +1,408 @@
+#[cfg(test)]
+mod query {
+    use chrono::{Duration, Utc};
+    use repository::{
+        mock::{mock_item_a_stock_line, mock_store_a, mock_store_b, MockData, MockDataInserts},
+        test_db::setup_all_with_data,
+        ActivityLogRowRepository, ActivityLogType, EqualFilter, HoldFilter, HoldRecordType,
+        HoldRowRepository, LocationRow, LocationRowRepository, StockLineFilter,
+        StockLineRepository, StockLineRow, StockLineRowRepository, HOLD_REASON_DAMAGED_ID,
+        HOLD_REASON_QA_PENDING_ID,
+    };
+    use util::{inline_edit, inline_init};
+
+    use crate::{
+        hold::{
+            place::{PlaceHold, PlaceHoldError},
+            reason::{UpsertHoldReason, UpsertHoldReasonError},
+            release::{ReleaseHold, ReleaseHoldError},
+        },
+        location::update::UpdateLocation,
+        service_provider::ServiceProvider,
+        stock_line::{repack::RepackStockLine, split::SplitStockLine, SplitStockLineError},
+    };
+
+    fn stock_a() -> StockLineRow {
+        mock_item_a_stock_line("stock_a", 1, 10.0)
+    }
+
+    fn stock_b() -> StockLineRow {
+        inline_edit(&mock_item_a_stock_line("stock_b", 1, 10.0), |mut u| {
+            u.store_id = mock_store_b().id;
+            u
+        })
+    }
+
+    fn location_a() -> LocationRow {
+        inline_init(|r: &mut LocationRow| {
+            r.id = "location_a".to_string();
+            r.code = "A".to_string();
+            r.store_id = mock_store_a().id;
+        })
+    }
+
+    fn mock_data() -> MockData {
+        inline_init(|r: &mut MockData| {
+            r.stock_lines = vec![stock_a(), stock_b()];
+            r.locations = vec![location_a()];
+        })
+    }
+
+    #[actix_rt::test]
+    async fn place_and_release_stock_line_hold() {
+        let (_, connection, connection_manager, _) = setup_all_with_data(
+            "place_and_release_stock_line_hold",
+            MockDataInserts::none().stores().items().names().units(),
+            mock_data(),
+        )
+        .await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let service = &service_provider.hold_service;
+        let context = service_provider
+            .context(mock_store_a().id, "user".to_string())
+            .unwrap();
+
+        let input = PlaceHold {
+            record_type: HoldRecordType::StockLine,
+            record_id: stock_a().id,
+            hold_reason_id: HOLD_REASON_QA_PENDING_ID.to_string(),
+            release_date: None,
+        };
+
+        // RecordDoesNotExist
+        assert_eq!(
+            service.place_hold(
+                &context,
+                PlaceHold {
+                    record_id: "invalid".to_string(),
+                    ..input.clone()
+                }
+            ),
+            Err(PlaceHoldError::RecordDoesNotExist)
+        );
+        // RecordDoesNotBelongToStore
+        assert_eq!(
+            service.place_hold(
+                &context,
+                PlaceHold {
+                    record_id: stock_b().id,
+                    ..input.clone()
+                }
+            ),
+            Err(PlaceHoldError::RecordDoesNotBelongToStore)
+        );
+        // HoldReasonDoesNotExist
+        assert_eq!(
+            service.place_hold(
+                &context,
+                PlaceHold {
+                    hold_reason_id: "invalid".to_string(),
+                    ..input.clone()
+                }
+            ),
+            Err(PlaceHoldError::HoldReasonDoesNotExist)
+        );
+        // ReleaseDateInThePast
+        assert_eq!(
+            service.place_hold(
+                &context,
+                PlaceHold {
+                    release_date: Some(Utc::now().naive_utc().date() - Duration::days(1)),
+                    ..input.clone()
+                }
+            ),
+            Err(PlaceHoldError::ReleaseDateInThePast)
+        );
+
+        // Success
+        let hold = service.place_hold(&context, input.clone()).unwrap();
+        assert_eq!(hold.hold_reason_row.id, HOLD_REASON_QA_PENDING_ID);
+        assert_eq!(hold.hold_row.user_id, Some("user".to_string()));
+        assert_eq!(hold.hold_row.store_id, mock_store_a().id);
+        assert!(
+            StockLineRowRepository::new(&connection)
+                .find_one_by_id(&stock_a().id)
+                .unwrap()
+                .on_hold
+        );
+
+        // Changing the reason keeps a single hold
+        let release_date = Utc::now().naive_utc().date() + Duration::days(7);
+        let changed = service
+            .place_hold(
+                &context,
+                PlaceHold {
+                    hold_reason_id: HOLD_REASON_DAMAGED_ID.to_string(),
+                    release_date: Some(release_date),
+                    ..input.clone()
+                },
+            )
+            .unwrap();
+        assert_eq!(changed.hold_row.id, hold.hold_row.id);
+        assert_eq!(changed.hold_row.release_date, Some(release_date));
+        assert_eq!(
+            service
+                .get_holds(
+                    &context,
+                    HoldFilter::new().record_id(EqualFilter::equal_to(&stock_a().id))
+                )
+                .unwrap(),
+            vec![changed]
+        );
+
+        // Stock lines can be filtered by hold reason
+        let filter = |hold_reason_id: &str| {
+            StockLineRepository::new(&connection)
+                .query_by_filter(
+                    StockLineFilter::new().hold_reason_id(EqualFilter::equal_to(hold_reason_id)),
+                    None,
+                )
+                .unwrap()
+                .into_iter()
+                .map(|stock_line| stock_line.stock_line_row.id)
+                .collect::<Vec<String>>()
+        };
+        assert_eq!(filter(HOLD_REASON_DAMAGED_ID), vec![stock_a().id]);
+        assert!(filter(HOLD_REASON_QA_PENDING_ID).is_empty());
+
+        // Release
+        service
+            .release_hold(
+                &context,
+                ReleaseHold {
+                    record_type: HoldRecordType::StockLine,
+                    record_id: stock_a().id,
+                },
+            )
+            .unwrap();
+        assert!(
+            !StockLineRowRepository::new(&connection)
+                .find_one_by_id(&stock_a().id)
+                .unwrap()
+                .on_hold
+        );
+        assert_eq!(
+            HoldRowRepository::new(&connection)
+                .find_one_by_record(&HoldRecordType::StockLine, &stock_a().id)
+                .unwrap(),
+            None
+        );
+        assert!(filter(HOLD_REASON_DAMAGED_ID).is_empty());
+
+        // RecordIsNotOnHold
+        assert_eq!(
+            service.release_hold(
+                &context,
+                ReleaseHold {
+                    record_type: HoldRecordType::StockLine,
+                    record_id: stock_a().id,
+                },
+            ),
+            Err(ReleaseHoldError::RecordIsNotOnHold)
+        );
+
+        // Hold history is kept in the activity log
+        let events: Vec<(ActivityLogType, Option<String>)> =
+            ActivityLogRowRepository::new(&connection)
+                .find_many_by_record_id(&stock_a().id)
+                .unwrap()
+                .into_iter()
+                .map(|log| (log.r#type, log.event))
+                .collect();
+        assert_eq!(
+            events,
+            vec![
+                (
+                    ActivityLogType::StockOnHold,
+                    Some("On hold: QA pending".to_string())
+                ),
+                (
+                    ActivityLogType::StockOnHold,
+                    Some(format!("On hold: Damaged, expected release {release_date}"))
+                ),
+                (
+                    ActivityLogType::StockOffHold,
+                    Some("Released from hold: Damaged".to_string())
+                ),
+            ]
+        );
+    }
+
+    #[actix_rt::test]
+    async fn location_hold_and_hold_reasons() {
+        let (_, connection, connection_manager, _) = setup_all_with_data(
+            "location_hold_and_hold_reasons",
+            MockDataInserts::none().stores().items().names().units(),
+            mock_data(),
+        )
+        .await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let service = &service_provider.hold_service;
+        let context = service_provider
+            .context(mock_store_a().id, "user".to_string())
+            .unwrap();
+
+        // Reasons created by migration
+        assert_eq!(service.get_hold_reasons(&context, true).unwrap().len(), 5);
+
+        assert_eq!(
+            service.upsert_hold_reason(
+                &context,
+                UpsertHoldReason {
+                    id: "cold_chain".to_string(),
+                    reason: " ".to_string(),
+                    is_active: true,
+                }
+            ),
+            Err(UpsertHoldReasonError::ReasonNotProvided)
+        );
+        service
+            .upsert_hold_reason(
+                &context,
+                UpsertHoldReason {
+                    id: "cold_chain".to_string(),
+                    reason: "Cold chain breach".to_string(),
+                    is_active: false,
+                },
+            )
+            .unwrap();
+        assert_eq!(service.get_hold_reasons(&context, true).unwrap().len(), 5);
+        assert_eq!(service.get_hold_reasons(&context, false).unwrap().len(), 6);
+
+        let input = PlaceHold {
+            record_type: HoldRecordType::Location,
+            record_id: location_a().id,
+            hold_reason_id: "cold_chain".to_string(),
+            release_date: None,
+        };
+        // HoldReasonIsNotActive
+        assert_eq!(
+            service.place_hold(&context, input.clone()),
+            Err(PlaceHoldError::HoldReasonIsNotActive)
+        );
+
+        service
+            .place_hold(
+                &context,
+                PlaceHold {
+                    hold_reason_id: HOLD_REASON_DAMAGED_ID.to_string(),
+                    ..input
+                },
+            )
+            .unwrap();
+        let location_repo = LocationRowRepository::new(&connection);
+        assert!(
+            location_repo
+                .find_one_by_id(&location_a().id)
+                .unwrap()
+                .unwrap()
+                .on_hold
+        );
+
+        // Taking the location off hold through a location update removes the hold details
+        service_provider
+            .location_service
+            .update_location(
+                &context,
+                inline_init(|r: &mut UpdateLocation| {
+                    r.id = location_a().id;
+                    r.on_hold = Some(false);
+                }),
+            )
+            .unwrap();
+        assert_eq!(
+            HoldRowRepository::new(&connection)
+                .find_one_by_record(&HoldRecordType::Location, &location_a().id)
+                .unwrap(),
+            None
+        );
+
+        let logs = ActivityLogRowRepository::new(&connection)
+            .find_many_by_record_id(&location_a().id)
+            .unwrap();
+        assert_eq!(logs.len(), 1);
+        assert_eq!(logs[0].r#type, ActivityLogType::LocationOnHold);
+    }
+
+    #[actix_rt::test]
+    async fn split_and_repack_keep_stock_line_hold() {
+        let (_, connection, connection_manager, _) = setup_all_with_data(
+            "split_and_repack_keep_stock_line_hold",
+            MockDataInserts::none().stores().items().names().units(),
+            mock_data(),
+        )
+        .await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider
+            .context(mock_store_a().id, "user".to_string())
+            .unwrap();
+        let release_date = Utc::now().naive_utc().date() + Duration::days(7);
+        service_provider
+            .hold_service
+            .place_hold(
+                &context,
+                PlaceHold {
+                    record_type: HoldRecordType::StockLine,
+                    record_id: stock_a().id,
+                    hold_reason_id: HOLD_REASON_DAMAGED_ID.to_string(),
+                    release_date: Some(release_date),
+                },
+            )
+            .unwrap();
+
+        let split = service_provider
+            .stock_line_service
+            .split_stock_line(
+                &context,
+                inline_init(|r: &mut SplitStockLine| {
+                    r.id = stock_a().id;
+                    r.number_of_packs = 4.0;
+                }),
+            )
+            .unwrap()
+            .stock_line_row;
+        let repacked = service_provider
+            .stock_line_service
+            .repack_stock_line(
+                &context,
+                inline_init(|r: &mut RepackStockLine| {
+                    r.id = stock_a().id;
+                    r.number_of_packs = 2.0;
+                    r.new_pack_size = 2;
+                }),
+            )
+            .unwrap()
+            .stock_line_row;
+        // Held stock can't be released by a split
+        assert_eq!(
+            service_provider.stock_line_service.split_stock_line(
+                &context,
+                inline_init(|r: &mut SplitStockLine| {
+                    r.id = stock_a().id;
+                    r.number_of_packs = 1.0;
+                    r.on_hold = Some(false);
+                }),
+            ),
+            Err(SplitStockLineError::CannotReleaseHold)
+        );
+
+        let hold_repo = HoldRowRepository::new(&connection);
+        for stock_line in [&split, &repacked] {
+            assert!(stock_line.on_hold);
+            let hold = hold_repo
+                .find_one_by_record(&HoldRecordType::StockLine, &stock_line.id)
+                .unwrap()
+                .unwrap();
+            assert_eq!(hold.hold_reason_id, HOLD_REASON_DAMAGED_ID);
+            assert_eq!(hold.release_date, Some(release_date));
+        }
+        // The hold of the existing stock line is kept
+        assert!(hold_repo
+            .find_one_by_record(&HoldRecordType::StockLine, &stock_a().id)
+            .unwrap()
+            .is_some());
+    }
+}
@@ -0,0 +1,496 @@
+use chrono::Utc;
+use repository::{
+    ActivityLogType, EqualFilter, Invoice, InvoiceLineRow, InvoiceLineRowRepository,
+    InvoiceLineRowType, InvoiceRow, InvoiceRowRepository, InvoiceRowStatus, InvoiceRowType,
+    ItemRowRepository, LocationMovementRow, LocationMovementRowRepository, NameRowRepository,
+    NumberRowType, RepositoryError, StockLine, StockLineFilter, StockLineRepository, StockLineRow,
+    StockLineRowRepository, StorageConnection,
+};
+use util::{constants::INVENTORY_ADJUSTMENT_NAME_CODE, uuid::uuid};
+
+use super::validate::{check_reason_is_required, check_reason_is_valid};
+use crate::{
+    activity_log::activity_log_entry, invoice::query::get_invoice, number::next_number,
+    service_provider::ServiceContext, stock_line::stock_transfer::generate_exit_location_movement,
+};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum AdjustmentType {
+    Addition,
+    Reduction,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct InsertInventoryAdjustment {
+    pub stock_line_id: String,
+    pub adjustment_type: AdjustmentType,
+    /// Number of packs added or removed
+    pub adjustment: f64,
+    pub inventory_adjustment_reason_id: Option<String>,
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum InsertInventoryAdjustmentError {
+    StockLineDoesNotExist,
+    StockLineDoesNotBelongToCurrentStore,
+    /// Adjustment must be a positive number of packs
+    InvalidAdjustment,
+    /// Reduction is more than the available number of packs of the stock line
+    StockLineReducedBelowZero(Box<StockLine>),
+    AdjustmentReasonNotProvided,
+    AdjustmentReasonNotValid,
+    NewlyCreatedInvoiceDoesNotExist,
+    InternalError(String),
+    DatabaseError(RepositoryError),
+}
+
+type OutError = InsertInventoryAdjustmentError;
+
+/// Rows written for an adjustment of a single stock line
+pub(crate) struct InventoryAdjustmentJob {
+    pub invoice: InvoiceRow,
+    pub invoice_line: InvoiceLineRow,
+    pub stock_line: StockLineRow,
+    pub location_movement: Option<LocationMovementRow>,
+}
+
+pub fn insert_inventory_adjustment(
+    ctx: &ServiceContext,
+    input: InsertInventoryAdjustment,
+) -> Result<Invoice, InsertInventoryAdjustmentError> {
+    let invoice = ctx
+        .connection
+        .transaction_sync(|connection| {
+            let stock_line = validate(connection, &ctx.store_id, &input)?;
+            let job = generate_inventory_adjustment(
+                ctx,
+                &stock_line.stock_line_row,
+                &input.adjustment_type,
+                input.adjustment,
+                input.inventory_adjustment_reason_id,
+                input.comment,
+            )?;
+            let invoice_id = job.invoice.id.clone();
+            let event = format!(
+                "{} {} packs",
+                match input.adjustment_type {
+                    AdjustmentType::Addition => "Added",
+                    AdjustmentType::Reduction => "Reduced",
+                },
+                input.adjustment
+            );
+            write_inventory_adjustment(connection, job)?;
+
+            activity_log_entry(
+                ctx,
+                ActivityLogType::InventoryAdjustment,
+                Some(input.stock_line_id),
+                Some(event),
+            )?;
+
+            get_invoice(ctx, None, &invoice_id)?.ok_or(OutError::NewlyCreatedInvoiceDoesNotExist)
+        })
+        .map_err(|error| error.to_inner_error())?;
+    Ok(invoice)
+}
+
+fn validate(
+    connection: &StorageConnection,
+    store_id: &str,
+    input: &InsertInventoryAdjustment,
+) -> Result<StockLine, OutError> {
+    let stock_line = StockLineRepository::new(connection)
+        .query_by_filter(
+            StockLineFilter::new().id(EqualFilter::equal_to(&input.stock_line_id)),
+            Some(store_id.to_string()),
+        )?
+        .pop()
+        .ok_or(OutError::StockLineDoesNotExist)?;
+    if stock_line.stock_line_row.store_id != store_id {
+        return Err(OutError::StockLineDoesNotBelongToCurrentStore);
+    }
+    if input.adjustment <= 0.0 {
+        return Err(OutError::InvalidAdjustment);
+    }
+    if input.adjustment_type == AdjustmentType::Reduction
+        && stock_line.stock_line_row.available_number_of_packs < input.adjustment
+    {
+        return Err(OutError::StockLineReducedBelowZero(Box::new(stock_line)));
+    }
+
+    match &input.inventory_adjustment_reason_id {
+        Some(reason_id) => {
+            if !check_reason_is_valid(connection, reason_id, &input.adjustment_type)? {
+                return Err(OutError::AdjustmentReasonNotValid);
+            }
+        }
+        None => {
+            if check_reason_is_required(connection, &input.adjustment_type)? {
+                return Err(OutError::AdjustmentReasonNotProvided);
+            }
+        }
+    }
+
+    Ok(stock_line)
+}
+
+/// Generates the verified inventory addition or reduction (with the same invoice and invoice
+/// line as a finalised stocktake) and the adjusted stock line
+pub(crate) fn generate_inventory_adjustment(
+    ctx: &ServiceContext,
+    stock_line: &StockLineRow,
+    adjustment_type: &AdjustmentType,
+    number_of_packs: f64,
+    inventory_adjustment_reason_id: Option<String>,
+    comment: Option<String>,
+) -> Result<InventoryAdjustmentJob, OutError> {
+    let invoice = generate_adjustment_invoice(ctx, adjustment_type, comment)?;
+    let (invoice_line, stock_line, location_movement) = generate_adjustment_line(
+        ctx,
+        &invoice.id,
+        stock_line,
+        adjustment_type,
+        number_of_packs,
+        inventory_adjustment_reason_id,
+    )?;
+
+    Ok(InventoryAdjustmentJob {
+        invoice,
+        invoice_line,
+        stock_line,
+        location_movement,
+    })
+}
+
+/// Verified inventory addition or reduction without lines
+pub(crate) fn generate_adjustment_invoice(
+    ctx: &ServiceContext,
+    adjustment_type: &AdjustmentType,
+    comment: Option<String>,
+) -> Result<InvoiceRow, OutError> {
+    let inventory_adjustment_name = NameRowRepository::new(&ctx.connection)
+        .find_one_by_code(INVENTORY_ADJUSTMENT_NAME_CODE)?
+        .ok_or(OutError::InternalError(
+            "Missing inventory adjustment name".to_string(),
+        ))?;
+
+    let invoice =
+        adjustment_invoice_row(ctx, &inventory_adjustment_name.id, adjustment_type, comment)?;
+    Ok(invoice)
+}
+
+/// Verified inventory addition or reduction of the store to the inventory adjustment name, with
+/// the next invoice number of its type
+pub(crate) fn adjustment_invoice_row(
+    ctx: &ServiceContext,
+    inventory_adjustment_name_id: &str,
+    adjustment_type: &AdjustmentType,
+    comment: Option<String>,
+) -> Result<InvoiceRow, RepositoryError> {
+    let (invoice_type, number_type) = match adjustment_type {
+        AdjustmentType::Addition => (
+            InvoiceRowType::InventoryAddition,
+            NumberRowType::InventoryAddition,
+        ),
+        AdjustmentType::Reduction => (
+            InvoiceRowType::InventoryReduction,
+            NumberRowType::InventoryReduction,
+        ),
+    };
+
+    let now = Utc::now().naive_utc();
+    Ok(InvoiceRow {
+        id: uuid(),
+        invoice_number: next_number(&ctx.connection, &number_type, &ctx.store_id)?,
+        r#type: invoice_type,
+        user_id: Some(ctx.user_id.clone()),
+        name_id: inventory_adjustment_name_id.to_string(),
+        store_id: ctx.store_id.clone(),
+        status: InvoiceRowStatus::Verified,
+        verified_datetime: Some(now),
+        comment,
+        created_datetime: now,
+        name_store_id: None,
+        transport_reference: None,
+        on_hold: false,
+        their_reference: None,
+        allocated_datetime: None,
+        picked_datetime: None,
+        shipped_datetime: None,
+        delivered_datetime: None,
+        colour: None,
+        requisition_id: None,
+        linked_invoice_id: None,
+        tax: None,
+    })
+}
+
+/// Invoice line of an adjustment, the adjusted stock line and the exit from its location when
+/// it's left without stock
+pub(crate) fn generate_adjustment_line(
+    ctx: &ServiceContext,
+    invoice_id: &str,
+    stock_line: &StockLineRow,
+    adjustment_type: &AdjustmentType,
+    number_of_packs: f64,
+    inventory_adjustment_reason_id: Option<String>,
+) -> Result<(InvoiceLineRow, StockLineRow, Option<LocationMovementRow>), OutError> {
+    let connection = &ctx.connection;
+    let item = ItemRowRepository::new(connection)
+        .find_one_by_id(&stock_line.item_id)?
+        .ok_or(OutError::InternalError(format!(
+            "Can't find item {} of stock line {}",
+            stock_line.item_id, stock_line.id
+        )))?;
+
+    let (line_type, delta) = match adjustment_type {
+        AdjustmentType::Addition => (InvoiceLineRowType::StockIn, number_of_packs),
+        AdjustmentType::Reduction => (InvoiceLineRowType::StockOut, -number_of_packs),
+    };
+
+    let invoice_line = InvoiceLineRow {
+        id: uuid(),
+        invoice_id: invoice_id.to_string(),
+        r#type: line_type,
+        item_id: item.id,
+        item_name: item.name,
+        item_code: item.code,
+        stock_line_id: Some(stock_line.id.clone()),
+        location_id: stock_line.location_id.clone(),
+        batch: stock_line.batch.clone(),
+        expiry_date: stock_line.expiry_date,
+        pack_size: stock_line.pack_size,
+        cost_price_per_pack: stock_line.cost_price_per_pack,
+        sell_price_per_pack: stock_line.sell_price_per_pack,
+        total_before_tax: 0.0,
+        total_after_tax: 0.0,
+        tax: None,
+        number_of_packs,
+        note: stock_line.note.clone(),
+        inventory_adjustment_reason_id,
+    };
+
+    let adjusted_stock_line = StockLineRow {
+        available_number_of_packs: stock_line.available_number_of_packs + delta,
+        total_number_of_packs: stock_line.total_number_of_packs + delta,
+        ..stock_line.clone()
+    };
+    let location_movement = if adjusted_stock_line.total_number_of_packs <= 0.0 {
+        generate_exit_location_movement(connection, &ctx.store_id, &adjusted_stock_line)?
+    } else {
+        None
+    };
+
+    Ok((invoice_line, adjusted_stock_line, location_movement))
+}
+
+pub(crate) fn write_inventory_adjustment(
+    connection: &StorageConnection,
+    job: InventoryAdjustmentJob,
+) -> Result<(), RepositoryError> {
+    let InventoryAdjustmentJob {
+        invoice,
+        invoice_line,
+        stock_line,
+        location_movement,
+    } = job;
+
+    InvoiceRowRepository::new(connection).upsert_one(&invoice)?;
+    InvoiceLineRowRepository::new(connection).upsert_one(&invoice_line)?;
+    StockLineRowRepository::new(connection).upsert_one(&stock_line)?;
+    if let Some(location_movement) = location_movement {
+        LocationMovementRowRepository::new(connection).upsert_one(&location_movement)?;
+    }
+
+    Ok(())
+}
+
+impl From<RepositoryError> for InsertInventoryAdjustmentError {
+    fn from(error: RepositoryError) -> Self {
+        InsertInventoryAdjustmentError::DatabaseError(error)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use repository::{
+        mock::{mock_item_a, mock_store_a, MockData, MockDataInserts},
+        test_db::setup_all_with_data,
+        ActivityLogRowRepository, ActivityLogType, InventoryAdjustmentReasonRow,
+        InventoryAdjustmentReasonType, InvoiceLineRowRepository, InvoiceLineRowType,
+        InvoiceRowStatus, InvoiceRowType, StockLineRow, StockLineRowRepository,
+    };
+    use util::inline_init;
+
+    use crate::{
+        inventory_adjustment::insert::{
+            AdjustmentType, InsertInventoryAdjustment,
+            InsertInventoryAdjustmentError as ServiceError,
+        },
+        service_provider::ServiceProvider,
+    };
+
+    fn negative_reason() -> InventoryAdjustmentReasonRow {
+        inline_init(|r: &mut InventoryAdjustmentReasonRow| {
+            r.id = "negative_reason".to_string();
+            r.is_active = true;
+            r.r#type = InventoryAdjustmentReasonType::Negative;
+            r.reason = "Broken".to_string();
+        })
+    }
+
+    fn stock_line() -> StockLineRow {
+        inline_init(|r: &mut StockLineRow| {
+            r.id = "stock_line".to_string();
+            r.store_id = mock_store_a().id;
+            r.item_id = mock_item_a().id;
+            r.pack_size = 10;
+            r.total_number_of_packs = 5.0;
+            r.available_number_of_packs = 4.0;
+        })
+    }
+
+    #[actix_rt::test]
+    async fn insert_inventory_adjustment() {
+        let (_, connection, connection_manager, _) = setup_all_with_data(
+            "insert_inventory_adjustment",
+            MockDataInserts::none().stores().items().names().units(),
+            inline_init(|r: &mut MockData| {
+                r.stock_lines = vec![stock_line()];
+                r.inventory_adjustment_reasons = vec![negative_reason()];
+            }),
+        )
+        .await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider
+            .context(mock_store_a().id, "user".to_string())
+            .unwrap();
+        let service = service_provider.inventory_adjustment_service;
+
+        let reduction = InsertInventoryAdjustment {
+            stock_line_id: stock_line().id,
+            adjustment_type: AdjustmentType::Reduction,
+            adjustment: 1.0,
+            inventory_adjustment_reason_id: None,
+            comment: None,
+        };
+
+        // Errors
+        assert_eq!(
+            service.insert_inventory_adjustment(
+                &context,
+                InsertInventoryAdjustment {
+                    stock_line_id: "invalid".to_string(),
+                    ..reduction.clone()
+                }
+            ),
+            Err(ServiceError::StockLineDoesNotExist)
+        );
+        assert_eq!(
+            service.insert_inventory_adjustment(
+                &context,
+                InsertInventoryAdjustment {
+                    adjustment: 0.0,
+                    ..reduction.clone()
+                }
+            ),
+            Err(ServiceError::InvalidAdjustment)
+        );
+        // Reason is required when negative reasons exist
+        assert_eq!(
+            service.insert_inventory_adjustment(&context, reduction.clone()),
+            Err(ServiceError::AdjustmentReasonNotProvided)
+        );
+        // Negative reason can't be used for an addition
+        assert_eq!(
+            service.insert_inventory_adjustment(
+                &context,
+                InsertInventoryAdjustment {
+                    adjustment_type: AdjustmentType::Addition,
+                    inventory_adjustment_reason_id: Some(negative_reason().id),
+                    ..reduction.clone()
+                }
+            ),
+            Err(ServiceError::AdjustmentReasonNotValid)
+        );
+        // Only available packs can be reduced
+        assert!(matches!(
+            service.insert_inventory_adjustment(
+                &context,
+                InsertInventoryAdjustment {
+                    adjustment: 5.0,
+                    inventory_adjustment_reason_id: Some(negative_reason().id),
+                    ..reduction.clone()
+                }
+            ),
+            Err(ServiceError::StockLineReducedBelowZero(_))
+        ));
+
+        // Success
+        let invoice = service
+            .insert_inventory_adjustment(
+                &context,
+                InsertInventoryAdjustment {
+                    adjustment: 1.5,
+                    inventory_adjustment_reason_id: Some(negative_reason().id),
+                    comment: Some("Dropped carton".to_string()),
+                    ..reduction.clone()
+                },
+            )
+            .unwrap();
+        let invoice_row = invoice.invoice_row;
+        assert_eq!(invoice_row.r#type, InvoiceRowType::InventoryReduction);
+        assert_eq!(invoice_row.status, InvoiceRowStatus::Verified);
+        assert_eq!(invoice_row.comment, Some("Dropped carton".to_string()));
+
+        let lines = InvoiceLineRowRepository::new(&connection)
+            .find_many_by_invoice_id(&invoice_row.id)
+            .unwrap();
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].r#type, InvoiceLineRowType::StockOut);
+        assert_eq!(lines[0].number_of_packs, 1.5);
+        assert_eq!(lines[0].stock_line_id, Some(stock_line().id));
+        assert_eq!(
+            lines[0].inventory_adjustment_reason_id,
+            Some(negative_reason().id)
+        );
+
+        let updated = StockLineRowRepository::new(&connection)
+            .find_one_by_id(&stock_line().id)
+            .unwrap();
+        assert_eq!(updated.total_number_of_packs, 3.5);
+        assert_eq!(updated.available_number_of_packs, 2.5);
+
+        // No positive reasons, so additions don't need a reason
+        let invoice = service
+            .insert_inventory_adjustment(
+                &context,
+                InsertInventoryAdjustment {
+                    adjustment_type: AdjustmentType::Addition,
+                    adjustment: 2.0,
+                    ..reduction
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            invoice.invoice_row.r#type,
+            InvoiceRowType::InventoryAddition
+        );
+        let updated = StockLineRowRepository::new(&connection)
+            .find_one_by_id(&stock_line().id)
+            .unwrap();
+        assert_eq!(updated.total_number_of_packs, 5.5);
+
+        let logs = ActivityLogRowRepository::new(&connection)
+            .find_many_by_record_id(&stock_line().id)
+            .unwrap();
+        assert_eq!(
+            logs.iter()
+                .filter(|log| log.r#type == ActivityLogType::InventoryAdjustment)
+                .count(),
+            2
+        );
+    }
+}
@@ -0,0 +1,24 @@
+use self::insert::{
+    insert_inventory_adjustment, InsertInventoryAdjustment, InsertInventoryAdjustmentError,
+};
+
+use crate::service_provider::ServiceContext;
+use repository::Invoice;
+
+pub mod insert;
+pub(crate) mod validate;
+
+pub trait InventoryAdjustmentServiceTrait: Sync + Send {
+    /// Adds or removes packs of a stock line, recorded as a verified inventory addition or
+    /// reduction
+    fn insert_inventory_adjustment(
+        &self,
+        ctx: &ServiceContext,
+        input: InsertInventoryAdjustment,
+    ) -> Result<Invoice, InsertInventoryAdjustmentError> {
+        insert_inventory_adjustment(ctx, input)
+    }
+}
+
+pub struct InventoryAdjustmentService {}
+impl InventoryAdjustmentServiceTrait for InventoryAdjustmentService {}
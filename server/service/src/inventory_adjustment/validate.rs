@@ -0,0 +1,42 @@
+use repository::{
+    EqualFilter, InventoryAdjustmentReasonFilter, InventoryAdjustmentReasonRepository,
+    InventoryAdjustmentReasonType, RepositoryError, StorageConnection,
+};
+
+use super::insert::AdjustmentType;
+
+fn reason_type(adjustment_type: &AdjustmentType) -> InventoryAdjustmentReasonType {
+    match adjustment_type {
+        AdjustmentType::Addition => InventoryAdjustmentReasonType::Positive,
+        AdjustmentType::Reduction => InventoryAdjustmentReasonType::Negative,
+    }
+}
+
+/// Reasons are mandatory when there are active reasons for the adjustment type
+pub fn check_reason_is_required(
+    connection: &StorageConnection,
+    adjustment_type: &AdjustmentType,
+) -> Result<bool, RepositoryError> {
+    let count = InventoryAdjustmentReasonRepository::new(connection).count(Some(
+        InventoryAdjustmentReasonFilter::new()
+            .r#type(reason_type(adjustment_type).equal_to())
+            .is_active(true),
+    ))?;
+
+    Ok(count > 0)
+}
+
+pub fn check_reason_is_valid(
+    connection: &StorageConnection,
+    reason_id: &str,
+    adjustment_type: &AdjustmentType,
+) -> Result<bool, RepositoryError> {
+    let count = InventoryAdjustmentReasonRepository::new(connection).count(Some(
+        InventoryAdjustmentReasonFilter::new()
+            .id(EqualFilter::equal_to(reason_id))
+            .r#type(reason_type(adjustment_type).equal_to())
+            .is_active(true),
+    ))?;
+
+    Ok(count == 1)
+}
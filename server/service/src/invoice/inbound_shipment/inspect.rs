@@ -0,0 +1,601 @@
+use crate::{
+    activity_log::activity_log_entry,
+    hold::{place::set_on_hold, record_hold},
+    invoice::{
+        check_invoice_exists, check_invoice_is_editable, check_invoice_type, check_store,
+        query::get_invoice,
+    },
+    service_provider::ServiceContext,
+    store_preference::get_store_preferences,
+};
+use chrono::Utc;
+use repository::{
+    ActivityLogType, EqualFilter, GoodsReceiptInspectionRow, GoodsReceiptInspectionRowRepository,
+    GoodsReceiptInspectionStatus, HoldRecordType, HoldRow, HoldRowRepository, Invoice,
+    InvoiceLineRow, InvoiceLineRowRepository, InvoiceRow, InvoiceRowStatus, InvoiceRowType,
+    LocationFilter, LocationRepository, LocationType, RepositoryError, StockLineRowRepository,
+    StorageConnection, HOLD_REASON_QA_PENDING_ID, HOLD_REASON_QUARANTINE_ID,
+};
+use util::uuid::uuid;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct InspectInboundShipmentLine {
+    pub invoice_line_id: String,
+    pub passed: bool,
+    pub note: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct InspectInboundShipment {
+    pub invoice_id: String,
+    pub lines: Vec<InspectInboundShipmentLine>,
+    /// Quarantine location stock of failed lines is moved to
+    pub quarantine_location_id: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum InspectInboundShipmentError {
+    InvoiceDoesNotExist,
+    NotAnInboundShipment,
+    NotThisStoreInvoice,
+    CannotEditFinalised,
+    NoLinesProvided,
+    LocationDoesNotExist,
+    /// Location must be a quarantine location of the store
+    LocationIsNotQuarantine,
+    LineDoesNotExist(String),
+    NotThisInvoiceLine(String),
+    LineIsNotPendingInspection(String),
+    DatabaseError(RepositoryError),
+    UpdatedInvoiceDoesNotExist,
+}
+
+type OutError = InspectInboundShipmentError;
+
+/// Records the QA inspection result of received lines. Stock of passed lines is released from
+/// hold, stock of failed lines stays on hold as quarantined.
+pub fn inspect_inbound_shipment(
+    ctx: &ServiceContext,
+    input: InspectInboundShipment,
+) -> Result<Invoice, OutError> {
+    let invoice = ctx
+        .connection
+        .transaction_sync(|connection| {
+            let inspections = validate(connection, &ctx.store_id, &input)?;
+
+            let inspection_repository = GoodsReceiptInspectionRowRepository::new(connection);
+            for (line, inspection) in input.lines.iter().zip(inspections) {
+                if line.passed {
+                    release_inspected_stock(ctx, &inspection.stock_line_id)?;
+                } else {
+                    quarantine_inspected_stock(
+                        ctx,
+                        &inspection,
+                        input.quarantine_location_id.clone(),
+                    )?;
+                }
+
+                inspection_repository.upsert_one(&GoodsReceiptInspectionRow {
+                    status: match line.passed {
+                        true => GoodsReceiptInspectionStatus::Passed,
+                        false => GoodsReceiptInspectionStatus::Failed,
+                    },
+                    note: line.note.clone(),
+                    user_id: Some(ctx.user_id.clone()),
+                    inspected_datetime: Some(Utc::now().naive_utc()),
+                    ..inspection
+                })?;
+            }
+
+            get_invoice(ctx, None, &input.invoice_id)
+                .map_err(OutError::DatabaseError)?
+                .ok_or(OutError::UpdatedInvoiceDoesNotExist)
+        })
+        .map_err(|error| error.to_inner_error())?;
+
+    Ok(invoice)
+}
+
+fn validate(
+    connection: &StorageConnection,
+    store_id: &str,
+    input: &InspectInboundShipment,
+) -> Result<Vec<GoodsReceiptInspectionRow>, OutError> {
+    use InspectInboundShipmentError::*;
+
+    let invoice =
+        check_invoice_exists(&input.invoice_id, connection)?.ok_or(InvoiceDoesNotExist)?;
+    if !check_store(&invoice, store_id) {
+        return Err(NotThisStoreInvoice);
+    }
+    if !check_invoice_type(&invoice, InvoiceRowType::InboundShipment) {
+        return Err(NotAnInboundShipment);
+    }
+    if !check_invoice_is_editable(&invoice) {
+        return Err(CannotEditFinalised);
+    }
+    if input.lines.is_empty() {
+        return Err(NoLinesProvided);
+    }
+
+    if let Some(location_id) = &input.quarantine_location_id {
+        let location = LocationRepository::new(connection)
+            .query_by_filter(
+                LocationFilter::new()
+                    .id(EqualFilter::equal_to(location_id))
+                    .store_id(EqualFilter::equal_to(store_id)),
+            )?
+            .pop()
+            .ok_or(LocationDoesNotExist)?;
+        if location.location_row.location_type != LocationType::Quarantine {
+            return Err(LocationIsNotQuarantine);
+        }
+    }
+
+    let line_repository = InvoiceLineRowRepository::new(connection);
+    let inspection_repository = GoodsReceiptInspectionRowRepository::new(connection);
+    input
+        .lines
+        .iter()
+        .map(
+            |InspectInboundShipmentLine {
+                 invoice_line_id, ..
+             }| {
+                let line = line_repository
+                    .find_one_by_id_option(invoice_line_id)?
+                    .ok_or_else(|| LineDoesNotExist(invoice_line_id.clone()))?;
+                if line.invoice_id != invoice.id {
+                    return Err(NotThisInvoiceLine(line.invoice_id));
+                }
+                inspection_repository
+                    .find_one_by_invoice_line_id(invoice_line_id)?
+                    .filter(|inspection| inspection.status == GoodsReceiptInspectionStatus::Pending)
+                    .ok_or_else(|| LineIsNotPendingInspection(invoice_line_id.clone()))
+            },
+        )
+        .collect()
+}
+
+fn release_inspected_stock(ctx: &ServiceContext, stock_line_id: &str) -> Result<(), OutError> {
+    let connection = &ctx.connection;
+    set_on_hold(connection, &HoldRecordType::StockLine, stock_line_id, false)?;
+    HoldRowRepository::new(connection)
+        .delete_by_record(&HoldRecordType::StockLine, stock_line_id)?;
+
+    activity_log_entry(
+        ctx,
+        ActivityLogType::StockOffHold,
+        Some(stock_line_id.to_string()),
+        Some("Released from hold: QA pending".to_string()),
+    )?;
+    Ok(())
+}
+
+fn quarantine_inspected_stock(
+    ctx: &ServiceContext,
+    inspection: &GoodsReceiptInspectionRow,
+    quarantine_location_id: Option<String>,
+) -> Result<(), OutError> {
+    let connection = &ctx.connection;
+    let stock_line_repository = StockLineRowRepository::new(connection);
+    let mut stock_line = stock_line_repository.find_one_by_id(&inspection.stock_line_id)?;
+    stock_line.on_hold = true;
+    if let Some(location_id) = quarantine_location_id {
+        // Keep the line in sync so later line edits don't move the stock back
+        let line_repository = InvoiceLineRowRepository::new(connection);
+        let mut line = line_repository.find_one_by_id(&inspection.invoice_line_id)?;
+        line.location_id = Some(location_id.clone());
+        line_repository.upsert_one(&line)?;
+
+        stock_line.location_id = Some(location_id);
+    }
+    stock_line_repository.upsert_one(&stock_line)?;
+    record_hold(
+        ctx,
+        HoldRecordType::StockLine,
+        &stock_line.id,
+        HOLD_REASON_QUARANTINE_ID,
+        None,
+    )?;
+
+    activity_log_entry(
+        ctx,
+        ActivityLogType::StockOnHold,
+        Some(stock_line.id),
+        Some("On hold: Quarantine".to_string()),
+    )?;
+    Ok(())
+}
+
+/// Puts stock received on an inbound shipment line on hold until it passes QA inspection, if the
+/// store requires goods receipt inspection. Stock lines are regenerated when the shipment is
+/// verified, stock of already inspected lines keeps its inspection result.
+pub(crate) fn hold_received_stock_for_inspection(
+    ctx: &ServiceContext,
+    invoice: &InvoiceRow,
+    line: &InvoiceLineRow,
+) -> Result<(), RepositoryError> {
+    let connection = &ctx.connection;
+    let stock_line_id = match &line.stock_line_id {
+        Some(stock_line_id) => stock_line_id,
+        None => return Ok(()),
+    };
+    if GoodsReceiptInspectionRowRepository::new(connection)
+        .find_one_by_invoice_line_id(&line.id)?
+        .is_some()
+    {
+        return keep_inspection_hold(connection, line);
+    }
+    if invoice.status == InvoiceRowStatus::New
+        || !get_store_preferences(connection, &invoice.store_id)?.requires_goods_receipt_inspection
+    {
+        return Ok(());
+    }
+
+    set_on_hold(connection, &HoldRecordType::StockLine, stock_line_id, true)?;
+    record_hold(
+        ctx,
+        HoldRecordType::StockLine,
+        stock_line_id,
+        HOLD_REASON_QA_PENDING_ID,
+        None,
+    )?;
+    GoodsReceiptInspectionRowRepository::new(connection).upsert_one(
+        &GoodsReceiptInspectionRow {
+            id: uuid(),
+            invoice_id: invoice.id.clone(),
+            invoice_line_id: line.id.clone(),
+            stock_line_id: stock_line_id.clone(),
+            status: GoodsReceiptInspectionStatus::Pending,
+            ..Default::default()
+        },
+    )?;
+
+    activity_log_entry(
+        ctx,
+        ActivityLogType::StockOnHold,
+        Some(stock_line_id.clone()),
+        Some("On hold: QA pending".to_string()),
+    )
+}
+
+/// Line edits regenerate the received stock line, keeps stock of pending or failed inspections
+/// on hold and follows the stock line when the edit creates a new one
+pub(crate) fn keep_inspection_hold(
+    connection: &StorageConnection,
+    line: &InvoiceLineRow,
+) -> Result<(), RepositoryError> {
+    let inspection_repository = GoodsReceiptInspectionRowRepository::new(connection);
+    let (inspection, stock_line_id) = match (
+        inspection_repository.find_one_by_invoice_line_id(&line.id)?,
+        &line.stock_line_id,
+    ) {
+        (Some(inspection), Some(stock_line_id)) => (inspection, stock_line_id),
+        _ => return Ok(()),
+    };
+    if inspection.status == GoodsReceiptInspectionStatus::Passed {
+        return Ok(());
+    }
+
+    set_on_hold(connection, &HoldRecordType::StockLine, stock_line_id, true)?;
+    if &inspection.stock_line_id != stock_line_id {
+        let hold_repository = HoldRowRepository::new(connection);
+        if let Some(hold) = hold_repository
+            .find_one_by_record(&HoldRecordType::StockLine, &inspection.stock_line_id)?
+        {
+            hold_repository
+                .delete_by_record(&HoldRecordType::StockLine, &inspection.stock_line_id)?;
+            hold_repository.upsert_one(&HoldRow {
+                record_id: stock_line_id.clone(),
+                ..hold
+            })?;
+        }
+        inspection_repository.upsert_one(&GoodsReceiptInspectionRow {
+            stock_line_id: stock_line_id.clone(),
+            ..inspection
+        })?;
+    }
+    Ok(())
+}
+
+/// Removes the inspection and hold details of a deleted line
+pub(crate) fn delete_inspection(
+    connection: &StorageConnection,
+    line: &InvoiceLineRow,
+) -> Result<(), RepositoryError> {
+    GoodsReceiptInspectionRowRepository::new(connection).delete_by_invoice_line_id(&line.id)?;
+    if let Some(stock_line_id) = &line.stock_line_id {
+        HoldRowRepository::new(connection)
+            .delete_by_record(&HoldRecordType::StockLine, stock_line_id)?;
+    }
+    Ok(())
+}
+
+pub(crate) fn has_pending_inspection(
+    connection: &StorageConnection,
+    invoice_id: &str,
+) -> Result<bool, RepositoryError> {
+    let inspections = GoodsReceiptInspectionRowRepository::new(connection)
+        .find_many_by_invoice_ids(&[invoice_id.to_string()])?;
+    Ok(inspections
+        .iter()
+        .any(|inspection| inspection.status == GoodsReceiptInspectionStatus::Pending))
+}
+
+impl From<RepositoryError> for InspectInboundShipmentError {
+    fn from(error: RepositoryError) -> Self {
+        InspectInboundShipmentError::DatabaseError(error)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use repository::{
+        mock::{mock_item_a, mock_name_a, mock_store_a, MockData, MockDataInserts},
+        test_db::setup_all_with_data,
+        GoodsReceiptInspectionRowRepository, GoodsReceiptInspectionStatus, HoldRecordType,
+        HoldRowRepository, InvoiceLineRow, InvoiceLineRowRepository, InvoiceLineRowType,
+        InvoiceRow, InvoiceRowStatus, InvoiceRowType, LocationRow, LocationType,
+        StockLineRowRepository, StorePreferenceRow, StorePreferenceRowRepository,
+        HOLD_REASON_QA_PENDING_ID, HOLD_REASON_QUARANTINE_ID,
+    };
+    use util::inline_init;
+
+    use crate::{
+        hold::release::{ReleaseHold, ReleaseHoldError},
+        invoice::inbound_shipment::{
+            InspectInboundShipment, InspectInboundShipmentError as ServiceError,
+            InspectInboundShipmentLine, UpdateInboundShipment, UpdateInboundShipmentError,
+            UpdateInboundShipmentStatus,
+        },
+        invoice_line::inbound_shipment_line::UpdateInboundShipmentLine,
+        service_provider::ServiceProvider,
+        stock_line::{UpdateStockLine, UpdateStockLineError},
+    };
+
+    fn inbound_shipment() -> InvoiceRow {
+        inline_init(|r: &mut InvoiceRow| {
+            r.id = "inspected_inbound_shipment".to_string();
+            r.name_id = mock_name_a().id;
+            r.store_id = mock_store_a().id;
+            r.r#type = InvoiceRowType::InboundShipment;
+            r.status = InvoiceRowStatus::New;
+        })
+    }
+
+    fn line(id: &str) -> InvoiceLineRow {
+        inline_init(|r: &mut InvoiceLineRow| {
+            r.id = id.to_string();
+            r.invoice_id = inbound_shipment().id;
+            r.item_id = mock_item_a().id;
+            r.r#type = InvoiceLineRowType::StockIn;
+            r.pack_size = 1;
+            r.number_of_packs = 10.0;
+        })
+    }
+
+    fn quarantine_location() -> LocationRow {
+        inline_init(|r: &mut LocationRow| {
+            r.id = "quarantine_location".to_string();
+            r.code = "Q".to_string();
+            r.store_id = mock_store_a().id;
+            r.location_type = LocationType::Quarantine;
+        })
+    }
+
+    #[actix_rt::test]
+    async fn inspect_inbound_shipment() {
+        let (_, connection, connection_manager, _) = setup_all_with_data(
+            "inspect_inbound_shipment",
+            MockDataInserts::none().stores().items().names().units(),
+            inline_init(|r: &mut MockData| {
+                r.invoices = vec![inbound_shipment()];
+                r.invoice_lines = vec![line("line_a"), line("line_b")];
+                r.locations = vec![quarantine_location()];
+            }),
+        )
+        .await;
+        StorePreferenceRowRepository::new(&connection)
+            .upsert_one(&StorePreferenceRow {
+                id: mock_store_a().id,
+                requires_goods_receipt_inspection: true,
+                ..Default::default()
+            })
+            .unwrap();
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let service = &service_provider.invoice_service;
+        let context = service_provider
+            .context(mock_store_a().id, "user".to_string())
+            .unwrap();
+
+        let update = |status: UpdateInboundShipmentStatus| UpdateInboundShipment {
+            id: inbound_shipment().id,
+            status: Some(status),
+            ..Default::default()
+        };
+        let stock_line = |line_id: &str| {
+            let stock_line_id = InvoiceLineRowRepository::new(&connection)
+                .find_one_by_id(line_id)
+                .unwrap()
+                .stock_line_id
+                .unwrap();
+            StockLineRowRepository::new(&connection)
+                .find_one_by_id(&stock_line_id)
+                .unwrap()
+        };
+        let hold_reason_id = |stock_line_id: &str| {
+            HoldRowRepository::new(&connection)
+                .find_one_by_record(&HoldRecordType::StockLine, stock_line_id)
+                .unwrap()
+                .map(|hold| hold.hold_reason_id)
+        };
+        let inspection_status = |line_id: &str| {
+            GoodsReceiptInspectionRowRepository::new(&connection)
+                .find_one_by_invoice_line_id(line_id)
+                .unwrap()
+                .map(|inspection| inspection.status)
+        };
+
+        // Received stock is held pending inspection
+        service
+            .update_inbound_shipment(&context, update(UpdateInboundShipmentStatus::Delivered))
+            .unwrap();
+        for line_id in ["line_a", "line_b"] {
+            let stock_line = stock_line(line_id);
+            assert!(stock_line.on_hold);
+            assert_eq!(
+                hold_reason_id(&stock_line.id),
+                Some(HOLD_REASON_QA_PENDING_ID.to_string())
+            );
+            assert_eq!(
+                inspection_status(line_id),
+                Some(GoodsReceiptInspectionStatus::Pending)
+            );
+        }
+
+        // Can't verify while inspection is pending
+        assert_eq!(
+            service
+                .update_inbound_shipment(&context, update(UpdateInboundShipmentStatus::Verified)),
+            Err(UpdateInboundShipmentError::GoodsReceiptInspectionPending)
+        );
+
+        // Stock pending inspection can only be released by the inspection
+        assert_eq!(
+            service_provider.stock_line_service.update_stock_line(
+                &context,
+                inline_init(|r: &mut UpdateStockLine| {
+                    r.id = stock_line("line_a").id;
+                    r.on_hold = Some(false);
+                }),
+            ),
+            Err(UpdateStockLineError::StockLineIsPendingInspection)
+        );
+        assert_eq!(
+            service_provider.hold_service.release_hold(
+                &context,
+                ReleaseHold {
+                    record_type: HoldRecordType::StockLine,
+                    record_id: stock_line("line_a").id,
+                },
+            ),
+            Err(ReleaseHoldError::StockLineIsPendingInspection)
+        );
+        assert!(stock_line("line_a").on_hold);
+
+        let input = InspectInboundShipment {
+            invoice_id: inbound_shipment().id,
+            lines: vec![
+                InspectInboundShipmentLine {
+                    invoice_line_id: "line_a".to_string(),
+                    passed: true,
+                    note: None,
+                },
+                InspectInboundShipmentLine {
+                    invoice_line_id: "line_b".to_string(),
+                    passed: false,
+                    note: Some("Broken seal".to_string()),
+                },
+            ],
+            quarantine_location_id: Some(quarantine_location().id),
+        };
+
+        // NoLinesProvided
+        assert_eq!(
+            service.inspect_inbound_shipment(
+                &context,
+                InspectInboundShipment {
+                    lines: Vec::new(),
+                    ..input.clone()
+                }
+            ),
+            Err(ServiceError::NoLinesProvided)
+        );
+        // LineDoesNotExist
+        assert_eq!(
+            service.inspect_inbound_shipment(
+                &context,
+                InspectInboundShipment {
+                    lines: vec![InspectInboundShipmentLine {
+                        invoice_line_id: "invalid".to_string(),
+                        ..Default::default()
+                    }],
+                    ..input.clone()
+                }
+            ),
+            Err(ServiceError::LineDoesNotExist("invalid".to_string()))
+        );
+        // LocationDoesNotExist
+        assert_eq!(
+            service.inspect_inbound_shipment(
+                &context,
+                InspectInboundShipment {
+                    quarantine_location_id: Some("invalid".to_string()),
+                    ..input.clone()
+                }
+            ),
+            Err(ServiceError::LocationDoesNotExist)
+        );
+
+        // Passed stock is released, failed stock is quarantined
+        let invoice = service
+            .inspect_inbound_shipment(&context, input.clone())
+            .unwrap();
+        assert_eq!(invoice.invoice_row.status, InvoiceRowStatus::Delivered);
+
+        let passed = stock_line("line_a");
+        assert!(!passed.on_hold);
+        assert_eq!(hold_reason_id(&passed.id), None);
+        assert_eq!(
+            inspection_status("line_a"),
+            Some(GoodsReceiptInspectionStatus::Passed)
+        );
+
+        let failed = stock_line("line_b");
+        assert!(failed.on_hold);
+        assert_eq!(failed.location_id, Some(quarantine_location().id));
+        assert_eq!(
+            hold_reason_id(&failed.id),
+            Some(HOLD_REASON_QUARANTINE_ID.to_string())
+        );
+        let inspection = GoodsReceiptInspectionRowRepository::new(&connection)
+            .find_one_by_invoice_line_id("line_b")
+            .unwrap()
+            .unwrap();
+        assert_eq!(inspection.status, GoodsReceiptInspectionStatus::Failed);
+        assert_eq!(inspection.note, Some("Broken seal".to_string()));
+        assert_eq!(inspection.user_id, Some("user".to_string()));
+
+        // LineIsNotPendingInspection
+        assert_eq!(
+            service.inspect_inbound_shipment(&context, input),
+            Err(ServiceError::LineIsNotPendingInspection(
+                "line_a".to_string()
+            ))
+        );
+
+        // Editing a failed line keeps its stock on hold
+        service_provider
+            .invoice_line_service
+            .update_inbound_shipment_line(
+                &context,
+                inline_init(|r: &mut UpdateInboundShipmentLine| {
+                    r.id = "line_b".to_string();
+                    r.number_of_packs = Some(5.0);
+                }),
+            )
+            .unwrap();
+        let failed = stock_line("line_b");
+        assert!(failed.on_hold);
+        assert_eq!(failed.location_id, Some(quarantine_location().id));
+
+        // Shipment can be verified once every line is inspected
+        let invoice = service
+            .update_inbound_shipment(&context, update(UpdateInboundShipmentStatus::Verified))
+            .unwrap();
+        assert_eq!(invoice.invoice_row.status, InvoiceRowStatus::Verified);
+        assert!(!stock_line("line_a").on_hold);
+        assert!(stock_line("line_b").on_hold);
+    }
+}
@@ -13,5 +13,8 @@ pub use self::delete::*;
 pub mod batch;
 pub use self::batch::*;
 
+pub mod inspect;
+pub use self::inspect::*;
+
 mod add_from_master_list;
 pub use self::add_from_master_list::*;
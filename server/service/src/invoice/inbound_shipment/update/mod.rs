@@ -1,10 +1,13 @@
 use crate::activity_log::{activity_log_entry, log_type_from_invoice_status};
+use crate::invoice::inbound_shipment::inspect::{
+    has_pending_inspection, hold_received_stock_for_inspection,
+};
 use crate::invoice_line::ShipmentTaxUpdate;
 use crate::{invoice::query::get_invoice, service_provider::ServiceContext, WithDBError};
 use repository::{Invoice, LocationMovementRowRepository};
 use repository::{
-    InvoiceLineRowRepository, InvoiceRowRepository, InvoiceRowStatus, RepositoryError,
-    StockLineRowRepository,
+    HoldRecordType, HoldRowRepository, InvoiceLineRowRepository, InvoiceRowRepository,
+    InvoiceRowStatus, RepositoryError, StockLineRowRepository,
 };
 
 mod generate;
@@ -61,6 +64,10 @@ pub fn update_inbound_shipment(
             )?;
 
             InvoiceRowRepository::new(connection).upsert_one(&update_invoice)?;
+            if !update_invoice.on_hold {
+                HoldRowRepository::new(connection)
+                    .delete_by_record(&HoldRecordType::Invoice, &update_invoice.id)?;
+            }
             let invoice_line_repository = InvoiceLineRowRepository::new(connection);
 
             if let Some(lines_and_invoice_lines) = batches_to_update {
@@ -69,9 +76,16 @@ pub fn update_inbound_shipment(
                 for LineAndStockLine { line, stock_line } in lines_and_invoice_lines.into_iter() {
                     stock_line_repository.upsert_one(&stock_line)?;
                     invoice_line_repository.upsert_one(&line)?;
+                    hold_received_stock_for_inspection(ctx, &update_invoice, &line)?;
                 }
             }
 
+            if update_invoice.status == InvoiceRowStatus::Verified
+                && has_pending_inspection(connection, &update_invoice.id)?
+            {
+                return Err(OutError::GoodsReceiptInspectionPending);
+            }
+
             if let Some(lines) = empty_lines_to_trim {
                 let repository = InvoiceLineRowRepository::new(connection);
                 for line in lines {
@@ -122,6 +136,8 @@ pub enum UpdateInboundShipmentError {
     CannotReverseInvoiceStatus,
     CannotEditFinalised,
     CannotChangeStatusOfInvoiceOnHold,
+    /// Received stock must pass or fail QA inspection before the shipment is verified
+    GoodsReceiptInspectionPending,
     // Name validation
     OtherPartyDoesNotExist,
     OtherPartyNotVisible,
@@ -80,6 +80,15 @@ pub trait InvoiceServiceTrait: Sync + Send {
         delete_inbound_shipment(ctx, input)
     }
 
+    /// Records the QA inspection result of received inbound shipment lines
+    fn inspect_inbound_shipment(
+        &self,
+        ctx: &ServiceContext,
+        input: InspectInboundShipment,
+    ) -> Result<Invoice, InspectInboundShipmentError> {
+        inspect_inbound_shipment(ctx, input)
+    }
+
     fn insert_outbound_shipment(
         &self,
         ctx: &ServiceContext,
@@ -234,10 +234,10 @@ pub fn batch_outbound_shipment(
                 return Err(WithDBError::err(results));
             }
 
-            let (has_errors, result) = mutations_processor.do_mutations(
-                input.allocate_line,
-                allocate_outbound_shipment_unallocated_line,
-            );
+            let (has_errors, result) =
+                mutations_processor.do_mutations(input.allocate_line, |ctx, line_id| {
+                    allocate_outbound_shipment_unallocated_line(ctx, line_id, Default::default())
+                });
             results.allocate_line = result;
             if has_errors && !continue_on_error {
                 return Err(WithDBError::err(results));
@@ -1,6 +1,7 @@
 use repository::{
-    Invoice, InvoiceLine, InvoiceLineRowRepository, InvoiceRowRepository, InvoiceRowStatus,
-    LocationMovementRowRepository, RepositoryError, StockLineRowRepository, TransactionError,
+    HoldRecordType, HoldRowRepository, Invoice, InvoiceLine, InvoiceLineRowRepository,
+    InvoiceRowRepository, InvoiceRowStatus, LocationMovementRowRepository, RepositoryError,
+    StockLineRowRepository, TransactionError,
 };
 
 pub mod generate;
@@ -69,6 +70,10 @@ pub fn update_outbound_shipment(
             } = generate(&ctx.store_id, invoice, patch.clone(), connection)?;
 
             InvoiceRowRepository::new(connection).upsert_one(&update_invoice)?;
+            if !update_invoice.on_hold {
+                HoldRowRepository::new(connection)
+                    .delete_by_record(&HoldRecordType::Invoice, &update_invoice.id)?;
+            }
             let invoice_line_repo = InvoiceLineRowRepository::new(connection);
 
             if let Some(stock_lines) = batches_to_update {
@@ -1,5 +1,9 @@
 use crate::{
-    invoice::common::generate_invoice_user_id_update, service_provider::ServiceContext, WithDBError,
+    invoice::{
+        common::generate_invoice_user_id_update, inbound_shipment::inspect::delete_inspection,
+    },
+    service_provider::ServiceContext,
+    WithDBError,
 };
 use repository::{
     InvoiceLineRowRepository, InvoiceRowRepository, RepositoryError, StockLineRowRepository,
@@ -28,6 +32,7 @@ pub fn delete_inbound_shipment_line(
             let delete_batch_id_option = line.stock_line_id.clone();
 
             InvoiceLineRowRepository::new(&connection).delete(&line.id)?;
+            delete_inspection(connection, &line)?;
 
             if let Some(id) = delete_batch_id_option {
                 StockLineRowRepository::new(&connection).delete(&id)?;
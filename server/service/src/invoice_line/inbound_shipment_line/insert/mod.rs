@@ -1,4 +1,7 @@
-use crate::{invoice_line::query::get_invoice_line, service_provider::ServiceContext, WithDBError};
+use crate::{
+    invoice::inbound_shipment::inspect::hold_received_stock_for_inspection,
+    invoice_line::query::get_invoice_line, service_provider::ServiceContext, WithDBError,
+};
 use chrono::NaiveDate;
 use repository::{
     InvoiceLine, InvoiceLineRowRepository, InvoiceRowRepository, RepositoryError,
@@ -38,12 +41,13 @@ pub fn insert_inbound_shipment_line(
         .transaction_sync(|connection| {
             let (item, invoice) = validate(&input, &ctx.store_id, &connection)?;
             let (invoice_row_option, new_line, new_batch_option) =
-                generate(&connection, &ctx.user_id, input, item, invoice)?;
+                generate(&connection, &ctx.user_id, input, item, invoice.clone())?;
 
             if let Some(new_batch) = new_batch_option {
                 StockLineRowRepository::new(&connection).upsert_one(&new_batch)?;
             }
             InvoiceLineRowRepository::new(&connection).upsert_one(&new_line)?;
+            hold_received_stock_for_inspection(ctx, &invoice, &new_line)?;
 
             if let Some(invoice_row) = invoice_row_option {
                 InvoiceRowRepository::new(&connection).upsert_one(&invoice_row)?;
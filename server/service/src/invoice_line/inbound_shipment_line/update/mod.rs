@@ -1,4 +1,5 @@
 use crate::{
+    invoice::inbound_shipment::inspect::keep_inspection_hold,
     invoice_line::{query::get_invoice_line, ShipmentTaxUpdate},
     service_provider::ServiceContext,
     WithDBError,
@@ -51,6 +52,7 @@ pub fn update_inbound_shipment_line(
             }
 
             InvoiceLineRowRepository::new(&connection).upsert_one(&updated_line)?;
+            keep_inspection_hold(connection, &updated_line)?;
 
             if let Some(id) = delete_batch_id_option {
                 stock_line_respository.delete(&id)?;
@@ -167,8 +167,9 @@ pub trait InvoiceLineServiceTrait: Sync + Send {
         &self,
         ctx: &ServiceContext,
         line_id: String,
+        location_types: AllocationLocationTypes,
     ) -> Result<AllocateLineResult, AllocateOutboundShipmentUnallocatedLineError> {
-        allocate_outbound_shipment_unallocated_line(ctx, line_id)
+        allocate_outbound_shipment_unallocated_line(ctx, line_id, location_types)
     }
 }
 
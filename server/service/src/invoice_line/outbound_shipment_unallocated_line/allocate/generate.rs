@@ -10,6 +10,7 @@ use util::{
     fraction_is_integer, uuid,
 };
 
+use super::AllocationLocationTypes;
 use crate::invoice_line::{
     outbound_shipment_line::{InsertOutboundShipmentLine, UpdateOutboundShipmentLine},
     outbound_shipment_unallocated_line::{
@@ -32,6 +33,7 @@ pub fn generate(
     connection: &StorageConnection,
     store_id: &str,
     unallocated_line: InvoiceLineRow,
+    location_types: &AllocationLocationTypes,
 ) -> Result<GenerateOutput, RepositoryError> {
     let mut result = GenerateOutput::default();
     let allocated_lines = get_allocated_lines(connection, &unallocated_line)?;
@@ -45,8 +47,10 @@ pub fn generate(
         return Ok(result);
     }
     // Asc, by expiry date, nulls last
-    let sorted_available_stock_lines =
-        get_sorted_available_stock_lines(connection, store_id, &unallocated_line)?;
+    let sorted_available_stock_lines = apply_location_types(
+        get_sorted_available_stock_lines(connection, store_id, &unallocated_line)?,
+        location_types,
+    );
     // Use FEFO to allocate
     for stock_line in sorted_available_stock_lines {
         let can_use = get_stock_line_eligibility(&stock_line)
@@ -217,6 +221,35 @@ fn get_sorted_available_stock_lines(
     StockLineRepository::new(connection).query(Pagination::new(), Some(filter), Some(sort), None)
 }
 
+/// Removes stock in excluded location types and moves stock in preferred location types first,
+/// keeping the expiry date order within each group
+fn apply_location_types(
+    stock_lines: Vec<StockLine>,
+    location_types: &AllocationLocationTypes,
+) -> Vec<StockLine> {
+    let location_type = |stock_line: &StockLine| {
+        stock_line
+            .location_row
+            .as_ref()
+            .map(|location_row| location_row.location_type.clone())
+    };
+
+    let mut stock_lines: Vec<StockLine> = stock_lines
+        .into_iter()
+        .filter(|stock_line| match location_type(stock_line) {
+            Some(location_type) => !location_types.exclude.contains(&location_type),
+            None => true,
+        })
+        .collect();
+    // sort_by_key is stable
+    stock_lines.sort_by_key(|stock_line| match location_type(stock_line) {
+        Some(location_type) => !location_types.prefer.contains(&location_type),
+        None => true,
+    });
+
+    stock_lines
+}
+
 fn get_allocated_lines(
     connection: &StorageConnection,
     unallocated_line: &InvoiceLineRow,
@@ -10,7 +10,8 @@ use crate::{
     service_provider::ServiceContext,
 };
 use repository::{
-    InvoiceLine, InvoiceLineRow, InvoiceLineRowType, RepositoryError, StockLine, StorageConnection,
+    InvoiceLine, InvoiceLineRow, InvoiceLineRowType, LocationType, RepositoryError, StockLine,
+    StorageConnection,
 };
 
 use super::{
@@ -74,9 +75,19 @@ pub struct AllocateLineResult {
 
 type ServiceResult = AllocateLineResult;
 
+/// Location types to prefer or exclude when allocating stock
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct AllocationLocationTypes {
+    /// Stock in these location types is allocated first (in expiry date order)
+    pub prefer: Vec<LocationType>,
+    /// Stock in these location types is not allocated
+    pub exclude: Vec<LocationType>,
+}
+
 pub fn allocate_outbound_shipment_unallocated_line(
     ctx: &ServiceContext,
     line_id: String,
+    location_types: AllocationLocationTypes,
 ) -> Result<ServiceResult, OutError> {
     let line =
         ctx.connection
@@ -90,7 +101,12 @@ pub fn allocate_outbound_shipment_unallocated_line(
                     skipped_expired_stock_lines,
                     skipped_on_hold_stock_lines,
                     issued_expiring_soon_stock_lines,
-                } = generate(&connection, &ctx.store_id, unallocated_line)?;
+                } = generate(
+                    &connection,
+                    &ctx.store_id,
+                    unallocated_line,
+                    &location_types,
+                )?;
 
                 let mut result = ServiceResult {
                     inserts: vec![],
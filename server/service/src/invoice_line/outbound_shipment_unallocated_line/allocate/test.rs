@@ -9,7 +9,7 @@ mod test {
         },
         test_db::{setup_all, setup_all_with_data},
         InvoiceLineRow, InvoiceLineRowRepository, InvoiceLineRowType, InvoiceRow, InvoiceRowType,
-        RepositoryError, StockLine, StockLineRow,
+        LocationRow, LocationType, RepositoryError, StockLine, StockLineRow,
     };
     use util::{
         constants::stock_line_expiring_soon_offset, date_now, date_now_with_offset, inline_edit,
@@ -17,7 +17,9 @@ mod test {
     };
 
     use crate::{
-        invoice_line::AllocateOutboundShipmentUnallocatedLineError as ServiceError,
+        invoice_line::{
+            AllocateOutboundShipmentUnallocatedLineError as ServiceError, AllocationLocationTypes,
+        },
         service_provider::ServiceProvider,
     };
 
@@ -34,7 +36,11 @@ mod test {
 
         // Line Does not Exist
         assert_eq!(
-            service.allocate_outbound_shipment_unallocated_line(&context, "invalid".to_string(),),
+            service.allocate_outbound_shipment_unallocated_line(
+                &context,
+                "invalid".to_string(),
+                Default::default()
+            ),
             Err(ServiceError::LineDoesNotExist)
         );
 
@@ -42,7 +48,8 @@ mod test {
         assert_eq!(
             service.allocate_outbound_shipment_unallocated_line(
                 &context,
-                mock_outbound_shipment_a_invoice_lines()[0].id.clone()
+                mock_outbound_shipment_a_invoice_lines()[0].id.clone(),
+                Default::default()
             ),
             Err(ServiceError::LineIsNotUnallocatedLine)
         );
@@ -120,7 +127,11 @@ mod test {
         let service = service_provider.invoice_line_service;
 
         let result = service
-            .allocate_outbound_shipment_unallocated_line(&context, line().id.clone())
+            .allocate_outbound_shipment_unallocated_line(
+                &context,
+                line().id.clone(),
+                Default::default(),
+            )
             .unwrap();
 
         assert_eq!(result.inserts.len(), 1);
@@ -222,7 +233,11 @@ mod test {
         let service = service_provider.invoice_line_service;
 
         let result = service
-            .allocate_outbound_shipment_unallocated_line(&context, line().id.clone())
+            .allocate_outbound_shipment_unallocated_line(
+                &context,
+                line().id.clone(),
+                Default::default(),
+            )
             .unwrap();
 
         assert_eq!(result.inserts.len(), 3);
@@ -380,7 +395,11 @@ mod test {
         let service = service_provider.invoice_line_service;
 
         let result = service
-            .allocate_outbound_shipment_unallocated_line(&context, line().id.clone())
+            .allocate_outbound_shipment_unallocated_line(
+                &context,
+                line().id.clone(),
+                Default::default(),
+            )
             .unwrap();
 
         assert_eq!(result.inserts.len(), 3);
@@ -526,7 +545,11 @@ mod test {
         let service = service_provider.invoice_line_service;
 
         let result = service
-            .allocate_outbound_shipment_unallocated_line(&context, line().id.clone())
+            .allocate_outbound_shipment_unallocated_line(
+                &context,
+                line().id.clone(),
+                Default::default(),
+            )
             .unwrap();
 
         assert_eq!(result.inserts.len(), 1);
@@ -613,7 +636,11 @@ mod test {
         let service = service_provider.invoice_line_service;
 
         let result = service
-            .allocate_outbound_shipment_unallocated_line(&context, line().id.clone())
+            .allocate_outbound_shipment_unallocated_line(
+                &context,
+                line().id.clone(),
+                Default::default(),
+            )
             .unwrap();
 
         assert_eq!(result.inserts.len(), 1);
@@ -640,4 +667,97 @@ mod test {
             })
         );
     }
+
+    #[actix_rt::test]
+    async fn allocate_unallocated_line_location_types() {
+        fn invoice() -> InvoiceRow {
+            inline_init(|r: &mut InvoiceRow| {
+                r.id = "invoice".to_string();
+                r.store_id = mock_store_a().id;
+                r.name_id = mock_name_a().id;
+                r.r#type = InvoiceRowType::OutboundShipment;
+            })
+        }
+
+        fn line() -> InvoiceLineRow {
+            inline_init(|r: &mut InvoiceLineRow| {
+                r.id = "line".to_string();
+                r.invoice_id = invoice().id;
+                r.item_id = mock_item_a().id;
+                r.r#type = InvoiceLineRowType::UnallocatedStock;
+                r.number_of_packs = 15.0;
+                r.pack_size = 1;
+            })
+        }
+
+        fn location(id: &str, location_type: LocationType) -> LocationRow {
+            inline_init(|r: &mut LocationRow| {
+                r.id = id.to_string();
+                r.code = id.to_string();
+                r.store_id = mock_store_a().id;
+                r.location_type = location_type;
+            })
+        }
+
+        fn stock_line(id: &str, location_id: &str, expiry_month: u32) -> StockLineRow {
+            inline_init(|r: &mut StockLineRow| {
+                r.id = id.to_string();
+                r.store_id = mock_store_a().id;
+                r.item_id = mock_item_a().id;
+                r.location_id = Some(location_id.to_string());
+                r.pack_size = 1;
+                r.available_number_of_packs = 10.0;
+                r.expiry_date = Some(NaiveDate::from_ymd_opt(3021, expiry_month, 01).unwrap());
+            })
+        }
+
+        let (_, _, connection_manager, _) = setup_all_with_data(
+            "allocate_unallocated_line_location_types",
+            MockDataInserts::none().stores().items().names().units(),
+            inline_init(|r: &mut MockData| {
+                r.invoices = vec![invoice()];
+                r.invoice_lines = vec![line()];
+                r.locations = vec![
+                    location("quarantine", LocationType::Quarantine),
+                    location("cold", LocationType::Cold),
+                    location("ambient", LocationType::Ambient),
+                ];
+                r.stock_lines = vec![
+                    stock_line("in_quarantine", "quarantine", 1),
+                    stock_line("in_cold", "cold", 2),
+                    stock_line("in_ambient", "ambient", 3),
+                ];
+            }),
+        )
+        .await;
+
+        let service_provider = ServiceProvider::new(connection_manager.clone(), "app_data");
+        let context = service_provider
+            .context(mock_store_a().id, "".to_string())
+            .unwrap();
+        let service = service_provider.invoice_line_service;
+
+        let result = service
+            .allocate_outbound_shipment_unallocated_line(
+                &context,
+                line().id.clone(),
+                AllocationLocationTypes {
+                    prefer: vec![LocationType::Ambient],
+                    exclude: vec![LocationType::Quarantine],
+                },
+            )
+            .unwrap();
+
+        // Preferred location first, then by expiry date skipping the excluded location
+        let stock_line_ids: Vec<Option<String>> = result
+            .inserts
+            .iter()
+            .map(|line| line.invoice_line_row.stock_line_id.clone())
+            .collect();
+        assert_eq!(
+            stock_line_ids,
+            vec![Some("in_ambient".to_string()), Some("in_cold".to_string())]
+        );
+        assert_eq!(result.inserts[1].invoice_line_row.number_of_packs, 5.0);
+    }
 }
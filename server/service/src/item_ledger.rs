@@ -0,0 +1,121 @@
+use repository::{
+    EqualFilter, ItemLedgerFilter, ItemLedgerRepository, ItemLedgerRow, PaginationOption,
+};
+
+use crate::{
+    get_default_pagination, i64_to_u32, service_provider::ServiceContext, ListError, ListResult,
+};
+
+pub const MAX_LIMIT: u32 = 5000;
+pub const MIN_LIMIT: u32 = 1;
+
+#[derive(Debug, PartialEq)]
+pub struct ItemLedgerLine {
+    pub ledger: ItemLedgerRow,
+    /// Balance in units after this movement
+    pub running_balance: f64,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ItemLedger {
+    /// Balance in units before the first line of the page
+    pub opening_balance: f64,
+    pub lines: ListResult<ItemLedgerLine>,
+}
+
+pub trait ItemLedgerServiceTrait: Sync + Send {
+    /// Stock card of an item or stock line in the current store, ordered by datetime.
+    /// The filter should contain an item or stock line filter, otherwise the running balance is
+    /// the balance of all matched items.
+    fn get_item_ledger(
+        &self,
+        ctx: &ServiceContext,
+        pagination: Option<PaginationOption>,
+        filter: Option<ItemLedgerFilter>,
+    ) -> Result<ItemLedger, ListError> {
+        get_item_ledger(ctx, pagination, filter)
+    }
+}
+
+pub struct ItemLedgerService {}
+impl ItemLedgerServiceTrait for ItemLedgerService {}
+
+pub fn get_item_ledger(
+    ctx: &ServiceContext,
+    pagination: Option<PaginationOption>,
+    filter: Option<ItemLedgerFilter>,
+) -> Result<ItemLedger, ListError> {
+    let pagination = get_default_pagination(pagination, MAX_LIMIT, MIN_LIMIT)?;
+    let repository = ItemLedgerRepository::new(&ctx.connection);
+
+    // always filter by the current store
+    let filter = filter
+        .unwrap_or_default()
+        .store_id(EqualFilter::equal_to(&ctx.store_id));
+
+    let opening_balance = repository.opening_balance(filter.clone(), pagination.offset)?;
+    let count = i64_to_u32(repository.count(Some(filter.clone()))?);
+    let mut balance = opening_balance;
+    let rows = repository
+        .query(pagination, Some(filter))?
+        .into_iter()
+        .map(|ledger| {
+            balance += ledger.quantity;
+            ItemLedgerLine {
+                ledger,
+                running_balance: balance,
+            }
+        })
+        .collect();
+
+    Ok(ItemLedger {
+        opening_balance,
+        lines: ListResult { rows, count },
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use repository::{
+        mock::{mock_item_a, mock_store_a, MockDataInserts},
+        test_db::setup_all,
+        EqualFilter, ItemLedgerFilter, PaginationOption,
+    };
+
+    use crate::service_provider::ServiceProvider;
+
+    #[actix_rt::test]
+    async fn item_ledger_running_balance() {
+        let (_, _, connection_manager, _) =
+            setup_all("item_ledger_running_balance", MockDataInserts::all()).await;
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider
+            .context(mock_store_a().id, "".to_string())
+            .unwrap();
+        let service = service_provider.item_ledger_service;
+        let filter = ItemLedgerFilter::new().item_id(EqualFilter::equal_to(&mock_item_a().id));
+
+        let mut all = service
+            .get_item_ledger(&context, None, Some(filter.clone()))
+            .unwrap();
+        assert!(all.lines.count > 2);
+        assert_eq!(all.opening_balance, 0.0);
+        let total: f64 = all.lines.rows.iter().map(|line| line.ledger.quantity).sum();
+        assert_eq!(all.lines.rows.last().unwrap().running_balance, total);
+
+        // the opening balance of a page continues from the previous page
+        let page = service
+            .get_item_ledger(
+                &context,
+                Some(PaginationOption {
+                    limit: Some(1),
+                    offset: Some(2),
+                }),
+                Some(filter),
+            )
+            .unwrap();
+        assert_eq!(page.lines.rows.len(), 1);
+        assert_eq!(page.opening_balance, all.lines.rows[1].running_balance);
+        assert_eq!(page.lines.rows[0], all.lines.rows.remove(2));
+    }
+}
@@ -11,12 +11,19 @@ pub mod app_data;
 pub mod auth;
 pub mod auth_data;
 pub mod barcode;
+pub mod cold_chain;
+pub mod cycle_count;
 pub mod dashboard;
+pub mod historical_stock;
+pub mod hold;
 pub mod display_settings_service;
+pub mod expired_stock;
+pub mod inventory_adjustment;
 pub mod inventory_adjustment_reason;
 pub mod invoice;
 pub mod invoice_line;
 pub mod item;
+pub mod item_ledger;
 pub mod item_stats;
 pub mod location;
 pub mod login;
@@ -42,6 +49,7 @@ pub mod sync;
 pub mod system_user;
 pub mod token;
 pub mod token_bucket;
+pub mod traceability;
 pub mod user_account;
 pub mod validate;
 
@@ -228,6 +236,13 @@ pub fn u32_to_i32(num: u32) -> i32 {
     num.try_into().unwrap_or(0)
 }
 
+/// Update of a nullable field, the field is left as is when the update is not provided and is
+/// cleared when the update has no value
+#[derive(Debug, PartialEq, Clone)]
+pub struct NullableUpdate<T> {
+    pub value: Option<T>,
+}
+
 #[derive(Debug, PartialEq)]
 pub struct InputWithResult<I, R> {
     pub input: I,
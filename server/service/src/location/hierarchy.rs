@@ -0,0 +1,92 @@
+use repository::{
+    EqualFilter, LocationFilter, LocationRepository, RepositoryError, StockLineFilter,
+    StockLineRepository, StorageConnection,
+};
+
+use crate::{service_provider::ServiceContext, SingleRecordError};
+
+use super::query::get_location;
+
+/// Stock of a location and all locations nested under it
+#[derive(Debug, PartialEq, Default)]
+pub struct LocationStock {
+    pub location_id: String,
+    /// Number of locations in the subtree, including the location itself
+    pub location_count: u32,
+    pub stock_line_count: u32,
+    pub total_number_of_packs: f64,
+    pub available_number_of_packs: f64,
+    /// Number of packs multiplied by pack size
+    pub total_number_of_units: f64,
+    /// Sum of the pack capacities in the subtree, `None` if no location has a pack capacity
+    pub pack_capacity: Option<f64>,
+}
+
+/// Returns the given location ids with the ids of all locations nested under them (at any depth)
+pub fn get_location_subtree_ids(
+    connection: &StorageConnection,
+    location_ids: &[String],
+) -> Result<Vec<String>, RepositoryError> {
+    let repo = LocationRepository::new(connection);
+    let mut result = location_ids.to_vec();
+    let mut parent_ids = location_ids.to_vec();
+
+    while !parent_ids.is_empty() {
+        let children = repo
+            .query_by_filter(LocationFilter::new().parent_id(EqualFilter::equal_any(parent_ids)))?;
+
+        parent_ids = children
+            .into_iter()
+            .map(|location| location.location_row.id)
+            // Guard against cycles in synced data
+            .filter(|id| !result.contains(id))
+            .collect();
+        result.extend(parent_ids.iter().cloned());
+    }
+
+    Ok(result)
+}
+
+pub fn get_location_stock(
+    ctx: &ServiceContext,
+    location_id: String,
+) -> Result<LocationStock, SingleRecordError> {
+    let connection = &ctx.connection;
+    let location = get_location(ctx, location_id)?;
+    let location_ids =
+        get_location_subtree_ids(connection, std::slice::from_ref(&location.location_row.id))?;
+
+    let locations = LocationRepository::new(connection)
+        .query_by_filter(LocationFilter::new().id(EqualFilter::equal_any(location_ids.clone())))?;
+    let pack_capacity = locations
+        .iter()
+        .filter_map(|location| location.location_row.pack_capacity)
+        .fold(None, |sum: Option<f64>, capacity| {
+            Some(sum.unwrap_or(0.0) + capacity)
+        });
+
+    let store_id = &location.location_row.store_id;
+    let stock_lines = StockLineRepository::new(connection).query_by_filter(
+        StockLineFilter::new()
+            .location_id(EqualFilter::equal_any(location_ids))
+            .store_id(EqualFilter::equal_to(store_id))
+            .has_packs_in_store(true),
+        Some(store_id.clone()),
+    )?;
+
+    let mut result = LocationStock {
+        location_id: location.location_row.id,
+        location_count: locations.len() as u32,
+        stock_line_count: stock_lines.len() as u32,
+        pack_capacity,
+        ..Default::default()
+    };
+    for stock_line in stock_lines {
+        let row = stock_line.stock_line_row;
+        result.total_number_of_packs += row.total_number_of_packs;
+        result.available_number_of_packs += row.available_number_of_packs;
+        result.total_number_of_units += row.total_number_of_packs * row.pack_size as f64;
+    }
+
+    Ok(result)
+}
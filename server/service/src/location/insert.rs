@@ -1,8 +1,11 @@
-use super::{query::get_location, validate::check_location_code_is_unique};
+use super::{
+    query::get_location,
+    validate::{check_capacity_is_valid, check_location_code_is_unique, check_parent_location},
+};
 use crate::{service_provider::ServiceContext, SingleRecordError};
 use repository::EqualFilter;
 use repository::{
-    Location, LocationFilter, LocationRepository, LocationRow, LocationRowRepository,
+    Location, LocationFilter, LocationRepository, LocationRow, LocationRowRepository, LocationType,
     RepositoryError, StorageConnection,
 };
 
@@ -10,15 +13,22 @@ use repository::{
 pub enum InsertLocationError {
     LocationAlreadyExists,
     LocationWithCodeAlreadyExists,
+    ParentLocationDoesNotExist,
+    InvalidCapacity,
     CreatedRecordNotFound,
     DatabaseError(RepositoryError),
 }
 
+#[derive(Default)]
 pub struct InsertLocation {
     pub id: String,
     pub code: String,
     pub name: Option<String>,
     pub on_hold: Option<bool>,
+    pub parent_id: Option<String>,
+    pub location_type: Option<LocationType>,
+    pub volume_capacity: Option<f64>,
+    pub pack_capacity: Option<f64>,
 }
 
 pub fn insert_location(
@@ -28,7 +38,7 @@ pub fn insert_location(
     let location = ctx
         .connection
         .transaction_sync(|connection| {
-            validate(&input, connection)?;
+            validate(&input, &ctx.store_id, connection)?;
             let new_location = generate(&ctx.store_id, input);
             LocationRowRepository::new(&connection).upsert_one(&new_location)?;
 
@@ -40,6 +50,7 @@ pub fn insert_location(
 
 pub fn validate(
     input: &InsertLocation,
+    store_id: &str,
     connection: &StorageConnection,
 ) -> Result<(), InsertLocationError> {
     if !check_location_does_not_exist(&input.id, connection)? {
@@ -48,6 +59,16 @@ pub fn validate(
     if !check_location_code_is_unique(&input.id, Some(input.code.clone()), connection)? {
         return Err(InsertLocationError::LocationWithCodeAlreadyExists);
     }
+    if let Some(parent_id) = &input.parent_id {
+        if !check_parent_location(&input.id, parent_id, store_id, connection)? {
+            return Err(InsertLocationError::ParentLocationDoesNotExist);
+        }
+    }
+    if !check_capacity_is_valid(input.volume_capacity)
+        || !check_capacity_is_valid(input.pack_capacity)
+    {
+        return Err(InsertLocationError::InvalidCapacity);
+    }
 
     Ok(())
 }
@@ -59,6 +80,10 @@ pub fn generate(
         code,
         name,
         on_hold,
+        parent_id,
+        location_type,
+        volume_capacity,
+        pack_capacity,
     }: InsertLocation,
 ) -> LocationRow {
     LocationRow {
@@ -67,6 +92,10 @@ pub fn generate(
         code,
         on_hold: on_hold.unwrap_or(false),
         store_id: store_id.to_string(),
+        parent_id,
+        location_type: location_type.unwrap_or_default(),
+        volume_capacity,
+        pack_capacity,
     }
 }
 
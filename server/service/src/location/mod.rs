@@ -1,16 +1,21 @@
 use self::{
     delete::{delete_location, DeleteLocation, DeleteLocationError},
+    hierarchy::{get_location_stock, LocationStock},
     insert::{insert_location, InsertLocation, InsertLocationError},
-    query::{get_location, get_locations},
+    query::{get_location, get_location_movements, get_locations},
     update::{update_location, UpdateLocation, UpdateLocationError},
 };
 
 use super::{ListError, ListResult};
 use crate::{service_provider::ServiceContext, SingleRecordError};
 use repository::PaginationOption;
-use repository::{Location, LocationFilter, LocationSort};
+use repository::{
+    Location, LocationFilter, LocationMovement, LocationMovementFilter, LocationMovementSort,
+    LocationSort,
+};
 
 pub mod delete;
+pub mod hierarchy;
 pub mod insert;
 pub mod query;
 pub mod update;
@@ -35,6 +40,25 @@ pub trait LocationServiceTrait: Sync + Send {
         get_location(ctx, id)
     }
 
+    /// Aggregates the stock of a location and all locations nested under it
+    fn get_location_stock(
+        &self,
+        ctx: &ServiceContext,
+        location_id: String,
+    ) -> Result<LocationStock, SingleRecordError> {
+        get_location_stock(ctx, location_id)
+    }
+
+    fn get_location_movements(
+        &self,
+        ctx: &ServiceContext,
+        pagination: Option<PaginationOption>,
+        filter: Option<LocationMovementFilter>,
+        sort: Option<LocationMovementSort>,
+    ) -> Result<ListResult<LocationMovement>, ListError> {
+        get_location_movements(ctx, pagination, filter, sort)
+    }
+
     fn delete_location(
         &self,
         ctx: &ServiceContext,
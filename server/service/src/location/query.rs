@@ -1,5 +1,8 @@
 use repository::{EqualFilter, PaginationOption};
-use repository::{Location, LocationFilter, LocationRepository, LocationSort};
+use repository::{
+    Location, LocationFilter, LocationMovement, LocationMovementFilter, LocationMovementRepository,
+    LocationMovementSort, LocationRepository, LocationSort,
+};
 
 use crate::{
     get_default_pagination, i64_to_u32, service_provider::ServiceContext, ListError, ListResult,
@@ -24,6 +27,21 @@ pub fn get_locations(
     })
 }
 
+pub fn get_location_movements(
+    ctx: &ServiceContext,
+    pagination: Option<PaginationOption>,
+    filter: Option<LocationMovementFilter>,
+    sort: Option<LocationMovementSort>,
+) -> Result<ListResult<LocationMovement>, ListError> {
+    let pagination = get_default_pagination(pagination, MAX_LIMIT, MIN_LIMIT)?;
+    let repository = LocationMovementRepository::new(&ctx.connection);
+
+    Ok(ListResult {
+        rows: repository.query(pagination, filter.clone(), sort)?,
+        count: i64_to_u32(repository.count(filter)?),
+    })
+}
+
 pub fn get_location(ctx: &ServiceContext, id: String) -> Result<Location, SingleRecordError> {
     let repository = LocationRepository::new(&ctx.connection);
 
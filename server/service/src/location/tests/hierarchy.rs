@@ -0,0 +1,197 @@
+#[cfg(test)]
+mod query {
+    use repository::{
+        mock::{mock_item_a_stock_line, mock_store_a, MockData, MockDataInserts},
+        test_db::setup_all_with_data,
+        LocationRow, LocationType,
+    };
+    use util::{inline_edit, inline_init};
+
+    use crate::{
+        location::{
+            hierarchy::LocationStock,
+            insert::{InsertLocation, InsertLocationError},
+            update::{UpdateLocation, UpdateLocationError},
+        },
+        service_provider::ServiceProvider,
+    };
+
+    fn location(id: &str, parent_id: Option<&str>, pack_capacity: Option<f64>) -> LocationRow {
+        inline_init(|r: &mut LocationRow| {
+            r.id = id.to_string();
+            r.code = id.to_string();
+            r.store_id = mock_store_a().id;
+            r.parent_id = parent_id.map(str::to_string);
+            r.pack_capacity = pack_capacity;
+        })
+    }
+
+    #[actix_rt::test]
+    async fn location_service_hierarchy() {
+        let (_, _, connection_manager, _) = setup_all_with_data(
+            "location_service_hierarchy",
+            MockDataInserts::none().stores().items().names().units(),
+            inline_init(|r: &mut MockData| {
+                r.locations = vec![
+                    location("zone", None, Some(100.0)),
+                    location("aisle", Some("zone"), Some(50.0)),
+                    location("shelf", Some("aisle"), None),
+                    location("cold_room", None, None),
+                ];
+                r.stock_lines = vec![
+                    inline_edit(&mock_item_a_stock_line("in_shelf", 2, 10.0), |mut u| {
+                        u.location_id = Some("shelf".to_string());
+                        u
+                    }),
+                    inline_edit(&mock_item_a_stock_line("in_zone", 1, 5.0), |mut u| {
+                        u.location_id = Some("zone".to_string());
+                        u
+                    }),
+                    inline_edit(&mock_item_a_stock_line("in_cold_room", 1, 7.0), |mut u| {
+                        u.location_id = Some("cold_room".to_string());
+                        u
+                    }),
+                ];
+            }),
+        )
+        .await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider
+            .context(mock_store_a().id, "".to_string())
+            .unwrap();
+        let service = service_provider.location_service;
+
+        assert_eq!(
+            service.get_location_stock(&context, "zone".to_string()),
+            Ok(LocationStock {
+                location_id: "zone".to_string(),
+                location_count: 3,
+                stock_line_count: 2,
+                total_number_of_packs: 15.0,
+                available_number_of_packs: 15.0,
+                total_number_of_units: 25.0,
+                pack_capacity: Some(150.0),
+            })
+        );
+
+        // Parent can't be the location itself or one of its child locations
+        assert_eq!(
+            service.update_location(
+                &context,
+                inline_init(|r: &mut UpdateLocation| {
+                    r.id = "zone".to_string();
+                    r.parent_id = Some(Some("shelf".to_string()));
+                }),
+            ),
+            Err(UpdateLocationError::InvalidParentLocation)
+        );
+        assert_eq!(
+            service.update_location(
+                &context,
+                inline_init(|r: &mut UpdateLocation| {
+                    r.id = "zone".to_string();
+                    r.parent_id = Some(Some("zone".to_string()));
+                }),
+            ),
+            Err(UpdateLocationError::InvalidParentLocation)
+        );
+
+        assert_eq!(
+            service.insert_location(
+                &context,
+                inline_init(|r: &mut InsertLocation| {
+                    r.id = "bin".to_string();
+                    r.code = "bin".to_string();
+                    r.parent_id = Some("invalid".to_string());
+                }),
+            ),
+            Err(InsertLocationError::ParentLocationDoesNotExist)
+        );
+        assert_eq!(
+            service.insert_location(
+                &context,
+                inline_init(|r: &mut InsertLocation| {
+                    r.id = "bin".to_string();
+                    r.code = "bin".to_string();
+                    r.pack_capacity = Some(-1.0);
+                }),
+            ),
+            Err(InsertLocationError::InvalidCapacity)
+        );
+
+        // Move the cold room into the zone
+        let cold_room = service
+            .update_location(
+                &context,
+                inline_init(|r: &mut UpdateLocation| {
+                    r.id = "cold_room".to_string();
+                    r.parent_id = Some(Some("aisle".to_string()));
+                    r.location_type = Some(LocationType::Cold);
+                }),
+            )
+            .unwrap();
+        assert_eq!(cold_room.location_row.parent_id, Some("aisle".to_string()));
+        assert_eq!(cold_room.location_row.location_type, LocationType::Cold);
+
+        let zone_stock = service
+            .get_location_stock(&context, "zone".to_string())
+            .unwrap();
+        assert_eq!(zone_stock.location_count, 4);
+        assert_eq!(zone_stock.total_number_of_packs, 22.0);
+
+        let aisle_stock = service
+            .get_location_stock(&context, "aisle".to_string())
+            .unwrap();
+        assert_eq!(aisle_stock.stock_line_count, 2);
+        assert_eq!(aisle_stock.total_number_of_units, 27.0);
+
+        // Move the cold room back to the top level
+        let cold_room = service
+            .update_location(
+                &context,
+                inline_init(|r: &mut UpdateLocation| {
+                    r.id = "cold_room".to_string();
+                    r.parent_id = Some(None);
+                }),
+            )
+            .unwrap();
+        assert_eq!(cold_room.location_row.parent_id, None);
+
+        // Capacity is kept when not provided and can be cleared
+        let aisle = service
+            .update_location(
+                &context,
+                inline_init(|r: &mut UpdateLocation| {
+                    r.id = "aisle".to_string();
+                    r.volume_capacity = Some(Some(2.5));
+                }),
+            )
+            .unwrap();
+        assert_eq!(aisle.location_row.volume_capacity, Some(2.5));
+        assert_eq!(aisle.location_row.pack_capacity, Some(50.0));
+
+        let aisle = service
+            .update_location(
+                &context,
+                inline_init(|r: &mut UpdateLocation| {
+                    r.id = "aisle".to_string();
+                    r.pack_capacity = Some(None);
+                }),
+            )
+            .unwrap();
+        assert_eq!(aisle.location_row.volume_capacity, Some(2.5));
+        assert_eq!(aisle.location_row.pack_capacity, None);
+
+        assert_eq!(
+            service.update_location(
+                &context,
+                inline_init(|r: &mut UpdateLocation| {
+                    r.id = "aisle".to_string();
+                    r.volume_capacity = Some(Some(-1.0));
+                }),
+            ),
+            Err(UpdateLocationError::InvalidCapacity)
+        );
+    }
+}
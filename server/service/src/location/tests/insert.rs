@@ -4,7 +4,7 @@ mod query {
     use repository::EqualFilter;
     use repository::{
         mock::MockDataInserts, test_db::setup_all, Location, LocationFilter, LocationRepository,
-        LocationRow,
+        LocationRow, LocationType,
     };
 
     use crate::{
@@ -36,7 +36,11 @@ mod query {
                     id: mock_data["base"].locations[0].id.clone(),
                     code: "invalid".to_owned(),
                     name: None,
-                    on_hold: None
+                    on_hold: None,
+                    parent_id: None,
+                    location_type: None,
+                    volume_capacity: None,
+                    pack_capacity: None,
                 },
             ),
             Err(InsertLocationError::LocationAlreadyExists)
@@ -49,7 +53,11 @@ mod query {
                     id: "new_id".to_owned(),
                     code: locations_in_store[0].location_row.code.clone(),
                     name: None,
-                    on_hold: None
+                    on_hold: None,
+                    parent_id: None,
+                    location_type: None,
+                    volume_capacity: None,
+                    pack_capacity: None,
                 },
             ),
             Err(InsertLocationError::LocationWithCodeAlreadyExists)
@@ -76,6 +84,10 @@ mod query {
                 name: "new_code".to_owned(),
                 on_hold: false,
                 store_id: "store_a".to_owned(),
+                parent_id: None,
+                location_type: LocationType::Ambient,
+                volume_capacity: None,
+                pack_capacity: None,
             },
         };
 
@@ -86,7 +98,11 @@ mod query {
                     id: "new_id".to_owned(),
                     code: "new_code".to_owned(),
                     name: None,
-                    on_hold: None
+                    on_hold: None,
+                    parent_id: None,
+                    location_type: None,
+                    volume_capacity: None,
+                    pack_capacity: None,
                 },
             ),
             Ok(result_location.clone())
@@ -112,6 +128,10 @@ mod query {
                     code: "store_b_location_code".to_owned(),
                     name: Some("new_location_name".to_owned()),
                     on_hold: Some(true),
+                    parent_id: None,
+                    location_type: None,
+                    volume_capacity: None,
+                    pack_capacity: None,
                 },
             ),
             Ok(Location {
@@ -121,6 +141,10 @@ mod query {
                     code: "store_b_location_code".to_owned(),
                     on_hold: true,
                     store_id: "store_a".to_owned(),
+                    parent_id: None,
+                    location_type: LocationType::Ambient,
+                    volume_capacity: None,
+                    pack_capacity: None,
                 }
             })
         );
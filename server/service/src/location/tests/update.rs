@@ -40,7 +40,11 @@ mod query {
                     id: "invalid".to_owned(),
                     code: None,
                     name: None,
-                    on_hold: None
+                    on_hold: None,
+                    parent_id: None,
+                    location_type: None,
+                    volume_capacity: None,
+                    pack_capacity: None,
                 },
             ),
             Err(UpdateLocationError::LocationDoesNotExist)
@@ -54,7 +58,11 @@ mod query {
                     id: locations_not_in_store[0].location_row.id.clone(),
                     code: None,
                     name: None,
-                    on_hold: None
+                    on_hold: None,
+                    parent_id: None,
+                    location_type: None,
+                    volume_capacity: None,
+                    pack_capacity: None,
                 },
             ),
             Err(UpdateLocationError::LocationDoesNotBelongToCurrentStore)
@@ -68,7 +76,11 @@ mod query {
                     id: locations_in_store[0].location_row.id.clone(),
                     code: Some(locations_in_store[1].location_row.code.clone()),
                     name: None,
-                    on_hold: None
+                    on_hold: None,
+                    parent_id: None,
+                    location_type: None,
+                    volume_capacity: None,
+                    pack_capacity: None,
                 },
             ),
             Err(UpdateLocationError::CodeAlreadyExists)
@@ -100,7 +112,11 @@ mod query {
                     id: location.location_row.id.clone(),
                     code: None,
                     name: None,
-                    on_hold: None
+                    on_hold: None,
+                    parent_id: None,
+                    location_type: None,
+                    volume_capacity: None,
+                    pack_capacity: None,
                 },
             ),
             Ok(location.clone())
@@ -129,6 +145,10 @@ mod query {
                     code: Some(location.location_row.code.clone()),
                     name: Some(location.location_row.name.clone()),
                     on_hold: Some(location.location_row.on_hold),
+                    parent_id: None,
+                    location_type: None,
+                    volume_capacity: None,
+                    pack_capacity: None,
                 },
             ),
             Ok(location.clone())
@@ -1,10 +1,14 @@
 use super::{
     query::get_location,
-    validate::{check_location_code_is_unique, check_location_exists},
+    validate::{
+        check_capacity_is_valid, check_location_code_is_unique, check_location_exists,
+        check_parent_location,
+    },
 };
 use crate::{service_provider::ServiceContext, SingleRecordError};
 use repository::{
-    Location, LocationRow, LocationRowRepository, RepositoryError, StorageConnection,
+    HoldRecordType, HoldRowRepository, Location, LocationRow, LocationRowRepository, LocationType,
+    RepositoryError, StorageConnection,
 };
 
 #[derive(PartialEq, Debug)]
@@ -12,15 +16,27 @@ pub enum UpdateLocationError {
     LocationDoesNotExist,
     CodeAlreadyExists,
     LocationDoesNotBelongToCurrentStore,
+    /// Parent location doesn't exist in the current store, or is the location itself or one of
+    /// its child locations
+    InvalidParentLocation,
+    InvalidCapacity,
     UpdatedRecordNotFound,
     DatabaseError(RepositoryError),
 }
 
+#[derive(Default)]
 pub struct UpdateLocation {
     pub id: String,
     pub code: Option<String>,
     pub name: Option<String>,
     pub on_hold: Option<bool>,
+    /// `Some(None)` moves the location to the top level
+    pub parent_id: Option<Option<String>>,
+    pub location_type: Option<LocationType>,
+    /// `Some(None)` clears the capacity
+    pub volume_capacity: Option<Option<f64>>,
+    /// `Some(None)` clears the capacity
+    pub pack_capacity: Option<Option<f64>>,
 }
 
 pub fn update_location(
@@ -33,6 +49,10 @@ pub fn update_location(
             let location_row = validate(connection, &ctx.store_id, &input)?;
             let updated_location_row = generate(input, location_row);
             LocationRowRepository::new(&connection).upsert_one(&updated_location_row)?;
+            if !updated_location_row.on_hold {
+                HoldRowRepository::new(connection)
+                    .delete_by_record(&HoldRecordType::Location, &updated_location_row.id)?;
+            }
 
             get_location(ctx, updated_location_row.id).map_err(UpdateLocationError::from)
         })
@@ -58,6 +78,18 @@ pub fn validate(
         return Err(UpdateLocationError::LocationDoesNotBelongToCurrentStore);
     }
 
+    if let Some(Some(parent_id)) = &input.parent_id {
+        if !check_parent_location(&input.id, parent_id, store_id, connection)? {
+            return Err(UpdateLocationError::InvalidParentLocation);
+        }
+    }
+
+    if !check_capacity_is_valid(input.volume_capacity.flatten())
+        || !check_capacity_is_valid(input.pack_capacity.flatten())
+    {
+        return Err(UpdateLocationError::InvalidCapacity);
+    }
+
     Ok(location_row)
 }
 
@@ -67,12 +99,20 @@ pub fn generate(
         code,
         name,
         on_hold,
+        parent_id,
+        location_type,
+        volume_capacity,
+        pack_capacity,
     }: UpdateLocation,
     mut location_row: LocationRow,
 ) -> LocationRow {
     location_row.code = code.unwrap_or(location_row.code);
     location_row.name = name.unwrap_or(location_row.name);
     location_row.on_hold = on_hold.unwrap_or(location_row.on_hold);
+    location_row.parent_id = parent_id.unwrap_or(location_row.parent_id);
+    location_row.location_type = location_type.unwrap_or(location_row.location_type);
+    location_row.volume_capacity = volume_capacity.unwrap_or(location_row.volume_capacity);
+    location_row.pack_capacity = pack_capacity.unwrap_or(location_row.pack_capacity);
     location_row
 }
 
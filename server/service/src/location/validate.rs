@@ -1,3 +1,4 @@
+use super::hierarchy::get_location_subtree_ids;
 use repository::EqualFilter;
 use repository::{
     LocationFilter, LocationRepository, LocationRow, LocationRowRepository, RepositoryError,
@@ -30,3 +31,27 @@ pub fn check_location_exists(
 ) -> Result<Option<LocationRow>, RepositoryError> {
     Ok(LocationRowRepository::new(connection).find_one_by_id(id)?)
 }
+
+/// Parent location must exist in the store and can't be the location itself or one of the
+/// locations nested under it
+pub fn check_parent_location(
+    id: &str,
+    parent_id: &str,
+    store_id: &str,
+    connection: &StorageConnection,
+) -> Result<bool, RepositoryError> {
+    let parent = match check_location_exists(parent_id, connection)? {
+        Some(parent) => parent,
+        None => return Ok(false),
+    };
+    if parent.store_id != store_id {
+        return Ok(false);
+    }
+
+    let subtree_ids = get_location_subtree_ids(connection, &[id.to_string()])?;
+    Ok(!subtree_ids.contains(&parent.id))
+}
+
+pub fn check_capacity_is_valid(capacity: Option<f64>) -> bool {
+    capacity.map(|capacity| capacity >= 0.0).unwrap_or(true)
+}
@@ -0,0 +1,979 @@
+use qrcode::{Color, QrCode};
+
+/// Barcode symbologies that can be rendered in reports
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum BarcodeFormat {
+    Code128,
+    /// Code128 with GS1 application identifiers, e.g. "(01)09501101530003(17)250101(10)AB12"
+    Gs1_128,
+    DataMatrix,
+    /// DataMatrix with GS1 application identifiers
+    Gs1DataMatrix,
+    Qr,
+}
+
+impl BarcodeFormat {
+    pub fn parse(format: &str) -> Option<BarcodeFormat> {
+        let format = match format.to_lowercase().as_str() {
+            "code128" => BarcodeFormat::Code128,
+            "gs1-128" => BarcodeFormat::Gs1_128,
+            "datamatrix" => BarcodeFormat::DataMatrix,
+            "gs1-datamatrix" => BarcodeFormat::Gs1DataMatrix,
+            "qr" => BarcodeFormat::Qr,
+            _ => return None,
+        };
+        Some(format)
+    }
+}
+
+pub struct BarcodeOptions {
+    /// Width of a single module (narrowest bar or matrix cell) in px
+    pub module_size: f64,
+    /// Bar height of linear barcodes in px
+    pub height: f64,
+    /// Print the human readable text below linear barcodes
+    pub show_text: bool,
+}
+
+impl Default for BarcodeOptions {
+    fn default() -> Self {
+        BarcodeOptions {
+            module_size: 2.0,
+            height: 60.0,
+            show_text: true,
+        }
+    }
+}
+
+/// Renders a barcode as inline SVG
+pub fn barcode_svg(
+    format: BarcodeFormat,
+    value: &str,
+    options: &BarcodeOptions,
+) -> Result<String, String> {
+    match format {
+        BarcodeFormat::Code128 => {
+            let symbols = code128_symbols(&to_code128_input(value)?)?;
+            Ok(linear_svg(&code128_modules(&symbols), value, options))
+        }
+        BarcodeFormat::Gs1_128 => {
+            let mut input = vec![Code128Input::Fnc1];
+            input.extend(gs1_input(value)?);
+            let symbols = code128_symbols(&input)?;
+            Ok(linear_svg(&code128_modules(&symbols), value, options))
+        }
+        BarcodeFormat::DataMatrix => {
+            let input = value.bytes().map(DataMatrixInput::Byte).collect::<Vec<_>>();
+            Ok(matrix_svg(&data_matrix(&input)?, 1, options))
+        }
+        BarcodeFormat::Gs1DataMatrix => {
+            let mut input = vec![DataMatrixInput::Fnc1];
+            input.extend(gs1_input(value)?.into_iter().map(|input| match input {
+                Code128Input::Byte(byte) => DataMatrixInput::Byte(byte),
+                Code128Input::Fnc1 => DataMatrixInput::Fnc1,
+            }));
+            Ok(matrix_svg(&data_matrix(&input)?, 1, options))
+        }
+        BarcodeFormat::Qr => {
+            let code = QrCode::new(value.as_bytes())
+                .map_err(|err| format!("Failed to encode QR code: {}", err))?;
+            let width = code.width();
+            let colors = code.to_colors();
+            let matrix = colors
+                .chunks(width)
+                .map(|row| row.iter().map(|color| *color == Color::Dark).collect())
+                .collect::<Vec<Vec<bool>>>();
+            Ok(matrix_svg(&matrix, 4, options))
+        }
+    }
+}
+
+pub(super) fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Renders a list of bar (true) and space (false) modules
+fn linear_svg(modules: &[bool], text: &str, options: &BarcodeOptions) -> String {
+    const QUIET_ZONE: usize = 10;
+    const TEXT_HEIGHT: f64 = 14.0;
+    let size = options.module_size;
+    let width = (modules.len() + 2 * QUIET_ZONE) as f64 * size;
+    let height = if options.show_text {
+        options.height + TEXT_HEIGHT
+    } else {
+        options.height
+    };
+
+    let mut bars = String::new();
+    let mut index = 0;
+    while index < modules.len() {
+        if !modules[index] {
+            index += 1;
+            continue;
+        }
+        let start = index;
+        while index < modules.len() && modules[index] {
+            index += 1;
+        }
+        bars.push_str(&format!(
+            r#"<rect x="{}" y="0" width="{}" height="{}"/>"#,
+            (start + QUIET_ZONE) as f64 * size,
+            (index - start) as f64 * size,
+            options.height
+        ));
+    }
+    if options.show_text {
+        bars.push_str(&format!(
+            r#"<text x="{}" y="{}" font-family="monospace" font-size="12" text-anchor="middle">{}</text>"#,
+            width / 2.0,
+            height - 2.0,
+            escape_xml(text)
+        ));
+    }
+    format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}"><rect width="{w}" height="{h}" fill="white"/><g fill="black">{bars}</g></svg>"#,
+        w = width,
+        h = height,
+        bars = bars
+    )
+}
+
+/// Renders a matrix of dark (true) and light (false) modules
+fn matrix_svg(matrix: &[Vec<bool>], quiet_zone: usize, options: &BarcodeOptions) -> String {
+    let size = options.module_size;
+    let rows = matrix.len();
+    let cols = matrix.first().map(Vec::len).unwrap_or(0);
+    let width = (cols + 2 * quiet_zone) as f64 * size;
+    let height = (rows + 2 * quiet_zone) as f64 * size;
+
+    let mut cells = String::new();
+    for (row, modules) in matrix.iter().enumerate() {
+        let mut col = 0;
+        while col < modules.len() {
+            if !modules[col] {
+                col += 1;
+                continue;
+            }
+            let start = col;
+            while col < modules.len() && modules[col] {
+                col += 1;
+            }
+            cells.push_str(&format!(
+                r#"<rect x="{}" y="{}" width="{}" height="{}"/>"#,
+                (start + quiet_zone) as f64 * size,
+                (row + quiet_zone) as f64 * size,
+                (col - start) as f64 * size,
+                size
+            ));
+        }
+    }
+    format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" shape-rendering="crispEdges"><rect width="{w}" height="{h}" fill="white"/><g fill="black">{cells}</g></svg>"#,
+        w = width,
+        h = height,
+        cells = cells
+    )
+}
+
+// GS1 application identifiers
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Code128Input {
+    Byte(u8),
+    Fnc1,
+}
+
+/// Application identifiers with a predefined length (including the AI itself), data following
+/// these AIs doesn't need to be terminated by FNC1
+fn gs1_predefined_length(ai: &str) -> Option<usize> {
+    let length = match ai.get(0..2)? {
+        "00" => 20,
+        "01" | "02" => 16,
+        "03" => 16,
+        "04" => 18,
+        "11" | "12" | "13" | "14" | "15" | "16" | "17" | "18" | "19" => 8,
+        "20" => 4,
+        "31" | "32" | "33" | "34" | "35" | "36" => 10,
+        "41" => 16,
+        _ => return None,
+    };
+    Some(length)
+}
+
+/// Parses GS1 element strings in the human readable form "(01)09501101530003(10)AB12" and
+/// inserts FNC1 separators after variable length fields
+fn gs1_input(value: &str) -> Result<Vec<Code128Input>, String> {
+    let mut input = Vec::new();
+    let mut rest = value;
+    if !rest.starts_with('(') {
+        return Err(format!(
+            "Invalid GS1 data, application identifiers must be in brackets: {}",
+            value
+        ));
+    }
+    while let Some(stripped) = rest.strip_prefix('(') {
+        let end = stripped
+            .find(')')
+            .ok_or_else(|| format!("Invalid GS1 data, missing closing bracket: {}", value))?;
+        let ai = &stripped[..end];
+        if ai.len() < 2 || ai.len() > 4 || !ai.chars().all(|c| c.is_ascii_digit()) {
+            return Err(format!("Invalid GS1 application identifier: {}", ai));
+        }
+        let data_end = stripped[end + 1..]
+            .find('(')
+            .map(|index| index + end + 1)
+            .unwrap_or(stripped.len());
+        let data = &stripped[end + 1..data_end];
+        if data.is_empty() {
+            return Err(format!(
+                "Missing data for GS1 application identifier {}",
+                ai
+            ));
+        }
+        let element = format!("{}{}", ai, data);
+        if let Some(length) = gs1_predefined_length(ai) {
+            if element.len() != length {
+                return Err(format!(
+                    "Invalid data length for GS1 application identifier {}",
+                    ai
+                ));
+            }
+        }
+        input.extend(element.bytes().map(Code128Input::Byte));
+        rest = &stripped[data_end..];
+        if !rest.is_empty() && gs1_predefined_length(ai).is_none() {
+            input.push(Code128Input::Fnc1);
+        }
+    }
+    Ok(input)
+}
+
+// Code128
+
+const CODE128_PATTERNS: [&str; 107] = [
+    "212222", "222122", "222221", "121223", "121322", "131222", "122213", "122312", "132212",
+    "221213", "221312", "231212", "112232", "122132", "122231", "113222", "123122", "123221",
+    "223211", "221132", "221231", "213212", "223112", "312131", "311222", "321122", "321221",
+    "312212", "322112", "322211", "212123", "212321", "232121", "111323", "131123", "131321",
+    "112313", "132113", "132311", "211313", "231113", "231311", "112133", "112331", "132131",
+    "113123", "113321", "133121", "313121", "211331", "231131", "213113", "213311", "213131",
+    "311123", "311321", "331121", "312113", "312311", "332111", "314111", "221411", "431111",
+    "111224", "111422", "121124", "121421", "141122", "141221", "112214", "112412", "122114",
+    "122411", "142112", "142211", "241211", "221114", "413111", "241112", "134111", "111242",
+    "121142", "121241", "114212", "124112", "124211", "411212", "421112", "421211", "212141",
+    "214121", "412121", "111143", "111341", "131141", "114113", "114311", "411113", "411311",
+    "113141", "114131", "311141", "411131", "211412", "211214", "211232", "2331112",
+];
+
+const CODE128_FNC1: u8 = 102;
+const CODE128_START_B: u8 = 104;
+const CODE128_START_C: u8 = 105;
+const CODE128_CODE_B: u8 = 100;
+const CODE128_CODE_C: u8 = 99;
+const CODE128_STOP: u8 = 106;
+
+fn to_code128_input(value: &str) -> Result<Vec<Code128Input>, String> {
+    if value.is_empty() {
+        return Err("Can't encode an empty barcode".to_string());
+    }
+    Ok(value.bytes().map(Code128Input::Byte).collect())
+}
+
+/// Number of consecutive digits starting at `index`
+fn digit_run(input: &[Code128Input], index: usize) -> usize {
+    input[index..]
+        .iter()
+        .take_while(|input| matches!(input, Code128Input::Byte(byte) if byte.is_ascii_digit()))
+        .count()
+}
+
+/// Encodes the input using code set B and switches to code set C for longer digit runs.
+/// Returns the symbol values including start, check and stop symbols.
+fn code128_symbols(input: &[Code128Input]) -> Result<Vec<u8>, String> {
+    let mut symbols = Vec::new();
+    let mut code_c = None;
+    let mut index = 0;
+    while index < input.len() {
+        // FNC1 can be encoded in any code set, choose the code set for the data following it
+        let (digits_start, is_fnc1) = match input[index] {
+            Code128Input::Fnc1 => (index + 1, true),
+            Code128Input::Byte(_) => (index, false),
+        };
+        let digits = if digits_start < input.len() {
+            digit_run(input, digits_start)
+        } else {
+            0
+        };
+        // code set C is worth it for 4 digits at the start or end and 6 digits in between
+        let use_c = digits >= 6
+            || (digits >= 4 && (digits_start == 0 || digits_start + digits == input.len()))
+            || (digits >= 2 && code_c.is_none() && digits_start + digits == input.len())
+            || (digits >= 2 && code_c == Some(true))
+            || (is_fnc1 && digits == 0 && code_c == Some(true));
+        let use_c = use_c || (is_fnc1 && code_c.is_none() && digits >= 4);
+
+        match (code_c, use_c) {
+            (None, true) => symbols.push(CODE128_START_C),
+            (None, false) => symbols.push(CODE128_START_B),
+            (Some(false), true) => symbols.push(CODE128_CODE_C),
+            (Some(true), false) => symbols.push(CODE128_CODE_B),
+            _ => {}
+        }
+        code_c = Some(use_c);
+
+        match input[index] {
+            Code128Input::Fnc1 => {
+                symbols.push(CODE128_FNC1);
+                index += 1;
+            }
+            Code128Input::Byte(_) if use_c => {
+                // encode an even number of digits in code set C
+                let digits = digits - digits % 2;
+                for pair in input[index..index + digits].chunks(2) {
+                    let value = match pair {
+                        [Code128Input::Byte(high), Code128Input::Byte(low)] => {
+                            (high - b'0') * 10 + (low - b'0')
+                        }
+                        _ => unreachable!(),
+                    };
+                    symbols.push(value);
+                }
+                index += digits;
+            }
+            Code128Input::Byte(byte) => {
+                if !(32..=126).contains(&byte) {
+                    return Err(format!(
+                        "Character {:?} can't be encoded in Code128",
+                        byte as char
+                    ));
+                }
+                symbols.push(byte - 32);
+                index += 1;
+            }
+        }
+    }
+
+    let checksum = symbols
+        .iter()
+        .enumerate()
+        .map(|(position, symbol)| *symbol as usize * position.max(1))
+        .sum::<usize>()
+        % 103;
+    symbols.push(checksum as u8);
+    symbols.push(CODE128_STOP);
+    Ok(symbols)
+}
+
+fn code128_modules(symbols: &[u8]) -> Vec<bool> {
+    let mut modules = Vec::new();
+    for symbol in symbols {
+        for (index, width) in CODE128_PATTERNS[*symbol as usize].bytes().enumerate() {
+            let is_bar = index % 2 == 0;
+            modules.extend(std::iter::repeat_n(is_bar, (width - b'0') as usize));
+        }
+    }
+    modules
+}
+
+// DataMatrix (ECC 200)
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum DataMatrixInput {
+    Byte(u8),
+    Fnc1,
+}
+
+struct DataMatrixSize {
+    /// Symbol width and height in modules
+    size: usize,
+    /// Width and height of a single data region
+    region_size: usize,
+    data_codewords: usize,
+    ecc_codewords: usize,
+    /// Number of interleaved Reed-Solomon blocks
+    blocks: usize,
+}
+
+const DATA_MATRIX_SIZES: [DataMatrixSize; 16] = [
+    DataMatrixSize {
+        size: 10,
+        region_size: 8,
+        data_codewords: 3,
+        ecc_codewords: 5,
+        blocks: 1,
+    },
+    DataMatrixSize {
+        size: 12,
+        region_size: 10,
+        data_codewords: 5,
+        ecc_codewords: 7,
+        blocks: 1,
+    },
+    DataMatrixSize {
+        size: 14,
+        region_size: 12,
+        data_codewords: 8,
+        ecc_codewords: 10,
+        blocks: 1,
+    },
+    DataMatrixSize {
+        size: 16,
+        region_size: 14,
+        data_codewords: 12,
+        ecc_codewords: 12,
+        blocks: 1,
+    },
+    DataMatrixSize {
+        size: 18,
+        region_size: 16,
+        data_codewords: 18,
+        ecc_codewords: 14,
+        blocks: 1,
+    },
+    DataMatrixSize {
+        size: 20,
+        region_size: 18,
+        data_codewords: 22,
+        ecc_codewords: 18,
+        blocks: 1,
+    },
+    DataMatrixSize {
+        size: 22,
+        region_size: 20,
+        data_codewords: 30,
+        ecc_codewords: 20,
+        blocks: 1,
+    },
+    DataMatrixSize {
+        size: 24,
+        region_size: 22,
+        data_codewords: 36,
+        ecc_codewords: 24,
+        blocks: 1,
+    },
+    DataMatrixSize {
+        size: 26,
+        region_size: 24,
+        data_codewords: 44,
+        ecc_codewords: 28,
+        blocks: 1,
+    },
+    DataMatrixSize {
+        size: 32,
+        region_size: 14,
+        data_codewords: 62,
+        ecc_codewords: 36,
+        blocks: 1,
+    },
+    DataMatrixSize {
+        size: 36,
+        region_size: 16,
+        data_codewords: 86,
+        ecc_codewords: 42,
+        blocks: 1,
+    },
+    DataMatrixSize {
+        size: 40,
+        region_size: 18,
+        data_codewords: 114,
+        ecc_codewords: 48,
+        blocks: 1,
+    },
+    DataMatrixSize {
+        size: 44,
+        region_size: 20,
+        data_codewords: 144,
+        ecc_codewords: 56,
+        blocks: 1,
+    },
+    DataMatrixSize {
+        size: 48,
+        region_size: 22,
+        data_codewords: 174,
+        ecc_codewords: 68,
+        blocks: 1,
+    },
+    DataMatrixSize {
+        size: 52,
+        region_size: 24,
+        data_codewords: 204,
+        ecc_codewords: 84,
+        blocks: 2,
+    },
+    DataMatrixSize {
+        size: 64,
+        region_size: 14,
+        data_codewords: 280,
+        ecc_codewords: 112,
+        blocks: 2,
+    },
+];
+
+/// ASCII encodation, digit pairs are packed into a single codeword
+fn data_matrix_codewords(input: &[DataMatrixInput]) -> Vec<u8> {
+    let mut codewords = Vec::new();
+    let mut index = 0;
+    while index < input.len() {
+        match (input[index], input.get(index + 1)) {
+            (DataMatrixInput::Fnc1, _) => codewords.push(232),
+            (DataMatrixInput::Byte(high), Some(DataMatrixInput::Byte(low)))
+                if high.is_ascii_digit() && low.is_ascii_digit() =>
+            {
+                codewords.push((high - b'0') * 10 + (low - b'0') + 130);
+                index += 1;
+            }
+            (DataMatrixInput::Byte(byte), _) if byte < 128 => codewords.push(byte + 1),
+            (DataMatrixInput::Byte(byte), _) => {
+                // upper shift for extended ASCII
+                codewords.push(235);
+                codewords.push(byte - 128 + 1);
+            }
+        }
+        index += 1;
+    }
+    codewords
+}
+
+/// Reed-Solomon error correction codewords over GF(256) with the polynomial 0x12D
+fn reed_solomon(data: &[u8], ecc_length: usize) -> Vec<u8> {
+    let mut exp = [0u8; 255];
+    let mut log = [0u8; 256];
+    let mut value: u16 = 1;
+    for (index, entry) in exp.iter_mut().enumerate() {
+        *entry = value as u8;
+        log[value as usize] = index as u8;
+        value <<= 1;
+        if value >= 256 {
+            value ^= 0x12D;
+        }
+    }
+    let multiply = |a: u8, b: u8| -> u8 {
+        if a == 0 || b == 0 {
+            return 0;
+        }
+        exp[(log[a as usize] as usize + log[b as usize] as usize) % 255]
+    };
+
+    // generator polynomial (x - 2^1)(x - 2^2)...(x - 2^n), highest coefficient first
+    let mut generator = vec![1u8];
+    for root in 1..=ecc_length {
+        let mut next = vec![0u8; generator.len() + 1];
+        for (index, coefficient) in generator.iter().enumerate() {
+            next[index] ^= coefficient;
+            next[index + 1] ^= multiply(*coefficient, exp[root % 255]);
+        }
+        generator = next;
+    }
+
+    let mut remainder = vec![0u8; ecc_length];
+    for byte in data {
+        let factor = byte ^ remainder[0];
+        remainder.remove(0);
+        remainder.push(0);
+        for (index, coefficient) in generator[1..].iter().enumerate() {
+            remainder[index] ^= multiply(factor, *coefficient);
+        }
+    }
+    remainder
+}
+
+/// Places the codeword bits in the mapping matrix (ECC 200 "utah" placement).
+/// Each module holds (codeword index, bit index) with bit 0 being the most significant bit, or
+/// None for the modules of the fixed pattern in the bottom right corner.
+struct Placement {
+    rows: usize,
+    cols: usize,
+    modules: Vec<Option<(usize, usize)>>,
+    filled: Vec<bool>,
+}
+
+impl Placement {
+    fn new(rows: usize, cols: usize) -> Placement {
+        let mut placement = Placement {
+            rows,
+            cols,
+            modules: vec![None; rows * cols],
+            filled: vec![false; rows * cols],
+        };
+        placement.place();
+        placement
+    }
+
+    fn module(&mut self, row: isize, col: isize, codeword: usize, bit: usize) {
+        let (rows, cols) = (self.rows as isize, self.cols as isize);
+        let (mut row, mut col) = (row, col);
+        if row < 0 {
+            row += rows;
+            col += 4 - ((rows + 4) % 8);
+        }
+        if col < 0 {
+            col += cols;
+            row += 4 - ((cols + 4) % 8);
+        }
+        let index = row as usize * self.cols + col as usize;
+        self.modules[index] = Some((codeword, bit));
+        self.filled[index] = true;
+    }
+
+    fn utah(&mut self, row: isize, col: isize, codeword: usize) {
+        self.module(row - 2, col - 2, codeword, 0);
+        self.module(row - 2, col - 1, codeword, 1);
+        self.module(row - 1, col - 2, codeword, 2);
+        self.module(row - 1, col - 1, codeword, 3);
+        self.module(row - 1, col, codeword, 4);
+        self.module(row, col - 2, codeword, 5);
+        self.module(row, col - 1, codeword, 6);
+        self.module(row, col, codeword, 7);
+    }
+
+    fn corner(&mut self, positions: [(isize, isize); 8], codeword: usize) {
+        for (bit, (row, col)) in positions.iter().enumerate() {
+            self.module(*row, *col, codeword, bit);
+        }
+    }
+
+    fn is_filled(&self, row: isize, col: isize) -> bool {
+        self.filled[row as usize * self.cols + col as usize]
+    }
+
+    fn place(&mut self) {
+        let (rows, cols) = (self.rows as isize, self.cols as isize);
+        let mut codeword = 0;
+        let mut row: isize = 4;
+        let mut col: isize = 0;
+        loop {
+            if row == rows && col == 0 {
+                self.corner(
+                    [
+                        (rows - 1, 0),
+                        (rows - 1, 1),
+                        (rows - 1, 2),
+                        (0, cols - 2),
+                        (0, cols - 1),
+                        (1, cols - 1),
+                        (2, cols - 1),
+                        (3, cols - 1),
+                    ],
+                    codeword,
+                );
+                codeword += 1;
+            }
+            if row == rows - 2 && col == 0 && cols % 4 != 0 {
+                self.corner(
+                    [
+                        (rows - 3, 0),
+                        (rows - 2, 0),
+                        (rows - 1, 0),
+                        (0, cols - 4),
+                        (0, cols - 3),
+                        (0, cols - 2),
+                        (0, cols - 1),
+                        (1, cols - 1),
+                    ],
+                    codeword,
+                );
+                codeword += 1;
+            }
+            if row == rows - 2 && col == 0 && cols % 8 == 4 {
+                self.corner(
+                    [
+                        (rows - 3, 0),
+                        (rows - 2, 0),
+                        (rows - 1, 0),
+                        (0, cols - 2),
+                        (0, cols - 1),
+                        (1, cols - 1),
+                        (2, cols - 1),
+                        (3, cols - 1),
+                    ],
+                    codeword,
+                );
+                codeword += 1;
+            }
+            if row == rows + 4 && col == 2 && cols % 8 == 0 {
+                self.corner(
+                    [
+                        (rows - 1, 0),
+                        (rows - 1, cols - 1),
+                        (0, cols - 3),
+                        (0, cols - 2),
+                        (0, cols - 1),
+                        (1, cols - 3),
+                        (1, cols - 2),
+                        (1, cols - 1),
+                    ],
+                    codeword,
+                );
+                codeword += 1;
+            }
+            // sweep upward diagonally
+            loop {
+                if row < rows && col >= 0 && !self.is_filled(row, col) {
+                    self.utah(row, col, codeword);
+                    codeword += 1;
+                }
+                row -= 2;
+                col += 2;
+                if !(row >= 0 && col < cols) {
+                    break;
+                }
+            }
+            row += 1;
+            col += 3;
+            // sweep downward diagonally
+            loop {
+                if row >= 0 && col < cols && !self.is_filled(row, col) {
+                    self.utah(row, col, codeword);
+                    codeword += 1;
+                }
+                row += 2;
+                col -= 2;
+                if !(row < rows && col >= 0) {
+                    break;
+                }
+            }
+            row += 3;
+            col += 1;
+            if !(row < rows || col < cols) {
+                break;
+            }
+        }
+        // the bottom right corner is left unfilled for some sizes, fill it with a fixed pattern
+        let last = (self.rows * self.cols) - 1;
+        if !self.filled[last] {
+            self.filled[last] = true;
+            self.filled[last - self.cols - 1] = true;
+        }
+    }
+
+    /// Returns whether the module is dark
+    fn is_dark(&self, row: usize, col: usize, codewords: &[u8]) -> bool {
+        let index = row * self.cols + col;
+        match self.modules[index] {
+            Some((codeword, bit)) => (codewords[codeword] >> (7 - bit)) & 1 == 1,
+            None => self.filled[index],
+        }
+    }
+}
+
+/// Encodes the input as ECC 200 DataMatrix and returns the module matrix
+fn data_matrix(input: &[DataMatrixInput]) -> Result<Vec<Vec<bool>>, String> {
+    let mut codewords = data_matrix_codewords(input);
+    if codewords.is_empty() {
+        return Err("Can't encode an empty barcode".to_string());
+    }
+    let symbol = DATA_MATRIX_SIZES
+        .iter()
+        .find(|symbol| symbol.data_codewords >= codewords.len())
+        .ok_or_else(|| "Data too long to be encoded as DataMatrix".to_string())?;
+
+    // padding
+    if codewords.len() < symbol.data_codewords {
+        codewords.push(129);
+    }
+    while codewords.len() < symbol.data_codewords {
+        let position = codewords.len() + 1;
+        let random = (149 * position) % 253 + 1;
+        let pad = 129 + random;
+        codewords.push(if pad > 254 { pad - 254 } else { pad } as u8);
+    }
+
+    // error correction, codewords are interleaved between the blocks
+    let mut ecc = vec![0u8; symbol.ecc_codewords];
+    for block in 0..symbol.blocks {
+        let data = codewords
+            .iter()
+            .skip(block)
+            .step_by(symbol.blocks)
+            .cloned()
+            .collect::<Vec<u8>>();
+        let block_ecc = reed_solomon(&data, symbol.ecc_codewords / symbol.blocks);
+        for (index, codeword) in block_ecc.into_iter().enumerate() {
+            ecc[block + index * symbol.blocks] = codeword;
+        }
+    }
+    codewords.extend(ecc);
+
+    // data regions per side, each region is surrounded by finder and clock patterns
+    let region_step = symbol.region_size + 2;
+    let mapping_size = (symbol.size / region_step) * symbol.region_size;
+    let placement = Placement::new(mapping_size, mapping_size);
+
+    let mut matrix = vec![vec![false; symbol.size]; symbol.size];
+    for (row, modules) in matrix.iter_mut().enumerate() {
+        for (col, module) in modules.iter_mut().enumerate() {
+            let region_row = row % region_step;
+            let region_col = col % region_step;
+            *module = if region_col == 0 || region_row == region_step - 1 {
+                // solid finder pattern on the left and bottom
+                true
+            } else if region_row == 0 {
+                // alternating clock pattern on the top
+                region_col % 2 == 0
+            } else if region_col == region_step - 1 {
+                // alternating clock pattern on the right
+                region_row % 2 == 1
+            } else {
+                let mapping_row = (row / region_step) * symbol.region_size + region_row - 1;
+                let mapping_col = (col / region_step) * symbol.region_size + region_col - 1;
+                placement.is_dark(mapping_row, mapping_col, &codewords)
+            };
+        }
+    }
+    Ok(matrix)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn code128_patterns() {
+        for (symbol, pattern) in CODE128_PATTERNS.iter().enumerate() {
+            let width: u32 = pattern.bytes().map(|width| (width - b'0') as u32).sum();
+            let expected = if symbol == CODE128_STOP as usize {
+                13
+            } else {
+                11
+            };
+            assert_eq!(width, expected, "Invalid pattern for symbol {}", symbol);
+        }
+    }
+
+    #[test]
+    fn code128_encoding() {
+        // code set B
+        assert_eq!(
+            code128_symbols(&to_code128_input("AB-1").unwrap()).unwrap(),
+            vec![CODE128_START_B, 33, 34, 13, 17, 3, CODE128_STOP]
+        );
+        // only digits are encoded in code set C
+        assert_eq!(
+            code128_symbols(&to_code128_input("123456").unwrap()).unwrap(),
+            vec![CODE128_START_C, 12, 34, 56, 44, CODE128_STOP]
+        );
+        // mixed code sets
+        let symbols = code128_symbols(&to_code128_input("AB12345678").unwrap()).unwrap();
+        assert_eq!(
+            &symbols[..8],
+            &[CODE128_START_B, 33, 34, CODE128_CODE_C, 12, 34, 56, 78]
+        );
+        assert!(code128_symbols(&to_code128_input("Ä").unwrap()).is_err());
+    }
+
+    #[test]
+    fn gs1_128_encoding() {
+        let mut input = vec![Code128Input::Fnc1];
+        input.extend(gs1_input("(01)09501101530003(10)AB12(17)250101").unwrap());
+        let symbols = code128_symbols(&input).unwrap();
+        // variable length batch (10) is terminated by FNC1, fixed length GTIN (01) isn't
+        assert_eq!(
+            &symbols[..10],
+            &[CODE128_START_C, CODE128_FNC1, 1, 9, 50, 11, 1, 53, 0, 3]
+        );
+        assert_eq!(
+            symbols
+                .iter()
+                .filter(|symbol| **symbol == CODE128_FNC1)
+                .count(),
+            2
+        );
+
+        assert!(gs1_input("0109501101530003").is_err());
+        // invalid GTIN length
+        assert!(gs1_input("(01)0950110153000").is_err());
+    }
+
+    #[test]
+    fn data_matrix_encoding() {
+        assert_eq!(
+            data_matrix_codewords(
+                &"123456"
+                    .bytes()
+                    .map(DataMatrixInput::Byte)
+                    .collect::<Vec<_>>()
+            ),
+            vec![142, 164, 186]
+        );
+        // ISO/IEC 16022 example: "123456" in a 10x10 symbol
+        assert_eq!(reed_solomon(&[142, 164, 186], 5), vec![114, 25, 5, 88, 102]);
+
+        let matrix = data_matrix(
+            &"123456"
+                .bytes()
+                .map(DataMatrixInput::Byte)
+                .collect::<Vec<_>>(),
+        )
+        .unwrap();
+        assert_eq!(matrix.len(), 10);
+        let rendered = matrix
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|dark| if *dark { '#' } else { '.' })
+                    .collect::<String>()
+            })
+            .collect::<Vec<String>>();
+        // finder and clock patterns
+        assert_eq!(rendered[0], "#.#.#.#.#.");
+        assert_eq!(rendered[9], "##########");
+        assert!(rendered.iter().all(|row| row.starts_with('#')));
+
+        // larger symbols with multiple data regions and interleaved blocks
+        let long = "A".repeat(250);
+        let matrix =
+            data_matrix(&long.bytes().map(DataMatrixInput::Byte).collect::<Vec<_>>()).unwrap();
+        assert_eq!(matrix.len(), 64);
+        assert!(data_matrix(
+            &"A".repeat(300)
+                .bytes()
+                .map(DataMatrixInput::Byte)
+                .collect::<Vec<_>>()
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn data_matrix_placement() {
+        // every codeword bit must be placed exactly once
+        for symbol in DATA_MATRIX_SIZES.iter() {
+            let mapping_size = (symbol.size / (symbol.region_size + 2)) * symbol.region_size;
+            let placement = Placement::new(mapping_size, mapping_size);
+            let mut placed = placement.modules.iter().flatten().collect::<Vec<_>>();
+            let count = placed.len();
+            placed.sort();
+            placed.dedup();
+            assert_eq!(placed.len(), count, "Size {}", symbol.size);
+            assert_eq!(
+                count,
+                (symbol.data_codewords + symbol.ecc_codewords) * 8,
+                "Size {}",
+                symbol.size
+            );
+        }
+    }
+
+    #[test]
+    fn barcode_svgs() {
+        let options = BarcodeOptions::default();
+        for (format, value) in [
+            (BarcodeFormat::Code128, "BATCH-001"),
+            (BarcodeFormat::Gs1_128, "(01)09501101530003(10)AB12"),
+            (BarcodeFormat::DataMatrix, "BATCH-001"),
+            (BarcodeFormat::Gs1DataMatrix, "(01)09501101530003(10)AB12"),
+            (BarcodeFormat::Qr, "https://msupply.foundation"),
+        ] {
+            let svg = barcode_svg(format, value, &options).unwrap();
+            assert!(svg.starts_with("<svg"));
+            assert!(svg.contains("<rect x="));
+        }
+        assert_eq!(
+            BarcodeFormat::parse("GS1-128"),
+            Some(BarcodeFormat::Gs1_128)
+        );
+        assert_eq!(BarcodeFormat::parse("ean13"), None);
+    }
+}
@@ -0,0 +1,586 @@
+use super::{
+    barcodes::escape_xml,
+    locale::{format_number, LocaleFormat},
+};
+
+/// Chart types that can be rendered in reports
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ChartType {
+    Line,
+    Bar,
+    Pie,
+}
+
+impl ChartType {
+    pub fn parse(chart_type: &str) -> Option<ChartType> {
+        let chart_type = match chart_type.to_lowercase().as_str() {
+            "line" => ChartType::Line,
+            "bar" => ChartType::Bar,
+            "pie" => ChartType::Pie,
+            _ => return None,
+        };
+        Some(chart_type)
+    }
+}
+
+/// Colours used for series (or pie slices) without an explicit colour
+const DEFAULT_COLORS: &[&str] = &[
+    "#3e7bfa", "#e95c30", "#33a474", "#f5a623", "#8e44ad", "#16a5b8", "#d0417e", "#7f8c8d",
+    "#b8860b", "#2c3e50",
+];
+
+const FONT_SIZE: f64 = 11.0;
+const TITLE_FONT_SIZE: f64 = 14.0;
+const PADDING: f64 = 10.0;
+const LINE_HEIGHT: f64 = 16.0;
+const LEGEND_SYMBOL_SIZE: f64 = 10.0;
+
+pub struct ChartSeries {
+    pub name: String,
+    /// One value per label, missing values are left out of the chart
+    pub values: Vec<Option<f64>>,
+}
+
+pub struct ChartData {
+    /// Labels of the x axis or of the pie slices
+    pub labels: Vec<String>,
+    pub series: Vec<ChartSeries>,
+}
+
+pub struct ChartOptions {
+    /// Chart width in px
+    pub width: f64,
+    /// Chart height in px
+    pub height: f64,
+    pub title: Option<String>,
+    pub x_label: Option<String>,
+    pub y_label: Option<String>,
+    /// Colours of the series (or pie slices), default colours are used for the remaining ones
+    pub colors: Vec<String>,
+    pub show_legend: bool,
+}
+
+impl Default for ChartOptions {
+    fn default() -> Self {
+        ChartOptions {
+            width: 600.0,
+            height: 300.0,
+            title: None,
+            x_label: None,
+            y_label: None,
+            colors: Vec::new(),
+            show_legend: true,
+        }
+    }
+}
+
+impl ChartOptions {
+    fn color(&self, index: usize) -> String {
+        match self.colors.get(index) {
+            Some(color) => escape_xml(color),
+            None => DEFAULT_COLORS[index % DEFAULT_COLORS.len()].to_string(),
+        }
+    }
+}
+
+/// Renders a chart as inline SVG.
+/// Pie charts only use the first series, one slice per label.
+pub fn chart_svg(
+    chart_type: ChartType,
+    data: &ChartData,
+    options: &ChartOptions,
+    format: &LocaleFormat,
+) -> Result<String, String> {
+    if data.series.is_empty() {
+        return Err("At least one data series is required".to_string());
+    }
+    if let Some(series) = data
+        .series
+        .iter()
+        .find(|series| series.values.len() != data.labels.len())
+    {
+        return Err(format!(
+            "Series `{}` has {} values but there are {} labels",
+            series.name,
+            series.values.len(),
+            data.labels.len()
+        ));
+    }
+    if options.width <= 0.0 || options.height <= 0.0 {
+        return Err("Chart width and height must be positive".to_string());
+    }
+
+    let mut content = String::new();
+    if let Some(title) = &options.title {
+        content.push_str(&format!(
+            r#"<text x="{:.1}" y="{:.1}" font-size="{}" font-weight="bold" text-anchor="middle">{}</text>"#,
+            options.width / 2.0,
+            PADDING + TITLE_FONT_SIZE,
+            TITLE_FONT_SIZE,
+            escape_xml(title)
+        ));
+    }
+    match chart_type {
+        ChartType::Line | ChartType::Bar => {
+            content.push_str(&axis_chart(chart_type, data, options, format))
+        }
+        ChartType::Pie => content.push_str(&pie_chart(data, options, format)),
+    }
+    Ok(format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" font-family="sans-serif" font-size="{f}"><rect width="{w}" height="{h}" fill="white"/>{content}</svg>"#,
+        w = options.width,
+        h = options.height,
+        f = FONT_SIZE,
+        content = content
+    ))
+}
+
+/// Rough text width estimate, SVG text can't be measured without a font renderer
+fn text_width(text: &str) -> f64 {
+    text.chars().count() as f64 * FONT_SIZE * 0.6
+}
+
+fn title_height(options: &ChartOptions) -> f64 {
+    match options.title {
+        Some(_) => TITLE_FONT_SIZE + PADDING,
+        None => 0.0,
+    }
+}
+
+/// Splits legend entries into rows that fit into the given width
+fn legend_rows(names: &[String], width: f64) -> Vec<Vec<usize>> {
+    let mut rows: Vec<Vec<usize>> = Vec::new();
+    let mut row_width = 0.0;
+    for (index, name) in names.iter().enumerate() {
+        let item_width = legend_item_width(name);
+        match rows.last_mut() {
+            Some(row) if row_width + item_width <= width => row.push(index),
+            _ => {
+                rows.push(vec![index]);
+                row_width = 0.0;
+            }
+        }
+        row_width += item_width;
+    }
+    rows
+}
+
+fn legend_item_width(name: &str) -> f64 {
+    LEGEND_SYMBOL_SIZE + 4.0 + text_width(name) + 2.0 * PADDING
+}
+
+fn legend_item(x: f64, y: f64, color: &str, name: &str) -> String {
+    format!(
+        r#"<rect x="{:.1}" y="{:.1}" width="{s}" height="{s}" fill="{}"/><text x="{:.1}" y="{:.1}">{}</text>"#,
+        x,
+        y - LEGEND_SYMBOL_SIZE + 1.0,
+        color,
+        x + LEGEND_SYMBOL_SIZE + 4.0,
+        y,
+        escape_xml(name),
+        s = LEGEND_SYMBOL_SIZE
+    )
+}
+
+/// Step between axis ticks rounded to 1, 2, 2.5 or 5 times a power of ten
+fn nice_step(range: f64, max_ticks: usize) -> f64 {
+    let raw = range / max_ticks as f64;
+    let magnitude = 10f64.powf(raw.log10().floor());
+    let nice = match raw / magnitude {
+        residual if residual <= 1.0 => 1.0,
+        residual if residual <= 2.0 => 2.0,
+        residual if residual <= 2.5 => 2.5,
+        residual if residual <= 5.0 => 5.0,
+        _ => 10.0,
+    };
+    nice * magnitude
+}
+
+/// Returns the axis range and tick values, the range always includes 0
+fn axis_ticks(values: impl Iterator<Item = f64>) -> (f64, f64, Vec<f64>) {
+    let (min, max) = values.fold((0.0f64, 0.0f64), |(min, max), value| {
+        (min.min(value), max.max(value))
+    });
+    let max = if min == max { min + 1.0 } else { max };
+    let step = nice_step(max - min, 5);
+    let axis_min = (min / step).floor() * step;
+    let axis_max = (max / step).ceil() * step;
+    let count = ((axis_max - axis_min) / step).round() as usize;
+    let ticks = (0..=count)
+        .map(|index| axis_min + index as f64 * step)
+        .collect();
+    (axis_min, axis_max, ticks)
+}
+
+/// Number of decimals needed to show the ticks of the given step
+fn tick_decimals(step: f64) -> usize {
+    (0..6)
+        .find(|decimals| {
+            let scaled = step * 10f64.powi(*decimals as i32);
+            (scaled - scaled.round()).abs() < 1e-6
+        })
+        .unwrap_or(6)
+}
+
+fn axis_chart(
+    chart_type: ChartType,
+    data: &ChartData,
+    options: &ChartOptions,
+    format: &LocaleFormat,
+) -> String {
+    let (axis_min, axis_max, ticks) = axis_ticks(
+        data.series
+            .iter()
+            .flat_map(|series| series.values.iter().flatten().copied()),
+    );
+    let step = ticks.get(1).map(|tick| tick - ticks[0]).unwrap_or(1.0);
+    let decimals = tick_decimals(step);
+    let tick_labels = ticks
+        .iter()
+        .map(|tick| format_number(*tick, decimals, format))
+        .collect::<Vec<String>>();
+
+    let names = data
+        .series
+        .iter()
+        .map(|series| series.name.clone())
+        .collect::<Vec<String>>();
+    let legend = if options.show_legend {
+        legend_rows(&names, options.width - 2.0 * PADDING)
+    } else {
+        Vec::new()
+    };
+
+    let y_label_width = match options.y_label {
+        Some(_) => LINE_HEIGHT,
+        None => 0.0,
+    };
+    let tick_label_width = tick_labels
+        .iter()
+        .map(|label| text_width(label))
+        .fold(0.0, f64::max);
+    let left = PADDING + y_label_width + tick_label_width + 4.0;
+    let right = options.width - PADDING;
+    let top = PADDING + title_height(options);
+    let x_label_height = match options.x_label {
+        Some(_) => LINE_HEIGHT,
+        None => 0.0,
+    };
+    let bottom =
+        options.height - PADDING - LINE_HEIGHT - x_label_height - legend.len() as f64 * LINE_HEIGHT;
+    let plot_width = (right - left).max(1.0);
+    let plot_height = (bottom - top).max(1.0);
+    let y = |value: f64| top + plot_height * (axis_max - value) / (axis_max - axis_min);
+
+    let mut content = String::new();
+    // grid lines and y axis labels
+    for (tick, label) in ticks.iter().zip(tick_labels.iter()) {
+        content.push_str(&format!(
+            r##"<line x1="{:.1}" y1="{y:.1}" x2="{:.1}" y2="{y:.1}" stroke="#e0e0e0"/><text x="{:.1}" y="{:.1}" text-anchor="end">{}</text>"##,
+            left,
+            right,
+            left - 4.0,
+            y(*tick) + FONT_SIZE / 3.0,
+            label,
+            y = y(*tick)
+        ));
+    }
+    content.push_str(&format!(
+        r##"<line x1="{l:.1}" y1="{:.1}" x2="{l:.1}" y2="{:.1}" stroke="#333"/><line x1="{l:.1}" y1="{z:.1}" x2="{:.1}" y2="{z:.1}" stroke="#333"/>"##,
+        top,
+        bottom,
+        right,
+        l = left,
+        z = y(0.0)
+    ));
+
+    // x axis labels, skip labels if they don't fit
+    let count = data.labels.len();
+    let band = plot_width / count.max(1) as f64;
+    let center = |index: usize| left + band * (index as f64 + 0.5);
+    let label_width = data
+        .labels
+        .iter()
+        .map(|label| text_width(label) + 4.0)
+        .fold(0.0, f64::max);
+    let label_step = (label_width / band).ceil().max(1.0) as usize;
+    for (index, label) in data.labels.iter().enumerate().step_by(label_step) {
+        content.push_str(&format!(
+            r#"<text x="{:.1}" y="{:.1}" text-anchor="middle">{}</text>"#,
+            center(index),
+            bottom + LINE_HEIGHT - 2.0,
+            escape_xml(label)
+        ));
+    }
+
+    // axis titles
+    if let Some(x_label) = &options.x_label {
+        content.push_str(&format!(
+            r#"<text x="{:.1}" y="{:.1}" text-anchor="middle">{}</text>"#,
+            left + plot_width / 2.0,
+            bottom + LINE_HEIGHT + x_label_height - 2.0,
+            escape_xml(x_label)
+        ));
+    }
+    if let Some(y_label) = &options.y_label {
+        let x = PADDING + FONT_SIZE;
+        let y = top + plot_height / 2.0;
+        content.push_str(&format!(
+            r#"<text x="{x:.1}" y="{y:.1}" text-anchor="middle" transform="rotate(-90 {x:.1} {y:.1})">{}</text>"#,
+            escape_xml(y_label),
+            x = x,
+            y = y
+        ));
+    }
+
+    // data
+    let series_count = data.series.len();
+    for (series_index, series) in data.series.iter().enumerate() {
+        let color = options.color(series_index);
+        match chart_type {
+            ChartType::Bar => {
+                let group_width = band * 0.8;
+                let bar_width = group_width / series_count as f64;
+                for (index, value) in series.values.iter().enumerate() {
+                    let value = match value {
+                        Some(value) => *value,
+                        None => continue,
+                    };
+                    let x = center(index) - group_width / 2.0 + series_index as f64 * bar_width;
+                    content.push_str(&format!(
+                        r#"<rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" fill="{}"/>"#,
+                        x,
+                        y(value.max(0.0)),
+                        bar_width,
+                        (y(0.0) - y(value)).abs(),
+                        color
+                    ));
+                }
+            }
+            _ => {
+                // missing values split the line into segments
+                let mut segments: Vec<Vec<(f64, f64)>> = vec![Vec::new()];
+                for (index, value) in series.values.iter().enumerate() {
+                    match value {
+                        Some(value) => segments
+                            .last_mut()
+                            .unwrap()
+                            .push((center(index), y(*value))),
+                        None => segments.push(Vec::new()),
+                    }
+                }
+                for segment in segments.iter().filter(|segment| segment.len() > 1) {
+                    let points = segment
+                        .iter()
+                        .map(|(x, y)| format!("{:.1},{:.1}", x, y))
+                        .collect::<Vec<String>>()
+                        .join(" ");
+                    content.push_str(&format!(
+                        r#"<polyline points="{}" fill="none" stroke="{}" stroke-width="2"/>"#,
+                        points, color
+                    ));
+                }
+                if band >= 6.0 {
+                    for (x, y) in segments.iter().flatten() {
+                        content.push_str(&format!(
+                            r#"<circle cx="{:.1}" cy="{:.1}" r="2.5" fill="{}"/>"#,
+                            x, y, color
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    // legend
+    let legend_top = options.height - PADDING - legend.len() as f64 * LINE_HEIGHT;
+    for (row_index, row) in legend.iter().enumerate() {
+        let row_width: f64 = row
+            .iter()
+            .map(|index| legend_item_width(&names[*index]))
+            .sum();
+        let mut x = (options.width - row_width) / 2.0 + PADDING;
+        let y = legend_top + (row_index + 1) as f64 * LINE_HEIGHT - 3.0;
+        for index in row {
+            content.push_str(&legend_item(x, y, &options.color(*index), &names[*index]));
+            x += legend_item_width(&names[*index]);
+        }
+    }
+    content
+}
+
+fn pie_chart(data: &ChartData, options: &ChartOptions, format: &LocaleFormat) -> String {
+    let series = &data.series[0];
+    // slices with a positive value
+    let slices = series
+        .values
+        .iter()
+        .enumerate()
+        .filter_map(|(index, value)| match value {
+            Some(value) if *value > 0.0 => Some((index, *value)),
+            _ => None,
+        })
+        .collect::<Vec<(usize, f64)>>();
+    let total: f64 = slices.iter().map(|(_, value)| value).sum();
+
+    let legend_names = slices
+        .iter()
+        .map(|(index, value)| {
+            format!(
+                "{} ({}%)",
+                data.labels[*index],
+                format_number(value / total * 100.0, 1, format)
+            )
+        })
+        .collect::<Vec<String>>();
+    let legend_width = if options.show_legend {
+        legend_names
+            .iter()
+            .map(|name| legend_item_width(name))
+            .fold(0.0, f64::max)
+    } else {
+        0.0
+    };
+
+    let top = PADDING + title_height(options);
+    let plot_width = options.width - legend_width - 2.0 * PADDING;
+    let plot_height = options.height - top - PADDING;
+    let radius = (plot_width.min(plot_height) / 2.0).max(1.0);
+    let cx = PADDING + plot_width / 2.0;
+    let cy = top + plot_height / 2.0;
+
+    let mut content = String::new();
+    if slices.is_empty() {
+        content.push_str(&format!(
+            r##"<circle cx="{:.1}" cy="{:.1}" r="{:.1}" fill="none" stroke="#e0e0e0"/>"##,
+            cx, cy, radius
+        ));
+    }
+    // start at 12 o'clock and go clockwise
+    let mut angle = -std::f64::consts::FRAC_PI_2;
+    for (slice_index, (index, value)) in slices.iter().enumerate() {
+        let color = options.color(*index);
+        let fraction = value / total;
+        let end = angle + fraction * 2.0 * std::f64::consts::PI;
+        if fraction > 0.9999 {
+            // an arc can't be a full circle
+            content.push_str(&format!(
+                r#"<circle cx="{:.1}" cy="{:.1}" r="{:.1}" fill="{}"/>"#,
+                cx, cy, radius, color
+            ));
+        } else {
+            content.push_str(&format!(
+            r#"<path d="M{:.1},{:.1} L{:.1},{:.1} A{r:.1},{r:.1} 0 {} 1 {:.1},{:.1} Z" fill="{}" stroke="white"/>"#,
+            cx,
+            cy,
+            cx + radius * angle.cos(),
+            cy + radius * angle.sin(),
+            if fraction > 0.5 { 1 } else { 0 },
+            cx + radius * end.cos(),
+            cy + radius * end.sin(),
+            color,
+            r = radius
+        ));
+        }
+        angle = end;
+        if options.show_legend {
+            let y = cy - slices.len() as f64 * LINE_HEIGHT / 2.0
+                + (slice_index + 1) as f64 * LINE_HEIGHT
+                - 3.0;
+            let x = options.width - PADDING - legend_width + PADDING;
+            content.push_str(&legend_item(x, y, &color, &legend_names[slice_index]));
+        }
+    }
+    content
+}
+
+#[cfg(test)]
+mod test {
+    use crate::report::locale::locale_format;
+
+    use super::*;
+
+    fn data() -> ChartData {
+        ChartData {
+            labels: vec![
+                "Jan".to_string(),
+                "Feb".to_string(),
+                "Mar".to_string(),
+                "Apr".to_string(),
+            ],
+            series: vec![
+                ChartSeries {
+                    name: "Consumption".to_string(),
+                    values: vec![Some(10.0), Some(25.0), None, Some(40.0)],
+                },
+                ChartSeries {
+                    name: "AMC <3 months>".to_string(),
+                    values: vec![Some(10.0), Some(17.5), Some(17.5), Some(25.0)],
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn chart_axis_ticks() {
+        assert_eq!(nice_step(40.0, 5), 10.0);
+        assert_eq!(nice_step(1.0, 5), 0.2);
+        assert_eq!(nice_step(12000.0, 5), 2500.0);
+        assert_eq!(tick_decimals(0.2), 1);
+        assert_eq!(tick_decimals(2500.0), 0);
+
+        let (min, max, ticks) = axis_ticks(vec![10.0, 25.0, 40.0].into_iter());
+        assert_eq!((min, max), (0.0, 40.0));
+        assert_eq!(ticks, vec![0.0, 10.0, 20.0, 30.0, 40.0]);
+        // negative values
+        let (min, max, _) = axis_ticks(vec![-3.0, 7.0].into_iter());
+        assert_eq!((min, max), (-4.0, 8.0));
+        // no values
+        let (min, max, _) = axis_ticks(Vec::new().into_iter());
+        assert_eq!((min, max), (0.0, 1.0));
+    }
+
+    #[test]
+    fn chart_svgs() {
+        let format = locale_format("en");
+        let options = ChartOptions {
+            title: Some("Consumption".to_string()),
+            y_label: Some("Units".to_string()),
+            colors: vec!["red".to_string()],
+            ..Default::default()
+        };
+
+        let svg = chart_svg(ChartType::Line, &data(), &options, &format).unwrap();
+        assert!(svg.starts_with("<svg"));
+        // the missing value splits the first series
+        assert_eq!(svg.matches("<polyline").count(), 2);
+        assert_eq!(svg.matches("<circle").count(), 7);
+        assert!(svg.contains(r#"stroke="red""#));
+        assert!(svg.contains(&format!(r#"stroke="{}""#, DEFAULT_COLORS[1])));
+        assert!(svg.contains("AMC &lt;3 months&gt;"));
+        assert!(svg.contains(">Units</text>"));
+
+        let svg = chart_svg(ChartType::Bar, &data(), &options, &format).unwrap();
+        assert_eq!(svg.matches(r#"fill="red"/>"#).count(), 3 + 1);
+
+        let mut pie_data = data();
+        pie_data.series.truncate(1);
+        let svg = chart_svg(ChartType::Pie, &pie_data, &options, &format).unwrap();
+        assert_eq!(svg.matches("<path").count(), 3);
+        assert!(svg.contains("Apr (53.3%)"));
+
+        // single slice
+        pie_data.series[0].values = vec![None, Some(5.0), None, None];
+        let svg = chart_svg(ChartType::Pie, &pie_data, &options, &format).unwrap();
+        assert!(svg.contains("Feb (100.0%)"));
+
+        // invalid data
+        pie_data.series[0].values.pop();
+        assert!(chart_svg(ChartType::Line, &pie_data, &options, &format).is_err());
+        pie_data.series.clear();
+        assert!(chart_svg(ChartType::Line, &pie_data, &options, &format).is_err());
+
+        assert_eq!(ChartType::parse("Bar"), Some(ChartType::Bar));
+        assert_eq!(ChartType::parse("scatter"), None);
+    }
+}
@@ -14,6 +14,34 @@ pub fn get_default_gql_query(query: DefaultQuery) -> GraphQlQuery {
             query: REQUISITION_QUERY.to_string(),
             variables: None,
         },
+        DefaultQuery::StockByLocation => GraphQlQuery {
+            query: STOCK_BY_LOCATION_QUERY.to_string(),
+            variables: None,
+        },
+        DefaultQuery::ItemLedger => GraphQlQuery {
+            query: ITEM_LEDGER_QUERY.to_string(),
+            variables: None,
+        },
+        DefaultQuery::ExpiringStock => GraphQlQuery {
+            query: EXPIRING_STOCK_QUERY.to_string(),
+            variables: None,
+        },
+        DefaultQuery::LocationMovementHistory => GraphQlQuery {
+            query: LOCATION_MOVEMENT_HISTORY_QUERY.to_string(),
+            variables: None,
+        },
+        DefaultQuery::RequisitionPeriodSummary => GraphQlQuery {
+            query: REQUISITION_PERIOD_SUMMARY_QUERY.to_string(),
+            variables: None,
+        },
+        DefaultQuery::HistoricalStock => GraphQlQuery {
+            query: HISTORICAL_STOCK_QUERY.to_string(),
+            variables: None,
+        },
+        DefaultQuery::DestructionCertificate => GraphQlQuery {
+            query: DESTRUCTION_CERTIFICATE_QUERY.to_string(),
+            variables: None,
+        },
     }
 }
 
@@ -339,3 +367,427 @@ const REQUISITION_QUERY: &str = r#"query RequisitionQuery($storeId: String, $dat
     }
   }
 }"#;
+
+const STOCK_BY_LOCATION_QUERY: &str = r#"query StockByLocationQuery($storeId: String, $locationId: String) {
+  stockLines(
+    storeId: $storeId
+    filter: { locationId: { equalTo: $locationId }, hasPacksInStore: true }
+    sort: { key: itemName }
+  ) {
+    ... on StockLineConnector {
+      totalCount
+      nodes {
+        id
+        batch
+        expiryDate
+        packSize
+        availableNumberOfPacks
+        totalNumberOfPacks
+        costPricePerPack
+        sellPricePerPack
+        onHold
+        locationId
+        location {
+          code
+          name
+          onHold
+        }
+        item {
+          code
+          name
+          unitName
+        }
+      }
+    }
+  }
+  store(id: $storeId) {
+    ... on StoreNode {
+      id
+      name(storeId: $storeId) {
+        address1
+        address2
+        chargeCode
+        code
+        comment
+        country
+        email
+        name
+        phone
+        website
+      }
+      code
+      storeName
+      logo
+    }
+    ... on NodeError {
+      __typename
+      error {
+        description
+      }
+    }
+  }
+}"#;
+
+const ITEM_LEDGER_QUERY: &str = r#"query ItemLedgerQuery($storeId: String, $dataId: String, $fromDatetime: DateTime, $toDatetime: DateTime) {
+  item: items(storeId: $storeId, filter: { id: { equalTo: $dataId } }) {
+    ... on ItemConnector {
+      nodes {
+        id
+        code
+        name
+        unitName
+      }
+    }
+  }
+  itemLedger(
+    storeId: $storeId
+    page: { first: 5000 }
+    filter: { itemId: { equalTo: $dataId }, datetime: { afterOrEqualTo: $fromDatetime, beforeOrEqualTo: $toDatetime } }
+  ) {
+    ... on ItemLedgerConnector {
+      totalCount
+      openingBalance
+      nodes {
+        datetime
+        invoiceType
+        invoiceNumber
+        name
+        batch
+        quantityIn
+        quantityOut
+        runningBalance
+      }
+    }
+  }
+  store(id: $storeId) {
+    ... on StoreNode {
+      id
+      name(storeId: $storeId) {
+        address1
+        address2
+        chargeCode
+        code
+        comment
+        country
+        email
+        name
+        phone
+        website
+      }
+      code
+      storeName
+      logo
+    }
+    ... on NodeError {
+      __typename
+      error {
+        description
+      }
+    }
+  }
+}"#;
+
+const EXPIRING_STOCK_QUERY: &str = r#"query ExpiringStockQuery($storeId: String, $expiryDate: NaiveDate) {
+  stockLines(
+    storeId: $storeId
+    filter: { expiryDate: { beforeOrEqualTo: $expiryDate }, hasPacksInStore: true }
+    sort: { key: expiryDate }
+  ) {
+    ... on StockLineConnector {
+      totalCount
+      nodes {
+        id
+        batch
+        expiryDate
+        packSize
+        totalNumberOfPacks
+        costPricePerPack
+        locationName
+        supplierName
+        item {
+          code
+          name
+          unitName
+        }
+      }
+    }
+  }
+  store(id: $storeId) {
+    ... on StoreNode {
+      id
+      name(storeId: $storeId) {
+        address1
+        address2
+        chargeCode
+        code
+        comment
+        country
+        email
+        name
+        phone
+        website
+      }
+      code
+      storeName
+      logo
+    }
+    ... on NodeError {
+      __typename
+      error {
+        description
+      }
+    }
+  }
+}"#;
+
+const LOCATION_MOVEMENT_HISTORY_QUERY: &str = r#"query LocationMovementHistoryQuery(
+  $storeId: String
+  $locationId: String
+  $fromDatetime: DateTime
+  $toDatetime: DateTime
+) {
+  locationMovements(
+    storeId: $storeId
+    filter: {
+      locationId: { equalTo: $locationId }
+      enterDatetime: { afterOrEqualTo: $fromDatetime, beforeOrEqualTo: $toDatetime }
+    }
+    sort: { key: enterDatetime }
+  ) {
+    ... on LocationMovementConnector {
+      totalCount
+      nodes {
+        id
+        enterDatetime
+        exitDatetime
+        location {
+          code
+          name
+        }
+        stockLine {
+          batch
+          expiryDate
+          packSize
+          totalNumberOfPacks
+          item {
+            code
+            name
+          }
+        }
+      }
+    }
+  }
+  store(id: $storeId) {
+    ... on StoreNode {
+      id
+      name(storeId: $storeId) {
+        address1
+        address2
+        chargeCode
+        code
+        comment
+        country
+        email
+        name
+        phone
+        website
+      }
+      code
+      storeName
+      logo
+    }
+    ... on NodeError {
+      __typename
+      error {
+        description
+      }
+    }
+  }
+}"#;
+
+const REQUISITION_PERIOD_SUMMARY_QUERY: &str = r#"query RequisitionPeriodSummaryQuery(
+  $storeId: String
+  $fromDatetime: DateTime
+  $toDatetime: DateTime
+  $type: RequisitionNodeType
+) {
+  requisitions(
+    storeId: $storeId
+    filter: {
+      createdDatetime: { afterOrEqualTo: $fromDatetime, beforeOrEqualTo: $toDatetime }
+      type: { equalTo: $type }
+    }
+    sort: { key: createdDatetime }
+  ) {
+    ... on RequisitionConnector {
+      totalCount
+      nodes {
+        id
+        requisitionNumber
+        type
+        status
+        createdDatetime
+        finalisedDatetime
+        otherPartyName
+        programName
+        period {
+          name
+          startDate
+          endDate
+        }
+        lines {
+          totalCount
+          nodes {
+            requestedQuantity
+            supplyQuantity
+          }
+        }
+      }
+    }
+  }
+  store(id: $storeId) {
+    ... on StoreNode {
+      id
+      name(storeId: $storeId) {
+        address1
+        address2
+        chargeCode
+        code
+        comment
+        country
+        email
+        name
+        phone
+        website
+      }
+      code
+      storeName
+      logo
+    }
+    ... on NodeError {
+      __typename
+      error {
+        description
+      }
+    }
+  }
+}"#;
+
+const HISTORICAL_STOCK_QUERY: &str = r#"query HistoricalStockQuery($storeId: String, $datetime: DateTime!, $groupBy: HistoricalStockGroupByInput, $valuationMethod: StockValuationMethodInput) {
+  historicalStock(
+    storeId: $storeId
+    datetime: $datetime
+    groupBy: $groupBy
+    valuationMethod: $valuationMethod
+  ) {
+    ... on HistoricalStockConnector {
+      totalCount
+      totalValue
+      nodes {
+        itemId
+        batch
+        expiryDate
+        quantity
+        value
+        item {
+          code
+          name
+          unitName
+        }
+        location {
+          code
+          name
+        }
+      }
+    }
+  }
+  store(id: $storeId) {
+    ... on StoreNode {
+      id
+      name(storeId: $storeId) {
+        address1
+        address2
+        chargeCode
+        code
+        comment
+        country
+        email
+        name
+        phone
+        website
+      }
+      code
+      storeName
+      logo
+    }
+    ... on NodeError {
+      __typename
+      error {
+        description
+      }
+    }
+  }
+}"#;
+
+const DESTRUCTION_CERTIFICATE_QUERY: &str = r#"query DestructionCertificateQuery($storeId: String, $dataId: String) {
+  invoice(storeId: $storeId, id: $dataId) {
+    ... on InvoiceNode {
+      id
+      invoiceNumber
+      type
+      comment
+      verifiedDatetime
+      user {
+        username
+      }
+      lines {
+        nodes {
+          id
+          itemCode
+          itemName
+          item {
+            unitName
+          }
+          batch
+          expiryDate
+          packSize
+          numberOfPacks
+          costPricePerPack
+          locationName
+        }
+      }
+    }
+    ... on NodeError {
+      __typename
+      error {
+        description
+      }
+    }
+  }
+  store(id: $storeId) {
+    ... on StoreNode {
+      id
+      name(storeId: $storeId) {
+        address1
+        address2
+        chargeCode
+        code
+        comment
+        country
+        email
+        name
+        phone
+        website
+      }
+      code
+      storeName
+      logo
+    }
+    ... on NodeError {
+      __typename
+      error {
+        description
+      }
+    }
+  }
+}"#;
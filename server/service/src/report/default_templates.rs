@@ -0,0 +1,350 @@
+use super::definition::{DefaultQuery, ReportOutputType, TeraTemplate};
+
+/// Returns the built-in template matching a default query.
+///
+/// Not all default queries have a built-in template, e.g. invoices and stocktakes are printed
+/// using custom report definitions.
+pub fn get_default_template(query: &DefaultQuery) -> Option<TeraTemplate> {
+    let template = match query {
+        DefaultQuery::Invoice | DefaultQuery::Stocktake | DefaultQuery::Requisition => return None,
+        DefaultQuery::StockByLocation => STOCK_BY_LOCATION_TEMPLATE,
+        DefaultQuery::ItemLedger => ITEM_LEDGER_TEMPLATE,
+        DefaultQuery::ExpiringStock => EXPIRING_STOCK_TEMPLATE,
+        DefaultQuery::LocationMovementHistory => LOCATION_MOVEMENT_HISTORY_TEMPLATE,
+        DefaultQuery::RequisitionPeriodSummary => REQUISITION_PERIOD_SUMMARY_TEMPLATE,
+        DefaultQuery::HistoricalStock => HISTORICAL_STOCK_TEMPLATE,
+        DefaultQuery::DestructionCertificate => DESTRUCTION_CERTIFICATE_TEMPLATE,
+    };
+    Some(TeraTemplate {
+        output: ReportOutputType::Html,
+        template: template.to_string(),
+    })
+}
+
+const STOCK_BY_LOCATION_TEMPLATE: &str = concat!(
+    include_str!("default_templates/style.html"),
+    include_str!("default_templates/stock_by_location.html")
+);
+
+const ITEM_LEDGER_TEMPLATE: &str = concat!(
+    include_str!("default_templates/style.html"),
+    include_str!("default_templates/item_ledger.html")
+);
+
+const EXPIRING_STOCK_TEMPLATE: &str = concat!(
+    include_str!("default_templates/style.html"),
+    include_str!("default_templates/expiring_stock.html")
+);
+
+const LOCATION_MOVEMENT_HISTORY_TEMPLATE: &str = concat!(
+    include_str!("default_templates/style.html"),
+    include_str!("default_templates/location_movement_history.html")
+);
+
+const REQUISITION_PERIOD_SUMMARY_TEMPLATE: &str = concat!(
+    include_str!("default_templates/style.html"),
+    include_str!("default_templates/requisition_period_summary.html")
+);
+
+const HISTORICAL_STOCK_TEMPLATE: &str = concat!(
+    include_str!("default_templates/style.html"),
+    include_str!("default_templates/historical_stock.html")
+);
+
+const DESTRUCTION_CERTIFICATE_TEMPLATE: &str = concat!(
+    include_str!("default_templates/style.html"),
+    include_str!("default_templates/destruction_certificate.html")
+);
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use serde_json::json;
+
+    use crate::report::{
+        definition::{
+            DefaultQuery, ReportDefinition, ReportDefinitionEntry, ReportDefinitionIndex,
+        },
+        locale::ReportLocale,
+        report_service::{generate_report, resolve_loaded_report_definition},
+    };
+
+    fn render(
+        query: DefaultQuery,
+        data: serde_json::Value,
+        arguments: serde_json::Value,
+    ) -> String {
+        let definition = ReportDefinition {
+            index: ReportDefinitionIndex {
+                template: Some("template".to_string()),
+                header: None,
+                footer: None,
+                query: Some("query".to_string()),
+            },
+            entries: HashMap::from([
+                (
+                    "template".to_string(),
+                    ReportDefinitionEntry::DefaultTemplate(query.clone()),
+                ),
+                (
+                    "query".to_string(),
+                    ReportDefinitionEntry::DefaultQuery(query),
+                ),
+            ]),
+        };
+        let report = resolve_loaded_report_definition(
+            "report".to_string(),
+            definition,
+            ReportLocale::default(),
+        )
+        .unwrap();
+        generate_report(&report, data, Some(arguments))
+            .unwrap()
+            .document
+    }
+
+    fn store() -> serde_json::Value {
+        json!({ "id": "store_a", "storeName": "Store A" })
+    }
+
+    #[test]
+    fn item_ledger_template() {
+        let node = |datetime: &str, quantity_in: f64, quantity_out: f64, running_balance: f64| {
+            json!({
+                "datetime": datetime,
+                "invoiceType": if quantity_in > 0.0 { "INBOUND_SHIPMENT" } else { "OUTBOUND_SHIPMENT" },
+                "invoiceNumber": 1,
+                "name": "Name A",
+                "batch": null,
+                "quantityIn": quantity_in,
+                "quantityOut": quantity_out,
+                "runningBalance": running_balance
+            })
+        };
+        let data = json!({
+            "item": { "nodes": [{ "id": "item_a", "code": "A", "name": "Item A", "unitName": null }] },
+            "itemLedger": {
+                "totalCount": 2,
+                "openingBalance": 100,
+                "nodes": [
+                    node("2022-02-01T10:00:00+00:00", 0.0, 30.0, 70.0),
+                    node("2022-03-01T10:00:00+00:00", 5.0, 0.0, 75.0),
+                ]
+            },
+            "store": store()
+        });
+
+        let document = render(
+            DefaultQuery::ItemLedger,
+            data.clone(),
+            json!({ "storeId": "store_a", "dataId": "item_a", "fromDatetime": "2022-01-15T00:00:00Z" }),
+        );
+        let document = document.split_whitespace().collect::<String>();
+        assert!(document.contains("Openingbalance</td><tdclass=\"number\">100</td>"));
+        assert!(document.contains("Outboundshipment#1"));
+        assert!(document.contains("<tdclass=\"number\">30</td><tdclass=\"number\">70</td>"));
+        assert!(document.contains("Closingbalance</td><tdclass=\"number\">75</td>"));
+
+        // without movements the closing balance is the opening balance
+        let mut empty = data;
+        empty["itemLedger"]["nodes"] = json!([]);
+        let document = render(
+            DefaultQuery::ItemLedger,
+            empty,
+            json!({ "storeId": "store_a", "dataId": "item_a" }),
+        );
+        let document = document.split_whitespace().collect::<String>();
+        assert!(document.contains("Closingbalance</td><tdclass=\"number\">100</td>"));
+    }
+
+    #[test]
+    fn stock_templates() {
+        let stock_line = json!({
+            "id": "line_a",
+            "batch": "B1",
+            "expiryDate": "2022-05-01",
+            "packSize": 10,
+            "availableNumberOfPacks": 2.0,
+            "totalNumberOfPacks": 2.0,
+            "costPricePerPack": 1.5,
+            "sellPricePerPack": 2.0,
+            "onHold": false,
+            "locationId": "location_a",
+            "locationName": "Shelf 1",
+            "supplierName": null,
+            "location": { "code": "S1", "name": "Shelf 1", "onHold": false },
+            "item": { "code": "A", "name": "Item A", "unitName": null }
+        });
+        let mut no_location = stock_line.clone();
+        no_location["locationId"] = json!(null);
+        no_location["location"] = json!(null);
+        no_location["expiryDate"] = json!(null);
+        let data = json!({
+            "stockLines": { "totalCount": 2, "nodes": [stock_line, no_location] },
+            "store": store()
+        });
+
+        let document = render(
+            DefaultQuery::StockByLocation,
+            data.clone(),
+            json!({ "storeId": "store_a" }),
+        );
+        assert!(document.contains("S1 - Shelf 1"));
+        assert!(document.contains("No location"));
+
+        let document = render(
+            DefaultQuery::ExpiringStock,
+            data,
+            json!({ "storeId": "store_a", "expiryDate": "2022-06-01" }),
+        );
+        assert!(document.contains("Expiring on or before"));
+        // stock without expiry date is not listed
+        assert_eq!(document.matches("Item A").count(), 1);
+    }
+
+    #[test]
+    fn historical_stock_template() {
+        let data = json!({
+            "historicalStock": {
+                "totalCount": 2,
+                "totalValue": 30.0,
+                "nodes": [
+                    {
+                        "itemId": "item_b",
+                        "batch": null,
+                        "expiryDate": null,
+                        "quantity": 5.0,
+                        "value": 10.0,
+                        "item": { "code": "B", "name": "Item B", "unitName": null },
+                        "location": null
+                    },
+                    {
+                        "itemId": "item_a",
+                        "batch": "B1",
+                        "expiryDate": "2022-05-01",
+                        "quantity": 10.0,
+                        "value": 20.0,
+                        "item": { "code": "A", "name": "Item A", "unitName": null },
+                        "location": { "code": "S1", "name": "Shelf 1" }
+                    }
+                ]
+            },
+            "store": store()
+        });
+
+        let document = render(
+            DefaultQuery::HistoricalStock,
+            data,
+            json!({ "storeId": "store_a", "datetime": "2022-06-01T00:00:00Z", "valuationMethod": "fifo" }),
+        );
+        assert!(document.contains("FIFO"));
+        assert!(document.contains("S1 - Shelf 1"));
+        // sorted by item name
+        assert!(document.find("Item A").unwrap() < document.find("Item B").unwrap());
+    }
+
+    #[test]
+    fn location_movement_and_requisition_templates() {
+        let data = json!({
+            "locationMovements": {
+                "totalCount": 1,
+                "nodes": [{
+                    "id": "movement_a",
+                    "enterDatetime": "2022-01-01T10:00:00+00:00",
+                    "exitDatetime": null,
+                    "location": { "code": "S1", "name": "Shelf 1" },
+                    "stockLine": {
+                        "batch": "B1",
+                        "expiryDate": null,
+                        "packSize": 1,
+                        "totalNumberOfPacks": 1.0,
+                        "item": { "code": "A", "name": "Item A" }
+                    }
+                }]
+            },
+            "store": store()
+        });
+        let document = render(
+            DefaultQuery::LocationMovementHistory,
+            data,
+            json!({ "storeId": "store_a" }),
+        );
+        assert!(document.contains("Item A"));
+
+        let data = json!({
+            "requisitions": {
+                "totalCount": 1,
+                "nodes": [{
+                    "id": "requisition_a",
+                    "requisitionNumber": 3,
+                    "type": "REQUEST",
+                    "status": "SENT",
+                    "createdDatetime": "2022-01-01T10:00:00+00:00",
+                    "finalisedDatetime": null,
+                    "otherPartyName": "Supplier",
+                    "programName": null,
+                    "period": null,
+                    "lines": {
+                        "totalCount": 2,
+                        "nodes": [
+                            { "requestedQuantity": 10, "supplyQuantity": 5 },
+                            { "requestedQuantity": 20, "supplyQuantity": 0 }
+                        ]
+                    }
+                }]
+            },
+            "store": store()
+        });
+        let document = render(
+            DefaultQuery::RequisitionPeriodSummary,
+            data,
+            json!({ "storeId": "store_a" }),
+        );
+        let document = document.split_whitespace().collect::<String>();
+        assert!(document.contains("<tdclass=\"number\">30</td><tdclass=\"number\">5</td>"));
+    }
+
+    #[test]
+    fn destruction_certificate_template() {
+        let line = |batch: &str, number_of_packs: f64| {
+            json!({
+                "id": batch,
+                "itemCode": "A",
+                "itemName": "Item A",
+                "item": { "unitName": null },
+                "batch": batch,
+                "expiryDate": "2022-05-01",
+                "packSize": 10,
+                "numberOfPacks": number_of_packs,
+                "costPricePerPack": 2.0,
+                "locationName": "Quarantine"
+            })
+        };
+        let data = json!({
+            "invoice": {
+                "id": "invoice_a",
+                "invoiceNumber": 3,
+                "type": "INVENTORY_REDUCTION",
+                "comment": "Incinerated",
+                "verifiedDatetime": "2022-06-01T10:00:00+00:00",
+                "user": { "username": "user_a" },
+                "lines": { "nodes": [line("B1", 2.0), line("B2", 3.0)] }
+            },
+            "store": store()
+        });
+
+        let document = render(
+            DefaultQuery::DestructionCertificate,
+            data,
+            json!({ "storeId": "store_a", "dataId": "invoice_a" }),
+        );
+        assert!(document.contains("Certificate of destruction"));
+        assert!(document.contains("Incinerated"));
+        assert!(document.contains("Witnessed by"));
+        let document = document.split_whitespace().collect::<String>();
+        assert!(document.contains("Inventoryreduction#3"));
+        assert!(document.contains("<tdclass=\"number\">20</td>"));
+        assert!(document.contains("<tdclass=\"number\">30</td>"));
+    }
+}
@@ -11,23 +11,75 @@ pub struct GraphQlQuery {
 }
 
 impl GraphQlQuery {
-    /// Create query variables for the query
-    pub fn query_variables(&self, store_id: &str, data_id: &str) -> Value {
-        let mut variables = match &self.variables {
-            Some(variables) => {
-                if matches!(variables, Value::Object(_)) {
-                    variables.clone()
-                } else {
-                    // ensure variables are an object
-                    serde_json::json!({})
-                }
+    /// Create query variables for the query.
+    ///
+    /// Report arguments are merged on top of the predefined query variables, `storeId` and
+    /// `dataId` always take precedence over any argument with the same name.
+    pub fn query_variables(
+        &self,
+        store_id: &str,
+        data_id: Option<&str>,
+        arguments: Option<&Value>,
+    ) -> Value {
+        merge_query_variables(&self.variables, store_id, data_id, arguments)
+    }
+}
+
+/// Read-only SQL query against a whitelisted set of tables and views.
+///
+/// Named parameters, e.g. `:storeId`, are taken from the query variables (see
+/// `GraphQlQuery::query_variables`); missing parameters are NULL.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct SqlQuery {
+    /// Query used if the server runs on sqlite
+    pub sqlite: Option<String>,
+    /// Query used if the server runs on Postgres
+    pub postgres: Option<String>,
+    /// Names of the result columns, inferred from the select list if not set
+    pub columns: Option<Vec<String>>,
+    /// Default query parameters
+    pub variables: Option<Value>,
+}
+
+impl SqlQuery {
+    /// Create query variables for the query, same as `GraphQlQuery::query_variables`
+    pub fn query_variables(
+        &self,
+        store_id: &str,
+        data_id: Option<&str>,
+        arguments: Option<&Value>,
+    ) -> Value {
+        merge_query_variables(&self.variables, store_id, data_id, arguments)
+    }
+}
+
+fn merge_query_variables(
+    variables: &Option<Value>,
+    store_id: &str,
+    data_id: Option<&str>,
+    arguments: Option<&Value>,
+) -> Value {
+    let mut variables = match variables {
+        Some(variables) => {
+            if matches!(variables, Value::Object(_)) {
+                variables.clone()
+            } else {
+                // ensure variables are an object
+                serde_json::json!({})
             }
-            None => serde_json::json!({}),
-        };
-        variables["storeId"] = Value::String(store_id.to_string());
+        }
+        None => serde_json::json!({}),
+    };
+    if let Some(Value::Object(arguments)) = arguments {
+        for (key, value) in arguments {
+            variables[key] = value.clone();
+        }
+    }
+    variables["storeId"] = Value::String(store_id.to_string());
+    if let Some(data_id) = data_id {
         variables["dataId"] = Value::String(data_id.to_string());
-        variables
     }
+    variables
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
@@ -35,6 +87,23 @@ pub enum DefaultQuery {
     Invoice,
     Stocktake,
     Requisition,
+    /// Stock on hand grouped by location, optional arguments: `locationId`
+    StockByLocation,
+    /// Stock movements of the item `dataId`, optional arguments: `fromDatetime`, `toDatetime`
+    ItemLedger,
+    /// Stock expiring on or before a date, optional arguments: `expiryDate`
+    ExpiringStock,
+    /// Stock movements in and out of locations, optional arguments: `locationId`,
+    /// `fromDatetime`, `toDatetime`
+    LocationMovementHistory,
+    /// Requisitions created in a period, optional arguments: `fromDatetime`, `toDatetime`,
+    /// `type`
+    RequisitionPeriodSummary,
+    /// Stock on hand and its value at `datetime`, optional arguments: `groupBy`,
+    /// `valuationMethod`
+    HistoricalStock,
+    /// Expired stock written off by the inventory reduction `dataId`
+    DestructionCertificate,
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
@@ -56,6 +125,145 @@ pub struct TeraTemplate {
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
 pub enum ReportOutputType {
     Html,
+    /// Raw printer commands, e.g. ZPL or ESC/POS for label printers.
+    /// Non printable bytes can be written as `\xHH` escape sequences, e.g. `\x1B` for ESC.
+    RawText,
+}
+
+/// Translated strings of a report for a single language
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct ReportTranslations {
+    /// Language code, e.g. "fr"
+    pub language: String,
+    /// Map of translation keys to translated strings
+    pub strings: HashMap<String, String>,
+}
+
+/// Paper size of a printed page
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub enum PaperSize {
+    A3,
+    A4,
+    A5,
+    Letter,
+    Legal,
+    /// Custom paper size in mm (in portrait orientation)
+    Custom {
+        width: f64,
+        height: f64,
+    },
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub enum PageOrientation {
+    Portrait,
+    Landscape,
+}
+
+/// Page margins in mm
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct PageMargins {
+    pub top: f64,
+    pub bottom: f64,
+    pub left: f64,
+    pub right: f64,
+}
+
+/// Page setup of a printed report.
+///
+/// Every PDF backend must honour all fields of the page setup; HTML output uses the equivalent
+/// CSS `@page` rule.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+pub struct ReportPageSetup {
+    /// Defaults to A4
+    pub paper_size: Option<PaperSize>,
+    /// Defaults to portrait
+    pub orientation: Option<PageOrientation>,
+    /// Defaults to 10mm on all sides
+    pub margins: Option<PageMargins>,
+    /// Scale of the page content between 0.1 and 2, defaults to 1
+    pub scale: Option<f64>,
+    /// HTML printed in the top margin of every page.
+    /// `{page}` and `{pages}` are replaced with the page number and the total number of pages.
+    pub page_header: Option<String>,
+    /// HTML printed in the bottom margin of every page, supports the same placeholders as the
+    /// page header
+    pub page_footer: Option<String>,
+}
+
+pub const PAGE_NUMBER_PLACEHOLDER: &str = "{page}";
+pub const TOTAL_PAGES_PLACEHOLDER: &str = "{pages}";
+
+impl ReportPageSetup {
+    /// Page width and height in mm, taking the orientation into account
+    pub fn page_size_mm(&self) -> (f64, f64) {
+        let (width, height) = match self.paper_size.as_ref().unwrap_or(&PaperSize::A4) {
+            PaperSize::A3 => (297.0, 420.0),
+            PaperSize::A4 => (210.0, 297.0),
+            PaperSize::A5 => (148.0, 210.0),
+            PaperSize::Letter => (215.9, 279.4),
+            PaperSize::Legal => (215.9, 355.6),
+            PaperSize::Custom { width, height } => (*width, *height),
+        };
+        match self.orientation {
+            Some(PageOrientation::Landscape) => (height, width),
+            Some(PageOrientation::Portrait) | None => (width, height),
+        }
+    }
+
+    pub fn margins_mm(&self) -> PageMargins {
+        self.margins.clone().unwrap_or(PageMargins {
+            top: 10.0,
+            bottom: 10.0,
+            left: 10.0,
+            right: 10.0,
+        })
+    }
+
+    pub fn scale(&self) -> f64 {
+        self.scale.unwrap_or(1.0)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        let (width, height) = self.page_size_mm();
+        if width <= 0.0 || height <= 0.0 {
+            return Err("Paper size must be positive".to_string());
+        }
+        let margins = self.margins_mm();
+        if [margins.top, margins.bottom, margins.left, margins.right]
+            .iter()
+            .any(|margin| *margin < 0.0)
+        {
+            return Err("Page margins must not be negative".to_string());
+        }
+        if margins.left + margins.right >= width || margins.top + margins.bottom >= height {
+            return Err("Page margins are larger than the paper".to_string());
+        }
+        let scale = self.scale();
+        if !(0.1..=2.0).contains(&scale) {
+            return Err(format!(
+                "Page scale must be between 0.1 and 2, got {}",
+                scale
+            ));
+        }
+        Ok(())
+    }
+
+    /// CSS `@page` rule matching the page size and margins
+    pub fn page_css(&self) -> String {
+        let (width, height) = self.page_size_mm();
+        let margins = self.margins_mm();
+        format!(
+            "@page {{ size: {}mm {}mm; margin: {}mm {}mm {}mm {}mm; }}",
+            width, height, margins.top, margins.right, margins.bottom, margins.left
+        )
+    }
+}
+
+/// Replaces the page number placeholders in a page header or footer
+pub fn replace_page_placeholders(text: &str, page_number: &str, total_pages: &str) -> String {
+    text.replace(PAGE_NUMBER_PLACEHOLDER, page_number)
+        .replace(TOTAL_PAGES_PLACEHOLDER, total_pages)
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
@@ -64,9 +272,17 @@ pub enum ReportDefinitionEntry {
     TeraTemplate(TeraTemplate),
     /// Custom http query
     GraphGLQuery(GraphQlQuery),
+    /// Read-only SQL query, the result rows are passed to the template as `data`
+    SqlQuery(SqlQuery),
     /// Use default predefined query
     DefaultQuery(DefaultQuery),
+    /// Use the default predefined template matching a default query
+    DefaultTemplate(DefaultQuery),
     Resource(serde_json::Value),
+    /// Translation bundle for a single language
+    Translations(ReportTranslations),
+    /// Paper size, margins etc. of the printed report, at most one per report
+    PageSetup(ReportPageSetup),
     /// Entry reference to another report definition
     Ref(ReportRef),
 }
@@ -93,8 +309,9 @@ mod report_dsl_test {
     use serde_json::json;
 
     use crate::report::definition::{
-        DefaultQuery, ReportDefinition, ReportDefinitionEntry, ReportDefinitionIndex,
-        ReportOutputType, ReportRef, TeraTemplate,
+        DefaultQuery, GraphQlQuery, PageMargins, PageOrientation, PaperSize, ReportDefinition,
+        ReportDefinitionEntry, ReportDefinitionIndex, ReportOutputType, ReportPageSetup, ReportRef,
+        TeraTemplate,
     };
 
     #[test]
@@ -184,4 +401,83 @@ mod report_dsl_test {
             }
         )
     }
+
+    #[test]
+    fn query_variables_with_arguments() {
+        let query = GraphQlQuery {
+            query: "".to_string(),
+            variables: Some(json!({ "fromDatetime": "2022-01-01T00:00:00Z", "limit": 10 })),
+        };
+        let arguments = json!({ "fromDatetime": "2022-02-01T00:00:00Z", "storeId": "other" });
+        assert_eq!(
+            query.query_variables("store_a", None, Some(&arguments)),
+            json!({
+                "fromDatetime": "2022-02-01T00:00:00Z",
+                "limit": 10,
+                "storeId": "store_a"
+            })
+        );
+        assert_eq!(
+            query.query_variables("store_a", Some("data_a"), None),
+            json!({
+                "fromDatetime": "2022-01-01T00:00:00Z",
+                "limit": 10,
+                "storeId": "store_a",
+                "dataId": "data_a"
+            })
+        );
+    }
+
+    #[test]
+    fn page_setup() {
+        let entry: ReportDefinitionEntry = serde_json::from_value(json!({
+            "type": "PageSetup",
+            "data": {
+                "paper_size": "A5",
+                "orientation": "Landscape",
+                "margins": { "top": 15, "bottom": 15, "left": 5, "right": 5 },
+                "page_footer": "Page {page} of {pages}"
+            }
+        }))
+        .unwrap();
+        let page_setup = match entry {
+            ReportDefinitionEntry::PageSetup(page_setup) => page_setup,
+            _ => panic!("Expected a page setup"),
+        };
+        assert_eq!(page_setup.paper_size, Some(PaperSize::A5));
+        assert_eq!(page_setup.page_size_mm(), (210.0, 148.0));
+        assert_eq!(page_setup.scale(), 1.0);
+        assert_eq!(page_setup.validate(), Ok(()));
+        assert_eq!(
+            page_setup.page_css(),
+            "@page { size: 210mm 148mm; margin: 15mm 5mm 15mm 5mm; }"
+        );
+
+        // defaults
+        let page_setup = ReportPageSetup::default();
+        assert_eq!(page_setup.page_size_mm(), (210.0, 297.0));
+        assert_eq!(page_setup.margins_mm().left, 10.0);
+
+        // invalid setups
+        let page_setup = ReportPageSetup {
+            paper_size: Some(PaperSize::Custom {
+                width: 50.0,
+                height: 30.0,
+            }),
+            orientation: Some(PageOrientation::Portrait),
+            margins: Some(PageMargins {
+                top: 20.0,
+                bottom: 20.0,
+                left: 0.0,
+                right: 0.0,
+            }),
+            ..Default::default()
+        };
+        assert!(page_setup.validate().is_err());
+        let page_setup = ReportPageSetup {
+            scale: Some(3.0),
+            ..Default::default()
+        };
+        assert!(page_setup.validate().is_err());
+    }
 }
@@ -2,30 +2,86 @@ use std::{fs, path::PathBuf, str::FromStr};
 
 use headless_chrome::{types::PrintToPdfOptions, Browser, LaunchOptionsBuilder};
 
+use super::definition::{replace_page_placeholders, ReportPageSetup};
+
+/// Chrome replaces the content of elements with these classes in page headers and footers
+const CHROME_PAGE_NUMBER: &str = "<span class=\"pageNumber\"></span>";
+const CHROME_TOTAL_PAGES: &str = "<span class=\"totalPages\"></span>";
+
+const MM_PER_INCH: f64 = 25.4;
+
+/// Chrome renders page headers and footers with a tiny default font and without margins
+fn chrome_header_footer_template(content: &Option<String>) -> String {
+    match content {
+        Some(content) => format!(
+            "<div style=\"font-size: 10px; width: 100%; margin: 0 10mm;\">{}</div>",
+            replace_page_placeholders(content, CHROME_PAGE_NUMBER, CHROME_TOTAL_PAGES)
+        ),
+        // an empty template, otherwise Chrome prints its default header/footer
+        None => "<span></span>".to_string(),
+    }
+}
+
+/// Maps the page setup to the Chrome print options, uses the Chrome defaults if there is no page
+/// setup
+fn chrome_pdf_options(page_setup: Option<&ReportPageSetup>) -> PrintToPdfOptions {
+    let page_setup = match page_setup {
+        Some(page_setup) => page_setup,
+        None => {
+            return PrintToPdfOptions {
+                display_header_footer: Some(false),
+                prefer_css_page_size: None,
+                landscape: None,
+                print_background: None,
+                scale: None,
+                paper_width: None,
+                paper_height: None,
+                margin_top: None,
+                margin_bottom: None,
+                margin_left: None,
+                margin_right: None,
+                page_ranges: None,
+                ignore_invalid_page_ranges: None,
+                header_template: None,
+                footer_template: None,
+                transfer_mode: None,
+            }
+        }
+    };
+    // the page size already takes the orientation into account, i.e. don't set landscape
+    let (width, height) = page_setup.page_size_mm();
+    let margins = page_setup.margins_mm();
+    let display_header_footer =
+        page_setup.page_header.is_some() || page_setup.page_footer.is_some();
+    PrintToPdfOptions {
+        display_header_footer: Some(display_header_footer),
+        prefer_css_page_size: Some(false),
+        landscape: Some(false),
+        print_background: None,
+        scale: Some(page_setup.scale()),
+        paper_width: Some(width / MM_PER_INCH),
+        paper_height: Some(height / MM_PER_INCH),
+        margin_top: Some(margins.top / MM_PER_INCH),
+        margin_bottom: Some(margins.bottom / MM_PER_INCH),
+        margin_left: Some(margins.left / MM_PER_INCH),
+        margin_right: Some(margins.right / MM_PER_INCH),
+        page_ranges: None,
+        ignore_invalid_page_ranges: None,
+        header_template: display_header_footer
+            .then(|| chrome_header_footer_template(&page_setup.page_header)),
+        footer_template: display_header_footer
+            .then(|| chrome_header_footer_template(&page_setup.page_footer)),
+        transfer_mode: None,
+    }
+}
+
 pub fn html_to_pdf(
     temp_dir: &Option<String>,
     document: &str,
     document_id: &str,
+    page_setup: Option<&ReportPageSetup>,
 ) -> Result<Vec<u8>, anyhow::Error> {
-    let pdf_options = Some(PrintToPdfOptions {
-        display_header_footer: Some(false),
-        prefer_css_page_size: None,
-        landscape: None,
-        print_background: None,
-        scale: None,
-        // Assuming 96 DPI (dots per inch)
-        paper_width: None,
-        paper_height: None,
-        margin_top: None,
-        margin_bottom: None,
-        margin_left: None,
-        margin_right: None,
-        page_ranges: None,
-        ignore_invalid_page_ranges: None,
-        header_template: None,
-        footer_template: None,
-        transfer_mode: None,
-    });
+    let pdf_options = Some(chrome_pdf_options(page_setup));
 
     let temp_dir = match temp_dir {
         Some(temp_dir) => PathBuf::from_str(temp_dir)?,
@@ -56,3 +112,35 @@ pub fn html_to_pdf(
     fs::remove_file(temp_html_doc_path)?;
     Ok(local_pdf)
 }
+
+#[cfg(test)]
+mod test {
+    use crate::report::definition::{PageOrientation, PaperSize, ReportPageSetup};
+
+    use super::chrome_pdf_options;
+
+    #[test]
+    fn chrome_options_from_page_setup() {
+        let options = chrome_pdf_options(None);
+        assert_eq!(options.display_header_footer, Some(false));
+        assert_eq!(options.paper_width, None);
+
+        let page_setup = ReportPageSetup {
+            paper_size: Some(PaperSize::A4),
+            orientation: Some(PageOrientation::Landscape),
+            scale: Some(0.8),
+            page_footer: Some("Page {page} of {pages}".to_string()),
+            ..Default::default()
+        };
+        let options = chrome_pdf_options(Some(&page_setup));
+        assert_eq!(options.paper_width, Some(297.0 / 25.4));
+        assert_eq!(options.paper_height, Some(210.0 / 25.4));
+        assert_eq!(options.margin_left, Some(10.0 / 25.4));
+        assert_eq!(options.scale, Some(0.8));
+        assert_eq!(options.display_header_footer, Some(true));
+        assert_eq!(options.header_template, Some("<span></span>".to_string()));
+        assert!(options.footer_template.unwrap().contains(
+            "Page <span class=\"pageNumber\"></span> of <span class=\"totalPages\"></span>"
+        ));
+    }
+}
@@ -0,0 +1,134 @@
+use repository::{Language, RepositoryError, UserAccountRowRepository};
+
+use crate::{service_provider::ServiceContext, store_preference::get_store_preferences};
+
+/// Locale settings a report is rendered with, derived from the user and store settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReportLocale {
+    /// Language code of the user, e.g. "fr"
+    pub language: String,
+    /// IANA timezone name of the store, UTC is used if not set
+    pub timezone: Option<String>,
+    /// ISO 4217 currency code of the store
+    pub currency_code: Option<String>,
+}
+
+impl Default for ReportLocale {
+    fn default() -> Self {
+        ReportLocale {
+            language: DEFAULT_LANGUAGE.to_string(),
+            timezone: None,
+            currency_code: None,
+        }
+    }
+}
+
+/// Language used if a translation is not available in the user language
+pub const DEFAULT_LANGUAGE: &str = "en";
+
+pub fn language_code(language: &Language) -> &'static str {
+    match language {
+        Language::English => "en",
+        Language::French => "fr",
+        Language::Spanish => "es",
+        Language::Laos => "lo",
+        Language::Khmer => "km",
+        Language::Portuguese => "pt",
+        Language::Russian => "ru",
+        Language::Tetum => "tet",
+    }
+}
+
+pub(crate) fn get_report_locale(ctx: &ServiceContext) -> Result<ReportLocale, RepositoryError> {
+    let language = UserAccountRowRepository::new(&ctx.connection)
+        .find_one_by_id(&ctx.user_id)?
+        .map(|user| user.language)
+        .unwrap_or_default();
+    let store_preferences = get_store_preferences(&ctx.connection, &ctx.store_id)?;
+
+    Ok(ReportLocale {
+        language: language_code(&language).to_string(),
+        timezone: store_preferences.timezone,
+        currency_code: store_preferences.currency_code,
+    })
+}
+
+/// Number and date conventions of a language
+pub(crate) struct LocaleFormat {
+    pub group_separator: &'static str,
+    pub decimal_separator: &'static str,
+    /// If the currency code is placed after the amount
+    pub currency_after_amount: bool,
+    pub date_format: &'static str,
+    pub datetime_format: &'static str,
+}
+
+pub(crate) fn locale_format(language: &str) -> LocaleFormat {
+    match language {
+        "fr" => LocaleFormat {
+            group_separator: "\u{202f}",
+            decimal_separator: ",",
+            currency_after_amount: true,
+            date_format: "%d/%m/%Y",
+            datetime_format: "%d/%m/%Y %H:%M",
+        },
+        "es" | "pt" | "tet" => LocaleFormat {
+            group_separator: ".",
+            decimal_separator: ",",
+            currency_after_amount: true,
+            date_format: "%d/%m/%Y",
+            datetime_format: "%d/%m/%Y %H:%M",
+        },
+        "ru" => LocaleFormat {
+            group_separator: "\u{a0}",
+            decimal_separator: ",",
+            currency_after_amount: true,
+            date_format: "%d.%m.%Y",
+            datetime_format: "%d.%m.%Y %H:%M",
+        },
+        "km" => LocaleFormat {
+            group_separator: ".",
+            decimal_separator: ",",
+            currency_after_amount: true,
+            date_format: "%d/%m/%Y",
+            datetime_format: "%d/%m/%Y %H:%M",
+        },
+        // "en", "lo" and unknown languages
+        _ => LocaleFormat {
+            group_separator: ",",
+            decimal_separator: ".",
+            currency_after_amount: false,
+            date_format: "%d/%m/%Y",
+            datetime_format: "%d/%m/%Y %H:%M",
+        },
+    }
+}
+
+/// Formats a number with the given number of decimals using the separators of the locale
+pub(crate) fn format_number(value: f64, decimals: usize, format: &LocaleFormat) -> String {
+    let formatted = format!("{:.*}", decimals, value.abs());
+    let (integer, fraction) = match formatted.split_once('.') {
+        Some((integer, fraction)) => (integer, Some(fraction)),
+        None => (formatted.as_str(), None),
+    };
+
+    let mut grouped = String::new();
+    for (index, digit) in integer.chars().enumerate() {
+        if index > 0 && (integer.len() - index) % 3 == 0 {
+            grouped.push_str(format.group_separator);
+        }
+        grouped.push(digit);
+    }
+    if let Some(fraction) = fraction {
+        grouped.push_str(format.decimal_separator);
+        grouped.push_str(fraction);
+    }
+
+    // don't show "-0.00"
+    let is_zero = formatted.chars().all(|c| c == '0' || c == '.');
+    if value < 0.0 && !is_zero {
+        format!("-{}", grouped)
+    } else {
+        grouped
+    }
+}
@@ -1,4 +1,10 @@
 pub mod default_queries;
+pub mod default_templates;
+mod barcodes;
+mod charts;
 pub mod definition;
 mod html_printing;
+pub mod locale;
 pub mod report_service;
+mod sql_query;
+mod tera_functions;
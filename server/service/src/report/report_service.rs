@@ -1,7 +1,10 @@
 use chrono::{DateTime, Utc};
 use repository::{
+    EqualFilter, InvoiceFilter, InvoiceRepository, InvoiceSort, InvoiceSortField, Pagination,
     PaginationOption, ReportFilter, ReportRepository, ReportRow, ReportRowRepository, ReportSort,
-    ReportType, RepositoryError,
+    ReportSqlQueryRepository, ReportType, RepositoryError, RequisitionFilter,
+    RequisitionRepository, RequisitionSort, RequisitionSortField, SqlDialect, StocktakeFilter,
+    StocktakeRepository, StocktakeSort, StocktakeSortField, SQL_DIALECT,
 };
 use std::{collections::HashMap, time::SystemTime};
 use util::uuid::uuid;
@@ -13,11 +16,19 @@ use crate::{
 
 use super::{
     default_queries::get_default_gql_query,
+    default_templates::get_default_template,
     definition::{
-        DefaultQuery, GraphQlQuery, ReportDefinition, ReportDefinitionEntry, ReportRef,
-        TeraTemplate,
+        DefaultQuery, GraphQlQuery, ReportDefinition, ReportDefinitionEntry, ReportOutputType,
+        ReportPageSetup, ReportRef, SqlQuery, TeraTemplate,
     },
     html_printing::html_to_pdf,
+    locale::{get_report_locale, ReportLocale},
+    sql_query::{prepare_sql_query, PreparedSqlQuery},
+    tera_functions::{
+        asset_function, barcode_function, chart_function, format_currency_function,
+        format_date_function, format_number_function, to_store_timezone_function,
+        translate_function,
+    },
 };
 
 pub enum PrintFormat {
@@ -42,6 +53,30 @@ pub enum ResolvedReportQuery {
     GraphQlQuery(GraphQlQuery),
     // Use default predefined query
     Default(DefaultQuery),
+    SqlQuery(SqlQuery),
+}
+
+/// Query to fetch the report data
+#[derive(Clone)]
+pub enum ReportQuery {
+    /// Fetched from the GraphQL API
+    GraphQlQuery(GraphQlQuery),
+    /// Fetched using `ReportServiceTrait::query_sql_report_data`
+    SqlQuery(SqlQuery),
+}
+
+impl ReportQuery {
+    pub fn query_variables(
+        &self,
+        store_id: &str,
+        data_id: Option<&str>,
+        arguments: Option<&serde_json::Value>,
+    ) -> serde_json::Value {
+        match self {
+            ReportQuery::GraphQlQuery(query) => query.query_variables(store_id, data_id, arguments),
+            ReportQuery::SqlQuery(query) => query.query_variables(store_id, data_id, arguments),
+        }
+    }
 }
 
 /// Resolved and validated report definition, i.e. its guaranteed that there is a main template and
@@ -56,8 +91,24 @@ pub struct ResolvedReportDefinition {
     pub footer: Option<String>,
     /// Map of all found Tera templates in the report definition
     pub templates: HashMap<String, TeraTemplate>,
-    pub query: GraphQlQuery,
+    pub query: ReportQuery,
     pub resources: HashMap<String, serde_json::Value>,
+    /// Map of language code to translated strings
+    pub translations: HashMap<String, HashMap<String, String>>,
+    /// Locale of the user and store the report is rendered for
+    pub locale: ReportLocale,
+    /// Page setup of the printed report, if not set the defaults of the PDF backend are used
+    pub page_setup: Option<ReportPageSetup>,
+}
+
+impl ResolvedReportDefinition {
+    /// The output type of the main template
+    pub fn output_type(&self) -> ReportOutputType {
+        self.templates
+            .get(&self.template)
+            .map(|template| template.output.clone())
+            .unwrap_or(ReportOutputType::Html)
+    }
 }
 
 pub struct GeneratedReport {
@@ -66,6 +117,38 @@ pub struct GeneratedReport {
     pub footer: Option<String>,
 }
 
+/// Max number of records that can be printed in a single batch
+pub const MAX_BATCH_SIZE: usize = 500;
+
+/// Selects the records of a batch print, the store id of the filter is always set to the
+/// current store
+#[allow(clippy::large_enum_variant)]
+pub enum ReportBatchFilter {
+    /// Used for inbound and outbound shipment reports
+    Invoice(InvoiceFilter),
+    Requisition(RequisitionFilter),
+    Stocktake(StocktakeFilter),
+}
+
+/// Fetched data of a single record in a batch print
+pub struct BatchReportData {
+    pub data_id: String,
+    pub report_data: serde_json::Value,
+    pub arguments: Option<serde_json::Value>,
+}
+
+pub struct BatchRecordError {
+    pub data_id: String,
+    pub error: ReportError,
+}
+
+pub struct BatchPrintResult {
+    /// The printed document, None if no record could be printed
+    pub file_id: Option<String>,
+    /// Records that failed to print, all other records are contained in the document
+    pub errors: Vec<BatchRecordError>,
+}
+
 pub trait ReportServiceTrait: Sync + Send {
     fn query_reports(
         &self,
@@ -96,36 +179,213 @@ pub trait ReportServiceTrait: Sync + Send {
         resolve_report_definition(ctx, name, report_definition)
     }
 
-    /// Converts a HTML report to a file for the target PrintFormat and returns file id
+    /// Converts a HTML report to a file for the target PrintFormat and returns file id.
+    /// Raw text reports, e.g. label printer commands, are stored as is and the format is ignored.
     fn print_html_report(
         &self,
         base_dir: &Option<String>,
         report: &ResolvedReportDefinition,
         report_data: serde_json::Value,
+        arguments: Option<serde_json::Value>,
         format: Option<PrintFormat>,
     ) -> Result<String, ReportError> {
-        let document = generate_report(report, report_data)?;
+        let document = generate_report(report, report_data, arguments)?;
 
+        if report.output_type() == ReportOutputType::RawText {
+            return print_raw_text_report(base_dir, document, report.name.clone());
+        }
         match format {
-            Some(PrintFormat::Html) => {
-                print_html_report_to_html(base_dir, document, report.name.clone())
-            }
+            Some(PrintFormat::Html) => print_html_report_to_html(base_dir, document, report),
+            Some(PrintFormat::Pdf) | None => print_html_report_to_pdf(base_dir, document, report),
+        }
+    }
+
+    /// Runs the SQL query of a report and returns the result rows as json array
+    fn query_sql_report_data(
+        &self,
+        ctx: &ServiceContext,
+        query: &SqlQuery,
+        variables: &serde_json::Value,
+    ) -> Result<serde_json::Value, ReportError> {
+        query_sql_report_data(ctx, query, variables)
+    }
+
+    /// Ids of the records in the current store matching the batch filter
+    fn batch_data_ids(
+        &self,
+        ctx: &ServiceContext,
+        filter: ReportBatchFilter,
+    ) -> Result<Vec<String>, RepositoryError> {
+        batch_data_ids(ctx, filter)
+    }
+
+    /// Prints multiple records into a single file, each record starting on a new page.
+    /// Records that fail to render are reported in the result and left out of the document.
+    fn print_html_report_batch(
+        &self,
+        base_dir: &Option<String>,
+        report: &ResolvedReportDefinition,
+        records: Vec<BatchReportData>,
+        format: Option<PrintFormat>,
+    ) -> Result<BatchPrintResult, ReportError> {
+        print_html_report_batch(base_dir, report, records, format)
+    }
+}
+
+/// Validates the query variant for the database backend of the server
+fn prepare_dialect_sql_query(query: &SqlQuery) -> Result<PreparedSqlQuery, String> {
+    let sql = match SQL_DIALECT {
+        SqlDialect::Sqlite => query.sqlite.as_ref(),
+        SqlDialect::Postgres => query.postgres.as_ref(),
+    }
+    .ok_or_else(|| format!("No query for {:?} databases", SQL_DIALECT))?;
+    let prepared = prepare_sql_query(sql, query.columns.as_ref())?;
+    // report data must be limited to the store the report is printed for
+    if !prepared
+        .parameters
+        .iter()
+        .any(|parameter| parameter == "storeId")
+    {
+        return Err("Report queries must filter by the :storeId parameter".to_string());
+    }
+    Ok(prepared)
+}
+
+fn query_sql_report_data(
+    ctx: &ServiceContext,
+    query: &SqlQuery,
+    variables: &serde_json::Value,
+) -> Result<serde_json::Value, ReportError> {
+    let prepared = prepare_dialect_sql_query(query).map_err(ReportError::QueryError)?;
+    // bind the store id of the context rather than trusting the passed variables
+    let mut variables = match variables {
+        serde_json::Value::Object(_) => variables.clone(),
+        _ => serde_json::json!({}),
+    };
+    variables["storeId"] = serde_json::Value::String(ctx.store_id.clone());
+    ReportSqlQueryRepository::new(&ctx.connection)
+        .query_json_rows(&prepared.query, &prepared.columns, &variables)
+        .map_err(|err| ReportError::QueryError(format!("{}", err)))
+}
+
+fn batch_data_ids(
+    ctx: &ServiceContext,
+    filter: ReportBatchFilter,
+) -> Result<Vec<String>, RepositoryError> {
+    let store_id = EqualFilter::equal_to(&ctx.store_id);
+    let ids = match filter {
+        ReportBatchFilter::Invoice(filter) => InvoiceRepository::new(&ctx.connection)
+            .query(
+                Pagination::all(),
+                Some(filter.store_id(store_id)),
+                Some(InvoiceSort {
+                    key: InvoiceSortField::InvoiceNumber,
+                    desc: None,
+                }),
+            )?
+            .into_iter()
+            .map(|invoice| invoice.invoice_row.id)
+            .collect(),
+        ReportBatchFilter::Requisition(filter) => RequisitionRepository::new(&ctx.connection)
+            .query(
+                Pagination::all(),
+                Some(filter.store_id(store_id)),
+                Some(RequisitionSort {
+                    key: RequisitionSortField::RequisitionNumber,
+                    desc: None,
+                }),
+            )?
+            .into_iter()
+            .map(|requisition| requisition.requisition_row.id)
+            .collect(),
+        ReportBatchFilter::Stocktake(filter) => StocktakeRepository::new(&ctx.connection)
+            .query(
+                Pagination::all(),
+                Some(filter.store_id(store_id)),
+                Some(StocktakeSort {
+                    key: StocktakeSortField::StocktakeNumber,
+                    desc: None,
+                }),
+            )?
+            .into_iter()
+            .map(|stocktake| stocktake.id)
+            .collect(),
+    };
+    Ok(ids)
+}
+
+fn print_html_report_batch(
+    base_dir: &Option<String>,
+    report: &ResolvedReportDefinition,
+    records: Vec<BatchReportData>,
+    format: Option<PrintFormat>,
+) -> Result<BatchPrintResult, ReportError> {
+    let mut documents = Vec::new();
+    let mut errors = Vec::new();
+    for record in records {
+        match generate_report(report, record.report_data, record.arguments) {
+            Ok(document) => documents.push(document),
+            Err(error) => errors.push(BatchRecordError {
+                data_id: record.data_id,
+                error,
+            }),
+        }
+    }
+    if documents.is_empty() {
+        return Ok(BatchPrintResult {
+            file_id: None,
+            errors,
+        });
+    }
+
+    let report_name = report.name.clone();
+    let file_id = if report.output_type() == ReportOutputType::RawText {
+        let text = documents
+            .into_iter()
+            .map(|document| document.document)
+            .collect::<Vec<String>>()
+            .join("");
+        store_raw_text(base_dir, &text, report_name)?
+    } else {
+        let page_setup = report.page_setup.as_ref();
+        let html = format_html_documents(documents, page_setup);
+        match format {
+            Some(PrintFormat::Html) => store_html(base_dir, &html, report_name)?,
             Some(PrintFormat::Pdf) | None => {
-                print_html_report_to_pdf(base_dir, document, report.name.clone())
+                store_html_as_pdf(base_dir, &html, page_setup, report_name)?
             }
         }
-    }
+    };
+    Ok(BatchPrintResult {
+        file_id: Some(file_id),
+        errors,
+    })
 }
 
 /// Converts a HTML report to a pdf file and returns the file id
 fn print_html_report_to_pdf(
     base_dir: &Option<String>,
     document: GeneratedReport,
+    report: &ResolvedReportDefinition,
+) -> Result<String, ReportError> {
+    let page_setup = report.page_setup.as_ref();
+    store_html_as_pdf(
+        base_dir,
+        &format_html_document(document, page_setup),
+        page_setup,
+        report.name.clone(),
+    )
+}
+
+fn store_html_as_pdf(
+    base_dir: &Option<String>,
+    html: &str,
+    page_setup: Option<&ReportPageSetup>,
     report_name: String,
 ) -> Result<String, ReportError> {
     let id = uuid();
     // TODO use a proper tmp dir here instead of base_dir?
-    let pdf = html_to_pdf(base_dir, &format_html_document(document), &id)
+    let pdf = html_to_pdf(base_dir, html, &id, page_setup)
         .map_err(|err| ReportError::HTMLToPDFError(format!("{}", err)))?;
 
     let file_service = StaticFileService::new(base_dir)
@@ -144,6 +404,15 @@ fn print_html_report_to_pdf(
 fn print_html_report_to_html(
     base_dir: &Option<String>,
     document: GeneratedReport,
+    report: &ResolvedReportDefinition,
+) -> Result<String, ReportError> {
+    let html = format_html_document(document, report.page_setup.as_ref());
+    store_html(base_dir, &html, report.name.clone())
+}
+
+fn store_html(
+    base_dir: &Option<String>,
+    html: &str,
     report_name: String,
 ) -> Result<String, ReportError> {
     let file_service = StaticFileService::new(base_dir)
@@ -152,21 +421,89 @@ fn print_html_report_to_html(
     let file = file_service
         .store_file(
             &format!("{}_{}.html", now.format("%Y%m%d_%H%M%S"), report_name),
-            format_html_document(document).as_bytes(),
+            html.as_bytes(),
         )
         .map_err(|err| ReportError::DocGenerationError(format!("{}", err)))?;
     Ok(file.id)
 }
 
+/// Stores raw printer commands as a .prn file and returns the file id.
+/// Header and footer are not used for raw text reports.
+fn print_raw_text_report(
+    base_dir: &Option<String>,
+    document: GeneratedReport,
+    report_name: String,
+) -> Result<String, ReportError> {
+    store_raw_text(base_dir, &document.document, report_name)
+}
+
+fn store_raw_text(
+    base_dir: &Option<String>,
+    text: &str,
+    report_name: String,
+) -> Result<String, ReportError> {
+    let file_service = StaticFileService::new(base_dir)
+        .map_err(|err| ReportError::DocGenerationError(format!("{}", err)))?;
+    let now: DateTime<Utc> = SystemTime::now().into();
+    let file = file_service
+        .store_file(
+            &format!("{}_{}.prn", now.format("%Y%m%d_%H%M%S"), report_name),
+            &unescape_raw_text(text),
+        )
+        .map_err(|err| ReportError::DocGenerationError(format!("{}", err)))?;
+    Ok(file.id)
+}
+
+/// Replaces `\xHH` escape sequences with the corresponding bytes
+pub fn unescape_raw_text(text: &str) -> Vec<u8> {
+    let bytes = text.as_bytes();
+    let mut result = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        if bytes[index] == b'\\' && bytes.get(index + 1) == Some(&b'x') {
+            let byte = text
+                .get(index + 2..index + 4)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+            if let Some(byte) = byte {
+                result.push(byte);
+                index += 4;
+                continue;
+            }
+        }
+        result.push(bytes[index]);
+        index += 1;
+    }
+    result
+}
+
 /// Puts the document content, header and footer into a <html> template.
 /// This assumes that the document contains the html body.
-fn format_html_document(document: GeneratedReport) -> String {
-    // ensure that <html> is at the start of the text
-    // if not, the cordova printer plugin renders as text not HTML!
-    format!(
-        "<html>
-    <body>
-        <table class=\"paging\">
+pub fn format_html_document(
+    document: GeneratedReport,
+    page_setup: Option<&ReportPageSetup>,
+) -> String {
+    format_html_documents(vec![document], page_setup)
+}
+
+/// Puts multiple documents into a single <html> template, each document starts on a new page and
+/// has its own header and footer.
+pub fn format_html_documents(
+    documents: Vec<GeneratedReport>,
+    page_setup: Option<&ReportPageSetup>,
+) -> String {
+    let count = documents.len();
+    let tables = documents
+        .into_iter()
+        .enumerate()
+        .map(|(index, document)| {
+            let page_break = if index + 1 < count {
+                " style=\"page-break-after: always;\""
+            } else {
+                ""
+            };
+            format!(
+                "
+        <table class=\"paging\"{}>
             <thead>
                 <tr>
                 <td>{}</td>
@@ -182,12 +519,33 @@ fn format_html_document(document: GeneratedReport) -> String {
                 <td>{}</td>
                 </tr>
             </tfoot>
-        </table>
+        </table>",
+                page_break,
+                document.header.unwrap_or("".to_string()),
+                document.document,
+                document.footer.unwrap_or("".to_string())
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("");
+    // ensure that <html> is at the start of the text
+    // if not, the cordova printer plugin renders as text not HTML!
+    let head = match page_setup {
+        Some(page_setup) => format!(
+            "
+    <head>
+        <style>{}</style>
+    </head>",
+            page_setup.page_css()
+        ),
+        None => "".to_string(),
+    };
+    format!(
+        "<html>{}
+    <body>{}
     </body>
 </html>",
-        document.header.unwrap_or("".to_string()),
-        document.document,
-        document.footer.unwrap_or("".to_string())
+        head, tables
     )
 }
 
@@ -206,7 +564,7 @@ fn query_reports(
     let repo = ReportRepository::new(&ctx.connection);
     let pagination = get_default_pagination(pagination, MAX_LIMIT, MIN_LIMIT)?;
     let filter = filter
-        .unwrap_or(ReportFilter::new())
+        .unwrap_or_default()
         .r#type(ReportType::OmSupply.equal_to());
     Ok(repo.query(pagination, Some(filter.clone()), sort)?)
 }
@@ -228,9 +586,18 @@ fn resolve_report_definition(
 ) -> Result<ResolvedReportDefinition, ReportError> {
     let repo = ReportRowRepository::new(&ctx.connection);
     let fully_loaded_report = load_template_references(&repo, &ctx.store_id, main)?;
+    let locale = get_report_locale(ctx)?;
+    resolve_loaded_report_definition(name, fully_loaded_report, locale)
+}
 
-    let templates = tera_templates_from_resolved_template(&fully_loaded_report)
-        .ok_or(ReportError::TemplateNotSpecified)?;
+/// Resolves a report definition in which all references to other report definitions have already
+/// been loaded, i.e. this doesn't require access to the database.
+pub fn resolve_loaded_report_definition(
+    name: String,
+    fully_loaded_report: ReportDefinition,
+    locale: ReportLocale,
+) -> Result<ResolvedReportDefinition, ReportError> {
+    let templates = tera_templates_from_resolved_template(&fully_loaded_report)?;
 
     // validate index entries are present
     let template =
@@ -284,11 +651,21 @@ fn resolve_report_definition(
     // resolve the query entry
     let query = query_from_resolved_template(query_entry).ok_or(ReportError::QueryNotSpecified)?;
     let query = match query {
-        ResolvedReportQuery::GraphQlQuery(query) => query,
-        ResolvedReportQuery::Default(query) => get_default_gql_query(query),
+        ResolvedReportQuery::GraphQlQuery(query) => ReportQuery::GraphQlQuery(query),
+        ResolvedReportQuery::Default(query) => {
+            ReportQuery::GraphQlQuery(get_default_gql_query(query))
+        }
+        ResolvedReportQuery::SqlQuery(query) => {
+            prepare_dialect_sql_query(&query).map_err(|err| {
+                ReportError::InvalidReportDefinition(format!("Invalid SQL query: {}", err))
+            })?;
+            ReportQuery::SqlQuery(query)
+        }
     };
 
     let resources = resources_from_resolved_template(&fully_loaded_report);
+    let translations = translations_from_resolved_template(&fully_loaded_report);
+    let page_setup = page_setup_from_resolved_template(&fully_loaded_report)?;
 
     Ok(ResolvedReportDefinition {
         name,
@@ -298,17 +675,46 @@ fn resolve_report_definition(
         templates,
         query,
         resources,
+        translations,
+        locale,
+        page_setup,
     })
 }
 
-fn generate_report(
+/// Renders the report.
+/// The `arguments` are the variables the report data has been queried with, e.g. to show the
+/// selected report period in the report.
+pub fn generate_report(
     report: &ResolvedReportDefinition,
     report_data: serde_json::Value,
+    arguments: Option<serde_json::Value>,
 ) -> Result<GeneratedReport, ReportError> {
     let mut context = tera::Context::new();
     context.insert("data", &report_data);
+    context.insert("arguments", &arguments.unwrap_or(serde_json::json!({})));
     context.insert("res", &report.resources);
+    context.insert("locale", &report.locale.language);
     let mut tera = tera::Tera::default();
+    tera.register_function("asset", asset_function(report.resources.clone()));
+    tera.register_function("barcode", barcode_function());
+    tera.register_function("chart", chart_function(report.locale.clone()));
+    tera.register_function(
+        "t",
+        translate_function(report.translations.clone(), report.locale.clone()),
+    );
+    tera.register_function(
+        "format_number",
+        format_number_function(report.locale.clone()),
+    );
+    tera.register_function(
+        "format_currency",
+        format_currency_function(report.locale.clone()),
+    );
+    tera.register_function("format_date", format_date_function(report.locale.clone()));
+    tera.register_function(
+        "to_store_timezone",
+        to_store_timezone_function(report.locale.clone()),
+    );
     let mut templates: HashMap<String, String> = report
         .templates
         .iter()
@@ -329,16 +735,22 @@ fn generate_report(
         templates.insert(resource.0.clone(), string_value);
     }
     tera.add_raw_templates(templates.iter()).map_err(|err| {
-        ReportError::DocGenerationError(format!("Failed to add templates: {}", err))
+        ReportError::DocGenerationError(format!(
+            "Failed to add templates: {}",
+            format_tera_error(&err)
+        ))
     })?;
 
-    let document = tera
-        .render(&report.template, &context)
-        .map_err(|err| ReportError::DocGenerationError(format!("Tera rendering: {:?}", err)))?;
+    let document = tera.render(&report.template, &context).map_err(|err| {
+        ReportError::DocGenerationError(format!("Tera rendering: {}", format_tera_error(&err)))
+    })?;
     let header = match &report.header {
         Some(header_key) => {
             let header = tera.render(header_key, &context).map_err(|err| {
-                ReportError::DocGenerationError(format!("Header generation: {}", err))
+                ReportError::DocGenerationError(format!(
+                    "Header generation: {}",
+                    format_tera_error(&err)
+                ))
             })?;
             Some(header)
         }
@@ -347,7 +759,10 @@ fn generate_report(
     let footer = match &report.footer {
         Some(footer_ref) => {
             let footer = tera.render(footer_ref, &context).map_err(|err| {
-                ReportError::DocGenerationError(format!("Footer generation: {}", err))
+                ReportError::DocGenerationError(format!(
+                    "Footer generation: {}",
+                    format_tera_error(&err)
+                ))
             })?;
             Some(footer)
         }
@@ -361,19 +776,40 @@ fn generate_report(
     })
 }
 
+/// Tera only puts the template name and, for syntax errors, the line and column of the error into
+/// the error sources. Collect the whole chain so that this information isn't lost.
+fn format_tera_error(err: &tera::Error) -> String {
+    let mut message = err.to_string();
+    let mut source = std::error::Error::source(err);
+    while let Some(err) = source {
+        message.push_str(&format!("\n{}", err));
+        source = err.source();
+    }
+    message
+}
+
 fn tera_templates_from_resolved_template(
     report: &ReportDefinition,
-) -> Option<HashMap<String, TeraTemplate>> {
+) -> Result<HashMap<String, TeraTemplate>, ReportError> {
     let mut templates = HashMap::new();
     for (name, entry) in &report.entries {
         match entry {
             ReportDefinitionEntry::TeraTemplate(template) => {
                 templates.insert(name.clone(), template.clone());
             }
+            ReportDefinitionEntry::DefaultTemplate(query) => {
+                let template = get_default_template(query).ok_or_else(|| {
+                    ReportError::InvalidReportDefinition(format!(
+                        "No default template for {:?}",
+                        query
+                    ))
+                })?;
+                templates.insert(name.clone(), template);
+            }
             _ => {}
         }
     }
-    Some(templates)
+    Ok(templates)
 }
 
 fn query_from_resolved_template(
@@ -384,6 +820,7 @@ fn query_from_resolved_template(
             ResolvedReportQuery::GraphQlQuery(query.clone())
         }
         ReportDefinitionEntry::DefaultQuery(query) => ResolvedReportQuery::Default(query.clone()),
+        ReportDefinitionEntry::SqlQuery(query) => ResolvedReportQuery::SqlQuery(query.clone()),
         _ => return None,
     };
     Some(query)
@@ -402,6 +839,43 @@ fn resources_from_resolved_template(
         .collect()
 }
 
+fn translations_from_resolved_template(
+    report: &ReportDefinition,
+) -> HashMap<String, HashMap<String, String>> {
+    let mut translations: HashMap<String, HashMap<String, String>> = HashMap::new();
+    for entry in report.entries.values() {
+        if let ReportDefinitionEntry::Translations(bundle) = entry {
+            translations
+                .entry(bundle.language.clone())
+                .or_default()
+                .extend(bundle.strings.clone());
+        }
+    }
+    translations
+}
+
+fn page_setup_from_resolved_template(
+    report: &ReportDefinition,
+) -> Result<Option<ReportPageSetup>, ReportError> {
+    let mut page_setups = report.entries.values().filter_map(|entry| match entry {
+        ReportDefinitionEntry::PageSetup(page_setup) => Some(page_setup),
+        _ => None,
+    });
+    let page_setup = match page_setups.next() {
+        Some(page_setup) => page_setup.clone(),
+        None => return Ok(None),
+    };
+    if page_setups.next().is_some() {
+        return Err(ReportError::InvalidReportDefinition(
+            "Only one page setup entry is allowed".to_string(),
+        ));
+    }
+    page_setup.validate().map_err(|err| {
+        ReportError::InvalidReportDefinition(format!("Invalid page setup: {}", err))
+    })?;
+    Ok(Some(page_setup))
+}
+
 fn load_report_definition(
     repo: &ReportRowRepository,
     report_id: &str,
@@ -477,7 +951,8 @@ mod report_service_test {
     use std::collections::HashMap;
 
     use repository::{
-        mock::MockDataInserts, test_db::setup_all, ReportContext, ReportRow, ReportRowRepository,
+        mock::MockDataInserts, test_db::setup_all, EqualFilter, InvoiceFilter,
+        InvoiceRowRepository, InvoiceRowType, ReportContext, ReportRow, ReportRowRepository,
         ReportType,
     };
 
@@ -485,11 +960,16 @@ mod report_service_test {
         report::{
             definition::{
                 DefaultQuery, ReportDefinition, ReportDefinitionEntry, ReportDefinitionIndex,
-                ReportOutputType, ReportRef, TeraTemplate,
+                ReportOutputType, ReportRef, ReportTranslations, SqlQuery, TeraTemplate,
+            },
+            locale::ReportLocale,
+            report_service::{
+                generate_report, resolve_loaded_report_definition, unescape_raw_text,
+                BatchReportData, PrintFormat, ReportBatchFilter, ReportError,
             },
-            report_service::generate_report,
         },
         service_provider::ServiceProvider,
+        static_files::StaticFileService,
     };
 
     #[actix_rt::test]
@@ -573,8 +1053,446 @@ mod report_service_test {
             serde_json::json!({
                 "test": "Hello"
             }),
+            None,
         )
         .unwrap();
         assert_eq!(doc.document, "Template: Hello Footer");
     }
+
+    #[test]
+    fn asset_function_embeds_resource() {
+        let report = ReportDefinition {
+            index: ReportDefinitionIndex {
+                template: Some("template.html".to_string()),
+                header: None,
+                footer: None,
+                query: Some("query".to_string()),
+            },
+            entries: HashMap::from([
+                (
+                    "template.html".to_string(),
+                    ReportDefinitionEntry::TeraTemplate(TeraTemplate {
+                        output: ReportOutputType::Html,
+                        template: "Logo: {{asset(name=\"images/icon.svg\")}}".to_string(),
+                    }),
+                ),
+                (
+                    "images/icon.svg".to_string(),
+                    ReportDefinitionEntry::Resource(serde_json::json!("IconData")),
+                ),
+                (
+                    "query".to_string(),
+                    ReportDefinitionEntry::DefaultQuery(DefaultQuery::Invoice),
+                ),
+            ]),
+        };
+        let resolved_def =
+            resolve_loaded_report_definition("report".to_string(), report, ReportLocale::default())
+                .unwrap();
+
+        let doc = generate_report(&resolved_def, serde_json::json!({}), None).unwrap();
+        assert_eq!(doc.document, "Logo: IconData");
+    }
+
+    #[test]
+    fn tera_error_contains_template_location() {
+        let report = ReportDefinition {
+            index: ReportDefinitionIndex {
+                template: Some("template.html".to_string()),
+                header: None,
+                footer: None,
+                query: Some("query".to_string()),
+            },
+            entries: HashMap::from([
+                (
+                    "template.html".to_string(),
+                    ReportDefinitionEntry::TeraTemplate(TeraTemplate {
+                        output: ReportOutputType::Html,
+                        template: "Line 1\n{{ data.test }\n".to_string(),
+                    }),
+                ),
+                (
+                    "query".to_string(),
+                    ReportDefinitionEntry::DefaultQuery(DefaultQuery::Invoice),
+                ),
+            ]),
+        };
+        let resolved_def =
+            resolve_loaded_report_definition("report".to_string(), report, ReportLocale::default())
+                .unwrap();
+
+        let message = match generate_report(&resolved_def, serde_json::json!({}), None) {
+            Err(ReportError::DocGenerationError(message)) => message,
+            _ => panic!("Expected a doc generation error"),
+        };
+        assert!(message.contains("'template.html'"));
+        assert!(message.contains("--> 2:"));
+    }
+
+    #[test]
+    fn locale_functions() {
+        let template = [
+            "{{ t(key=\"batch\") }}",
+            "{{ t(key=\"expiry\") }}",
+            "{{ t(key=\"missing\", fallback=\"Fallback\") }}",
+            "{{ format_number(value=1234567.891) }}",
+            "{{ format_number(value=5, decimals=0) }}",
+            "{{ format_currency(value=-12.5) }}",
+            "{{ format_date(value=\"2022-01-31\") }}",
+            "{{ format_date(value=\"2022-01-31T20:00:00\") }}",
+            "{{ to_store_timezone(value=\"2022-01-31T20:00:00\") }}",
+        ]
+        .join("|");
+        let report = ReportDefinition {
+            index: ReportDefinitionIndex {
+                template: Some("template".to_string()),
+                header: None,
+                footer: None,
+                query: Some("query".to_string()),
+            },
+            entries: HashMap::from([
+                (
+                    "template".to_string(),
+                    ReportDefinitionEntry::TeraTemplate(TeraTemplate {
+                        output: ReportOutputType::Html,
+                        template,
+                    }),
+                ),
+                (
+                    "query".to_string(),
+                    ReportDefinitionEntry::DefaultQuery(DefaultQuery::Invoice),
+                ),
+                (
+                    "en.translations".to_string(),
+                    ReportDefinitionEntry::Translations(ReportTranslations {
+                        language: "en".to_string(),
+                        strings: HashMap::from([
+                            ("batch".to_string(), "Batch".to_string()),
+                            ("expiry".to_string(), "Expiry".to_string()),
+                        ]),
+                    }),
+                ),
+                (
+                    "fr.translations".to_string(),
+                    ReportDefinitionEntry::Translations(ReportTranslations {
+                        language: "fr".to_string(),
+                        strings: HashMap::from([("batch".to_string(), "Lot".to_string())]),
+                    }),
+                ),
+            ]),
+        };
+        let locale = ReportLocale {
+            language: "fr".to_string(),
+            timezone: Some("Pacific/Auckland".to_string()),
+            currency_code: Some("NZD".to_string()),
+        };
+        let resolved_def =
+            resolve_loaded_report_definition("report".to_string(), report, locale).unwrap();
+
+        let doc = generate_report(&resolved_def, serde_json::json!({}), None).unwrap();
+        assert_eq!(
+            doc.document,
+            [
+                "Lot",
+                "Expiry",
+                "Fallback",
+                "1\u{202f}234\u{202f}567,89",
+                "5",
+                "-12,50 NZD",
+                "31/01/2022",
+                // NZDT is UTC+13
+                "01/02/2022 09:00",
+                "2022-02-01T09:00:00",
+            ]
+            .join("|")
+        );
+    }
+
+    #[test]
+    fn barcodes_and_raw_text() {
+        let resolve = |output: ReportOutputType, template: &str| {
+            let report = ReportDefinition {
+                index: ReportDefinitionIndex {
+                    template: Some("label.zpl".to_string()),
+                    header: None,
+                    footer: None,
+                    query: Some("query".to_string()),
+                },
+                entries: HashMap::from([
+                    (
+                        "label.zpl".to_string(),
+                        ReportDefinitionEntry::TeraTemplate(TeraTemplate {
+                            output,
+                            template: template.to_string(),
+                        }),
+                    ),
+                    (
+                        "query".to_string(),
+                        ReportDefinitionEntry::DefaultQuery(DefaultQuery::Invoice),
+                    ),
+                ]),
+            };
+            resolve_loaded_report_definition("label".to_string(), report, ReportLocale::default())
+                .unwrap()
+        };
+
+        let resolved_def = resolve(
+            ReportOutputType::RawText,
+            concat!(
+                "\\x02^XA^FO20,20^BCN,80^FD{{ data.batch }}^FS^XZ\\x03",
+                "|{{ barcode(format=\"code128\", value=data.batch) }}",
+                "|{{ barcode(format=\"qr\", value=1234) }}",
+            ),
+        );
+        assert_eq!(resolved_def.output_type(), ReportOutputType::RawText);
+        let doc =
+            generate_report(&resolved_def, serde_json::json!({ "batch": "B<1>" }), None).unwrap();
+        let parts: Vec<&str> = doc.document.split('|').collect();
+        assert_eq!(parts[0], "\\x02^XA^FO20,20^BCN,80^FDB<1>^FS^XZ\\x03");
+        assert_eq!(
+            unescape_raw_text(parts[0]),
+            b"\x02^XA^FO20,20^BCN,80^FDB<1>^FS^XZ\x03".to_vec()
+        );
+        assert!(parts[1].starts_with("<svg"));
+        assert!(parts[1].contains("B&lt;1&gt;"));
+        assert!(parts[2].starts_with("<svg"));
+
+        let resolved_def = resolve(
+            ReportOutputType::Html,
+            "{{ barcode(format=\"ean13\", value=\"1\") }}",
+        );
+        let message = match generate_report(&resolved_def, serde_json::json!({}), None) {
+            Err(ReportError::DocGenerationError(message)) => message,
+            _ => panic!("Expected a doc generation error"),
+        };
+        assert!(message.contains("unsupported format"));
+    }
+
+    #[test]
+    fn charts() {
+        let resolve = |template: &str| {
+            let report = ReportDefinition {
+                index: ReportDefinitionIndex {
+                    template: Some("template".to_string()),
+                    header: None,
+                    footer: None,
+                    query: Some("query".to_string()),
+                },
+                entries: HashMap::from([
+                    (
+                        "template".to_string(),
+                        ReportDefinitionEntry::TeraTemplate(TeraTemplate {
+                            output: ReportOutputType::Html,
+                            template: template.to_string(),
+                        }),
+                    ),
+                    (
+                        "query".to_string(),
+                        ReportDefinitionEntry::DefaultQuery(DefaultQuery::Invoice),
+                    ),
+                ]),
+            };
+            resolve_loaded_report_definition("report".to_string(), report, ReportLocale::default())
+                .unwrap()
+        };
+
+        let resolved_def = resolve(concat!(
+            "{{ chart(type=\"line\", rows=data.nodes, label=\"date\", ",
+            "values=[\"consumption\", \"averageMonthlyConsumption\"], names=[\"Consumption\"], ",
+            "colors=[\"red\"], title=\"History\") | safe }}",
+            "|{{ chart(type=\"pie\", rows=data.nodes, label=\"date\", values=\"consumption\") | safe }}",
+        ));
+        let data = serde_json::json!({ "nodes": [
+            { "date": "2022-01-01", "consumption": 10, "averageMonthlyConsumption": 10 },
+            { "date": "2022-02-01", "consumption": "30", "averageMonthlyConsumption": 20 },
+            { "date": "2022-03-01", "consumption": null, "averageMonthlyConsumption": 20 },
+        ]});
+        let doc = generate_report(&resolved_def, data, None).unwrap();
+        let parts = doc.document.split('|').collect::<Vec<&str>>();
+        assert!(parts[0].starts_with("<svg"));
+        assert!(parts[0].contains(">History</text>"));
+        assert!(parts[0].contains(">Consumption</text>"));
+        // series without a name use the field name
+        assert!(parts[0].contains(">averageMonthlyConsumption</text>"));
+        assert!(parts[0].contains(r#"stroke="red""#));
+        assert_eq!(parts[1].matches("<path").count(), 2);
+        assert!(parts[1].contains("2022-02-01 (75.0%)"));
+
+        let resolved_def =
+            resolve("{{ chart(type=\"scatter\", rows=[], label=\"x\", values=\"y\") }}");
+        let message = match generate_report(&resolved_def, serde_json::json!({}), None) {
+            Err(ReportError::DocGenerationError(message)) => message,
+            _ => panic!("Expected a doc generation error"),
+        };
+        assert!(message.contains("unsupported chart type"));
+    }
+
+    #[actix_rt::test]
+    async fn print_report_batch() {
+        let (_, connection, connection_manager, _) =
+            setup_all("print_report_batch", MockDataInserts::all()).await;
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider
+            .context("store_a".to_string(), "".to_string())
+            .unwrap();
+        let service = service_provider.report_service;
+
+        // filter is restricted to the current store
+        let ids = service
+            .batch_data_ids(
+                &context,
+                ReportBatchFilter::Invoice(
+                    InvoiceFilter::new()
+                        .r#type(InvoiceRowType::OutboundShipment.equal_to())
+                        .store_id(EqualFilter::equal_to("store_b")),
+                ),
+            )
+            .unwrap();
+        assert!(!ids.is_empty());
+        let invoice_repo = InvoiceRowRepository::new(&connection);
+        for id in &ids {
+            let invoice = invoice_repo.find_one_by_id(id).unwrap();
+            assert_eq!(invoice.store_id, "store_a");
+            assert_eq!(invoice.r#type, InvoiceRowType::OutboundShipment);
+        }
+
+        // failing records are left out of the document
+        let report = ReportDefinition {
+            index: ReportDefinitionIndex {
+                template: Some("template.html".to_string()),
+                header: None,
+                footer: None,
+                query: Some("query".to_string()),
+            },
+            entries: HashMap::from([
+                (
+                    "template.html".to_string(),
+                    ReportDefinitionEntry::TeraTemplate(TeraTemplate {
+                        output: ReportOutputType::Html,
+                        template: "Record {{ data.name }}".to_string(),
+                    }),
+                ),
+                (
+                    "query".to_string(),
+                    ReportDefinitionEntry::DefaultQuery(DefaultQuery::Invoice),
+                ),
+            ]),
+        };
+        let resolved_def =
+            resolve_loaded_report_definition("batch".to_string(), report, ReportLocale::default())
+                .unwrap();
+        let record = |data_id: &str, report_data: serde_json::Value| BatchReportData {
+            data_id: data_id.to_string(),
+            report_data,
+            arguments: None,
+        };
+        let base_dir = Some("print_report_batch".to_string());
+        let result = service
+            .print_html_report_batch(
+                &base_dir,
+                &resolved_def,
+                vec![
+                    record("a", serde_json::json!({ "name": "A" })),
+                    record("b", serde_json::json!({})),
+                    record("c", serde_json::json!({ "name": "C" })),
+                ],
+                Some(PrintFormat::Html),
+            )
+            .unwrap();
+        assert_eq!(result.errors.len(), 1);
+        assert_eq!(result.errors[0].data_id, "b");
+        let file = StaticFileService::new(&base_dir)
+            .unwrap()
+            .find_file(&result.file_id.unwrap())
+            .unwrap()
+            .unwrap();
+        let html = std::fs::read_to_string(file.path).unwrap();
+        std::fs::remove_dir_all("print_report_batch").unwrap();
+        assert!(html.starts_with("<html>"));
+        assert!(html.contains("Record A"));
+        assert!(html.contains("Record C"));
+        assert_eq!(html.matches("<table class=\"paging\"").count(), 2);
+        assert_eq!(html.matches("page-break-after: always").count(), 1);
+
+        // no document if all records fail
+        let result = service
+            .print_html_report_batch(
+                &base_dir,
+                &resolved_def,
+                vec![record("b", serde_json::json!({}))],
+                Some(PrintFormat::Html),
+            )
+            .unwrap();
+        assert_eq!(result.file_id, None);
+        assert_eq!(result.errors.len(), 1);
+    }
+
+    #[actix_rt::test]
+    async fn sql_query_report_data() {
+        let (_, _, connection_manager, _) = setup_all(
+            "sql_query_report_data",
+            MockDataInserts::none().names().stores(),
+        )
+        .await;
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider
+            .context("store_a".to_string(), "".to_string())
+            .unwrap();
+        let service = service_provider.report_service;
+
+        let query = SqlQuery {
+            sqlite: Some(
+                "SELECT store.id, name.name AS store_name FROM store JOIN name ON name.id = store.name_id WHERE store.id = :storeId"
+                    .to_string(),
+            ),
+            postgres: Some(
+                "SELECT store.id, name.name AS store_name FROM store JOIN name ON name.id = store.name_id WHERE store.id = :storeId"
+                    .to_string(),
+            ),
+            columns: None,
+            variables: None,
+        };
+        let variables = query.query_variables("store_a", None, None);
+        let data = service
+            .query_sql_report_data(&context, &query, &variables)
+            .unwrap();
+        assert_eq!(
+            data,
+            serde_json::json!([{ "id": "store_a", "store_name": "Store A" }])
+        );
+
+        // the store id is bound from the context
+        let other_store_variables = serde_json::json!({ "storeId": "store_b" });
+        let data = service
+            .query_sql_report_data(&context, &query, &other_store_variables)
+            .unwrap();
+        assert_eq!(
+            data,
+            serde_json::json!([{ "id": "store_a", "store_name": "Store A" }])
+        );
+
+        // queries must filter by the store id
+        let query = SqlQuery {
+            sqlite: Some("SELECT id FROM store".to_string()),
+            postgres: Some("SELECT id FROM store".to_string()),
+            columns: None,
+            variables: None,
+        };
+        assert!(matches!(
+            service.query_sql_report_data(&context, &query, &variables),
+            Err(ReportError::QueryError(_))
+        ));
+
+        // tables outside the whitelist are rejected
+        let query = SqlQuery {
+            sqlite: Some("SELECT id FROM user_account WHERE id = :storeId".to_string()),
+            postgres: Some("SELECT id FROM user_account WHERE id = :storeId".to_string()),
+            columns: None,
+            variables: None,
+        };
+        assert!(matches!(
+            service.query_sql_report_data(&context, &query, &variables),
+            Err(ReportError::QueryError(_))
+        ));
+    }
 }
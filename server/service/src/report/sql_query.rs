@@ -0,0 +1,751 @@
+use repository::{ReportSqlQueryRepository, REPORT_SQL_TABLES};
+
+/// Statements that are never allowed in a report query, even though the query runs in read only
+/// mode
+const FORBIDDEN_KEYWORDS: &[&str] = &[
+    "alter",
+    "analyze",
+    "attach",
+    "copy",
+    "create",
+    "delete",
+    "detach",
+    "drop",
+    "grant",
+    "insert",
+    "pragma",
+    "recursive",
+    "reindex",
+    "revoke",
+    "table",
+    "truncate",
+    "update",
+    "vacuum",
+];
+
+/// Functions that can be used in report queries. Functions are whitelisted since many database
+/// functions can read files or other tables, or run dynamic SQL, e.g. `ts_stat` in Postgres.
+const ALLOWED_FUNCTIONS: &[&str] = &[
+    // aggregate
+    "avg",
+    "count",
+    "max",
+    "min",
+    "sum",
+    "total",
+    "group_concat",
+    "string_agg",
+    "array_agg",
+    "bool_and",
+    "bool_or",
+    // window
+    "row_number",
+    "rank",
+    "dense_rank",
+    "percent_rank",
+    "cume_dist",
+    "ntile",
+    "lag",
+    "lead",
+    "first_value",
+    "last_value",
+    "nth_value",
+    // conditional
+    "coalesce",
+    "ifnull",
+    "nullif",
+    "iif",
+    "greatest",
+    "least",
+    // numeric
+    "abs",
+    "ceil",
+    "ceiling",
+    "floor",
+    "round",
+    "trunc",
+    "sign",
+    "mod",
+    "power",
+    "sqrt",
+    "exp",
+    "ln",
+    "log",
+    // text
+    "length",
+    "char_length",
+    "lower",
+    "upper",
+    "trim",
+    "ltrim",
+    "rtrim",
+    "substr",
+    "substring",
+    "replace",
+    "instr",
+    "position",
+    "strpos",
+    "concat",
+    "concat_ws",
+    "printf",
+    "lpad",
+    "rpad",
+    // date and time
+    "date",
+    "time",
+    "datetime",
+    "julianday",
+    "strftime",
+    "now",
+    "to_char",
+    "to_date",
+    "to_timestamp",
+    "date_trunc",
+    "date_part",
+    "extract",
+    "age",
+    "make_date",
+    // types
+    "cast",
+    "typeof",
+    // json
+    "json_extract",
+    "json_object",
+    "json_array",
+    "json_group_array",
+    "json_build_object",
+    "json_agg",
+];
+
+/// Keywords that can be followed by an opening bracket without being a function call
+const KEYWORDS_BEFORE_BRACKET: &[&str] = &[
+    "all",
+    "and",
+    "any",
+    "as",
+    "between",
+    "by",
+    "case",
+    "else",
+    "exists",
+    "filter",
+    "from",
+    "group",
+    "having",
+    "in",
+    "is",
+    "join",
+    "like",
+    "limit",
+    "not",
+    "offset",
+    "on",
+    "or",
+    "over",
+    "select",
+    "then",
+    "union",
+    "intersect",
+    "except",
+    "using",
+    "when",
+    "where",
+    "with",
+];
+
+/// Keywords that can't be a column name at the end of a select list item
+const NON_COLUMN_KEYWORDS: &[&str] = &["end", "null", "true", "false", "distinct", "all"];
+
+#[derive(Debug, Clone, PartialEq)]
+enum TokenKind {
+    /// Keyword or unquoted identifier, lower case
+    Word(String),
+    /// Double quoted identifier
+    QuotedIdentifier(String),
+    StringLiteral,
+    Number,
+    /// Named parameter, e.g. `:storeId`
+    Parameter(String),
+    Symbol(char),
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+    kind: TokenKind,
+    start: usize,
+    end: usize,
+}
+
+impl Token {
+    fn is_word(&self, word: &str) -> bool {
+        matches!(&self.kind, TokenKind::Word(w) if w == word)
+    }
+
+    fn is_symbol(&self, symbol: char) -> bool {
+        self.kind == TokenKind::Symbol(symbol)
+    }
+
+    fn identifier(&self) -> Option<&str> {
+        match &self.kind {
+            TokenKind::Word(name) | TokenKind::QuotedIdentifier(name) => Some(name),
+            _ => None,
+        }
+    }
+}
+
+fn is_identifier_start(c: char) -> bool {
+    c.is_ascii_alphabetic() || c == '_'
+}
+
+fn is_identifier_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+/// Splits the query into tokens, comments are skipped.
+///
+/// The tokenizer is stricter than sqlite and Postgres, e.g. it rejects backslashes in string
+/// literals and dollar quoting. This ensures the query isn't interpreted differently by the
+/// database.
+fn tokenize(query: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<(usize, char)> = query.char_indices().collect();
+    let byte_at = |index: usize| chars.get(index).map(|(i, _)| *i).unwrap_or(query.len());
+    let mut tokens = Vec::new();
+    let mut index = 0;
+    while index < chars.len() {
+        let (start, c) = chars[index];
+        let next = chars.get(index + 1).map(|(_, c)| *c);
+        if c.is_whitespace() {
+            index += 1;
+            continue;
+        }
+        // comments
+        if c == '-' && next == Some('-') {
+            while index < chars.len() && chars[index].1 != '\n' {
+                index += 1;
+            }
+            continue;
+        }
+        if c == '/' && next == Some('*') {
+            index += 2;
+            loop {
+                match chars.get(index) {
+                    Some((_, '*')) if chars.get(index + 1).map(|(_, c)| *c) == Some('/') => {
+                        index += 2;
+                        break;
+                    }
+                    Some(_) => index += 1,
+                    None => return Err("Unterminated comment".to_string()),
+                }
+            }
+            continue;
+        }
+
+        let kind = if c == '\'' || c == '"' {
+            let mut value = String::new();
+            index += 1;
+            loop {
+                match chars.get(index) {
+                    Some((_, current)) if *current == c => {
+                        // doubled quote is an escaped quote
+                        if chars.get(index + 1).map(|(_, c)| *c) == Some(c) {
+                            value.push(c);
+                            index += 2;
+                        } else {
+                            index += 1;
+                            break;
+                        }
+                    }
+                    Some((_, '\\')) => {
+                        return Err("Backslashes are not allowed in strings".to_string())
+                    }
+                    Some((_, current)) => {
+                        value.push(*current);
+                        index += 1;
+                    }
+                    None => return Err("Unterminated string".to_string()),
+                }
+            }
+            if c == '\'' {
+                TokenKind::StringLiteral
+            } else {
+                TokenKind::QuotedIdentifier(value.to_lowercase())
+            }
+        } else if is_identifier_start(c) {
+            let word_start = index;
+            while index < chars.len() && is_identifier_char(chars[index].1) {
+                index += 1;
+            }
+            let word: String = chars[word_start..index].iter().map(|(_, c)| *c).collect();
+            TokenKind::Word(word.to_lowercase())
+        } else if c.is_ascii_digit() {
+            let char_at = |index: usize| chars.get(index).map(|(_, c)| *c);
+            while char_at(index)
+                .map(|c| c.is_ascii_digit() || c == '.')
+                .unwrap_or(false)
+            {
+                index += 1;
+            }
+            // exponent
+            if matches!(char_at(index), Some('e') | Some('E')) {
+                let digits = match char_at(index + 1) {
+                    Some('+') | Some('-') => index + 2,
+                    _ => index + 1,
+                };
+                if char_at(digits).map(|c| c.is_ascii_digit()).unwrap_or(false) {
+                    index = digits;
+                    while char_at(index).map(|c| c.is_ascii_digit()).unwrap_or(false) {
+                        index += 1;
+                    }
+                }
+            }
+            // e.g. `1from` is a number followed by a keyword in Postgres
+            if char_at(index).map(is_identifier_char).unwrap_or(false) {
+                return Err("Numbers must be separated from keywords".to_string());
+            }
+            TokenKind::Number
+        } else if c == ':' && next == Some(':') {
+            // Postgres cast
+            index += 2;
+            tokens.push(Token {
+                kind: TokenKind::Symbol(':'),
+                start,
+                end: byte_at(index),
+            });
+            continue;
+        } else if c == ':' && next.map(is_identifier_start).unwrap_or(false) {
+            index += 1;
+            let name_start = index;
+            while index < chars.len() && is_identifier_char(chars[index].1) {
+                index += 1;
+            }
+            let name: String = chars[name_start..index].iter().map(|(_, c)| *c).collect();
+            TokenKind::Parameter(name)
+        } else if "(),.*=<>!+-/%|".contains(c) {
+            index += 1;
+            TokenKind::Symbol(c)
+        } else if c == ';' {
+            // allow a single trailing semicolon
+            if chars[index + 1..].iter().all(|(_, c)| c.is_whitespace()) {
+                break;
+            }
+            return Err("Only a single statement is allowed".to_string());
+        } else {
+            return Err(format!("Unexpected character `{}`", c));
+        };
+        tokens.push(Token {
+            kind,
+            start,
+            end: byte_at(index),
+        });
+    }
+    Ok(tokens)
+}
+
+/// Index of the closing bracket matching the opening bracket at `open`
+fn closing_bracket(tokens: &[Token], open: usize) -> Result<usize, String> {
+    let mut depth = 0;
+    for (index, token) in tokens.iter().enumerate().skip(open) {
+        if token.is_symbol('(') {
+            depth += 1;
+        } else if token.is_symbol(')') {
+            depth -= 1;
+            if depth == 0 {
+                return Ok(index);
+            }
+        }
+    }
+    Err("Unbalanced brackets".to_string())
+}
+
+/// Common table expression, e.g. `monthly` in `WITH monthly AS (...)`
+struct Cte {
+    name: String,
+    /// Token index of the closing bracket of the CTE definition
+    definition_end: usize,
+    /// Token index of the end of the statement the CTE is defined in
+    scope_end: usize,
+}
+
+impl Cte {
+    /// A CTE can only be referenced after its definition and within its statement, otherwise the
+    /// database resolves the name to a table
+    fn is_visible_at(&self, index: usize) -> bool {
+        index > self.definition_end && index <= self.scope_end
+    }
+}
+
+fn ctes(tokens: &[Token]) -> Result<Vec<Cte>, String> {
+    let mut ctes = Vec::new();
+    for index in 0..tokens.len().saturating_sub(2) {
+        if !(tokens[index + 1].is_word("as") && tokens[index + 2].is_symbol('(')) {
+            continue;
+        }
+        let name = match tokens[index].identifier() {
+            Some(name) => name.to_string(),
+            None => continue,
+        };
+        // find the bracket enclosing the WITH statement
+        let mut depth = 0;
+        let mut scope_end = tokens.len();
+        for open in (0..index).rev() {
+            if tokens[open].is_symbol(')') {
+                depth += 1;
+            } else if tokens[open].is_symbol('(') {
+                if depth == 0 {
+                    scope_end = closing_bracket(tokens, open)?;
+                    break;
+                }
+                depth -= 1;
+            }
+        }
+        ctes.push(Cte {
+            name,
+            definition_end: closing_bracket(tokens, index + 2)?,
+            scope_end,
+        });
+    }
+    Ok(ctes)
+}
+
+/// Checks the table list following a FROM or JOIN keyword or an opening bracket of a
+/// parenthesized join
+fn check_table_references(tokens: &[Token], mut index: usize, ctes: &[Cte]) -> Result<(), String> {
+    loop {
+        let token = tokens.get(index).ok_or("Missing table name")?;
+        if token.is_symbol('(') {
+            let is_sub_query = tokens
+                .get(index + 1)
+                .map(|next| next.is_word("select") || next.is_word("with"))
+                .unwrap_or(false);
+            // the content of a sub query is checked separately, a parenthesized join starts with
+            // a table list, e.g. `FROM (item i JOIN store s ON ...)`. Tables joined inside the
+            // brackets follow a JOIN keyword and are checked with it.
+            if !is_sub_query {
+                check_table_references(tokens, index + 1, ctes)?;
+            }
+            index = closing_bracket(tokens, index)? + 1;
+        } else {
+            let table = token
+                .identifier()
+                .ok_or_else(|| "Expected a table name".to_string())?;
+            match tokens.get(index + 1) {
+                Some(next) if next.is_symbol('.') => {
+                    return Err(format!(
+                        "Schema qualified table names are not allowed: {}",
+                        table
+                    ))
+                }
+                Some(next) if next.is_symbol('(') => {
+                    return Err(format!("Table functions are not allowed: {}", table))
+                }
+                _ => {}
+            }
+            let is_cte = ctes
+                .iter()
+                .any(|cte| cte.name == table && cte.is_visible_at(index));
+            if !REPORT_SQL_TABLES.contains(&table) && !is_cte {
+                return Err(format!("Table is not allowed in report queries: {}", table));
+            }
+            index += 1;
+        }
+        // optional alias
+        if tokens.get(index).map(|t| t.is_word("as")).unwrap_or(false) {
+            index += 1;
+        }
+        if let Some(TokenKind::Word(word)) = tokens.get(index).map(|t| &t.kind) {
+            if !is_keyword_after_table(word) {
+                index += 1;
+            }
+        }
+        // comma separated table list
+        match tokens.get(index) {
+            Some(token) if token.is_symbol(',') => index += 1,
+            _ => return Ok(()),
+        }
+    }
+}
+
+fn is_keyword_after_table(word: &str) -> bool {
+    [
+        "where",
+        "join",
+        "inner",
+        "left",
+        "right",
+        "full",
+        "cross",
+        "natural",
+        "on",
+        "using",
+        "group",
+        "order",
+        "limit",
+        "offset",
+        "having",
+        "union",
+        "intersect",
+        "except",
+        "window",
+    ]
+    .contains(&word)
+}
+
+fn validate(tokens: &[Token]) -> Result<(), String> {
+    match tokens.first() {
+        Some(token) if token.is_word("select") || token.is_word("with") => {}
+        _ => return Err("Query must start with SELECT or WITH".to_string()),
+    }
+    let ctes = ctes(tokens)?;
+    for (index, token) in tokens.iter().enumerate() {
+        if let TokenKind::Word(word) = &token.kind {
+            if FORBIDDEN_KEYWORDS.contains(&word.as_str()) {
+                return Err(format!(
+                    "Keyword is not allowed in report queries: {}",
+                    word
+                ));
+            }
+            let is_function_call = tokens
+                .get(index + 1)
+                .map(|next| next.is_symbol('('))
+                .unwrap_or(false);
+            if is_function_call
+                && !KEYWORDS_BEFORE_BRACKET.contains(&word.as_str())
+                && !ALLOWED_FUNCTIONS.contains(&word.as_str())
+            {
+                return Err(format!(
+                    "Function is not allowed in report queries: {}",
+                    word
+                ));
+            }
+            // sqlite allows `x IN table`, only lists and sub queries are allowed
+            if word == "in" && !is_function_call {
+                return Err("IN must be followed by a list or a sub query".to_string());
+            }
+            if word == "from" || word == "join" {
+                check_table_references(tokens, index + 1, &ctes)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Infers the result column names from the select list of the main query
+fn infer_columns(tokens: &[Token]) -> Result<Vec<String>, String> {
+    // the main select is the first select that is not inside brackets, i.e. not in a CTE
+    let mut depth = 0;
+    let mut select = None;
+    for (index, token) in tokens.iter().enumerate() {
+        if token.is_symbol('(') {
+            depth += 1;
+        } else if token.is_symbol(')') {
+            depth -= 1;
+        } else if depth == 0 && token.is_word("select") {
+            select = Some(index);
+            break;
+        }
+    }
+    let mut index = select.ok_or("Missing SELECT")? + 1;
+    if tokens
+        .get(index)
+        .map(|t| t.is_word("distinct") || t.is_word("all"))
+        .unwrap_or(false)
+    {
+        index += 1;
+    }
+
+    // split the select list into items
+    let mut items: Vec<Vec<&Token>> = vec![vec![]];
+    let mut depth = 0;
+    for token in &tokens[index..] {
+        if depth == 0 && (token.is_word("from") || token.is_word("union")) {
+            break;
+        }
+        if token.is_symbol('(') {
+            depth += 1;
+        } else if token.is_symbol(')') {
+            depth -= 1;
+        }
+        if depth == 0 && token.is_symbol(',') {
+            items.push(vec![]);
+            continue;
+        }
+        items.last_mut().unwrap().push(token);
+    }
+
+    items
+        .into_iter()
+        .map(|item| {
+            let error = || {
+                "Can't infer the column names, use `AS name` for expressions or specify the columns"
+                    .to_string()
+            };
+            let last = item.last().ok_or_else(error)?;
+            let name = last.identifier().ok_or_else(error)?;
+            if NON_COLUMN_KEYWORDS.contains(&name) {
+                return Err(error());
+            }
+            Ok(name.to_string())
+        })
+        .collect()
+}
+
+/// Validated report SQL query ready to be run by the `ReportSqlQueryRepository`
+#[derive(Debug, PartialEq)]
+pub struct PreparedSqlQuery {
+    /// Query with the named parameters replaced by database specific placeholders
+    pub query: String,
+    pub columns: Vec<String>,
+    /// Names of all parameters used in the query
+    pub parameters: Vec<String>,
+}
+
+/// Validates the query, i.e. checks that it is a single select statement that only reads from
+/// the whitelisted tables and views, and replaces the named parameters with placeholders.
+///
+/// # Arguments
+/// * `columns` - result column names, inferred from the select list if not set
+pub fn prepare_sql_query(
+    query: &str,
+    columns: Option<&Vec<String>>,
+) -> Result<PreparedSqlQuery, String> {
+    let tokens = tokenize(query)?;
+    validate(&tokens)?;
+    let columns = match columns {
+        Some(columns) => columns.clone(),
+        None => infer_columns(&tokens)?,
+    };
+    if let Some(column) = columns.iter().find(|column| {
+        column.is_empty()
+            || !column.starts_with(is_identifier_start)
+            || !column.chars().all(is_identifier_char)
+    }) {
+        return Err(format!("Invalid column name: {}", column));
+    }
+
+    let mut prepared = String::new();
+    let mut parameters = Vec::new();
+    let mut position = 0;
+    for token in &tokens {
+        if let TokenKind::Parameter(name) = &token.kind {
+            prepared.push_str(&query[position..token.start]);
+            prepared.push_str(&ReportSqlQueryRepository::parameter_placeholder(name));
+            position = token.end;
+            if !parameters.contains(name) {
+                parameters.push(name.clone());
+            }
+        }
+    }
+    let end = tokens.last().map(|token| token.end).unwrap_or(0);
+    prepared.push_str(&query[position..end]);
+
+    Ok(PreparedSqlQuery {
+        query: prepared,
+        columns,
+        parameters,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use repository::ReportSqlQueryRepository;
+
+    use super::prepare_sql_query;
+
+    #[test]
+    fn prepare_valid_queries() {
+        let query = "
+            -- monthly consumption
+            WITH monthly AS (
+                SELECT item_id, strftime('%Y-%m', date) AS month, SUM(quantity) AS quantity
+                FROM consumption c
+                WHERE c.store_id = :storeId AND date >= :fromDate
+                GROUP BY item_id, month
+            )
+            SELECT m.item_id, i.name AS item_name, m.month, m.quantity total /* implicit alias */
+            FROM monthly m JOIN item i ON i.id = m.item_id
+            WHERE i.id IN (SELECT item_id FROM stock_line WHERE store_id = :storeId)
+            ORDER BY m.month;
+        ";
+        let prepared = prepare_sql_query(query, None).unwrap();
+        assert_eq!(
+            prepared.columns,
+            vec!["item_id", "item_name", "month", "total"]
+        );
+        assert_eq!(prepared.parameters, vec!["storeId", "fromDate"]);
+        let placeholder = ReportSqlQueryRepository::parameter_placeholder("storeId");
+        assert_eq!(prepared.query.matches(&placeholder).count(), 2);
+        assert!(!prepared.query.contains(":storeId"));
+        assert!(prepared.query.trim_end().ends_with("ORDER BY m.month"));
+
+        // Postgres casts and explicit columns
+        let prepared = prepare_sql_query(
+            "SELECT * FROM stock_movement, store s WHERE quantity::text = '1' AND 1.5e3 > 2",
+            Some(&vec!["id".to_string()]),
+        )
+        .unwrap();
+        assert_eq!(prepared.columns, vec!["id"]);
+        assert_eq!(prepared.parameters, Vec::<String>::new());
+
+        // parenthesized joins of whitelisted tables
+        let prepared = prepare_sql_query(
+            "SELECT i.id FROM (item i JOIN stock_line sl ON sl.item_id = i.id) WHERE i.id IN ('a', 'b')",
+            None,
+        )
+        .unwrap();
+        assert_eq!(prepared.columns, vec!["id"]);
+    }
+
+    #[test]
+    fn reject_invalid_queries() {
+        let invalid = [
+            "DELETE FROM item",
+            "SELECT id FROM item; DELETE FROM item",
+            "SELECT id FROM user_account",
+            "SELECT id FROM item, user_account",
+            "SELECT id FROM item JOIN \"USER_ACCOUNT\" u ON 1 = 1",
+            "SELECT id FROM (SELECT id FROM key_value_store) k",
+            "SELECT id FROM main.item",
+            "SELECT name FROM sqlite_master",
+            "SELECT value FROM item, json_each(item.name)",
+            "SELECT pg_read_file('/etc/passwd') AS file",
+            "SELECT query_to_xml('select 1', true, true, '') AS xml",
+            "SELECT id FROM item WHERE name = E'\\' FROM user_account'",
+            "SELECT $$text$$ AS text",
+            "WITH x AS (INSERT INTO item DEFAULT VALUES RETURNING id) SELECT id FROM x",
+            "SELECT id FROM item /* unterminated",
+            "SELECT id FROM item WHERE id IN (TABLE user_account)",
+            "SELECT 1from user_account",
+            // CTEs can't shadow tables outside of their scope
+            "WITH user_account AS (SELECT id FROM user_account) SELECT id FROM user_account",
+            "WITH a AS (SELECT id FROM user_account), user_account AS (SELECT 1 AS id) SELECT id FROM a",
+            "SELECT t.id FROM (WITH user_account AS (SELECT 1 AS id) SELECT id FROM user_account) t, user_account",
+            "WITH RECURSIVE user_account AS (SELECT id FROM user_account) SELECT id FROM user_account",
+            // parenthesized joins
+            "SELECT u.username, u.hashed_password FROM (user_account u JOIN item i ON 1=1)",
+            "SELECT i.id FROM item i JOIN (user_account u CROSS JOIN store s) ON 1=1",
+            "SELECT i.id FROM ((user_account u JOIN item i ON 1=1))",
+            // sqlite `IN table`
+            "SELECT id FROM item WHERE 'x' IN key_value_store",
+            "SELECT id FROM item WHERE 'x' NOT IN main.key_value_store",
+            // functions running dynamic SQL
+            "SELECT (ts_stat('SELECT to_tsvector(hashed_password) FROM user_account')).word",
+            "SELECT ts_rewrite('a', 'SELECT 1') AS word",
+        ];
+        for query in invalid {
+            assert!(
+                prepare_sql_query(query, None).is_err(),
+                "Query should be rejected: {}",
+                query
+            );
+        }
+
+        // columns must be named
+        assert!(prepare_sql_query("SELECT * FROM item", None).is_err());
+        assert!(prepare_sql_query("SELECT count(*) FROM item", None).is_err());
+        assert!(prepare_sql_query(
+            "SELECT id FROM item",
+            Some(&vec!["id\" FROM item --".to_string()])
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,296 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use chrono_tz::Tz;
+use serde_json::Value;
+use tera::Function;
+
+use super::{
+    barcodes::{barcode_svg, BarcodeFormat, BarcodeOptions},
+    charts::{chart_svg, ChartData, ChartOptions, ChartSeries, ChartType},
+    locale::{format_number, locale_format, ReportLocale, DEFAULT_LANGUAGE},
+};
+
+/// Tera function to access a report resource by its name, e.g. the data URI of an embedded image:
+/// `<img src="{{ asset(name="images/logo.png") | safe }}"/>`
+pub fn asset_function(resources: HashMap<String, Value>) -> impl Function {
+    move |args: &HashMap<String, Value>| -> tera::Result<Value> {
+        let name = string_arg("asset", args, "name")?;
+        resources
+            .get(&name)
+            .cloned()
+            .ok_or_else(|| tera::Error::msg(format!("asset: resource `{}` not found", name)))
+    }
+}
+
+/// Tera function to translate a string into the user language:
+/// `{{ t(key="label.batch", fallback="Batch") }}`
+///
+/// If there is no translation for the user language the default language is used, then the
+/// fallback and finally the key itself.
+pub fn translate_function(
+    translations: HashMap<String, HashMap<String, String>>,
+    locale: ReportLocale,
+) -> impl Function {
+    move |args: &HashMap<String, Value>| -> tera::Result<Value> {
+        let key = string_arg("t", args, "key")?;
+        let translated = [locale.language.as_str(), DEFAULT_LANGUAGE]
+            .iter()
+            .find_map(|language| translations.get(*language)?.get(&key).cloned())
+            .or_else(|| {
+                args.get("fallback")
+                    .and_then(Value::as_str)
+                    .map(str::to_string)
+            })
+            .unwrap_or(key);
+        Ok(Value::String(translated))
+    }
+}
+
+/// Tera function to format a number in the user locale:
+/// `{{ format_number(value=line.numberOfPacks, decimals=0) }}`
+pub fn format_number_function(locale: ReportLocale) -> impl Function {
+    move |args: &HashMap<String, Value>| -> tera::Result<Value> {
+        let value = number_arg("format_number", args, "value")?;
+        let decimals = decimals_arg(args, 2);
+        let format = locale_format(&locale.language);
+        Ok(Value::String(format_number(value, decimals, &format)))
+    }
+}
+
+/// Tera function to format an amount in the store currency and user locale:
+/// `{{ format_currency(value=line.totalAfterTax) }}`
+pub fn format_currency_function(locale: ReportLocale) -> impl Function {
+    move |args: &HashMap<String, Value>| -> tera::Result<Value> {
+        let value = number_arg("format_currency", args, "value")?;
+        let decimals = decimals_arg(args, 2);
+        let format = locale_format(&locale.language);
+        let amount = format_number(value, decimals, &format);
+        let formatted = match &locale.currency_code {
+            Some(code) if format.currency_after_amount => format!("{} {}", amount, code),
+            Some(code) => format!("{} {}", code, amount),
+            None => amount,
+        };
+        Ok(Value::String(formatted))
+    }
+}
+
+/// Tera function to format a date or a UTC datetime in the user locale.
+/// Datetimes are converted to the store timezone.
+/// The locale date format can be overwritten using chrono format strings:
+/// `{{ format_date(value=data.invoice.createdDatetime, format="%d %b %Y") }}`
+pub fn format_date_function(locale: ReportLocale) -> impl Function {
+    move |args: &HashMap<String, Value>| -> tera::Result<Value> {
+        let value = string_arg("format_date", args, "value")?;
+        let format = args.get("format").and_then(Value::as_str);
+        let locale_format = locale_format(&locale.language);
+
+        if let Some(datetime) = parse_utc_datetime(&value) {
+            let local = to_timezone(datetime, &locale)?;
+            let format = format.unwrap_or(locale_format.datetime_format);
+            return Ok(Value::String(local.format(format).to_string()));
+        }
+        let date = NaiveDate::parse_from_str(&value, "%Y-%m-%d").map_err(|_| {
+            tera::Error::msg(format!("format_date: invalid date or datetime `{}`", value))
+        })?;
+        let format = format.unwrap_or(locale_format.date_format);
+        Ok(Value::String(date.format(format).to_string()))
+    }
+}
+
+/// Tera function to convert a UTC datetime to the store timezone, e.g. to be used with the Tera
+/// `date` filter: `{{ to_store_timezone(value=data.invoice.createdDatetime) | date }}`
+pub fn to_store_timezone_function(locale: ReportLocale) -> impl Function {
+    move |args: &HashMap<String, Value>| -> tera::Result<Value> {
+        let value = string_arg("to_store_timezone", args, "value")?;
+        let datetime = parse_utc_datetime(&value).ok_or_else(|| {
+            tera::Error::msg(format!("to_store_timezone: invalid datetime `{}`", value))
+        })?;
+        let local = to_timezone(datetime, &locale)?;
+        Ok(Value::String(local.format("%Y-%m-%dT%H:%M:%S").to_string()))
+    }
+}
+
+/// Tera function to render a barcode as inline SVG:
+/// `{{ barcode(format="gs1-128", value="(01)09501101530003(10)" ~ line.batch) | safe }}`
+///
+/// Supported formats are "code128", "gs1-128", "datamatrix", "gs1-datamatrix" and "qr". GS1 data
+/// is passed in the human readable form with the application identifiers in brackets.
+/// Optional arguments: `module_size` (px, default 2), `height` (bar height of linear barcodes in
+/// px, default 60) and `show_text` (print the value below linear barcodes, default true).
+pub fn barcode_function() -> impl Function {
+    move |args: &HashMap<String, Value>| -> tera::Result<Value> {
+        let format = string_arg("barcode", args, "format")?;
+        let format = BarcodeFormat::parse(&format)
+            .ok_or_else(|| tera::Error::msg(format!("barcode: unsupported format `{}`", format)))?;
+        let value = match args.get("value") {
+            Some(Value::String(value)) => value.clone(),
+            Some(Value::Number(value)) => value.to_string(),
+            _ => return Err(tera::Error::msg("barcode: missing string argument `value`")),
+        };
+        let defaults = BarcodeOptions::default();
+        let options = BarcodeOptions {
+            module_size: args
+                .get("module_size")
+                .and_then(Value::as_f64)
+                .unwrap_or(defaults.module_size),
+            height: args
+                .get("height")
+                .and_then(Value::as_f64)
+                .unwrap_or(defaults.height),
+            show_text: args
+                .get("show_text")
+                .and_then(Value::as_bool)
+                .unwrap_or(defaults.show_text),
+        };
+        let svg = barcode_svg(format, &value, &options)
+            .map_err(|err| tera::Error::msg(format!("barcode: {}", err)))?;
+        Ok(Value::String(svg))
+    }
+}
+
+/// Tera function to render a line, bar or pie chart as inline SVG from a list of rows, e.g. the
+/// consumption history of a requisition line:
+/// `{{ chart(type="line", rows=data.consumptionHistory.nodes, label="date",
+/// values=["consumption", "averageMonthlyConsumption"], names=["Consumption", "AMC"]) | safe }}`
+///
+/// `label` is the field used for the x axis (or the pie slices) and `values` the fields of the
+/// series, nested fields can be accessed using dots, e.g. `item.name`. Pie charts only use the
+/// first series.
+/// Optional arguments: `names` (legend names of the series, default are the field names),
+/// `colors`, `title`, `x_label`, `y_label`, `width` and `height` (px, default 600x300) and
+/// `legend` (show the legend, default true).
+pub fn chart_function(locale: ReportLocale) -> impl Function {
+    move |args: &HashMap<String, Value>| -> tera::Result<Value> {
+        let chart_type = string_arg("chart", args, "type")?;
+        let chart_type = ChartType::parse(&chart_type).ok_or_else(|| {
+            tera::Error::msg(format!("chart: unsupported chart type `{}`", chart_type))
+        })?;
+        let rows = args
+            .get("rows")
+            .and_then(Value::as_array)
+            .ok_or_else(|| tera::Error::msg("chart: missing array argument `rows`"))?;
+        let label = string_arg("chart", args, "label")?;
+        let fields = match args.get("values") {
+            Some(Value::String(field)) => vec![field.clone()],
+            Some(Value::Array(_)) => string_list_arg(args, "values"),
+            _ => return Err(tera::Error::msg("chart: missing array argument `values`")),
+        };
+        let names = string_list_arg(args, "names");
+
+        let labels = rows
+            .iter()
+            .map(|row| match field_value(row, &label) {
+                Some(Value::String(label)) => label.clone(),
+                Some(Value::Null) | None => "".to_string(),
+                Some(value) => value.to_string(),
+            })
+            .collect();
+        let series = fields
+            .iter()
+            .enumerate()
+            .map(|(index, field)| ChartSeries {
+                name: names.get(index).unwrap_or(field).clone(),
+                values: rows
+                    .iter()
+                    .map(|row| match field_value(row, field) {
+                        Some(Value::Number(value)) => value.as_f64(),
+                        Some(Value::String(value)) => value.parse().ok(),
+                        _ => None,
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        let defaults = ChartOptions::default();
+        let options = ChartOptions {
+            width: args
+                .get("width")
+                .and_then(Value::as_f64)
+                .unwrap_or(defaults.width),
+            height: args
+                .get("height")
+                .and_then(Value::as_f64)
+                .unwrap_or(defaults.height),
+            title: optional_string_arg(args, "title"),
+            x_label: optional_string_arg(args, "x_label"),
+            y_label: optional_string_arg(args, "y_label"),
+            colors: string_list_arg(args, "colors"),
+            show_legend: args
+                .get("legend")
+                .and_then(Value::as_bool)
+                .unwrap_or(defaults.show_legend),
+        };
+        let svg = chart_svg(
+            chart_type,
+            &ChartData { labels, series },
+            &options,
+            &locale_format(&locale.language),
+        )
+        .map_err(|err| tera::Error::msg(format!("chart: {}", err)))?;
+        Ok(Value::String(svg))
+    }
+}
+
+/// Value of a (dot separated) field path in a json object
+fn field_value<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.').try_fold(value, |value, key| value.get(key))
+}
+
+fn string_arg(function: &str, args: &HashMap<String, Value>, name: &str) -> tera::Result<String> {
+    args.get(name)
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .ok_or_else(|| {
+            tera::Error::msg(format!("{}: missing string argument `{}`", function, name))
+        })
+}
+
+fn optional_string_arg(args: &HashMap<String, Value>, name: &str) -> Option<String> {
+    args.get(name).and_then(Value::as_str).map(str::to_string)
+}
+
+fn string_list_arg(args: &HashMap<String, Value>, name: &str) -> Vec<String> {
+    args.get(name)
+        .and_then(Value::as_array)
+        .map(|values| {
+            values
+                .iter()
+                .filter_map(|value| value.as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn number_arg(function: &str, args: &HashMap<String, Value>, name: &str) -> tera::Result<f64> {
+    args.get(name).and_then(Value::as_f64).ok_or_else(|| {
+        tera::Error::msg(format!("{}: missing number argument `{}`", function, name))
+    })
+}
+
+fn decimals_arg(args: &HashMap<String, Value>, default: usize) -> usize {
+    args.get("decimals")
+        .and_then(Value::as_u64)
+        .map(|decimals| decimals as usize)
+        .unwrap_or(default)
+}
+
+/// Parses datetimes as returned by the graphql api, datetimes without offset are UTC
+fn parse_utc_datetime(value: &str) -> Option<DateTime<Utc>> {
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(value) {
+        return Some(datetime.with_timezone(&Utc));
+    }
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f")
+        .ok()
+        .map(|datetime| DateTime::<Utc>::from_utc(datetime, Utc))
+}
+
+fn to_timezone(datetime: DateTime<Utc>, locale: &ReportLocale) -> tera::Result<NaiveDateTime> {
+    let timezone = match &locale.timezone {
+        Some(timezone) => timezone
+            .parse::<Tz>()
+            .map_err(|err| tera::Error::msg(format!("Invalid store timezone: {}", err)))?,
+        None => return Ok(datetime.naive_utc()),
+    };
+    Ok(datetime.with_timezone(&timezone).naive_local())
+}
@@ -54,7 +54,7 @@ pub fn get_stock_evolution_for_item(
             points.last_historic_datetime,
         ));
 
-    let stock_on_hand_rows = StockMovementRepository::new(&connection).query(Some(filter))?;
+    let stock_on_hand_rows = StockMovementRepository::new(&connection).query_by_filter(filter)?;
     // Calculate
     Ok(StockEvolutionResult {
         historic_stock: calculate_historic_stock_evolution(
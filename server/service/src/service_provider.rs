@@ -2,6 +2,8 @@ use crate::{
     app_data::{AppDataService, AppDataServiceTrait},
     auth::{AuthService, AuthServiceTrait},
     barcode::{BarcodeService, BarcodeServiceTrait},
+    cold_chain::{ColdChainService, ColdChainServiceTrait},
+    cycle_count::{CycleCountService, CycleCountServiceTrait},
     dashboard::{
         invoice_count::{InvoiceCountService, InvoiceCountServiceTrait},
         item_count::{ItemCountServiceTrait, ItemServiceCount},
@@ -9,8 +11,13 @@ use crate::{
         stock_expiry_count::{StockExpiryCountServiceTrait, StockExpiryServiceCount},
     },
     display_settings_service::{DisplaySettingsService, DisplaySettingsServiceTrait},
+    expired_stock::{ExpiredStockService, ExpiredStockServiceTrait},
+    historical_stock::{HistoricalStockService, HistoricalStockServiceTrait},
+    hold::{HoldService, HoldServiceTrait},
+    inventory_adjustment::{InventoryAdjustmentService, InventoryAdjustmentServiceTrait},
     invoice::{InvoiceService, InvoiceServiceTrait},
     invoice_line::{InvoiceLineService, InvoiceLineServiceTrait},
+    item_ledger::{ItemLedgerService, ItemLedgerServiceTrait},
     item_stats::{ItemStatsService, ItemStatsServiceTrait},
     location::{LocationService, LocationServiceTrait},
     master_list::{MasterListService, MasterListServiceTrait},
@@ -31,6 +38,7 @@ use crate::{
         synchroniser_driver::{SiteIsInitialisedTrigger, SyncTrigger},
     },
     system_user::create_system_user,
+    traceability::{TraceabilityService, TraceabilityServiceTrait},
     ListError, ListResult,
 };
 use repository::{
@@ -43,10 +51,12 @@ pub struct ServiceProvider {
     pub validation_service: Box<dyn AuthServiceTrait>,
 
     pub location_service: Box<dyn LocationServiceTrait>,
+    pub cold_chain_service: Box<dyn ColdChainServiceTrait>,
     pub invoice_service: Box<dyn InvoiceServiceTrait>,
     pub master_list_service: Box<dyn MasterListServiceTrait>,
     pub stocktake_service: Box<dyn StocktakeServiceTrait>,
     pub stocktake_line_service: Box<dyn StocktakeLineServiceTrait>,
+    pub cycle_count_service: Box<dyn CycleCountServiceTrait>,
     pub invoice_line_service: Box<dyn InvoiceLineServiceTrait>,
     pub requisition_service: Box<dyn RequisitionServiceTrait>,
     pub requisition_line_service: Box<dyn RequisitionLineServiceTrait>,
@@ -60,6 +70,12 @@ pub struct ServiceProvider {
     pub item_stats_service: Box<dyn ItemStatsServiceTrait>,
     // Stock
     pub stock_line_service: Box<dyn StockLineServiceTrait>,
+    pub item_ledger_service: Box<dyn ItemLedgerServiceTrait>,
+    pub expired_stock_service: Box<dyn ExpiredStockServiceTrait>,
+    pub traceability_service: Box<dyn TraceabilityServiceTrait>,
+    pub hold_service: Box<dyn HoldServiceTrait>,
+    pub historical_stock_service: Box<dyn HistoricalStockServiceTrait>,
+    pub inventory_adjustment_service: Box<dyn InventoryAdjustmentServiceTrait>,
     // Reports
     pub report_service: Box<dyn ReportServiceTrait>,
     // Settings
@@ -110,6 +126,8 @@ impl ServiceProvider {
             connection_manager: connection_manager.clone(),
             validation_service: Box::new(AuthService::new()),
             location_service: Box::new(LocationService {}),
+            cold_chain_service: Box::new(ColdChainService {}),
+            cycle_count_service: Box::new(CycleCountService {}),
             master_list_service: Box::new(MasterListService {}),
             invoice_line_service: Box::new(InvoiceLineService {}),
             invoice_count_service: Box::new(InvoiceCountService {}),
@@ -132,6 +150,12 @@ impl ServiceProvider {
             site_is_initialised_trigger,
             display_settings_service: Box::new(DisplaySettingsService {}),
             stock_line_service: Box::new(StockLineService {}),
+            item_ledger_service: Box::new(ItemLedgerService {}),
+            expired_stock_service: Box::new(ExpiredStockService {}),
+            traceability_service: Box::new(TraceabilityService {}),
+            hold_service: Box::new(HoldService {}),
+            historical_stock_service: Box::new(HistoricalStockService {}),
+            inventory_adjustment_service: Box::new(InventoryAdjustmentService {}),
             item_count_service: Box::new(ItemServiceCount {}),
             barcode_service: Box::new(BarcodeService {}),
         }
@@ -0,0 +1,175 @@
+use repository::{RepositoryError, StockLine, StockLineRow, StorageConnection};
+
+use crate::{service_provider::ServiceContext, SingleRecordError};
+
+use super::{
+    query::get_stock_line,
+    stock_transfer::{
+        generate_exit_location_movement, generate_transfer_adjustments, write_stock_transfer,
+        PackTransfer, StockTransferError, StockTransferJob,
+    },
+    validate::{
+        check_stock_line_exists, check_stock_line_not_allocated,
+        check_stock_line_not_pending_inspection, check_store,
+    },
+};
+
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct MergeStockLines {
+    /// Stock line receiving the packs, it keeps its location, prices and hold status
+    pub id: String,
+    /// Stock lines merged into the stock line `id`, they are left without packs
+    pub stock_line_ids: Vec<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum MergeStockLinesError {
+    DatabaseError(RepositoryError),
+    InternalError(String),
+    StockDoesNotBelongToStore,
+    StockDoesNotExist,
+    /// No stock lines to merge or the stock line `id` is in the list of stock lines to merge
+    InvalidStockLineIds,
+    /// Only stock lines of the same item, batch, expiry date and pack size can be merged
+    StockLinesDoNotMatch,
+    /// One of the stock lines is used by an outbound shipment that hasn't been shipped yet
+    StockLineIsAllocated,
+    /// One of the stock lines to merge is on hold but the stock line `id` is not
+    CannotReleaseHold,
+    /// One of the stock lines is waiting for its goods receipt inspection
+    StockLineIsPendingInspection,
+    MergedStockLineNotFound,
+}
+
+/// Consolidates duplicate stock lines of the same batch into one stock line
+pub fn merge_stock_lines(
+    ctx: &ServiceContext,
+    input: MergeStockLines,
+) -> Result<StockLine, MergeStockLinesError> {
+    let result = ctx
+        .connection
+        .transaction_sync(|connection| {
+            let (target, sources) = validate(connection, &ctx.store_id, &input)?;
+            let job = generate(ctx, target, sources)?;
+            write_stock_transfer(connection, job)?;
+
+            get_stock_line(ctx, input.id).map_err(|error| match error {
+                SingleRecordError::DatabaseError(error) => {
+                    MergeStockLinesError::DatabaseError(error)
+                }
+                SingleRecordError::NotFound(_) => MergeStockLinesError::MergedStockLineNotFound,
+            })
+        })
+        .map_err(|error| error.to_inner_error())?;
+    Ok(result)
+}
+
+fn validate(
+    connection: &StorageConnection,
+    store_id: &str,
+    input: &MergeStockLines,
+) -> Result<(StockLineRow, Vec<StockLineRow>), MergeStockLinesError> {
+    use MergeStockLinesError::*;
+
+    let mut ids = input.stock_line_ids.clone();
+    ids.sort();
+    ids.dedup();
+    if ids.is_empty() || ids.contains(&input.id) {
+        return Err(InvalidStockLineIds);
+    }
+
+    let target = check_stock_line_exists(connection, &input.id)?.ok_or(StockDoesNotExist)?;
+    let mut sources = Vec::new();
+    for id in ids {
+        sources.push(check_stock_line_exists(connection, &id)?.ok_or(StockDoesNotExist)?);
+    }
+
+    for stock_line in std::iter::once(&target).chain(sources.iter()) {
+        if !check_store(stock_line, store_id) {
+            return Err(StockDoesNotBelongToStore);
+        }
+    }
+    let matches = |stock_line: &StockLineRow| {
+        stock_line.item_id == target.item_id
+            && stock_line.batch == target.batch
+            && stock_line.expiry_date == target.expiry_date
+            && stock_line.pack_size == target.pack_size
+    };
+    if !sources.iter().all(matches) {
+        return Err(StockLinesDoNotMatch);
+    }
+    for stock_line in std::iter::once(&target).chain(sources.iter()) {
+        if !check_stock_line_not_allocated(connection, &stock_line.id)? {
+            return Err(StockLineIsAllocated);
+        }
+        if !check_stock_line_not_pending_inspection(connection, &stock_line.id)? {
+            return Err(StockLineIsPendingInspection);
+        }
+    }
+    // held packs have to be released through their hold
+    if !target.on_hold && sources.iter().any(|source| source.on_hold) {
+        return Err(CannotReleaseHold);
+    }
+
+    Ok((target, sources))
+}
+
+fn generate(
+    ctx: &ServiceContext,
+    mut target: StockLineRow,
+    sources: Vec<StockLineRow>,
+) -> Result<StockTransferJob, MergeStockLinesError> {
+    let (invoices, invoice_lines) = generate_transfer_adjustments(
+        ctx,
+        &sources
+            .iter()
+            .map(|source| PackTransfer {
+                from: source,
+                to: &target,
+                number_of_packs: source.total_number_of_packs,
+            })
+            .collect::<Vec<_>>(),
+        "Stock lines merged",
+    )?;
+
+    let mut stock_lines = Vec::new();
+    let mut location_movements = Vec::new();
+    for source in sources {
+        target.available_number_of_packs += source.available_number_of_packs;
+        target.total_number_of_packs += source.total_number_of_packs;
+        if let Some(movement) =
+            generate_exit_location_movement(&ctx.connection, &ctx.store_id, &source)?
+        {
+            location_movements.push(movement);
+        }
+        stock_lines.push(StockLineRow {
+            available_number_of_packs: 0.0,
+            total_number_of_packs: 0.0,
+            ..source
+        });
+    }
+    stock_lines.push(target);
+
+    Ok(StockTransferJob {
+        stock_lines,
+        invoices,
+        invoice_lines,
+        location_movements,
+        holds: Vec::new(),
+    })
+}
+
+impl From<RepositoryError> for MergeStockLinesError {
+    fn from(error: RepositoryError) -> Self {
+        MergeStockLinesError::DatabaseError(error)
+    }
+}
+
+impl From<StockTransferError> for MergeStockLinesError {
+    fn from(error: StockTransferError) -> Self {
+        match error {
+            StockTransferError::DatabaseError(error) => MergeStockLinesError::DatabaseError(error),
+            StockTransferError::InternalError(error) => MergeStockLinesError::InternalError(error),
+        }
+    }
+}
@@ -1,13 +1,23 @@
-use self::query::{get_stock_line, get_stock_lines};
+use self::query::{get_stock_line, get_stock_lines, get_stock_movements};
 
 use super::{ListError, ListResult};
 use crate::{service_provider::ServiceContext, SingleRecordError};
-use repository::{PaginationOption, StockLine, StockLineFilter, StockLineSort};
+use repository::{
+    PaginationOption, StockLine, StockLineFilter, StockLineSort, StockMovementFilter,
+    StockMovementRow,
+};
 
+pub mod merge;
 pub mod query;
+pub mod repack;
+pub mod split;
 pub mod update;
+pub use self::merge::*;
+pub use self::repack::*;
+pub use self::split::*;
 pub use self::update::*;
-mod validate;
+pub(crate) mod stock_transfer;
+pub(crate) mod validate;
 
 pub trait StockLineServiceTrait: Sync + Send {
     fn get_stock_lines(
@@ -36,6 +46,40 @@ pub trait StockLineServiceTrait: Sync + Send {
     ) -> Result<StockLine, UpdateStockLineError> {
         update_stock_line(ctx, input)
     }
+
+    fn split_stock_line(
+        &self,
+        ctx: &ServiceContext,
+        input: SplitStockLine,
+    ) -> Result<StockLine, SplitStockLineError> {
+        split_stock_line(ctx, input)
+    }
+
+    fn merge_stock_lines(
+        &self,
+        ctx: &ServiceContext,
+        input: MergeStockLines,
+    ) -> Result<StockLine, MergeStockLinesError> {
+        merge_stock_lines(ctx, input)
+    }
+
+    fn repack_stock_line(
+        &self,
+        ctx: &ServiceContext,
+        input: RepackStockLine,
+    ) -> Result<StockLine, RepackStockLineError> {
+        repack_stock_line(ctx, input)
+    }
+
+    fn get_stock_movements(
+        &self,
+        ctx: &ServiceContext,
+        store_id: &str,
+        pagination: Option<PaginationOption>,
+        filter: Option<StockMovementFilter>,
+    ) -> Result<ListResult<StockMovementRow>, ListError> {
+        get_stock_movements(ctx, store_id, pagination, filter)
+    }
 }
 
 pub struct StockLineService {}
@@ -4,6 +4,7 @@ use crate::{
 };
 use repository::{
     EqualFilter, PaginationOption, StockLine, StockLineFilter, StockLineRepository, StockLineSort,
+    StockMovementFilter, StockMovementRepository, StockMovementRow,
 };
 
 pub const MAX_LIMIT: u32 = 1000;
@@ -39,3 +40,22 @@ pub fn get_stock_lines(
         count: i64_to_u32(repository.count(filter, store_id)?),
     })
 }
+
+/// Returns the stock movements of a store ordered by datetime
+pub fn get_stock_movements(
+    ctx: &ServiceContext,
+    store_id: &str,
+    pagination: Option<PaginationOption>,
+    filter: Option<StockMovementFilter>,
+) -> Result<ListResult<StockMovementRow>, ListError> {
+    let pagination = get_default_pagination(pagination, MAX_LIMIT, MIN_LIMIT)?;
+    let filter = filter
+        .unwrap_or_default()
+        .store_id(EqualFilter::equal_to(store_id));
+    let repository = StockMovementRepository::new(&ctx.connection);
+
+    Ok(ListResult {
+        rows: repository.query(pagination, Some(filter.clone()))?,
+        count: i64_to_u32(repository.count(Some(filter))?),
+    })
+}
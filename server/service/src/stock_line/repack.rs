@@ -0,0 +1,249 @@
+use repository::{ActivityLogType, RepositoryError, StockLine, StockLineRow, StorageConnection};
+use util::uuid::uuid;
+
+use crate::{
+    activity_log::activity_log_entry, service_provider::ServiceContext, SingleRecordError,
+};
+
+use super::{
+    query::get_stock_line,
+    stock_transfer::{
+        generate_copied_hold, generate_enter_location_movement, generate_exit_location_movement,
+        generate_transfer_adjustments, write_stock_transfer, PackTransfer, StockTransferError,
+        StockTransferJob,
+    },
+    validate::{
+        check_location_exists, check_stock_line_exists, check_stock_line_not_allocated,
+        check_stock_line_not_pending_inspection, check_store,
+    },
+};
+
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct RepackStockLine {
+    pub id: String,
+    /// Number of packs of the stock line that are repacked
+    pub number_of_packs: f64,
+    pub new_pack_size: u32,
+    /// Existing stock line of the same batch and the new pack size receiving the repacked stock,
+    /// a new stock line is created when not set
+    pub new_stock_line_id: Option<String>,
+    /// Location of the new stock line, defaults to the location of the existing stock line
+    pub location_id: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum RepackStockLineError {
+    DatabaseError(RepositoryError),
+    InternalError(String),
+    StockDoesNotBelongToStore,
+    StockDoesNotExist,
+    LocationDoesNotExist,
+    /// Number of packs must be positive and not more than the available number of packs
+    InvalidNumberOfPacks,
+    /// New pack size must be positive and different to the pack size of the stock line
+    InvalidPackSize,
+    /// Stock line receiving the repacked stock must be of the same item, batch, expiry date,
+    /// supplier and hold status, and have the new pack size
+    NewStockLineDoesNotMatch,
+    /// Stock line is used by an outbound shipment that hasn't been shipped yet
+    StockLineIsAllocated,
+    /// The stock line or the stock line receiving the repacked stock is waiting for its goods
+    /// receipt inspection
+    StockLineIsPendingInspection,
+    NewStockLineNotFound,
+}
+
+/// Converts packs of a stock line into packs of a different size, e.g. when a box of 100 tablets
+/// is broken into strips of 10. The number of units, batch, expiry date, cost per unit and
+/// supplier are kept. Returns the stock line receiving the repacked stock.
+pub fn repack_stock_line(
+    ctx: &ServiceContext,
+    input: RepackStockLine,
+) -> Result<StockLine, RepackStockLineError> {
+    let result = ctx
+        .connection
+        .transaction_sync(|connection| {
+            let (existing, new_stock_line) = validate(connection, &ctx.store_id, &input)?;
+            let event = format!(
+                "{} packs of {} repacked into {} packs of {}",
+                input.number_of_packs,
+                existing.pack_size,
+                input.number_of_packs * existing.pack_size as f64 / input.new_pack_size as f64,
+                input.new_pack_size
+            );
+            let stock_line_id = input.id.clone();
+            let (new_stock_line_id, job) = generate(ctx, existing, new_stock_line, input)?;
+            write_stock_transfer(connection, job)?;
+
+            activity_log_entry(
+                ctx,
+                ActivityLogType::Repack,
+                Some(stock_line_id),
+                Some(event),
+            )?;
+
+            get_stock_line(ctx, new_stock_line_id).map_err(|error| match error {
+                SingleRecordError::DatabaseError(error) => {
+                    RepackStockLineError::DatabaseError(error)
+                }
+                SingleRecordError::NotFound(_) => RepackStockLineError::NewStockLineNotFound,
+            })
+        })
+        .map_err(|error| error.to_inner_error())?;
+    Ok(result)
+}
+
+fn validate(
+    connection: &StorageConnection,
+    store_id: &str,
+    input: &RepackStockLine,
+) -> Result<(StockLineRow, Option<StockLineRow>), RepackStockLineError> {
+    use RepackStockLineError::*;
+
+    let stock_line = check_stock_line_exists(connection, &input.id)?.ok_or(StockDoesNotExist)?;
+    if !check_store(&stock_line, store_id) {
+        return Err(StockDoesNotBelongToStore);
+    }
+    if let Some(location_id) = &input.location_id {
+        if !check_location_exists(connection, location_id)? {
+            return Err(LocationDoesNotExist);
+        }
+    }
+    if input.number_of_packs <= 0.0 || input.number_of_packs > stock_line.available_number_of_packs
+    {
+        return Err(InvalidNumberOfPacks);
+    }
+    if input.new_pack_size == 0 || input.new_pack_size as i32 == stock_line.pack_size {
+        return Err(InvalidPackSize);
+    }
+
+    let new_stock_line = match &input.new_stock_line_id {
+        Some(id) => {
+            let new_stock_line =
+                check_stock_line_exists(connection, id)?.ok_or(StockDoesNotExist)?;
+            if !check_store(&new_stock_line, store_id) {
+                return Err(StockDoesNotBelongToStore);
+            }
+            if new_stock_line.item_id != stock_line.item_id
+                || new_stock_line.batch != stock_line.batch
+                || new_stock_line.expiry_date != stock_line.expiry_date
+                || new_stock_line.supplier_id != stock_line.supplier_id
+                || new_stock_line.pack_size != input.new_pack_size as i32
+                // repacking must neither release held stock nor put stock on hold
+                || new_stock_line.on_hold != stock_line.on_hold
+            {
+                return Err(NewStockLineDoesNotMatch);
+            }
+            Some(new_stock_line)
+        }
+        None => None,
+    };
+
+    if !check_stock_line_not_allocated(connection, &stock_line.id)? {
+        return Err(StockLineIsAllocated);
+    }
+    for id in std::iter::once(&stock_line.id).chain(input.new_stock_line_id.iter()) {
+        if !check_stock_line_not_pending_inspection(connection, id)? {
+            return Err(StockLineIsPendingInspection);
+        }
+    }
+
+    Ok((stock_line, new_stock_line))
+}
+
+fn generate(
+    ctx: &ServiceContext,
+    existing: StockLineRow,
+    new_stock_line: Option<StockLineRow>,
+    RepackStockLine {
+        id: _,
+        number_of_packs,
+        new_pack_size,
+        new_stock_line_id: _,
+        location_id,
+    }: RepackStockLine,
+) -> Result<(String, StockTransferJob), RepackStockLineError> {
+    let pack_size = new_pack_size as i32;
+    let price_per_pack = |price: f64| price / existing.pack_size as f64 * pack_size as f64;
+    let mut location_movements = Vec::new();
+    let mut holds = Vec::new();
+
+    let new_stock_line = match new_stock_line {
+        Some(new_stock_line) => new_stock_line,
+        None => {
+            let new_stock_line = StockLineRow {
+                id: uuid(),
+                location_id: location_id.or(existing.location_id.clone()),
+                pack_size,
+                cost_price_per_pack: price_per_pack(existing.cost_price_per_pack),
+                sell_price_per_pack: price_per_pack(existing.sell_price_per_pack),
+                available_number_of_packs: 0.0,
+                total_number_of_packs: 0.0,
+                ..existing.clone()
+            };
+            location_movements.extend(generate_enter_location_movement(
+                &ctx.store_id,
+                &new_stock_line,
+            ));
+            holds.extend(generate_copied_hold(
+                &ctx.connection,
+                &existing,
+                &new_stock_line,
+            )?);
+            new_stock_line
+        }
+    };
+
+    let transfer = PackTransfer {
+        from: &existing,
+        to: &new_stock_line,
+        number_of_packs,
+    };
+    let new_number_of_packs = transfer.to_number_of_packs();
+    let (invoices, invoice_lines) =
+        generate_transfer_adjustments(ctx, &[transfer], "Stock line repack")?;
+
+    let updated_new_stock_line = StockLineRow {
+        available_number_of_packs: new_stock_line.available_number_of_packs + new_number_of_packs,
+        total_number_of_packs: new_stock_line.total_number_of_packs + new_number_of_packs,
+        ..new_stock_line
+    };
+    let updated_stock_line = StockLineRow {
+        available_number_of_packs: existing.available_number_of_packs - number_of_packs,
+        total_number_of_packs: existing.total_number_of_packs - number_of_packs,
+        ..existing
+    };
+    if updated_stock_line.total_number_of_packs <= 0.0 {
+        location_movements.extend(generate_exit_location_movement(
+            &ctx.connection,
+            &ctx.store_id,
+            &updated_stock_line,
+        )?);
+    }
+
+    Ok((
+        updated_new_stock_line.id.clone(),
+        StockTransferJob {
+            stock_lines: vec![updated_stock_line, updated_new_stock_line],
+            invoices,
+            invoice_lines,
+            location_movements,
+            holds,
+        },
+    ))
+}
+
+impl From<RepositoryError> for RepackStockLineError {
+    fn from(error: RepositoryError) -> Self {
+        RepackStockLineError::DatabaseError(error)
+    }
+}
+
+impl From<StockTransferError> for RepackStockLineError {
+    fn from(error: StockTransferError) -> Self {
+        match error {
+            StockTransferError::DatabaseError(error) => RepackStockLineError::DatabaseError(error),
+            StockTransferError::InternalError(error) => RepackStockLineError::InternalError(error),
+        }
+    }
+}
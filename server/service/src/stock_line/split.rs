@@ -0,0 +1,170 @@
+use repository::{RepositoryError, StockLine, StockLineRow, StorageConnection};
+use util::uuid::uuid;
+
+use crate::{service_provider::ServiceContext, SingleRecordError};
+
+use super::{
+    query::get_stock_line,
+    stock_transfer::{
+        generate_copied_hold, generate_enter_location_movement, generate_transfer_adjustments,
+        write_stock_transfer, PackTransfer, StockTransferError, StockTransferJob,
+    },
+    validate::{
+        check_location_exists, check_stock_line_exists, check_stock_line_not_allocated,
+        check_stock_line_not_pending_inspection, check_store,
+    },
+};
+
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct SplitStockLine {
+    pub id: String,
+    /// Number of packs moved to the new stock line
+    pub number_of_packs: f64,
+    /// Location of the new stock line, defaults to the location of the existing stock line
+    pub location_id: Option<String>,
+    /// Hold status of the new stock line, defaults to the hold status of the existing stock line.
+    /// Held stock can't be released by a split, it has to be released through its hold.
+    pub on_hold: Option<bool>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum SplitStockLineError {
+    DatabaseError(RepositoryError),
+    InternalError(String),
+    StockDoesNotBelongToStore,
+    StockDoesNotExist,
+    LocationDoesNotExist,
+    /// Number of packs must be positive and less than the available number of packs
+    InvalidNumberOfPacks,
+    /// Stock line is used by an outbound shipment that hasn't been shipped yet
+    StockLineIsAllocated,
+    /// The existing stock line is on hold and the new stock line would not be
+    CannotReleaseHold,
+    /// Stock waiting for its goods receipt inspection can't be moved to another stock line
+    StockLineIsPendingInspection,
+    NewStockLineNotFound,
+}
+
+/// Moves part of the available packs of a stock line to a new stock line, e.g. to store them in
+/// another location or to put them on hold. Returns the new stock line.
+pub fn split_stock_line(
+    ctx: &ServiceContext,
+    input: SplitStockLine,
+) -> Result<StockLine, SplitStockLineError> {
+    let result = ctx
+        .connection
+        .transaction_sync(|connection| {
+            let existing = validate(connection, &ctx.store_id, &input)?;
+            let (new_stock_line_id, job) = generate(ctx, existing, input)?;
+            write_stock_transfer(connection, job)?;
+
+            get_stock_line(ctx, new_stock_line_id).map_err(|error| match error {
+                SingleRecordError::DatabaseError(error) => {
+                    SplitStockLineError::DatabaseError(error)
+                }
+                SingleRecordError::NotFound(_) => SplitStockLineError::NewStockLineNotFound,
+            })
+        })
+        .map_err(|error| error.to_inner_error())?;
+    Ok(result)
+}
+
+fn validate(
+    connection: &StorageConnection,
+    store_id: &str,
+    input: &SplitStockLine,
+) -> Result<StockLineRow, SplitStockLineError> {
+    use SplitStockLineError::*;
+
+    let stock_line = check_stock_line_exists(connection, &input.id)?.ok_or(StockDoesNotExist)?;
+    if !check_store(&stock_line, store_id) {
+        return Err(StockDoesNotBelongToStore);
+    }
+    if let Some(location_id) = &input.location_id {
+        if !check_location_exists(connection, location_id)? {
+            return Err(LocationDoesNotExist);
+        }
+    }
+    if input.number_of_packs <= 0.0 || input.number_of_packs >= stock_line.available_number_of_packs
+    {
+        return Err(InvalidNumberOfPacks);
+    }
+    if !check_stock_line_not_allocated(connection, &stock_line.id)? {
+        return Err(StockLineIsAllocated);
+    }
+    if stock_line.on_hold && input.on_hold == Some(false) {
+        return Err(CannotReleaseHold);
+    }
+    if !check_stock_line_not_pending_inspection(connection, &stock_line.id)? {
+        return Err(StockLineIsPendingInspection);
+    }
+
+    Ok(stock_line)
+}
+
+fn generate(
+    ctx: &ServiceContext,
+    existing: StockLineRow,
+    SplitStockLine {
+        id: _,
+        number_of_packs,
+        location_id,
+        on_hold,
+    }: SplitStockLine,
+) -> Result<(String, StockTransferJob), SplitStockLineError> {
+    let new_stock_line = StockLineRow {
+        id: uuid(),
+        location_id: location_id.or(existing.location_id.clone()),
+        on_hold: on_hold.unwrap_or(existing.on_hold),
+        available_number_of_packs: number_of_packs,
+        total_number_of_packs: number_of_packs,
+        ..existing.clone()
+    };
+    let updated_stock_line = StockLineRow {
+        available_number_of_packs: existing.available_number_of_packs - number_of_packs,
+        total_number_of_packs: existing.total_number_of_packs - number_of_packs,
+        ..existing.clone()
+    };
+
+    let (invoices, invoice_lines) = generate_transfer_adjustments(
+        ctx,
+        &[PackTransfer {
+            from: &existing,
+            to: &new_stock_line,
+            number_of_packs,
+        }],
+        "Stock line split",
+    )?;
+    let location_movements = generate_enter_location_movement(&ctx.store_id, &new_stock_line)
+        .into_iter()
+        .collect();
+    let holds = generate_copied_hold(&ctx.connection, &existing, &new_stock_line)?
+        .into_iter()
+        .collect();
+
+    Ok((
+        new_stock_line.id.clone(),
+        StockTransferJob {
+            stock_lines: vec![updated_stock_line, new_stock_line],
+            invoices,
+            invoice_lines,
+            location_movements,
+            holds,
+        },
+    ))
+}
+
+impl From<RepositoryError> for SplitStockLineError {
+    fn from(error: RepositoryError) -> Self {
+        SplitStockLineError::DatabaseError(error)
+    }
+}
+
+impl From<StockTransferError> for SplitStockLineError {
+    fn from(error: StockTransferError) -> Self {
+        match error {
+            StockTransferError::DatabaseError(error) => SplitStockLineError::DatabaseError(error),
+            StockTransferError::InternalError(error) => SplitStockLineError::InternalError(error),
+        }
+    }
+}
@@ -0,0 +1,243 @@
+use chrono::Utc;
+use repository::{
+    DatetimeFilter, EqualFilter, HoldRecordType, HoldRow, HoldRowRepository, InvoiceLineRow,
+    InvoiceLineRowRepository, InvoiceLineRowType, InvoiceRow, InvoiceRowRepository,
+    ItemRowRepository, LocationMovementFilter, LocationMovementRepository, LocationMovementRow,
+    LocationMovementRowRepository, NameRowRepository, RepositoryError, StockLineRow,
+    StockLineRowRepository, StorageConnection,
+};
+use util::{constants::INVENTORY_ADJUSTMENT_NAME_CODE, uuid::uuid};
+
+use crate::{
+    inventory_adjustment::insert::{adjustment_invoice_row, AdjustmentType},
+    service_provider::ServiceContext,
+};
+
+/// Packs moved from one stock line to another stock line of the same item, when the pack sizes
+/// differ the packs are converted so the number of units stays the same
+pub(crate) struct PackTransfer<'a> {
+    pub from: &'a StockLineRow,
+    pub to: &'a StockLineRow,
+    /// Number of packs of the `from` stock line
+    pub number_of_packs: f64,
+}
+
+impl<'a> PackTransfer<'a> {
+    pub fn to_number_of_packs(&self) -> f64 {
+        self.number_of_packs * self.from.pack_size as f64 / self.to.pack_size as f64
+    }
+}
+
+pub(crate) enum StockTransferError {
+    DatabaseError(RepositoryError),
+    InternalError(String),
+}
+
+/// Rows written when packs are moved between stock lines of a store
+#[derive(Default)]
+pub(crate) struct StockTransferJob {
+    pub stock_lines: Vec<StockLineRow>,
+    pub invoices: Vec<InvoiceRow>,
+    pub invoice_lines: Vec<InvoiceLineRow>,
+    pub location_movements: Vec<LocationMovementRow>,
+    pub holds: Vec<HoldRow>,
+}
+
+/// Records the transfers as a verified inventory reduction (on the source stock lines) and a
+/// verified inventory addition (on the destination stock lines), so the stock movements of each
+/// stock line stay consistent with its number of packs. The two invoices are linked to each other
+/// so a batch can be traced through the transfer
+pub(crate) fn generate_transfer_adjustments(
+    ctx: &ServiceContext,
+    transfers: &[PackTransfer],
+    comment: &str,
+) -> Result<(Vec<InvoiceRow>, Vec<InvoiceLineRow>), StockTransferError> {
+    let connection = &ctx.connection;
+    let transfers: Vec<&PackTransfer> = transfers
+        .iter()
+        .filter(|transfer| transfer.number_of_packs > 0.0)
+        .collect();
+    if transfers.is_empty() {
+        return Ok((Vec::new(), Vec::new()));
+    }
+
+    let inventory_adjustment_name = NameRowRepository::new(connection)
+        .find_one_by_code(INVENTORY_ADJUSTMENT_NAME_CODE)?
+        .ok_or(StockTransferError::InternalError(
+            "Missing inventory adjustment name".to_string(),
+        ))?;
+
+    let comment = Some(comment.to_string());
+    let mut reduction = adjustment_invoice_row(
+        ctx,
+        &inventory_adjustment_name.id,
+        &AdjustmentType::Reduction,
+        comment.clone(),
+    )?;
+    let mut addition = adjustment_invoice_row(
+        ctx,
+        &inventory_adjustment_name.id,
+        &AdjustmentType::Addition,
+        comment,
+    )?;
+    reduction.linked_invoice_id = Some(addition.id.clone());
+    addition.linked_invoice_id = Some(reduction.id.clone());
+
+    let mut lines = Vec::new();
+    for transfer in transfers {
+        let item = ItemRowRepository::new(connection)
+            .find_one_by_id(&transfer.from.item_id)?
+            .ok_or(StockTransferError::InternalError(format!(
+                "Can't find item {} of stock line {}",
+                transfer.from.item_id, transfer.from.id
+            )))?;
+        let line = |invoice_id: &str,
+                    r#type: InvoiceLineRowType,
+                    stock_line: &StockLineRow,
+                    number_of_packs: f64| {
+            InvoiceLineRow {
+                id: uuid(),
+                invoice_id: invoice_id.to_string(),
+                r#type,
+                item_id: item.id.clone(),
+                item_name: item.name.clone(),
+                item_code: item.code.clone(),
+                stock_line_id: Some(stock_line.id.clone()),
+                location_id: stock_line.location_id.clone(),
+                batch: stock_line.batch.clone(),
+                expiry_date: stock_line.expiry_date,
+                pack_size: stock_line.pack_size,
+                cost_price_per_pack: stock_line.cost_price_per_pack,
+                sell_price_per_pack: stock_line.sell_price_per_pack,
+                total_before_tax: 0.0,
+                total_after_tax: 0.0,
+                tax: None,
+                number_of_packs,
+                note: stock_line.note.clone(),
+                inventory_adjustment_reason_id: None,
+            }
+        };
+        lines.push(line(
+            &reduction.id,
+            InvoiceLineRowType::StockOut,
+            transfer.from,
+            transfer.number_of_packs,
+        ));
+        lines.push(line(
+            &addition.id,
+            InvoiceLineRowType::StockIn,
+            transfer.to,
+            transfer.to_number_of_packs(),
+        ));
+    }
+
+    Ok((vec![reduction, addition], lines))
+}
+
+/// Hold of a new stock line that keeps the on hold status of the stock line it was created from,
+/// with the reason and release date of the existing hold
+pub(crate) fn generate_copied_hold(
+    connection: &StorageConnection,
+    from: &StockLineRow,
+    to: &StockLineRow,
+) -> Result<Option<HoldRow>, RepositoryError> {
+    if !(from.on_hold && to.on_hold) {
+        return Ok(None);
+    }
+    let hold = HoldRowRepository::new(connection)
+        .find_one_by_record(&HoldRecordType::StockLine, &from.id)?
+        .map(|hold| HoldRow {
+            id: uuid(),
+            record_id: to.id.clone(),
+            ..hold
+        });
+    Ok(hold)
+}
+
+pub(crate) fn generate_enter_location_movement(
+    store_id: &str,
+    stock_line: &StockLineRow,
+) -> Option<LocationMovementRow> {
+    stock_line
+        .location_id
+        .as_ref()
+        .map(|location_id| LocationMovementRow {
+            id: uuid(),
+            store_id: store_id.to_string(),
+            stock_line_id: stock_line.id.clone(),
+            location_id: Some(location_id.clone()),
+            enter_datetime: Some(Utc::now().naive_utc()),
+            exit_datetime: None,
+        })
+}
+
+/// Closes the open location movement of a stock line that no longer has stock
+pub(crate) fn generate_exit_location_movement(
+    connection: &StorageConnection,
+    store_id: &str,
+    stock_line: &StockLineRow,
+) -> Result<Option<LocationMovementRow>, RepositoryError> {
+    let location_id = match &stock_line.location_id {
+        Some(location_id) => location_id,
+        None => return Ok(None),
+    };
+    let movement = LocationMovementRepository::new(connection)
+        .query_by_filter(
+            LocationMovementFilter::new()
+                .enter_datetime(DatetimeFilter::is_null(false))
+                .exit_datetime(DatetimeFilter::is_null(true))
+                .location_id(EqualFilter::equal_to(location_id))
+                .stock_line_id(EqualFilter::equal_to(&stock_line.id))
+                .store_id(EqualFilter::equal_to(store_id)),
+        )?
+        .into_iter()
+        .map(|l| l.location_movement_row)
+        .min_by_key(|l| l.enter_datetime);
+
+    Ok(movement.map(|mut movement| {
+        movement.exit_datetime = Some(Utc::now().naive_utc());
+        movement
+    }))
+}
+
+pub(crate) fn write_stock_transfer(
+    connection: &StorageConnection,
+    job: StockTransferJob,
+) -> Result<(), RepositoryError> {
+    let StockTransferJob {
+        stock_lines,
+        invoices,
+        invoice_lines,
+        location_movements,
+        holds,
+    } = job;
+
+    let stock_line_repo = StockLineRowRepository::new(connection);
+    for stock_line in stock_lines {
+        stock_line_repo.upsert_one(&stock_line)?;
+    }
+    let invoice_repo = InvoiceRowRepository::new(connection);
+    for invoice in invoices {
+        invoice_repo.upsert_one(&invoice)?;
+    }
+    let invoice_line_repo = InvoiceLineRowRepository::new(connection);
+    for line in invoice_lines {
+        invoice_line_repo.upsert_one(&line)?;
+    }
+    let location_movement_repo = LocationMovementRowRepository::new(connection);
+    for movement in location_movements {
+        location_movement_repo.upsert_one(&movement)?;
+    }
+    let hold_repo = HoldRowRepository::new(connection);
+    for hold in holds {
+        hold_repo.upsert_one(&hold)?;
+    }
+
+    Ok(())
+}
+
+impl From<RepositoryError> for StockTransferError {
+    fn from(error: RepositoryError) -> Self {
+        StockTransferError::DatabaseError(error)
+    }
+}
@@ -0,0 +1,182 @@
+#[cfg(test)]
+mod test {
+    use repository::{
+        mock::{
+            mock_item_a_stock_line, mock_item_b, mock_location_1, mock_store_a,
+            mock_user_account_a, MockData, MockDataInserts,
+        },
+        test_db::setup_all_with_data,
+        EqualFilter, ItemLedgerFilter, ItemLedgerRepository, LocationMovementRow,
+        LocationMovementRowRepository, StockLineRowRepository,
+    };
+    use util::{inline_edit, inline_init};
+
+    use crate::{
+        service_provider::ServiceProvider,
+        stock_line::{tests::mock::insert_pending_inspection, MergeStockLines},
+    };
+
+    type ServiceError = crate::stock_line::MergeStockLinesError;
+
+    fn merge_data() -> MockData {
+        inline_init(|r: &mut MockData| {
+            r.stock_lines = vec![
+                mock_item_a_stock_line("merge_target", 5, 3.0),
+                mock_item_a_stock_line("merge_source_1", 5, 2.0),
+                inline_edit(
+                    &mock_item_a_stock_line("merge_source_2", 5, 1.0),
+                    |mut u| {
+                        u.location_id = Some(mock_location_1().id);
+                        u
+                    },
+                ),
+                inline_edit(
+                    &mock_item_a_stock_line("merge_other_item", 5, 1.0),
+                    |mut u| {
+                        u.item_id = mock_item_b().id;
+                        u
+                    },
+                ),
+                inline_edit(
+                    &mock_item_a_stock_line("merge_held_source", 5, 1.0),
+                    |mut u| {
+                        u.on_hold = true;
+                        u
+                    },
+                ),
+                mock_item_a_stock_line("merge_pending_source", 5, 1.0),
+            ];
+        })
+    }
+
+    #[actix_rt::test]
+    async fn merge_stock_lines_errors() {
+        let (_, connection, connection_manager, _) = setup_all_with_data(
+            "merge_stock_lines_errors",
+            MockDataInserts::all(),
+            merge_data(),
+        )
+        .await;
+        insert_pending_inspection(&connection, "merge_pending_source");
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let mut context = service_provider
+            .context(mock_store_a().id, "".to_string())
+            .unwrap();
+        let service = service_provider.stock_line_service;
+        let input = |ids: &[&str]| MergeStockLines {
+            id: "merge_target".to_string(),
+            stock_line_ids: ids.iter().map(|id| id.to_string()).collect(),
+        };
+
+        // InvalidStockLineIds
+        assert_eq!(
+            service.merge_stock_lines(&context, input(&[])),
+            Err(ServiceError::InvalidStockLineIds)
+        );
+        assert_eq!(
+            service.merge_stock_lines(&context, input(&["merge_target"])),
+            Err(ServiceError::InvalidStockLineIds)
+        );
+
+        // StockDoesNotExist
+        assert_eq!(
+            service.merge_stock_lines(&context, input(&["invalid"])),
+            Err(ServiceError::StockDoesNotExist)
+        );
+
+        // StockLinesDoNotMatch
+        assert_eq!(
+            service.merge_stock_lines(&context, input(&["merge_source_1", "merge_other_item"])),
+            Err(ServiceError::StockLinesDoNotMatch)
+        );
+
+        // CannotReleaseHold
+        assert_eq!(
+            service.merge_stock_lines(&context, input(&["merge_source_1", "merge_held_source"])),
+            Err(ServiceError::CannotReleaseHold)
+        );
+
+        // StockLineIsPendingInspection
+        assert_eq!(
+            service.merge_stock_lines(&context, input(&["merge_source_1", "merge_pending_source"])),
+            Err(ServiceError::StockLineIsPendingInspection)
+        );
+
+        // StockDoesNotBelongToStore
+        context.store_id = "store_b".to_string();
+        assert_eq!(
+            service.merge_stock_lines(&context, input(&["merge_source_1"])),
+            Err(ServiceError::StockDoesNotBelongToStore)
+        );
+    }
+
+    #[actix_rt::test]
+    async fn merge_stock_lines_success() {
+        let (_, connection, connection_manager, _) = setup_all_with_data(
+            "merge_stock_lines_success",
+            MockDataInserts::all(),
+            merge_data(),
+        )
+        .await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider
+            .context(mock_store_a().id, mock_user_account_a().id)
+            .unwrap();
+        LocationMovementRowRepository::new(&connection)
+            .upsert_one(&LocationMovementRow {
+                id: "merge_source_2_movement".to_string(),
+                store_id: mock_store_a().id,
+                stock_line_id: "merge_source_2".to_string(),
+                location_id: Some(mock_location_1().id),
+                enter_datetime: Some(chrono::Utc::now().naive_utc()),
+                exit_datetime: None,
+            })
+            .unwrap();
+        let service = service_provider.stock_line_service;
+
+        let merged = service
+            .merge_stock_lines(
+                &context,
+                MergeStockLines {
+                    id: "merge_target".to_string(),
+                    stock_line_ids: vec![
+                        "merge_source_1".to_string(),
+                        "merge_source_2".to_string(),
+                    ],
+                },
+            )
+            .unwrap()
+            .stock_line_row;
+        assert_eq!(merged.available_number_of_packs, 6.0);
+        assert_eq!(merged.total_number_of_packs, 6.0);
+
+        let repo = StockLineRowRepository::new(&connection);
+        for id in ["merge_source_1", "merge_source_2"] {
+            let source = repo.find_one_by_id(id).unwrap();
+            assert_eq!(source.available_number_of_packs, 0.0);
+            assert_eq!(source.total_number_of_packs, 0.0);
+        }
+
+        // Stock movements balance out for the item
+        let rows = ItemLedgerRepository::new(&connection)
+            .query_by_filter(
+                ItemLedgerFilter::new().stock_line_id(EqualFilter::equal_any(vec![
+                    "merge_target".to_string(),
+                    "merge_source_1".to_string(),
+                    "merge_source_2".to_string(),
+                ])),
+            )
+            .unwrap();
+        assert_eq!(rows.len(), 4);
+        assert_eq!(rows.iter().map(|row| row.quantity).sum::<f64>(), 0.0);
+
+        // The emptied stock line left its location
+        let movement = LocationMovementRowRepository::new(&connection)
+            .find_one_by_id("merge_source_2_movement")
+            .unwrap()
+            .unwrap();
+        assert!(movement.exit_datetime.is_some());
+    }
+}
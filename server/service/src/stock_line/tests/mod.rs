@@ -1,3 +1,53 @@
 #[cfg(test)]
+mod merge;
 mod query;
+mod repack;
+mod split;
 mod update;
+
+#[cfg(test)]
+mod mock {
+    use repository::{
+        mock::{mock_name_a, mock_store_a, MockData},
+        GoodsReceiptInspectionRow, GoodsReceiptInspectionRowRepository,
+        GoodsReceiptInspectionStatus, InvoiceLineRow, InvoiceLineRowType, InvoiceRow,
+        InvoiceRowStatus, InvoiceRowType, StockLineRow, StorageConnection,
+    };
+    use util::inline_init;
+
+    /// Stock line with one pack issued by an outbound shipment of the given status
+    pub fn allocated_stock_line(stock_line: StockLineRow, status: InvoiceRowStatus) -> MockData {
+        inline_init(|r: &mut MockData| {
+            r.invoices = vec![inline_init(|r: &mut InvoiceRow| {
+                r.id = "allocated_outbound".to_string();
+                r.store_id = mock_store_a().id;
+                r.name_id = mock_name_a().id;
+                r.r#type = InvoiceRowType::OutboundShipment;
+                r.status = status;
+            })];
+            r.invoice_lines = vec![inline_init(|r: &mut InvoiceLineRow| {
+                r.id = "allocated_outbound_line".to_string();
+                r.invoice_id = "allocated_outbound".to_string();
+                r.item_id = stock_line.item_id.clone();
+                r.stock_line_id = Some(stock_line.id.clone());
+                r.r#type = InvoiceLineRowType::StockOut;
+                r.pack_size = stock_line.pack_size;
+                r.number_of_packs = 1.0;
+            })];
+            r.stock_lines = vec![stock_line];
+        })
+    }
+
+    /// Marks the stock line as waiting for its goods receipt inspection
+    pub fn insert_pending_inspection(connection: &StorageConnection, stock_line_id: &str) {
+        GoodsReceiptInspectionRowRepository::new(connection)
+            .upsert_one(&inline_init(|r: &mut GoodsReceiptInspectionRow| {
+                r.id = format!("{}_inspection", stock_line_id);
+                r.invoice_id = "inbound_shipment".to_string();
+                r.invoice_line_id = format!("{}_inbound_line", stock_line_id);
+                r.stock_line_id = stock_line_id.to_string();
+                r.status = GoodsReceiptInspectionStatus::Pending;
+            }))
+            .unwrap();
+    }
+}
@@ -150,6 +150,8 @@ mod query {
             store_id: None,
             item_code_or_name: None,
             has_packs_in_store: None,
+            is_on_hold: None,
+            hold_reason_id: None,
         });
 
         // Test ExpiryDate sort with default sort order
@@ -202,6 +204,8 @@ mod query {
             store_id: None,
             item_code_or_name: None,
             has_packs_in_store: None,
+            is_on_hold: None,
+            hold_reason_id: None,
         });
 
         // Test ExpiryDate sort with desc sort order
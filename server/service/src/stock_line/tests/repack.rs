@@ -0,0 +1,266 @@
+#[cfg(test)]
+mod test {
+    use repository::{
+        mock::{
+            mock_item_a_stock_line, mock_store_a, mock_user_account_a, MockData, MockDataInserts,
+        },
+        test_db::setup_all_with_data,
+        ActivityLogRowRepository, ActivityLogType, EqualFilter, InvoiceRowStatus, ItemLedgerFilter,
+        ItemLedgerRepository, StockLineRowRepository,
+    };
+    use util::{inline_edit, inline_init};
+
+    use crate::{
+        service_provider::ServiceProvider,
+        stock_line::{
+            tests::mock::{allocated_stock_line, insert_pending_inspection},
+            RepackStockLine,
+        },
+    };
+
+    type ServiceError = crate::stock_line::RepackStockLineError;
+
+    fn allocated_line() -> MockData {
+        allocated_stock_line(
+            mock_item_a_stock_line("allocated_line", 10, 10.0),
+            InvoiceRowStatus::Picked,
+        )
+    }
+
+    #[actix_rt::test]
+    async fn repack_stock_line_errors() {
+        let (_, connection, connection_manager, _) = setup_all_with_data(
+            "repack_stock_line_errors",
+            MockDataInserts::all(),
+            inline_init(|r: &mut MockData| {
+                r.stock_lines = vec![
+                    mock_item_a_stock_line("repack_line", 10, 10.0),
+                    inline_edit(&mock_item_a_stock_line("other_batch", 5, 1.0), |mut u| {
+                        u.batch = Some("other_batch".to_string());
+                        u
+                    }),
+                    inline_edit(&mock_item_a_stock_line("held_target", 5, 1.0), |mut u| {
+                        u.on_hold = true;
+                        u
+                    }),
+                    mock_item_a_stock_line("pending_target", 5, 1.0),
+                ];
+            })
+            .join(allocated_line()),
+        )
+        .await;
+        insert_pending_inspection(&connection, "pending_target");
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let mut context = service_provider
+            .context(mock_store_a().id, "".to_string())
+            .unwrap();
+        let service = service_provider.stock_line_service;
+
+        // StockDoesNotExist
+        assert_eq!(
+            service.repack_stock_line(
+                &context,
+                inline_init(|r: &mut RepackStockLine| {
+                    r.id = "invalid".to_string();
+                })
+            ),
+            Err(ServiceError::StockDoesNotExist)
+        );
+
+        // InvalidNumberOfPacks
+        assert_eq!(
+            service.repack_stock_line(
+                &context,
+                inline_init(|r: &mut RepackStockLine| {
+                    r.id = "repack_line".to_string();
+                    r.number_of_packs = 11.0;
+                    r.new_pack_size = 5;
+                })
+            ),
+            Err(ServiceError::InvalidNumberOfPacks)
+        );
+
+        // InvalidPackSize
+        assert_eq!(
+            service.repack_stock_line(
+                &context,
+                inline_init(|r: &mut RepackStockLine| {
+                    r.id = "repack_line".to_string();
+                    r.number_of_packs = 1.0;
+                    r.new_pack_size = 10;
+                })
+            ),
+            Err(ServiceError::InvalidPackSize)
+        );
+
+        // NewStockLineDoesNotMatch
+        assert_eq!(
+            service.repack_stock_line(
+                &context,
+                inline_init(|r: &mut RepackStockLine| {
+                    r.id = "repack_line".to_string();
+                    r.number_of_packs = 1.0;
+                    r.new_pack_size = 5;
+                    r.new_stock_line_id = Some("other_batch".to_string());
+                })
+            ),
+            Err(ServiceError::NewStockLineDoesNotMatch)
+        );
+        assert_eq!(
+            service.repack_stock_line(
+                &context,
+                inline_init(|r: &mut RepackStockLine| {
+                    r.id = "repack_line".to_string();
+                    r.number_of_packs = 1.0;
+                    r.new_pack_size = 5;
+                    r.new_stock_line_id = Some("held_target".to_string());
+                })
+            ),
+            Err(ServiceError::NewStockLineDoesNotMatch)
+        );
+
+        // StockLineIsAllocated
+        assert_eq!(
+            service.repack_stock_line(
+                &context,
+                inline_init(|r: &mut RepackStockLine| {
+                    r.id = "allocated_line".to_string();
+                    r.number_of_packs = 1.0;
+                    r.new_pack_size = 5;
+                })
+            ),
+            Err(ServiceError::StockLineIsAllocated)
+        );
+
+        // StockLineIsPendingInspection
+        assert_eq!(
+            service.repack_stock_line(
+                &context,
+                inline_init(|r: &mut RepackStockLine| {
+                    r.id = "repack_line".to_string();
+                    r.number_of_packs = 1.0;
+                    r.new_pack_size = 5;
+                    r.new_stock_line_id = Some("pending_target".to_string());
+                })
+            ),
+            Err(ServiceError::StockLineIsPendingInspection)
+        );
+
+        // StockDoesNotBelongToStore
+        context.store_id = "store_b".to_string();
+        assert_eq!(
+            service.repack_stock_line(
+                &context,
+                inline_init(|r: &mut RepackStockLine| {
+                    r.id = "repack_line".to_string();
+                    r.number_of_packs = 1.0;
+                    r.new_pack_size = 5;
+                })
+            ),
+            Err(ServiceError::StockDoesNotBelongToStore)
+        );
+    }
+
+    #[actix_rt::test]
+    async fn repack_stock_line_success() {
+        let (_, connection, connection_manager, _) = setup_all_with_data(
+            "repack_stock_line_success",
+            MockDataInserts::all(),
+            inline_init(|r: &mut MockData| {
+                r.stock_lines = vec![
+                    inline_edit(&mock_item_a_stock_line("repack_line", 10, 10.0), |mut u| {
+                        u.cost_price_per_pack = 20.0;
+                        u.sell_price_per_pack = 30.0;
+                        u
+                    }),
+                    mock_item_a_stock_line("repack_target", 5, 2.0),
+                ];
+            }),
+        )
+        .await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider
+            .context(mock_store_a().id, mock_user_account_a().id)
+            .unwrap();
+        let service = service_provider.stock_line_service;
+
+        // Into a new stock line
+        let new_stock_line = service
+            .repack_stock_line(
+                &context,
+                RepackStockLine {
+                    id: "repack_line".to_string(),
+                    number_of_packs: 2.0,
+                    new_pack_size: 5,
+                    new_stock_line_id: None,
+                    location_id: None,
+                },
+            )
+            .unwrap()
+            .stock_line_row;
+
+        // Cost per unit is kept
+        assert_eq!(
+            new_stock_line,
+            inline_edit(
+                &mock_item_a_stock_line(&new_stock_line.id, 5, 4.0),
+                |mut u| {
+                    u.cost_price_per_pack = 10.0;
+                    u.sell_price_per_pack = 15.0;
+                    u
+                }
+            )
+        );
+        let stock_line_repo = StockLineRowRepository::new(&connection);
+        let existing = stock_line_repo.find_one_by_id("repack_line").unwrap();
+        assert_eq!(existing.available_number_of_packs, 8.0);
+        assert_eq!(existing.total_number_of_packs, 8.0);
+
+        // The number of units moved shows up in the ledger of both stock lines
+        let ledger = ItemLedgerRepository::new(&connection);
+        let quantities = |stock_line_id: &str| {
+            ledger
+                .query_by_filter(
+                    ItemLedgerFilter::new().stock_line_id(EqualFilter::equal_to(stock_line_id)),
+                )
+                .unwrap()
+                .into_iter()
+                .map(|row| row.quantity)
+                .collect::<Vec<f64>>()
+        };
+        assert_eq!(quantities("repack_line"), vec![-20.0]);
+        assert_eq!(quantities(&new_stock_line.id), vec![20.0]);
+
+        let logs = ActivityLogRowRepository::new(&connection)
+            .find_many_by_record_id("repack_line")
+            .unwrap();
+        assert_eq!(logs.len(), 1);
+        assert_eq!(logs[0].r#type, ActivityLogType::Repack);
+        assert_eq!(
+            logs[0].event,
+            Some("2 packs of 10 repacked into 4 packs of 5".to_string())
+        );
+
+        // All remaining packs into an existing stock line
+        let target = service
+            .repack_stock_line(
+                &context,
+                RepackStockLine {
+                    id: "repack_line".to_string(),
+                    number_of_packs: 8.0,
+                    new_pack_size: 5,
+                    new_stock_line_id: Some("repack_target".to_string()),
+                    location_id: None,
+                },
+            )
+            .unwrap()
+            .stock_line_row;
+        assert_eq!(target.id, "repack_target");
+        assert_eq!(target.available_number_of_packs, 18.0);
+        assert_eq!(target.total_number_of_packs, 18.0);
+        let existing = stock_line_repo.find_one_by_id("repack_line").unwrap();
+        assert_eq!(existing.total_number_of_packs, 0.0);
+    }
+}
@@ -0,0 +1,230 @@
+#[cfg(test)]
+mod test {
+    use repository::{
+        mock::{
+            mock_item_a, mock_item_a_stock_line, mock_location_1, mock_store_a,
+            mock_user_account_a, MockData, MockDataInserts,
+        },
+        test_db::setup_all_with_data,
+        EqualFilter, InvoiceRowStatus, ItemLedgerFilter, ItemLedgerRepository,
+        LocationMovementFilter, LocationMovementRepository, StockLineRow, StockLineRowRepository,
+    };
+    use util::{inline_edit, inline_init};
+
+    use crate::{
+        service_provider::ServiceProvider,
+        stock_line::{
+            tests::mock::{allocated_stock_line, insert_pending_inspection},
+            SplitStockLine,
+        },
+    };
+
+    type ServiceError = crate::stock_line::SplitStockLineError;
+
+    fn allocated_line() -> MockData {
+        allocated_stock_line(
+            mock_item_a_stock_line("allocated_line", 10, 10.0),
+            InvoiceRowStatus::Allocated,
+        )
+    }
+
+    #[actix_rt::test]
+    async fn split_stock_line_errors() {
+        let (_, connection, connection_manager, _) = setup_all_with_data(
+            "split_stock_line_errors",
+            MockDataInserts::all(),
+            inline_init(|r: &mut MockData| {
+                r.stock_lines = vec![
+                    mock_item_a_stock_line("split_line", 10, 10.0),
+                    inline_edit(&mock_item_a_stock_line("held_line", 10, 10.0), |mut u| {
+                        u.on_hold = true;
+                        u
+                    }),
+                    mock_item_a_stock_line("pending_line", 10, 10.0),
+                ];
+            })
+            .join(allocated_line()),
+        )
+        .await;
+        insert_pending_inspection(&connection, "pending_line");
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let mut context = service_provider
+            .context(mock_store_a().id, "".to_string())
+            .unwrap();
+        let service = service_provider.stock_line_service;
+
+        // StockDoesNotExist
+        assert_eq!(
+            service.split_stock_line(
+                &context,
+                inline_init(|r: &mut SplitStockLine| {
+                    r.id = "invalid".to_string();
+                })
+            ),
+            Err(ServiceError::StockDoesNotExist)
+        );
+
+        // LocationDoesNotExist
+        assert_eq!(
+            service.split_stock_line(
+                &context,
+                inline_init(|r: &mut SplitStockLine| {
+                    r.id = "split_line".to_string();
+                    r.number_of_packs = 1.0;
+                    r.location_id = Some("invalid".to_string());
+                })
+            ),
+            Err(ServiceError::LocationDoesNotExist)
+        );
+
+        // InvalidNumberOfPacks
+        assert_eq!(
+            service.split_stock_line(
+                &context,
+                inline_init(|r: &mut SplitStockLine| {
+                    r.id = "split_line".to_string();
+                    r.number_of_packs = 10.0;
+                })
+            ),
+            Err(ServiceError::InvalidNumberOfPacks)
+        );
+
+        // StockLineIsAllocated
+        assert_eq!(
+            service.split_stock_line(
+                &context,
+                inline_init(|r: &mut SplitStockLine| {
+                    r.id = "allocated_line".to_string();
+                    r.number_of_packs = 1.0;
+                })
+            ),
+            Err(ServiceError::StockLineIsAllocated)
+        );
+
+        // CannotReleaseHold
+        assert_eq!(
+            service.split_stock_line(
+                &context,
+                inline_init(|r: &mut SplitStockLine| {
+                    r.id = "held_line".to_string();
+                    r.number_of_packs = 1.0;
+                    r.on_hold = Some(false);
+                })
+            ),
+            Err(ServiceError::CannotReleaseHold)
+        );
+
+        // StockLineIsPendingInspection
+        assert_eq!(
+            service.split_stock_line(
+                &context,
+                inline_init(|r: &mut SplitStockLine| {
+                    r.id = "pending_line".to_string();
+                    r.number_of_packs = 1.0;
+                })
+            ),
+            Err(ServiceError::StockLineIsPendingInspection)
+        );
+
+        // StockDoesNotBelongToStore
+        context.store_id = "store_b".to_string();
+        assert_eq!(
+            service.split_stock_line(
+                &context,
+                inline_init(|r: &mut SplitStockLine| {
+                    r.id = "split_line".to_string();
+                    r.number_of_packs = 1.0;
+                })
+            ),
+            Err(ServiceError::StockDoesNotBelongToStore)
+        );
+    }
+
+    #[actix_rt::test]
+    async fn split_stock_line_success() {
+        let (_, connection, connection_manager, _) = setup_all_with_data(
+            "split_stock_line_success",
+            MockDataInserts::all(),
+            inline_init(|r: &mut MockData| {
+                r.stock_lines = vec![inline_edit(
+                    &mock_item_a_stock_line("split_line", 10, 10.0),
+                    |mut u| {
+                        u.batch = Some("split_batch".to_string());
+                        u.cost_price_per_pack = 2.0;
+                        u
+                    },
+                )];
+            }),
+        )
+        .await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider
+            .context(mock_store_a().id, mock_user_account_a().id)
+            .unwrap();
+        let service = service_provider.stock_line_service;
+
+        let new_stock_line = service
+            .split_stock_line(
+                &context,
+                SplitStockLine {
+                    id: "split_line".to_string(),
+                    number_of_packs: 4.0,
+                    location_id: Some(mock_location_1().id),
+                    on_hold: Some(true),
+                },
+            )
+            .unwrap()
+            .stock_line_row;
+
+        assert_eq!(
+            new_stock_line,
+            inline_init(|r: &mut StockLineRow| {
+                r.id = new_stock_line.id.clone();
+                r.location_id = Some(mock_location_1().id);
+                r.on_hold = true;
+                r.available_number_of_packs = 4.0;
+                r.total_number_of_packs = 4.0;
+                r.item_id = mock_item_a().id;
+                r.store_id = mock_store_a().id;
+                r.batch = Some("split_batch".to_string());
+                r.pack_size = 10;
+                r.cost_price_per_pack = 2.0;
+            })
+        );
+        let existing = StockLineRowRepository::new(&connection)
+            .find_one_by_id("split_line")
+            .unwrap();
+        assert_eq!(existing.available_number_of_packs, 6.0);
+        assert_eq!(existing.total_number_of_packs, 6.0);
+
+        // The moved packs show up in the ledger of both stock lines
+        let ledger = ItemLedgerRepository::new(&connection);
+        let quantities = |stock_line_id: &str| {
+            ledger
+                .query_by_filter(
+                    ItemLedgerFilter::new().stock_line_id(EqualFilter::equal_to(stock_line_id)),
+                )
+                .unwrap()
+                .into_iter()
+                .map(|row| row.quantity)
+                .collect::<Vec<f64>>()
+        };
+        assert_eq!(quantities("split_line"), vec![-40.0]);
+        assert_eq!(quantities(&new_stock_line.id), vec![40.0]);
+
+        // The new stock line entered its location
+        let movements = LocationMovementRepository::new(&connection)
+            .query_by_filter(
+                LocationMovementFilter::new()
+                    .stock_line_id(EqualFilter::equal_to(&new_stock_line.id)),
+            )
+            .unwrap();
+        assert_eq!(movements.len(), 1);
+        assert_eq!(
+            movements[0].location_movement_row.location_id,
+            Some(mock_location_1().id)
+        );
+    }
+}
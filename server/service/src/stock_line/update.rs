@@ -1,15 +1,19 @@
 use chrono::{NaiveDate, Utc};
 use repository::{
-    ActivityLogType, DatetimeFilter, EqualFilter, LocationMovementFilter,
-    LocationMovementRepository, LocationMovementRow, LocationMovementRowRepository,
-    RepositoryError, StockLine, StockLineRow, StockLineRowRepository, StorageConnection,
+    ActivityLogType, DatetimeFilter, EqualFilter, HoldRecordType, HoldRowRepository,
+    LocationMovementFilter, LocationMovementRepository, LocationMovementRow,
+    LocationMovementRowRepository, RepositoryError, StockLine, StockLineRow,
+    StockLineRowRepository, StorageConnection,
 };
 use util::uuid::uuid;
 
 use crate::{
     activity_log::activity_log_stock_entry,
     service_provider::ServiceContext,
-    stock_line::validate::{check_location_exists, check_stock_line_exists, check_store},
+    stock_line::validate::{
+        check_location_exists, check_stock_line_exists, check_stock_line_not_pending_inspection,
+        check_store,
+    },
     SingleRecordError,
 };
 
@@ -32,6 +36,8 @@ pub enum UpdateStockLineError {
     StockDoesNotBelongToStore,
     StockDoesNotExist,
     LocationDoesNotExist,
+    /// Stock waiting for its goods receipt inspection can't be taken off hold
+    StockLineIsPendingInspection,
     UpdatedStockNotFound,
     StockMovementNotFound,
 }
@@ -49,6 +55,10 @@ pub fn update_stock_line(
             let (new_stock_line, location_movements) =
                 generate(ctx.store_id.clone(), connection, existing.clone(), input)?;
             StockLineRowRepository::new(&connection).upsert_one(&new_stock_line)?;
+            if !new_stock_line.on_hold {
+                HoldRowRepository::new(connection)
+                    .delete_by_record(&HoldRecordType::StockLine, &new_stock_line.id)?;
+            }
 
             if let Some(location_movements) = location_movements {
                 for movement in location_movements {
@@ -84,6 +94,11 @@ fn validate(
             return Err(LocationDoesNotExist);
         }
     }
+    if input.on_hold == Some(false)
+        && !check_stock_line_not_pending_inspection(connection, &stock_line.id)?
+    {
+        return Err(StockLineIsPendingInspection);
+    }
 
     Ok(stock_line)
 }
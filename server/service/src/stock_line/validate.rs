@@ -1,6 +1,7 @@
 use repository::{
-    EqualFilter, LocationFilter, LocationRepository, RepositoryError, StockLineRow,
-    StockLineRowRepository, StorageConnection,
+    EqualFilter, GoodsReceiptInspectionRowRepository, InvoiceLineFilter, InvoiceLineRepository,
+    InvoiceRowStatus, InvoiceRowType, LocationFilter, LocationRepository, RepositoryError,
+    StockLineRow, StockLineRowRepository, StorageConnection,
 };
 
 pub fn check_stock_line_exists(
@@ -31,3 +32,33 @@ pub fn check_location_exists(
         .count(Some(LocationFilter::new().id(EqualFilter::equal_to(id))))?;
     Ok(count == 1)
 }
+
+/// Stock lines used by outbound shipments that haven't been shipped yet can't be restructured,
+/// the shipment lines would refer to packs that moved to another stock line
+pub fn check_stock_line_not_allocated(
+    connection: &StorageConnection,
+    stock_line_id: &str,
+) -> Result<bool, RepositoryError> {
+    let count = InvoiceLineRepository::new(connection).count(Some(
+        InvoiceLineFilter::new()
+            .stock_line_id(EqualFilter::equal_to(stock_line_id))
+            .invoice_type(InvoiceRowType::OutboundShipment.equal_to())
+            .invoice_status(InvoiceRowStatus::equal_any(vec![
+                InvoiceRowStatus::New,
+                InvoiceRowStatus::Allocated,
+                InvoiceRowStatus::Picked,
+            ])),
+    ))?;
+    Ok(count == 0)
+}
+
+/// Stock of a received line waiting for its goods receipt inspection is only released by
+/// recording the inspection result
+pub fn check_stock_line_not_pending_inspection(
+    connection: &StorageConnection,
+    stock_line_id: &str,
+) -> Result<bool, RepositoryError> {
+    let inspection = GoodsReceiptInspectionRowRepository::new(connection)
+        .find_pending_by_stock_line_id(stock_line_id)?;
+    Ok(inspection.is_none())
+}
@@ -0,0 +1,953 @@
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+use repository::{
+    BarcodeFilter, BarcodeRepository, EqualFilter, ItemFilter, ItemRepository, RepositoryError,
+    SimpleStringFilter, StocktakeLine, StocktakeLineFilter, StocktakeLineRepository, StocktakeRow,
+    StorageConnection,
+};
+use util::{csv::parse_csv, uuid::uuid};
+
+use crate::{
+    service_provider::ServiceContext, stocktake_line::*, validate::check_store_id_matches,
+};
+
+use super::{
+    batch_stocktake,
+    validate::{check_stocktake_exist, check_stocktake_not_finalised},
+    BatchStocktake, BatchStocktakeResult,
+};
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct ImportStocktakeCounts {
+    pub stocktake_id: String,
+    /// Delimited export of a scanner or spreadsheet
+    pub content: String,
+    /// Only report how the rows would be applied
+    pub dry_run: bool,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ImportStocktakeCountsError {
+    DatabaseError(RepositoryError),
+    InvalidStore,
+    StocktakeDoesNotExist,
+    CannotEditFinalised,
+    StocktakeIsLocked,
+    InvalidFile(String),
+    InternalError(String),
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum ImportedCountConflict {
+    /// Row doesn't identify a single line, e.g. the batch is missing and the item has several
+    /// lines
+    MultipleLinesMatch,
+    /// Line was already counted with a different number of packs, the count is not overwritten
+    AlreadyCounted { counted_number_of_packs: f64 },
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum ImportedCountError {
+    ItemNotFound,
+    InvalidQuantity,
+    InvalidExpiryDate,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum ImportedCountStatus {
+    /// Counted packs are set on the existing stocktake line
+    Matched {
+        stocktake_line_id: String,
+    },
+    /// No stocktake line matches, a new line is inserted
+    NewLine {
+        stocktake_line_id: String,
+    },
+    Conflict(ImportedCountConflict),
+    Error(ImportedCountError),
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct ImportedCountRow {
+    /// Row number in the file (empty lines excluded)
+    pub row_number: u32,
+    pub barcode: Option<String>,
+    pub item_code: Option<String>,
+    pub item_id: Option<String>,
+    pub batch: Option<String>,
+    pub expiry_date: Option<NaiveDate>,
+    /// Packs of the matched line, quantities scanned with a barcode of another pack size are
+    /// converted to the pack size of the line
+    pub number_of_packs: f64,
+    pub status: ImportedCountStatus,
+}
+
+#[derive(Debug, Default)]
+pub struct ImportStocktakeCountsResult {
+    pub rows: Vec<ImportedCountRow>,
+    /// Result of applying the matched and new rows, None for a dry run
+    pub batch_result: Option<BatchStocktakeResult>,
+    /// Lines of a blind stocktake the counts of the user were recorded on, empty for a dry run
+    pub blind_count_lines: Vec<StocktakeLine>,
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) struct CountRow {
+    pub row_number: u32,
+    pub barcode: Option<String>,
+    pub item_code: Option<String>,
+    pub batch: Option<String>,
+    pub expiry_date: Result<Option<NaiveDate>, ()>,
+    pub number_of_packs: Result<f64, ()>,
+}
+
+struct Columns {
+    barcode: Option<usize>,
+    item_code: Option<usize>,
+    batch: Option<usize>,
+    expiry: Option<usize>,
+    quantity: Option<usize>,
+}
+
+/// Column order of scanner exports without a header row
+const SCANNER_COLUMNS: Columns = Columns {
+    barcode: Some(0),
+    item_code: None,
+    batch: Some(1),
+    expiry: Some(2),
+    quantity: Some(3),
+};
+
+fn find_columns(header: &[String]) -> Option<Columns> {
+    let position = |matches: &dyn Fn(&str) -> bool| {
+        header
+            .iter()
+            .position(|column| matches(&column.to_lowercase()))
+    };
+
+    let barcode =
+        position(&|column| column.contains("barcode") || column == "gtin" || column == "ean");
+    let item_code = position(&|column| {
+        (column.contains("code") && !column.contains("barcode")) || column == "sku"
+    });
+    if barcode.is_none() && item_code.is_none() {
+        return None;
+    }
+
+    Some(Columns {
+        barcode,
+        item_code,
+        batch: position(&|column| column.contains("batch") || column.contains("lot")),
+        expiry: position(&|column| column.contains("expir") || column == "exp"),
+        quantity: position(&|column| {
+            column.contains("quantity") || column.contains("qty") || column.contains("count")
+        }),
+    })
+}
+
+const DATE_FORMATS: &[&str] = &["%Y-%m-%d", "%d/%m/%Y", "%d.%m.%Y"];
+
+fn parse_date(value: &str) -> Option<NaiveDate> {
+    DATE_FORMATS
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(value, format).ok())
+}
+
+/// Rows are read by the column names of the header row (barcode, item code, batch, expiry and
+/// quantity), files without a header are read in the scanner order: barcode, batch, expiry and
+/// quantity. Rows without a quantity count as one pack, i.e. one row per scan.
+pub(crate) fn parse_count_rows(content: &str) -> Result<Vec<CountRow>, String> {
+    let rows = parse_csv(content);
+    let header = rows
+        .first()
+        .ok_or_else(|| "File doesn't have any rows".to_string())?;
+    let (columns, skip) = match find_columns(header) {
+        Some(columns) => (columns, 1),
+        None => (SCANNER_COLUMNS, 0),
+    };
+
+    let count_rows = rows
+        .iter()
+        .enumerate()
+        .skip(skip)
+        .map(|(index, row)| {
+            let field = |column: Option<usize>| {
+                column
+                    .and_then(|column| row.get(column))
+                    .filter(|value| !value.is_empty())
+                    .cloned()
+            };
+
+            let expiry_date = match field(columns.expiry) {
+                Some(expiry) => parse_date(&expiry).map(Some).ok_or(()),
+                None => Ok(None),
+            };
+            let number_of_packs = match field(columns.quantity) {
+                Some(quantity) => quantity
+                    .replace(',', ".")
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|quantity| *quantity >= 0.0)
+                    .ok_or(()),
+                None => Ok(1.0),
+            };
+
+            CountRow {
+                row_number: index as u32 + 1,
+                barcode: field(columns.barcode),
+                item_code: field(columns.item_code),
+                batch: field(columns.batch),
+                expiry_date,
+                number_of_packs,
+            }
+        })
+        .collect();
+
+    Ok(count_rows)
+}
+
+/// Item id and pack size of the scanned barcode, barcodes that aren't registered are tried as
+/// item codes since item codes are often printed as barcodes
+struct ItemLookup<'a> {
+    connection: &'a StorageConnection,
+    barcodes: HashMap<String, Option<(String, Option<i32>)>>,
+    item_codes: HashMap<String, Option<String>>,
+}
+
+impl<'a> ItemLookup<'a> {
+    fn new(connection: &'a StorageConnection) -> Self {
+        ItemLookup {
+            connection,
+            barcodes: HashMap::new(),
+            item_codes: HashMap::new(),
+        }
+    }
+
+    fn by_item_code(&mut self, code: &str) -> Result<Option<String>, RepositoryError> {
+        if let Some(item_id) = self.item_codes.get(code) {
+            return Ok(item_id.clone());
+        }
+        let item_id = ItemRepository::new(self.connection)
+            .query_one(
+                None,
+                ItemFilter::new().code(SimpleStringFilter::equal_to(code)),
+            )?
+            .map(|item| item.item_row.id);
+        self.item_codes.insert(code.to_string(), item_id.clone());
+        Ok(item_id)
+    }
+
+    fn by_barcode(
+        &mut self,
+        value: &str,
+    ) -> Result<Option<(String, Option<i32>)>, RepositoryError> {
+        if let Some(item) = self.barcodes.get(value) {
+            return Ok(item.clone());
+        }
+        let barcode = BarcodeRepository::new(self.connection)
+            .query_by_filter(BarcodeFilter::new().value(EqualFilter::equal_to(value)))?
+            .pop();
+        let item = match barcode {
+            Some(barcode) => Some((barcode.barcode_row.item_id, barcode.barcode_row.pack_size)),
+            None => self.by_item_code(value)?.map(|item_id| (item_id, None)),
+        };
+        self.barcodes.insert(value.to_string(), item.clone());
+        Ok(item)
+    }
+}
+
+fn same_batch(line_batch: &Option<String>, batch: &str) -> bool {
+    line_batch
+        .as_ref()
+        .map(|line_batch| line_batch.trim().eq_ignore_ascii_case(batch))
+        .unwrap_or(false)
+}
+
+/// Lines of the item with the batch of the row, when several lines match these are narrowed
+/// down by the expiry date and the pack size of the barcode
+fn matching_lines<'a>(
+    lines: &'a [StocktakeLine],
+    item_id: &str,
+    batch: &Option<String>,
+    expiry_date: &Option<NaiveDate>,
+    pack_size: Option<i32>,
+) -> Vec<&'a StocktakeLine> {
+    let mut matches: Vec<&StocktakeLine> = lines
+        .iter()
+        .filter(|line| line.line.item_id == item_id)
+        .filter(|line| match batch {
+            Some(batch) => same_batch(&line.line.batch, batch),
+            None => true,
+        })
+        .collect();
+
+    if matches.len() > 1 && expiry_date.is_some() {
+        matches.retain(|line| &line.line.expiry_date == expiry_date);
+    }
+    if matches.len() > 1 && pack_size.is_some() {
+        matches.retain(|line| line.line.pack_size == pack_size);
+    }
+    matches
+}
+
+struct NewLine {
+    id: String,
+    item_id: String,
+    batch: Option<String>,
+    expiry_date: Option<NaiveDate>,
+    pack_size: i32,
+    number_of_packs: f64,
+}
+
+struct GenerateResult {
+    rows: Vec<ImportedCountRow>,
+    update_lines: Vec<UpdateStocktakeLine>,
+    insert_lines: Vec<InsertStocktakeLine>,
+    /// Counts of the user for the lines of a blind stocktake, recorded after the new lines are
+    /// inserted
+    blind_counts: Vec<InsertStocktakeLineCount>,
+}
+
+/// New lines of a dry run get ids from the row they were first scanned on, so repeated dry runs
+/// of a file report the same lines
+fn generate(
+    connection: &StorageConnection,
+    stocktake: &StocktakeRow,
+    count_rows: Vec<CountRow>,
+    dry_run: bool,
+) -> Result<GenerateResult, RepositoryError> {
+    let lines = StocktakeLineRepository::new(connection).query_by_filter(
+        StocktakeLineFilter::new().stocktake_id(EqualFilter::equal_to(&stocktake.id)),
+    )?;
+    let mut lookup = ItemLookup::new(connection);
+
+    let mut rows = Vec::new();
+    // Rows scanned for the same line add up
+    let mut line_counts: Vec<(&StocktakeLine, f64)> = Vec::new();
+    let mut new_lines: Vec<NewLine> = Vec::new();
+
+    for count_row in count_rows {
+        let CountRow {
+            row_number,
+            barcode,
+            item_code,
+            batch,
+            expiry_date,
+            number_of_packs,
+        } = count_row;
+
+        let mut item = match &barcode {
+            Some(barcode) => lookup.by_barcode(barcode)?,
+            None => None,
+        };
+        if let (None, Some(item_code)) = (&item, &item_code) {
+            item = lookup
+                .by_item_code(item_code)?
+                .map(|item_id| (item_id, None));
+        }
+        let mut row = ImportedCountRow {
+            row_number,
+            barcode,
+            item_code,
+            item_id: item.as_ref().map(|(item_id, _)| item_id.clone()),
+            batch,
+            expiry_date: expiry_date.unwrap_or_default(),
+            number_of_packs: number_of_packs.unwrap_or_default(),
+            status: ImportedCountStatus::Error(ImportedCountError::ItemNotFound),
+        };
+
+        let (item_id, pack_size) = match item {
+            Some(item) => item,
+            None => {
+                rows.push(row);
+                continue;
+            }
+        };
+        if expiry_date.is_err() {
+            row.status = ImportedCountStatus::Error(ImportedCountError::InvalidExpiryDate);
+            rows.push(row);
+            continue;
+        }
+        if number_of_packs.is_err() {
+            row.status = ImportedCountStatus::Error(ImportedCountError::InvalidQuantity);
+            rows.push(row);
+            continue;
+        }
+
+        let matches = matching_lines(&lines, &item_id, &row.batch, &row.expiry_date, pack_size);
+        row.status = match matches.as_slice() {
+            [line] => {
+                if let (Some(barcode_pack_size), Some(line_pack_size)) =
+                    (pack_size, line.line.pack_size)
+                {
+                    row.number_of_packs *= barcode_pack_size as f64 / line_pack_size as f64;
+                }
+                match line_counts
+                    .iter_mut()
+                    .find(|(counted_line, _)| counted_line.line.id == line.line.id)
+                {
+                    Some((_, count)) => *count += row.number_of_packs,
+                    None => line_counts.push((line, row.number_of_packs)),
+                }
+                ImportedCountStatus::Matched {
+                    stocktake_line_id: line.line.id.clone(),
+                }
+            }
+            [] => {
+                let pack_size = pack_size.unwrap_or(1);
+                let existing = new_lines.iter_mut().find(|new_line| {
+                    new_line.item_id == item_id
+                        && new_line.batch.as_deref().map(str::to_lowercase)
+                            == row.batch.as_deref().map(str::to_lowercase)
+                        && new_line.expiry_date == row.expiry_date
+                        && new_line.pack_size == pack_size
+                });
+                let id = match existing {
+                    Some(new_line) => {
+                        new_line.number_of_packs += row.number_of_packs;
+                        new_line.id.clone()
+                    }
+                    None => {
+                        let id = if dry_run {
+                            format!("{}_row_{}", stocktake.id, row.row_number)
+                        } else {
+                            uuid()
+                        };
+                        new_lines.push(NewLine {
+                            id: id.clone(),
+                            item_id,
+                            batch: row.batch.clone(),
+                            expiry_date: row.expiry_date,
+                            pack_size,
+                            number_of_packs: row.number_of_packs,
+                        });
+                        id
+                    }
+                };
+                ImportedCountStatus::NewLine {
+                    stocktake_line_id: id,
+                }
+            }
+            _ => ImportedCountStatus::Conflict(ImportedCountConflict::MultipleLinesMatch),
+        };
+        rows.push(row);
+    }
+
+    let mut update_lines = Vec::new();
+    let mut blind_counts = Vec::new();
+    for (line, number_of_packs) in line_counts {
+        if stocktake.is_blind_count {
+            // A counter's recount replaces their previous count
+            blind_counts.push(InsertStocktakeLineCount {
+                stocktake_line_id: line.line.id.clone(),
+                counted_number_of_packs: number_of_packs,
+            });
+            continue;
+        }
+        match line.line.counted_number_of_packs {
+            Some(counted) if counted == number_of_packs => {}
+            Some(counted) => {
+                for row in rows.iter_mut() {
+                    if row.status
+                        == (ImportedCountStatus::Matched {
+                            stocktake_line_id: line.line.id.clone(),
+                        })
+                    {
+                        row.status =
+                            ImportedCountStatus::Conflict(ImportedCountConflict::AlreadyCounted {
+                                counted_number_of_packs: counted,
+                            });
+                    }
+                }
+            }
+            None => update_lines.push(UpdateStocktakeLine {
+                id: line.line.id.clone(),
+                counted_number_of_packs: Some(number_of_packs),
+                ..Default::default()
+            }),
+        }
+    }
+
+    let mut insert_lines = Vec::new();
+    for new_line in new_lines {
+        let counted_number_of_packs = if stocktake.is_blind_count {
+            blind_counts.push(InsertStocktakeLineCount {
+                stocktake_line_id: new_line.id.clone(),
+                counted_number_of_packs: new_line.number_of_packs,
+            });
+            None
+        } else {
+            Some(new_line.number_of_packs)
+        };
+        insert_lines.push(InsertStocktakeLine {
+            id: new_line.id,
+            stocktake_id: stocktake.id.clone(),
+            counted_number_of_packs,
+            item_id: Some(new_line.item_id),
+            batch: new_line.batch,
+            expiry_date: new_line.expiry_date,
+            pack_size: Some(new_line.pack_size as u32),
+            ..Default::default()
+        });
+    }
+
+    Ok(GenerateResult {
+        rows,
+        update_lines,
+        insert_lines,
+        blind_counts,
+    })
+}
+
+fn validate(
+    connection: &StorageConnection,
+    store_id: &str,
+    input: &ImportStocktakeCounts,
+) -> Result<StocktakeRow, ImportStocktakeCountsError> {
+    let stocktake = match check_stocktake_exist(connection, &input.stocktake_id)? {
+        Some(stocktake) => stocktake,
+        None => return Err(ImportStocktakeCountsError::StocktakeDoesNotExist),
+    };
+    if !check_store_id_matches(store_id, &stocktake.store_id) {
+        return Err(ImportStocktakeCountsError::InvalidStore);
+    }
+    if !check_stocktake_not_finalised(&stocktake.status) {
+        return Err(ImportStocktakeCountsError::CannotEditFinalised);
+    }
+    if stocktake.is_locked {
+        return Err(ImportStocktakeCountsError::StocktakeIsLocked);
+    }
+
+    Ok(stocktake)
+}
+
+/// Errors of the import transaction, a failed batch is returned as the result of the import once
+/// the lines written before the failure are rolled back
+enum ImportError {
+    Error(ImportStocktakeCountsError),
+    BatchFailed(Box<ImportStocktakeCountsResult>),
+}
+
+fn batch_has_errors(result: &BatchStocktakeResult) -> bool {
+    result.insert_line.iter().any(|line| line.result.is_err())
+        || result.update_line.iter().any(|line| line.result.is_err())
+}
+
+/// Matches the rows of a scanner or spreadsheet export to the stocktake lines by barcode or item
+/// code and batch. Unless it's a dry run, the counts of matched lines are set and unmatched rows
+/// are inserted as new lines through [batch_stocktake], rows with conflicts or errors are skipped.
+/// For a blind stocktake the counts are recorded as the counts of the user through
+/// [insert_stocktake_line_count].
+pub fn import_stocktake_counts(
+    ctx: &ServiceContext,
+    input: ImportStocktakeCounts,
+) -> Result<ImportStocktakeCountsResult, ImportStocktakeCountsError> {
+    let result = ctx
+        .connection
+        .transaction_sync(|connection| {
+            let stocktake = validate(connection, &ctx.store_id, &input)?;
+            let count_rows = parse_count_rows(&input.content)
+                .map_err(ImportStocktakeCountsError::InvalidFile)?;
+            let GenerateResult {
+                rows,
+                update_lines,
+                insert_lines,
+                blind_counts,
+            } = generate(connection, &stocktake, count_rows, input.dry_run)?;
+
+            if input.dry_run {
+                return Ok(ImportStocktakeCountsResult {
+                    rows,
+                    batch_result: None,
+                    blind_count_lines: Vec::new(),
+                });
+            }
+
+            let batch_result = batch_stocktake(
+                ctx,
+                BatchStocktake {
+                    insert_stocktake: None,
+                    insert_line: Some(insert_lines),
+                    update_line: Some(update_lines),
+                    delete_line: None,
+                    update_stocktake: None,
+                    delete_stocktake: None,
+                    continue_on_error: Some(false),
+                },
+            )?;
+            if batch_has_errors(&batch_result) {
+                return Err(ImportError::BatchFailed(Box::new(
+                    ImportStocktakeCountsResult {
+                        rows,
+                        batch_result: Some(batch_result),
+                        blind_count_lines: Vec::new(),
+                    },
+                )));
+            }
+
+            let blind_count_lines = blind_counts
+                .into_iter()
+                .map(|count| {
+                    insert_stocktake_line_count(ctx, count).map_err(|error| match error {
+                        InsertStocktakeLineCountError::DatabaseError(error) => {
+                            ImportStocktakeCountsError::DatabaseError(error)
+                        }
+                        error => ImportStocktakeCountsError::InternalError(format!("{:?}", error)),
+                    })
+                })
+                .collect::<Result<Vec<StocktakeLine>, ImportStocktakeCountsError>>()?;
+
+            Ok(ImportStocktakeCountsResult {
+                rows,
+                batch_result: Some(batch_result),
+                blind_count_lines,
+            })
+        })
+        .map_err(|error| error.to_inner_error());
+
+    match result {
+        Ok(result) => Ok(result),
+        Err(ImportError::BatchFailed(result)) => Ok(*result),
+        Err(ImportError::Error(error)) => Err(error),
+    }
+}
+
+impl From<RepositoryError> for ImportStocktakeCountsError {
+    fn from(error: RepositoryError) -> Self {
+        ImportStocktakeCountsError::DatabaseError(error)
+    }
+}
+
+impl From<ImportStocktakeCountsError> for ImportError {
+    fn from(error: ImportStocktakeCountsError) -> Self {
+        ImportError::Error(error)
+    }
+}
+
+impl From<RepositoryError> for ImportError {
+    fn from(error: RepositoryError) -> Self {
+        ImportError::Error(ImportStocktakeCountsError::DatabaseError(error))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::NaiveDate;
+    use repository::{
+        mock::{mock_item_a, mock_item_b, mock_store_a, MockData, MockDataInserts},
+        test_db::setup_all_with_data,
+        BarcodeRow, StocktakeLineCountRowRepository, StocktakeLineRow, StocktakeLineRowRepository,
+        StocktakeRow, StocktakeStatus,
+    };
+    use util::inline_init;
+
+    use crate::{
+        service_provider::ServiceProvider,
+        stocktake::{
+            ImportStocktakeCounts, ImportStocktakeCountsError as ServiceError,
+            ImportedCountConflict, ImportedCountError, ImportedCountStatus,
+        },
+    };
+
+    use super::parse_count_rows;
+
+    fn stocktake() -> StocktakeRow {
+        inline_init(|r: &mut StocktakeRow| {
+            r.id = "stocktake".to_string();
+            r.store_id = mock_store_a().id;
+        })
+    }
+
+    fn blind_stocktake() -> StocktakeRow {
+        inline_init(|r: &mut StocktakeRow| {
+            r.id = "blind_stocktake".to_string();
+            r.store_id = mock_store_a().id;
+            r.is_blind_count = true;
+        })
+    }
+
+    fn finalised_stocktake() -> StocktakeRow {
+        inline_init(|r: &mut StocktakeRow| {
+            r.id = "finalised_stocktake".to_string();
+            r.store_id = mock_store_a().id;
+            r.status = StocktakeStatus::Finalised;
+        })
+    }
+
+    fn stocktake_line(
+        id: &str,
+        item_id: &str,
+        batch: &str,
+        counted: Option<f64>,
+    ) -> StocktakeLineRow {
+        inline_init(|r: &mut StocktakeLineRow| {
+            r.id = id.to_string();
+            r.stocktake_id = stocktake().id;
+            r.item_id = item_id.to_string();
+            r.batch = Some(batch.to_string());
+            r.pack_size = Some(1);
+            r.snapshot_number_of_packs = 10.0;
+            r.counted_number_of_packs = counted;
+        })
+    }
+
+    #[test]
+    fn parse_scanner_file_without_header() {
+        let rows = parse_count_rows("9300001;A1;31/01/2030;2,5\n9300001;;;\n").unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].barcode, Some("9300001".to_string()));
+        assert_eq!(rows[0].batch, Some("A1".to_string()));
+        assert_eq!(
+            rows[0].expiry_date,
+            Ok(Some(NaiveDate::from_ymd_opt(2030, 1, 31).unwrap()))
+        );
+        assert_eq!(rows[0].number_of_packs, Ok(2.5));
+        // One row per scan
+        assert_eq!(rows[1].batch, None);
+        assert_eq!(rows[1].number_of_packs, Ok(1.0));
+    }
+
+    #[actix_rt::test]
+    async fn import_stocktake_counts() {
+        let (_, connection, connection_manager, _) = setup_all_with_data(
+            "import_stocktake_counts",
+            MockDataInserts::none()
+                .stores()
+                .items()
+                .names()
+                .units()
+                .barcodes(),
+            inline_init(|r: &mut MockData| {
+                r.barcodes = vec![
+                    inline_init(|r: &mut BarcodeRow| {
+                        r.id = "barcode".to_string();
+                        r.value = "9300001".to_string();
+                        r.item_id = mock_item_a().id;
+                        r.pack_size = Some(1);
+                    }),
+                    // Box of two packs
+                    inline_init(|r: &mut BarcodeRow| {
+                        r.id = "box_barcode".to_string();
+                        r.value = "9300002".to_string();
+                        r.item_id = mock_item_b().id;
+                        r.pack_size = Some(2);
+                    }),
+                ];
+                r.stocktakes = vec![stocktake(), finalised_stocktake()];
+                r.stocktake_lines = vec![
+                    stocktake_line("line_a1", &mock_item_a().id, "A1", None),
+                    stocktake_line("line_a2", &mock_item_a().id, "A2", Some(3.0)),
+                    stocktake_line("line_b1", &mock_item_b().id, "B1", None),
+                ];
+            }),
+        )
+        .await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider
+            .context(mock_store_a().id, "".to_string())
+            .unwrap();
+        let service = service_provider.stocktake_service;
+
+        let content = "Barcode,Batch,Expiry,Qty\n\
+            9300001,A1,,2\n\
+            9300001,a1,,3\n\
+            9300001,A2,,4\n\
+            9300001,,,1\n\
+            item_b_code,B1,,6\n\
+            item_b_code,B2,2030-01-31,2\n\
+            unknown,X,,1\n\
+            9300001,A1,31/02/2030,1\n\
+            9300001,A1,,-1\n\
+            9300002,B1,,2\n"
+            .to_string();
+
+        // StocktakeDoesNotExist
+        let error = service
+            .import_stocktake_counts(
+                &context,
+                ImportStocktakeCounts {
+                    stocktake_id: "invalid".to_string(),
+                    content: content.clone(),
+                    dry_run: true,
+                },
+            )
+            .unwrap_err();
+        assert_eq!(error, ServiceError::StocktakeDoesNotExist);
+
+        // CannotEditFinalised
+        let error = service
+            .import_stocktake_counts(
+                &context,
+                ImportStocktakeCounts {
+                    stocktake_id: finalised_stocktake().id,
+                    content: content.clone(),
+                    dry_run: true,
+                },
+            )
+            .unwrap_err();
+        assert_eq!(error, ServiceError::CannotEditFinalised);
+
+        // InvalidFile
+        let error = service
+            .import_stocktake_counts(
+                &context,
+                ImportStocktakeCounts {
+                    stocktake_id: stocktake().id,
+                    content: "".to_string(),
+                    dry_run: true,
+                },
+            )
+            .unwrap_err();
+        assert!(matches!(error, ServiceError::InvalidFile(_)));
+
+        // Dry run
+        let result = service
+            .import_stocktake_counts(
+                &context,
+                ImportStocktakeCounts {
+                    stocktake_id: stocktake().id,
+                    content: content.clone(),
+                    dry_run: true,
+                },
+            )
+            .unwrap();
+        assert!(result.batch_result.is_none());
+        let statuses: Vec<ImportedCountStatus> =
+            result.rows.iter().map(|row| row.status.clone()).collect();
+        // Id of the row the new line was first scanned on
+        let new_line_id = "stocktake_row_7".to_string();
+        assert_eq!(
+            statuses,
+            vec![
+                ImportedCountStatus::Matched {
+                    stocktake_line_id: "line_a1".to_string()
+                },
+                ImportedCountStatus::Matched {
+                    stocktake_line_id: "line_a1".to_string()
+                },
+                ImportedCountStatus::Conflict(ImportedCountConflict::AlreadyCounted {
+                    counted_number_of_packs: 3.0
+                }),
+                ImportedCountStatus::Conflict(ImportedCountConflict::MultipleLinesMatch),
+                // Item code scanned as barcode
+                ImportedCountStatus::Matched {
+                    stocktake_line_id: "line_b1".to_string()
+                },
+                ImportedCountStatus::NewLine {
+                    stocktake_line_id: new_line_id
+                },
+                ImportedCountStatus::Error(ImportedCountError::ItemNotFound),
+                ImportedCountStatus::Error(ImportedCountError::InvalidExpiryDate),
+                ImportedCountStatus::Error(ImportedCountError::InvalidQuantity),
+                ImportedCountStatus::Matched {
+                    stocktake_line_id: "line_b1".to_string()
+                },
+            ]
+        );
+        // Boxes of two packs
+        assert_eq!(result.rows[9].number_of_packs, 4.0);
+        let line_repo = StocktakeLineRowRepository::new(&connection);
+        let line_a1 = line_repo.find_one_by_id("line_a1").unwrap().unwrap();
+        assert_eq!(line_a1.counted_number_of_packs, None);
+
+        // Apply
+        let result = service
+            .import_stocktake_counts(
+                &context,
+                ImportStocktakeCounts {
+                    stocktake_id: stocktake().id,
+                    content,
+                    dry_run: false,
+                },
+            )
+            .unwrap();
+        let batch_result = result.batch_result.unwrap();
+        assert_eq!(batch_result.update_line.len(), 2);
+        assert_eq!(batch_result.insert_line.len(), 1);
+
+        let line_a1 = line_repo.find_one_by_id("line_a1").unwrap().unwrap();
+        assert_eq!(line_a1.counted_number_of_packs, Some(5.0));
+        let line_a2 = line_repo.find_one_by_id("line_a2").unwrap().unwrap();
+        assert_eq!(line_a2.counted_number_of_packs, Some(3.0));
+        let line_b1 = line_repo.find_one_by_id("line_b1").unwrap().unwrap();
+        assert_eq!(line_b1.counted_number_of_packs, Some(10.0));
+        let new_line = line_repo
+            .find_one_by_id(&batch_result.insert_line[0].input.id)
+            .unwrap()
+            .unwrap();
+        assert_eq!(new_line.item_id, mock_item_b().id);
+        assert_eq!(new_line.batch, Some("B2".to_string()));
+        assert_eq!(
+            new_line.expiry_date,
+            Some(NaiveDate::from_ymd_opt(2030, 1, 31).unwrap())
+        );
+        assert_eq!(new_line.counted_number_of_packs, Some(2.0));
+    }
+
+    #[actix_rt::test]
+    async fn import_blind_stocktake_counts() {
+        let (_, connection, connection_manager, _) = setup_all_with_data(
+            "import_blind_stocktake_counts",
+            MockDataInserts::none()
+                .stores()
+                .items()
+                .names()
+                .units()
+                .barcodes(),
+            inline_init(|r: &mut MockData| {
+                r.stocktakes = vec![blind_stocktake()];
+                r.stocktake_lines = vec![inline_init(|r: &mut StocktakeLineRow| {
+                    r.id = "blind_line".to_string();
+                    r.stocktake_id = blind_stocktake().id;
+                    r.item_id = mock_item_a().id;
+                    r.batch = Some("A1".to_string());
+                    r.pack_size = Some(1);
+                    r.snapshot_number_of_packs = 10.0;
+                })];
+            }),
+        )
+        .await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider
+            .context(mock_store_a().id, "counter_a".to_string())
+            .unwrap();
+
+        let result = service_provider
+            .stocktake_service
+            .import_stocktake_counts(
+                &context,
+                ImportStocktakeCounts {
+                    stocktake_id: blind_stocktake().id,
+                    content: "Code,Batch,Qty\nitem_a_code,A1,4\nitem_b_code,B1,2\n".to_string(),
+                    dry_run: false,
+                },
+            )
+            .unwrap();
+        assert_eq!(result.blind_count_lines.len(), 2);
+
+        // Counts are recorded for the user, the line count needs a second counter
+        let count_repo = StocktakeLineCountRowRepository::new(&connection);
+        let counts = count_repo
+            .find_many_by_stocktake_line_id("blind_line")
+            .unwrap();
+        assert_eq!(counts.len(), 1);
+        assert_eq!(counts[0].user_id, "counter_a");
+        assert_eq!(counts[0].counted_number_of_packs, 4.0);
+        let line_repo = StocktakeLineRowRepository::new(&connection);
+        let blind_line = line_repo.find_one_by_id("blind_line").unwrap().unwrap();
+        assert_eq!(blind_line.counted_number_of_packs, None);
+
+        let new_line_id = &result.batch_result.unwrap().insert_line[0].input.id;
+        let new_line = line_repo.find_one_by_id(new_line_id).unwrap().unwrap();
+        assert_eq!(new_line.item_id, mock_item_b().id);
+        assert_eq!(new_line.counted_number_of_packs, None);
+        let counts = count_repo
+            .find_many_by_stocktake_line_id(new_line_id)
+            .unwrap();
+        assert_eq!(counts.len(), 1);
+        assert_eq!(counts[0].counted_number_of_packs, 2.0);
+    }
+}
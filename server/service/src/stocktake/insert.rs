@@ -1,16 +1,19 @@
-use chrono::{NaiveDate, Utc};
+use std::collections::HashSet;
+
+use chrono::{Duration, NaiveDate, Utc};
 use repository::{
-    ActivityLogType, EqualFilter, LocationFilter, LocationRepository, MasterListFilter,
-    MasterListLineFilter, MasterListLineRepository, MasterListRepository, NumberRowType,
-    RepositoryError, StockLineFilter, StockLineRepository, StockLineRow, Stocktake,
-    StocktakeFilter, StocktakeLineRow, StocktakeLineRowRepository, StocktakeRepository,
-    StocktakeRow, StocktakeRowRepository, StocktakeStatus, StorageConnection,
+    ActivityLogType, DateFilter, DatetimeFilter, EqualFilter, LocationFilter, LocationRepository,
+    MasterListFilter, MasterListLineFilter, MasterListLineRepository, MasterListRepository,
+    NumberRowType, RepositoryError, SimpleStringFilter, StockLineFilter, StockLineRepository,
+    StockLineRow, Stocktake, StocktakeFilter, StocktakeLineFilter, StocktakeLineRepository,
+    StocktakeLineRow, StocktakeLineRowRepository, StocktakeRepository, StocktakeRow,
+    StocktakeRowRepository, StocktakeStatus, StorageConnection,
 };
 use util::uuid::uuid;
 
 use crate::{
-    activity_log::activity_log_entry, number::next_number, service_provider::ServiceContext,
-    validate::check_store_exists,
+    activity_log::activity_log_entry, location::hierarchy::get_location_subtree_ids,
+    number::next_number, service_provider::ServiceContext, validate::check_store_exists,
 };
 
 use super::query::get_stocktake;
@@ -22,9 +25,22 @@ pub struct InsertStocktake {
     pub description: Option<String>,
     pub stocktake_date: Option<NaiveDate>,
     pub is_locked: Option<bool>,
+    pub is_blind_count: Option<bool>,
     pub master_list_id: Option<String>,
     pub location_id: Option<String>,
+    /// Generate lines for the given items, e.g. for cycle counts
+    pub item_ids: Option<Vec<String>>,
     pub items_have_stock: Option<bool>,
+    /// Stock expiring on or before the date
+    pub expires_before: Option<NaiveDate>,
+    /// Stock lines that are on hold
+    pub stock_on_hold: Option<bool>,
+    /// Stock of items with a code or name containing the text
+    pub item_code_or_name: Option<String>,
+    /// Items in the store's master lists without stock
+    pub master_list_items_without_stock: Option<bool>,
+    /// Leave out stock counted in a stocktake finalised within this many days
+    pub not_counted_in_days: Option<u32>,
 }
 
 #[derive(Debug, PartialEq)]
@@ -84,7 +100,12 @@ fn validate(
     if !check_store_exists(connection, store_id)? {
         return Err(InsertStocktakeError::InvalidStore);
     }
-    if stocktake.master_list_id.is_some() && stocktake.location_id.is_some() {
+    let line_sources = [
+        stocktake.master_list_id.is_some(),
+        stocktake.location_id.is_some(),
+        stocktake.item_ids.is_some(),
+    ];
+    if line_sources.iter().filter(|is_set| **is_set).count() > 1 {
         return Err(InsertStocktakeError::InvalidArguments);
     }
     if let Some(master_list_id) = &stocktake.master_list_id {
@@ -110,9 +131,16 @@ fn generate(
         description,
         stocktake_date,
         is_locked,
+        is_blind_count,
         location_id,
         master_list_id,
+        item_ids,
         items_have_stock,
+        expires_before,
+        stock_on_hold,
+        item_code_or_name,
+        master_list_items_without_stock,
+        not_counted_in_days,
     }: InsertStocktake,
 ) -> Result<(StocktakeRow, Vec<StocktakeLineRow>), RepositoryError> {
     let stocktake_number = next_number(connection, &NumberRowType::Stocktake, store_id)?;
@@ -123,15 +151,69 @@ fn generate(
         }
         None => Vec::new(),
     };
-    let location_lines = match location_id {
-        Some(location_id) => generate_lines_from_location(connection, store_id, &id, &location_id)?,
+    let location_lines = match &location_id {
+        Some(location_id) => generate_lines_from_location(connection, store_id, &id, location_id)?,
+        None => Vec::new(),
+    };
+    let item_lines = match item_ids {
+        Some(item_ids) => generate_lines_for_items(connection, store_id, &id, &item_ids)?,
         None => Vec::new(),
     };
     let items_have_stock_lines = match items_have_stock {
         Some(_) => generate_lines_with_stock(connection, store_id, &id)?,
         None => Vec::new(),
     };
-    let lines = [master_list_lines, location_lines, items_have_stock_lines].concat();
+    let expiring_lines = match expires_before {
+        Some(expires_before) => generate_lines_with_stock_filter(
+            connection,
+            store_id,
+            &id,
+            StockLineFilter::new().expiry_date(DateFilter::before_or_equal_to(expires_before)),
+        )?,
+        None => Vec::new(),
+    };
+    let on_hold_lines = match stock_on_hold {
+        Some(true) => generate_lines_with_stock_filter(
+            connection,
+            store_id,
+            &id,
+            StockLineFilter::new().is_on_hold(true),
+        )?,
+        _ => Vec::new(),
+    };
+    let item_code_or_name_lines = match item_code_or_name {
+        Some(item_code_or_name) => generate_lines_with_stock_filter(
+            connection,
+            store_id,
+            &id,
+            StockLineFilter::new().item_code_or_name(SimpleStringFilter::like(&item_code_or_name)),
+        )?,
+        None => Vec::new(),
+    };
+    let without_stock_lines = match master_list_items_without_stock {
+        Some(true) => {
+            generate_lines_for_master_list_items_without_stock(connection, store_id, &id)?
+        }
+        _ => Vec::new(),
+    };
+
+    let lines = deduplicate_lines(
+        [
+            master_list_lines,
+            location_lines,
+            item_lines,
+            items_have_stock_lines,
+            expiring_lines,
+            on_hold_lines,
+            item_code_or_name_lines,
+            without_stock_lines,
+        ]
+        .concat(),
+    );
+    let lines = match not_counted_in_days {
+        Some(days) => remove_recently_counted_lines(connection, store_id, lines, days)?,
+        None => lines,
+    };
 
     Ok((
         StocktakeRow {
@@ -145,6 +227,8 @@ fn generate(
             user_id: user_id.to_string(),
             store_id: store_id.to_string(),
             is_locked: is_locked.unwrap_or(false),
+            is_blind_count: is_blind_count.unwrap_or(false),
+            location_id,
             // Default
             finalised_datetime: None,
             inventory_addition_id: None,
@@ -154,6 +238,63 @@ fn generate(
     ))
 }
 
+fn line_from_stock_line(stocktake_id: &str, stock_line_row: StockLineRow) -> StocktakeLineRow {
+    let StockLineRow {
+        id: stock_line_id,
+        item_id,
+        location_id,
+        batch,
+        pack_size,
+        cost_price_per_pack,
+        sell_price_per_pack,
+        total_number_of_packs,
+        expiry_date,
+        note,
+        supplier_id: _,
+        store_id: _,
+        on_hold: _,
+        available_number_of_packs: _,
+    } = stock_line_row;
+
+    StocktakeLineRow {
+        id: uuid(),
+        stocktake_id: stocktake_id.to_string(),
+        snapshot_number_of_packs: total_number_of_packs,
+        item_id,
+        location_id,
+        batch,
+        expiry_date,
+        note,
+        stock_line_id: Some(stock_line_id),
+        pack_size: Some(pack_size),
+        cost_price_per_pack: Some(cost_price_per_pack),
+        sell_price_per_pack: Some(sell_price_per_pack),
+        comment: None,
+        counted_number_of_packs: None,
+        inventory_adjustment_reason_id: None,
+    }
+}
+
+fn line_without_stock(stocktake_id: &str, item_id: &str) -> StocktakeLineRow {
+    StocktakeLineRow {
+        id: uuid(),
+        stocktake_id: stocktake_id.to_string(),
+        snapshot_number_of_packs: 0.0,
+        item_id: item_id.to_string(),
+        location_id: None,
+        batch: None,
+        expiry_date: None,
+        note: None,
+        stock_line_id: None,
+        pack_size: None,
+        cost_price_per_pack: None,
+        sell_price_per_pack: None,
+        comment: None,
+        counted_number_of_packs: None,
+        inventory_adjustment_reason_id: None,
+    }
+}
+
 fn generate_lines_from_master_list(
     connection: &StorageConnection,
     store_id: &str,
@@ -168,76 +309,37 @@ fn generate_lines_from_master_list(
         .map(|r| r.item_id)
         .collect();
 
-    let mut result = Vec::<StocktakeLineRow>::new();
+    generate_lines_for_items(connection, store_id, stocktake_id, &item_ids)
+}
 
-    item_ids.iter().for_each(|item_id| {
-        let stock_lines = StockLineRepository::new(&connection)
-            .query_by_filter(
-                StockLineFilter::new()
-                    .item_id(EqualFilter::equal_to(item_id))
-                    .store_id(EqualFilter::equal_to(store_id))
-                    .has_packs_in_store(true),
-                Some(store_id.to_string()),
-            )
-            .unwrap();
+/// Generates a line per stock line of each item, or an empty line for items without stock
+fn generate_lines_for_items(
+    connection: &StorageConnection,
+    store_id: &str,
+    stocktake_id: &str,
+    item_ids: &[String],
+) -> Result<Vec<StocktakeLineRow>, RepositoryError> {
+    let mut result = Vec::<StocktakeLineRow>::new();
 
-        if stock_lines.len() == 0 {
-            result.push(StocktakeLineRow {
-                id: uuid(),
-                stocktake_id: stocktake_id.to_string(),
-                snapshot_number_of_packs: 0.0,
-                item_id: item_id.to_string(),
-                location_id: None,
-                batch: None,
-                expiry_date: None,
-                note: None,
-                stock_line_id: None,
-                pack_size: None,
-                cost_price_per_pack: None,
-                sell_price_per_pack: None,
-                comment: None,
-                counted_number_of_packs: None,
-                inventory_adjustment_reason_id: None,
-            });
+    for item_id in item_ids {
+        let stock_lines = StockLineRepository::new(&connection).query_by_filter(
+            StockLineFilter::new()
+                .item_id(EqualFilter::equal_to(item_id))
+                .store_id(EqualFilter::equal_to(store_id))
+                .has_packs_in_store(true),
+            Some(store_id.to_string()),
+        )?;
+
+        if stock_lines.is_empty() {
+            result.push(line_without_stock(stocktake_id, item_id));
         } else {
-            stock_lines.into_iter().for_each(|line| {
-                let StockLineRow {
-                    id: stock_line_id,
-                    item_id,
-                    location_id,
-                    batch,
-                    pack_size,
-                    cost_price_per_pack,
-                    sell_price_per_pack,
-                    total_number_of_packs,
-                    expiry_date,
-                    note,
-                    supplier_id: _,
-                    store_id: _,
-                    on_hold: _,
-                    available_number_of_packs: _,
-                } = line.stock_line_row;
-
-                result.push(StocktakeLineRow {
-                    id: uuid(),
-                    stocktake_id: stocktake_id.to_string(),
-                    snapshot_number_of_packs: total_number_of_packs,
-                    item_id,
-                    location_id,
-                    batch,
-                    expiry_date,
-                    note,
-                    stock_line_id: Some(stock_line_id),
-                    pack_size: Some(pack_size),
-                    cost_price_per_pack: Some(cost_price_per_pack),
-                    sell_price_per_pack: Some(sell_price_per_pack),
-                    comment: None,
-                    counted_number_of_packs: None,
-                    inventory_adjustment_reason_id: None,
-                });
-            });
+            result.extend(
+                stock_lines
+                    .into_iter()
+                    .map(|line| line_from_stock_line(stocktake_id, line.stock_line_row)),
+            );
         }
-    });
+    }
 
     Ok(result)
 }
@@ -247,109 +349,140 @@ fn generate_lines_from_location(
     store_id: &str,
     stocktake_id: &str,
     location_id: &str,
+) -> Result<Vec<StocktakeLineRow>, RepositoryError> {
+    // Include stock in the locations nested under the location
+    let location_ids = get_location_subtree_ids(connection, &[location_id.to_string()])?;
+    generate_lines_with_stock_filter(
+        connection,
+        store_id,
+        stocktake_id,
+        StockLineFilter::new().location_id(EqualFilter::equal_any(location_ids)),
+    )
+}
+
+pub fn generate_lines_with_stock(
+    connection: &StorageConnection,
+    store_id: &str,
+    stocktake_id: &str,
+) -> Result<Vec<StocktakeLineRow>, RepositoryError> {
+    generate_lines_with_stock_filter(connection, store_id, stocktake_id, StockLineFilter::new())
+}
+
+/// Generates a line for each stock line in the store matching the filter
+fn generate_lines_with_stock_filter(
+    connection: &StorageConnection,
+    store_id: &str,
+    stocktake_id: &str,
+    filter: StockLineFilter,
 ) -> Result<Vec<StocktakeLineRow>, RepositoryError> {
     let stock_lines = StockLineRepository::new(&connection).query_by_filter(
-        StockLineFilter::new()
-            .location_id(EqualFilter::equal_to(&location_id))
+        filter
             .store_id(EqualFilter::equal_to(store_id))
             .has_packs_in_store(true),
         Some(store_id.to_string()),
     )?;
 
-    let result = stock_lines
+    Ok(stock_lines
         .into_iter()
-        .map(|line| {
-            let StockLineRow {
-                id: stock_line_id,
-                item_id,
-                location_id,
-                batch,
-                pack_size,
-                cost_price_per_pack,
-                sell_price_per_pack,
-                total_number_of_packs,
-                expiry_date,
-                note,
-                supplier_id: _,
-                store_id: _,
-                on_hold: _,
-                available_number_of_packs: _,
-            } = line.stock_line_row;
-
-            StocktakeLineRow {
-                id: uuid(),
-                stocktake_id: stocktake_id.to_string(),
-                snapshot_number_of_packs: total_number_of_packs,
-                item_id,
-                location_id,
-                batch,
-                expiry_date,
-                note,
-                stock_line_id: Some(stock_line_id),
-                pack_size: Some(pack_size),
-                cost_price_per_pack: Some(cost_price_per_pack),
-                sell_price_per_pack: Some(sell_price_per_pack),
-                comment: None,
-                counted_number_of_packs: None,
-                inventory_adjustment_reason_id: None,
-            }
-        })
-        .collect();
-    Ok(result)
+        .map(|line| line_from_stock_line(stocktake_id, line.stock_line_row))
+        .collect())
 }
 
-pub fn generate_lines_with_stock(
+/// Empty lines for items in the store's master lists without stock, to record stock that was
+/// never entered
+fn generate_lines_for_master_list_items_without_stock(
     connection: &StorageConnection,
     store_id: &str,
     stocktake_id: &str,
 ) -> Result<Vec<StocktakeLineRow>, RepositoryError> {
-    let stock_lines = StockLineRepository::new(&connection).query_by_filter(
-        StockLineFilter::new()
-            .store_id(EqualFilter::equal_to(store_id))
-            .has_packs_in_store(true),
-        Some(store_id.to_string()),
-    )?;
+    let master_list_ids: Vec<String> = MasterListRepository::new(connection)
+        .query_by_filter(
+            MasterListFilter::new().exists_for_store_id(EqualFilter::equal_to(store_id)),
+        )?
+        .into_iter()
+        .map(|master_list| master_list.id)
+        .collect();
+    let mut item_ids: Vec<String> = MasterListLineRepository::new(connection)
+        .query_by_filter(
+            MasterListLineFilter::new().master_list_id(EqualFilter::equal_any(master_list_ids)),
+        )?
+        .into_iter()
+        .map(|line| line.item_id)
+        .collect();
+    item_ids.sort();
+    item_ids.dedup();
 
-    let result = stock_lines
+    let items_with_stock: HashSet<String> = StockLineRepository::new(connection)
+        .query_by_filter(
+            StockLineFilter::new()
+                .item_id(EqualFilter::equal_any(item_ids.clone()))
+                .store_id(EqualFilter::equal_to(store_id))
+                .has_packs_in_store(true),
+            Some(store_id.to_string()),
+        )?
         .into_iter()
-        .map(|line| {
-            let StockLineRow {
-                id: stock_line_id,
-                item_id,
-                location_id,
-                batch,
-                pack_size,
-                cost_price_per_pack,
-                sell_price_per_pack,
-                total_number_of_packs,
-                expiry_date,
-                note,
-                supplier_id: _,
-                store_id: _,
-                on_hold: _,
-                available_number_of_packs: _,
-            } = line.stock_line_row;
-
-            StocktakeLineRow {
-                id: uuid(),
-                stocktake_id: stocktake_id.to_string(),
-                snapshot_number_of_packs: total_number_of_packs,
-                item_id,
-                location_id,
-                batch,
-                expiry_date,
-                note,
-                stock_line_id: Some(stock_line_id),
-                pack_size: Some(pack_size),
-                cost_price_per_pack: Some(cost_price_per_pack),
-                sell_price_per_pack: Some(sell_price_per_pack),
-                comment: None,
-                counted_number_of_packs: None,
-                inventory_adjustment_reason_id: None,
-            }
+        .map(|line| line.stock_line_row.item_id)
+        .collect();
+
+    Ok(item_ids
+        .iter()
+        .filter(|item_id| !items_with_stock.contains(*item_id))
+        .map(|item_id| line_without_stock(stocktake_id, item_id))
+        .collect())
+}
+
+/// Keeps the first line for each stock line, or for each item without stock, when several
+/// selectors match the same stock
+fn deduplicate_lines(lines: Vec<StocktakeLineRow>) -> Vec<StocktakeLineRow> {
+    let mut seen = HashSet::<String>::new();
+    lines
+        .into_iter()
+        .filter(|line| {
+            let key = match &line.stock_line_id {
+                Some(stock_line_id) => format!("stock_line:{}", stock_line_id),
+                None => format!("item:{}", line.item_id),
+            };
+            seen.insert(key)
         })
+        .collect()
+}
+
+/// Removes lines for stock counted in a stocktake finalised in the last `days`, lines without
+/// stock are removed if any stock of the item was counted
+fn remove_recently_counted_lines(
+    connection: &StorageConnection,
+    store_id: &str,
+    lines: Vec<StocktakeLineRow>,
+    days: u32,
+) -> Result<Vec<StocktakeLineRow>, RepositoryError> {
+    let counted_since = Utc::now().naive_utc() - Duration::days(days as i64);
+    let counted_lines: Vec<StocktakeLineRow> = StocktakeLineRepository::new(connection)
+        .query_by_filter(
+            StocktakeLineFilter::new().stocktake(
+                StocktakeFilter::new()
+                    .store_id(EqualFilter::equal_to(store_id))
+                    .status(StocktakeStatus::Finalised.equal_to())
+                    .finalised_datetime(DatetimeFilter::after_or_equal_to(counted_since)),
+            ),
+        )?
+        .into_iter()
+        .map(|line| line.line)
+        .filter(|line| line.counted_number_of_packs.is_some())
         .collect();
-    Ok(result)
+    let counted_stock_line_ids: HashSet<&String> = counted_lines
+        .iter()
+        .filter_map(|line| line.stock_line_id.as_ref())
+        .collect();
+    let counted_item_ids: HashSet<&String> =
+        counted_lines.iter().map(|line| &line.item_id).collect();
+
+    Ok(lines
+        .into_iter()
+        .filter(|line| match &line.stock_line_id {
+            Some(stock_line_id) => !counted_stock_line_ids.contains(stock_line_id),
+            None => !counted_item_ids.contains(&line.item_id),
+        })
+        .collect())
 }
 
 pub fn insert_stocktake(
@@ -395,14 +528,15 @@ mod test {
     use chrono::{NaiveDate, Utc};
     use repository::{
         mock::{
-            item_query_test1, mock_item_a, mock_item_b, mock_location_1,
+            item_query_test1, mock_item_a, mock_item_b, mock_item_c, mock_location_1,
             mock_master_list_item_query_test1, mock_stocktake_a, mock_store_a, mock_store_b,
             mock_user_account_a, MockData, MockDataInserts,
         },
         test_db::{setup_all, setup_all_with_data},
-        EqualFilter, MasterListLineRow, MasterListLineRowRepository, StockLineRow,
-        StockLineRowRepository, StocktakeLineFilter, StocktakeLineRepository, StocktakeRow,
-        StocktakeRowRepository, StocktakeStatus,
+        EqualFilter, LocationRow, LocationRowRepository, MasterListLineRow,
+        MasterListLineRowRepository, MasterListNameJoinRow, MasterListRow, StockLineRow,
+        StockLineRowRepository, StocktakeLineFilter, StocktakeLineRepository,
+        StocktakeLineRowRepository, StocktakeRow, StocktakeRowRepository, StocktakeStatus,
     };
     use util::{inline_edit, inline_init};
 
@@ -458,9 +592,16 @@ mod test {
                     description: Some("description".to_string()),
                     stocktake_date: Some(NaiveDate::from_ymd_opt(2020, 01, 02).unwrap()),
                     is_locked: Some(true),
+                    is_blind_count: None,
                     location_id: None,
                     master_list_id: None,
+                    item_ids: None,
                     items_have_stock: None,
+                    expires_before: None,
+                    stock_on_hold: None,
+                    item_code_or_name: None,
+                    master_list_items_without_stock: None,
+                    not_counted_in_days: None,
                 },
             )
             .unwrap();
@@ -513,9 +654,16 @@ mod test {
                 description: Some("description".to_string()),
                 stocktake_date: Some(NaiveDate::from_ymd_opt(2020, 01, 02).unwrap()),
                 is_locked: Some(true),
+                is_blind_count: None,
                 location_id: None,
                 master_list_id: Some("master_list_filter_test".to_string()),
+                item_ids: None,
                 items_have_stock: None,
+                expires_before: None,
+                stock_on_hold: None,
+                item_code_or_name: None,
+                master_list_items_without_stock: None,
+                not_counted_in_days: None,
             },
         );
         assert!(invalid_result.is_err());
@@ -539,9 +687,16 @@ mod test {
                     description: Some("description".to_string()),
                     stocktake_date: Some(NaiveDate::from_ymd_opt(2020, 01, 02).unwrap()),
                     is_locked: Some(true),
+                    is_blind_count: None,
                     location_id: None,
                     master_list_id: Some(master_list_id.clone()),
+                    item_ids: None,
                     items_have_stock: None,
+                    expires_before: None,
+                    stock_on_hold: None,
+                    item_code_or_name: None,
+                    master_list_items_without_stock: None,
+                    not_counted_in_days: None,
                 },
             )
             .unwrap();
@@ -588,9 +743,16 @@ mod test {
                     description: Some("description".to_string()),
                     stocktake_date: Some(NaiveDate::from_ymd_opt(2020, 01, 02).unwrap()),
                     is_locked: Some(true),
+                    is_blind_count: None,
                     location_id: None,
                     master_list_id: Some(master_list_id.clone()),
+                    item_ids: None,
                     items_have_stock: None,
+                    expires_before: None,
+                    stock_on_hold: None,
+                    item_code_or_name: None,
+                    master_list_items_without_stock: None,
+                    not_counted_in_days: None,
                 },
             )
             .unwrap();
@@ -636,9 +798,16 @@ mod test {
                     description: Some("description".to_string()),
                     stocktake_date: Some(NaiveDate::from_ymd_opt(2020, 01, 02).unwrap()),
                     is_locked: Some(true),
+                    is_blind_count: None,
                     location_id: Some(location_id.clone()),
                     master_list_id: None,
+                    item_ids: None,
                     items_have_stock: None,
+                    expires_before: None,
+                    stock_on_hold: None,
+                    item_code_or_name: None,
+                    master_list_items_without_stock: None,
+                    not_counted_in_days: None,
                 },
             )
             .unwrap();
@@ -673,9 +842,16 @@ mod test {
                     description: Some("description".to_string()),
                     stocktake_date: Some(NaiveDate::from_ymd_opt(2020, 01, 02).unwrap()),
                     is_locked: Some(true),
+                    is_blind_count: None,
                     location_id: Some(location_id.clone()),
                     master_list_id: None,
+                    item_ids: None,
                     items_have_stock: None,
+                    expires_before: None,
+                    stock_on_hold: None,
+                    item_code_or_name: None,
+                    master_list_items_without_stock: None,
+                    not_counted_in_days: None,
                 },
             )
             .unwrap();
@@ -696,6 +872,53 @@ mod test {
             stock_line_row.unwrap().line.stock_line_id,
             Some("stock_line_row_1".to_string())
         );
+
+        // stock in a location nested under the location is included
+        LocationRowRepository::new(&connection)
+            .upsert_one(&inline_init(|r: &mut LocationRow| {
+                r.id = "child_location".to_string();
+                r.code = "child_location".to_string();
+                r.store_id = mock_store_a().id;
+                r.parent_id = Some(location_id.clone());
+            }))
+            .unwrap();
+        StockLineRowRepository::new(&connection)
+            .upsert_one(&inline_init(|r: &mut StockLineRow| {
+                r.id = "stock_line_row_2".to_string();
+                r.store_id = mock_store_a().id;
+                r.item_id = mock_item_b().id;
+                r.location_id = Some("child_location".to_string());
+                r.total_number_of_packs = 10.0;
+            }))
+            .unwrap();
+
+        service
+            .insert_stocktake(
+                &context,
+                inline_init(|r: &mut InsertStocktake| {
+                    r.id = "stocktake_3".to_string();
+                    r.location_id = Some(location_id.clone());
+                }),
+            )
+            .unwrap();
+
+        let stocktake_rows = StocktakeLineRepository::new(&connection)
+            .query_by_filter(
+                StocktakeLineFilter::new().stocktake_id(EqualFilter::equal_to("stocktake_3")),
+            )
+            .unwrap();
+        let mut stock_line_ids: Vec<Option<String>> = stocktake_rows
+            .into_iter()
+            .map(|r| r.line.stock_line_id)
+            .collect();
+        stock_line_ids.sort();
+        assert_eq!(
+            stock_line_ids,
+            vec![
+                Some("stock_line_row_1".to_string()),
+                Some("stock_line_row_2".to_string())
+            ]
+        );
     }
 
     #[actix_rt::test]
@@ -759,9 +982,16 @@ mod test {
                     description: Some("description".to_string()),
                     stocktake_date: Some(NaiveDate::from_ymd_opt(2020, 01, 02).unwrap()),
                     is_locked: Some(true),
+                    is_blind_count: None,
                     location_id: None,
                     master_list_id: None,
+                    item_ids: None,
                     items_have_stock: None,
+                    expires_before: None,
+                    stock_on_hold: None,
+                    item_code_or_name: None,
+                    master_list_items_without_stock: None,
+                    not_counted_in_days: None,
                 },
             )
             .unwrap();
@@ -783,9 +1013,16 @@ mod test {
                     description: Some("description".to_string()),
                     stocktake_date: Some(NaiveDate::from_ymd_opt(2020, 01, 02).unwrap()),
                     is_locked: Some(true),
+                    is_blind_count: None,
                     location_id: None,
                     master_list_id: None,
+                    item_ids: None,
                     items_have_stock: Some(true),
+                    expires_before: None,
+                    stock_on_hold: None,
+                    item_code_or_name: None,
+                    master_list_items_without_stock: None,
+                    not_counted_in_days: None,
                 },
             )
             .unwrap();
@@ -798,4 +1035,164 @@ mod test {
 
         assert_eq!(stocktake_rows.len(), 2);
     }
+
+    #[actix_rt::test]
+    async fn insert_stocktake_with_selectors() {
+        fn stock_line(
+            id: &str,
+            item_id: &str,
+            expiry_date: NaiveDate,
+            on_hold: bool,
+        ) -> StockLineRow {
+            inline_init(|s: &mut StockLineRow| {
+                s.id = id.to_string();
+                s.store_id = mock_store_a().id;
+                s.item_id = item_id.to_string();
+                s.expiry_date = Some(expiry_date);
+                s.on_hold = on_hold;
+                s.pack_size = 1;
+                s.total_number_of_packs = 10.0;
+            })
+        }
+
+        let expired = NaiveDate::from_ymd_opt(2020, 1, 1).unwrap();
+        let later = NaiveDate::from_ymd_opt(2030, 1, 1).unwrap();
+        let (_, connection, connection_manager, _) = setup_all_with_data(
+            "insert_stocktake_with_selectors",
+            MockDataInserts::none()
+                .names()
+                .stores()
+                .user_accounts()
+                .items()
+                .units(),
+            inline_init(|m: &mut MockData| {
+                m.stock_lines = vec![
+                    stock_line("expired", &mock_item_a().id, expired, false),
+                    stock_line("expired_on_hold", &mock_item_a().id, expired, true),
+                    stock_line("later", &mock_item_b().id, later, false),
+                ];
+                m.master_lists = vec![inline_init(|r: &mut MasterListRow| {
+                    r.id = "store_master_list".to_string();
+                })];
+                m.master_list_name_joins = vec![MasterListNameJoinRow {
+                    id: "store_master_list_join".to_string(),
+                    master_list_id: "store_master_list".to_string(),
+                    name_id: mock_store_a().name_id,
+                }];
+            }),
+        )
+        .await;
+        for item_id in [mock_item_a().id, mock_item_b().id, mock_item_c().id] {
+            MasterListLineRowRepository::new(&connection)
+                .upsert_one(&MasterListLineRow {
+                    id: format!("{}_master_list_line", item_id),
+                    item_id,
+                    master_list_id: "store_master_list".to_string(),
+                })
+                .unwrap();
+        }
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider
+            .context(mock_store_a().id, mock_user_account_a().id)
+            .unwrap();
+        let service = service_provider.stocktake_service;
+        let line_ids = |stocktake_id: &str| -> Vec<String> {
+            let mut ids: Vec<String> = StocktakeLineRepository::new(&connection)
+                .query_by_filter(
+                    StocktakeLineFilter::new().stocktake_id(EqualFilter::equal_to(stocktake_id)),
+                )
+                .unwrap()
+                .into_iter()
+                .map(|line| {
+                    line.line
+                        .stock_line_id
+                        .unwrap_or(format!("no_stock_{}", line.line.item_id))
+                })
+                .collect();
+            ids.sort();
+            ids
+        };
+
+        // Stock matching several selectors is only added once
+        service
+            .insert_stocktake(
+                &context,
+                inline_init(|r: &mut InsertStocktake| {
+                    r.id = "expiring_and_on_hold".to_string();
+                    r.expires_before = Some(NaiveDate::from_ymd_opt(2021, 1, 1).unwrap());
+                    r.stock_on_hold = Some(true);
+                }),
+            )
+            .unwrap();
+        assert_eq!(
+            line_ids("expiring_and_on_hold"),
+            vec!["expired".to_string(), "expired_on_hold".to_string()]
+        );
+
+        service
+            .insert_stocktake(
+                &context,
+                inline_init(|r: &mut InsertStocktake| {
+                    r.id = "item_code".to_string();
+                    r.item_code_or_name = Some("item_b".to_string());
+                }),
+            )
+            .unwrap();
+        assert_eq!(line_ids("item_code"), vec!["later".to_string()]);
+
+        service
+            .insert_stocktake(
+                &context,
+                inline_init(|r: &mut InsertStocktake| {
+                    r.id = "without_stock".to_string();
+                    r.master_list_items_without_stock = Some(true);
+                    r.stock_on_hold = Some(true);
+                }),
+            )
+            .unwrap();
+        assert_eq!(
+            line_ids("without_stock"),
+            vec!["expired_on_hold".to_string(), "no_stock_item_c".to_string()]
+        );
+
+        // Stock counted recently is left out
+        let lines = StocktakeLineRepository::new(&connection)
+            .query_by_filter(
+                StocktakeLineFilter::new()
+                    .stocktake_id(EqualFilter::equal_to("expiring_and_on_hold")),
+            )
+            .unwrap();
+        for line in lines {
+            StocktakeLineRowRepository::new(&connection)
+                .upsert_one(&inline_edit(&line.line, |mut r| {
+                    r.counted_number_of_packs = Some(10.0);
+                    r
+                }))
+                .unwrap();
+        }
+        let stocktake = StocktakeRowRepository::new(&connection)
+            .find_one_by_id("expiring_and_on_hold")
+            .unwrap()
+            .unwrap();
+        StocktakeRowRepository::new(&connection)
+            .upsert_one(&inline_edit(&stocktake, |mut r| {
+                r.status = StocktakeStatus::Finalised;
+                r.finalised_datetime = Some(Utc::now().naive_utc());
+                r
+            }))
+            .unwrap();
+
+        service
+            .insert_stocktake(
+                &context,
+                inline_init(|r: &mut InsertStocktake| {
+                    r.id = "not_counted".to_string();
+                    r.items_have_stock = Some(true);
+                    r.not_counted_in_days = Some(30);
+                }),
+            )
+            .unwrap();
+        assert_eq!(line_ids("not_counted"), vec!["later".to_string()]);
+    }
 }
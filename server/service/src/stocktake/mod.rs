@@ -18,6 +18,12 @@ pub use self::update::*;
 mod batch;
 pub use self::batch::*;
 
+mod refresh;
+pub use self::refresh::*;
+
+mod import_counts;
+pub use self::import_counts::*;
+
 pub trait StocktakeServiceTrait: Sync + Send {
     fn get_stocktakes(
         &self,
@@ -67,6 +73,22 @@ pub trait StocktakeServiceTrait: Sync + Send {
         update_stocktake(ctx, input)
     }
 
+    fn refresh_stocktake_snapshot(
+        &self,
+        ctx: &ServiceContext,
+        stocktake_id: &str,
+    ) -> Result<RefreshStocktakeSnapshotResult, RefreshStocktakeSnapshotError> {
+        refresh_stocktake_snapshot(ctx, stocktake_id)
+    }
+
+    fn import_stocktake_counts(
+        &self,
+        ctx: &ServiceContext,
+        input: ImportStocktakeCounts,
+    ) -> Result<ImportStocktakeCountsResult, ImportStocktakeCountsError> {
+        import_stocktake_counts(ctx, input)
+    }
+
     fn batch_stocktake(
         &self,
         ctx: &ServiceContext,
@@ -0,0 +1,476 @@
+use chrono::NaiveDateTime;
+use repository::{
+    EqualFilter, InvoiceLine, InvoiceLineFilter, InvoiceLineRepository, InvoiceLineRowType,
+    RepositoryError, StockLineFilter, StockLineRepository, StockLineRow, StocktakeLine,
+    StocktakeLineCountRowRepository, StocktakeLineFilter, StocktakeLineRepository,
+    StocktakeLineRow, StocktakeLineRowRepository, StocktakeRow, StorageConnection,
+    TransactionError,
+};
+use util::uuid::uuid;
+
+use crate::{
+    location::hierarchy::get_location_subtree_ids, service_provider::ServiceContext,
+    validate::check_store_id_matches,
+};
+
+use super::validate::{check_stocktake_exist, check_stocktake_not_finalised};
+
+#[derive(Debug, PartialEq)]
+pub enum RefreshStocktakeSnapshotError {
+    DatabaseError(RepositoryError),
+    InternalError(String),
+    InvalidStore,
+    StocktakeDoesNotExist,
+    CannotEditFinalised,
+    StocktakeIsLocked,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct RefreshedStocktakeLine {
+    pub line: StocktakeLine,
+    pub previous_snapshot_number_of_packs: f64,
+    pub previous_counted_number_of_packs: Option<f64>,
+}
+
+#[derive(Debug, PartialEq, Default)]
+pub struct RefreshStocktakeSnapshotResult {
+    /// Lines where the stock moved since the snapshot was taken
+    pub updated_lines: Vec<RefreshedStocktakeLine>,
+    /// Lines for stock of the stocktake items received since the stocktake was created, limited
+    /// to the location of the stocktake
+    pub inserted_lines: Vec<StocktakeLine>,
+}
+
+fn validate(
+    connection: &StorageConnection,
+    store_id: &str,
+    stocktake_id: &str,
+) -> Result<StocktakeRow, RefreshStocktakeSnapshotError> {
+    let stocktake = match check_stocktake_exist(connection, stocktake_id)? {
+        Some(stocktake) => stocktake,
+        None => return Err(RefreshStocktakeSnapshotError::StocktakeDoesNotExist),
+    };
+    if !check_store_id_matches(store_id, &stocktake.store_id) {
+        return Err(RefreshStocktakeSnapshotError::InvalidStore);
+    }
+    if !check_stocktake_not_finalised(&stocktake.status) {
+        return Err(RefreshStocktakeSnapshotError::CannotEditFinalised);
+    }
+    if stocktake.is_locked {
+        return Err(RefreshStocktakeSnapshotError::StocktakeIsLocked);
+    }
+    Ok(stocktake)
+}
+
+/// Counted number of packs adjusted by the stock movement since the snapshot, i.e. the
+/// difference between the count and the snapshot stays the same
+fn adjust_count(counted_number_of_packs: f64, movement: f64) -> f64 {
+    (counted_number_of_packs + movement).max(0.0)
+}
+
+fn generate_new_line(stocktake_id: &str, stock_line: StockLineRow) -> StocktakeLineRow {
+    let StockLineRow {
+        id: stock_line_id,
+        item_id,
+        location_id,
+        batch,
+        pack_size,
+        cost_price_per_pack,
+        sell_price_per_pack,
+        total_number_of_packs,
+        expiry_date,
+        note,
+        supplier_id: _,
+        store_id: _,
+        on_hold: _,
+        available_number_of_packs: _,
+    } = stock_line;
+
+    StocktakeLineRow {
+        id: uuid(),
+        stocktake_id: stocktake_id.to_string(),
+        snapshot_number_of_packs: total_number_of_packs,
+        item_id,
+        location_id,
+        batch,
+        expiry_date,
+        note,
+        stock_line_id: Some(stock_line_id),
+        pack_size: Some(pack_size),
+        cost_price_per_pack: Some(cost_price_per_pack),
+        sell_price_per_pack: Some(sell_price_per_pack),
+        comment: None,
+        counted_number_of_packs: None,
+        inventory_adjustment_reason_id: None,
+    }
+}
+
+/// Stock lines received after the stocktake was created, i.e. stock that wasn't in the store
+/// when the lines were generated. Stock lines without a received stock in line are excluded.
+fn received_stock_line_ids(
+    connection: &StorageConnection,
+    stock_line_ids: Vec<String>,
+    stocktake_created_datetime: &NaiveDateTime,
+) -> Result<Vec<String>, RepositoryError> {
+    if stock_line_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+    let stock_in_lines = InvoiceLineRepository::new(connection).query_by_filter(
+        InvoiceLineFilter::new()
+            .stock_line_id(EqualFilter::equal_any(stock_line_ids))
+            .r#type(InvoiceLineRowType::StockIn.equal_to()),
+    )?;
+
+    Ok(stock_in_lines
+        .into_iter()
+        .filter_map(
+            |InvoiceLine {
+                 invoice_line_row,
+                 invoice_row,
+                 ..
+             }| {
+                // inbound shipments are received when delivered, other stock in invoices (e.g.
+                // inventory additions or repacks) are verified when created
+                let received_datetime = invoice_row
+                    .delivered_datetime
+                    .or(invoice_row.verified_datetime)?;
+                if received_datetime <= *stocktake_created_datetime {
+                    return None;
+                }
+                invoice_line_row.stock_line_id
+            },
+        )
+        .collect())
+}
+
+fn load_lines(
+    connection: &StorageConnection,
+    ids: Vec<String>,
+) -> Result<Vec<StocktakeLine>, RepositoryError> {
+    if ids.is_empty() {
+        return Ok(Vec::new());
+    }
+    StocktakeLineRepository::new(connection)
+        .query_by_filter(StocktakeLineFilter::new().id(EqualFilter::equal_any(ids)))
+}
+
+/// Re-reads the current stock of the stocktake lines, moving the snapshot and the counts by the
+/// stock movement since the snapshot, and adds lines for stock of the stocktake items received
+/// since the stocktake was created
+pub fn refresh_stocktake_snapshot(
+    ctx: &ServiceContext,
+    stocktake_id: &str,
+) -> Result<RefreshStocktakeSnapshotResult, RefreshStocktakeSnapshotError> {
+    let result = ctx
+        .connection
+        .transaction_sync(|connection| {
+            let stocktake = validate(connection, &ctx.store_id, stocktake_id)?;
+            let line_repo = StocktakeLineRowRepository::new(connection);
+            let count_repo = StocktakeLineCountRowRepository::new(connection);
+
+            let lines = StocktakeLineRepository::new(connection).query_by_filter(
+                StocktakeLineFilter::new().stocktake_id(EqualFilter::equal_to(stocktake_id)),
+            )?;
+
+            let mut updated = Vec::new();
+            for StocktakeLine {
+                line, stock_line, ..
+            } in &lines
+            {
+                let stock_line = match stock_line {
+                    Some(stock_line) => stock_line,
+                    None => continue,
+                };
+                let movement = stock_line.total_number_of_packs - line.snapshot_number_of_packs;
+                if movement == 0.0 {
+                    continue;
+                }
+
+                if stocktake.is_blind_count {
+                    for mut count in count_repo.find_many_by_stocktake_line_id(&line.id)? {
+                        count.counted_number_of_packs =
+                            adjust_count(count.counted_number_of_packs, movement);
+                        count_repo.upsert_one(&count)?;
+                    }
+                }
+                line_repo.upsert_one(&StocktakeLineRow {
+                    snapshot_number_of_packs: stock_line.total_number_of_packs,
+                    counted_number_of_packs: line
+                        .counted_number_of_packs
+                        .map(|counted| adjust_count(counted, movement)),
+                    ..line.clone()
+                })?;
+                updated.push((
+                    line.id.clone(),
+                    line.snapshot_number_of_packs,
+                    line.counted_number_of_packs,
+                ));
+            }
+
+            let mut item_ids: Vec<String> = lines.iter().map(|l| l.line.item_id.clone()).collect();
+            item_ids.sort();
+            item_ids.dedup();
+            let counted_stock_line_ids: Vec<&String> = lines
+                .iter()
+                .filter_map(|l| l.line.stock_line_id.as_ref())
+                .collect();
+            let mut stock_line_filter = StockLineFilter::new()
+                .item_id(EqualFilter::equal_any(item_ids))
+                .store_id(EqualFilter::equal_to(&ctx.store_id))
+                .has_packs_in_store(true);
+            if let Some(location_id) = &stocktake.location_id {
+                // Include stock in the locations nested under the location
+                let location_ids =
+                    get_location_subtree_ids(connection, std::slice::from_ref(location_id))?;
+                stock_line_filter =
+                    stock_line_filter.location_id(EqualFilter::equal_any(location_ids));
+            }
+            let uncounted_stock_lines: Vec<StockLineRow> = StockLineRepository::new(connection)
+                .query_by_filter(stock_line_filter, Some(ctx.store_id.clone()))?
+                .into_iter()
+                .map(|stock_line| stock_line.stock_line_row)
+                .filter(|stock_line| !counted_stock_line_ids.contains(&&stock_line.id))
+                .collect();
+            let received_ids = received_stock_line_ids(
+                connection,
+                uncounted_stock_lines.iter().map(|l| l.id.clone()).collect(),
+                &stocktake.created_datetime,
+            )?;
+            let new_stock_lines = uncounted_stock_lines
+                .into_iter()
+                .filter(|stock_line| received_ids.contains(&stock_line.id));
+
+            let mut inserted_ids = Vec::new();
+            for stock_line in new_stock_lines {
+                let new_line = generate_new_line(stocktake_id, stock_line);
+                line_repo.upsert_one(&new_line)?;
+                inserted_ids.push(new_line.id);
+            }
+
+            let updated_lines = load_lines(
+                connection,
+                updated.iter().map(|(id, _, _)| id.clone()).collect(),
+            )?
+            .into_iter()
+            .filter_map(|line| {
+                let (_, previous_snapshot, previous_counted) =
+                    updated.iter().find(|(id, _, _)| *id == line.line.id)?;
+                Some(RefreshedStocktakeLine {
+                    line,
+                    previous_snapshot_number_of_packs: *previous_snapshot,
+                    previous_counted_number_of_packs: *previous_counted,
+                })
+            })
+            .collect();
+
+            Ok(RefreshStocktakeSnapshotResult {
+                updated_lines,
+                inserted_lines: load_lines(connection, inserted_ids)?,
+            })
+        })
+        .map_err(|error: TransactionError<RefreshStocktakeSnapshotError>| error.to_inner_error())?;
+    Ok(result)
+}
+
+impl From<RepositoryError> for RefreshStocktakeSnapshotError {
+    fn from(error: RepositoryError) -> Self {
+        RefreshStocktakeSnapshotError::DatabaseError(error)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::Duration;
+    use repository::{
+        mock::{
+            mock_item_a, mock_item_a_stock_line, mock_item_b, mock_name_a, mock_store_a, MockData,
+            MockDataInserts,
+        },
+        test_db::setup_all_with_data,
+        InvoiceLineRow, InvoiceLineRowType, InvoiceRow, InvoiceRowStatus, InvoiceRowType,
+        LocationRow, StocktakeLineRow, StocktakeRow, StocktakeStatus,
+    };
+    use util::{inline_edit, inline_init};
+
+    use crate::{
+        service_provider::ServiceProvider,
+        stocktake::{
+            RefreshStocktakeSnapshotError as ServiceError, UpdateStocktake, UpdateStocktakeStatus,
+        },
+    };
+
+    fn location(id: &str, parent_id: Option<&str>) -> LocationRow {
+        inline_init(|r: &mut LocationRow| {
+            r.id = id.to_string();
+            r.code = id.to_string();
+            r.store_id = mock_store_a().id;
+            r.parent_id = parent_id.map(str::to_string);
+        })
+    }
+
+    fn stocktake() -> StocktakeRow {
+        inline_init(|r: &mut StocktakeRow| {
+            r.id = "stocktake".to_string();
+            r.store_id = mock_store_a().id;
+            r.location_id = Some("shelf".to_string());
+        })
+    }
+
+    /// Inbound shipment delivered the given number of days after the stocktake was created
+    fn inbound_shipment(id: &str, days_after_stocktake: i64) -> InvoiceRow {
+        inline_init(|r: &mut InvoiceRow| {
+            r.id = id.to_string();
+            r.store_id = mock_store_a().id;
+            r.name_id = mock_name_a().id;
+            r.r#type = InvoiceRowType::InboundShipment;
+            r.status = InvoiceRowStatus::Delivered;
+            r.delivered_datetime =
+                Some(stocktake().created_datetime + Duration::days(days_after_stocktake));
+        })
+    }
+
+    fn inbound_line(id: &str, invoice_id: &str, stock_line_id: &str) -> InvoiceLineRow {
+        inline_init(|r: &mut InvoiceLineRow| {
+            r.id = id.to_string();
+            r.invoice_id = invoice_id.to_string();
+            r.item_id = mock_item_a().id;
+            r.stock_line_id = Some(stock_line_id.to_string());
+            r.r#type = InvoiceLineRowType::StockIn;
+            r.pack_size = 1;
+            r.number_of_packs = 3.0;
+        })
+    }
+
+    fn finalised_stocktake() -> StocktakeRow {
+        inline_init(|r: &mut StocktakeRow| {
+            r.id = "finalised_stocktake".to_string();
+            r.store_id = mock_store_a().id;
+            r.status = StocktakeStatus::Finalised;
+        })
+    }
+
+    fn stocktake_line(
+        id: &str,
+        stock_line_id: &str,
+        snapshot: f64,
+        counted: Option<f64>,
+    ) -> StocktakeLineRow {
+        inline_init(|r: &mut StocktakeLineRow| {
+            r.id = id.to_string();
+            r.stocktake_id = stocktake().id;
+            r.stock_line_id = Some(stock_line_id.to_string());
+            r.item_id = mock_item_a().id;
+            r.snapshot_number_of_packs = snapshot;
+            r.counted_number_of_packs = counted;
+        })
+    }
+
+    #[actix_rt::test]
+    async fn refresh_stocktake_snapshot() {
+        let (_, _, connection_manager, _) = setup_all_with_data(
+            "refresh_stocktake_snapshot",
+            MockDataInserts::none().stores().items().names().units(),
+            inline_init(|r: &mut MockData| {
+                r.locations = vec![
+                    location("shelf", None),
+                    location("shelf_bin", Some("shelf")),
+                    location("cold_room", None),
+                ];
+                r.stock_lines = vec![
+                    // Received 2 packs since the snapshot
+                    ("moved", mock_item_a().id, "shelf", 12.0),
+                    ("unchanged", mock_item_a().id, "shelf", 5.0),
+                    // Received since the stocktake was created in a location nested in the
+                    // stocktake location
+                    ("new", mock_item_a().id, "shelf_bin", 3.0),
+                    // Received before the stocktake was created but not counted
+                    ("old", mock_item_a().id, "shelf", 3.0),
+                    // Received since the stocktake was created in another location
+                    ("new_in_cold_room", mock_item_a().id, "cold_room", 3.0),
+                    // Not an item of the stocktake
+                    ("other_item", mock_item_b().id, "shelf", 3.0),
+                ]
+                .into_iter()
+                .map(|(id, item_id, location_id, total_number_of_packs)| {
+                    inline_edit(
+                        &mock_item_a_stock_line(id, 1, total_number_of_packs),
+                        |mut u| {
+                            u.item_id = item_id;
+                            u.location_id = Some(location_id.to_string());
+                            u
+                        },
+                    )
+                })
+                .collect();
+                r.invoices = vec![
+                    inbound_shipment("received_before", -1),
+                    inbound_shipment("received_after", 1),
+                ];
+                r.invoice_lines = vec![
+                    inbound_line("old_line", "received_before", "old"),
+                    inbound_line("new_line", "received_after", "new"),
+                    inbound_line("cold_room_line", "received_after", "new_in_cold_room"),
+                ];
+                r.stocktakes = vec![stocktake(), finalised_stocktake()];
+                r.stocktake_lines = vec![
+                    stocktake_line("moved_line", "moved", 10.0, Some(8.0)),
+                    stocktake_line("unchanged_line", "unchanged", 5.0, Some(5.0)),
+                ];
+            }),
+        )
+        .await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider
+            .context(mock_store_a().id, "user".to_string())
+            .unwrap();
+        let service = &service_provider.stocktake_service;
+
+        // Errors
+        assert_eq!(
+            service.refresh_stocktake_snapshot(&context, "invalid"),
+            Err(ServiceError::StocktakeDoesNotExist)
+        );
+        assert_eq!(
+            service.refresh_stocktake_snapshot(&context, &finalised_stocktake().id),
+            Err(ServiceError::CannotEditFinalised)
+        );
+
+        // Finalisation fails due to the moved stock
+        let finalise = UpdateStocktake {
+            id: stocktake().id,
+            status: Some(UpdateStocktakeStatus::Finalised),
+            ..Default::default()
+        };
+        assert!(service
+            .update_stocktake(&context, finalise.clone())
+            .is_err());
+
+        let result = service
+            .refresh_stocktake_snapshot(&context, &stocktake().id)
+            .unwrap();
+
+        assert_eq!(result.updated_lines.len(), 1);
+        let updated = &result.updated_lines[0];
+        assert_eq!(updated.line.line.id, "moved_line");
+        assert_eq!(updated.previous_snapshot_number_of_packs, 10.0);
+        assert_eq!(updated.previous_counted_number_of_packs, Some(8.0));
+        assert_eq!(updated.line.line.snapshot_number_of_packs, 12.0);
+        // 2 packs missing at the time of counting are still missing
+        assert_eq!(updated.line.line.counted_number_of_packs, Some(10.0));
+
+        assert_eq!(result.inserted_lines.len(), 1);
+        let inserted = &result.inserted_lines[0].line;
+        assert_eq!(inserted.stock_line_id, Some("new".to_string()));
+        assert_eq!(inserted.snapshot_number_of_packs, 3.0);
+        assert_eq!(inserted.counted_number_of_packs, None);
+
+        // Refreshing again doesn't change anything
+        assert_eq!(
+            service.refresh_stocktake_snapshot(&context, &stocktake().id),
+            Ok(Default::default())
+        );
+
+        service.update_stocktake(&context, finalise).unwrap();
+    }
+}
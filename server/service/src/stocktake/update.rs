@@ -52,6 +52,8 @@ pub enum UpdateStocktakeError {
     /// Holds list of affected stock lines
     SnapshotCountCurrentCountMismatch(Vec<StocktakeLine>),
     StockLinesReducedBelowZero(Vec<StockLine>),
+    /// Lines of a blind stocktake without an agreed count
+    CountsNotReconciled(Vec<StocktakeLine>),
 }
 
 fn check_snapshot_matches_current_count(
@@ -73,6 +75,18 @@ fn check_snapshot_matches_current_count(
     None
 }
 
+fn check_counts_reconciled(stocktake_lines: &[StocktakeLine]) -> Option<Vec<StocktakeLine>> {
+    let not_reconciled: Vec<StocktakeLine> = stocktake_lines
+        .iter()
+        .filter(|line| line.line.counted_number_of_packs.is_none())
+        .cloned()
+        .collect();
+    if !not_reconciled.is_empty() {
+        return Some(not_reconciled);
+    }
+    None
+}
+
 fn check_stock_lines_reduced_to_zero(
     connection: &StorageConnection,
     stocktake_lines: &Vec<StocktakeLine>,
@@ -147,6 +161,12 @@ fn validate(
             return Err(UpdateStocktakeError::NoLines);
         }
 
+        if existing.is_blind_count {
+            if let Some(not_reconciled) = check_counts_reconciled(&stocktake_lines) {
+                return Err(UpdateStocktakeError::CountsNotReconciled(not_reconciled));
+            }
+        }
+
         if let Some(stock_reduced_to_zero) =
             check_stock_lines_reduced_to_zero(connection, &stocktake_lines)?
         {
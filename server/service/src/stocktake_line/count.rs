@@ -0,0 +1,364 @@
+use chrono::Utc;
+use repository::{
+    RepositoryError, StocktakeLine, StocktakeLineCountRow, StocktakeLineCountRowRepository,
+    StocktakeLineRow, StocktakeLineRowRepository, StorageConnection,
+};
+use util::uuid::uuid;
+
+use crate::{
+    service_provider::ServiceContext,
+    stocktake::validate::{check_stocktake_exist, check_stocktake_not_finalised},
+    stocktake_line::{query::get_stocktake_line, validate::check_stocktake_line_exist},
+    validate::check_store_id_matches,
+};
+
+/// Number of independent counters that need to agree on the count of a blind stocktake line
+pub const BLIND_COUNT_REQUIRED_COUNTERS: usize = 2;
+
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct InsertStocktakeLineCount {
+    pub stocktake_line_id: String,
+    pub counted_number_of_packs: f64,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum InsertStocktakeLineCountError {
+    DatabaseError(RepositoryError),
+    InternalError(String),
+    InvalidStore,
+    StocktakeLineDoesNotExist,
+    CannotEditFinalised,
+    StocktakeIsLocked,
+    /// Counts can only be added to lines of a blind stocktake
+    NotABlindCount,
+    InvalidCount,
+}
+
+/// Returns the agreed count if enough counters have counted the line and all counts match
+pub fn reconcile_counts(counts: &[StocktakeLineCountRow]) -> Option<f64> {
+    let first = counts.first()?;
+    if counts.len() < BLIND_COUNT_REQUIRED_COUNTERS {
+        return None;
+    }
+    counts
+        .iter()
+        .all(|count| count.counted_number_of_packs == first.counted_number_of_packs)
+        .then_some(first.counted_number_of_packs)
+}
+
+/// Counts disagree and the line needs to be recounted
+pub fn counts_need_recount(counts: &[StocktakeLineCountRow]) -> bool {
+    match counts.first() {
+        Some(first) => counts
+            .iter()
+            .any(|count| count.counted_number_of_packs != first.counted_number_of_packs),
+        None => false,
+    }
+}
+
+fn validate(
+    connection: &StorageConnection,
+    store_id: &str,
+    input: &InsertStocktakeLineCount,
+) -> Result<StocktakeLineRow, InsertStocktakeLineCountError> {
+    let stocktake_line = match check_stocktake_line_exist(connection, &input.stocktake_line_id)? {
+        Some(stocktake_line) => stocktake_line,
+        None => return Err(InsertStocktakeLineCountError::StocktakeLineDoesNotExist),
+    };
+    let stocktake = match check_stocktake_exist(connection, &stocktake_line.stocktake_id)? {
+        Some(stocktake) => stocktake,
+        None => {
+            return Err(InsertStocktakeLineCountError::InternalError(
+                "Orphan stocktake line!".to_string(),
+            ))
+        }
+    };
+    if !check_stocktake_not_finalised(&stocktake.status) {
+        return Err(InsertStocktakeLineCountError::CannotEditFinalised);
+    }
+    if stocktake.is_locked {
+        return Err(InsertStocktakeLineCountError::StocktakeIsLocked);
+    }
+    if !check_store_id_matches(store_id, &stocktake.store_id) {
+        return Err(InsertStocktakeLineCountError::InvalidStore);
+    }
+    if !stocktake.is_blind_count {
+        return Err(InsertStocktakeLineCountError::NotABlindCount);
+    }
+    if input.counted_number_of_packs < 0.0 {
+        return Err(InsertStocktakeLineCountError::InvalidCount);
+    }
+
+    Ok(stocktake_line)
+}
+
+/// A counter recounting a line replaces their previous count
+fn generate(
+    connection: &StorageConnection,
+    user_id: &str,
+    existing: StocktakeLineRow,
+    input: InsertStocktakeLineCount,
+) -> Result<(StocktakeLineCountRow, StocktakeLineRow), RepositoryError> {
+    let mut counts = StocktakeLineCountRowRepository::new(connection)
+        .find_many_by_stocktake_line_id(&existing.id)?;
+    let previous_count = counts.iter().position(|count| count.user_id == user_id);
+
+    let count = StocktakeLineCountRow {
+        id: previous_count
+            .map(|index| counts[index].id.clone())
+            .unwrap_or_else(uuid),
+        stocktake_line_id: existing.id.clone(),
+        user_id: user_id.to_string(),
+        counted_number_of_packs: input.counted_number_of_packs,
+        datetime: Utc::now().naive_utc(),
+    };
+    match previous_count {
+        Some(index) => counts[index] = count.clone(),
+        None => counts.push(count.clone()),
+    }
+
+    let line = StocktakeLineRow {
+        counted_number_of_packs: reconcile_counts(&counts),
+        ..existing
+    };
+
+    Ok((count, line))
+}
+
+pub fn insert_stocktake_line_count(
+    ctx: &ServiceContext,
+    input: InsertStocktakeLineCount,
+) -> Result<StocktakeLine, InsertStocktakeLineCountError> {
+    let result = ctx
+        .connection
+        .transaction_sync(|connection| {
+            let existing = validate(connection, &ctx.store_id, &input)?;
+            let (count, line) = generate(connection, &ctx.user_id, existing, input)?;
+            StocktakeLineCountRowRepository::new(connection).upsert_one(&count)?;
+            StocktakeLineRowRepository::new(connection).upsert_one(&line)?;
+
+            let line = get_stocktake_line(ctx, line.id)?;
+            line.ok_or(InsertStocktakeLineCountError::InternalError(
+                "Failed to read the just updated stocktake line!".to_string(),
+            ))
+        })
+        .map_err(|error| error.to_inner_error())?;
+    Ok(result)
+}
+
+impl From<RepositoryError> for InsertStocktakeLineCountError {
+    fn from(error: RepositoryError) -> Self {
+        InsertStocktakeLineCountError::DatabaseError(error)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use repository::{
+        mock::{mock_item_a, mock_item_a_stock_line, mock_store_a, MockData, MockDataInserts},
+        test_db::setup_all_with_data,
+        StocktakeLineCountRowRepository, StocktakeLineRow, StocktakeRow,
+    };
+    use util::inline_init;
+
+    use crate::{
+        service_provider::ServiceProvider,
+        stocktake::{UpdateStocktake, UpdateStocktakeError, UpdateStocktakeStatus},
+        stocktake_line::{
+            counts_need_recount, InsertStocktakeLine, InsertStocktakeLineCount,
+            InsertStocktakeLineCountError as ServiceError, InsertStocktakeLineError,
+            UpdateStocktakeLine, UpdateStocktakeLineError,
+        },
+    };
+
+    fn blind_stocktake() -> StocktakeRow {
+        inline_init(|r: &mut StocktakeRow| {
+            r.id = "blind_stocktake".to_string();
+            r.store_id = mock_store_a().id;
+            r.is_blind_count = true;
+        })
+    }
+
+    fn open_stocktake() -> StocktakeRow {
+        inline_init(|r: &mut StocktakeRow| {
+            r.id = "open_stocktake".to_string();
+            r.store_id = mock_store_a().id;
+        })
+    }
+
+    fn blind_stocktake_line() -> StocktakeLineRow {
+        inline_init(|r: &mut StocktakeLineRow| {
+            r.id = "blind_stocktake_line".to_string();
+            r.stocktake_id = blind_stocktake().id;
+            r.stock_line_id = Some("stock_line".to_string());
+            r.item_id = mock_item_a().id;
+            r.snapshot_number_of_packs = 10.0;
+        })
+    }
+
+    fn open_stocktake_line() -> StocktakeLineRow {
+        inline_init(|r: &mut StocktakeLineRow| {
+            r.id = "open_stocktake_line".to_string();
+            r.stocktake_id = open_stocktake().id;
+            r.item_id = mock_item_a().id;
+        })
+    }
+
+    #[actix_rt::test]
+    async fn insert_stocktake_line_count() {
+        let (_, connection, connection_manager, _) = setup_all_with_data(
+            "insert_stocktake_line_count",
+            MockDataInserts::none().stores().items().names().units(),
+            inline_init(|r: &mut MockData| {
+                r.stock_lines = vec![mock_item_a_stock_line("stock_line", 1, 10.0)];
+                r.stocktakes = vec![blind_stocktake(), open_stocktake()];
+                r.stocktake_lines = vec![blind_stocktake_line(), open_stocktake_line()];
+            }),
+        )
+        .await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let counter_a = service_provider
+            .context(mock_store_a().id, "counter_a".to_string())
+            .unwrap();
+        let counter_b = service_provider
+            .context(mock_store_a().id, "counter_b".to_string())
+            .unwrap();
+        let service = &service_provider.stocktake_line_service;
+
+        let count = |counted_number_of_packs: f64| InsertStocktakeLineCount {
+            stocktake_line_id: blind_stocktake_line().id,
+            counted_number_of_packs,
+        };
+
+        // Errors
+        assert_eq!(
+            service.insert_stocktake_line_count(
+                &counter_a,
+                InsertStocktakeLineCount {
+                    stocktake_line_id: "invalid".to_string(),
+                    counted_number_of_packs: 1.0,
+                }
+            ),
+            Err(ServiceError::StocktakeLineDoesNotExist)
+        );
+        assert_eq!(
+            service.insert_stocktake_line_count(
+                &counter_a,
+                InsertStocktakeLineCount {
+                    stocktake_line_id: open_stocktake_line().id,
+                    counted_number_of_packs: 1.0,
+                }
+            ),
+            Err(ServiceError::NotABlindCount)
+        );
+        assert_eq!(
+            service.insert_stocktake_line_count(&counter_a, count(-1.0)),
+            Err(ServiceError::InvalidCount)
+        );
+
+        // A single count isn't reconciled
+        let line = service
+            .insert_stocktake_line_count(&counter_a, count(10.0))
+            .unwrap();
+        assert_eq!(line.line.counted_number_of_packs, None);
+
+        let finalise = UpdateStocktake {
+            id: blind_stocktake().id,
+            status: Some(UpdateStocktakeStatus::Finalised),
+            ..Default::default()
+        };
+        assert!(matches!(
+            service_provider
+                .stocktake_service
+                .update_stocktake(&counter_a, finalise.clone()),
+            Err(UpdateStocktakeError::CountsNotReconciled(lines))
+                if lines[0].line.id == blind_stocktake_line().id
+        ));
+
+        // Counts disagree
+        let line = service
+            .insert_stocktake_line_count(&counter_b, count(9.0))
+            .unwrap();
+        assert_eq!(line.line.counted_number_of_packs, None);
+        let counts = StocktakeLineCountRowRepository::new(&connection)
+            .find_many_by_stocktake_line_id(&blind_stocktake_line().id)
+            .unwrap();
+        assert_eq!(counts.len(), 2);
+        assert!(counts_need_recount(&counts));
+
+        // Recount replaces the previous count of the counter
+        let line = service
+            .insert_stocktake_line_count(&counter_b, count(10.0))
+            .unwrap();
+        assert_eq!(line.line.counted_number_of_packs, Some(10.0));
+        let counts = StocktakeLineCountRowRepository::new(&connection)
+            .find_many_by_stocktake_line_id(&blind_stocktake_line().id)
+            .unwrap();
+        assert_eq!(counts.len(), 2);
+        assert!(!counts_need_recount(&counts));
+
+        // Every line has an agreed count
+        let stocktake = service_provider
+            .stocktake_service
+            .update_stocktake(&counter_a, finalise)
+            .unwrap();
+        assert_eq!(stocktake.id, blind_stocktake().id);
+    }
+
+    #[actix_rt::test]
+    async fn blind_stocktake_line_cannot_be_counted_directly() {
+        let (_, _, connection_manager, _) = setup_all_with_data(
+            "blind_stocktake_line_cannot_be_counted_directly",
+            MockDataInserts::none().stores().items().names().units(),
+            inline_init(|r: &mut MockData| {
+                r.stock_lines = vec![mock_item_a_stock_line("stock_line", 1, 10.0)];
+                r.stocktakes = vec![blind_stocktake()];
+                r.stocktake_lines = vec![blind_stocktake_line()];
+            }),
+        )
+        .await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider
+            .context(mock_store_a().id, "counter_a".to_string())
+            .unwrap();
+        let service = &service_provider.stocktake_line_service;
+
+        assert_eq!(
+            service.insert_stocktake_line(
+                &context,
+                inline_init(|r: &mut InsertStocktakeLine| {
+                    r.id = "new_blind_stocktake_line".to_string();
+                    r.stocktake_id = blind_stocktake().id;
+                    r.item_id = Some(mock_item_a().id);
+                    r.counted_number_of_packs = Some(5.0);
+                }),
+            ),
+            Err(InsertStocktakeLineError::CannotEditBlindCount)
+        );
+        assert_eq!(
+            service.update_stocktake_line(
+                &context,
+                inline_init(|r: &mut UpdateStocktakeLine| {
+                    r.id = blind_stocktake_line().id;
+                    r.counted_number_of_packs = Some(5.0);
+                }),
+            ),
+            Err(UpdateStocktakeLineError::CannotEditBlindCount)
+        );
+
+        // Lines without a count can still be added
+        let line = service
+            .insert_stocktake_line(
+                &context,
+                inline_init(|r: &mut InsertStocktakeLine| {
+                    r.id = "new_blind_stocktake_line".to_string();
+                    r.stocktake_id = blind_stocktake().id;
+                    r.item_id = Some(mock_item_a().id);
+                }),
+            )
+            .unwrap();
+        assert_eq!(line.line.counted_number_of_packs, None);
+    }
+}
@@ -1,5 +1,6 @@
 use repository::{
-    RepositoryError, StocktakeLineRowRepository, StorageConnection, TransactionError,
+    RepositoryError, StocktakeLineCountRowRepository, StocktakeLineRowRepository, StorageConnection,
+    TransactionError,
 };
 
 use crate::{
@@ -59,6 +60,8 @@ pub fn delete_stocktake_line(
     ctx.connection
         .transaction_sync(|connection| {
             validate(connection, &ctx.store_id, &stocktake_line_id)?;
+            StocktakeLineCountRowRepository::new(&connection)
+                .delete_by_stocktake_line_id(&stocktake_line_id)?;
             StocktakeLineRowRepository::new(&connection).delete(&stocktake_line_id)?;
             Ok(())
         })
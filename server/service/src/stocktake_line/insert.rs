@@ -53,6 +53,8 @@ pub enum InsertStocktakeLineError {
     StockLineXOrItem,
     ItemDoesNotExist,
     StocktakeIsLocked,
+    /// Counted number of packs of a blind stocktake is set from the line counts
+    CannotEditBlindCount,
     AdjustmentReasonNotProvided,
     AdjustmentReasonNotValid,
     StockLineReducedBelowZero(StockLine),
@@ -155,6 +157,10 @@ fn validate(
         return Err(InsertStocktakeLineError::StocktakeIsLocked);
     }
 
+    if stocktake.is_blind_count && input.counted_number_of_packs.is_some() {
+        return Err(InsertStocktakeLineError::CannotEditBlindCount);
+    }
+
     let stock_line = if let Some(stock_line_id) = &input.stock_line_id {
         check_stock_line_exists(connection, stock_line_id)?
     } else {
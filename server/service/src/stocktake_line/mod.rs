@@ -6,6 +6,9 @@ use crate::{service_provider::ServiceContext, ListResult};
 pub mod query;
 pub mod validate;
 
+mod count;
+pub use self::count::*;
+
 mod delete;
 pub use self::delete::*;
 
@@ -53,6 +56,16 @@ pub trait StocktakeLineServiceTrait: Sync + Send {
         update_stocktake_line(ctx, input)
     }
 
+    /// Adds the count of the current user to a line of a blind stocktake, the line's counted number
+    /// of packs is set once the counts agree
+    fn insert_stocktake_line_count(
+        &self,
+        ctx: &ServiceContext,
+        input: InsertStocktakeLineCount,
+    ) -> Result<StocktakeLine, InsertStocktakeLineCountError> {
+        insert_stocktake_line_count(ctx, input)
+    }
+
     fn delete_stocktake_line(
         &self,
         ctx: &ServiceContext,
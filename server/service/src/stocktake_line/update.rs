@@ -46,6 +46,8 @@ pub enum UpdateStocktakeLineError {
     LocationDoesNotExist,
     CannotEditFinalised,
     StocktakeIsLocked,
+    /// Counted number of packs of a blind stocktake is set from the line counts
+    CannotEditBlindCount,
     AdjustmentReasonNotProvided,
     AdjustmentReasonNotValid,
     StockLineReducedBelowZero(StockLine),
@@ -76,6 +78,12 @@ fn validate(
         return Err(UpdateStocktakeLineError::StocktakeIsLocked);
     }
 
+    if stocktake.is_blind_count
+        && (input.counted_number_of_packs.is_some() || input.snapshot_number_of_packs.is_some())
+    {
+        return Err(UpdateStocktakeLineError::CannotEditBlindCount);
+    }
+
     if !check_store_id_matches(store_id, &stocktake.store_id) {
         return Err(UpdateStocktakeLineError::InvalidStore);
     }
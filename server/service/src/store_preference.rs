@@ -1,7 +1,10 @@
+use chrono_tz::Tz;
 use repository::{
     RepositoryError, StorageConnection, StorePreferenceRow, StorePreferenceRowRepository,
 };
 
+use crate::NullableUpdate;
+
 pub fn get_store_preferences(
     connection: &StorageConnection,
     store_id: &str,
@@ -11,3 +14,178 @@ pub fn get_store_preferences(
         .unwrap_or_default();
     Ok(store_preferences)
 }
+
+/// Local store settings, i.e. settings that are not synced with the legacy store preferences.
+/// Existing settings are kept when not provided.
+#[derive(Default)]
+pub struct UpdateStorePreferences {
+    pub timezone: Option<NullableUpdate<String>>,
+    pub currency_code: Option<NullableUpdate<String>>,
+    pub requires_goods_receipt_inspection: Option<bool>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum UpdateStorePreferencesError {
+    InvalidTimezone,
+    InvalidCurrencyCode,
+    DatabaseError(RepositoryError),
+}
+
+pub fn update_store_preferences(
+    connection: &StorageConnection,
+    store_id: &str,
+    input: UpdateStorePreferences,
+) -> Result<StorePreferenceRow, UpdateStorePreferencesError> {
+    let UpdateStorePreferences {
+        timezone,
+        currency_code,
+        requires_goods_receipt_inspection,
+    } = input;
+    if let Some(NullableUpdate {
+        value: Some(timezone),
+    }) = &timezone
+    {
+        if timezone.parse::<Tz>().is_err() {
+            return Err(UpdateStorePreferencesError::InvalidTimezone);
+        }
+    }
+    if let Some(NullableUpdate {
+        value: Some(currency_code),
+    }) = &currency_code
+    {
+        if currency_code.len() != 3 || !currency_code.chars().all(|c| c.is_ascii_uppercase()) {
+            return Err(UpdateStorePreferencesError::InvalidCurrencyCode);
+        }
+    }
+
+    let repo = StorePreferenceRowRepository::new(&connection);
+    let existing = repo
+        .find_one_by_id(store_id)?
+        .unwrap_or_else(|| StorePreferenceRow {
+            id: store_id.to_string(),
+            ..Default::default()
+        });
+    let row = StorePreferenceRow {
+        timezone: match timezone {
+            Some(update) => update.value,
+            None => existing.timezone.clone(),
+        },
+        currency_code: match currency_code {
+            Some(update) => update.value,
+            None => existing.currency_code.clone(),
+        },
+        requires_goods_receipt_inspection: requires_goods_receipt_inspection
+            .unwrap_or(existing.requires_goods_receipt_inspection),
+        ..existing
+    };
+    repo.upsert_one(&row)?;
+    Ok(row)
+}
+
+impl From<RepositoryError> for UpdateStorePreferencesError {
+    fn from(error: RepositoryError) -> Self {
+        UpdateStorePreferencesError::DatabaseError(error)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use repository::{mock::MockDataInserts, test_db::setup_all};
+
+    use super::*;
+
+    #[actix_rt::test]
+    async fn update_store_preferences_test() {
+        let (_, connection, _, _) =
+            setup_all("update_store_preferences_test", MockDataInserts::all()).await;
+
+        assert_eq!(
+            update_store_preferences(
+                &connection,
+                "store_a",
+                UpdateStorePreferences {
+                    timezone: Some(NullableUpdate {
+                        value: Some("Not/A_Timezone".to_string()),
+                    }),
+                    ..Default::default()
+                }
+            ),
+            Err(UpdateStorePreferencesError::InvalidTimezone)
+        );
+        assert_eq!(
+            update_store_preferences(
+                &connection,
+                "store_a",
+                UpdateStorePreferences {
+                    currency_code: Some(NullableUpdate {
+                        value: Some("usd".to_string()),
+                    }),
+                    ..Default::default()
+                }
+            ),
+            Err(UpdateStorePreferencesError::InvalidCurrencyCode)
+        );
+
+        update_store_preferences(
+            &connection,
+            "store_a",
+            UpdateStorePreferences {
+                timezone: Some(NullableUpdate {
+                    value: Some("Pacific/Auckland".to_string()),
+                }),
+                currency_code: Some(NullableUpdate {
+                    value: Some("NZD".to_string()),
+                }),
+                requires_goods_receipt_inspection: Some(true),
+            },
+        )
+        .unwrap();
+        let preferences = get_store_preferences(&connection, "store_a").unwrap();
+        assert_eq!(preferences.timezone, Some("Pacific/Auckland".to_string()));
+        assert_eq!(preferences.currency_code, Some("NZD".to_string()));
+        assert!(preferences.requires_goods_receipt_inspection);
+
+        // Settings that are not provided are kept
+        let preferences = update_store_preferences(
+            &connection,
+            "store_a",
+            UpdateStorePreferences {
+                timezone: Some(NullableUpdate {
+                    value: Some("Europe/London".to_string()),
+                }),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(preferences.timezone, Some("Europe/London".to_string()));
+        assert_eq!(preferences.currency_code, Some("NZD".to_string()));
+        assert!(preferences.requires_goods_receipt_inspection);
+
+        let preferences = update_store_preferences(
+            &connection,
+            "store_a",
+            UpdateStorePreferences {
+                currency_code: Some(NullableUpdate {
+                    value: Some("GBP".to_string()),
+                }),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(preferences.timezone, Some("Europe/London".to_string()));
+        assert_eq!(preferences.currency_code, Some("GBP".to_string()));
+
+        // Settings are cleared with an empty update
+        let preferences = update_store_preferences(
+            &connection,
+            "store_a",
+            UpdateStorePreferences {
+                timezone: Some(NullableUpdate { value: None }),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(preferences.timezone, None);
+        assert_eq!(preferences.currency_code, Some("GBP".to_string()));
+    }
+}
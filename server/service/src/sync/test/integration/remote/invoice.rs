@@ -23,6 +23,10 @@ impl SyncRecordTester for InvoiceRecordTester {
             code: "TestLocationCode".to_string(),
             on_hold: false,
             store_id: store_id.to_string(),
+            parent_id: None,
+            location_type: LocationType::Ambient,
+            volume_capacity: None,
+            pack_capacity: None,
         };
         // test option (inventory adjustment reason)
         let inventory_adjustment_reason_id = uuid();
@@ -4,7 +4,7 @@ use crate::sync::{
     },
     translations::{IntegrationRecords, PullUpsertRecord},
 };
-use repository::LocationRow;
+use repository::{LocationRow, LocationType};
 use serde_json::json;
 use util::{inline_edit, uuid::uuid};
 
@@ -20,6 +20,10 @@ impl SyncRecordTester for LocationRecordTester {
             code: "LocationCode".to_string(),
             on_hold: false,
             store_id: store_id.to_string(),
+            parent_id: None,
+            location_type: LocationType::Ambient,
+            volume_capacity: None,
+            pack_capacity: None,
         };
 
         result.push(TestStepData {
@@ -5,7 +5,7 @@ use crate::sync::{
     translations::{IntegrationRecords, PullUpsertRecord},
 };
 use chrono::NaiveDate;
-use repository::{LocationRow, StockLineRow};
+use repository::{LocationRow, LocationType, StockLineRow};
 use serde_json::json;
 use util::{inline_edit, uuid::uuid};
 pub struct StockLineRecordTester;
@@ -21,6 +21,10 @@ impl SyncRecordTester for StockLineRecordTester {
             code: "LocationCode".to_string(),
             on_hold: false,
             store_id: store_id.to_string(),
+            parent_id: None,
+            location_type: LocationType::Ambient,
+            volume_capacity: None,
+            pack_capacity: None,
         };
 
         let stock_line_row = StockLineRow {
@@ -26,6 +26,10 @@ impl SyncRecordTester for StocktakeRecordTester {
             code: "TestLocationCode".to_string(),
             on_hold: false,
             store_id: store_id.to_string(),
+            parent_id: None,
+            location_type: LocationType::Ambient,
+            volume_capacity: None,
+            pack_capacity: None,
         };
         let stocktake_row = StocktakeRow {
             id: uuid(),
@@ -44,6 +48,8 @@ impl SyncRecordTester for StocktakeRecordTester {
             inventory_addition_id: None,
             inventory_reduction_id: None,
             is_locked: true,
+            is_blind_count: false,
+            location_id: None,
         };
         let stocktake_line_row = StocktakeLineRow {
             id: uuid(),
@@ -1,6 +1,6 @@
 use crate::sync::translations::{location::LegacyLocationRow, LegacyTableName, PullUpsertRecord};
 
-use repository::LocationRow;
+use repository::{LocationRow, LocationType};
 use serde_json::json;
 
 use super::{TestSyncPullRecord, TestSyncPushRecord};
@@ -37,6 +37,10 @@ pub(crate) fn test_pull_upsert_records() -> Vec<TestSyncPullRecord> {
             code: "Red.02".to_string(),
             on_hold: false,
             store_id: "store_a".to_string(),
+            parent_id: None,
+            location_type: LocationType::Ambient,
+            volume_capacity: None,
+            pack_capacity: None,
         }),
     )]
 }
@@ -51,6 +55,8 @@ pub(crate) fn test_push_records() -> Vec<TestSyncPushRecord> {
             code: "Red.02".to_string(),
             on_hold: false,
             store_id: "store_a".to_string(),
+            parent_id: None,
+            volume: 0.0,
         }),
     }]
 }
@@ -52,6 +52,8 @@ fn stocktake_pull_record() -> TestSyncPullRecord {
             inventory_addition_id: Some("inbound_shipment_a".to_string()),
             inventory_reduction_id: Some("inbound_shipment_b".to_string()),
             is_locked: false,
+            is_blind_count: false,
+            location_id: None,
             stocktake_date: Some(NaiveDate::from_ymd_opt(2021, 07, 30).unwrap()),
         }),
     )
@@ -132,6 +134,8 @@ fn stocktake_om_field_pull_record() -> TestSyncPullRecord {
             inventory_addition_id: None,
             inventory_reduction_id: None,
             is_locked: false,
+            is_blind_count: false,
+            location_id: None,
             stocktake_date: Some(NaiveDate::from_ymd_opt(2021, 07, 30).unwrap()),
         }),
     )
@@ -145,6 +145,9 @@ pub(crate) fn test_pull_upsert_records() -> Vec<TestSyncPullRecord> {
                 pack_to_one: true,
                 response_requisition_requires_authorisation: true,
                 request_requisition_requires_authorisation: false,
+                timezone: None,
+                currency_code: None,
+                requires_goods_receipt_inspection: false,
             }),
         ),
         TestSyncPullRecord::new_pull_upsert(
@@ -156,6 +159,9 @@ pub(crate) fn test_pull_upsert_records() -> Vec<TestSyncPullRecord> {
                 pack_to_one: false,
                 response_requisition_requires_authorisation: false,
                 request_requisition_requires_authorisation: true,
+                timezone: None,
+                currency_code: None,
+                requires_goods_receipt_inspection: false,
             }),
         ),
     ]
@@ -1,10 +1,10 @@
 use repository::{
-    ChangelogRow, ChangelogTableName, LocationRow, LocationRowRepository, StorageConnection,
-    SyncBufferRow,
+    ChangelogRow, ChangelogTableName, LocationRow, LocationRowRepository, LocationType,
+    StorageConnection, SyncBufferRow,
 };
 use serde::{Deserialize, Serialize};
 
-use crate::sync::api::RemoteSyncRecordV5;
+use crate::sync::{api::RemoteSyncRecordV5, sync_serde::empty_str_as_option_string};
 
 use super::{IntegrationRecords, LegacyTableName, PullUpsertRecord, SyncTranslation};
 
@@ -28,13 +28,19 @@ pub struct LegacyLocationRow {
     pub on_hold: bool,
     #[serde(rename = "store_ID")]
     pub store_id: String,
+    #[serde(default)]
+    #[serde(deserialize_with = "empty_str_as_option_string")]
+    pub parent_id: Option<String>,
+    #[serde(rename = "Volume")]
+    #[serde(default)]
+    pub volume: f64,
 }
 
 pub(crate) struct LocationTranslation {}
 impl SyncTranslation for LocationTranslation {
     fn try_translate_pull_upsert(
         &self,
-        _: &StorageConnection,
+        connection: &StorageConnection,
         sync_record: &SyncBufferRow,
     ) -> Result<Option<IntegrationRecords>, anyhow::Error> {
         if !match_pull_table(sync_record) {
@@ -47,14 +53,27 @@ impl SyncTranslation for LocationTranslation {
             code,
             on_hold,
             store_id,
+            parent_id,
+            volume,
         } = serde_json::from_str::<LegacyLocationRow>(&sync_record.data)?;
 
+        // Location type and pack capacity are not part of the legacy location, keep local values
+        let existing = LocationRowRepository::new(connection).find_one_by_id(&id)?;
+        let (location_type, pack_capacity) = match existing {
+            Some(existing) => (existing.location_type, existing.pack_capacity),
+            None => (LocationType::default(), None),
+        };
+
         let result = LocationRow {
             id,
             name,
             code,
             on_hold,
             store_id,
+            parent_id,
+            location_type,
+            volume_capacity: (volume > 0.0).then_some(volume),
+            pack_capacity,
         };
 
         Ok(Some(IntegrationRecords::from_upsert(
@@ -77,6 +96,10 @@ impl SyncTranslation for LocationTranslation {
             code,
             on_hold,
             store_id,
+            parent_id,
+            location_type: _,
+            volume_capacity,
+            pack_capacity: _,
         } = LocationRowRepository::new(connection)
             .find_one_by_id(&changelog.record_id)?
             .ok_or(anyhow::Error::msg(format!(
@@ -90,6 +113,8 @@ impl SyncTranslation for LocationTranslation {
             code,
             on_hold,
             store_id: store_id,
+            parent_id,
+            volume: volume_capacity.unwrap_or(0.0),
         };
 
         Ok(Some(vec![RemoteSyncRecordV5::new_upsert(
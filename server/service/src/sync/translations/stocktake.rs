@@ -88,7 +88,7 @@ pub(crate) struct StocktakeTranslation {}
 impl SyncTranslation for StocktakeTranslation {
     fn try_translate_pull_upsert(
         &self,
-        _: &StorageConnection,
+        connection: &StorageConnection,
         sync_record: &SyncBufferRow,
     ) -> Result<Option<IntegrationRecords>, anyhow::Error> {
         if !match_pull_table(sync_record) {
@@ -96,6 +96,13 @@ impl SyncTranslation for StocktakeTranslation {
         }
 
         let data = serde_json::from_str::<LegacyStocktakeRow>(&sync_record.data)?;
+        // Blind counts and stocktake locations are only in Open mSupply, keep local values
+        let existing = StocktakeRowRepository::new(connection).find_one_by_id(&data.ID)?;
+        let (is_blind_count, location_id) = match existing {
+            Some(existing) => (existing.is_blind_count, existing.location_id),
+            None => (false, None),
+        };
+
         let (created_datetime, finalised_datetime) = match data.created_datetime {
             Some(created_datetime) => {
                 // use new om_* fields
@@ -124,6 +131,8 @@ impl SyncTranslation for StocktakeTranslation {
             inventory_reduction_id: data.inventory_reduction_id,
             stocktake_date: data.stocktake_date,
             is_locked: data.is_locked,
+            is_blind_count,
+            location_id,
         };
 
         Ok(Some(IntegrationRecords::from_upsert(
@@ -154,6 +163,8 @@ impl SyncTranslation for StocktakeTranslation {
             stocktake_date,
             inventory_addition_id,
             inventory_reduction_id,
+            is_blind_count: _,
+            location_id: _,
         } = StocktakeRowRepository::new(connection)
             .find_one_by_id(&changelog.record_id)?
             .ok_or(anyhow::Error::msg("Stocktake row not found"))?;
@@ -214,7 +225,10 @@ fn legacy_stocktake_status(status: &StocktakeStatus) -> LegacyStocktakeStatus {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use repository::{mock::MockDataInserts, test_db::setup_all};
+    use repository::{
+        mock::{mock_location_1, mock_store_a, MockDataInserts},
+        test_db::setup_all,
+    };
 
     #[actix_rt::test]
     async fn test_stocktake_translation() {
@@ -232,4 +246,52 @@ mod tests {
             assert_eq!(translation_result, record.translated_record);
         }
     }
+
+    #[actix_rt::test]
+    async fn test_stocktake_translation_keeps_local_fields() {
+        use crate::sync::test::test_data::stocktake as test_data;
+        let translator = StocktakeTranslation {};
+
+        let (_, connection, _, _) = setup_all(
+            "test_stocktake_translation_keeps_local_fields",
+            MockDataInserts::none().names().stores().locations(),
+        )
+        .await;
+
+        let record = test_data::test_pull_upsert_records().remove(0);
+        let existing = StocktakeRow {
+            id: record.sync_buffer_row.record_id.clone(),
+            user_id: "".to_string(),
+            store_id: mock_store_a().id,
+            stocktake_number: 3,
+            comment: None,
+            description: None,
+            status: StocktakeStatus::New,
+            created_datetime: NaiveDate::from_ymd_opt(2021, 7, 30)
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+                .unwrap(),
+            finalised_datetime: None,
+            inventory_addition_id: None,
+            inventory_reduction_id: None,
+            is_locked: false,
+            is_blind_count: true,
+            location_id: Some(mock_location_1().id),
+            stocktake_date: None,
+        };
+        StocktakeRowRepository::new(&connection)
+            .upsert_one(&existing)
+            .unwrap();
+
+        let translation_result = translator
+            .try_translate_pull_upsert(&connection, &record.sync_buffer_row)
+            .unwrap()
+            .unwrap();
+        match &translation_result.upserts[..] {
+            [PullUpsertRecord::Stocktake(row)] => {
+                assert!(row.is_blind_count);
+                assert_eq!(row.location_id, Some(mock_location_1().id));
+            }
+            upserts => panic!("Unexpected upserts {:?}", upserts),
+        }
+    }
 }
@@ -1,4 +1,7 @@
-use repository::{StorageConnection, StorePreferenceRow, StorePreferenceType, SyncBufferRow};
+use repository::{
+    StorageConnection, StorePreferenceRow, StorePreferenceRowRepository, StorePreferenceType,
+    SyncBufferRow,
+};
 use serde::{Deserialize, Serialize};
 
 use super::{IntegrationRecords, LegacyTableName, PullUpsertRecord, SyncTranslation};
@@ -37,7 +40,7 @@ pub(crate) struct StorePreferenceTranslation {}
 impl SyncTranslation for StorePreferenceTranslation {
     fn try_translate_pull_upsert(
         &self,
-        _: &StorageConnection,
+        connection: &StorageConnection,
         sync_record: &SyncBufferRow,
     ) -> Result<Option<IntegrationRecords>, anyhow::Error> {
         if !match_pull_table(sync_record) {
@@ -58,12 +61,26 @@ impl SyncTranslation for StorePreferenceTranslation {
             request_requisition_requires_authorisation,
         } = data;
 
+        // Keep local store settings, they are not part of the legacy preferences
+        let existing = StorePreferenceRowRepository::new(connection).find_one_by_id(&id)?;
+        let (timezone, currency_code, requires_goods_receipt_inspection) = match existing {
+            Some(existing) => (
+                existing.timezone,
+                existing.currency_code,
+                existing.requires_goods_receipt_inspection,
+            ),
+            None => (None, None, false),
+        };
+
         let result = StorePreferenceRow {
             id,
             r#type,
             pack_to_one,
             response_requisition_requires_authorisation,
             request_requisition_requires_authorisation,
+            timezone,
+            currency_code,
+            requires_goods_receipt_inspection,
         };
 
         Ok(Some(IntegrationRecords::from_upsert(
@@ -0,0 +1,47 @@
+use self::{
+    recall::{recall_batch, RecallBatch, RecallBatchError},
+    trace::{get_batch_trace, BatchTrace, BatchTraceError, BatchTraceStart},
+};
+
+use crate::service_provider::ServiceContext;
+use repository::StockLine;
+
+pub mod recall;
+pub mod trace;
+
+pub trait TraceabilityServiceTrait: Sync + Send {
+    /// Distribution tree of a batch: the stock lines it was received from and every shipment and
+    /// downstream stock line it was distributed to
+    fn get_batch_trace(
+        &self,
+        ctx: &ServiceContext,
+        start: BatchTraceStart,
+    ) -> Result<BatchTrace, BatchTraceError> {
+        get_batch_trace(ctx, start)
+    }
+
+    /// Puts all remaining stock of a batch in the store on hold
+    fn recall_batch(
+        &self,
+        ctx: &ServiceContext,
+        input: RecallBatch,
+    ) -> Result<Vec<StockLine>, RecallBatchError> {
+        recall_batch(ctx, input)
+    }
+}
+
+pub struct TraceabilityService {}
+impl TraceabilityServiceTrait for TraceabilityService {}
+
+/// Batches are entered by hand in some places, match them ignoring case and surrounding spaces
+pub(crate) fn batch_matches(batch: &Option<String>, other: &Option<String>) -> bool {
+    let normalise = |batch: &Option<String>| {
+        batch
+            .as_deref()
+            .map(|batch| batch.trim().to_lowercase())
+            .unwrap_or_default()
+    };
+    normalise(batch) == normalise(other)
+}
+
+mod tests;
@@ -0,0 +1,92 @@
+use repository::{
+    ActivityLogType, EqualFilter, HoldRecordType, RepositoryError, StockLine, StockLineFilter,
+    StockLineRepository, StockLineRow, StockLineRowRepository, HOLD_REASON_RECALL_ID,
+};
+
+use crate::{
+    activity_log::activity_log_entry, hold::record_hold, service_provider::ServiceContext,
+};
+
+use super::batch_matches;
+
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct RecallBatch {
+    pub item_id: String,
+    pub batch: String,
+    pub reason: String,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum RecallBatchError {
+    DatabaseError(RepositoryError),
+    ReasonNotProvided,
+}
+
+/// Puts the stock lines of the batch with stock remaining in the store on hold, so they're no
+/// longer allocated, with the recall hold reason. The reason is logged on each stock line.
+/// Returns the recalled stock lines.
+pub fn recall_batch(
+    ctx: &ServiceContext,
+    input: RecallBatch,
+) -> Result<Vec<StockLine>, RecallBatchError> {
+    let result = ctx
+        .connection
+        .transaction_sync(|connection| {
+            if input.reason.trim().is_empty() {
+                return Err(RecallBatchError::ReasonNotProvided);
+            }
+
+            let stock_lines: Vec<StockLine> = StockLineRepository::new(connection)
+                .query_by_filter(
+                    StockLineFilter::new()
+                        .store_id(EqualFilter::equal_to(&ctx.store_id))
+                        .item_id(EqualFilter::equal_to(&input.item_id))
+                        .has_packs_in_store(true),
+                    None,
+                )?
+                .into_iter()
+                .filter(|stock_line| {
+                    batch_matches(&stock_line.stock_line_row.batch, &Some(input.batch.clone()))
+                })
+                .collect();
+
+            for stock_line in &stock_lines {
+                StockLineRowRepository::new(connection).upsert_one(&StockLineRow {
+                    on_hold: true,
+                    ..stock_line.stock_line_row.clone()
+                })?;
+                record_hold(
+                    ctx,
+                    HoldRecordType::StockLine,
+                    &stock_line.stock_line_row.id,
+                    HOLD_REASON_RECALL_ID,
+                    None,
+                )?;
+                activity_log_entry(
+                    ctx,
+                    ActivityLogType::StockRecall,
+                    Some(stock_line.stock_line_row.id.clone()),
+                    Some(format!(
+                        "Batch {} recalled: {}",
+                        input.batch,
+                        input.reason.trim()
+                    )),
+                )?;
+            }
+
+            let ids = stock_lines
+                .into_iter()
+                .map(|stock_line| stock_line.stock_line_row.id)
+                .collect();
+            Ok(StockLineRepository::new(connection)
+                .query_by_filter(StockLineFilter::new().id(EqualFilter::equal_any(ids)), None)?)
+        })
+        .map_err(|error| error.to_inner_error())?;
+    Ok(result)
+}
+
+impl From<RepositoryError> for RecallBatchError {
+    fn from(error: RepositoryError) -> Self {
+        RecallBatchError::DatabaseError(error)
+    }
+}
@@ -0,0 +1,475 @@
+#[cfg(test)]
+mod query {
+    use repository::{
+        mock::{
+            mock_item_a, mock_item_a_stock_line, mock_name_a, mock_name_store_a, mock_name_store_b,
+            mock_store_a, mock_store_b, MockData, MockDataInserts,
+        },
+        test_db::setup_all_with_data,
+        ActivityLogRowRepository, ActivityLogType, EqualFilter, HoldRecordType, HoldRowRepository,
+        InvoiceLineFilter, InvoiceLineRepository, InvoiceLineRow, InvoiceLineRowType, InvoiceRow,
+        InvoiceRowStatus, InvoiceRowType, StockLineRow, StockLineRowRepository,
+        HOLD_REASON_RECALL_ID,
+    };
+    use util::{inline_edit, inline_init};
+
+    use crate::{
+        service_provider::ServiceProvider,
+        stock_line::{repack::RepackStockLine, split::SplitStockLine},
+        traceability::{
+            recall::{RecallBatch, RecallBatchError},
+            trace::{BatchTraceError, BatchTraceStart},
+        },
+    };
+
+    // Store A: stock_a (batch B1) ships 3 packs to store B, received as stock_b
+    // Store B: stock_b ships 1 pack to customer name_a
+    fn stock_a() -> StockLineRow {
+        inline_edit(&mock_item_a_stock_line("stock_a", 10, 7.0), |mut u| {
+            u.batch = Some("B1".to_string());
+            u
+        })
+    }
+
+    fn stock_a_other_batch() -> StockLineRow {
+        inline_edit(
+            &mock_item_a_stock_line("stock_a_other_batch", 10, 5.0),
+            |mut u| {
+                u.batch = Some("B2".to_string());
+                u
+            },
+        )
+    }
+
+    fn stock_a_empty() -> StockLineRow {
+        inline_edit(
+            &mock_item_a_stock_line("stock_a_empty", 10, 0.0),
+            |mut u| {
+                u.batch = Some("b1".to_string());
+                u
+            },
+        )
+    }
+
+    fn stock_b() -> StockLineRow {
+        inline_edit(&mock_item_a_stock_line("stock_b", 10, 2.0), |mut u| {
+            u.store_id = mock_store_b().id;
+            u.batch = Some("b1 ".to_string());
+            u
+        })
+    }
+
+    fn invoice(
+        id: &str,
+        store_id: &str,
+        name_id: &str,
+        r#type: InvoiceRowType,
+        status: InvoiceRowStatus,
+    ) -> InvoiceRow {
+        inline_init(|r: &mut InvoiceRow| {
+            r.id = id.to_string();
+            r.store_id = store_id.to_string();
+            r.name_id = name_id.to_string();
+            r.r#type = r#type;
+            r.status = status;
+        })
+    }
+
+    fn outbound_to_store_b() -> InvoiceRow {
+        invoice(
+            "outbound_to_store_b",
+            &mock_store_a().id,
+            &mock_name_store_b().id,
+            InvoiceRowType::OutboundShipment,
+            InvoiceRowStatus::Shipped,
+        )
+    }
+
+    fn outbound_not_picked() -> InvoiceRow {
+        invoice(
+            "outbound_not_picked",
+            &mock_store_a().id,
+            &mock_name_a().id,
+            InvoiceRowType::OutboundShipment,
+            InvoiceRowStatus::Allocated,
+        )
+    }
+
+    fn inbound_from_store_a() -> InvoiceRow {
+        InvoiceRow {
+            linked_invoice_id: Some(outbound_to_store_b().id),
+            ..invoice(
+                "inbound_from_store_a",
+                &mock_store_b().id,
+                &mock_name_store_a().id,
+                InvoiceRowType::InboundShipment,
+                InvoiceRowStatus::Delivered,
+            )
+        }
+    }
+
+    fn outbound_to_customer() -> InvoiceRow {
+        invoice(
+            "outbound_to_customer",
+            &mock_store_b().id,
+            &mock_name_a().id,
+            InvoiceRowType::OutboundShipment,
+            InvoiceRowStatus::Verified,
+        )
+    }
+
+    fn line(
+        id: &str,
+        invoice_id: &str,
+        stock_line: &StockLineRow,
+        number_of_packs: f64,
+    ) -> InvoiceLineRow {
+        inline_init(|r: &mut InvoiceLineRow| {
+            r.id = id.to_string();
+            r.invoice_id = invoice_id.to_string();
+            r.item_id = mock_item_a().id;
+            r.stock_line_id = Some(stock_line.id.clone());
+            r.batch = stock_line.batch.clone();
+            r.pack_size = stock_line.pack_size;
+            r.number_of_packs = number_of_packs;
+            r.r#type = InvoiceLineRowType::StockOut;
+        })
+    }
+
+    fn mock_data() -> MockData {
+        inline_init(|r: &mut MockData| {
+            r.stock_lines = vec![stock_a(), stock_a_other_batch(), stock_a_empty(), stock_b()];
+            r.invoices = vec![
+                outbound_to_store_b(),
+                outbound_not_picked(),
+                inbound_from_store_a(),
+                outbound_to_customer(),
+            ];
+            r.invoice_lines = vec![
+                line(
+                    "line_to_store_b",
+                    &outbound_to_store_b().id,
+                    &stock_a(),
+                    3.0,
+                ),
+                line(
+                    "line_not_picked",
+                    &outbound_not_picked().id,
+                    &stock_a(),
+                    2.0,
+                ),
+                InvoiceLineRow {
+                    r#type: InvoiceLineRowType::StockIn,
+                    ..line(
+                        "line_from_store_a",
+                        &inbound_from_store_a().id,
+                        &stock_b(),
+                        3.0,
+                    )
+                },
+                line(
+                    "line_to_customer",
+                    &outbound_to_customer().id,
+                    &stock_b(),
+                    1.0,
+                ),
+            ];
+        })
+    }
+
+    #[actix_rt::test]
+    async fn batch_trace() {
+        let (_, _, connection_manager, _) = setup_all_with_data(
+            "batch_trace",
+            MockDataInserts::none().stores().items().names().units(),
+            mock_data(),
+        )
+        .await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let service = &service_provider.traceability_service;
+        let context = service_provider
+            .context(mock_store_a().id, "user".to_string())
+            .unwrap();
+
+        // Errors
+        assert_eq!(
+            service.get_batch_trace(&context, BatchTraceStart::StockLine("invalid".to_string())),
+            Err(BatchTraceError::StockLineDoesNotExist)
+        );
+        assert_eq!(
+            service.get_batch_trace(&context, BatchTraceStart::StockLine(stock_b().id)),
+            Err(BatchTraceError::StockLineDoesNotBelongToStore)
+        );
+
+        // Forward from item and batch
+        let trace = service
+            .get_batch_trace(
+                &context,
+                BatchTraceStart::Batch {
+                    item_id: mock_item_a().id,
+                    batch: "b1".to_string(),
+                },
+            )
+            .unwrap();
+        let stock_lines: Vec<(String, i32, Option<String>)> = trace
+            .stock_lines
+            .iter()
+            .map(|line| {
+                (
+                    line.stock_line.stock_line_row.id.clone(),
+                    line.depth,
+                    line.received_through_invoice_id.clone(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            stock_lines,
+            vec![
+                (stock_a().id, 0, None),
+                (stock_a_empty().id, 0, None),
+                (stock_b().id, 1, Some(outbound_to_store_b().id)),
+            ]
+        );
+
+        // Allocated shipment is not included
+        assert_eq!(trace.shipments.len(), 2);
+        let to_store_b = &trace.shipments[0];
+        assert_eq!(
+            to_store_b.outbound_shipment.invoice_row.id,
+            outbound_to_store_b().id
+        );
+        assert_eq!(
+            to_store_b
+                .inbound_shipment
+                .as_ref()
+                .map(|invoice| invoice.invoice_row.id.clone()),
+            Some(inbound_from_store_a().id)
+        );
+        assert_eq!(to_store_b.received_stock_line_ids, vec![stock_b().id]);
+        assert_eq!(to_store_b.number_of_units, 30.0);
+        let to_customer = &trace.shipments[1];
+        assert_eq!(
+            to_customer.outbound_shipment.invoice_row.id,
+            outbound_to_customer().id
+        );
+        assert_eq!(to_customer.inbound_shipment, None);
+        assert_eq!(to_customer.from_stock_line_id, stock_b().id);
+        assert_eq!(to_customer.depth, 1);
+        assert_eq!(to_customer.number_of_units, 10.0);
+
+        assert_eq!(trace.units_on_hand(), 90.0);
+
+        // Backward from the received stock line
+        let context = service_provider
+            .context(mock_store_b().id, "user".to_string())
+            .unwrap();
+        let trace = service
+            .get_batch_trace(&context, BatchTraceStart::StockLine(stock_b().id))
+            .unwrap();
+        let stock_lines: Vec<(String, i32)> = trace
+            .stock_lines
+            .iter()
+            .map(|line| (line.stock_line.stock_line_row.id.clone(), line.depth))
+            .collect();
+        assert_eq!(stock_lines, vec![(stock_a().id, -1), (stock_b().id, 0)]);
+        assert_eq!(
+            trace.stock_lines[1].received_through_invoice_id,
+            Some(outbound_to_store_b().id)
+        );
+        let shipments: Vec<(String, i32)> = trace
+            .shipments
+            .iter()
+            .map(|shipment| {
+                (
+                    shipment.outbound_shipment.invoice_row.id.clone(),
+                    shipment.depth,
+                )
+            })
+            .collect();
+        assert_eq!(
+            shipments,
+            vec![
+                (outbound_to_store_b().id, -1),
+                (outbound_to_customer().id, 0)
+            ]
+        );
+        assert_eq!(trace.units_on_hand(), 20.0);
+    }
+
+    #[actix_rt::test]
+    async fn batch_trace_through_transfers() {
+        let (_, connection, connection_manager, _) = setup_all_with_data(
+            "batch_trace_through_transfers",
+            MockDataInserts::none().stores().items().names().units(),
+            mock_data(),
+        )
+        .await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let service = &service_provider.traceability_service;
+        let context = service_provider
+            .context(mock_store_a().id, "user".to_string())
+            .unwrap();
+
+        // stock_a_other_batch is split, and the split stock line repacked
+        let split = service_provider
+            .stock_line_service
+            .split_stock_line(
+                &context,
+                inline_init(|r: &mut SplitStockLine| {
+                    r.id = stock_a_other_batch().id;
+                    r.number_of_packs = 2.0;
+                }),
+            )
+            .unwrap()
+            .stock_line_row;
+        let repacked = service_provider
+            .stock_line_service
+            .repack_stock_line(
+                &context,
+                inline_init(|r: &mut RepackStockLine| {
+                    r.id = split.id.clone();
+                    r.number_of_packs = 1.0;
+                    r.new_pack_size = 5;
+                }),
+            )
+            .unwrap()
+            .stock_line_row;
+
+        let reduction_id = |stock_line_id: &str| {
+            InvoiceLineRepository::new(&connection)
+                .query_by_filter(
+                    InvoiceLineFilter::new()
+                        .stock_line_id(EqualFilter::equal_to(stock_line_id))
+                        .invoice_type(InvoiceRowType::InventoryReduction.equal_to()),
+                )
+                .unwrap()
+                .pop()
+                .map(|line| line.invoice_line_row.invoice_id)
+        };
+        let split_reduction_id = reduction_id(&stock_a_other_batch().id);
+        let repack_reduction_id = reduction_id(&split.id);
+
+        // Forward from the original stock line
+        let trace = service
+            .get_batch_trace(
+                &context,
+                BatchTraceStart::StockLine(stock_a_other_batch().id),
+            )
+            .unwrap();
+        let stock_lines: Vec<(String, i32, Option<String>)> = trace
+            .stock_lines
+            .iter()
+            .map(|line| {
+                (
+                    line.stock_line.stock_line_row.id.clone(),
+                    line.depth,
+                    line.received_through_invoice_id.clone(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            stock_lines,
+            vec![
+                (stock_a_other_batch().id, 0, None),
+                (split.id.clone(), 1, split_reduction_id.clone()),
+                (repacked.id.clone(), 2, repack_reduction_id.clone()),
+            ]
+        );
+        // Transfers are not shipments
+        assert!(trace.shipments.is_empty());
+        // 3 packs of 10 left, 1 pack of 10 and 2 packs of 5 after the repack
+        assert_eq!(trace.units_on_hand(), 50.0);
+
+        // Backward from the repacked stock line
+        let trace = service
+            .get_batch_trace(&context, BatchTraceStart::StockLine(repacked.id.clone()))
+            .unwrap();
+        let stock_lines: Vec<(String, i32, Option<String>)> = trace
+            .stock_lines
+            .iter()
+            .map(|line| {
+                (
+                    line.stock_line.stock_line_row.id.clone(),
+                    line.depth,
+                    line.received_through_invoice_id.clone(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            stock_lines,
+            vec![
+                (stock_a_other_batch().id, -2, None),
+                (split.id, -1, split_reduction_id),
+                (repacked.id, 0, repack_reduction_id),
+            ]
+        );
+    }
+
+    #[actix_rt::test]
+    async fn recall_batch() {
+        let (_, connection, connection_manager, _) = setup_all_with_data(
+            "recall_batch",
+            MockDataInserts::none().stores().items().names().units(),
+            mock_data(),
+        )
+        .await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let service = &service_provider.traceability_service;
+        let context = service_provider
+            .context(mock_store_a().id, "user".to_string())
+            .unwrap();
+
+        let input = RecallBatch {
+            item_id: mock_item_a().id,
+            batch: "B1".to_string(),
+            reason: "Contaminated".to_string(),
+        };
+
+        assert_eq!(
+            service.recall_batch(
+                &context,
+                RecallBatch {
+                    reason: " ".to_string(),
+                    ..input.clone()
+                }
+            ),
+            Err(RecallBatchError::ReasonNotProvided)
+        );
+
+        let recalled = service.recall_batch(&context, input).unwrap();
+        assert_eq!(recalled.len(), 1);
+        assert_eq!(recalled[0].stock_line_row.id, stock_a().id);
+        assert!(recalled[0].stock_line_row.on_hold);
+
+        let repo = StockLineRowRepository::new(&connection);
+        // Other batches, empty stock lines and other stores are not put on hold
+        assert!(
+            !repo
+                .find_one_by_id(&stock_a_other_batch().id)
+                .unwrap()
+                .on_hold
+        );
+        assert!(!repo.find_one_by_id(&stock_a_empty().id).unwrap().on_hold);
+        assert!(!repo.find_one_by_id(&stock_b().id).unwrap().on_hold);
+
+        let hold = HoldRowRepository::new(&connection)
+            .find_one_by_record(&HoldRecordType::StockLine, &stock_a().id)
+            .unwrap()
+            .unwrap();
+        assert_eq!(hold.hold_reason_id, HOLD_REASON_RECALL_ID);
+        assert_eq!(hold.user_id, Some("user".to_string()));
+
+        let logs = ActivityLogRowRepository::new(&connection)
+            .find_many_by_record_id(&stock_a().id)
+            .unwrap();
+        assert_eq!(logs.len(), 1);
+        assert_eq!(logs[0].r#type, ActivityLogType::StockRecall);
+        assert_eq!(
+            logs[0].event,
+            Some("Batch B1 recalled: Contaminated".to_string())
+        );
+    }
+}
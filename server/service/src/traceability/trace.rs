@@ -0,0 +1,421 @@
+use std::collections::{HashMap, VecDeque};
+
+use repository::{
+    EqualFilter, Invoice, InvoiceFilter, InvoiceLineFilter, InvoiceLineRepository, InvoiceLineRow,
+    InvoiceRepository, InvoiceRowStatus, InvoiceRowType, RepositoryError, StockLine,
+    StockLineFilter, StockLineRepository, StockLineRow, StockLineRowRepository, StorageConnection,
+};
+
+use crate::{
+    service_provider::ServiceContext,
+    stock_line::validate::{check_stock_line_exists, check_store},
+};
+
+use super::batch_matches;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BatchTraceStart {
+    StockLine(String),
+    /// All stock lines of the item and batch in the store
+    Batch {
+        item_id: String,
+        batch: String,
+    },
+}
+
+#[derive(Debug, PartialEq)]
+pub enum BatchTraceError {
+    DatabaseError(RepositoryError),
+    StockLineDoesNotExist,
+    StockLineDoesNotBelongToStore,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchTraceStockLine {
+    pub stock_line: StockLine,
+    /// 0 for the stock lines the trace started from, negative for the stock lines they were
+    /// received from and positive for the stock lines they were distributed to
+    pub depth: i32,
+    /// Outbound shipment through which the stock line was received, or the inventory reduction
+    /// when it was split, merged or repacked from another stock line
+    pub received_through_invoice_id: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchTraceShipment {
+    pub outbound_shipment: Invoice,
+    /// Inbound shipment created by the transfer processors when shipping to another store
+    pub inbound_shipment: Option<Invoice>,
+    pub from_stock_line_id: String,
+    /// Stock lines created when the inbound shipment was delivered, empty while in transit or
+    /// when shipped to a customer
+    pub received_stock_line_ids: Vec<String>,
+    pub number_of_units: f64,
+    /// Depth of the stock line the shipment was made from
+    pub depth: i32,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct BatchTrace {
+    pub stock_lines: Vec<BatchTraceStockLine>,
+    pub shipments: Vec<BatchTraceShipment>,
+}
+
+impl BatchTrace {
+    /// Units still on hand in the starting and downstream stock lines
+    pub fn units_on_hand(&self) -> f64 {
+        self.stock_lines
+            .iter()
+            .filter(|line| line.depth >= 0)
+            .map(|line| {
+                let row = &line.stock_line.stock_line_row;
+                row.total_number_of_packs * row.pack_size as f64
+            })
+            .sum()
+    }
+}
+
+/// Shipments that reduced stock, i.e. picked or further
+const SHIPPED_STATUSES: [InvoiceRowStatus; 4] = [
+    InvoiceRowStatus::Picked,
+    InvoiceRowStatus::Shipped,
+    InvoiceRowStatus::Delivered,
+    InvoiceRowStatus::Verified,
+];
+
+pub fn get_batch_trace(
+    ctx: &ServiceContext,
+    start: BatchTraceStart,
+) -> Result<BatchTrace, BatchTraceError> {
+    let connection = &ctx.connection;
+    let start_lines = validate(connection, &ctx.store_id, &start)?;
+
+    let mut tracer = Tracer {
+        connection,
+        stock_lines: Vec::new(),
+        visited: HashMap::new(),
+        shipments: Vec::new(),
+    };
+    for stock_line in &start_lines {
+        tracer.visit(&stock_line.id, 0, None);
+    }
+    tracer.trace_forward(start_lines.clone())?;
+    tracer.trace_backward(start_lines)?;
+
+    tracer.into_batch_trace()
+}
+
+fn validate(
+    connection: &StorageConnection,
+    store_id: &str,
+    start: &BatchTraceStart,
+) -> Result<Vec<StockLineRow>, BatchTraceError> {
+    use BatchTraceError::*;
+
+    match start {
+        BatchTraceStart::StockLine(id) => {
+            let stock_line =
+                check_stock_line_exists(connection, id)?.ok_or(StockLineDoesNotExist)?;
+            if !check_store(&stock_line, store_id) {
+                return Err(StockLineDoesNotBelongToStore);
+            }
+            Ok(vec![stock_line])
+        }
+        BatchTraceStart::Batch { item_id, batch } => Ok(StockLineRepository::new(connection)
+            .query_by_filter(
+                StockLineFilter::new()
+                    .store_id(EqualFilter::equal_to(store_id))
+                    .item_id(EqualFilter::equal_to(item_id)),
+                None,
+            )?
+            .into_iter()
+            .map(|stock_line| stock_line.stock_line_row)
+            .filter(|row| batch_matches(&row.batch, &Some(batch.clone())))
+            .collect()),
+    }
+}
+
+struct Tracer<'a> {
+    connection: &'a StorageConnection,
+    /// (stock line id, depth, received through invoice id)
+    stock_lines: Vec<(String, i32, Option<String>)>,
+    /// Index into `stock_lines`, guards against cycles when stock is shipped back and forth
+    visited: HashMap<String, usize>,
+    shipments: Vec<BatchTraceShipment>,
+}
+
+impl<'a> Tracer<'a> {
+    /// Returns false when the stock line was already visited
+    fn visit(&mut self, id: &str, depth: i32, received_through_invoice_id: Option<String>) -> bool {
+        if self.visited.contains_key(id) {
+            return false;
+        }
+        self.visited.insert(id.to_string(), self.stock_lines.len());
+        self.stock_lines
+            .push((id.to_string(), depth, received_through_invoice_id));
+        true
+    }
+
+    fn trace_forward(&mut self, start_lines: Vec<StockLineRow>) -> Result<(), RepositoryError> {
+        let mut queue: VecDeque<(StockLineRow, i32)> =
+            start_lines.into_iter().map(|line| (line, 0)).collect();
+
+        while let Some((stock_line, depth)) = queue.pop_front() {
+            let shipped = InvoiceLineRepository::new(self.connection).query_by_filter(
+                InvoiceLineFilter::new()
+                    .stock_line_id(EqualFilter::equal_to(&stock_line.id))
+                    .invoice_type(InvoiceRowType::OutboundShipment.equal_to())
+                    .invoice_status(InvoiceRowStatus::equal_any(SHIPPED_STATUSES.to_vec())),
+            )?;
+
+            for (invoice_id, number_of_units) in
+                units_by_invoice(shipped.iter().map(|line| &line.invoice_line_row).collect())
+            {
+                let outbound_shipment = self.invoice(&invoice_id)?;
+                let inbound_shipment = InvoiceRepository::new(self.connection)
+                    .query_one(InvoiceFilter::new_match_linked_invoice_id(&invoice_id))?;
+
+                let mut received_stock_line_ids = Vec::new();
+                if let Some(inbound_shipment) = &inbound_shipment {
+                    let received = matching_lines(
+                        self.connection,
+                        &inbound_shipment.invoice_row.id,
+                        &stock_line,
+                    )?;
+                    for received_id in received.into_iter().filter_map(|line| line.stock_line_id) {
+                        if self.visit(&received_id, depth + 1, Some(invoice_id.clone())) {
+                            let received_line = StockLineRowRepository::new(self.connection)
+                                .find_one_by_id(&received_id)?;
+                            queue.push_back((received_line, depth + 1));
+                        }
+                        received_stock_line_ids.push(received_id);
+                    }
+                }
+
+                self.shipments.push(BatchTraceShipment {
+                    outbound_shipment,
+                    inbound_shipment,
+                    from_stock_line_id: stock_line.id.clone(),
+                    received_stock_line_ids,
+                    number_of_units,
+                    depth,
+                });
+            }
+
+            for (reduction_id, to_stock_line_ids) in self.transfers(
+                &stock_line,
+                InvoiceRowType::InventoryReduction,
+                InvoiceRowType::InventoryAddition,
+            )? {
+                for to_stock_line_id in to_stock_line_ids {
+                    if self.visit(&to_stock_line_id, depth + 1, Some(reduction_id.clone())) {
+                        let to_line = StockLineRowRepository::new(self.connection)
+                            .find_one_by_id(&to_stock_line_id)?;
+                        queue.push_back((to_line, depth + 1));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn trace_backward(&mut self, start_lines: Vec<StockLineRow>) -> Result<(), RepositoryError> {
+        let mut queue: VecDeque<(StockLineRow, i32)> =
+            start_lines.into_iter().map(|line| (line, 0)).collect();
+
+        while let Some((stock_line, depth)) = queue.pop_front() {
+            let received = InvoiceLineRepository::new(self.connection).query_by_filter(
+                InvoiceLineFilter::new()
+                    .stock_line_id(EqualFilter::equal_to(&stock_line.id))
+                    .invoice_type(InvoiceRowType::InboundShipment.equal_to()),
+            )?;
+
+            for inbound_line in received {
+                let outbound_id = match &inbound_line.invoice_row.linked_invoice_id {
+                    Some(outbound_id) => outbound_id.clone(),
+                    // Received from an external supplier
+                    None => continue,
+                };
+                let shipped = matching_lines(self.connection, &outbound_id, &stock_line)?;
+                if let Some(index) = self.visited.get(&stock_line.id) {
+                    self.stock_lines[*index].2 = Some(outbound_id.clone());
+                }
+
+                for (from_stock_line_id, number_of_units) in units_by_stock_line(&shipped) {
+                    if !self.visit(&from_stock_line_id, depth - 1, None) {
+                        continue;
+                    }
+                    let source_line = StockLineRowRepository::new(self.connection)
+                        .find_one_by_id(&from_stock_line_id)?;
+                    queue.push_back((source_line, depth - 1));
+
+                    self.shipments.push(BatchTraceShipment {
+                        outbound_shipment: self.invoice(&outbound_id)?,
+                        inbound_shipment: Some(self.invoice(&inbound_line.invoice_row.id)?),
+                        from_stock_line_id,
+                        received_stock_line_ids: vec![stock_line.id.clone()],
+                        number_of_units,
+                        depth: depth - 1,
+                    });
+                }
+            }
+
+            for (reduction_id, from_stock_line_ids) in self.transfers(
+                &stock_line,
+                InvoiceRowType::InventoryAddition,
+                InvoiceRowType::InventoryReduction,
+            )? {
+                if let Some(index) = self.visited.get(&stock_line.id) {
+                    // Keep the shipment a merge target was originally received through
+                    self.stock_lines[*index].2.get_or_insert(reduction_id);
+                }
+                for from_stock_line_id in from_stock_line_ids {
+                    if self.visit(&from_stock_line_id, depth - 1, None) {
+                        let from_line = StockLineRowRepository::new(self.connection)
+                            .find_one_by_id(&from_stock_line_id)?;
+                        queue.push_back((from_line, depth - 1));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Stock lines on the other side of the split, merge and repack transfers of a stock line,
+    /// by the id of the inventory reduction of the transfer. Transfers are recorded as an
+    /// inventory reduction linked to an inventory addition, other inventory adjustments are not
+    /// linked and are skipped.
+    fn transfers(
+        &self,
+        stock_line: &StockLineRow,
+        r#type: InvoiceRowType,
+        linked_type: InvoiceRowType,
+    ) -> Result<Vec<(String, Vec<String>)>, RepositoryError> {
+        let lines = InvoiceLineRepository::new(self.connection).query_by_filter(
+            InvoiceLineFilter::new()
+                .stock_line_id(EqualFilter::equal_to(&stock_line.id))
+                .invoice_type(r#type.equal_to()),
+        )?;
+
+        let mut result: Vec<(String, Vec<String>)> = Vec::new();
+        for line in lines {
+            let linked_invoice_id = match &line.invoice_row.linked_invoice_id {
+                Some(linked_invoice_id) => linked_invoice_id,
+                None => continue,
+            };
+            let reduction_id = match r#type {
+                InvoiceRowType::InventoryReduction => &line.invoice_row.id,
+                _ => linked_invoice_id,
+            };
+            if self.invoice(linked_invoice_id)?.invoice_row.r#type != linked_type
+                || result.iter().any(|(id, _)| id == reduction_id)
+            {
+                continue;
+            }
+            let stock_line_ids = matching_lines(self.connection, linked_invoice_id, stock_line)?
+                .into_iter()
+                .filter_map(|line| line.stock_line_id)
+                .filter(|id| id != &stock_line.id)
+                .collect();
+            result.push((reduction_id.clone(), stock_line_ids));
+        }
+        Ok(result)
+    }
+
+    fn invoice(&self, id: &str) -> Result<Invoice, RepositoryError> {
+        InvoiceRepository::new(self.connection)
+            .query_one(InvoiceFilter::by_id(id))?
+            .ok_or(RepositoryError::NotFound)
+    }
+
+    fn into_batch_trace(self) -> Result<BatchTrace, BatchTraceError> {
+        let ids = self
+            .stock_lines
+            .iter()
+            .map(|(id, _, _)| id.clone())
+            .collect();
+        let mut stock_lines: HashMap<String, StockLine> = StockLineRepository::new(self.connection)
+            .query_by_filter(StockLineFilter::new().id(EqualFilter::equal_any(ids)), None)?
+            .into_iter()
+            .map(|stock_line| (stock_line.stock_line_row.id.clone(), stock_line))
+            .collect();
+
+        let mut stock_lines: Vec<BatchTraceStockLine> = self
+            .stock_lines
+            .into_iter()
+            .filter_map(|(id, depth, received_through_invoice_id)| {
+                stock_lines
+                    .remove(&id)
+                    .map(|stock_line| BatchTraceStockLine {
+                        stock_line,
+                        depth,
+                        received_through_invoice_id,
+                    })
+            })
+            .collect();
+        stock_lines.sort_by_key(|line| line.depth);
+        let mut shipments = self.shipments;
+        shipments.sort_by_key(|shipment| shipment.depth);
+
+        Ok(BatchTrace {
+            stock_lines,
+            shipments,
+        })
+    }
+}
+
+/// Lines of the invoice with the item and batch of the stock line
+fn matching_lines(
+    connection: &StorageConnection,
+    invoice_id: &str,
+    stock_line: &StockLineRow,
+) -> Result<Vec<InvoiceLineRow>, RepositoryError> {
+    Ok(InvoiceLineRepository::new(connection)
+        .query_by_filter(
+            InvoiceLineFilter::new()
+                .invoice_id(EqualFilter::equal_to(invoice_id))
+                .item_id(EqualFilter::equal_to(&stock_line.item_id)),
+        )?
+        .into_iter()
+        .map(|line| line.invoice_line_row)
+        .filter(|line| batch_matches(&line.batch, &stock_line.batch))
+        .collect())
+}
+
+fn units(line: &InvoiceLineRow) -> f64 {
+    line.number_of_packs * line.pack_size as f64
+}
+
+/// Units per invoice, in the order the invoices first appear
+fn units_by_invoice(lines: Vec<&InvoiceLineRow>) -> Vec<(String, f64)> {
+    let mut result: Vec<(String, f64)> = Vec::new();
+    for line in lines {
+        match result.iter_mut().find(|(id, _)| id == &line.invoice_id) {
+            Some((_, total)) => *total += units(line),
+            None => result.push((line.invoice_id.clone(), units(line))),
+        }
+    }
+    result
+}
+
+/// Units per stock line, in the order the stock lines first appear
+fn units_by_stock_line(lines: &[InvoiceLineRow]) -> Vec<(String, f64)> {
+    let mut result: Vec<(String, f64)> = Vec::new();
+    for line in lines {
+        let stock_line_id = match &line.stock_line_id {
+            Some(stock_line_id) => stock_line_id,
+            None => continue,
+        };
+        match result.iter_mut().find(|(id, _)| id == stock_line_id) {
+            Some((_, total)) => *total += units(line),
+            None => result.push((stock_line_id.clone(), units(line))),
+        }
+    }
+    result
+}
+
+impl From<RepositoryError> for BatchTraceError {
+    fn from(error: RepositoryError) -> Self {
+        BatchTraceError::DatabaseError(error)
+    }
+}
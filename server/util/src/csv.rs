@@ -0,0 +1,78 @@
+/// Splits delimited text (e.g. a spreadsheet or data logger export) into rows of fields.
+/// The delimiter is detected from the first line (comma, semicolon or tab), double quoted fields
+/// can contain delimiters, line breaks and escaped quotes (""). Empty lines are skipped.
+pub fn parse_csv(text: &str) -> Vec<Vec<String>> {
+    let text = text.trim_start_matches('\u{feff}');
+    let delimiter = detect_delimiter(text.lines().next().unwrap_or_default());
+
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    field.push('"');
+                    chars.next();
+                }
+                '"' => in_quotes = false,
+                _ => field.push(c),
+            }
+            continue;
+        }
+
+        match c {
+            '"' => in_quotes = true,
+            '\r' => {}
+            '\n' => {
+                row.push(std::mem::take(&mut field).trim().to_string());
+                push_row(&mut rows, std::mem::take(&mut row));
+            }
+            c if c == delimiter => row.push(std::mem::take(&mut field).trim().to_string()),
+            _ => field.push(c),
+        }
+    }
+    row.push(field.trim().to_string());
+    push_row(&mut rows, row);
+
+    rows
+}
+
+fn detect_delimiter(first_line: &str) -> char {
+    [',', ';', '\t']
+        .iter()
+        .copied()
+        .max_by_key(|delimiter| first_line.matches(*delimiter).count())
+        .filter(|delimiter| first_line.contains(*delimiter))
+        .unwrap_or(',')
+}
+
+fn push_row(rows: &mut Vec<Vec<String>>, row: Vec<String>) {
+    if row.iter().any(|field| !field.is_empty()) {
+        rows.push(row);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::parse_csv;
+
+    #[test]
+    fn test_parse_csv() {
+        assert_eq!(
+            parse_csv("a,b,c\n1,\"2,5\",3\r\n\n4,\"say \"\"hi\"\"\",\n"),
+            vec![
+                vec!["a", "b", "c"],
+                vec!["1", "2,5", "3"],
+                vec!["4", "say \"hi\"", ""]
+            ]
+        );
+        assert_eq!(
+            parse_csv("Date;Temperature\n2023-01-01 10:00;4,5"),
+            vec![vec!["Date", "Temperature"], vec!["2023-01-01 10:00", "4,5"]]
+        );
+    }
+}
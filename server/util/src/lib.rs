@@ -1,4 +1,5 @@
 pub mod constants;
+pub mod csv;
 pub mod hash;
 pub mod timezone;
 pub mod uuid;